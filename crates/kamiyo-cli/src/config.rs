@@ -0,0 +1,73 @@
+// Endpoints and signer, from ~/.config/kamiyo/cli.toml with flag overrides
+
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+const DEFAULT_ER_URL: &str = "https://devnet.magicblock.app";
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Base layer RPC
+    pub rpc_url: Option<String>,
+    /// MagicBlock ephemeral rollup RPC
+    pub er_url: Option<String>,
+    pub keypair: Option<String>,
+}
+
+pub struct Config {
+    pub rpc_url: String,
+    pub er_url: String,
+    pub keypair: PathBuf,
+}
+
+fn home() -> PathBuf {
+    std::env::var_os("HOME").map(PathBuf::from).unwrap_or_default()
+}
+
+fn expand(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => home().join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+pub fn default_path() -> PathBuf {
+    home().join(".config/kamiyo/cli.toml")
+}
+
+impl Config {
+    /// A missing file is fine when it is the default path; an explicit
+    /// `--config` must exist.
+    pub fn load(
+        path: Option<&str>,
+        rpc_url: Option<String>,
+        er_url: Option<String>,
+        keypair: Option<String>,
+    ) -> Result<Self, String> {
+        let file = match path {
+            Some(path) => Some(expand(path)),
+            None => Some(default_path()).filter(|p| p.exists()),
+        };
+        let file: ConfigFile = match file {
+            Some(path) => {
+                let text = std::fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+                toml::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?
+            }
+            None => ConfigFile::default(),
+        };
+
+        Ok(Self {
+            rpc_url: rpc_url.or(file.rpc_url).unwrap_or_else(|| DEFAULT_RPC_URL.into()),
+            er_url: er_url.or(file.er_url).unwrap_or_else(|| DEFAULT_ER_URL.into()),
+            keypair: expand(
+                keypair
+                    .or(file.keypair)
+                    .as_deref()
+                    .unwrap_or("~/.config/solana/id.json"),
+            ),
+        })
+    }
+}
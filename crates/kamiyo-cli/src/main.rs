@@ -0,0 +1,2285 @@
+//! Operator CLI for KAMIYO fast voting.
+//!
+//! Actions are created, delegated and cancelled on the base layer; votes and
+//! tallies go to the ephemeral rollup, except ballots on live token balances,
+//! which lock the voter's stake on the base layer. Endpoints and keypair come from
+//! `~/.config/kamiyo/cli.toml` (see `config.rs`), overridable per flag.
+
+mod config;
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::AccountDeserialize;
+use clap::{Parser, Subcommand, ValueEnum};
+use kamiyo_fast_voting::{dispute, market, tally, DEFAULT_PRIORITY, NO_ELIMINATION, TAG_PAGE_LEN};
+use kamiyo_hive_client::instructions::MemberNft;
+use kamiyo_hive_client::rpc::blocking::Client;
+use kamiyo_hive_client::state::{
+    ActionMetadata, ConvictionProposal, CreatorAllowlist, EpochStats, FastAction, FederatedAction, GlobalConfig, Hive,
+    Metrics, ProposalDraft, ReceiptTree, StakeLock, TagIndex, TallyAudit, VoteMode, VoteResult,
+};
+use kamiyo_hive_client::{
+    instructions, pda, ActionAmendment, ActionKind, ActionParams, AdaptiveQuorum, AgentClass, ClassLimit,
+    DelegationShare, Election, ExtensionPolicy, FederatedChild, HiveOverrides, HiveUpdate, MarketGate, MetricsLayer,
+    PrivacyLevel, TaskAssignment, WeightCap, ID,
+};
+use solana_sdk::signature::{read_keypair_file, Keypair};
+use solana_sdk::signer::Signer;
+
+use config::Config;
+
+type CliResult<T = ()> = Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(name = "kamiyo-cli", version, about)]
+struct Cli {
+    /// Config file [default: ~/.config/kamiyo/cli.toml]
+    #[arg(long, global = true)]
+    config: Option<String>,
+    /// Base layer RPC URL
+    #[arg(long, short = 'u', global = true)]
+    url: Option<String>,
+    /// Ephemeral rollup RPC URL
+    #[arg(long, global = true)]
+    er_url: Option<String>,
+    /// Signer keypair file
+    #[arg(long, short = 'k', global = true)]
+    keypair: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+// See ActionCommand
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand)]
+enum Command {
+    #[command(subcommand)]
+    Action(ActionCommand),
+    /// Cast a vote on the ephemeral rollup, or on the base layer for a
+    /// token-weighted ballot, which locks the voter's stake
+    Vote {
+        action_id: u64,
+        /// Required for for/against actions
+        side: Option<Side>,
+        /// Per-option weights for multi-option actions, comma separated;
+        /// must sum to the voter's weight (1 for headcount)
+        #[arg(long, value_delimiter = ',', conflicts_with = "side")]
+        weights: Vec<u64>,
+        /// Option indices in order of preference for ranked-choice actions,
+        /// comma separated
+        #[arg(long, value_delimiter = ',', conflicts_with_all = ["side", "weights"])]
+        ranking: Vec<u8>,
+        /// 32-byte hex voter commitment [default: sha256(voter || action)]
+        #[arg(long, value_parser = parse_hash)]
+        commitment: Option<[u8; 32]>,
+        /// Token account holding the weight mint; required for token-weighted actions
+        #[arg(long)]
+        token_account: Option<Pubkey>,
+        /// Core identity asset held by the signer; required for
+        /// identity-weighted actions
+        #[arg(long, conflicts_with_all = ["token_account", "weights", "ranking"])]
+        identity_asset: Option<Pubkey>,
+        /// NFT of a collection-gated action's member collection: a Core
+        /// ASSET, or TOKEN_ACCOUNT:MINT for a Token Metadata NFT
+        #[arg(long, value_parser = parse_member_nft)]
+        member_nft: Option<MemberNft>,
+    },
+    /// Split the signer's token weight across delegates, as DELEGATE:BPS,
+    /// keeping the rest; no shares revokes every delegation
+    DelegateVotes {
+        #[arg(value_parser = parse_share)]
+        shares: Vec<DelegationShare>,
+    },
+    /// Finalize an action and commit it back to the base layer. Ranked-choice
+    /// actions run their elimination rounds first
+    Tally { action_id: u64 },
+    /// Cancel an action, wherever it currently lives
+    Cancel { action_id: u64 },
+    /// Show the program's activity counters on both layers
+    Metrics {
+        /// Show the hive's own counters instead
+        #[arg(long)]
+        hive: Option<Pubkey>,
+        /// Create the hive's counters on both layers instead; hive authority
+        /// only
+        #[arg(long, requires = "hive")]
+        init: bool,
+    },
+    /// Show the finalization statistics of a hive's actions for an epoch
+    Stats {
+        /// Hive whose actions to show [default: unscoped actions]
+        #[arg(long)]
+        hive: Option<Pubkey>,
+        /// ER epoch [default: the current one]
+        #[arg(long)]
+        epoch: Option<u64>,
+        /// Create the epoch's stats account on the ER instead
+        #[arg(long)]
+        init: bool,
+    },
+    /// Call the callbacks of every scheduled action that has settled,
+    /// collecting its reward
+    Keeper {
+        /// Seconds between scans
+        #[arg(long, default_value_t = 30)]
+        interval: u64,
+        /// Scan once and exit
+        #[arg(long)]
+        once: bool,
+    },
+    #[command(subcommand)]
+    Votes(VotesCommand),
+    #[command(subcommand)]
+    Conviction(ConvictionCommand),
+    #[command(subcommand)]
+    Draft(DraftCommand),
+    #[command(subcommand)]
+    Agent(AgentCommand),
+    #[command(subcommand)]
+    Hive(HiveCommand),
+    #[command(subcommand)]
+    Federation(FederationCommand),
+}
+
+// Parsed once per run; boxing Create buys nothing
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand)]
+enum ActionCommand {
+    /// Open a new action on the base layer
+    Create {
+        /// 32-byte hex action hash
+        #[arg(long, value_parser = parse_hash)]
+        hash: [u8; 32],
+        /// Approval percentage required to pass (1-100)
+        #[arg(long)]
+        threshold: u8,
+        /// Finer pass bar in basis points, e.g. 6667; --threshold must be it
+        /// rounded up to a whole percent
+        #[arg(long, conflicts_with = "optimistic")]
+        threshold_bps: Option<u16>,
+        /// 32-byte hex description hash
+        #[arg(long, value_parser = parse_hash)]
+        description_hash: Option<[u8; 32]>,
+        /// Weigh votes by the configured token instead of one per voter
+        #[arg(long)]
+        weighted: bool,
+        /// Incident response: 10-slot window and a threshold of at least 80.
+        /// Requires --hive with the guardian as a member
+        #[arg(long, conflicts_with_all = ["optimistic", "options"], requires = "hive")]
+        emergency: bool,
+        /// Elect the hive's leader: each option is a candidate agent, and
+        /// --hash must be the election's. Requires --hive and --options
+        #[arg(long, conflicts_with_all = ["emergency", "optimistic"], requires_all = ["hive", "options"])]
+        election: bool,
+        /// Assign a task: each option is a candidate assignee, and --hash
+        /// must be the task assignment's. Requires --options
+        #[arg(long, conflicts_with_all = ["emergency", "election", "optimistic"], requires = "options")]
+        task_assignment: bool,
+        /// Pass unless vetoed; the threshold becomes the veto percentage of
+        /// the hive. Requires --hive and bonds the signer
+        #[arg(long, conflicts_with_all = ["weighted", "tiers"], requires = "hive")]
+        optimistic: bool,
+        /// Graded tiers above the threshold, ascending, comma separated
+        #[arg(long, value_delimiter = ',')]
+        tiers: Vec<u8>,
+        /// Votes required at the deadline [default: fixed program quorum]
+        #[arg(long)]
+        quorum: Option<u32>,
+        /// Votes required once --quorum-grace slots past the deadline
+        #[arg(long, requires = "quorum")]
+        quorum_floor: Option<u32>,
+        /// Slots over which the quorum decays to the floor
+        #[arg(long, requires = "quorum")]
+        quorum_grace: Option<u64>,
+        /// Share of the hive that must vote, in basis points; requires --hive
+        #[arg(long, default_value_t = 0, requires = "hive")]
+        min_turnout_bps: u16,
+        /// Offer this many options (2-8) instead of for/against
+        #[arg(long, default_value_t = 0, conflicts_with_all = ["optimistic", "tiers"])]
+        options: u8,
+        /// Count the options as an instant runoff over ranked ballots
+        #[arg(long, requires = "options", conflicts_with = "weighted")]
+        ranked: bool,
+        /// Tally only after every ballot has been recounted on-chain
+        #[arg(long, conflicts_with = "options")]
+        audited: bool,
+        /// Weigh early ballots up to 1.2x, decaying to 1.0x at the deadline,
+        /// on the action kind's curve
+        #[arg(long, requires = "weighted")]
+        early_bonus: bool,
+        /// Weigh ballots by the voter's identity asset through the config's
+        /// identity tiers instead of a token balance
+        #[arg(long, requires = "weighted", conflicts_with = "options")]
+        identity: bool,
+        /// Take ballots only from holders of an NFT in this Metaplex
+        /// collection (Core or Token Metadata)
+        #[arg(long, conflicts_with_all = ["weighted", "options"])]
+        member_collection: Option<Pubkey>,
+        /// Lock each voter's token account through the config's stake lock
+        /// program until the action is final; voted on the base layer
+        #[arg(long, requires = "weighted", conflicts_with_all = ["identity", "options"])]
+        lock_stake: bool,
+        /// Count quorum as this share, in basis points, of the weight
+        /// snapshot's total instead of in heads; ballots then need the
+        /// snapshot
+        #[arg(long, requires = "weighted", conflicts_with_all = ["quorum", "identity", "options", "lock_stake"])]
+        quorum_weight_bps: Option<u16>,
+        /// Priority lane, 0 the lowest; validators and keepers commit higher
+        /// lanes first
+        #[arg(long, default_value_t = DEFAULT_PRIORITY)]
+        priority: u8,
+        /// Label of up to 8 bytes agents can find the action by; repeatable.
+        /// Each tag's index must exist
+        #[arg(long = "tag", value_parser = parse_tag)]
+        tags: Vec<[u8; 8]>,
+        /// Most weight one ballot counts [default: the config's cap for the
+        /// action kind, if any]
+        #[arg(long, requires = "weighted", conflicts_with = "max_weight_bps")]
+        max_weight: Option<u64>,
+        /// Like --max-weight, in basis points of the weight mint's supply
+        #[arg(long, requires = "weighted")]
+        max_weight_bps: Option<u16>,
+        /// Per-class ballot cap and, for --weighted, weight scale in basis
+        /// points: CLASS:MAX_VOTES[:WEIGHT_BPS], MAX_VOTES 0 = unlimited
+        #[arg(long = "class-limit", value_parser = parse_class_limit)]
+        class_limits: Vec<ClassLimit>,
+        /// Experimental: price account that must exceed --market-min-price
+        /// before the passed action is executable
+        #[arg(long, requires_all = ["market_program", "market_min_price"], conflicts_with = "options")]
+        market_feed: Option<Pubkey>,
+        /// Program owning the market feed
+        #[arg(long, requires = "market_feed")]
+        market_program: Option<Pubkey>,
+        /// Byte offset of the little-endian u64 price in the feed
+        #[arg(long, default_value_t = 0, requires = "market_feed")]
+        market_price_offset: u16,
+        /// Price the feed must exceed
+        #[arg(long, requires = "market_feed")]
+        market_min_price: Option<u64>,
+        /// Prerequisite action address; repeatable
+        #[arg(long = "depends-on")]
+        dependencies: Vec<Pubkey>,
+        /// Voter barred from the ballot; repeatable
+        #[arg(long = "exclude")]
+        exclusions: Vec<Pubkey>,
+        /// Let the deadline be extended this many times, each by up to one
+        /// window, when enough voters co-sign
+        #[arg(long, requires = "extension_support_bps")]
+        max_extensions: Option<u8>,
+        /// Share of voters that must co-sign an extension, in basis points
+        #[arg(long, requires = "max_extensions")]
+        extension_support_bps: Option<u16>,
+        /// Scope the action to a hive; the signer must hold Role::Creator
+        #[arg(long)]
+        hive: Option<Pubkey>,
+        /// Finalized action last recorded for this hash, to reuse it
+        #[arg(long)]
+        previous_action: Option<Pubkey>,
+        /// Stage a draft for co-sponsorship instead of opening the action;
+        /// repeatable
+        #[arg(long = "sponsor", requires = "required", conflicts_with = "from_draft")]
+        sponsors: Vec<Pubkey>,
+        /// Sponsor signatures the draft needs
+        #[arg(long, requires = "sponsors")]
+        required: Option<u8>,
+        /// Open the action from the signer's sponsored draft; the other
+        /// flags must repeat the draft's params
+        #[arg(long)]
+        from_draft: bool,
+    },
+    /// Open one headcount action per manifest line, in one transaction.
+    /// Lines read HASH THRESHOLD [PRIORITY]; blank lines and # comments are
+    /// skipped
+    CreateBatch {
+        manifest: PathBuf,
+        /// Scope the actions to this hive; the signer must hold Role::Creator
+        #[arg(long)]
+        hive: Option<Pubkey>,
+    },
+    /// Delegate an action to the ephemeral rollup
+    Delegate {
+        action_id: u64,
+        /// Pin to a TEE validator
+        #[arg(long)]
+        validator: Option<Pubkey>,
+    },
+    /// Show an action's current state
+    Status { action_id: u64 },
+    /// Open a runoff between the two leading options of a failed
+    /// multi-option action
+    Runoff {
+        action_id: u64,
+        /// Delegate the runoff to the parent's validator in the same instruction
+        #[arg(long)]
+        delegate: bool,
+    },
+    /// Clear a passed action's market gate once its feed exceeds the bound
+    ClearMarket { action_id: u64 },
+    /// Close an action nobody finalized once its expiry grace has passed,
+    /// returning its rent to the creator
+    Expire { action_id: u64 },
+    /// Force back every listed action that is delegated past its liveness
+    /// timeout, in one ephemeral rollup transaction
+    Reap { action_ids: Vec<u64> },
+    /// Take a final action out of its priority lane
+    Retire { action_id: u64 },
+    /// Take an action back on the base layer off its validator's count of
+    /// hosted actions
+    Release { action_id: u64 },
+    /// Create the ActiveActions index counting running actions per priority
+    /// lane, of a hive or of unscoped actions
+    IndexLanes {
+        #[arg(long)]
+        hive: Option<Pubkey>,
+    },
+    /// Create a page of a tag's index of open actions, of a hive or of
+    /// unscoped actions
+    IndexTag {
+        #[arg(value_parser = parse_tag)]
+        tag: [u8; 8],
+        /// Page to create; the pages before it must exist
+        #[arg(long, default_value_t = 0)]
+        page: u32,
+        #[arg(long)]
+        hive: Option<Pubkey>,
+    },
+    /// Take a final action out of its tags' indexes
+    RetireTags { action_id: u64 },
+    /// List the open actions carrying a tag
+    Tagged {
+        #[arg(value_parser = parse_tag)]
+        tag: [u8; 8],
+        #[arg(long)]
+        hive: Option<Pubkey>,
+    },
+    /// Give back a final action's share of a voter's stake lock, releasing
+    /// the voter's tokens once no other action holds them
+    ReleaseStake { action_id: u64, voter: Pubkey },
+    /// Install the winner of a settled election as its hive's leader
+    InstallLeader {
+        action_id: u64,
+        /// Candidates in option order, comma separated, as committed to by
+        /// the action hash
+        #[arg(long, value_delimiter = ',', required = true)]
+        candidates: Vec<Pubkey>,
+        /// Slots the leader's term runs
+        #[arg(long)]
+        term_slots: u64,
+    },
+    /// Push the task a settled task assignment voted for into its queue
+    PushTask {
+        action_id: u64,
+        #[arg(long)]
+        queue_program: Pubkey,
+        #[arg(long)]
+        queue: Pubkey,
+        #[arg(long, value_parser = parse_hash)]
+        task_hash: [u8; 32],
+        /// Candidates in option order, comma separated, as committed to by
+        /// the action hash
+        #[arg(long, value_delimiter = ',', required = true)]
+        assignees: Vec<Pubkey>,
+        #[arg(long)]
+        deadline_slot: u64,
+    },
+    /// Mint a ballot's compressed NFT receipt to its voter once the result
+    /// has settled
+    MintReceipt {
+        action_id: u64,
+        /// Whose ballot [default: the signer]
+        #[arg(long)]
+        voter: Option<Pubkey>,
+    },
+    /// Overturn a tallied result during its dispute window; guardian only
+    Veto {
+        action_id: u64,
+        /// Recorded in the ActionVetoed event
+        #[arg(long)]
+        reason: String,
+    },
+    /// Fix an undelegated action's parameters before its first vote
+    Amend {
+        action_id: u64,
+        /// New approval percentage (1-100)
+        #[arg(long)]
+        threshold: Option<u8>,
+        /// New voting window in slots, counted from creation
+        #[arg(long)]
+        window: Option<u64>,
+        /// New 32-byte hex description hash
+        #[arg(long, value_parser = parse_hash)]
+        description_hash: Option<[u8; 32]>,
+    },
+    /// Set an action's on-chain description and metadata URI before its
+    /// first vote
+    Metadata {
+        action_id: u64,
+        /// Up to 256 bytes
+        #[arg(long, default_value = "")]
+        description: String,
+        /// Up to 200 bytes
+        #[arg(long, default_value = "")]
+        uri: String,
+    },
+    /// Record that an action's description is encrypted to a TEE's key,
+    /// to be revealed once it passes; before the first vote
+    Seal {
+        action_id: u64,
+        /// sha256 of the ciphertext handed to the TEE
+        #[arg(long, value_parser = parse_hash)]
+        ciphertext_hash: [u8; 32],
+        /// 32-byte hex key the description is encrypted to
+        #[arg(long, value_parser = parse_hash)]
+        encryption_key: [u8; 32],
+    },
+    /// Hand an action's cancel and amend rights to another key
+    TransferAuthority { action_id: u64, new_authority: Pubkey },
+    /// Register a program to be called with the action's result
+    Callback {
+        action_id: u64,
+        program_id: Pubkey,
+        /// Account passed to the callback, suffixed `:w` if writable;
+        /// repeatable, in order
+        #[arg(long = "account", value_parser = parse_account_meta)]
+        accounts: Vec<AccountMeta>,
+    },
+    /// Stage and then call a settled action's registered callbacks
+    Execute {
+        action_id: u64,
+        /// `PROGRAM[,ACCOUNT[:w]...]` for each registered callback, in
+        /// registration order
+        #[arg(long = "callback", value_parser = parse_callback)]
+        callbacks: Vec<(Pubkey, Vec<AccountMeta>)>,
+    },
+    /// Pay a keeper to call a finalized action's callbacks once it settles
+    Schedule {
+        action_id: u64,
+        /// Lamports for the keeper, on top of any already posted
+        #[arg(long)]
+        reward: u64,
+        /// As for `execute`
+        #[arg(long = "callback", value_parser = parse_callback)]
+        callbacks: Vec<(Pubkey, Vec<AccountMeta>)>,
+    },
+    /// Withdraw an action's keeper schedule and its reward
+    Unschedule { action_id: u64 },
+}
+
+#[derive(Subcommand)]
+enum VotesCommand {
+    /// List votes cast on an action
+    List { action_id: u64 },
+}
+
+/// Conviction proposals, keyed by their action hash; all on the base layer
+#[derive(Subcommand)]
+enum ConvictionCommand {
+    /// Open a proposal that executes once conviction reaches the threshold
+    Create {
+        #[arg(value_parser = parse_hash)]
+        hash: [u8; 32],
+        /// Conviction required, in staked lamports
+        #[arg(long)]
+        threshold: u64,
+        /// Slots for conviction to close half the gap to the total staked
+        #[arg(long)]
+        half_life: u64,
+        /// Scope the proposal to a hive; the signer must hold Role::Creator
+        #[arg(long)]
+        hive: Option<Pubkey>,
+    },
+    /// Stake lamports behind a proposal
+    Stake {
+        #[arg(value_parser = parse_hash)]
+        hash: [u8; 32],
+        lamports: u64,
+    },
+    /// Withdraw staked lamports
+    Withdraw {
+        #[arg(value_parser = parse_hash)]
+        hash: [u8; 32],
+        lamports: u64,
+    },
+    /// Accrue conviction, executing the proposal if it crossed the threshold
+    Update {
+        #[arg(value_parser = parse_hash)]
+        hash: [u8; 32],
+    },
+    /// Show a proposal's current state
+    Status {
+        #[arg(value_parser = parse_hash)]
+        hash: [u8; 32],
+    },
+}
+
+/// Co-sponsored drafts, keyed by creator and action hash; all on the base layer
+#[derive(Subcommand)]
+enum DraftCommand {
+    /// Sign a draft as one of its sponsors
+    Sponsor {
+        creator: Pubkey,
+        #[arg(value_parser = parse_hash)]
+        hash: [u8; 32],
+    },
+    /// Show a draft's sponsors and signatures
+    Status {
+        creator: Pubkey,
+        #[arg(value_parser = parse_hash)]
+        hash: [u8; 32],
+    },
+}
+
+/// Voting agent bonds; all on the base layer
+#[derive(Subcommand)]
+enum AgentCommand {
+    /// Bond the signer as a voting agent, or add to its bond
+    Register { lamports: u64 },
+    /// Config admin only: deregister an agent and refund its bond
+    Evict { agent: Pubkey },
+    /// Config admin only: set the class an agent votes as
+    Class {
+        agent: Pubkey,
+        /// unclassified, scout, worker or coordinator
+        #[arg(value_parser = parse_class)]
+        class: AgentClass,
+    },
+    /// Grow an agent's record made before registration epochs were kept
+    Migrate { agent: Pubkey },
+}
+
+/// Hive accounts; all on the base layer
+#[derive(Subcommand)]
+enum HiveCommand {
+    /// Register the signer as a hive
+    Create {
+        /// Hashed into the account; only the hash is stored
+        name: String,
+        /// Key managing the hive [default: the signer]
+        #[arg(long)]
+        authority: Option<Pubkey>,
+    },
+    /// Hive authority only: change the authority, member root or overrides
+    Update {
+        hive: Pubkey,
+        #[arg(long)]
+        authority: Option<Pubkey>,
+        /// 32-byte hex Merkle root of the member registry
+        #[arg(long, value_parser = parse_hash)]
+        member_root: Option<[u8; 32]>,
+        /// Actions one creator may open per epoch in the hive; 0 = global
+        #[arg(long)]
+        max_actions_per_epoch: Option<u32>,
+        /// Quorum of actions that leave it unset; 0 = program default
+        #[arg(long)]
+        default_quorum: Option<u32>,
+    },
+    /// Hive authority only: let only allowlisted creators open the hive's
+    /// actions
+    Restrict {
+        hive: Pubkey,
+        /// Reopen creation to every Creator instead
+        #[arg(long)]
+        lift: bool,
+    },
+    /// Hive authority only: add a creator to the hive's allowlist
+    Allow { hive: Pubkey, creator: Pubkey },
+    /// Hive authority only: remove a creator from the hive's allowlist
+    Disallow { hive: Pubkey, creator: Pubkey },
+    /// Show a hive's account
+    Status { hive: Pubkey },
+}
+
+/// Decisions spanning hives, keyed by their action hash; all on the base layer
+#[derive(Subcommand)]
+enum FederationCommand {
+    /// Weigh one hive-scoped action per hive into a single decision
+    Create {
+        #[arg(value_parser = parse_hash)]
+        hash: [u8; 32],
+        /// Percent of the total hive weight that must pass (1-100)
+        #[arg(long)]
+        threshold: u8,
+        /// Child action as ACTION:HIVE:WEIGHT; repeat for each hive
+        #[arg(long = "child", value_parser = parse_child, required = true)]
+        children: Vec<FederatedChild>,
+    },
+    /// Record the result once every child action is final
+    Settle {
+        #[arg(value_parser = parse_hash)]
+        hash: [u8; 32],
+    },
+    /// Show a federated action's children and result
+    Status {
+        #[arg(value_parser = parse_hash)]
+        hash: [u8; 32],
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Side {
+    For,
+    Against,
+}
+
+/// HASH THRESHOLD [PRIORITY], as a headcount action with default settings
+fn parse_manifest_line(line: &str) -> Result<ActionParams, String> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let (hash, threshold, priority) = match fields[..] {
+        [hash, threshold] => (hash, threshold, None),
+        [hash, threshold, priority] => (hash, threshold, Some(priority)),
+        _ => return Err(format!("expected HASH THRESHOLD [PRIORITY]: {line}")),
+    };
+    let priority = priority.map_or(Ok(DEFAULT_PRIORITY), str::parse).map_err(|e| format!("{e}: {line}"))?;
+    Ok(ActionParams {
+        action_hash: parse_hash(hash)?,
+        threshold: threshold.parse().map_err(|e| format!("{e}: {line}"))?,
+        description_hash: [0; 32],
+        vote_mode: VoteMode::Headcount,
+        tier_thresholds: [0; kamiyo_fast_voting::MAX_EXTRA_TIERS],
+        dependencies: Vec::new(),
+        adaptive_quorum: None,
+        min_turnout_bps: 0,
+        option_count: 0,
+        ranked: false,
+        market_gate: None,
+        kind: ActionKind::Standard,
+        weight_cap: None,
+        class_limits: Vec::new(),
+        audited: false,
+        early_vote_bonus: false,
+        exclusions: Vec::new(),
+        deadline_extension: None,
+        identity_weighted: false,
+        member_collection: None,
+        lock_stake: false,
+        priority,
+        tags: Vec::new(),
+        quorum_weight_bps: 0,
+        privacy: PrivacyLevel::Public,
+        threshold_bps: 0,
+    })
+}
+
+/// Up to 8 bytes, zero-padded
+fn parse_tag(s: &str) -> Result<[u8; 8], String> {
+    if s.is_empty() || s.len() > 8 {
+        return Err("expected 1 to 8 bytes".into());
+    }
+    let mut tag = [0u8; 8];
+    tag[..s.len()].copy_from_slice(s.as_bytes());
+    Ok(tag)
+}
+
+fn tag_name(tag: &[u8; 8]) -> String {
+    let len = tag.iter().position(|b| *b == 0).unwrap_or(tag.len());
+    String::from_utf8_lossy(&tag[..len]).into_owned()
+}
+
+fn parse_hash(s: &str) -> Result<[u8; 32], String> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    if s.len() != 64 {
+        return Err("expected 64 hex characters".into());
+    }
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).map_err(|e| e.to_string())?;
+    }
+    Ok(out)
+}
+
+fn parse_account_meta(s: &str) -> Result<AccountMeta, String> {
+    let (key, writable) = match s.strip_suffix(":w") {
+        Some(key) => (key, true),
+        None => (s, false),
+    };
+    let pubkey = key.parse::<Pubkey>().map_err(|e| e.to_string())?;
+    Ok(AccountMeta {
+        pubkey,
+        is_signer: false,
+        is_writable: writable,
+    })
+}
+
+fn parse_callback(s: &str) -> Result<(Pubkey, Vec<AccountMeta>), String> {
+    let mut parts = s.split(',');
+    let program_id = parts.next().unwrap_or_default().parse::<Pubkey>().map_err(|e| e.to_string())?;
+    let accounts = parts.map(parse_account_meta).collect::<Result<_, _>>()?;
+    Ok((program_id, accounts))
+}
+
+fn parse_member_nft(s: &str) -> Result<MemberNft, String> {
+    let key = |s: &str| s.parse::<Pubkey>().map_err(|e| e.to_string());
+    Ok(match s.split_once(':') {
+        Some((token_account, mint)) => MemberNft::TokenMetadata {
+            token_account: key(token_account)?,
+            mint: key(mint)?,
+        },
+        None => MemberNft::Core(key(s)?),
+    })
+}
+
+fn parse_share(s: &str) -> Result<DelegationShare, String> {
+    let (delegate, bps) = s.split_once(':').ok_or("expected DELEGATE:BPS")?;
+    Ok(DelegationShare {
+        delegate: delegate.parse::<Pubkey>().map_err(|e| e.to_string())?,
+        bps: bps.parse::<u16>().map_err(|e| e.to_string())?,
+    })
+}
+
+fn parse_child(s: &str) -> Result<FederatedChild, String> {
+    let mut parts = s.splitn(3, ':');
+    let (Some(action), Some(hive), Some(weight)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err("expected ACTION:HIVE:WEIGHT".into());
+    };
+    Ok(FederatedChild {
+        action: action.parse::<Pubkey>().map_err(|e| e.to_string())?,
+        hive: hive.parse::<Pubkey>().map_err(|e| e.to_string())?,
+        weight: weight.parse::<u16>().map_err(|e| e.to_string())?,
+    })
+}
+
+fn parse_class(s: &str) -> Result<AgentClass, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "unclassified" => Ok(AgentClass::Unclassified),
+        "scout" => Ok(AgentClass::Scout),
+        "worker" => Ok(AgentClass::Worker),
+        "coordinator" => Ok(AgentClass::Coordinator),
+        _ => Err("expected unclassified, scout, worker or coordinator".into()),
+    }
+}
+
+fn parse_class_limit(s: &str) -> Result<ClassLimit, String> {
+    let mut parts = s.split(':');
+    let (Some(class), Some(max_votes)) = (parts.next(), parts.next()) else {
+        return Err("expected CLASS:MAX_VOTES[:WEIGHT_BPS]".into());
+    };
+    Ok(ClassLimit {
+        class: parse_class(class)?,
+        max_votes: max_votes.parse::<u32>().map_err(|e| e.to_string())?,
+        weight_bps: parts.next().map_or(Ok(0), str::parse::<u16>).map_err(|e| e.to_string())?,
+    })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+struct Ctx {
+    base: Client,
+    er: Client,
+    signer: Keypair,
+}
+
+impl Ctx {
+    fn client(&self, layer: Layer) -> &Client {
+        match layer {
+            Layer::Base => &self.base,
+            Layer::Er => &self.er,
+        }
+    }
+
+    fn send(&self, client: &Client, ix: Instruction) -> CliResult {
+        let signature = client.send(&[ix], &self.signer.pubkey(), &[&self.signer])?;
+        println!("{signature}");
+        Ok(())
+    }
+
+    /// One transaction per instruction, in order
+    fn send_all(&self, instructions: Vec<(Layer, Instruction)>) -> CliResult {
+        for (layer, ix) in instructions {
+            self.send(self.client(layer), ix)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Layer {
+    Base,
+    Er,
+}
+
+/// The chain state commands read to build their instructions: the live
+/// clusters for a run, fixed accounts in tests
+trait Chain {
+    fn next_action_id(&self) -> CliResult<u64>;
+    fn config(&self) -> CliResult<GlobalConfig>;
+    fn fast_action(&self, layer: Layer, action_id: u64) -> CliResult<FastAction>;
+    /// Delegated actions are owned by the delegation program on the base
+    /// layer; their live state is on the ER.
+    fn locate(&self, action_id: u64) -> CliResult<(Layer, FastAction)>;
+    fn account<T: AccountDeserialize>(&self, layer: Layer, address: &Pubkey) -> CliResult<T>;
+    /// Pages of a tag's index: `hive`'s, or the unscoped one with None
+    fn tag_pages(&self, hive: Option<&Pubkey>, tag: &[u8; 8]) -> CliResult<Vec<TagIndex>>;
+    /// The ER's current epoch
+    fn epoch(&self) -> CliResult<u64>;
+}
+
+impl Chain for Ctx {
+    fn next_action_id(&self) -> CliResult<u64> {
+        Ok(self.base.next_action_id()?)
+    }
+
+    fn config(&self) -> CliResult<GlobalConfig> {
+        Ok(self.base.config()?)
+    }
+
+    fn fast_action(&self, layer: Layer, action_id: u64) -> CliResult<FastAction> {
+        Ok(self.client(layer).fast_action(action_id)?)
+    }
+
+    fn locate(&self, action_id: u64) -> CliResult<(Layer, FastAction)> {
+        let address = pda::fast_action(action_id).0;
+        let account = self.base.rpc.get_account(&address)?;
+        let layer = if account.owner == ID { Layer::Base } else { Layer::Er };
+        Ok((layer, self.fast_action(layer, action_id)?))
+    }
+
+    fn account<T: AccountDeserialize>(&self, layer: Layer, address: &Pubkey) -> CliResult<T> {
+        Ok(self.client(layer).account(address)?)
+    }
+
+    fn tag_pages(&self, hive: Option<&Pubkey>, tag: &[u8; 8]) -> CliResult<Vec<TagIndex>> {
+        Ok(self.base.tag_pages(hive, tag)?)
+    }
+
+    fn epoch(&self) -> CliResult<u64> {
+        Ok(self.er.rpc.get_epoch_info()?.epoch)
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(cli) {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> CliResult {
+    let config = Config::load(cli.config.as_deref(), cli.url, cli.er_url, cli.keypair)?;
+    let signer = read_keypair_file(&config.keypair)
+        .map_err(|e| format!("{}: {e}", config.keypair.display()))?;
+    let ctx = Ctx {
+        base: Client::new(&config.rpc_url),
+        er: Client::new(&config.er_url),
+        signer,
+    };
+    let me = ctx.signer.pubkey();
+
+    match cli.command {
+        Command::Action(ActionCommand::Tagged { tag, hive }) => {
+            for page in ctx.base.tag_pages(hive.as_ref(), &tag)? {
+                for action in page.actions() {
+                    println!("{action}");
+                }
+            }
+            Ok(())
+        }
+        Command::Action(ActionCommand::Execute { action_id, callbacks }) => execute_staged(
+            &ctx,
+            instructions::stage_execution(me, action_id, &callbacks),
+            instructions::execute_action(me, action_id, &callbacks),
+        ),
+        Command::Keeper { interval, once } => loop {
+            crank(&ctx)?;
+            if once {
+                return Ok(());
+            }
+            std::thread::sleep(Duration::from_secs(interval));
+        },
+        Command::Action(ActionCommand::Status { action_id }) => {
+            let (layer, action) = ctx.locate(action_id)?;
+            print_action(action_id, layer == Layer::Er, &action);
+            let metadata = pda::action_metadata(&pda::fast_action(action_id).0).0;
+            if let Ok(metadata) = ctx.base.account::<ActionMetadata>(&metadata) {
+                if !metadata.description.is_empty() {
+                    println!("description {}", metadata.description);
+                }
+                if !metadata.uri.is_empty() {
+                    println!("uri         {}", metadata.uri);
+                }
+            }
+            Ok(())
+        }
+        Command::Tally { action_id } => {
+            let mut action = ctx.er.fast_action(action_id)?;
+            if action.ranked {
+                run_off(&ctx, action_id, &mut action)?;
+            }
+            if action.audited {
+                audit(&ctx, action_id)?;
+            }
+            ctx.send_all(instructions_for(&ctx, me, Command::Tally { action_id })?)
+        }
+        Command::Metrics { hive, init: false } => {
+            for (name, client, layer) in [("base", &ctx.base, MetricsLayer::Base), ("er", &ctx.er, MetricsLayer::Er)] {
+                let address = hive.map_or(pda::metrics(layer).0, |hive| pda::hive_metrics(layer, &hive).0);
+                // The program-wide accounts may predate `hive`
+                let data = client.rpc.get_account_data(&address).ok();
+                let Some(metrics) = data.and_then(|data| Metrics::decode(&data).ok()) else {
+                    println!("{name:<5} not initialized");
+                    continue;
+                };
+                println!(
+                    "{name:<5} created {}, finalized {}, expired {}, cancelled {}, votes {}, last activity slot {}",
+                    metrics.actions_created,
+                    metrics.actions_finalized,
+                    metrics.actions_expired,
+                    metrics.actions_cancelled,
+                    metrics.votes,
+                    metrics.last_activity_slot,
+                );
+            }
+            Ok(())
+        }
+        Command::Stats { hive, epoch, init: false } => {
+            let hive = hive.unwrap_or_default();
+            let epoch = match epoch {
+                Some(epoch) => epoch,
+                None => ctx.epoch()?,
+            };
+            let stats: EpochStats = ctx.er.account(&pda::epoch_stats(&hive, epoch).0)?;
+            println!("epoch       {}", stats.epoch);
+            println!("finalized   {}", stats.finalized);
+            println!("pass rate   {:.2}%", stats.pass_rate_bps() as f64 / 100.0);
+            println!("ballots     {}", stats.ballots);
+            println!("turnout     {:.2}%", stats.turnout_bps() as f64 / 100.0);
+            println!("to quorum   {} slots ({} sampled)", stats.avg_slots_to_quorum(), stats.quorum_samples);
+            println!("kinds       {:?}", stats.kinds);
+            Ok(())
+        }
+        Command::Votes(VotesCommand::List { action_id }) => {
+            let mut votes = ctx.er.fast_votes(action_id)?;
+            votes.sort_by_key(|(_, v)| v.voted_slot);
+            for (address, vote) in votes {
+                println!(
+                    "{address}  voter {}  {}  weight {}  slot {}",
+                    vote.voter,
+                    if vote.vote_value { "for" } else { "against" },
+                    vote.weight,
+                    vote.voted_slot,
+                );
+            }
+            Ok(())
+        }
+        Command::Conviction(ConvictionCommand::Status { hash }) => {
+            let address = pda::conviction_proposal(&hash).0;
+            let proposal: ConvictionProposal = ctx.base.account(&address)?;
+            println!("proposal    {address}");
+            println!("creator     {}", proposal.creator);
+            if proposal.hive != Pubkey::default() {
+                println!("hive        {}", proposal.hive);
+            }
+            println!(
+                "conviction  {} / {} ({} staked)",
+                proposal.conviction, proposal.threshold, proposal.total_staked
+            );
+            println!("updated     slot {}", proposal.last_update_slot);
+            if proposal.executed {
+                println!("executed    slot {}", proposal.executed_slot);
+            }
+            Ok(())
+        }
+        Command::Draft(DraftCommand::Status { creator, hash }) => {
+            let address = pda::proposal_draft(&creator, &hash).0;
+            let draft: ProposalDraft = ctx.base.account(&address)?;
+            println!("draft       {address}");
+            println!("creator     {}", draft.creator);
+            println!("params      {}", hex(&draft.params_digest));
+            println!("signatures  {} / {}", draft.signatures(), draft.required);
+            for (i, sponsor) in draft.sponsors[..draft.sponsor_count as usize].iter().enumerate() {
+                let signed = draft.signed_mask & (1 << i) != 0;
+                println!("  {sponsor} {}", if signed { "signed" } else { "pending" });
+            }
+            if draft.action != Pubkey::default() {
+                println!("action      {}", draft.action);
+            }
+            Ok(())
+        }
+        Command::Hive(HiveCommand::Status { hive }) => {
+            let address = pda::hive(&hive).0;
+            let account: Hive = ctx.base.account(&address)?;
+            println!("hive        {address}");
+            println!("key         {}", account.hive);
+            println!("authority   {}", account.authority);
+            println!("name        {}", hex(&account.name_hash));
+            println!("members     {}", hex(&account.member_root));
+            println!("max/epoch   {}", account.overrides.max_actions_per_epoch);
+            println!("quorum      {}", account.overrides.default_quorum);
+            if let Ok(allowlist) = ctx.base.account::<CreatorAllowlist>(&pda::creator_allowlist(&hive).0) {
+                println!("restricted  {}", allowlist.restricted);
+                for creator in allowlist.creators() {
+                    println!("  {creator}");
+                }
+            }
+            Ok(())
+        }
+        Command::Federation(FederationCommand::Status { hash }) => {
+            let address = pda::federated_action(&hash).0;
+            let federated: FederatedAction = ctx.base.account(&address)?;
+            println!("federated   {address}");
+            println!("creator     {}", federated.creator);
+            println!("threshold   {}%", federated.threshold);
+            for child in federated.children() {
+                println!("  {} hive {} weight {}", child.action, child.hive, child.weight);
+            }
+            println!("result      {:?}", federated.result);
+            if federated.result != VoteResult::Pending {
+                println!(
+                    "passed      {} / {} (slot {})",
+                    federated.weight_passed,
+                    federated.total_weight(),
+                    federated.settled_slot
+                );
+            }
+            Ok(())
+        }
+        command => ctx.send_all(instructions_for(&ctx, me, command)?),
+    }
+}
+
+/// Where action `action_id` is created: under `hive` if it is scoped to one
+fn action_address(hive: Option<&Pubkey>, action_id: u64) -> Pubkey {
+    hive.map_or(pda::fast_action(action_id).0, |h| pda::hive_action(h, action_id).0)
+}
+
+/// The instructions `command` sends, in order, and the layer each goes to;
+/// everything they read comes from `chain`
+fn instructions_for(chain: &impl Chain, me: Pubkey, command: Command) -> CliResult<Vec<(Layer, Instruction)>> {
+    match command {
+        Command::Action(ActionCommand::Create {
+            hash,
+            threshold,
+            threshold_bps,
+            description_hash,
+            weighted,
+            emergency,
+            election,
+            task_assignment,
+            optimistic,
+            tiers,
+            quorum,
+            quorum_floor,
+            quorum_grace,
+            min_turnout_bps,
+            options,
+            ranked,
+            audited,
+            early_bonus,
+            identity,
+            member_collection,
+            lock_stake,
+            quorum_weight_bps,
+            priority,
+            tags,
+            max_weight,
+            max_weight_bps,
+            class_limits,
+            market_feed,
+            market_program,
+            market_price_offset,
+            market_min_price,
+            dependencies,
+            exclusions,
+            max_extensions,
+            extension_support_bps,
+            hive,
+            previous_action,
+            sponsors,
+            required,
+            from_draft,
+        }) => {
+            let mut tier_thresholds = [0u8; kamiyo_fast_voting::MAX_EXTRA_TIERS];
+            if tiers.len() > tier_thresholds.len() {
+                return Err(format!("at most {} tiers", tier_thresholds.len()).into());
+            }
+            tier_thresholds[..tiers.len()].copy_from_slice(&tiers);
+
+            let action_id = chain.next_action_id()?;
+            let params = ActionParams {
+                action_hash: hash,
+                threshold,
+                description_hash: description_hash.unwrap_or_default(),
+                vote_mode: match (weighted, optimistic) {
+                    (true, _) => VoteMode::TokenWeighted,
+                    (_, true) => VoteMode::Optimistic,
+                    _ => VoteMode::Headcount,
+                },
+                tier_thresholds,
+                dependencies,
+                adaptive_quorum: quorum.map(|quorum| AdaptiveQuorum {
+                    quorum,
+                    floor: quorum_floor.unwrap_or(quorum),
+                    grace_slots: quorum_grace.unwrap_or(0),
+                }),
+                min_turnout_bps,
+                option_count: options,
+                ranked,
+                market_gate: market_feed.map(|feed| MarketGate {
+                    feed,
+                    feed_program: market_program.unwrap_or_default(),
+                    price_offset: market_price_offset,
+                    min_price: market_min_price.unwrap_or_default(),
+                }),
+                kind: match (emergency, election, task_assignment) {
+                    (true, _, _) => ActionKind::Emergency,
+                    (_, true, _) => ActionKind::Election,
+                    (_, _, true) => ActionKind::TaskAssignment,
+                    _ => ActionKind::Standard,
+                },
+                weight_cap: max_weight
+                    .map(WeightCap::Absolute)
+                    .or(max_weight_bps.map(WeightCap::SupplyBps)),
+                class_limits,
+                audited,
+                early_vote_bonus: early_bonus,
+                exclusions,
+                deadline_extension: max_extensions
+                    .zip(extension_support_bps)
+                    .map(|(max_extensions, support_bps)| ExtensionPolicy { max_extensions, support_bps }),
+                identity_weighted: identity,
+                member_collection,
+                lock_stake,
+                priority,
+                tags: tags.clone(),
+                quorum_weight_bps: quorum_weight_bps.unwrap_or(0),
+                privacy: PrivacyLevel::Public,
+                threshold_bps: threshold_bps.unwrap_or(0),
+            };
+            if let Some(required) = required {
+                println!("draft at {}", pda::proposal_draft(&me, &hash).0);
+                return base(instructions::create_draft(me, params, sponsors, required));
+            }
+            println!("action {action_id} at {}", action_address(hive.as_ref(), action_id));
+            let create = if from_draft {
+                instructions::promote_draft
+            } else {
+                instructions::create_fast_action
+            };
+            // Supply-relative caps, explicit or the kind's default, read the mint
+            let weight_mint = weighted.then(|| chain.config()).transpose()?.map(|c| c.weight_mint);
+            let mut ix = create(me, action_id, params, previous_action, hive, weight_mint);
+            // List each tag in its first page with room
+            let mut pages = Vec::new();
+            for tag in &tags {
+                let page = chain
+                    .tag_pages(hive.as_ref(), tag)?
+                    .iter()
+                    .position(|page| (page.count as usize) < TAG_PAGE_LEN)
+                    .ok_or_else(|| format!("no page of tag {} has room; index-tag one", tag_name(tag)))?;
+                pages.push((*tag, page as u32));
+            }
+            ix.accounts.truncate(ix.accounts.len() - tags.len());
+            ix.accounts.extend(instructions::tag_accounts(hive.as_ref(), &pages));
+            base(ix)
+        }
+        Command::Action(ActionCommand::CreateBatch { manifest, hive }) => {
+            let batch = std::fs::read_to_string(&manifest)?
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(parse_manifest_line)
+                .collect::<Result<Vec<_>, _>>()?;
+            let first_id = chain.next_action_id()?;
+            for action_id in (first_id..).take(batch.len()) {
+                println!("action {action_id} at {}", action_address(hive.as_ref(), action_id));
+            }
+            base(instructions::create_actions_batch(me, first_id, batch, hive))
+        }
+        Command::Action(ActionCommand::Delegate { action_id, validator }) => {
+            base(instructions::delegate_action(me, action_id, validator))
+        }
+        Command::Action(ActionCommand::Runoff { action_id, delegate }) => {
+            let runoff_id = chain.next_action_id()?;
+            println!("runoff {runoff_id} at {}", pda::fast_action(runoff_id).0);
+            if delegate {
+                let parent = chain.fast_action(Layer::Base, action_id)?;
+                let validator = Some(parent.delegated_validator).filter(|v| *v != Pubkey::default());
+                return base(instructions::spawn_and_delegate(me, action_id, runoff_id, validator));
+            }
+            base(instructions::spawn_runoff(me, action_id, runoff_id))
+        }
+        Command::Action(ActionCommand::ClearMarket { action_id }) => {
+            let action = chain.fast_action(Layer::Base, action_id)?;
+            if action.market_feed == Pubkey::default() {
+                return Err("action has no market gate".into());
+            }
+            base(instructions::clear_market_gate(action_id, action.market_feed))
+        }
+        Command::Action(ActionCommand::Expire { action_id }) => {
+            let action = chain.fast_action(Layer::Base, action_id)?;
+            let validator = action.validator_counted.then_some(action.delegated_validator);
+            let mut ix = instructions::expire_action(action_id, action.creator, validator);
+            if action.tags_indexed {
+                ix.accounts.extend(instructions::tag_accounts(None, &listing_pages(chain, action_id, &action)?));
+            }
+            base(ix)
+        }
+        Command::Action(ActionCommand::Retire { action_id }) => {
+            base(instructions::retire_action(pda::fast_action(action_id).0, None))
+        }
+        Command::Action(ActionCommand::Release { action_id }) => {
+            let action = chain.fast_action(Layer::Base, action_id)?;
+            if !action.validator_counted {
+                return Err("action is not counted against a validator".into());
+            }
+            let fast_action = pda::fast_action(action_id).0;
+            base(instructions::release_validator(fast_action, action.delegated_validator))
+        }
+        Command::Action(ActionCommand::IndexLanes { hive }) => {
+            println!("index at {}", pda::active_actions(hive.as_ref()).0);
+            base(instructions::initialize_active_actions(me, hive))
+        }
+        Command::Action(ActionCommand::IndexTag { tag, page, hive }) => {
+            println!("page at {}", pda::tag_index(hive.as_ref(), &tag, page).0);
+            base(instructions::initialize_tag_index(me, hive, tag, page))
+        }
+        Command::Action(ActionCommand::RetireTags { action_id }) => {
+            let action = chain.fast_action(Layer::Base, action_id)?;
+            let pages = listing_pages(chain, action_id, &action)?;
+            base(instructions::retire_tags(pda::fast_action(action_id).0, None, &pages))
+        }
+        Command::Action(ActionCommand::Reap { action_ids }) => {
+            let actions: Vec<Pubkey> = action_ids.iter().map(|id| pda::fast_action(*id).0).collect();
+            er(instructions::reap_stale_delegations(me, &actions))
+        }
+        Command::Action(ActionCommand::ReleaseStake { action_id, voter }) => {
+            let lock: StakeLock = chain.account(Layer::Base, &pda::stake_lock(&voter).0)?;
+            let weight_mint = chain.config()?.weight_mint;
+            let ix = instructions::release_stake_lock(action_id, voter, lock.program, lock.token_account, weight_mint);
+            base(ix)
+        }
+        Command::Action(ActionCommand::InstallLeader {
+            action_id,
+            candidates,
+            term_slots,
+        }) => {
+            let action = chain.fast_action(Layer::Base, action_id)?;
+            let VoteResult::Chosen { option } = action.result else {
+                return Err(format!("election has not chosen a winner: {:?}", action.result).into());
+            };
+            let leader = *candidates.get(option as usize).ok_or("fewer candidates than options")?;
+            println!("leader {leader} of hive {}", action.hive);
+            let election = Election { candidates, term_slots };
+            base(instructions::install_leader(me, action_id, action.hive, leader, election))
+        }
+        Command::Action(ActionCommand::PushTask {
+            action_id,
+            queue_program,
+            queue,
+            task_hash,
+            assignees,
+            deadline_slot,
+        }) => {
+            let task = TaskAssignment {
+                queue_program,
+                queue,
+                task_hash,
+                assignees,
+                deadline_slot,
+            };
+            base(instructions::push_task(me, action_id, task, &[]))
+        }
+        Command::Action(ActionCommand::MintReceipt { action_id, voter }) => {
+            let tree = chain.account::<ReceiptTree>(Layer::Base, &pda::receipt_tree().0)?;
+            let voter = voter.unwrap_or(me);
+            base(instructions::mint_receipt(me, action_id, voter, tree.merkle_tree))
+        }
+        Command::Action(ActionCommand::Veto { action_id, reason }) => {
+            base(instructions::veto_action(me, action_id, reason))
+        }
+        Command::Action(ActionCommand::Amend {
+            action_id,
+            threshold,
+            window,
+            description_hash,
+        }) => {
+            let action = chain.fast_action(Layer::Base, action_id)?;
+            let amendment = ActionAmendment {
+                threshold,
+                window_slots: window,
+                description_hash,
+            };
+            base(instructions::amend_action(me, action_id, action.action_hash, amendment))
+        }
+        Command::Action(ActionCommand::Metadata {
+            action_id,
+            description,
+            uri,
+        }) => base(instructions::set_metadata(me, action_id, description, uri)),
+        Command::Action(ActionCommand::Seal {
+            action_id,
+            ciphertext_hash,
+            encryption_key,
+        }) => base(instructions::seal_description(me, action_id, ciphertext_hash, encryption_key)),
+        Command::Action(ActionCommand::TransferAuthority { action_id, new_authority }) => {
+            let (layer, _) = chain.locate(action_id)?;
+            Ok(vec![(layer, instructions::transfer_action_authority(me, action_id, new_authority))])
+        }
+        Command::Action(ActionCommand::Callback {
+            action_id,
+            program_id,
+            accounts,
+        }) => base(instructions::register_callback(me, action_id, program_id, &accounts)),
+        Command::Action(ActionCommand::Schedule {
+            action_id,
+            reward,
+            callbacks,
+        }) => base(instructions::schedule_execution(me, action_id, reward, &callbacks)),
+        Command::Action(ActionCommand::Unschedule { action_id }) => {
+            base(instructions::cancel_execution(me, action_id))
+        }
+        Command::Vote {
+            action_id,
+            side,
+            weights,
+            ranking,
+            commitment,
+            token_account,
+            identity_asset,
+            member_nft,
+        } => {
+            let action = chain.fast_action(Layer::Er, action_id)?;
+            let address = pda::fast_action(action_id).0;
+            let commitment = commitment.unwrap_or_else(|| hashv(&[me.as_ref(), address.as_ref()]).to_bytes());
+            let epoch = action.delegation_epoch;
+            if action.ranked {
+                if ranking.is_empty() {
+                    return Err("--ranking is required for ranked-choice actions".into());
+                }
+                return er(instructions::vote_ranked(me, action_id, epoch, ranking, commitment));
+            }
+            if action.option_count > 0 {
+                if weights.is_empty() {
+                    return Err("--weights is required for multi-option actions".into());
+                }
+                let weight_accounts = match action.vote_mode {
+                    VoteMode::TokenWeighted => {
+                        let token_account =
+                            token_account.ok_or("--token-account is required for token-weighted actions")?;
+                        let config = chain.config()?;
+                        Some((config.weight_mint, token_account, config.stake_lock_program))
+                    }
+                    _ => None,
+                };
+                // A token-weighted ballot locks the voter's stake, on the base layer
+                let layer = if weight_accounts.is_some() { Layer::Base } else { Layer::Er };
+                let ix = instructions::vote_options(me, action_id, epoch, weights, commitment, weight_accounts);
+                return Ok(vec![(layer, ix)]);
+            }
+            let vote_value = matches!(side.ok_or("for or against is required")?, Side::For);
+            let ix = match action.vote_mode {
+                VoteMode::Headcount | VoteMode::Optimistic => {
+                    if action.vote_mode == VoteMode::Optimistic && vote_value {
+                        return Err("optimistic actions only accept vetoes".into());
+                    }
+                    let ix = instructions::vote_fast(me, action_id, epoch, vote_value, commitment);
+                    match (action.member_collection != Pubkey::default(), &member_nft) {
+                        (false, _) => ix,
+                        (true, Some(nft)) => instructions::with_member_nft(ix, nft),
+                        (true, None) => return Err("--member-nft is required for collection-gated actions".into()),
+                    }
+                }
+                VoteMode::TokenWeighted if action.stake_lock_program != Pubkey::default() => {
+                    let token_account = token_account.ok_or("--token-account is required for token-weighted actions")?;
+                    let ix = instructions::vote_fast_locked(
+                        me,
+                        action_id,
+                        epoch,
+                        vote_value,
+                        commitment,
+                        (chain.config()?.weight_mint, token_account),
+                        action.stake_lock_program,
+                    );
+                    return base(ix);
+                }
+                VoteMode::TokenWeighted if action.identity_weighted => {
+                    let asset = identity_asset.ok_or("--identity-asset is required for identity-weighted actions")?;
+                    instructions::vote_fast_identity(me, action_id, epoch, vote_value, commitment, asset)
+                }
+                VoteMode::TokenWeighted => {
+                    let token_account = token_account.ok_or("--token-account is required for token-weighted actions")?;
+                    let config = chain.config()?;
+                    let ix = instructions::vote_fast_weighted(
+                        me,
+                        action_id,
+                        epoch,
+                        vote_value,
+                        commitment,
+                        (config.weight_mint, token_account),
+                        config.stake_lock_program,
+                    );
+                    return base(ix);
+                }
+            };
+            er(ix)
+        }
+        Command::DelegateVotes { shares } => base(instructions::set_vote_delegation(me, shares)),
+        Command::Tally { action_id } => {
+            let action = chain.fast_action(Layer::Er, action_id)?;
+            let hive = (action.hive != Pubkey::default()).then_some(action.hive);
+            let dependencies = &action.dependencies[..action.dependency_count as usize];
+            let epoch = chain.epoch()?;
+            er(instructions::tally_and_commit(me, action_id, hive, dependencies, action.audited, Some(epoch)))
+        }
+        Command::Cancel { action_id } => {
+            let (layer, action) = chain.locate(action_id)?;
+            // The action authority cancels directly; anyone else needs the hive's roles
+            let as_canceller = action.authority != me && action.hive != Pubkey::default();
+            let metrics = match layer {
+                Layer::Base => MetricsLayer::Base,
+                Layer::Er => MetricsLayer::Er,
+            };
+            let ix = instructions::cancel_action(me, action_id, action.hive, as_canceller, metrics);
+            Ok(vec![(layer, ix)])
+        }
+        Command::Conviction(ConvictionCommand::Create {
+            hash,
+            threshold,
+            half_life,
+            hive,
+        }) => {
+            if half_life == 0 {
+                return Err("--half-life must be at least 1 slot".into());
+            }
+            let retention_ppb = (0.5f64.powf(1.0 / half_life as f64) * 1e9) as u32;
+            println!("proposal at {}", pda::conviction_proposal(&hash).0);
+            base(instructions::create_conviction_proposal(me, hash, threshold, retention_ppb, hive))
+        }
+        Command::Conviction(ConvictionCommand::Stake { hash, lamports }) => {
+            base(instructions::stake_conviction(me, &hash, lamports))
+        }
+        Command::Conviction(ConvictionCommand::Withdraw { hash, lamports }) => {
+            base(instructions::withdraw_conviction(me, &hash, lamports))
+        }
+        Command::Conviction(ConvictionCommand::Update { hash }) => {
+            base(instructions::update_conviction(&hash))
+        }
+        Command::Draft(DraftCommand::Sponsor { creator, hash }) => {
+            base(instructions::sponsor_draft(me, creator, hash))
+        }
+        Command::Agent(AgentCommand::Register { lamports }) => {
+            base(instructions::register_agent(me, lamports))
+        }
+        Command::Agent(AgentCommand::Evict { agent }) => base(instructions::evict_agent(me, agent)),
+        Command::Agent(AgentCommand::Class { agent, class }) => {
+            base(instructions::set_agent_class(me, agent, class))
+        }
+        Command::Agent(AgentCommand::Migrate { agent }) => {
+            base(instructions::migrate_agent_record(me, agent))
+        }
+        Command::Hive(HiveCommand::Create { name, authority }) => {
+            let name_hash = hashv(&[name.as_bytes()]).to_bytes();
+            println!("hive at {}", pda::hive(&me).0);
+            base(instructions::create_hive(me, me, name_hash, authority.unwrap_or(me)))
+        }
+        Command::Hive(HiveCommand::Update {
+            hive,
+            authority,
+            member_root,
+            max_actions_per_epoch,
+            default_quorum,
+        }) => {
+            let overrides = if max_actions_per_epoch.is_some() || default_quorum.is_some() {
+                let current: Hive = chain.account(Layer::Base, &pda::hive(&hive).0)?;
+                Some(HiveOverrides {
+                    max_actions_per_epoch: max_actions_per_epoch.unwrap_or(current.overrides.max_actions_per_epoch),
+                    default_quorum: default_quorum.unwrap_or(current.overrides.default_quorum),
+                })
+            } else {
+                None
+            };
+            let update = HiveUpdate {
+                authority,
+                member_root,
+                overrides,
+            };
+            base(instructions::update_hive(me, hive, update))
+        }
+        Command::Hive(HiveCommand::Restrict { hive, lift }) => {
+            base(instructions::set_creator_restriction(me, hive, !lift))
+        }
+        Command::Hive(HiveCommand::Allow { hive, creator }) => {
+            base(instructions::allow_creator(me, hive, creator))
+        }
+        Command::Hive(HiveCommand::Disallow { hive, creator }) => {
+            base(instructions::disallow_creator(me, hive, creator))
+        }
+        Command::Federation(FederationCommand::Create {
+            hash,
+            threshold,
+            children,
+        }) => {
+            println!("federated action at {}", pda::federated_action(&hash).0);
+            base(instructions::create_federated_action(me, hash, threshold, children))
+        }
+        Command::Federation(FederationCommand::Settle { hash }) => {
+            let federated: FederatedAction = chain.account(Layer::Base, &pda::federated_action(&hash).0)?;
+            let children: Vec<Pubkey> = federated.children().iter().map(|c| c.action).collect();
+            base(instructions::settle_federated_action(&hash, &children))
+        }
+        Command::Metrics { hive: Some(hive), init: true } => Ok(vec![
+            (Layer::Base, instructions::initialize_hive_metrics(me, hive, MetricsLayer::Base)),
+            (Layer::Er, instructions::initialize_hive_metrics(me, hive, MetricsLayer::Er)),
+        ]),
+        Command::Stats { hive, epoch, init: true } => {
+            let epoch = match epoch {
+                Some(epoch) => epoch,
+                None => chain.epoch()?,
+            };
+            er(instructions::initialize_epoch_stats(me, hive.unwrap_or_default(), epoch))
+        }
+        _ => Err("command sends no instructions".into()),
+    }
+}
+
+fn base(ix: Instruction) -> CliResult<Vec<(Layer, Instruction)>> {
+    Ok(vec![(Layer::Base, ix)])
+}
+
+fn er(ix: Instruction) -> CliResult<Vec<(Layer, Instruction)>> {
+    Ok(vec![(Layer::Er, ix)])
+}
+
+/// Eliminate options until one holds a majority, moving ballots in batches
+/// small enough for one transaction each.
+fn run_off(ctx: &Ctx, action_id: u64, action: &mut FastAction) -> CliResult {
+    const BALLOTS_PER_TX: usize = 20;
+    loop {
+        if action.pending_elimination != NO_ELIMINATION {
+            let eliminated = action.pending_elimination;
+            let ballots: Vec<Pubkey> = ctx
+                .er
+                .ranked_ballots(action_id)?
+                .into_iter()
+                .filter(|(_, ballot)| ballot.current() == Some(eliminated))
+                .map(|(address, _)| address)
+                .collect();
+            for batch in ballots.chunks(BALLOTS_PER_TX) {
+                ctx.send(&ctx.er, instructions::tally_round(action_id, batch))?;
+            }
+        } else {
+            let options = &action.option_weights[..action.option_count as usize];
+            if tally::runoff_winner(options, action.eliminated_mask).is_some() {
+                return Ok(());
+            }
+            ctx.send(&ctx.er, instructions::tally_round(action_id, &[]))?;
+        }
+        *action = ctx.er.fast_action(action_id)?;
+    }
+}
+
+/// Recount an audited action's ballots, picking up after any pages already
+/// verified.
+fn audit(ctx: &Ctx, action_id: u64) -> CliResult {
+    const VOTES_PER_TX: usize = 20;
+    let audit = pda::tally_audit(&pda::fast_action(action_id).0).0;
+    let last_vote = match ctx.er.account::<TallyAudit>(&audit) {
+        Ok(audit) => audit.last_vote,
+        Err(kamiyo_hive_client::Error::AccountNotFound(_)) => Pubkey::default(),
+        Err(err) => return Err(err.into()),
+    };
+    let mut votes: Vec<Pubkey> = ctx
+        .er
+        .fast_votes(action_id)?
+        .into_iter()
+        .map(|(address, _)| address)
+        .filter(|address| *address > last_vote)
+        .collect();
+    votes.sort();
+    for page in votes.chunks(VOTES_PER_TX) {
+        ctx.send(&ctx.er, instructions::verify_tally_page(ctx.signer.pubkey(), action_id, page))?;
+    }
+    Ok(())
+}
+
+/// One keeper pass: execute every scheduled action that has settled, higher
+/// priority lanes first. A failed send is reported and the pass moves on,
+/// since another keeper may have executed it first.
+fn crank(ctx: &Ctx) -> CliResult {
+    let slot = ctx.base.rpc.get_slot()?;
+    // Closed by expire_action; its funder has to unschedule it
+    let mut due: Vec<_> = ctx
+        .base
+        .execution_schedules()?
+        .into_iter()
+        .filter_map(|(address, schedule)| {
+            let action = ctx.base.account::<FastAction>(&schedule.action).ok()?;
+            Some((address, schedule, action))
+        })
+        .collect();
+    due.sort_by_key(|(_, _, action)| std::cmp::Reverse(action.priority));
+    for (address, schedule, action) in due {
+        let passed = matches!(action.result, VoteResult::Passed { .. });
+        if !dispute::is_settled(&action, slot) || (passed && !market::is_executable(&action, slot)) {
+            continue;
+        }
+        println!("executing   {} (reward {} lamports)", schedule.action, schedule.reward);
+        let keeper = ctx.signer.pubkey();
+        let stage = instructions::stage_scheduled(keeper, &schedule);
+        let ix = instructions::execute_scheduled(keeper, address, &schedule);
+        if let Err(err) = execute_staged(ctx, stage, ix) {
+            eprintln!("  failed: {err}");
+        }
+    }
+    Ok(())
+}
+
+/// The page listing the unscoped action `action_id` for each of its tags
+fn listing_pages(chain: &impl Chain, action_id: u64, action: &FastAction) -> CliResult<Vec<([u8; 8], u32)>> {
+    let address = pda::fast_action(action_id).0;
+    let mut pages = Vec::new();
+    for tag in action.tag_list() {
+        let page = chain
+            .tag_pages(None, tag)?
+            .iter()
+            .find(|page| page.actions().contains(&address))
+            .map(|page| page.page)
+            .ok_or_else(|| format!("action is not listed under tag {}", tag_name(tag)))?;
+        pages.push((*tag, page));
+    }
+    Ok(pages)
+}
+
+/// Send `stage`, then `execute` once the base layer has moved past its slot
+fn execute_staged(ctx: &Ctx, stage: Instruction, execute: Instruction) -> CliResult {
+    ctx.send(&ctx.base, stage)?;
+    let staged = ctx.base.rpc.get_slot()?;
+    while ctx.base.rpc.get_slot()? <= staged {
+        std::thread::sleep(Duration::from_millis(400));
+    }
+    ctx.send(&ctx.base, execute)
+}
+
+fn print_action(action_id: u64, delegated: bool, action: &FastAction) {
+    println!("action      {}", pda::fast_action(action_id).0);
+    println!("id          {}", action.action_id);
+    println!("location    {}", if delegated { "ephemeral rollup" } else { "base layer" });
+    println!("creator     {}", action.creator);
+    if action.authority != action.creator {
+        println!("authority   {}", action.authority);
+    }
+    if action.hive != Pubkey::default() {
+        println!("hive        {}", action.hive);
+    }
+    println!("hash        {}", hex(&action.action_hash));
+    println!("mode        {:?}", action.vote_mode);
+    println!("priority    {}{}", action.priority, if action.lane_counted { " (counted)" } else { "" });
+    if !action.tag_list().is_empty() {
+        let tags: Vec<String> = action.tag_list().iter().map(tag_name).collect();
+        println!("tags        {}{}", tags.join(", "), if action.tags_indexed { " (indexed)" } else { "" });
+    }
+    if action.identity_weighted {
+        println!("weighing    identity tiers");
+    }
+    if action.member_collection != Pubkey::default() {
+        println!("members     holders of {}", action.member_collection);
+    }
+    if action.stake_lock_program != Pubkey::default() {
+        println!("stake lock  {}", action.stake_lock_program);
+    }
+    if action.kind != ActionKind::Standard {
+        println!("kind        {:?}", action.kind);
+    }
+    if action.vote_mode == VoteMode::Optimistic {
+        println!("veto at     {}% of {} members", action.threshold, action.electorate);
+    } else {
+        println!("threshold   {}% tiers {:?}", action.threshold, action.tier_thresholds);
+    }
+    if action.option_count > 0 {
+        let options = &action.option_weights[..action.option_count as usize];
+        println!("options     {:?} ({} cast)", options, action.vote_count);
+        if action.ranked {
+            let eliminated: Vec<usize> = (0..options.len())
+                .filter(|i| action.eliminated_mask & (1 << i) != 0)
+                .collect();
+            println!(
+                "runoff      round {} eliminated {:?} ({} exhausted)",
+                action.round, eliminated, action.exhausted_ballots
+            );
+        }
+    } else {
+        println!(
+            "votes       {} for / {} against ({} cast)",
+            action.votes_for, action.votes_against, action.vote_count
+        );
+    }
+    if action.vote_mode == VoteMode::TokenWeighted {
+        println!("weight      {} for / {} against", action.weight_for, action.weight_against);
+    }
+    if action.audited {
+        println!("tally       audited");
+    }
+    if action.market_feed != Pubkey::default() {
+        println!(
+            "market      {} above {}{}",
+            action.market_feed,
+            action.market_min_price,
+            if action.market_cleared { " (cleared)" } else { "" }
+        );
+    }
+    if action.parent_action != Pubkey::default() {
+        println!("parent      {}", action.parent_action);
+    }
+    if action.runoff_action != Pubkey::default() {
+        println!("runoff at   {}", action.runoff_action);
+    }
+    for dependency in &action.dependencies[..action.dependency_count as usize] {
+        println!("depends on  {dependency}");
+    }
+    for excluded in &action.excluded[..action.excluded_count as usize] {
+        println!("excludes    {excluded}");
+    }
+    if action.min_turnout_bps > 0 {
+        println!("turnout     {} bps of {} members", action.min_turnout_bps, action.electorate);
+    }
+    println!("deadline    slot {}", action.deadline_slot);
+    if action.extension_policy.max_extensions > 0 {
+        println!(
+            "extensions  {} of {} used ({} slots), {} bps of voters to extend",
+            action.extensions,
+            action.extension_policy.max_extensions,
+            action.extended_slots,
+            action.extension_policy.support_bps
+        );
+    }
+    if action.quorum_grace_slots > 0 {
+        println!(
+            "quorum      {} decaying to {} over {} slots",
+            action.quorum, action.quorum_floor, action.quorum_grace_slots
+        );
+    }
+    println!("result      {:?}", action.result);
+    if action.finalized_slot != 0 {
+        println!("finalized   slot {}", action.finalized_slot);
+        println!("digest      {}", hex(&action.result_digest));
+    }
+    for entry in action.log.recent() {
+        println!("log         slot {} {:?} {}", entry.slot, entry.tag, entry.payload);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{AccountSerialize, Discriminator};
+
+    const ACTION_ID: u64 = 7;
+    const NEXT_ID: u64 = 40;
+    const EPOCH: u64 = 12;
+    const TAG: [u8; 8] = *b"treasury";
+
+    fn key(n: u8) -> Pubkey {
+        Pubkey::new_from_array([n; 32])
+    }
+
+    fn me() -> Pubkey {
+        key(1)
+    }
+
+    fn hash() -> String {
+        "ab".repeat(32)
+    }
+
+    fn zeroed<T: AccountDeserialize + Discriminator>() -> T {
+        let mut data = T::DISCRIMINATOR.to_vec();
+        data.resize(4096, 0);
+        T::try_deserialize(&mut &data[..]).unwrap()
+    }
+
+    /// Action 7 on `layer`; the config's weight mint is key(30) and its
+    /// stake lock program key(31). Page 0 of the unscoped treasury tag is
+    /// full and page 1 lists action 7
+    struct Fixture {
+        layer: Layer,
+        action: FastAction,
+        config: GlobalConfig,
+        accounts: Vec<(Layer, Pubkey, Vec<u8>)>,
+        tag_pages: Vec<TagIndex>,
+    }
+
+    impl Fixture {
+        fn on(layer: Layer) -> Self {
+            let mut action: FastAction = zeroed();
+            action.action_id = ACTION_ID;
+            action.creator = key(2);
+            action.authority = me();
+            action.delegation_epoch = 3;
+            let mut config: GlobalConfig = zeroed();
+            config.weight_mint = key(30);
+            config.stake_lock_program = key(31);
+            let tag_pages = (0..2)
+                .map(|page| {
+                    let mut index: TagIndex = zeroed();
+                    index.tag = TAG;
+                    index.page = page;
+                    index.count = if page == 0 { TAG_PAGE_LEN as u8 } else { 1 };
+                    index.actions[0] = if page == 0 { key(50) } else { pda::fast_action(ACTION_ID).0 };
+                    index
+                })
+                .collect();
+            Fixture {
+                layer,
+                action,
+                config,
+                accounts: Vec::new(),
+                tag_pages,
+            }
+        }
+
+        fn with<T: AccountSerialize>(mut self, address: Pubkey, account: &T) -> Self {
+            let mut data = Vec::new();
+            account.try_serialize(&mut data).unwrap();
+            self.accounts.push((Layer::Base, address, data));
+            self
+        }
+
+        /// What `args` sends, signed by key(1)
+        fn build(&self, args: &str) -> CliResult<Vec<(Layer, Instruction)>> {
+            let cli = Cli::try_parse_from(std::iter::once("kamiyo-cli").chain(args.split_whitespace()))?;
+            instructions_for(self, me(), cli.command)
+        }
+    }
+
+    impl Chain for Fixture {
+        fn next_action_id(&self) -> CliResult<u64> {
+            Ok(NEXT_ID)
+        }
+
+        fn config(&self) -> CliResult<GlobalConfig> {
+            Ok(self.config.clone())
+        }
+
+        fn fast_action(&self, layer: Layer, action_id: u64) -> CliResult<FastAction> {
+            if layer != self.layer || action_id != ACTION_ID {
+                return Err(format!("no action {action_id} on {layer:?}").into());
+            }
+            Ok(self.action.clone())
+        }
+
+        fn locate(&self, action_id: u64) -> CliResult<(Layer, FastAction)> {
+            Ok((self.layer, self.fast_action(self.layer, action_id)?))
+        }
+
+        fn account<T: AccountDeserialize>(&self, layer: Layer, address: &Pubkey) -> CliResult<T> {
+            let (_, _, data) = self
+                .accounts
+                .iter()
+                .find(|(on, at, _)| *on == layer && at == address)
+                .ok_or_else(|| format!("no account {address} on {layer:?}"))?;
+            Ok(T::try_deserialize(&mut &data[..])?)
+        }
+
+        fn tag_pages(&self, _hive: Option<&Pubkey>, tag: &[u8; 8]) -> CliResult<Vec<TagIndex>> {
+            Ok(self.tag_pages.iter().filter(|page| page.tag == *tag).cloned().collect())
+        }
+
+        fn epoch(&self) -> CliResult<u64> {
+            Ok(EPOCH)
+        }
+    }
+
+    fn base(ix: Instruction) -> Vec<(Layer, Instruction)> {
+        vec![(Layer::Base, ix)]
+    }
+
+    fn er(ix: Instruction) -> Vec<(Layer, Instruction)> {
+        vec![(Layer::Er, ix)]
+    }
+
+    #[test]
+    fn stateless_commands() {
+        let chain = Fixture::on(Layer::Er);
+        let me = me();
+        let hash = [0xab; 32];
+        let callback = (key(20), vec![AccountMeta::new(key(21), false), AccountMeta::new_readonly(key(22), false)]);
+        let task = TaskAssignment {
+            queue_program: key(23),
+            queue: key(24),
+            task_hash: hash,
+            assignees: vec![key(25), key(26)],
+            deadline_slot: 900,
+        };
+        let child = FederatedChild {
+            action: key(27),
+            hive: key(4),
+            weight: 60,
+        };
+        let cases = [
+            (
+                format!("action delegate 7 --validator {}", key(3)),
+                base(instructions::delegate_action(me, 7, Some(key(3)))),
+            ),
+            ("action retire 7".into(), base(instructions::retire_action(pda::fast_action(7).0, None))),
+            (
+                format!("action index-lanes --hive {}", key(4)),
+                base(instructions::initialize_active_actions(me, Some(key(4)))),
+            ),
+            ("action index-tag treasury --page 2".into(), base(instructions::initialize_tag_index(me, None, TAG, 2))),
+            (
+                "action reap 7 8".into(),
+                er(instructions::reap_stale_delegations(me, &[pda::fast_action(7).0, pda::fast_action(8).0])),
+            ),
+            (
+                format!(
+                    "action push-task 7 --queue-program {} --queue {} --task-hash {} --assignees {},{} {}",
+                    key(23),
+                    key(24),
+                    self::hash(),
+                    key(25),
+                    key(26),
+                    "--deadline-slot 900"
+                ),
+                base(instructions::push_task(me, 7, task, &[])),
+            ),
+            ("action veto 7 --reason spam".into(), base(instructions::veto_action(me, 7, "spam".into()))),
+            (
+                "action metadata 7 --description payroll".into(),
+                base(instructions::set_metadata(me, 7, "payroll".into(), String::new())),
+            ),
+            (
+                format!("action seal 7 --ciphertext-hash {} --encryption-key {}", self::hash(), "cd".repeat(32)),
+                base(instructions::seal_description(me, 7, hash, [0xcd; 32])),
+            ),
+            (
+                format!("action callback 7 {} --account {}:w --account {}", key(20), key(21), key(22)),
+                base(instructions::register_callback(me, 7, key(20), &callback.1)),
+            ),
+            (
+                format!("action schedule 7 --reward 5000 --callback {},{}:w,{}", key(20), key(21), key(22)),
+                base(instructions::schedule_execution(me, 7, 5000, std::slice::from_ref(&callback))),
+            ),
+            ("action unschedule 7".into(), base(instructions::cancel_execution(me, 7))),
+            (
+                format!("delegate-votes {}:2500", key(5)),
+                base(instructions::set_vote_delegation(me, vec![DelegationShare { delegate: key(5), bps: 2500 }])),
+            ),
+            (
+                format!("metrics --hive {} --init", key(4)),
+                vec![
+                    (Layer::Base, instructions::initialize_hive_metrics(me, key(4), MetricsLayer::Base)),
+                    (Layer::Er, instructions::initialize_hive_metrics(me, key(4), MetricsLayer::Er)),
+                ],
+            ),
+            ("stats --epoch 9 --init".into(), er(instructions::initialize_epoch_stats(me, Pubkey::default(), 9))),
+            (
+                format!("stats --hive {} --init", key(4)),
+                er(instructions::initialize_epoch_stats(me, key(4), EPOCH)),
+            ),
+            (
+                // Half of the gap closes every 2 slots: 2^(-1/2) in ppb
+                format!("conviction create {} --threshold 1000 --half-life 2", self::hash()),
+                base(instructions::create_conviction_proposal(me, hash, 1000, 707_106_781, None)),
+            ),
+            (
+                format!("conviction stake {} 300", self::hash()),
+                base(instructions::stake_conviction(me, &hash, 300)),
+            ),
+            (
+                format!("conviction withdraw {} 100", self::hash()),
+                base(instructions::withdraw_conviction(me, &hash, 100)),
+            ),
+            (format!("conviction update {}", self::hash()), base(instructions::update_conviction(&hash))),
+            (
+                format!("draft sponsor {} {}", key(2), self::hash()),
+                base(instructions::sponsor_draft(me, key(2), hash)),
+            ),
+            ("agent register 1000".into(), base(instructions::register_agent(me, 1000))),
+            (format!("agent evict {}", key(5)), base(instructions::evict_agent(me, key(5)))),
+            (
+                format!("agent class {} scout", key(5)),
+                base(instructions::set_agent_class(me, key(5), AgentClass::Scout)),
+            ),
+            (format!("agent migrate {}", key(5)), base(instructions::migrate_agent_record(me, key(5)))),
+            (
+                "hive create bees".into(),
+                base(instructions::create_hive(me, me, hashv(&[b"bees"]).to_bytes(), me)),
+            ),
+            (
+                format!("hive update {} --authority {}", key(4), key(5)),
+                base(instructions::update_hive(
+                    me,
+                    key(4),
+                    HiveUpdate {
+                        authority: Some(key(5)),
+                        member_root: None,
+                        overrides: None,
+                    },
+                )),
+            ),
+            (format!("hive restrict {}", key(4)), base(instructions::set_creator_restriction(me, key(4), true))),
+            (
+                format!("hive restrict {} --lift", key(4)),
+                base(instructions::set_creator_restriction(me, key(4), false)),
+            ),
+            (format!("hive allow {} {}", key(4), key(5)), base(instructions::allow_creator(me, key(4), key(5)))),
+            (
+                format!("hive disallow {} {}", key(4), key(5)),
+                base(instructions::disallow_creator(me, key(4), key(5))),
+            ),
+            (
+                format!("federation create {} --threshold 51 --child {}:{}:60", self::hash(), key(27), key(4)),
+                base(instructions::create_federated_action(me, hash, 51, vec![child])),
+            ),
+        ];
+        for (args, expected) in cases {
+            assert_eq!(chain.build(&args).unwrap(), expected, "{args}");
+        }
+    }
+
+    #[test]
+    fn create_opens_the_next_action() {
+        let chain = Fixture::on(Layer::Base);
+        let params = || parse_manifest_line(&format!("{} 60", hash())).unwrap();
+        let create = format!("action create --hash {} --threshold 60", hash());
+
+        let ix = instructions::create_fast_action(me(), NEXT_ID, params(), None, None, None);
+        assert_eq!(chain.build(&create).unwrap(), base(ix));
+        let ix = instructions::promote_draft(me(), NEXT_ID, params(), None, None, None);
+        assert_eq!(chain.build(&format!("{create} --from-draft")).unwrap(), base(ix));
+        let ix = instructions::create_draft(me(), params(), vec![key(5), key(6)], 2);
+        let draft = format!("{create} --sponsor {} --sponsor {} --required 2", key(5), key(6));
+        assert_eq!(chain.build(&draft).unwrap(), base(ix));
+
+        // Weighted actions name the config's mint; tags go in their first
+        // page with room
+        let mut weighted = params();
+        weighted.vote_mode = VoteMode::TokenWeighted;
+        weighted.tags = vec![TAG];
+        let mut ix = instructions::create_fast_action(me(), NEXT_ID, weighted, None, Some(key(4)), Some(key(30)));
+        *ix.accounts.last_mut().unwrap() = AccountMeta::new(pda::tag_index(Some(&key(4)), &TAG, 1).0, false);
+        let built = chain.build(&format!("{create} --weighted --tag treasury --hive {}", key(4))).unwrap();
+        assert_eq!(built, base(ix));
+
+        let mut full = Fixture::on(Layer::Base);
+        full.tag_pages.truncate(1);
+        assert!(full.build(&format!("{create} --tag treasury")).is_err());
+    }
+
+    #[test]
+    fn create_batch_reads_its_manifest() {
+        let manifest = std::env::temp_dir().join(format!("kamiyo-cli-batch-{}.txt", std::process::id()));
+        std::fs::write(&manifest, format!("# payroll\n{} 60\n\n{} 75 3\n", hash(), "cd".repeat(32))).unwrap();
+        let built = Fixture::on(Layer::Base).build(&format!("action create-batch {}", manifest.display()));
+        std::fs::remove_file(&manifest).unwrap();
+
+        let mut second = parse_manifest_line(&format!("{} 75", "cd".repeat(32))).unwrap();
+        second.priority = 3;
+        let batch = vec![parse_manifest_line(&format!("{} 60", hash())).unwrap(), second];
+        assert_eq!(built.unwrap(), base(instructions::create_actions_batch(me(), NEXT_ID, batch, None)));
+    }
+
+    #[test]
+    fn base_layer_upkeep_reads_the_action() {
+        let fast_action = pda::fast_action(ACTION_ID).0;
+        let mut chain = Fixture::on(Layer::Base);
+        assert!(chain.build("action clear-market 7").is_err());
+        assert!(chain.build("action release 7").is_err());
+
+        chain.action.market_feed = key(11);
+        chain.action.validator_counted = true;
+        chain.action.delegated_validator = key(3);
+        chain.action.tags_indexed = true;
+        chain.action.tags[0] = TAG;
+        chain.action.action_hash = [7; 32];
+        chain.action.hive = key(4);
+        chain.action.result = VoteResult::Chosen { option: 1 };
+        let pages = [(TAG, 1)];
+        let mut expire = instructions::expire_action(ACTION_ID, key(2), Some(key(3)));
+        expire.accounts.extend(instructions::tag_accounts(None, &pages));
+        let election = Election {
+            candidates: vec![key(20), key(21)],
+            term_slots: 1000,
+        };
+        let amendment = ActionAmendment {
+            threshold: Some(70),
+            window_slots: None,
+            description_hash: None,
+        };
+        let cases = [
+            ("action clear-market 7".into(), base(instructions::clear_market_gate(ACTION_ID, key(11)))),
+            ("action expire 7".into(), base(expire)),
+            ("action release 7".into(), base(instructions::release_validator(fast_action, key(3)))),
+            ("action retire-tags 7".into(), base(instructions::retire_tags(fast_action, None, &pages))),
+            ("action runoff 7".into(), base(instructions::spawn_runoff(me(), ACTION_ID, NEXT_ID))),
+            (
+                "action runoff 7 --delegate".into(),
+                base(instructions::spawn_and_delegate(me(), ACTION_ID, NEXT_ID, Some(key(3)))),
+            ),
+            (
+                format!("action install-leader 7 --candidates {},{} --term-slots 1000", key(20), key(21)),
+                base(instructions::install_leader(me(), ACTION_ID, key(4), key(21), election)),
+            ),
+            (
+                "action amend 7 --threshold 70".into(),
+                base(instructions::amend_action(me(), ACTION_ID, [7; 32], amendment)),
+            ),
+        ];
+        for (args, expected) in cases {
+            assert_eq!(chain.build(&args).unwrap(), expected, "{args}");
+        }
+
+        // Only the winner of an election is installed
+        chain.action.result = VoteResult::Failed;
+        assert!(chain.build(&format!("action install-leader 7 --candidates {} --term-slots 1", key(20))).is_err());
+        // Actions still on the ER are out of reach
+        assert!(Fixture::on(Layer::Er).build("action expire 7").is_err());
+    }
+
+    #[test]
+    fn accounts_are_read_for_their_keys() {
+        let mut lock: StakeLock = zeroed();
+        lock.program = key(31);
+        lock.token_account = key(5);
+        let mut tree: ReceiptTree = zeroed();
+        tree.merkle_tree = key(12);
+        let mut hive: Hive = zeroed();
+        hive.overrides.max_actions_per_epoch = 5;
+        hive.overrides.default_quorum = 9;
+        let mut federated: FederatedAction = zeroed();
+        federated.child_count = 2;
+        federated.children[0].action = key(27);
+        federated.children[1].action = key(28);
+        let hash = [0xab; 32];
+        let chain = Fixture::on(Layer::Base)
+            .with(pda::stake_lock(&key(2)).0, &lock)
+            .with(pda::receipt_tree().0, &tree)
+            .with(pda::hive(&key(4)).0, &hive)
+            .with(pda::federated_action(&hash).0, &federated);
+
+        let update = HiveUpdate {
+            authority: None,
+            member_root: None,
+            overrides: Some(HiveOverrides {
+                max_actions_per_epoch: 5,
+                default_quorum: 12,
+            }),
+        };
+        let cases = [
+            (
+                format!("action release-stake 7 {}", key(2)),
+                base(instructions::release_stake_lock(ACTION_ID, key(2), key(31), key(5), key(30))),
+            ),
+            ("action mint-receipt 7".into(), base(instructions::mint_receipt(me(), ACTION_ID, me(), key(12)))),
+            (
+                format!("action mint-receipt 7 --voter {}", key(13)),
+                base(instructions::mint_receipt(me(), ACTION_ID, key(13), key(12))),
+            ),
+            (
+                format!("hive update {} --default-quorum 12", key(4)),
+                base(instructions::update_hive(me(), key(4), update)),
+            ),
+            (
+                format!("federation settle {}", self::hash()),
+                base(instructions::settle_federated_action(&hash, &[key(27), key(28)])),
+            ),
+        ];
+        for (args, expected) in cases {
+            assert_eq!(chain.build(&args).unwrap(), expected, "{args}");
+        }
+        assert!(chain.build(&format!("hive update {} --default-quorum 12", key(5))).is_err());
+    }
+
+    #[test]
+    fn authority_commands_follow_the_action() {
+        for layer in [Layer::Base, Layer::Er] {
+            let mut chain = Fixture::on(layer);
+            let metrics = if layer == Layer::Base { MetricsLayer::Base } else { MetricsLayer::Er };
+            let ix = instructions::transfer_action_authority(me(), ACTION_ID, key(5));
+            assert_eq!(chain.build(&format!("action transfer-authority 7 {}", key(5))).unwrap(), vec![(layer, ix)]);
+            let ix = instructions::cancel_action(me(), ACTION_ID, Pubkey::default(), false, metrics);
+            assert_eq!(chain.build("cancel 7").unwrap(), vec![(layer, ix)]);
+
+            // Anyone but the authority cancels a hive's action through its roles
+            chain.action.authority = key(2);
+            chain.action.hive = key(4);
+            let ix = instructions::cancel_action(me(), ACTION_ID, key(4), true, metrics);
+            assert_eq!(chain.build("cancel 7").unwrap(), vec![(layer, ix)]);
+        }
+    }
+
+    #[test]
+    fn votes_match_the_action() {
+        let commitment = hashv(&[me().as_ref(), pda::fast_action(ACTION_ID).0.as_ref()]).to_bytes();
+        let tokens = format!("--token-account {}", key(5));
+        let vote = |chain: &Fixture, args: &str| chain.build(&format!("vote 7 {args}"));
+
+        let mut chain = Fixture::on(Layer::Er);
+        let ix = instructions::vote_fast(me(), ACTION_ID, 3, true, commitment);
+        assert_eq!(vote(&chain, "for").unwrap(), er(ix));
+        let ix = instructions::vote_fast(me(), ACTION_ID, 3, false, [0xab; 32]);
+        assert_eq!(vote(&chain, &format!("against --commitment {}", hash())).unwrap(), er(ix));
+        assert!(vote(&chain, "").is_err());
+
+        chain.action.vote_mode = VoteMode::Optimistic;
+        assert!(vote(&chain, "for").is_err());
+        let ix = instructions::vote_fast(me(), ACTION_ID, 3, false, commitment);
+        assert_eq!(vote(&chain, "against").unwrap(), er(ix));
+
+        chain.action.vote_mode = VoteMode::Headcount;
+        chain.action.member_collection = key(9);
+        assert!(vote(&chain, "for").is_err());
+        let ix = instructions::vote_fast(me(), ACTION_ID, 3, true, commitment);
+        let ix = instructions::with_member_nft(ix, &MemberNft::Core(key(10)));
+        assert_eq!(vote(&chain, &format!("for --member-nft {}", key(10))).unwrap(), er(ix));
+
+        let mut chain = Fixture::on(Layer::Er);
+        chain.action.option_count = 3;
+        assert!(vote(&chain, "").is_err());
+        let ix = instructions::vote_options(me(), ACTION_ID, 3, vec![0, 1, 0], commitment, None);
+        assert_eq!(vote(&chain, "--weights 0,1,0").unwrap(), er(ix));
+        chain.action.ranked = true;
+        assert!(vote(&chain, "--weights 0,1,0").is_err());
+        let ix = instructions::vote_ranked(me(), ACTION_ID, 3, vec![2, 0, 1], commitment);
+        assert_eq!(vote(&chain, "--ranking 2,0,1").unwrap(), er(ix));
+
+        // Token-weighted ballots lock the voter's stake on the base layer
+        chain.action.ranked = false;
+        chain.action.vote_mode = VoteMode::TokenWeighted;
+        assert!(vote(&chain, "--weights 0,40,0").is_err());
+        let weight_accounts = Some((key(30), key(5), key(31)));
+        let ix = instructions::vote_options(me(), ACTION_ID, 3, vec![0, 40, 0], commitment, weight_accounts);
+        assert_eq!(vote(&chain, &format!("--weights 0,40,0 {tokens}")).unwrap(), base(ix));
+
+        let mut chain = Fixture::on(Layer::Er);
+        chain.action.vote_mode = VoteMode::TokenWeighted;
+        assert!(vote(&chain, "for").is_err());
+        let ix = instructions::vote_fast_weighted(me(), ACTION_ID, 3, true, commitment, (key(30), key(5)), key(31));
+        assert_eq!(vote(&chain, &format!("for {tokens}")).unwrap(), base(ix));
+        chain.action.stake_lock_program = key(8);
+        let ix = instructions::vote_fast_locked(me(), ACTION_ID, 3, true, commitment, (key(30), key(5)), key(8));
+        assert_eq!(vote(&chain, &format!("for {tokens}")).unwrap(), base(ix));
+
+        chain.action.stake_lock_program = Pubkey::default();
+        chain.action.identity_weighted = true;
+        assert!(vote(&chain, "for").is_err());
+        let ix = instructions::vote_fast_identity(me(), ACTION_ID, 3, true, commitment, key(6));
+        assert_eq!(vote(&chain, &format!("for --identity-asset {}", key(6))).unwrap(), er(ix));
+    }
+
+    #[test]
+    fn tally_commits_from_the_er() {
+        let mut chain = Fixture::on(Layer::Er);
+        chain.action.hive = key(4);
+        chain.action.dependencies[0] = key(10);
+        chain.action.dependency_count = 1;
+        chain.action.audited = true;
+        let ix = instructions::tally_and_commit(me(), ACTION_ID, Some(key(4)), &[key(10)], true, Some(EPOCH));
+        assert_eq!(chain.build("tally 7").unwrap(), er(ix));
+        assert!(Fixture::on(Layer::Base).build("tally 7").is_err());
+    }
+
+    #[test]
+    fn reads_send_nothing() {
+        let chain = Fixture::on(Layer::Er);
+        for args in ["action status 7", "votes list 7", "metrics", "stats", "keeper --once", "action execute 7"] {
+            assert!(chain.build(args).is_err(), "{args}");
+        }
+    }
+}
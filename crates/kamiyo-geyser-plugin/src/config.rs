@@ -0,0 +1,30 @@
+// The plugin's section of the validator's Geyser config file
+
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct Config {
+    pub sink: SinkConfig,
+    /// Leading segment of every subject or channel
+    #[serde(default = "default_prefix")]
+    pub prefix: String,
+    /// Updates buffered for the publisher; past this they are dropped rather
+    /// than holding up the validator
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SinkConfig {
+    Nats { url: String },
+    Redis { url: String },
+}
+
+fn default_prefix() -> String {
+    "kamiyo".to_string()
+}
+
+fn default_queue_capacity() -> usize {
+    65_536
+}
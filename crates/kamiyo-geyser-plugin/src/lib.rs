@@ -0,0 +1,115 @@
+//! Geyser plugin streaming live fast voting state out of a validator, for
+//! consumers that cannot wait on RPC polling or websocket fan-out. Loaded
+//! into the ER validator, it sees every ballot land. See README.md for the
+//! config and message format.
+
+mod config;
+mod sink;
+mod update;
+
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+
+use agave_geyser_plugin_interface::geyser_plugin_interface::{
+    GeyserPlugin, GeyserPluginError, ReplicaAccountInfoVersions, Result,
+};
+use anchor_lang::prelude::Pubkey;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::Sender;
+
+use config::Config;
+pub use update::{ActionUpdate, TallyDelta, Update, VoteUpdate};
+use update::Tallies;
+
+#[derive(Default)]
+pub struct KamiyoGeyserPlugin {
+    sender: Option<Sender<Update>>,
+    publisher: Option<JoinHandle<()>>,
+    tallies: Mutex<Tallies>,
+}
+
+impl std::fmt::Debug for KamiyoGeyserPlugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KamiyoGeyserPlugin").finish_non_exhaustive()
+    }
+}
+
+impl GeyserPlugin for KamiyoGeyserPlugin {
+    fn name(&self) -> &'static str {
+        "kamiyo-geyser-plugin"
+    }
+
+    fn on_load(&mut self, config_file: &str, _is_reload: bool) -> Result<()> {
+        let file = std::fs::read_to_string(config_file).map_err(GeyserPluginError::ConfigFileOpenError)?;
+        let config: Config = serde_json::from_str(&file)
+            .map_err(|e| GeyserPluginError::ConfigFileReadError { msg: e.to_string() })?;
+        let (sender, publisher) = sink::spawn(config).map_err(GeyserPluginError::Custom)?;
+        self.sender = Some(sender);
+        self.publisher = Some(publisher);
+        Ok(())
+    }
+
+    fn on_unload(&mut self) {
+        // Closing the queue lets the publisher drain it and exit
+        self.sender = None;
+        if let Some(publisher) = self.publisher.take() {
+            let _ = publisher.join();
+        }
+    }
+
+    fn update_account(&self, account: ReplicaAccountInfoVersions, slot: u64, is_startup: bool) -> Result<()> {
+        let (pubkey, owner, lamports, data) = match account {
+            ReplicaAccountInfoVersions::V0_0_1(a) => (a.pubkey, a.owner, a.lamports, a.data),
+            ReplicaAccountInfoVersions::V0_0_2(a) => (a.pubkey, a.owner, a.lamports, a.data),
+            ReplicaAccountInfoVersions::V0_0_3(a) => (a.pubkey, a.owner, a.lamports, a.data),
+        };
+        // Every account write in the validator comes through here
+        if owner != kamiyo_hive_interface::ID.as_ref() {
+            return Ok(());
+        }
+        let Ok(address) = Pubkey::try_from(pubkey) else {
+            return Ok(());
+        };
+        let update = {
+            let mut tallies = self.tallies.lock().unwrap();
+            if lamports == 0 {
+                tallies.forget(&address);
+                return Ok(());
+            }
+            tallies.observe(address, data, slot, is_startup)
+        };
+        let Some(update) = update else {
+            return Ok(());
+        };
+
+        if let Some(sender) = &self.sender {
+            match sender.try_send(update) {
+                Ok(()) => {}
+                Err(TrySendError::Full(update)) => {
+                    log::warn!("kamiyo-geyser: queue full, dropped {} update for {}", update.kind(), update.action());
+                }
+                Err(TrySendError::Closed(_)) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        true
+    }
+
+    fn transaction_notifications_enabled(&self) -> bool {
+        false
+    }
+}
+
+/// # Safety
+///
+/// Called by the validator's plugin loader, which takes ownership of the
+/// returned plugin.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub unsafe extern "C" fn _create_plugin() -> *mut dyn GeyserPlugin {
+    let plugin: Box<dyn GeyserPlugin> = Box::<KamiyoGeyserPlugin>::default();
+    Box::into_raw(plugin)
+}
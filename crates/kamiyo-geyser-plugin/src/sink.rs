@@ -0,0 +1,75 @@
+// Publishing, on a thread of its own so a slow or unreachable broker never
+// blocks the validator. Updates reach it through a bounded queue; the
+// plugin drops what does not fit.
+
+use std::thread::JoinHandle;
+
+use redis::AsyncCommands;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+
+use crate::config::{Config, SinkConfig};
+use crate::update::Update;
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+enum Sink {
+    Nats(async_nats::Client),
+    Redis(redis::aio::MultiplexedConnection),
+}
+
+impl Sink {
+    async fn connect(config: &SinkConfig) -> Result<Self, Error> {
+        Ok(match config {
+            SinkConfig::Nats { url } => Sink::Nats(async_nats::connect(url.as_str()).await?),
+            SinkConfig::Redis { url } => {
+                Sink::Redis(redis::Client::open(url.as_str())?.get_multiplexed_async_connection().await?)
+            }
+        })
+    }
+
+    /// NATS subjects are `<prefix>.<kind>.<action>`, Redis channels
+    /// `<prefix>:<kind>:<action>`
+    fn topic(&self, prefix: &str, update: &Update) -> String {
+        let separator = match self {
+            Sink::Nats(_) => '.',
+            Sink::Redis(_) => ':',
+        };
+        format!("{prefix}{separator}{}{separator}{}", update.kind(), update.action())
+    }
+
+    async fn publish(&mut self, topic: String, payload: Vec<u8>) -> Result<(), Error> {
+        match self {
+            Sink::Nats(client) => client.publish(topic, payload.into()).await?,
+            Sink::Redis(connection) => connection.publish::<_, _, ()>(topic, payload).await?,
+        }
+        Ok(())
+    }
+}
+
+/// Connects to the configured broker and starts the publishing thread
+pub fn spawn(config: Config) -> Result<(Sender<Update>, JoinHandle<()>), Error> {
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    let sink = runtime.block_on(Sink::connect(&config.sink))?;
+    let (sender, receiver) = mpsc::channel(config.queue_capacity);
+    let handle = std::thread::Builder::new()
+        .name("kamiyoGeyserPub".to_string())
+        .spawn(move || runtime.block_on(run(sink, config.prefix, receiver)))?;
+    Ok((sender, handle))
+}
+
+/// Until the plugin drops its sender
+async fn run(mut sink: Sink, prefix: String, mut receiver: Receiver<Update>) {
+    while let Some(update) = receiver.recv().await {
+        let topic = sink.topic(&prefix, &update);
+        let payload = match serde_json::to_vec(&update) {
+            Ok(payload) => payload,
+            Err(e) => {
+                log::error!("kamiyo-geyser: encoding {topic}: {e}");
+                continue;
+            }
+        };
+        if let Err(e) = sink.publish(topic.clone(), payload).await {
+            log::warn!("kamiyo-geyser: publishing {topic}: {e}");
+        }
+    }
+}
@@ -0,0 +1,193 @@
+// Decoding account writes into the updates the plugin publishes. A FastAction
+// write is published only when its tally or result moved, with the change
+// since the last one seen; a FastVote write is a new ballot.
+
+use std::collections::HashMap;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use kamiyo_hive_interface::{FastAction, FastVote, VoteResult};
+use serde::Serialize;
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+struct Tally {
+    votes_for: u32,
+    votes_against: u32,
+    vote_count: u32,
+    weight_for: u64,
+    weight_against: u64,
+    option_weights: Vec<u64>,
+    result: (&'static str, Option<u8>),
+}
+
+impl Tally {
+    fn of(action: &FastAction) -> Self {
+        Tally {
+            votes_for: action.votes_for,
+            votes_against: action.votes_against,
+            vote_count: action.vote_count,
+            weight_for: action.weight_for,
+            weight_against: action.weight_against,
+            option_weights: action.option_weights[..action.option_count as usize].to_vec(),
+            result: result(&action.result),
+        }
+    }
+}
+
+/// Name and tier or option of a result
+fn result(result: &VoteResult) -> (&'static str, Option<u8>) {
+    match result {
+        VoteResult::Pending => ("pending", None),
+        VoteResult::Passed { tier } => ("passed", Some(*tier)),
+        VoteResult::Failed => ("failed", None),
+        VoteResult::Cancelled => ("cancelled", None),
+        VoteResult::Expired => ("expired", None),
+        VoteResult::Voided => ("voided", None),
+        VoteResult::Chosen { option } => ("chosen", Some(*option)),
+        VoteResult::Vetoed => ("vetoed", None),
+        VoteResult::Contested { tier } => ("contested", Some(*tier)),
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Update {
+    Action(ActionUpdate),
+    Vote(VoteUpdate),
+}
+
+impl Update {
+    /// Account the update is filed under: the action for both kinds, so one
+    /// subscription follows an action's tally and its ballots
+    pub fn action(&self) -> &str {
+        match self {
+            Update::Action(update) => &update.address,
+            Update::Vote(update) => &update.fast_action,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Update::Action(_) => "action",
+            Update::Vote(_) => "vote",
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ActionUpdate {
+    pub address: String,
+    pub slot: u64,
+    pub action_id: u64,
+    pub votes_for: u32,
+    pub votes_against: u32,
+    pub vote_count: u32,
+    pub weight_for: u64,
+    pub weight_against: u64,
+    /// Empty for for/against actions
+    pub option_weights: Vec<u64>,
+    pub result: &'static str,
+    /// Tier of a pass, or the chosen option
+    pub result_detail: Option<u8>,
+    pub deadline_slot: u64,
+    pub delegation_epoch: u32,
+    /// Change since the previous update for this action, or since zero for
+    /// the first one
+    pub delta: TallyDelta,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TallyDelta {
+    pub votes_for: i64,
+    pub votes_against: i64,
+    pub vote_count: i64,
+    pub weight_for: i128,
+    pub weight_against: i128,
+    pub option_weights: Vec<i128>,
+}
+
+impl TallyDelta {
+    fn between(previous: &Tally, current: &Tally) -> Self {
+        let option_weights = current
+            .option_weights
+            .iter()
+            .enumerate()
+            .map(|(i, weight)| *weight as i128 - previous.option_weights.get(i).copied().unwrap_or(0) as i128)
+            .collect();
+        TallyDelta {
+            votes_for: current.votes_for as i64 - previous.votes_for as i64,
+            votes_against: current.votes_against as i64 - previous.votes_against as i64,
+            vote_count: current.vote_count as i64 - previous.vote_count as i64,
+            weight_for: current.weight_for as i128 - previous.weight_for as i128,
+            weight_against: current.weight_against as i128 - previous.weight_against as i128,
+            option_weights,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct VoteUpdate {
+    pub address: String,
+    pub slot: u64,
+    pub fast_action: String,
+    pub voter: String,
+    pub vote_value: bool,
+    pub weight: u64,
+    pub voted_slot: u64,
+}
+
+/// Last tally published per action
+#[derive(Default)]
+pub struct Tallies {
+    seen: HashMap<Pubkey, Tally>,
+}
+
+impl Tallies {
+    /// The update for a write of `data` to `address`, an account owned by the
+    /// program. `startup` writes only seed the cache: they replay the
+    /// snapshot, not live ballots.
+    pub fn observe(&mut self, address: Pubkey, data: &[u8], slot: u64, startup: bool) -> Option<Update> {
+        if data.starts_with(FastAction::DISCRIMINATOR) {
+            let action = FastAction::try_deserialize(&mut &data[..]).ok()?;
+            let tally = Tally::of(&action);
+            let previous = self.seen.insert(address, tally.clone()).unwrap_or_default();
+            if startup || previous == tally {
+                return None;
+            }
+            return Some(Update::Action(ActionUpdate {
+                address: address.to_string(),
+                slot,
+                action_id: action.action_id,
+                votes_for: action.votes_for,
+                votes_against: action.votes_against,
+                vote_count: action.vote_count,
+                weight_for: action.weight_for,
+                weight_against: action.weight_against,
+                option_weights: tally.option_weights.clone(),
+                result: tally.result.0,
+                result_detail: tally.result.1,
+                deadline_slot: action.deadline_slot,
+                delegation_epoch: action.delegation_epoch,
+                delta: TallyDelta::between(&previous, &tally),
+            }));
+        }
+        if data.starts_with(FastVote::DISCRIMINATOR) && !startup {
+            let vote = FastVote::try_deserialize(&mut &data[..]).ok()?;
+            return Some(Update::Vote(VoteUpdate {
+                address: address.to_string(),
+                slot,
+                fast_action: vote.fast_action.to_string(),
+                voter: vote.voter.to_string(),
+                vote_value: vote.vote_value,
+                weight: vote.weight,
+                voted_slot: vote.voted_slot,
+            }));
+        }
+        None
+    }
+
+    /// Forget an action whose account was closed
+    pub fn forget(&mut self, address: &Pubkey) {
+        self.seen.remove(address);
+    }
+}
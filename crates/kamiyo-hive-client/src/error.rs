@@ -0,0 +1,25 @@
+use anchor_lang::prelude::Pubkey;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("rpc: {0}")]
+    Rpc(#[from] Box<solana_rpc_client_api::client_error::Error>),
+    #[error("account {0} not found")]
+    AccountNotFound(Pubkey),
+    #[error("decode: {0}")]
+    Decode(#[from] anchor_lang::error::Error),
+    #[error("account {0} is not an address lookup table")]
+    InvalidLookupTable(Pubkey),
+    #[error("compile: {0}")]
+    Compile(#[from] solana_sdk::message::CompileError),
+    #[error("sign: {0}")]
+    Sign(#[from] solana_sdk::signer::SignerError),
+}
+
+impl From<solana_rpc_client_api::client_error::Error> for Error {
+    fn from(err: solana_rpc_client_api::client_error::Error) -> Self {
+        Error::Rpc(Box::new(err))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,3411 @@
+// Instruction builders. Optional accounts left as `None` are encoded the way
+// Anchor expects (the program ID in their slot).
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use ephemeral_rollups_sdk::consts::{MAGIC_CONTEXT_ID, MAGIC_PROGRAM_ID};
+use kamiyo_fast_voting::{
+    accounts, accounts_hash, instruction, ActionAmendment, AddressProof, ActionKind, ActionParams, AgentClass,
+    ApprovalRounding, Ballot, BallotChoice, Budget, CancellationPolicy, CompressedBallot, CompressedProof,
+    ConfigChange, ConfigUpdate, DelegationShare, EarlyVoteCurve, Election, Evidence, FederatedChild, HiveInit,
+    HiveUpdate, IdentityTier, MetricsLayer, ProgramUpgrade, ScheduledAccount, Role, SequencedBallot, TaskAssignment,
+    TemplateArgs, TemplateParams, ValidatorStatus, WeightCap, BUBBLEGUM_PROGRAM_ID, ID,
+    LIGHT_ACCOUNT_COMPRESSION_PROGRAM_ID, LIGHT_SYSTEM_PROGRAM_ID, SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+    SPL_NOOP_PROGRAM_ID,
+};
+
+use crate::pda;
+use crate::state::ExecutionSchedule;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// `action_id` must be the counter's current `next_id`; see
+/// `rpc::Client::next_action_id`. Pass `previous_action` to reuse the hash of
+/// a finalized action, and `hive` for hive-scoped actions, which are created
+/// through its Hive account at `pda::hive_action`. `weight_mint`, the
+/// config's, is needed when a supply-relative weight cap applies.
+pub fn create_fast_action(
+    creator: Pubkey,
+    action_id: u64,
+    params: ActionParams,
+    previous_action: Option<Pubkey>,
+    hive: Option<Pubkey>,
+    weight_mint: Option<Pubkey>,
+) -> Instruction {
+    create(creator, action_id, params, previous_action, hive, weight_mint, false)
+}
+
+/// create_fast_action from the creator's sponsored draft for
+/// `params.action_hash`; `params` must match the draft exactly.
+pub fn promote_draft(
+    creator: Pubkey,
+    action_id: u64,
+    params: ActionParams,
+    previous_action: Option<Pubkey>,
+    hive: Option<Pubkey>,
+    weight_mint: Option<Pubkey>,
+) -> Instruction {
+    create(creator, action_id, params, previous_action, hive, weight_mint, true)
+}
+
+/// create_fast_action for each of `batch`, in one instruction; the actions
+/// take ids `first_id..` in order, so `first_id` must be the counter's
+/// current `next_id`. `hive` scopes them as in `create_fast_action`.
+pub fn create_actions_batch(
+    creator: Pubkey,
+    first_id: u64,
+    batch: Vec<ActionParams>,
+    hive: Option<Pubkey>,
+) -> Instruction {
+    let mut ix = build(
+        accounts::CreateActionsBatch { creator },
+        instruction::CreateActionsBatch { batch: batch.clone() },
+    );
+    for (action_id, params) in (first_id..).zip(batch) {
+        let create = create(creator, action_id, params, None, hive, None, false);
+        ix.accounts.extend(create.accounts);
+    }
+    ix
+}
+
+/// The TagIndex pages an instruction lists or unlists a tagged action in:
+/// page `page` of each `tag`, in the action's tag order. `hive` as for
+/// `pda::tag_index`.
+pub fn tag_accounts(hive: Option<&Pubkey>, pages: &[([u8; 8], u32)]) -> Vec<AccountMeta> {
+    pages
+        .iter()
+        .map(|(tag, page)| AccountMeta::new(pda::tag_index(hive, tag, *page).0, false))
+        .collect()
+}
+
+/// `hive` pairs the hive with whether the action is created through its Hive
+/// account. Tags are listed in page 0 of their index; to use a later page,
+/// replace the trailing accounts with `tag_accounts`.
+fn create(
+    creator: Pubkey,
+    action_id: u64,
+    params: ActionParams,
+    previous_action: Option<Pubkey>,
+    hive: Option<Pubkey>,
+    weight_mint: Option<Pubkey>,
+    from_draft: bool,
+) -> Instruction {
+    let fast_action = match &hive {
+        Some(h) => pda::hive_action(h, action_id).0,
+        None => pda::fast_action(action_id).0,
+    };
+    let pages: Vec<([u8; 8], u32)> = params.tags.iter().map(|tag| (*tag, 0)).collect();
+    let mut ix = build(
+        accounts::CreateFastAction {
+            action_counter: pda::action_counter().0,
+            hive_account: hive.map(|h| pda::hive(&h).0),
+            fast_action,
+            creator,
+            creator_state: pda::creator_state(&creator).0,
+            action_hash_record: pda::action_hash_record(&params.action_hash).0,
+            previous_action,
+            config: pda::global_config().0,
+            roles: hive.map(|h| pda::roles(&h).0),
+            creator_allowlist: hive.map(|h| pda::creator_allowlist(&h).0),
+            hive_freeze: hive.map(|h| pda::hive_freeze(&h).0),
+            hive_config: hive.map(|h| pda::hive_config(&h).0),
+            reward_pool: pda::reward_pool().0,
+            draft: from_draft.then(|| pda::proposal_draft(&creator, &params.action_hash).0),
+            weight_caps: pda::weight_caps().0,
+            early_vote_curves: pda::early_vote_curves().0,
+            weight_mint,
+            template: None,
+            callback_registry: None,
+            active_actions: Some(pda::active_actions(hive.as_ref()).0),
+            insurance_pool: hive.map(|h| pda::insurance_pool(&h).0),
+            system_program: system_program::ID,
+            metrics: Some(pda::metrics(MetricsLayer::Base).0),
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::CreateFastAction { params },
+    );
+    ix.accounts.extend(tag_accounts(hive.as_ref(), &pages));
+    ix
+}
+
+/// create_from_template with `hive`'s template `template_id`; the action's
+/// address is `pda::hive_action`. Set `with_callbacks` when the template has
+/// default callbacks, so their registry is created with the action.
+pub fn create_from_template(
+    creator: Pubkey,
+    action_id: u64,
+    hive: Pubkey,
+    template_id: u16,
+    args: TemplateArgs,
+    previous_action: Option<Pubkey>,
+    with_callbacks: bool,
+) -> Instruction {
+    let fast_action = pda::hive_action(&hive, action_id).0;
+    build(
+        accounts::CreateFastAction {
+            action_counter: pda::action_counter().0,
+            hive_account: Some(pda::hive(&hive).0),
+            fast_action,
+            creator,
+            creator_state: pda::creator_state(&creator).0,
+            action_hash_record: pda::action_hash_record(&args.action_hash).0,
+            previous_action,
+            config: pda::global_config().0,
+            roles: Some(pda::roles(&hive).0),
+            creator_allowlist: Some(pda::creator_allowlist(&hive).0),
+            hive_freeze: Some(pda::hive_freeze(&hive).0),
+            hive_config: Some(pda::hive_config(&hive).0),
+            reward_pool: pda::reward_pool().0,
+            draft: None,
+            weight_caps: pda::weight_caps().0,
+            early_vote_curves: pda::early_vote_curves().0,
+            weight_mint: None,
+            template: Some(pda::template(&hive, template_id).0),
+            callback_registry: with_callbacks.then(|| pda::callback_registry(&fast_action).0),
+            active_actions: Some(pda::active_actions(Some(&hive)).0),
+            insurance_pool: Some(pda::insurance_pool(&hive).0),
+            system_program: system_program::ID,
+            metrics: Some(pda::metrics(MetricsLayer::Base).0),
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::CreateFromTemplate { args },
+    )
+}
+
+pub fn create_draft(creator: Pubkey, params: ActionParams, sponsors: Vec<Pubkey>, required: u8) -> Instruction {
+    build(
+        accounts::CreateDraft {
+            draft: pda::proposal_draft(&creator, &params.action_hash).0,
+            creator,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::CreateDraft {
+            params,
+            sponsors,
+            required,
+        },
+    )
+}
+
+pub fn sponsor_draft(sponsor: Pubkey, creator: Pubkey, action_hash: [u8; 32]) -> Instruction {
+    build(
+        accounts::SponsorDraft {
+            draft: pda::proposal_draft(&creator, &action_hash).0,
+            sponsor,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SponsorDraft {},
+    )
+}
+
+/// Delegate an action to the ephemeral rollup, optionally pinned to a TEE
+/// validator, which must be allowlisted and live in the registry.
+pub fn delegate_action(payer: Pubkey, action_id: u64, validator: Option<Pubkey>) -> Instruction {
+    let pda = pda::fast_action(action_id).0;
+    build(
+        accounts::DelegateAction {
+            payer,
+            buffer_pda: pda::delegate_buffer(&pda).0,
+            delegation_record_pda: pda::delegation_record(&pda).0,
+            delegation_metadata_pda: pda::delegation_metadata(&pda).0,
+            pda,
+            validator,
+            validator_record: validator.map(|v| pda::validator_record(&v).0),
+            config: pda::global_config().0,
+            owner_program: ID,
+            delegation_program: ephemeral_rollups_sdk::id(),
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::DelegateAction { action_id },
+    )
+}
+
+/// `delegation_epoch` is the action's current FastAction::delegation_epoch.
+pub fn vote_fast(
+    voter: Pubkey,
+    action_id: u64,
+    delegation_epoch: u32,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::VoteFast {
+            fast_action,
+            fast_vote: pda::fast_vote(&fast_action, &voter).0,
+            vote_credits: pda::vote_credits(&voter).0,
+            agent_record: pda::agent_record(&voter).0,
+            vote_key: pda::vote_key(&voter).0,
+            agent: voter,
+            voter,
+            config: pda::global_config().0,
+            compressed_ballots: pda::compressed_ballots(&fast_action).0,
+            system_program: system_program::ID,
+            metrics: Some(pda::metrics(MetricsLayer::Er).0),
+            hive_freeze: Some(pda::hive_freeze(&Pubkey::default()).0),
+            standings_board: Some(pda::standings_board(&Pubkey::default()).0),
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VoteFast {
+            action_id,
+            vote_value,
+            voter_commitment,
+            delegation_epoch,
+        },
+    )
+}
+
+/// The NFT a voter shows on a collection-gated action
+#[derive(Clone, Debug)]
+pub enum MemberNft {
+    /// A Core asset in the collection
+    Core(Pubkey),
+    /// A Token Metadata NFT: the voter's token account holding `mint`
+    TokenMetadata { token_account: Pubkey, mint: Pubkey },
+}
+
+/// `ix`, a vote_fast or vote_compact ballot, showing `nft` for an action
+/// with a member collection
+pub fn with_member_nft(mut ix: Instruction, nft: &MemberNft) -> Instruction {
+    match nft {
+        MemberNft::Core(asset) => ix.accounts.push(AccountMeta::new_readonly(*asset, false)),
+        MemberNft::TokenMetadata { token_account, mint } => ix.accounts.extend([
+            AccountMeta::new_readonly(*token_account, false),
+            AccountMeta::new_readonly(pda::token_metadata(mint).0, false),
+        ]),
+    }
+    ix
+}
+
+/// `ix`, a ballot built by this module, on an action of `hive` rather than
+/// an unscoped one: passes the hive's HiveFreeze and, for vote_fast and
+/// vote_compact, its StandingsBoard in place of the unscoped placeholders
+pub fn for_hive(mut ix: Instruction, hive: &Pubkey) -> Instruction {
+    let placeholders = [
+        (pda::hive_freeze(&Pubkey::default()).0, pda::hive_freeze(hive).0),
+        (pda::standings_board(&Pubkey::default()).0, pda::standings_board(hive).0),
+    ];
+    for meta in ix.accounts.iter_mut() {
+        if let Some((_, scoped)) = placeholders.iter().find(|(unscoped, _)| meta.pubkey == *unscoped) {
+            meta.pubkey = *scoped;
+        }
+    }
+    ix
+}
+
+/// `ix`, a headcount ballot built for `agent` (vote_fast, vote_compact,
+/// vote_options, vote_ranked or vote_fast_compressed), signed by `key`, one
+/// of the agent's vote keys, instead. The ballot is still the agent's.
+pub fn signed_by_vote_key(mut ix: Instruction, agent: &Pubkey, key: &Pubkey) -> Instruction {
+    let agent_vote_key = pda::vote_key(agent).0;
+    for meta in ix.accounts.iter_mut() {
+        if meta.pubkey == *agent && meta.is_signer {
+            meta.pubkey = *key;
+        } else if meta.pubkey == agent_vote_key {
+            meta.pubkey = pda::vote_key(key).0;
+        }
+    }
+    ix
+}
+
+/// vote_fast in its compact encoding; `choice` may also pick an option on a
+/// headcount multi-option action. Without a `commitment` the ballot records
+/// the voter's key as its commitment. Batch these in a v0 transaction whose
+/// lookup tables hold `vote_lookup_addresses`.
+pub fn vote_compact(
+    voter: Pubkey,
+    action_id: u64,
+    delegation_epoch: u32,
+    choice: BallotChoice,
+    commitment: Option<[u8; 16]>,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::VoteFast {
+            fast_action,
+            fast_vote: pda::fast_vote(&fast_action, &voter).0,
+            vote_credits: pda::vote_credits(&voter).0,
+            agent_record: pda::agent_record(&voter).0,
+            vote_key: pda::vote_key(&voter).0,
+            agent: voter,
+            voter,
+            config: pda::global_config().0,
+            compressed_ballots: pda::compressed_ballots(&fast_action).0,
+            system_program: system_program::ID,
+            metrics: Some(pda::metrics(MetricsLayer::Er).0),
+            hive_freeze: Some(pda::hive_freeze(&Pubkey::default()).0),
+            standings_board: Some(pda::standings_board(&Pubkey::default()).0),
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VoteCompact {
+            ballot: choice.pack(),
+            delegation_epoch,
+            commitment,
+        },
+    )
+}
+
+/// The addresses vote_compact touches for `voters` balloting on
+/// `action_ids` that an address lookup table can hold: every account but
+/// the voter and the program. A table holds 256: up to 5 shared, 2 per
+/// action, 2 per voter and 1 per ballot. Each voter still signs, so a
+/// transaction fits many ballots from a few voters rather than one each from
+/// many.
+pub fn vote_lookup_addresses(action_ids: &[u64], voters: &[Pubkey]) -> Vec<Pubkey> {
+    let mut addresses = vec![
+        pda::global_config().0,
+        pda::hive_freeze(&Pubkey::default()).0,
+        pda::standings_board(&Pubkey::default()).0,
+        system_program::ID,
+    ];
+    if cfg!(feature = "event-cpi") {
+        addresses.push(pda::event_authority().0);
+    }
+    let actions: Vec<Pubkey> = action_ids.iter().map(|id| pda::fast_action(*id).0).collect();
+    for action in &actions {
+        addresses.extend([*action, pda::compressed_ballots(action).0]);
+    }
+    for voter in voters {
+        addresses.extend([pda::vote_credits(voter).0, pda::agent_record(voter).0]);
+        addresses.extend(actions.iter().map(|action| pda::fast_vote(action, voter).0));
+    }
+    addresses
+}
+
+/// Split ballot on a multi-option action. `weight_accounts` is
+/// `(weight_mint, voter_token_account, stake_lock_program)` for
+/// token-weighted actions, whose ballots lock the voter's stake and go to
+/// the base layer; `stake_lock_program` is the config's.
+pub fn vote_options(
+    voter: Pubkey,
+    action_id: u64,
+    delegation_epoch: u32,
+    weights: Vec<u64>,
+    voter_commitment: [u8; 32],
+    weight_accounts: Option<(Pubkey, Pubkey, Pubkey)>,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    let weighted = weight_accounts.is_some();
+    let layer = if weighted { MetricsLayer::Base } else { MetricsLayer::Er };
+    build(
+        accounts::VoteOptions {
+            fast_action,
+            fast_vote: pda::fast_vote(&fast_action, &voter).0,
+            vote_credits: pda::vote_credits(&voter).0,
+            stake_lock: weighted.then(|| pda::stake_lock(&voter).0),
+            stake_lock_claim: weighted.then(|| pda::stake_lock_claim(&fast_action, &voter).0),
+            lock_authority: weighted.then(|| pda::stake_lock_authority().0),
+            stake_lock_program: weight_accounts.map(|(_, _, program)| program),
+            agent_record: pda::agent_record(&voter).0,
+            vote_key: pda::vote_key(&voter).0,
+            agent: voter,
+            voter,
+            config: pda::global_config().0,
+            weight_mint: weight_accounts.map(|(mint, _, _)| mint),
+            voter_token_account: weight_accounts.map(|(_, account, _)| account),
+            vote_delegation: pda::vote_delegation(&voter).0,
+            system_program: system_program::ID,
+            metrics: Some(pda::metrics(layer).0),
+            hive_freeze: pda::hive_freeze(&Pubkey::default()).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VoteOptions {
+            _action_id: action_id,
+            weights,
+            voter_commitment,
+            delegation_epoch,
+        },
+    )
+}
+
+/// Base layer only. `weight_accounts` is `(weight_mint, voter_token_account)`;
+/// `stake_lock_program` is the config's.
+pub fn vote_fast_weighted(
+    voter: Pubkey,
+    action_id: u64,
+    delegation_epoch: u32,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+    (weight_mint, voter_token_account): (Pubkey, Pubkey),
+    stake_lock_program: Pubkey,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::VoteFastWeighted {
+            fast_action,
+            fast_vote: pda::fast_vote(&fast_action, &voter).0,
+            vote_credits: pda::vote_credits(&voter).0,
+            stake_lock: pda::stake_lock(&voter).0,
+            stake_lock_claim: pda::stake_lock_claim(&fast_action, &voter).0,
+            lock_authority: pda::stake_lock_authority().0,
+            stake_lock_program,
+            agent_record: pda::agent_record(&voter).0,
+            voter,
+            config: pda::global_config().0,
+            weight_mint,
+            voter_token_account,
+            vote_delegation: pda::vote_delegation(&voter).0,
+            weight_snapshot: pda::weight_snapshot(&fast_action).0,
+            weight_strategy: pda::weight_strategy(&fast_action).0,
+            system_program: system_program::ID,
+            metrics: Some(pda::metrics(MetricsLayer::Base).0),
+            hive_freeze: pda::hive_freeze(&Pubkey::default()).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VoteFastWeighted {
+            _action_id: action_id,
+            vote_value,
+            voter_commitment,
+            delegation_epoch,
+        },
+    )
+}
+
+/// Base layer only. `weight_accounts` is `(weight_mint, voter_token_account)`;
+/// `stake_lock_program` is the action's.
+pub fn vote_fast_locked(
+    voter: Pubkey,
+    action_id: u64,
+    delegation_epoch: u32,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+    (weight_mint, voter_token_account): (Pubkey, Pubkey),
+    stake_lock_program: Pubkey,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::VoteFastLocked {
+            fast_action,
+            fast_vote: pda::fast_vote(&fast_action, &voter).0,
+            vote_credits: pda::vote_credits(&voter).0,
+            stake_lock: pda::stake_lock(&voter).0,
+            stake_lock_claim: pda::stake_lock_claim(&fast_action, &voter).0,
+            lock_authority: pda::stake_lock_authority().0,
+            stake_lock_program,
+            agent_record: pda::agent_record(&voter).0,
+            voter,
+            config: pda::global_config().0,
+            weight_mint,
+            voter_token_account,
+            vote_delegation: pda::vote_delegation(&voter).0,
+            metrics: Some(pda::metrics(MetricsLayer::Base).0),
+            hive_freeze: pda::hive_freeze(&Pubkey::default()).0,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VoteFastLocked {
+            _action_id: action_id,
+            vote_value,
+            voter_commitment,
+            delegation_epoch,
+        },
+    )
+}
+
+/// `stake_lock_program` and `voter_token_account` are the ones the voter's
+/// lock was placed with (its StakeLock account).
+pub fn release_stake_lock(
+    action_id: u64,
+    voter: Pubkey,
+    stake_lock_program: Pubkey,
+    voter_token_account: Pubkey,
+    weight_mint: Pubkey,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::ReleaseStakeLock {
+            fast_action,
+            stake_lock_claim: pda::stake_lock_claim(&fast_action, &voter).0,
+            stake_lock: pda::stake_lock(&voter).0,
+            voter,
+            lock_authority: pda::stake_lock_authority().0,
+            stake_lock_program,
+            voter_token_account,
+            config: pda::global_config().0,
+            weight_mint,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ReleaseStakeLock {},
+    )
+}
+
+/// `identity_asset` is the voter's Core asset in the identity collection.
+pub fn vote_fast_identity(
+    voter: Pubkey,
+    action_id: u64,
+    delegation_epoch: u32,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+    identity_asset: Pubkey,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::VoteFastIdentity {
+            fast_action,
+            fast_vote: pda::fast_vote(&fast_action, &voter).0,
+            vote_credits: pda::vote_credits(&voter).0,
+            identity_weights: pda::identity_weights().0,
+            identity_asset,
+            identity_claim: pda::identity_claim(&fast_action, &identity_asset).0,
+            agent_record: pda::agent_record(&voter).0,
+            voter,
+            config: pda::global_config().0,
+            metrics: Some(pda::metrics(MetricsLayer::Er).0),
+            hive_freeze: pda::hive_freeze(&Pubkey::default()).0,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VoteFastIdentity {
+            _action_id: action_id,
+            vote_value,
+            voter_commitment,
+            delegation_epoch,
+        },
+    )
+}
+
+/// `weight` and `proof` come from the snapshot's
+/// [`WeightTree`](crate::snapshot::WeightTree).
+pub fn vote_fast_snapshot(
+    voter: Pubkey,
+    action_id: u64,
+    delegation_epoch: u32,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+    weight: u64,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::VoteFastSnapshot {
+            fast_action,
+            fast_vote: pda::fast_vote(&fast_action, &voter).0,
+            vote_credits: pda::vote_credits(&voter).0,
+            weight_snapshot: pda::weight_snapshot(&fast_action).0,
+            agent_record: pda::agent_record(&voter).0,
+            voter,
+            config: pda::global_config().0,
+            system_program: system_program::ID,
+            metrics: Some(pda::metrics(MetricsLayer::Er).0),
+            hive_freeze: pda::hive_freeze(&Pubkey::default()).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VoteFastSnapshot {
+            _action_id: action_id,
+            vote_value,
+            voter_commitment,
+            delegation_epoch,
+            weight,
+            proof,
+        },
+    )
+}
+
+/// Send to the base layer before the action is delegated. `total_weight` is
+/// the sum of the tree's leaves (`WeightTree::total_weight`). `as_validator`
+/// passes the poster's ValidatorRecord; leave it unset for the config's
+/// snapshotter.
+pub fn post_weight_snapshot(
+    poster: Pubkey,
+    action_id: u64,
+    root: [u8; 32],
+    total_weight: u64,
+    as_validator: bool,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::PostWeightSnapshot {
+            fast_action,
+            weight_snapshot: pda::weight_snapshot(&fast_action).0,
+            weight_strategy: pda::weight_strategy(&fast_action).0,
+            config: pda::global_config().0,
+            validator_record: as_validator.then(|| pda::validator_record(&poster).0),
+            poster,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::PostWeightSnapshot { root, total_weight },
+    )
+}
+
+/// Send to the base layer before the action is delegated; action authority
+/// only. `params` are passed to `strategy_program` with every ballot.
+pub fn set_weight_strategy(
+    authority: Pubkey,
+    action_id: u64,
+    strategy_program: Pubkey,
+    params: Vec<u8>,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::SetWeightStrategy {
+            fast_action,
+            weight_strategy: pda::weight_strategy(&fast_action).0,
+            weight_snapshot: pda::weight_snapshot(&fast_action).0,
+            strategy_program,
+            authority,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetWeightStrategy { params },
+    )
+}
+
+/// `strategy_accounts` are what the action's strategy program reads to weigh
+/// the voter, passed after the voter and the action.
+pub fn vote_fast_strategy(
+    voter: Pubkey,
+    action_id: u64,
+    delegation_epoch: u32,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+    strategy_program: Pubkey,
+    strategy_accounts: &[Pubkey],
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    let mut ix = build(
+        accounts::VoteFastStrategy {
+            fast_action,
+            fast_vote: pda::fast_vote(&fast_action, &voter).0,
+            vote_credits: pda::vote_credits(&voter).0,
+            weight_strategy: pda::weight_strategy(&fast_action).0,
+            strategy_program,
+            agent_record: pda::agent_record(&voter).0,
+            voter,
+            config: pda::global_config().0,
+            system_program: system_program::ID,
+            metrics: Some(pda::metrics(MetricsLayer::Er).0),
+            hive_freeze: pda::hive_freeze(&Pubkey::default()).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VoteFastStrategy {
+            _action_id: action_id,
+            vote_value,
+            voter_commitment,
+            delegation_epoch,
+        },
+    );
+    ix.accounts.extend(strategy_accounts.iter().map(|key| AccountMeta::new_readonly(*key, false)));
+    ix
+}
+
+/// Light trees a compressed ballot is written to
+pub struct LightTrees {
+    pub address_tree: Pubkey,
+    pub address_queue: Pubkey,
+    pub state_tree: Pubkey,
+}
+
+/// Send before the action's first vote; action authority only. Every ballot
+/// must then be created in `address_tree`.
+pub fn enable_compressed_ballots(authority: Pubkey, action_id: u64, address_tree: Pubkey) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::EnableCompressedBallots {
+            fast_action,
+            compressed_ballots: pda::compressed_ballots(&fast_action).0,
+            authority,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::EnableCompressedBallots { address_tree },
+    )
+}
+
+/// Base layer only. `proof` comes from the Light prover for the address
+/// `kamiyo_fast_voting::compressed_vote_address(&trees.address_tree, ..)`.
+pub fn vote_fast_compressed(
+    voter: Pubkey,
+    action_id: u64,
+    delegation_epoch: u32,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+    trees: LightTrees,
+    proof: AddressProof,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::VoteFastCompressed {
+            fast_action,
+            compressed_ballots: pda::compressed_ballots(&fast_action).0,
+            vote_credits: pda::vote_credits(&voter).0,
+            agent_record: pda::agent_record(&voter).0,
+            vote_key: pda::vote_key(&voter).0,
+            agent: voter,
+            voter,
+            config: pda::global_config().0,
+            cpi_authority: pda::light_cpi_authority().0,
+            registered_program_pda: pda::light_registered_program().0,
+            noop_program: SPL_NOOP_PROGRAM_ID,
+            account_compression_authority: pda::light_account_compression_authority().0,
+            account_compression_program: LIGHT_ACCOUNT_COMPRESSION_PROGRAM_ID,
+            self_program: ID,
+            address_tree: trees.address_tree,
+            address_queue: trees.address_queue,
+            state_tree: trees.state_tree,
+            light_system_program: LIGHT_SYSTEM_PROGRAM_ID,
+            system_program: system_program::ID,
+            metrics: Some(pda::metrics(MetricsLayer::Er).0),
+            hive_freeze: pda::hive_freeze(&Pubkey::default()).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VoteFastCompressed {
+            _action_id: action_id,
+            vote_value,
+            voter_commitment,
+            delegation_epoch,
+            proof,
+        },
+    )
+}
+
+/// Once voting has ended. `ballots` are in `state_tree`, in ascending
+/// compressed address order, and `proof` is the Light prover's validity
+/// proof over them.
+pub fn verify_compressed_tally_page(
+    payer: Pubkey,
+    action_id: u64,
+    state_tree: Pubkey,
+    nullifier_queue: Pubkey,
+    ballots: Vec<CompressedBallot>,
+    proof: CompressedProof,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::VerifyCompressedTallyPage {
+            fast_action,
+            compressed_ballots: pda::compressed_ballots(&fast_action).0,
+            tally_audit: pda::tally_audit(&fast_action).0,
+            payer,
+            cpi_authority: pda::light_cpi_authority().0,
+            registered_program_pda: pda::light_registered_program().0,
+            noop_program: SPL_NOOP_PROGRAM_ID,
+            account_compression_authority: pda::light_account_compression_authority().0,
+            account_compression_program: LIGHT_ACCOUNT_COMPRESSION_PROGRAM_ID,
+            self_program: ID,
+            state_tree,
+            nullifier_queue,
+            light_system_program: LIGHT_SYSTEM_PROGRAM_ID,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VerifyCompressedTallyPage { ballots, proof },
+    )
+}
+
+/// Permissionless, in the reveal window after the deadline. `salt` is what
+/// the voter committed to with `kamiyo_fast_voting::ballot_commitment`, sent
+/// as the ballot's voter commitment.
+pub fn reveal_ballot(action_id: u64, voter: Pubkey, vote_value: bool, salt: [u8; 32]) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::RevealBallot {
+            fast_action,
+            fast_vote: pda::fast_vote(&fast_action, &voter).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::RevealBallot { vote_value, salt },
+    )
+}
+
+/// Admin only; pays for the verifier's record on first use
+pub fn set_ballot_verifier(admin: Pubkey, verifier: Pubkey, allowed: bool) -> Instruction {
+    build(
+        accounts::SetBallotVerifier {
+            ballot_verifier: pda::ballot_verifier(&verifier).0,
+            verifier,
+            config: pda::global_config().0,
+            admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetBallotVerifier { allowed },
+    )
+}
+
+/// Send to the base layer before the action's first vote; action authority
+/// only. `hive` is the action's hive, whose member_root the ballots are
+/// proven against, and `verifier` must be allowed by the admin.
+pub fn enable_anonymous_ballots(authority: Pubkey, action_id: u64, hive: Pubkey, verifier: Pubkey) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::EnableAnonymousBallots {
+            fast_action,
+            anonymous_ballots: pda::anonymous_ballots(&fast_action).0,
+            hive_account: pda::hive(&hive).0,
+            verifier,
+            ballot_verifier: pda::ballot_verifier(&verifier).0,
+            authority,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::EnableAnonymousBallots {},
+    )
+}
+
+/// `payer` need not be, and should not be, the voter; `proof` is what the
+/// action's verifier checks.
+pub fn vote_anonymous(
+    payer: Pubkey,
+    action_id: u64,
+    verifier: Pubkey,
+    nullifier: [u8; 32],
+    vote_value: bool,
+    proof: Vec<u8>,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::VoteAnonymous {
+            fast_action,
+            anonymous_ballots: pda::anonymous_ballots(&fast_action).0,
+            verifier,
+            ballot_verifier: pda::ballot_verifier(&verifier).0,
+            nullifier_record: pda::nullifier(&fast_action, &nullifier).0,
+            hive_freeze: pda::hive_freeze(&Pubkey::default()).0,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VoteAnonymous {
+            nullifier,
+            vote_value,
+            proof,
+        },
+    )
+}
+
+/// Registers `agent` on first use; later calls add `amount` to its bond.
+pub fn register_agent(agent: Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::RegisterAgent {
+            agent_record: pda::agent_record(&agent).0,
+            vote_key: pda::vote_key(&agent).0,
+            agent,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::RegisterAgent { amount },
+    )
+}
+
+pub fn evict_agent(admin: Pubkey, agent: Pubkey) -> Instruction {
+    build(
+        accounts::EvictAgent {
+            agent_record: pda::agent_record(&agent).0,
+            agent,
+            config: pda::global_config().0,
+            admin,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::EvictAgent {},
+    )
+}
+
+/// Config admin only. Creates `agent`'s record, unbonded, if it has none.
+pub fn set_agent_class(admin: Pubkey, agent: Pubkey, class: AgentClass) -> Instruction {
+    build(
+        accounts::SetAgentClass {
+            agent_record: pda::agent_record(&agent).0,
+            agent,
+            vote_key: pda::vote_key(&agent).0,
+            config: pda::global_config().0,
+            admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetAgentClass { class },
+    )
+}
+
+pub fn migrate_agent_record(payer: Pubkey, agent: Pubkey) -> Instruction {
+    build(
+        accounts::MigrateAgentRecord {
+            agent_record: pda::agent_record(&agent).0,
+            agent,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateAgentRecord {},
+    )
+}
+
+/// `key` co-signs with `agent`.
+pub fn set_vote_key(agent: Pubkey, key: Pubkey) -> Instruction {
+    build(
+        accounts::SetVoteKey {
+            vote_key: pda::vote_key(&key).0,
+            agent_record: pda::agent_record(&agent).0,
+            key_record: pda::agent_record(&key).0,
+            agent_vote_key: pda::vote_key(&agent).0,
+            agent,
+            key,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetVoteKey {},
+    )
+}
+
+pub fn clear_vote_key(agent: Pubkey, key: Pubkey) -> Instruction {
+    build(
+        accounts::ClearVoteKey {
+            vote_key: pda::vote_key(&key).0,
+            agent,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ClearVoteKey {},
+    )
+}
+
+/// An empty `shares` revokes every delegation.
+pub fn set_vote_delegation(delegator: Pubkey, shares: Vec<DelegationShare>) -> Instruction {
+    build(
+        accounts::SetVoteDelegation {
+            vote_delegation: pda::vote_delegation(&delegator).0,
+            delegator,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetVoteDelegation { shares },
+    )
+}
+
+/// `dependencies` are the action's prerequisite actions, in declaration order;
+/// `audited` is FastAction::audited. Pass the rollup's current epoch to count
+/// the action in its hive's EpochStats. Sent to the ephemeral rollup, not
+/// the base layer; for a hive with result attestors, after ed25519 program
+/// instructions verifying their signatures over the result's
+/// `attestation_digest`.
+pub fn tally_and_commit(
+    payer: Pubkey,
+    action_id: u64,
+    hive: Option<Pubkey>,
+    dependencies: &[Pubkey],
+    audited: bool,
+    epoch: Option<u64>,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    let mut ix = build(
+        accounts::TallyAndCommit {
+            fast_action,
+            payer,
+            magic_context: MAGIC_CONTEXT_ID,
+            magic_program: MAGIC_PROGRAM_ID,
+            roles: hive.map(|h| pda::roles(&h).0),
+            tally_audit: audited.then(|| pda::tally_audit(&fast_action).0),
+            metrics: Some(pda::metrics(MetricsLayer::Er).0),
+            epoch_stats: epoch.map(|e| pda::epoch_stats(&hive.unwrap_or_default(), e).0),
+            result_attestors: pda::result_attestors(&hive.unwrap_or_default()).0,
+            instructions: sysvar::instructions::ID,
+            category_config: pda::category_config(&hive.unwrap_or_default()).0,
+            config: pda::global_config().0,
+            standings_board: hive.map(|h| pda::standings_board(&h).0),
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::TallyAndCommit {},
+    );
+    ix.accounts
+        .extend(dependencies.iter().map(|d| AccountMeta::new_readonly(*d, false)));
+    ix
+}
+
+/// Create the EpochStats of `hive`'s actions, or of unscoped actions for
+/// default, finalized in `epoch`; sent to the layer they finalize on
+pub fn initialize_epoch_stats(payer: Pubkey, hive: Pubkey, epoch: u64) -> Instruction {
+    build(
+        accounts::InitializeEpochStats {
+            epoch_stats: pda::epoch_stats(&hive, epoch).0,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::InitializeEpochStats { hive, epoch },
+    )
+}
+
+/// Recount `votes`, FastVote addresses above any already counted, in
+/// ascending order. Sent to the ephemeral rollup.
+pub fn verify_tally_page(payer: Pubkey, action_id: u64, votes: &[Pubkey]) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    let mut ix = build(
+        accounts::VerifyTallyPage {
+            fast_action,
+            tally_audit: pda::tally_audit(&fast_action).0,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VerifyTallyPage {},
+    );
+    ix.accounts.extend(votes.iter().map(|v| AccountMeta::new_readonly(*v, false)));
+    ix
+}
+
+/// Commit the live tally to the base layer without undelegating. Sent to the
+/// ephemeral rollup.
+pub fn checkpoint(payer: Pubkey, action_id: u64) -> Instruction {
+    build(
+        accounts::Checkpoint {
+            fast_action: pda::fast_action(action_id).0,
+            payer,
+            magic_context: MAGIC_CONTEXT_ID,
+            magic_program: MAGIC_PROGRAM_ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::Checkpoint {},
+    )
+}
+
+/// Undelegate an action nobody finalized, after the liveness timeout. Sent to
+/// the ephemeral rollup.
+pub fn force_undelegate(payer: Pubkey, action_id: u64) -> Instruction {
+    build(
+        accounts::ForceUndelegate {
+            fast_action: pda::fast_action(action_id).0,
+            payer,
+            magic_context: MAGIC_CONTEXT_ID,
+            magic_program: MAGIC_PROGRAM_ID,
+            metrics: Some(pda::metrics(MetricsLayer::Er).0),
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ForceUndelegate {},
+    )
+}
+
+/// Sent to the ephemeral rollup. `actions` are addresses of delegated actions;
+/// the program skips any that are not yet stale.
+pub fn reap_stale_delegations(payer: Pubkey, actions: &[Pubkey]) -> Instruction {
+    let mut ix = build(
+        accounts::ReapStaleDelegations {
+            payer,
+            magic_context: MAGIC_CONTEXT_ID,
+            magic_program: MAGIC_PROGRAM_ID,
+            metrics: Some(pda::metrics(MetricsLayer::Er).0),
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ReapStaleDelegations {},
+    );
+    ix.accounts.extend(actions.iter().map(|a| AccountMeta::new(*a, false)));
+    ix
+}
+
+/// Send to the base layer, before the action is delegated; `backup` must be
+/// a live allowlisted validator other than the one it will be pinned to.
+pub fn enable_redundancy(authority: Pubkey, action_id: u64, backup: Pubkey) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::EnableRedundancy {
+            fast_action,
+            action_mirror: pda::action_mirror(&fast_action).0,
+            backup_record: pda::validator_record(&backup).0,
+            authority,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::EnableRedundancy { backup },
+    )
+}
+
+/// Send to the base layer; delegates the action's mirror to `backup`, the
+/// validator named in enable_redundancy.
+pub fn delegate_mirror(payer: Pubkey, action_id: u64, backup: Pubkey) -> Instruction {
+    let action_mirror = pda::action_mirror(&pda::fast_action(action_id).0).0;
+    build(
+        accounts::DelegateMirror {
+            buffer_action_mirror: pda::delegate_buffer(&action_mirror).0,
+            delegation_record_action_mirror: pda::delegation_record(&action_mirror).0,
+            delegation_metadata_action_mirror: pda::delegation_metadata(&action_mirror).0,
+            action_mirror,
+            backup_record: pda::validator_record(&backup).0,
+            payer,
+            owner_program: ID,
+            delegation_program: ephemeral_rollups_sdk::id(),
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::DelegateMirror {},
+    )
+}
+
+/// Sent to the backup's ephemeral rollup after the liveness timeout, behind
+/// an ed25519 instruction in which the backup signs the tally's
+/// attestation_digest.
+pub fn commit_mirror(payer: Pubkey, action_id: u64) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::CommitMirror {
+            action_mirror: pda::action_mirror(&fast_action).0,
+            fast_action,
+            instructions: sysvar::instructions::ID,
+            payer,
+            magic_context: MAGIC_CONTEXT_ID,
+            magic_program: MAGIC_PROGRAM_ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::CommitMirror {},
+    )
+}
+
+/// Send to the base layer once the mirror has committed and the action is
+/// back; permissionless.
+pub fn resolve_redundant_commit(action_id: u64) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::ResolveRedundantCommit {
+            fast_action,
+            action_mirror: pda::action_mirror(&fast_action).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ResolveRedundantCommit {},
+    )
+}
+
+/// Send to the base layer; `creator` is the action's and receives its rent.
+/// `validator` is its FastAction::delegated_validator while
+/// FastAction::validator_counted is set. An action still listed under its
+/// tags needs their pages appended, from `tag_accounts`.
+pub fn expire_action(action_id: u64, creator: Pubkey, validator: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::ExpireAction {
+            fast_action: pda::fast_action(action_id).0,
+            creator,
+            active_actions: Some(pda::active_actions(None).0),
+            metrics: Some(pda::metrics(MetricsLayer::Base).0),
+            validator_record: validator.map(|v| pda::validator_record(&v).0),
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ExpireAction {},
+    )
+}
+
+/// Create the ActiveActions index of `hive`'s actions, or of unscoped actions
+/// for None.
+pub fn initialize_active_actions(payer: Pubkey, hive: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::InitializeActiveActions {
+            hive_account: hive.map(|h| pda::hive(&h).0),
+            active_actions: pda::active_actions(hive.as_ref()).0,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::InitializeActiveActions {},
+    )
+}
+
+/// Create page `page` of `tag`'s index, of actions created through `hive`'s
+/// Hive account or of unscoped actions for None; the pages before it must
+/// exist.
+pub fn initialize_tag_index(payer: Pubkey, hive: Option<Pubkey>, tag: [u8; 8], page: u32) -> Instruction {
+    build(
+        accounts::InitializeTagIndex {
+            hive_account: hive.map(|h| pda::hive(&h).0),
+            previous: page.checked_sub(1).map(|previous| pda::tag_index(hive.as_ref(), &tag, previous).0),
+            tag_index: pda::tag_index(hive.as_ref(), &tag, page).0,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::InitializeTagIndex { tag, page },
+    )
+}
+
+/// Send to the base layer once the action is final; `fast_action` is its
+/// address and `pages` the page listing it for each of its tags, in tag
+/// order. `hive` as for retire_action.
+pub fn retire_tags(fast_action: Pubkey, hive: Option<Pubkey>, pages: &[([u8; 8], u32)]) -> Instruction {
+    let mut ix = build(
+        accounts::RetireTags {
+            fast_action,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::RetireTags {},
+    );
+    ix.accounts.extend(tag_accounts(hive.as_ref(), pages));
+    ix
+}
+
+/// Send to the base layer once the action is final; `fast_action` is its
+/// address, hive-scoped or not, and `hive` the hive it was created through.
+pub fn retire_action(fast_action: Pubkey, hive: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::RetireAction {
+            fast_action,
+            active_actions: pda::active_actions(hive.as_ref()).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::RetireAction {},
+    )
+}
+
+/// Simulate on whichever layer holds the action; the Standing is the
+/// transaction's return data.
+pub fn get_standing(action_id: u64) -> Instruction {
+    build(
+        accounts::GetStanding {
+            fast_action: pda::fast_action(action_id).0,
+        },
+        instruction::GetStanding { _action_id: action_id },
+    )
+}
+
+/// Simulate on whichever layer holds the action; the TallyPreview is the
+/// transaction's return data.
+pub fn preview_tally(action_id: u64) -> Instruction {
+    build(
+        accounts::PreviewTally {
+            fast_action: pda::fast_action(action_id).0,
+        },
+        instruction::PreviewTally { _action_id: action_id },
+    )
+}
+
+/// Base layer only. Fails unless the action passed; the ActionPassed is the
+/// transaction's return data. Other programs make this call through
+/// kamiyo-voting-interface.
+pub fn assert_action_passed(fast_action: Pubkey) -> Instruction {
+    build(accounts::AssertActionPassed { fast_action }, instruction::AssertActionPassed {})
+}
+
+/// `hive` is the action's hive (default when unscoped); `as_canceller` when
+/// `authority` cancels through Role::Canceller rather than as the action
+/// authority. `metrics_layer` is the layer the instruction is sent to.
+pub fn cancel_action(
+    authority: Pubkey,
+    action_id: u64,
+    hive: Pubkey,
+    as_canceller: bool,
+    metrics_layer: MetricsLayer,
+) -> Instruction {
+    build(
+        accounts::CancelAction {
+            fast_action: pda::fast_action(action_id).0,
+            authority,
+            roles: as_canceller.then(|| pda::roles(&hive).0),
+            hive_config: pda::hive_config(&hive).0,
+            metrics: Some(pda::metrics(metrics_layer).0),
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::CancelAction { _action_id: action_id },
+    )
+}
+
+/// Send to the base layer before the action is delegated.
+pub fn amend_action(
+    authority: Pubkey,
+    action_id: u64,
+    action_hash: [u8; 32],
+    amendment: ActionAmendment,
+) -> Instruction {
+    build(
+        accounts::AmendAction {
+            fast_action: pda::fast_action(action_id).0,
+            action_hash_record: pda::action_hash_record(&action_hash).0,
+            config: pda::global_config().0,
+            authority,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::AmendAction { amendment },
+    )
+}
+
+/// Send to whichever layer holds the action. Each of `voters` must have
+/// voted and must sign the transaction alongside `authority`.
+pub fn extend_deadline(authority: Pubkey, action_id: u64, slots: u64, voters: &[Pubkey]) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    let mut ix = build(
+        accounts::ExtendDeadline {
+            fast_action,
+            authority,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ExtendDeadline { slots },
+    );
+    let mut supporters: Vec<_> = voters.iter().map(|v| (pda::fast_vote(&fast_action, v).0, *v)).collect();
+    supporters.sort();
+    for (vote, voter) in supporters {
+        ix.accounts.push(AccountMeta::new_readonly(vote, false));
+        ix.accounts.push(AccountMeta::new_readonly(voter, true));
+    }
+    ix
+}
+
+/// Send to the base layer before the first vote; `authority` pays any rent.
+pub fn set_metadata(authority: Pubkey, action_id: u64, description: String, uri: String) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::SetMetadata {
+            fast_action,
+            metadata: pda::action_metadata(&fast_action).0,
+            authority,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetMetadata { description, uri },
+    )
+}
+
+/// Send to the base layer before the first vote; the ciphertext itself is
+/// handed to the key's TEE off-chain.
+pub fn seal_description(
+    authority: Pubkey,
+    action_id: u64,
+    ciphertext_hash: [u8; 32],
+    encryption_key: [u8; 32],
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::SealDescription {
+            fast_action,
+            sealed_description: pda::sealed_description(&fast_action).0,
+            authority,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SealDescription {
+            ciphertext_hash,
+            encryption_key,
+        },
+    )
+}
+
+/// Send to the base layer once the action has passed and settled; signed by
+/// the TEE validator holding the key.
+pub fn reveal_description(validator: Pubkey, action_id: u64, description: String) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::RevealDescription {
+            fast_action,
+            sealed_description: pda::sealed_description(&fast_action).0,
+            validator_record: pda::validator_record(&validator).0,
+            validator,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::RevealDescription { description },
+    )
+}
+
+/// `merkle_tree` must be delegated to `pda::receipt_authority`.
+pub fn set_receipt_tree(admin: Pubkey, merkle_tree: Pubkey, uri_prefix: String) -> Instruction {
+    build(
+        accounts::SetReceiptTree {
+            receipt_tree: pda::receipt_tree().0,
+            config: pda::global_config().0,
+            admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetReceiptTree { merkle_tree, uri_prefix },
+    )
+}
+
+/// Send to the base layer once the action's result has settled;
+/// `merkle_tree` is the ReceiptTree's.
+pub fn mint_receipt(payer: Pubkey, action_id: u64, voter: Pubkey, merkle_tree: Pubkey) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    let fast_vote = pda::fast_vote(&fast_action, &voter).0;
+    build(
+        accounts::MintReceipt {
+            fast_action,
+            fast_vote,
+            receipt: pda::vote_receipt(&fast_vote).0,
+            receipt_tree: pda::receipt_tree().0,
+            receipt_authority: pda::receipt_authority().0,
+            voter,
+            tree_config: pda::tree_config(&merkle_tree).0,
+            merkle_tree,
+            payer,
+            log_wrapper: SPL_NOOP_PROGRAM_ID,
+            compression_program: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+            bubblegum_program: BUBBLEGUM_PROGRAM_ID,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::MintReceipt {},
+    )
+}
+
+/// `reason_hash` is `kamiyo_fast_voting::reason_hash` of the ballot's
+/// FastVote address and the reason text; send while voting is open.
+pub fn set_vote_reason(voter: Pubkey, action_id: u64, reason_hash: [u8; 32]) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::SetVoteReason {
+            fast_action,
+            fast_vote: pda::fast_vote(&fast_action, &voter).0,
+            voter,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetVoteReason { reason_hash },
+    )
+}
+
+/// Anyone may send once the action is finalized; needs no signer
+pub fn reveal_reason(action_id: u64, voter: Pubkey, reason: String) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::RevealReason {
+            fast_action,
+            fast_vote: pda::fast_vote(&fast_action, &voter).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::RevealReason { reason },
+    )
+}
+
+/// `accounts` are those the callback program will receive after the action
+/// account; execute_action must later be given the same list.
+pub fn register_callback(
+    authority: Pubkey,
+    action_id: u64,
+    program_id: Pubkey,
+    accounts: &[AccountMeta],
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::RegisterCallback {
+            fast_action,
+            registry: pda::callback_registry(&fast_action).0,
+            authority,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::RegisterCallback {
+            program_id,
+            accounts_hash: accounts_hash(accounts),
+            account_count: accounts.len() as u8,
+        },
+    )
+}
+
+/// execute_action's remaining accounts: each program, then its accounts
+fn callback_accounts(callbacks: &[(Pubkey, Vec<AccountMeta>)]) -> Vec<AccountMeta> {
+    let mut metas = Vec::new();
+    for (program_id, accounts) in callbacks {
+        metas.push(AccountMeta::new_readonly(*program_id, false));
+        metas.extend(accounts.iter().map(|meta| AccountMeta {
+            is_signer: false,
+            ..meta.clone()
+        }));
+    }
+    metas
+}
+
+/// Commit `executor` to the accounts execute_action will be given; send to
+/// the base layer at least a slot before it. `callbacks` as for
+/// execute_action.
+pub fn stage_execution(executor: Pubkey, action_id: u64, callbacks: &[(Pubkey, Vec<AccountMeta>)]) -> Instruction {
+    stage(executor, pda::fast_action(action_id).0, &callback_accounts(callbacks))
+}
+
+fn stage(executor: Pubkey, fast_action: Pubkey, remaining: &[AccountMeta]) -> Instruction {
+    build(
+        accounts::StageExecution {
+            fast_action,
+            registry: pda::callback_registry(&fast_action).0,
+            stage: pda::execution_stage(&fast_action, &executor).0,
+            executor,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::StageExecution {
+            commitment: accounts_hash(remaining),
+        },
+    )
+}
+
+/// Send to the base layer once the action has settled, in a later slot
+/// than `executor`'s stage_execution. `callbacks` holds each registered
+/// program and its accounts, in registration order.
+pub fn execute_action(executor: Pubkey, action_id: u64, callbacks: &[(Pubkey, Vec<AccountMeta>)]) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    let mut ix = build(
+        accounts::ExecuteAction {
+            fast_action,
+            registry: pda::callback_registry(&fast_action).0,
+            stage: pda::execution_stage(&fast_action, &executor).0,
+            executor,
+            schedule: None,
+            keeper: None,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ExecuteAction {},
+    );
+    ix.accounts.extend(callback_accounts(callbacks));
+    ix
+}
+
+/// Send to the base layer once the action is finalized, after its last
+/// callback is registered; `callbacks` as for execute_action. `reward`
+/// lamports are added to any already posted.
+pub fn schedule_execution(
+    funder: Pubkey,
+    action_id: u64,
+    reward: u64,
+    callbacks: &[(Pubkey, Vec<AccountMeta>)],
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    let accounts = callback_accounts(callbacks)
+        .into_iter()
+        .map(|meta| ScheduledAccount {
+            pubkey: meta.pubkey,
+            is_writable: meta.is_writable,
+        })
+        .collect();
+    build(
+        accounts::ScheduleExecution {
+            fast_action,
+            registry: pda::callback_registry(&fast_action).0,
+            schedule: pda::execution_schedule(&fast_action).0,
+            funder,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ScheduleExecution { reward, accounts },
+    )
+}
+
+/// stage_execution for `keeper` of the accounts in `schedule`
+pub fn stage_scheduled(keeper: Pubkey, schedule: &ExecutionSchedule) -> Instruction {
+    stage(keeper, schedule.action, &schedule.remaining_accounts())
+}
+
+/// execute_action built from the ExecutionSchedule at `address`, paying
+/// `keeper`. Send to the base layer once the action has settled, a slot
+/// after stage_scheduled.
+pub fn execute_scheduled(keeper: Pubkey, address: Pubkey, schedule: &ExecutionSchedule) -> Instruction {
+    let mut ix = build(
+        accounts::ExecuteAction {
+            fast_action: schedule.action,
+            registry: pda::callback_registry(&schedule.action).0,
+            stage: pda::execution_stage(&schedule.action, &keeper).0,
+            executor: keeper,
+            schedule: Some(address),
+            keeper: Some(keeper),
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ExecuteAction {},
+    );
+    ix.accounts.extend(schedule.remaining_accounts());
+    ix
+}
+
+/// Close the action's schedule, returning its balance to `funder`.
+pub fn cancel_execution(funder: Pubkey, action_id: u64) -> Instruction {
+    build(
+        accounts::CancelExecution {
+            schedule: pda::execution_schedule(&pda::fast_action(action_id).0).0,
+            funder,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::CancelExecution {},
+    )
+}
+
+pub fn transfer_action_authority(authority: Pubkey, action_id: u64, new_authority: Pubkey) -> Instruction {
+    build(
+        accounts::TransferActionAuthority {
+            fast_action: pda::fast_action(action_id).0,
+            authority,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::TransferActionAuthority { new_authority },
+    )
+}
+
+/// Permissionless; `payer` covers any extra rent.
+pub fn migrate_action(payer: Pubkey, action_id: u64) -> Instruction {
+    build(
+        accounts::MigrateAction {
+            fast_action: pda::fast_action(action_id).0,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::MigrateAction { action_id },
+    )
+}
+
+/// `admin` must be the program's upgrade authority.
+pub fn initialize_config(
+    admin: Pubkey,
+    weight_mint: Pubkey,
+    weight_extensions: u8,
+    transfer_hook_program: Pubkey,
+) -> Instruction {
+    build(
+        accounts::InitializeConfig {
+            config: pda::global_config().0,
+            admin,
+            fast_voting_program: ID,
+            program_data: pda::program_data().0,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::InitializeConfig {
+            weight_mint,
+            weight_extensions,
+            transfer_hook_program,
+        },
+    )
+}
+
+pub fn update_config(admin: Pubkey, update: ConfigUpdate) -> Instruction {
+    build(
+        accounts::UpdateConfig {
+            config: pda::global_config().0,
+            admin,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::UpdateConfig { update },
+    )
+}
+
+/// Send to the layer `layer` names; the ER's Metrics account is created on
+/// the ephemeral rollup.
+pub fn initialize_metrics(admin: Pubkey, layer: MetricsLayer) -> Instruction {
+    build(
+        accounts::InitializeMetrics {
+            metrics: pda::metrics(layer).0,
+            config: pda::global_config().0,
+            admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::InitializeMetrics { layer },
+    )
+}
+
+/// As initialize_metrics, for `hive`'s own account; its authority signs.
+pub fn initialize_hive_metrics(authority: Pubkey, hive: Pubkey, layer: MetricsLayer) -> Instruction {
+    build(
+        accounts::InitializeHiveMetrics {
+            hive_account: pda::hive(&hive).0,
+            metrics: pda::hive_metrics(layer, &hive).0,
+            authority,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::InitializeHiveMetrics { layer },
+    )
+}
+
+/// Sent to the ephemeral rollup; `hive`'s authority signs.
+pub fn initialize_standings_board(authority: Pubkey, hive: Pubkey) -> Instruction {
+    build(
+        accounts::InitializeStandingsBoard {
+            hive_account: pda::hive(&hive).0,
+            standings_board: pda::standings_board(&hive).0,
+            authority,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::InitializeStandingsBoard {},
+    )
+}
+
+/// `None` clears the default cap for `kind`.
+pub fn set_weight_cap(admin: Pubkey, kind: ActionKind, cap: Option<WeightCap>) -> Instruction {
+    build(
+        accounts::SetWeightCap {
+            weight_caps: pda::weight_caps().0,
+            config: pda::global_config().0,
+            admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetWeightCap { kind, cap },
+    )
+}
+
+/// Send after an ed25519 program instruction verifying the group key's
+/// signature over `Ballot { action, choice: vote_value as u8 }.message()`,
+/// carrying its own signature, key and message. `submitter` pays.
+pub fn vote_fast_group(
+    submitter: Pubkey,
+    group_key: Pubkey,
+    action_id: u64,
+    delegation_epoch: u32,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::VoteFastGroup {
+            fast_action,
+            fast_vote: pda::fast_vote(&fast_action, &group_key).0,
+            vote_credits: pda::vote_credits(&group_key).0,
+            voter_group: pda::voter_group(&group_key).0,
+            agent_record: pda::agent_record(&group_key).0,
+            instructions: sysvar::instructions::ID,
+            action_relayers: pda::action_relayers(&fast_action).0,
+            self_submit: pda::self_submit(&fast_action, &group_key).0,
+            submitter,
+            config: pda::global_config().0,
+            metrics: Some(pda::metrics(MetricsLayer::Er).0),
+            hive_freeze: pda::hive_freeze(&Pubkey::default()).0,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VoteFastGroup {
+            _action_id: action_id,
+            vote_value,
+            voter_commitment,
+            delegation_epoch,
+        },
+    )
+}
+
+pub fn set_voter_group(
+    admin: Pubkey,
+    group_key: Pubkey,
+    members: Vec<Pubkey>,
+    threshold: u8,
+    weight: u64,
+) -> Instruction {
+    build(
+        accounts::SetVoterGroup {
+            voter_group: pda::voter_group(&group_key).0,
+            group_key,
+            config: pda::global_config().0,
+            admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetVoterGroup { members, threshold, weight },
+    )
+}
+
+pub fn remove_voter_group(admin: Pubkey, group_key: Pubkey) -> Instruction {
+    build(
+        accounts::RemoveVoterGroup {
+            voter_group: pda::voter_group(&group_key).0,
+            config: pda::global_config().0,
+            admin,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::RemoveVoterGroup {},
+    )
+}
+
+/// Action authority only, before the first ballot.
+pub fn set_action_relayers(
+    authority: Pubkey,
+    action_id: u64,
+    relayers: Vec<Pubkey>,
+    fallback_slots: u64,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::SetActionRelayers {
+            fast_action,
+            action_relayers: pda::action_relayers(&fast_action).0,
+            authority,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetActionRelayers { relayers, fallback_slots },
+    )
+}
+
+/// `member` belongs to the group keyed `group_key`.
+pub fn request_self_submit(member: Pubkey, group_key: Pubkey, action_id: u64) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::RequestSelfSubmit {
+            fast_action,
+            action_relayers: pda::action_relayers(&fast_action).0,
+            voter_group: pda::voter_group(&group_key).0,
+            self_submit: pda::self_submit(&fast_action, &group_key).0,
+            member,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::RequestSelfSubmit {},
+    )
+}
+
+/// `admin` must hold Role::Admin in `hive`; `emitter` is the EVM contract's
+/// address, left-padded to 32 bytes.
+pub fn register_foreign_hive(
+    admin: Pubkey,
+    hive: Pubkey,
+    chain: u16,
+    emitter: [u8; 32],
+    max_weight: u64,
+) -> Instruction {
+    build(
+        accounts::RegisterForeignHive {
+            foreign_hive: pda::foreign_hive(&hive, chain, &emitter).0,
+            roles: pda::roles(&hive).0,
+            admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::RegisterForeignHive { chain, emitter, max_weight },
+    )
+}
+
+/// Send to the base layer once the core bridge has posted the VAA;
+/// `posted_vaa` is its PostedVAA account. `fast_action` is the hive action's
+/// address, from `pda::hive_action` or `pda::fast_action`.
+pub fn submit_foreign_tally(
+    payer: Pubkey,
+    fast_action: Pubkey,
+    hive: Pubkey,
+    chain: u16,
+    emitter: [u8; 32],
+    posted_vaa: Pubkey,
+) -> Instruction {
+    let foreign_hive = pda::foreign_hive(&hive, chain, &emitter).0;
+    build(
+        accounts::SubmitForeignTally {
+            fast_action,
+            fast_vote: pda::fast_vote(&fast_action, &foreign_hive).0,
+            foreign_hive,
+            posted_vaa,
+            payer,
+            metrics: Some(pda::metrics(MetricsLayer::Base).0),
+            hive_freeze: pda::hive_freeze(&hive).0,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SubmitForeignTally {},
+    )
+}
+
+pub fn set_identity_weights(
+    admin: Pubkey,
+    collection: Pubkey,
+    attribute: String,
+    tiers: Vec<IdentityTier>,
+) -> Instruction {
+    build(
+        accounts::SetIdentityWeights {
+            identity_weights: pda::identity_weights().0,
+            config: pda::global_config().0,
+            admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetIdentityWeights { collection, attribute, tiers },
+    )
+}
+
+/// `None` restores the default early-vote curve for `kind`.
+pub fn set_early_vote_curve(admin: Pubkey, kind: ActionKind, curve: Option<EarlyVoteCurve>) -> Instruction {
+    build(
+        accounts::SetEarlyVoteCurve {
+            early_vote_curves: pda::early_vote_curves().0,
+            config: pda::global_config().0,
+            admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetEarlyVoteCurve { kind, curve },
+    )
+}
+
+/// Send to the base layer once the ConfigChange action's timelock expires;
+/// `payer` covers the receipt.
+pub fn apply_config_change(payer: Pubkey, action_id: u64, change: ConfigChange) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::ApplyConfigChange {
+            fast_action,
+            config: pda::global_config().0,
+            receipt: pda::config_change_receipt(&fast_action).0,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ApplyConfigChange { change },
+    )
+}
+
+/// Send to the base layer once the Election action has settled; `leader` is
+/// the candidate its winning option names.
+pub fn install_leader(payer: Pubkey, action_id: u64, hive: Pubkey, leader: Pubkey, election: Election) -> Instruction {
+    build(
+        accounts::InstallLeader {
+            fast_action: pda::fast_action(action_id).0,
+            leader_term: pda::leader_term(&hive).0,
+            agent_record: pda::agent_record(&leader).0,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::InstallLeader { election },
+    )
+}
+
+/// Send to the base layer once the TaskAssignment action has settled;
+/// `queue_accounts` are passed on to the task's queue program.
+pub fn push_task(payer: Pubkey, action_id: u64, task: TaskAssignment, queue_accounts: &[AccountMeta]) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    let mut ix = build(
+        accounts::PushTask {
+            fast_action,
+            receipt: pda::task_receipt(&fast_action).0,
+            queue: task.queue,
+            task_authority: pda::task_authority().0,
+            queue_program: task.queue_program,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::PushTask { task },
+    );
+    ix.accounts.extend(queue_accounts.iter().map(|meta| AccountMeta {
+        is_signer: false,
+        ..meta.clone()
+    }));
+    ix
+}
+
+/// Send to the base layer once the ProgramUpgrade action's timelock expires;
+/// `payer` covers the receipt and receives the buffer's lamports.
+pub fn execute_upgrade(payer: Pubkey, action_id: u64, upgrade: ProgramUpgrade) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::ExecuteUpgrade {
+            fast_action,
+            receipt: pda::upgrade_receipt(&fast_action).0,
+            target_program: upgrade.program,
+            program_data: pda::program_data_of(&upgrade.program).0,
+            buffer: upgrade.buffer,
+            upgrade_authority: pda::upgrade_authority().0,
+            payer,
+            rent: sysvar::rent::ID,
+            clock: sysvar::clock::ID,
+            bpf_loader: anchor_lang::solana_program::bpf_loader_upgradeable::ID,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ExecuteUpgrade { upgrade },
+    )
+}
+
+/// Send to the base layer once the Budget action has settled; `payer` covers
+/// the stream account.
+pub fn open_stream(payer: Pubkey, action_id: u64, budget: Budget) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::OpenStream {
+            fast_action,
+            stream: pda::stream(&fast_action).0,
+            slow_config: pda::slow_config().0,
+            slow_action: pda::slow_action(&fast_action).0,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::OpenStream { budget },
+    )
+}
+
+/// Create `hive`'s insurance pool; `authority` is the hive's and pays for it
+pub fn initialize_insurance_pool(authority: Pubkey, hive: Pubkey, fee_bps: u16) -> Instruction {
+    build(
+        accounts::InitializeInsurancePool {
+            hive_account: pda::hive(&hive).0,
+            insurance_pool: pda::insurance_pool(&hive).0,
+            authority,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::InitializeInsurancePool { fee_bps },
+    )
+}
+
+pub fn fund_insurance(funder: Pubkey, hive: Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::FundInsurance {
+            insurance_pool: pda::insurance_pool(&hive).0,
+            funder,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::FundInsurance { amount },
+    )
+}
+
+/// Send to the base layer; `fast_action` is the voided action's address and
+/// `hive` its hive
+pub fn file_claim(
+    claimant: Pubkey,
+    fast_action: Pubkey,
+    hive: Pubkey,
+    amount: u64,
+    evidence_hash: [u8; 32],
+) -> Instruction {
+    build(
+        accounts::FileClaim {
+            fast_action,
+            insurance_pool: pda::insurance_pool(&hive).0,
+            claim: pda::insurance_claim(&fast_action, &claimant).0,
+            claimant,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::FileClaim { amount, evidence_hash },
+    )
+}
+
+/// Send to the base layer once `resolution_action`, the Emergency action
+/// carrying the claim's resolution hash, has settled
+pub fn resolve_claim(claimant: Pubkey, fast_action: Pubkey, hive: Pubkey, resolution_action: Pubkey) -> Instruction {
+    build(
+        accounts::ResolveClaim {
+            claim: pda::insurance_claim(&fast_action, &claimant).0,
+            insurance_pool: pda::insurance_pool(&hive).0,
+            resolution_action,
+            claimant,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ResolveClaim {},
+    )
+}
+
+/// Base layer. `fast_action` is a passed Emergency action of `hive` whose
+/// action hash is `HiveFreeze::action_hash(&hive, frozen)`.
+pub fn set_hive_frozen(payer: Pubkey, fast_action: Pubkey, hive: Pubkey, frozen: bool) -> Instruction {
+    build(
+        accounts::SetHiveFrozen {
+            fast_action,
+            hive_freeze: pda::hive_freeze(&hive).0,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetHiveFrozen { frozen },
+    )
+}
+
+/// Accounts paying out a token stream
+pub struct StreamTokenAccounts {
+    pub mint: Pubkey,
+    /// Owned by the treasury PDA
+    pub treasury_token_account: Pubkey,
+    /// Owned by the stream's recipient
+    pub recipient_token_account: Pubkey,
+    pub token_program: Pubkey,
+}
+
+/// `tokens` is required for streams with a mint and ignored otherwise.
+pub fn withdraw_stream(action_id: u64, recipient: Pubkey, tokens: Option<StreamTokenAccounts>) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::WithdrawStream {
+            stream: pda::stream(&fast_action).0,
+            treasury: pda::treasury().0,
+            recipient,
+            mint: tokens.as_ref().map(|t| t.mint),
+            treasury_token_account: tokens.as_ref().map(|t| t.treasury_token_account),
+            recipient_token_account: tokens.as_ref().map(|t| t.recipient_token_account),
+            token_program: tokens.as_ref().map(|t| t.token_program),
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::WithdrawStream {},
+    )
+}
+
+/// `cancel_action_id` must carry `Stream::cancel_hash` of the stream opened
+/// by `action_id`.
+pub fn cancel_stream(action_id: u64, cancel_action_id: u64) -> Instruction {
+    build(
+        accounts::CancelStream {
+            stream: pda::stream(&pda::fast_action(action_id).0).0,
+            cancel_action: pda::fast_action(cancel_action_id).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::CancelStream {},
+    )
+}
+
+/// Accounts of an action's reward pool
+pub struct VoteRewardAccounts {
+    pub mint: Pubkey,
+    /// Owned by the pool PDA
+    pub vault: Pubkey,
+    pub token_program: Pubkey,
+}
+
+/// Send in the action's creation transaction, before any vote; `vault` must
+/// be a token account of `mint` owned by `pda::vote_reward_pool`.
+pub fn open_vote_rewards(
+    authority: Pubkey,
+    action_id: u64,
+    tokens: VoteRewardAccounts,
+    funder_token_account: Pubkey,
+    amount: u64,
+    claim_slots: u64,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::OpenVoteRewards {
+            fast_action,
+            pool: pda::vote_reward_pool(&fast_action).0,
+            mint: tokens.mint,
+            vault: tokens.vault,
+            funder_token_account,
+            token_program: tokens.token_program,
+            authority,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::OpenVoteRewards { amount, claim_slots },
+    )
+}
+
+/// `voter_token_account` must be owned by `voter`.
+pub fn claim_vote_reward(
+    payer: Pubkey,
+    action_id: u64,
+    voter: Pubkey,
+    tokens: VoteRewardAccounts,
+    voter_token_account: Pubkey,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    let fast_vote = pda::fast_vote(&fast_action, &voter).0;
+    build(
+        accounts::ClaimVoteReward {
+            fast_action,
+            fast_vote,
+            pool: pda::vote_reward_pool(&fast_action).0,
+            claim: pda::vote_reward_claim(&fast_vote).0,
+            mint: tokens.mint,
+            vault: tokens.vault,
+            voter_token_account,
+            token_program: tokens.token_program,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ClaimVoteReward {},
+    )
+}
+
+/// `treasury_token_account` must be owned by the treasury PDA.
+pub fn sweep_vote_rewards(action_id: u64, tokens: VoteRewardAccounts, treasury_token_account: Pubkey) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::SweepVoteRewards {
+            fast_action,
+            pool: pda::vote_reward_pool(&fast_action).0,
+            mint: tokens.mint,
+            vault: tokens.vault,
+            treasury: pda::treasury().0,
+            treasury_token_account,
+            token_program: tokens.token_program,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SweepVoteRewards {},
+    )
+}
+
+pub fn initialize_roles(hive: Pubkey, payer: Pubkey) -> Instruction {
+    build(
+        accounts::InitializeRoles {
+            roles: pda::roles(&hive).0,
+            hive,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::InitializeRoles {},
+    )
+}
+
+pub fn grant_role(admin: Pubkey, hive: Pubkey, member: Pubkey, role: Role) -> Instruction {
+    build(manage_roles(admin, hive), instruction::GrantRole { member, role })
+}
+
+pub fn revoke_role(admin: Pubkey, hive: Pubkey, member: Pubkey, role: Role) -> Instruction {
+    build(manage_roles(admin, hive), instruction::RevokeRole { member, role })
+}
+
+/// Start `member`'s inactivity clock; permissionless
+pub fn track_member_activity(payer: Pubkey, hive: Pubkey, member: Pubkey) -> Instruction {
+    build(
+        accounts::TrackMemberActivity {
+            roles: pda::roles(&hive).0,
+            member_activity: pda::member_activity(&hive, &member).0,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::TrackMemberActivity { member },
+    )
+}
+
+/// Refresh `member`'s activity, reactivating it if it was marked inactive
+pub fn member_heartbeat(member: Pubkey, hive: Pubkey) -> Instruction {
+    build(
+        accounts::MemberHeartbeat {
+            roles: pda::roles(&hive).0,
+            member_activity: pda::member_activity(&hive, &member).0,
+            member,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::MemberHeartbeat {},
+    )
+}
+
+/// Leave an idle `member` out of the electorate; permissionless
+pub fn mark_inactive(hive: Pubkey, member: Pubkey) -> Instruction {
+    build(
+        accounts::MarkInactive {
+            roles: pda::roles(&hive).0,
+            member_activity: pda::member_activity(&hive, &member).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::MarkInactive {},
+    )
+}
+
+pub fn set_result_attestors(admin: Pubkey, hive: Pubkey, validators: Vec<Pubkey>, threshold: u8) -> Instruction {
+    build(
+        accounts::SetResultAttestors {
+            result_attestors: pda::result_attestors(&hive).0,
+            roles: pda::roles(&hive).0,
+            admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetResultAttestors { validators, threshold },
+    )
+}
+
+pub fn set_cancellation_policy(admin: Pubkey, hive: Pubkey, policy: CancellationPolicy) -> Instruction {
+    build(
+        accounts::SetCancellationPolicy {
+            hive_config: pda::hive_config(&hive).0,
+            roles: pda::roles(&hive).0,
+            admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetCancellationPolicy { policy },
+    )
+}
+
+pub fn set_approval_rounding(admin: Pubkey, hive: Pubkey, rounding: ApprovalRounding) -> Instruction {
+    build(
+        accounts::SetApprovalRounding {
+            hive_config: pda::hive_config(&hive).0,
+            roles: pda::roles(&hive).0,
+            admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetApprovalRounding { rounding },
+    )
+}
+
+pub fn migrate_hive_config(payer: Pubkey, hive: Pubkey) -> Instruction {
+    build(
+        accounts::MigrateHiveConfig {
+            hive_config: pda::hive_config(&hive).0,
+            hive,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateHiveConfig {},
+    )
+}
+
+pub fn set_contest_margin(admin: Pubkey, hive: Pubkey, kind: ActionKind, margin: u8) -> Instruction {
+    build(
+        accounts::SetContestMargin {
+            category_config: pda::category_config(&hive).0,
+            roles: pda::roles(&hive).0,
+            admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetContestMargin { kind, margin },
+    )
+}
+
+/// Send to the base layer once the contested action's slow vote is decided
+pub fn ratify_contested(fast_action: Pubkey) -> Instruction {
+    build(
+        accounts::RatifyContested {
+            fast_action,
+            slow_action: pda::slow_action(&fast_action).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::RatifyContested {},
+    )
+}
+
+pub fn create_template(admin: Pubkey, hive: Pubkey, template_id: u16, params: TemplateParams) -> Instruction {
+    build(
+        accounts::CreateTemplate {
+            template: pda::template(&hive, template_id).0,
+            roles: pda::roles(&hive).0,
+            config: pda::global_config().0,
+            admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::CreateTemplate { template_id, params },
+    )
+}
+
+pub fn update_template(admin: Pubkey, hive: Pubkey, template_id: u16, params: TemplateParams) -> Instruction {
+    build(
+        accounts::UpdateTemplate {
+            template: pda::template(&hive, template_id).0,
+            roles: pda::roles(&hive).0,
+            config: pda::global_config().0,
+            admin,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::UpdateTemplate { params },
+    )
+}
+
+pub fn create_hive(hive: Pubkey, payer: Pubkey, name_hash: [u8; 32], authority: Pubkey) -> Instruction {
+    build(
+        accounts::CreateHive {
+            hive_account: pda::hive(&hive).0,
+            hive,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::CreateHive { name_hash, authority },
+    )
+}
+
+/// create_hive together with the hive's Roles, HiveConfig, CategoryConfig
+/// and insurance pool; `hive` signs and becomes the first Admin
+pub fn initialize_hive(hive: Pubkey, payer: Pubkey, init: HiveInit) -> Instruction {
+    build(
+        accounts::InitializeHive {
+            hive_account: pda::hive(&hive).0,
+            roles: pda::roles(&hive).0,
+            hive_config: pda::hive_config(&hive).0,
+            category_config: pda::category_config(&hive).0,
+            insurance_pool: pda::insurance_pool(&hive).0,
+            hive,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::InitializeHive { init },
+    )
+}
+
+pub fn update_hive(authority: Pubkey, hive: Pubkey, update: HiveUpdate) -> Instruction {
+    build(
+        accounts::UpdateHive {
+            hive_account: pda::hive(&hive).0,
+            authority,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::UpdateHive { update },
+    )
+}
+
+/// `authority` is the hive's authority and pays for the allowlist on first use
+pub fn set_creator_restriction(authority: Pubkey, hive: Pubkey, restricted: bool) -> Instruction {
+    build(
+        accounts::SetCreatorRestriction {
+            hive_account: pda::hive(&hive).0,
+            creator_allowlist: pda::creator_allowlist(&hive).0,
+            authority,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetCreatorRestriction { restricted },
+    )
+}
+
+pub fn allow_creator(authority: Pubkey, hive: Pubkey, creator: Pubkey) -> Instruction {
+    build(manage_creator_allowlist(authority, hive), instruction::AllowCreator { creator })
+}
+
+pub fn disallow_creator(authority: Pubkey, hive: Pubkey, creator: Pubkey) -> Instruction {
+    build(manage_creator_allowlist(authority, hive), instruction::DisallowCreator { creator })
+}
+
+fn manage_creator_allowlist(authority: Pubkey, hive: Pubkey) -> accounts::ManageCreatorAllowlist {
+    accounts::ManageCreatorAllowlist {
+        hive_account: pda::hive(&hive).0,
+        creator_allowlist: pda::creator_allowlist(&hive).0,
+        authority,
+        #[cfg(feature = "event-cpi")]
+        event_authority: pda::event_authority().0,
+        #[cfg(feature = "event-cpi")]
+        program: ID,
+    }
+}
+
+fn manage_roles(admin: Pubkey, hive: Pubkey) -> accounts::ManageRoles {
+    accounts::ManageRoles {
+        roles: pda::roles(&hive).0,
+        admin,
+        #[cfg(feature = "event-cpi")]
+        event_authority: pda::event_authority().0,
+        #[cfg(feature = "event-cpi")]
+        program: ID,
+    }
+}
+
+/// `payer` funds the rent and the `bond`.
+pub fn register_validator(validator: Pubkey, payer: Pubkey, bond: u64) -> Instruction {
+    build(
+        accounts::RegisterValidator {
+            validator_record: pda::validator_record(&validator).0,
+            validator,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::RegisterValidator { bond },
+    )
+}
+
+pub fn validator_heartbeat(validator: Pubkey) -> Instruction {
+    build(
+        accounts::ValidatorHeartbeat {
+            validator_record: pda::validator_record(&validator).0,
+            validator,
+        },
+        instruction::ValidatorHeartbeat {},
+    )
+}
+
+/// `admin` must be the config admin.
+pub fn set_validator_status(admin: Pubkey, validator: Pubkey, status: ValidatorStatus) -> Instruction {
+    build(
+        accounts::SetValidatorStatus {
+            validator_record: pda::validator_record(&validator).0,
+            config: pda::global_config().0,
+            admin,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetValidatorStatus { status },
+    )
+}
+
+/// Send to the base layer once the action is back there; `validator` is its
+/// FastAction::delegated_validator.
+pub fn release_validator(fast_action: Pubkey, validator: Pubkey) -> Instruction {
+    build(
+        accounts::ReleaseValidator {
+            fast_action,
+            validator_record: pda::validator_record(&validator).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ReleaseValidator {},
+    )
+}
+
+pub fn migrate_validator_record(payer: Pubkey, validator: Pubkey) -> Instruction {
+    build(
+        accounts::MigrateValidatorRecord {
+            validator_record: pda::validator_record(&validator).0,
+            validator,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateValidatorRecord {},
+    )
+}
+
+/// `admin` must be the config admin.
+pub fn set_creation_fee(admin: Pubkey, creation_fee: u64) -> Instruction {
+    build(
+        accounts::SetCreationFee {
+            reward_pool: pda::reward_pool().0,
+            config: pda::global_config().0,
+            admin,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SetCreationFee { creation_fee },
+    )
+}
+
+/// Credit `validator` for hosting a finalized action; must be sent to the base
+/// layer after the action is undelegated.
+pub fn record_session(action_id: u64, validator: Pubkey) -> Instruction {
+    build(
+        accounts::RecordSession {
+            fast_action: pda::fast_action(action_id).0,
+            validator_record: pda::validator_record(&validator).0,
+            reward_pool: pda::reward_pool().0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::RecordSession {},
+    )
+}
+
+pub fn claim_validator_rewards(validator: Pubkey) -> Instruction {
+    build(
+        accounts::ClaimValidatorRewards {
+            validator_record: pda::validator_record(&validator).0,
+            reward_pool: pda::reward_pool().0,
+            validator,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ClaimValidatorRewards {},
+    )
+}
+
+/// Report the validator that hosted `action_id` for a missed commit.
+pub fn report_missed_commit(reporter: Pubkey, action_id: u64) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::ReportMissedCommit {
+            fast_action,
+            slash_report: pda::slash_report(&fast_action).0,
+            reporter,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ReportMissedCommit {},
+    )
+}
+
+/// `admin` must be the config admin.
+pub fn void_slash(admin: Pubkey, action_id: u64) -> Instruction {
+    build(
+        accounts::VoidSlash {
+            slash_report: pda::slash_report(&pda::fast_action(action_id).0).0,
+            config: pda::global_config().0,
+            admin,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VoidSlash {},
+    )
+}
+
+/// `reporter` is the SlashReport's, and receives the whistleblower share.
+pub fn execute_slash(payer: Pubkey, action_id: u64, validator: Pubkey, reporter: Pubkey) -> Instruction {
+    build(
+        accounts::ExecuteSlash {
+            slash_report: pda::slash_report(&pda::fast_action(action_id).0).0,
+            validator_record: pda::validator_record(&validator).0,
+            treasury: pda::treasury().0,
+            config: pda::global_config().0,
+            reporter,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ExecuteSlash {},
+    )
+}
+
+/// Send after ed25519 program instructions verifying `agent`'s signatures
+/// over `first.message()` and `second.message()`, each carrying its own
+/// signature, key and message.
+pub fn report_equivocation(reporter: Pubkey, agent: Pubkey, first: Ballot, second: Ballot) -> Instruction {
+    build(
+        accounts::ReportEquivocation {
+            report: pda::equivocation_report(&first.action, &agent).0,
+            agent_record: pda::agent_record(&agent).0,
+            agent,
+            treasury: pda::treasury().0,
+            config: pda::global_config().0,
+            instructions: sysvar::instructions::ID,
+            reporter,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ReportEquivocation { first, second },
+    )
+}
+
+/// `vote_records` are the FastVote accounts backing `Evidence::Votes`, or
+/// the action's mirror for `Evidence::ConflictingCommit`; pass none for
+/// `Evidence::Counters`.
+pub fn challenge_result(
+    challenger: Pubkey,
+    action_id: u64,
+    validator: Option<Pubkey>,
+    evidence: Evidence,
+    vote_records: &[Pubkey],
+) -> Instruction {
+    let mut ix = build(
+        accounts::ChallengeResult {
+            fast_action: pda::fast_action(action_id).0,
+            validator_record: validator.map(|v| pda::validator_record(&v).0),
+            treasury: pda::treasury().0,
+            config: pda::global_config().0,
+            challenger,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ChallengeResult { evidence },
+    );
+    ix.accounts
+        .extend(vote_records.iter().map(|v| AccountMeta::new_readonly(*v, false)));
+    ix
+}
+
+/// Sent by the validator `fast_action` is pinned to, on the base layer;
+/// batches go in order from index 0, each after the one before it.
+pub fn post_sequence_commitment(
+    validator: Pubkey,
+    fast_action: Pubkey,
+    index: u32,
+    ballots: Vec<SequencedBallot>,
+) -> Instruction {
+    build(
+        accounts::PostSequenceCommitment {
+            sequence_batch: pda::sequence_batch(&fast_action, &validator, index).0,
+            previous: index.checked_sub(1).map(|i| pda::sequence_batch(&fast_action, &validator, i).0),
+            validator_record: pda::validator_record(&validator).0,
+            validator,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::PostSequenceCommitment {
+            action: fast_action,
+            index,
+            ballots,
+        },
+    )
+}
+
+/// `ballots` are all of batch `index` of the sequence posted by `validator`,
+/// the action's pinned validator; the one at `position` has no FastVote.
+pub fn challenge_exclusion(
+    challenger: Pubkey,
+    action_id: u64,
+    validator: Pubkey,
+    index: u32,
+    ballots: Vec<SequencedBallot>,
+    position: u8,
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    let voter = ballots.get(position as usize).map(|b| b.voter).unwrap_or_default();
+    build(
+        accounts::ChallengeExclusion {
+            fast_action,
+            sequence_batch: pda::sequence_batch(&fast_action, &validator, index).0,
+            fast_vote: pda::fast_vote(&fast_action, &voter).0,
+            compressed_ballots: pda::compressed_ballots(&fast_action).0,
+            validator_record: pda::validator_record(&validator).0,
+            treasury: pda::treasury().0,
+            config: pda::global_config().0,
+            challenger,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ChallengeExclusion {
+            index,
+            ballots,
+            position,
+        },
+    )
+}
+
+/// Send to the base layer once the action has settled, after ed25519 program
+/// instructions in which the hive's result attestors, and optionally the
+/// validator that hosted the action, sign the result's
+/// `attestation_digest`. `payer` covers the proof buffer until it closes it.
+pub fn export_result_proof(payer: Pubkey, action_id: u64, hive: Option<Pubkey>) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::ExportResultProof {
+            fast_action,
+            result_attestors: pda::result_attestors(&hive.unwrap_or_default()).0,
+            instructions: sysvar::instructions::ID,
+            result_proof: pda::result_proof(&fast_action, &payer).0,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ExportResultProof {},
+    )
+}
+
+pub fn close_result_proof(payer: Pubkey, fast_action: Pubkey) -> Instruction {
+    build(
+        accounts::CloseResultProof {
+            result_proof: pda::result_proof(&fast_action, &payer).0,
+            payer,
+        },
+        instruction::CloseResultProof {},
+    )
+}
+
+/// Send to the base layer once the action has settled; `payer` covers the
+/// treasury account if it does not exist yet.
+pub fn release_bond(payer: Pubkey, action_id: u64, creator: Pubkey) -> Instruction {
+    build(
+        accounts::ReleaseBond {
+            fast_action: pda::fast_action(action_id).0,
+            creator,
+            treasury: pda::treasury().0,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ReleaseBond {},
+    )
+}
+
+/// Send to the base layer once the action is tallied; pays `creator` its
+/// fee rebate from the reward pool
+pub fn claim_fee_rebate(action_id: u64, creator: Pubkey) -> Instruction {
+    build(
+        accounts::ClaimFeeRebate {
+            fast_action: pda::fast_action(action_id).0,
+            creator,
+            reward_pool: pda::reward_pool().0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ClaimFeeRebate {},
+    )
+}
+
+/// Send to the ephemeral rollup. `ranking` lists option indices, most
+/// preferred first.
+pub fn vote_ranked(
+    voter: Pubkey,
+    action_id: u64,
+    delegation_epoch: u32,
+    ranking: Vec<u8>,
+    voter_commitment: [u8; 32],
+) -> Instruction {
+    let fast_action = pda::fast_action(action_id).0;
+    build(
+        accounts::VoteRanked {
+            fast_action,
+            fast_vote: pda::fast_vote(&fast_action, &voter).0,
+            ranked_ballot: pda::ranked_ballot(&fast_action, &voter).0,
+            vote_credits: pda::vote_credits(&voter).0,
+            agent_record: pda::agent_record(&voter).0,
+            vote_key: pda::vote_key(&voter).0,
+            agent: voter,
+            voter,
+            config: pda::global_config().0,
+            system_program: system_program::ID,
+            metrics: Some(pda::metrics(MetricsLayer::Er).0),
+            hive_freeze: pda::hive_freeze(&Pubkey::default()).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VoteRanked {
+            _action_id: action_id,
+            ranking,
+            voter_commitment,
+            delegation_epoch,
+        },
+    )
+}
+
+/// Send to the ephemeral rollup. `ballots` are RankedBallot accounts to move
+/// off a pending elimination; pass none to eliminate the next option.
+pub fn tally_round(action_id: u64, ballots: &[Pubkey]) -> Instruction {
+    let mut ix = build(
+        accounts::TallyRound {
+            fast_action: pda::fast_action(action_id).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::TallyRound {},
+    );
+    ix.accounts
+        .extend(ballots.iter().map(|b| AccountMeta::new(*b, false)));
+    ix
+}
+
+/// Send to the base layer once the failed parent has settled. `action_id` is
+/// the counter's next ID, as for create_fast_action.
+pub fn spawn_runoff(payer: Pubkey, parent_action_id: u64, action_id: u64) -> Instruction {
+    build(
+        accounts::SpawnRunoff {
+            parent_action: pda::fast_action(parent_action_id).0,
+            action_counter: pda::action_counter().0,
+            runoff_action: pda::fast_action(action_id).0,
+            payer,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SpawnRunoff {},
+    )
+}
+
+/// spawn_runoff, with the runoff delegated in the same instruction.
+/// `validator` is the parent's FastAction::delegated_validator, if set.
+pub fn spawn_and_delegate(
+    payer: Pubkey,
+    parent_action_id: u64,
+    action_id: u64,
+    validator: Option<Pubkey>,
+) -> Instruction {
+    let runoff_action = pda::fast_action(action_id).0;
+    build(
+        accounts::SpawnAndDelegate {
+            parent_action: pda::fast_action(parent_action_id).0,
+            action_counter: pda::action_counter().0,
+            buffer_runoff_action: pda::delegate_buffer(&runoff_action).0,
+            delegation_record_runoff_action: pda::delegation_record(&runoff_action).0,
+            delegation_metadata_runoff_action: pda::delegation_metadata(&runoff_action).0,
+            runoff_action,
+            validator_record: validator.map(|v| pda::validator_record(&v).0),
+            config: pda::global_config().0,
+            payer,
+            owner_program: ID,
+            delegation_program: ephemeral_rollups_sdk::id(),
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SpawnAndDelegate {},
+    )
+}
+
+/// Send to the base layer. `hive` scopes the proposal; the creator must hold
+/// Role::Creator there.
+pub fn create_conviction_proposal(
+    creator: Pubkey,
+    action_hash: [u8; 32],
+    threshold: u64,
+    retention_ppb: u32,
+    hive: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::CreateConvictionProposal {
+            proposal: pda::conviction_proposal(&action_hash).0,
+            creator,
+            roles: hive.map(|h| pda::roles(&h).0),
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::CreateConvictionProposal {
+            action_hash,
+            threshold,
+            retention_ppb,
+        },
+    )
+}
+
+pub fn stake_conviction(staker: Pubkey, action_hash: &[u8; 32], amount: u64) -> Instruction {
+    let proposal = pda::conviction_proposal(action_hash).0;
+    build(
+        accounts::StakeConviction {
+            proposal,
+            stake: pda::conviction_stake(&proposal, &staker).0,
+            staker,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::StakeConviction { amount },
+    )
+}
+
+pub fn withdraw_conviction(staker: Pubkey, action_hash: &[u8; 32], amount: u64) -> Instruction {
+    let proposal = pda::conviction_proposal(action_hash).0;
+    build(
+        accounts::WithdrawConviction {
+            proposal,
+            stake: pda::conviction_stake(&proposal, &staker).0,
+            staker,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::WithdrawConviction { amount },
+    )
+}
+
+pub fn update_conviction(action_hash: &[u8; 32]) -> Instruction {
+    build(
+        accounts::UpdateConviction {
+            proposal: pda::conviction_proposal(action_hash).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::UpdateConviction {},
+    )
+}
+
+pub fn create_federated_action(
+    creator: Pubkey,
+    action_hash: [u8; 32],
+    threshold: u8,
+    children: Vec<FederatedChild>,
+) -> Instruction {
+    let child_actions: Vec<Pubkey> = children.iter().map(|c| c.action).collect();
+    let mut ix = build(
+        accounts::CreateFederatedAction {
+            federated_action: pda::federated_action(&action_hash).0,
+            creator,
+            system_program: system_program::ID,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::CreateFederatedAction {
+            action_hash,
+            threshold,
+            children,
+        },
+    );
+    ix.accounts.extend(child_actions.iter().map(|a| AccountMeta::new_readonly(*a, false)));
+    ix
+}
+
+/// `children` are the child action addresses, in the federation's order.
+pub fn settle_federated_action(action_hash: &[u8; 32], children: &[Pubkey]) -> Instruction {
+    let mut ix = build(
+        accounts::SettleFederatedAction {
+            federated_action: pda::federated_action(action_hash).0,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::SettleFederatedAction {},
+    );
+    ix.accounts.extend(children.iter().map(|a| AccountMeta::new_readonly(*a, false)));
+    ix
+}
+
+/// Send to the base layer once the action has passed and settled.
+pub fn clear_market_gate(action_id: u64, feed: Pubkey) -> Instruction {
+    build(
+        accounts::ClearMarketGate {
+            fast_action: pda::fast_action(action_id).0,
+            feed,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::ClearMarketGate {},
+    )
+}
+
+/// Send to the base layer, signed by the configured guardian.
+pub fn veto_action(guardian: Pubkey, action_id: u64, reason: String) -> Instruction {
+    build(
+        accounts::VetoAction {
+            fast_action: pda::fast_action(action_id).0,
+            config: pda::global_config().0,
+            guardian,
+            #[cfg(feature = "event-cpi")]
+            event_authority: pda::event_authority().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        },
+        instruction::VetoAction { reason },
+    )
+}
+
+pub fn migrate_config(payer: Pubkey) -> Instruction {
+    build(
+        accounts::MigrateConfig {
+            config: pda::global_config().0,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateConfig {},
+    )
+}
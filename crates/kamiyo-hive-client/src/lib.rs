@@ -0,0 +1,39 @@
+//! Rust client for the KAMIYO fast voting program.
+//!
+//! - [`pda`]: address derivation for every program account
+//! - [`instructions`]: one builder per instruction
+//! - [`state`]: account decoding
+//! - [`snapshot`]: weight snapshot roots and proofs
+//! - [`rpc`]: blocking and async wrappers (`rpc` feature, on by default)
+//! - `wasm`: JS bindings for browsers and WASM agents (`wasm` feature)
+//!
+//! Enable `event-cpi` when targeting a program built with that feature; it
+//! adds the event authority accounts to every emitting instruction.
+//!
+//! Without `rpc` the crate builds for `wasm32-unknown-unknown`; for a JS
+//! package, `wasm-pack build crates/kamiyo-hive-client --target web --
+//! --no-default-features --features wasm`.
+
+pub mod instructions;
+pub mod pda;
+pub mod snapshot;
+pub mod state;
+
+#[cfg(feature = "rpc")]
+mod error;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "rpc")]
+pub use error::{Error, Result};
+pub use kamiyo_fast_voting::{
+    ballot_commitment, ActionAmendment, ActionKind, ActionParams, ActionPassed, AdaptiveQuorum, AddressProof,
+    AgentClass, Ballot, BallotChoice, Budget, Callback, CancellationPolicy, ClassLimit, CompressedProof,
+    ConfigChange, ConfigUpdate, CurveShape, DelegationShare, EarlyVoteCurve, Election, Evidence, ExtensionPolicy,
+    FederatedChild, FeeRebate, HiveFreeze, HiveOverrides, HiveUpdate, IdentityTier, MarketGate, MetricsLayer,
+    PrivacyLevel, ProgramUpgrade, ProofSignature, ResultProof, Role, ScheduledAccount, SequencedBallot, Standing,
+    StandingsBoard, TallyPreview, TaskAssignment, TaskRecord, TemplateArgs, TemplateParams, ValidatorStatus,
+    WeightCap, ID,
+};
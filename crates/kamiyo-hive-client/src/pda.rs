@@ -0,0 +1,436 @@
+// Program-derived addresses, mirroring the seeds in the program's account
+// constraints
+
+use anchor_lang::prelude::Pubkey;
+use ephemeral_rollups_sdk::pda::{DELEGATE_BUFFER_TAG, DELEGATION_METADATA_TAG, DELEGATION_RECORD_TAG};
+use kamiyo_fast_voting::{
+    MetricsLayer, ACTION_COUNTER_SEED, ACTION_MIRROR_SEED, ACTION_RELAYERS_SEED,
+    ACTIVE_ACTIONS_SEED, ACTION_HASH_SEED, ACTION_METADATA_SEED, AGENT_SEED,
+    ANONYMOUS_BALLOTS_SEED, BALLOT_VERIFIER_SEED,
+    BUBBLEGUM_PROGRAM_ID, CALLBACK_REGISTRY_SEED, CATEGORY_CONFIG_SEED, COMPRESSED_BALLOTS_SEED, CONFIG_CHANGE_SEED, CONVICTION_SEED,
+    CONVICTION_STAKE_SEED, CREATOR_ALLOWLIST_SEED, CREATOR_STATE_SEED, EQUIVOCATION_SEED, EARLY_VOTE_CURVES_SEED, EPOCH_STATS_SEED,
+    EXECUTION_SCHEDULE_SEED, EXECUTION_STAGE_SEED, FAST_ACTION_SEED, FAST_VOTE_SEED, FEDERATED_ACTION_SEED,
+    FOREIGN_HIVE_SEED, GLOBAL_CONFIG_SEED, HIVE_CONFIG_SEED, HIVE_FREEZE_SEED, HIVE_SEED, ID, IDENTITY_CLAIM_SEED,
+    IDENTITY_WEIGHTS_SEED, INSURANCE_CLAIM_SEED, INSURANCE_POOL_SEED, LEADER_TERM_SEED,
+    LIGHT_ACCOUNT_COMPRESSION_PROGRAM_ID, LIGHT_CPI_AUTHORITY_SEED, LIGHT_SYSTEM_PROGRAM_ID, MEMBER_ACTIVITY_SEED,
+    METRICS_SEED, NULLIFIER_SEED, PROPOSAL_DRAFT_SEED, RANKED_BALLOT_SEED, RESULT_ATTESTORS_SEED,
+    RESULT_PROOF_SEED,
+    REWARD_POOL_SEED, PROGRAM_UPGRADE_SEED, RECEIPT_AUTHORITY_SEED, RECEIPT_TREE_SEED, ROLES_SEED,
+    SEALED_DESCRIPTION_SEED,
+    SELF_SUBMIT_SEED, SEQUENCE_BATCH_SEED, SLASH_REPORT_SEED, STAKE_LOCK_AUTHORITY_SEED, STAKE_LOCK_CLAIM_SEED,
+    STAKE_LOCK_SEED,
+    STANDINGS_BOARD_SEED, STREAM_SEED,
+    TAG_INDEX_SEED, TALLY_AUDIT_SEED, TASK_AUTHORITY_SEED, TASK_RECEIPT_SEED, TEMPLATE_SEED,
+    TOKEN_METADATA_PROGRAM_ID, TREASURY_SEED, UPGRADE_AUTHORITY_SEED, VALIDATOR_SEED, VOTE_CREDITS_SEED,
+    VOTE_DELEGATION_SEED, VOTE_KEY_SEED, VOTE_RECEIPT_SEED, VOTE_REWARD_CLAIM_SEED, VOTE_REWARD_POOL_SEED,
+    VOTER_GROUP_SEED,
+    WEIGHT_CAPS_SEED, WEIGHT_SNAPSHOT_SEED, WEIGHT_STRATEGY_SEED,
+};
+use kamiyo_slow_voting::{SLOW_ACTION_SEED, SLOW_CONFIG_SEED};
+
+pub fn fast_action(action_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FAST_ACTION_SEED, &action_id.to_le_bytes()], &ID)
+}
+
+/// Address of an action created through `hive`'s Hive account
+pub fn hive_action(hive: &Pubkey, action_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FAST_ACTION_SEED, hive.as_ref(), &action_id.to_le_bytes()], &ID)
+}
+
+pub fn fast_vote(fast_action: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FAST_VOTE_SEED, fast_action.as_ref(), voter.as_ref()], &ID)
+}
+
+pub fn action_metadata(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ACTION_METADATA_SEED, fast_action.as_ref()], &ID)
+}
+
+pub fn sealed_description(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SEALED_DESCRIPTION_SEED, fast_action.as_ref()], &ID)
+}
+
+pub fn callback_registry(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CALLBACK_REGISTRY_SEED, fast_action.as_ref()], &ID)
+}
+
+pub fn execution_schedule(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EXECUTION_SCHEDULE_SEED, fast_action.as_ref()], &ID)
+}
+
+pub fn execution_stage(fast_action: &Pubkey, executor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EXECUTION_STAGE_SEED, fast_action.as_ref(), executor.as_ref()], &ID)
+}
+
+pub fn ranked_ballot(fast_action: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RANKED_BALLOT_SEED, fast_action.as_ref(), voter.as_ref()], &ID)
+}
+
+pub fn tally_audit(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TALLY_AUDIT_SEED, fast_action.as_ref()], &ID)
+}
+
+pub fn action_counter() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ACTION_COUNTER_SEED], &ID)
+}
+
+pub fn creator_state(creator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CREATOR_STATE_SEED, creator.as_ref()], &ID)
+}
+
+pub fn action_hash_record(action_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ACTION_HASH_SEED, action_hash.as_ref()], &ID)
+}
+
+pub fn global_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GLOBAL_CONFIG_SEED], &ID)
+}
+
+pub fn config_change_receipt(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_CHANGE_SEED, fast_action.as_ref()], &ID)
+}
+
+pub fn stream(budget_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STREAM_SEED, budget_action.as_ref()], &ID)
+}
+
+/// The slow voting program's config
+pub fn slow_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SLOW_CONFIG_SEED], &kamiyo_slow_voting::ID)
+}
+
+/// `fast_action`'s promotion to a slow vote, as a large Budget or a
+/// contested result
+pub fn slow_action(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SLOW_ACTION_SEED, fast_action.as_ref()], &kamiyo_slow_voting::ID)
+}
+
+/// Set as the upgrade authority of programs governed by ProgramUpgrade actions
+pub fn upgrade_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[UPGRADE_AUTHORITY_SEED], &ID)
+}
+
+pub fn upgrade_receipt(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROGRAM_UPGRADE_SEED, fast_action.as_ref()], &ID)
+}
+
+pub fn reward_pool() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REWARD_POOL_SEED], &ID)
+}
+
+pub fn treasury() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SEED], &ID)
+}
+
+pub fn slash_report(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SLASH_REPORT_SEED, fast_action.as_ref()], &ID)
+}
+
+pub fn equivocation_report(fast_action: &Pubkey, agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EQUIVOCATION_SEED, fast_action.as_ref(), agent.as_ref()], &ID)
+}
+
+pub fn federated_action(action_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FEDERATED_ACTION_SEED, action_hash.as_ref()], &ID)
+}
+
+pub fn conviction_proposal(action_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONVICTION_SEED, action_hash.as_ref()], &ID)
+}
+
+pub fn conviction_stake(proposal: &Pubkey, staker: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONVICTION_STAKE_SEED, proposal.as_ref(), staker.as_ref()], &ID)
+}
+
+pub fn roles(hive: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ROLES_SEED, hive.as_ref()], &ID)
+}
+
+/// The EVM hive contract `emitter`, on Wormhole chain `chain`, as
+/// registered with `hive`
+pub fn foreign_hive(hive: &Pubkey, chain: u16, emitter: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FOREIGN_HIVE_SEED, hive.as_ref(), &chain.to_le_bytes(), emitter.as_ref()], &ID)
+}
+
+/// `member`'s activity marker in `hive`
+pub fn member_activity(hive: &Pubkey, member: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MEMBER_ACTIVITY_SEED, hive.as_ref(), member.as_ref()], &ID)
+}
+
+pub fn proposal_draft(creator: &Pubkey, action_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROPOSAL_DRAFT_SEED, creator.as_ref(), action_hash], &ID)
+}
+
+pub fn hive(hive: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[HIVE_SEED, hive.as_ref()], &ID)
+}
+
+pub fn leader_term(hive: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LEADER_TERM_SEED, hive.as_ref()], &ID)
+}
+
+pub fn task_receipt(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TASK_RECEIPT_SEED, fast_action.as_ref()], &ID)
+}
+
+pub fn task_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TASK_AUTHORITY_SEED], &ID)
+}
+
+pub fn hive_config(hive: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[HIVE_CONFIG_SEED, hive.as_ref()], &ID)
+}
+
+pub fn category_config(hive: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CATEGORY_CONFIG_SEED, hive.as_ref()], &ID)
+}
+
+pub fn creator_allowlist(hive: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CREATOR_ALLOWLIST_SEED, hive.as_ref()], &ID)
+}
+
+/// Validators co-signing `hive`'s results; default `hive` for unscoped
+/// actions, which never have any
+pub fn result_attestors(hive: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RESULT_ATTESTORS_SEED, hive.as_ref()], &ID)
+}
+
+pub fn template(hive: &Pubkey, template_id: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TEMPLATE_SEED, hive.as_ref(), &template_id.to_le_bytes()], &ID)
+}
+
+pub fn agent_record(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AGENT_SEED, agent.as_ref()], &ID)
+}
+
+pub fn vote_credits(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VOTE_CREDITS_SEED, agent.as_ref()], &ID)
+}
+
+/// The VoteKey binding `key` to an agent, if it exists
+pub fn vote_key(key: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VOTE_KEY_SEED, key.as_ref()], &ID)
+}
+
+/// The VoterGroup registered for the threshold key `group_key`
+pub fn voter_group(group_key: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VOTER_GROUP_SEED, group_key.as_ref()], &ID)
+}
+
+/// Relayers pinned to `fast_action`, if any
+pub fn action_relayers(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ACTION_RELAYERS_SEED, fast_action.as_ref()], &ID)
+}
+
+/// `voter`'s request to submit its own ballot on `fast_action`
+pub fn self_submit(fast_action: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SELF_SUBMIT_SEED, fast_action.as_ref(), voter.as_ref()], &ID)
+}
+
+pub fn vote_delegation(delegator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VOTE_DELEGATION_SEED, delegator.as_ref()], &ID)
+}
+
+pub fn weight_caps() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WEIGHT_CAPS_SEED], &ID)
+}
+
+pub fn identity_weights() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[IDENTITY_WEIGHTS_SEED], &ID)
+}
+
+/// Marks `asset` as having voted on `fast_action`
+pub fn identity_claim(fast_action: &Pubkey, asset: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[IDENTITY_CLAIM_SEED, fast_action.as_ref(), asset.as_ref()], &ID)
+}
+
+/// ActiveActions index of actions created through `hive`'s Hive account, or
+/// of unscoped actions for None
+pub fn active_actions(hive: Option<&Pubkey>) -> (Pubkey, u8) {
+    let hive_seed: &[u8] = hive.map_or(&[], |h| h.as_ref());
+    Pubkey::find_program_address(&[ACTIVE_ACTIONS_SEED, hive_seed], &ID)
+}
+
+/// Page `page` of `tag`'s TagIndex for actions created through `hive`'s Hive
+/// account, or for unscoped actions with None
+pub fn tag_index(hive: Option<&Pubkey>, tag: &[u8; 8], page: u32) -> (Pubkey, u8) {
+    let hive_seed: &[u8] = hive.map_or(&[], |h| h.as_ref());
+    Pubkey::find_program_address(&[TAG_INDEX_SEED, hive_seed, tag, &page.to_le_bytes()], &ID)
+}
+
+/// Statistics of `hive`'s actions finalized in `epoch`; default `hive` for
+/// unscoped actions
+pub fn epoch_stats(hive: &Pubkey, epoch: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EPOCH_STATS_SEED, hive.as_ref(), &epoch.to_le_bytes()], &ID)
+}
+
+/// `hive`'s insurance pool
+pub fn insurance_pool(hive: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[INSURANCE_POOL_SEED, hive.as_ref()], &ID)
+}
+
+pub fn hive_freeze(hive: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[HIVE_FREEZE_SEED, hive.as_ref()], &ID)
+}
+
+/// `claimant`'s claim for damage from the voided `fast_action`
+pub fn insurance_claim(fast_action: &Pubkey, claimant: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[INSURANCE_CLAIM_SEED, fast_action.as_ref(), claimant.as_ref()], &ID)
+}
+
+/// The voter's stake lock, counted across the actions holding it
+pub fn stake_lock(voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STAKE_LOCK_SEED, voter.as_ref()], &ID)
+}
+
+/// Marks `voter`'s ballot on `fast_action` as holding a share of its lock
+pub fn stake_lock_claim(fast_action: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STAKE_LOCK_CLAIM_SEED, fast_action.as_ref(), voter.as_ref()], &ID)
+}
+
+/// Signs the stake lock program's lock_stake and release_stake
+pub fn stake_lock_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STAKE_LOCK_AUTHORITY_SEED], &ID)
+}
+
+pub fn early_vote_curves() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EARLY_VOTE_CURVES_SEED], &ID)
+}
+
+/// `layer`'s Metrics account; each layer has its own
+pub fn metrics(layer: MetricsLayer) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[METRICS_SEED, &[layer as u8]], &ID)
+}
+
+/// `hive`'s own Metrics account on `layer`, counting only its actions
+pub fn hive_metrics(layer: MetricsLayer, hive: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[METRICS_SEED, &[layer as u8], hive.as_ref()], &ID)
+}
+
+/// `hive`'s standings board, on the ephemeral rollup
+pub fn standings_board(hive: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STANDINGS_BOARD_SEED, hive.as_ref()], &ID)
+}
+
+/// Batch `index` of `validator`'s ballot sequence for `fast_action`
+pub fn sequence_batch(fast_action: &Pubkey, validator: &Pubkey, index: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SEQUENCE_BATCH_SEED, fast_action.as_ref(), validator.as_ref(), &index.to_le_bytes()],
+        &ID,
+    )
+}
+
+/// `payer`'s exported result proof for `fast_action`
+pub fn result_proof(fast_action: &Pubkey, payer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RESULT_PROOF_SEED, fast_action.as_ref(), payer.as_ref()], &ID)
+}
+
+pub fn validator_record(validator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VALIDATOR_SEED, validator.as_ref()], &ID)
+}
+
+/// ProgramData account of the upgradeable loader, needed by initialize_config
+pub fn program_data() -> (Pubkey, u8) {
+    program_data_of(&ID)
+}
+
+/// ProgramData account of any upgradeable program
+pub fn program_data_of(program: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[program.as_ref()], &anchor_lang::solana_program::bpf_loader_upgradeable::ID)
+}
+
+pub fn receipt_tree() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECEIPT_TREE_SEED], &ID)
+}
+
+pub fn vote_receipt(fast_vote: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VOTE_RECEIPT_SEED, fast_vote.as_ref()], &ID)
+}
+
+pub fn receipt_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECEIPT_AUTHORITY_SEED], &ID)
+}
+
+pub fn weight_snapshot(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WEIGHT_SNAPSHOT_SEED, fast_action.as_ref()], &ID)
+}
+
+pub fn weight_strategy(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WEIGHT_STRATEGY_SEED, fast_action.as_ref()], &ID)
+}
+
+pub fn anonymous_ballots(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ANONYMOUS_BALLOTS_SEED, fast_action.as_ref()], &ID)
+}
+
+/// The admin's record of `verifier` as a ballot verifier
+pub fn ballot_verifier(verifier: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BALLOT_VERIFIER_SEED, verifier.as_ref()], &ID)
+}
+
+/// Stand-in for `fast_action` on its backup validator
+pub fn action_mirror(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ACTION_MIRROR_SEED, fast_action.as_ref()], &ID)
+}
+
+/// Spent `nullifier` of an anonymous ballot on `fast_action`
+pub fn nullifier(fast_action: &Pubkey, nullifier: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[NULLIFIER_SEED, fast_action.as_ref(), nullifier], &ID)
+}
+
+pub fn vote_reward_pool(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VOTE_REWARD_POOL_SEED, fast_action.as_ref()], &ID)
+}
+
+pub fn vote_reward_claim(fast_vote: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VOTE_REWARD_CLAIM_SEED, fast_vote.as_ref()], &ID)
+}
+
+pub fn compressed_ballots(fast_action: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[COMPRESSED_BALLOTS_SEED, fast_action.as_ref()], &ID)
+}
+
+/// Signs this program's Light invoke_cpi calls
+pub fn light_cpi_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LIGHT_CPI_AUTHORITY_SEED], &ID)
+}
+
+/// This program's registration with Light account compression
+pub fn light_registered_program() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ID.as_ref()], &LIGHT_ACCOUNT_COMPRESSION_PROGRAM_ID)
+}
+
+/// The Light system program's authority over account compression
+pub fn light_account_compression_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LIGHT_CPI_AUTHORITY_SEED], &LIGHT_SYSTEM_PROGRAM_ID)
+}
+
+/// Signer for self-CPI event emission (`event-cpi` feature)
+pub fn event_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"__event_authority"], &ID)
+}
+
+/// Delegation buffer, owned by this program
+pub fn delegate_buffer(delegated: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DELEGATE_BUFFER_TAG, delegated.as_ref()], &ID)
+}
+
+pub fn delegation_record(delegated: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DELEGATION_RECORD_TAG, delegated.as_ref()], &ephemeral_rollups_sdk::id())
+}
+
+pub fn delegation_metadata(delegated: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DELEGATION_METADATA_TAG, delegated.as_ref()], &ephemeral_rollups_sdk::id())
+}
+
+/// Token Metadata's Metadata account for `mint`
+pub fn token_metadata(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+}
+
+/// Bubblegum's TreeConfig for `merkle_tree`
+pub fn tree_config(merkle_tree: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[merkle_tree.as_ref()], &BUBBLEGUM_PROGRAM_ID)
+}
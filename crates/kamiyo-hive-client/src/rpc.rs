@@ -0,0 +1,340 @@
+// Thin RPC wrappers. Point the client at the base layer for create, delegate
+// and cancel, and at the ephemeral rollup for votes and tally.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_address_lookup_table_interface::state::AddressLookupTable;
+use solana_rpc_client_api::config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_rpc_client_api::filter::{Memcmp, RpcFilterType};
+use solana_sdk::account::Account;
+use solana_sdk::message::{v0, AddressLookupTableAccount, VersionedMessage};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::pda;
+use crate::state::{decode, ActionCounter, ExecutionSchedule, FastVote, RankedBallot, TagIndex};
+use crate::{Error, Result};
+
+fn decode_account<T: AccountDeserialize>(address: &Pubkey, account: Option<Account>) -> Result<T> {
+    let account = account.ok_or(Error::AccountNotFound(*address))?;
+    Ok(decode(&account.data)?)
+}
+
+fn decode_lookup_table(address: &Pubkey, account: Option<Account>) -> Result<AddressLookupTableAccount> {
+    let account = account.ok_or(Error::AccountNotFound(*address))?;
+    let table = AddressLookupTable::deserialize(&account.data).map_err(|_| Error::InvalidLookupTable(*address))?;
+    Ok(AddressLookupTableAccount {
+        key: *address,
+        addresses: table.addresses.to_vec(),
+    })
+}
+
+/// v0 transaction drawing accounts from `lookup_tables`, e.g. a batch of
+/// vote_compact ballots
+fn versioned_transaction(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    signers: &[&dyn Signer],
+    lookup_tables: &[AddressLookupTableAccount],
+    blockhash: Hash,
+) -> Result<VersionedTransaction> {
+    let message = v0::Message::try_compile(payer, instructions, lookup_tables, blockhash)?;
+    Ok(VersionedTransaction::try_new(VersionedMessage::V0(message), signers)?)
+}
+
+fn next_id(account: Option<Account>) -> Result<u64> {
+    match account {
+        Some(account) => Ok(decode::<ActionCounter>(&account.data)?.next_id),
+        // Counter is created by the first create_fast_action
+        None => Ok(0),
+    }
+}
+
+/// Per-voter accounts of one action (FastVote, RankedBallot): matched on size
+/// and the action key right after the discriminator
+fn by_action_config(len: usize, fast_action: &Pubkey) -> RpcProgramAccountsConfig {
+    RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(len as u64),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(8, fast_action.as_ref())),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Every ExecutionSchedule, for keepers: matched on size and discriminator
+fn schedules_config() -> RpcProgramAccountsConfig {
+    RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(ExecutionSchedule::LEN as u64),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, ExecutionSchedule::DISCRIMINATOR)),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn decode_all<T: AccountDeserialize>(accounts: Vec<(Pubkey, Account)>) -> Result<Vec<(Pubkey, T)>> {
+    accounts
+        .into_iter()
+        .map(|(address, account)| Ok((address, decode(&account.data)?)))
+        .collect()
+}
+
+pub mod blocking {
+    use solana_rpc_client::rpc_client::RpcClient;
+    use solana_sdk::signature::Signature;
+    use solana_sdk::transaction::Transaction;
+
+    use super::*;
+    use crate::state::{FastAction, GlobalConfig, Roles};
+
+    pub struct Client {
+        pub rpc: RpcClient,
+    }
+
+    impl Client {
+        pub fn new(url: impl ToString) -> Self {
+            Self {
+                rpc: RpcClient::new(url.to_string()),
+            }
+        }
+
+        pub fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+            let account = self
+                .rpc
+                .get_account_with_commitment(address, self.rpc.commitment())?
+                .value;
+            decode_account(address, account)
+        }
+
+        pub fn fast_action(&self, action_id: u64) -> Result<FastAction> {
+            self.account(&pda::fast_action(action_id).0)
+        }
+
+        pub fn fast_vote(&self, action_id: u64, voter: &Pubkey) -> Result<FastVote> {
+            self.account(&pda::fast_vote(&pda::fast_action(action_id).0, voter).0)
+        }
+
+        pub fn config(&self) -> Result<GlobalConfig> {
+            self.account(&pda::global_config().0)
+        }
+
+        pub fn roles(&self, hive: &Pubkey) -> Result<Roles> {
+            self.account(&pda::roles(hive).0)
+        }
+
+        pub fn fast_votes(&self, action_id: u64) -> Result<Vec<(Pubkey, FastVote)>> {
+            let config = by_action_config(FastVote::LEN, &pda::fast_action(action_id).0);
+            decode_all(self.rpc.get_program_accounts_with_config(&crate::ID, config)?)
+        }
+
+        pub fn ranked_ballots(&self, action_id: u64) -> Result<Vec<(Pubkey, RankedBallot)>> {
+            let config = by_action_config(RankedBallot::LEN, &pda::fast_action(action_id).0);
+            decode_all(self.rpc.get_program_accounts_with_config(&crate::ID, config)?)
+        }
+
+        pub fn execution_schedules(&self) -> Result<Vec<(Pubkey, ExecutionSchedule)>> {
+            decode_all(self.rpc.get_program_accounts_with_config(&crate::ID, schedules_config())?)
+        }
+
+        /// Every page of `tag`'s index for `hive`, or for unscoped actions
+        /// with None, from page 0 until one is missing
+        pub fn tag_pages(&self, hive: Option<&Pubkey>, tag: &[u8; 8]) -> Result<Vec<TagIndex>> {
+            let mut pages = Vec::new();
+            loop {
+                let address = pda::tag_index(hive, tag, pages.len() as u32).0;
+                let account = self
+                    .rpc
+                    .get_account_with_commitment(&address, self.rpc.commitment())?
+                    .value;
+                let Some(account) = account else {
+                    return Ok(pages);
+                };
+                pages.push(decode(&account.data)?);
+            }
+        }
+
+        /// ID the next create_fast_action will be assigned
+        pub fn next_action_id(&self) -> Result<u64> {
+            let account = self
+                .rpc
+                .get_account_with_commitment(&pda::action_counter().0, self.rpc.commitment())?
+                .value;
+            next_id(account)
+        }
+
+        pub fn send(&self, instructions: &[Instruction], payer: &Pubkey, signers: &[&dyn Signer]) -> Result<Signature> {
+            let blockhash = self.rpc.get_latest_blockhash()?;
+            let tx = Transaction::new_signed_with_payer(instructions, Some(payer), signers, blockhash);
+            Ok(self.rpc.send_and_confirm_transaction(&tx)?)
+        }
+
+        pub fn lookup_table(&self, address: &Pubkey) -> Result<AddressLookupTableAccount> {
+            let account = self
+                .rpc
+                .get_account_with_commitment(address, self.rpc.commitment())?
+                .value;
+            decode_lookup_table(address, account)
+        }
+
+        /// `send` as a v0 transaction whose accounts may come from
+        /// `lookup_tables`
+        pub fn send_with_lookup_tables(
+            &self,
+            instructions: &[Instruction],
+            payer: &Pubkey,
+            signers: &[&dyn Signer],
+            lookup_tables: &[AddressLookupTableAccount],
+        ) -> Result<Signature> {
+            let blockhash = self.rpc.get_latest_blockhash()?;
+            let tx = versioned_transaction(instructions, payer, signers, lookup_tables, blockhash)?;
+            Ok(self.rpc.send_and_confirm_transaction(&tx)?)
+        }
+    }
+}
+
+pub mod nonblocking {
+    use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    use solana_sdk::signature::Signature;
+    use solana_sdk::transaction::Transaction;
+
+    use super::*;
+    use crate::state::{FastAction, GlobalConfig, Roles};
+
+    pub struct Client {
+        pub rpc: RpcClient,
+    }
+
+    impl Client {
+        pub fn new(url: impl ToString) -> Self {
+            Self {
+                rpc: RpcClient::new(url.to_string()),
+            }
+        }
+
+        pub async fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+            let account = self
+                .rpc
+                .get_account_with_commitment(address, self.rpc.commitment())
+                .await?
+                .value;
+            decode_account(address, account)
+        }
+
+        pub async fn fast_action(&self, action_id: u64) -> Result<FastAction> {
+            self.account(&pda::fast_action(action_id).0).await
+        }
+
+        pub async fn fast_vote(&self, action_id: u64, voter: &Pubkey) -> Result<FastVote> {
+            self.account(&pda::fast_vote(&pda::fast_action(action_id).0, voter).0)
+                .await
+        }
+
+        pub async fn config(&self) -> Result<GlobalConfig> {
+            self.account(&pda::global_config().0).await
+        }
+
+        pub async fn roles(&self, hive: &Pubkey) -> Result<Roles> {
+            self.account(&pda::roles(hive).0).await
+        }
+
+        pub async fn fast_votes(&self, action_id: u64) -> Result<Vec<(Pubkey, FastVote)>> {
+            let config = by_action_config(FastVote::LEN, &pda::fast_action(action_id).0);
+            decode_all(
+                self.rpc
+                    .get_program_accounts_with_config(&crate::ID, config)
+                    .await?,
+            )
+        }
+
+        pub async fn ranked_ballots(&self, action_id: u64) -> Result<Vec<(Pubkey, RankedBallot)>> {
+            let config = by_action_config(RankedBallot::LEN, &pda::fast_action(action_id).0);
+            decode_all(
+                self.rpc
+                    .get_program_accounts_with_config(&crate::ID, config)
+                    .await?,
+            )
+        }
+
+        pub async fn execution_schedules(&self) -> Result<Vec<(Pubkey, ExecutionSchedule)>> {
+            decode_all(
+                self.rpc
+                    .get_program_accounts_with_config(&crate::ID, schedules_config())
+                    .await?,
+            )
+        }
+
+        /// Every page of `tag`'s index for `hive`, or for unscoped actions
+        /// with None, from page 0 until one is missing
+        pub async fn tag_pages(&self, hive: Option<&Pubkey>, tag: &[u8; 8]) -> Result<Vec<TagIndex>> {
+            let mut pages = Vec::new();
+            loop {
+                let address = pda::tag_index(hive, tag, pages.len() as u32).0;
+                let account = self
+                    .rpc
+                    .get_account_with_commitment(&address, self.rpc.commitment())
+                    .await?
+                    .value;
+                let Some(account) = account else {
+                    return Ok(pages);
+                };
+                pages.push(decode(&account.data)?);
+            }
+        }
+
+        /// ID the next create_fast_action will be assigned
+        pub async fn next_action_id(&self) -> Result<u64> {
+            let account = self
+                .rpc
+                .get_account_with_commitment(&pda::action_counter().0, self.rpc.commitment())
+                .await?
+                .value;
+            next_id(account)
+        }
+
+        pub async fn send(
+            &self,
+            instructions: &[Instruction],
+            payer: &Pubkey,
+            signers: &[&dyn Signer],
+        ) -> Result<Signature> {
+            let blockhash = self.rpc.get_latest_blockhash().await?;
+            let tx = Transaction::new_signed_with_payer(instructions, Some(payer), signers, blockhash);
+            Ok(self.rpc.send_and_confirm_transaction(&tx).await?)
+        }
+
+        pub async fn lookup_table(&self, address: &Pubkey) -> Result<AddressLookupTableAccount> {
+            let account = self
+                .rpc
+                .get_account_with_commitment(address, self.rpc.commitment())
+                .await?
+                .value;
+            decode_lookup_table(address, account)
+        }
+
+        /// `send` as a v0 transaction whose accounts may come from
+        /// `lookup_tables`
+        pub async fn send_with_lookup_tables(
+            &self,
+            instructions: &[Instruction],
+            payer: &Pubkey,
+            signers: &[&dyn Signer],
+            lookup_tables: &[AddressLookupTableAccount],
+        ) -> Result<Signature> {
+            let blockhash = self.rpc.get_latest_blockhash().await?;
+            let tx = versioned_transaction(instructions, payer, signers, lookup_tables, blockhash)?;
+            Ok(self.rpc.send_and_confirm_transaction(&tx).await?)
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Weight snapshot trees: the root a snapshotter posts with
+//! `post_weight_snapshot` and the proofs voters pass to `vote_fast_snapshot`.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hashv;
+use kamiyo_fast_voting::weight_leaf;
+
+pub struct WeightTree {
+    weights: Vec<(Pubkey, u64)>,
+    /// Leaves first, root last; an odd node out moves up unhashed
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+fn parent(pair: &[[u8; 32]]) -> [u8; 32] {
+    match pair {
+        [a, b] if a <= b => hashv(&[a, b]).to_bytes(),
+        [a, b] => hashv(&[b, a]).to_bytes(),
+        [a] => *a,
+        _ => [0; 32],
+    }
+}
+
+impl WeightTree {
+    /// One leaf per (voter, weight); fold delegated weight into the
+    /// delegate's entry first.
+    pub fn new(weights: &[(Pubkey, u64)]) -> Self {
+        let mut level: Vec<[u8; 32]> = weights.iter().map(|(voter, weight)| weight_leaf(voter, *weight)).collect();
+        let mut levels = Vec::new();
+        while level.len() > 1 {
+            let next = level.chunks(2).map(parent).collect();
+            levels.push(std::mem::replace(&mut level, next));
+        }
+        levels.push(level);
+        Self {
+            weights: weights.to_vec(),
+            levels,
+        }
+    }
+
+    /// Zero for an empty tree, which post_weight_snapshot rejects
+    pub fn root(&self) -> [u8; 32] {
+        self.levels.last().and_then(|level| level.first()).copied().unwrap_or_default()
+    }
+
+    /// Sum of the leaves' weights, posted with the root as the total a
+    /// weight quorum is measured against
+    pub fn total_weight(&self) -> u64 {
+        self.weights.iter().fold(0u64, |total, (_, weight)| total.saturating_add(*weight))
+    }
+
+    /// `voter`'s weight and proof, if it has a leaf
+    pub fn proof(&self, voter: &Pubkey) -> Option<(u64, Vec<[u8; 32]>)> {
+        let leaf = self.weights.iter().position(|(v, _)| v == voter)?;
+        let mut index = leaf;
+        let mut proof = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        Some((self.weights[leaf].1, proof))
+    }
+}
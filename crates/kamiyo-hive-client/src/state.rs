@@ -0,0 +1,20 @@
+// Account decoding. Checks the Anchor discriminator, so passing the wrong
+// account type fails rather than misreading bytes.
+
+use anchor_lang::AccountDeserialize;
+
+pub use kamiyo_fast_voting::{
+    ActionCounter, ActionHashRecord, ActionLog, ActionMetadata, AgentRecord, CallbackRegistry,
+    CompressedBallots, ConfigChangeReceipt, ConvictionProposal, ConvictionStake, CreatorAllowlist, CreatorState,
+    EarlyVoteCurves, EpochStats, EquivocationReport, ExecutionSchedule, ExecutionStage, FastAction, FastVote,
+    FederatedAction, ForeignHive, GlobalConfig, Hive, InsuranceClaim, InsurancePool, LeaderTerm, LogEntry, LogTag,
+    MemberActivity, Metrics, ProposalDraft, RankedBallot, ReceiptTree, ResultAttestors, RewardPool, Roles,
+    SealedDescription, SlashReport, SlashStatus, StakeLock, StakeLockClaim, Stream, TagIndex, TallyAudit, TaskReceipt,
+    Template, Treasury, UpgradeReceipt, ValidatorRecord, ValidatorStatus, VoteCredits, VoteDelegation,
+    VoteReceipt, VoteRewardClaim, VoteRewardPool, VoteMode, VoteResult, VoterGroup, WeightCaps,
+    WeightSnapshot, WeightStrategy,
+};
+
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> anchor_lang::Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
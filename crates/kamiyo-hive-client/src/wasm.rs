@@ -0,0 +1,262 @@
+// JS bindings for browser and WASM agents (`wasm` feature). Nothing here
+// needs RPC: derive addresses, build ballots and decode account data the
+// page fetched itself. Pubkeys cross as base58 strings, u64s as BigInt and
+// hashes as Uint8Array; instructions come back shaped like web3.js
+// TransactionInstruction arguments ({ programId, keys, data }).
+
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use js_sys::{Array, Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+
+use crate::state::{decode, FastAction, FastVote, VoteCredits, VoteMode, VoteResult};
+use crate::{instructions, pda};
+
+fn pubkey(s: &str) -> Result<Pubkey, JsError> {
+    Pubkey::from_str(s).map_err(|_| JsError::new(&format!("invalid pubkey: {s}")))
+}
+
+fn hash(bytes: &[u8]) -> Result<[u8; 32], JsError> {
+    bytes.try_into().map_err(|_| JsError::new("expected 32 bytes"))
+}
+
+fn set(object: &Object, key: &str, value: impl Into<JsValue>) {
+    Reflect::set(object, &key.into(), &value.into()).unwrap_throw();
+}
+
+fn to_js(ix: Instruction) -> JsValue {
+    let keys = Array::new();
+    for meta in ix.accounts {
+        let key = Object::new();
+        set(&key, "pubkey", meta.pubkey.to_string());
+        set(&key, "isSigner", meta.is_signer);
+        set(&key, "isWritable", meta.is_writable);
+        keys.push(&key);
+    }
+    let object = Object::new();
+    set(&object, "programId", ix.program_id.to_string());
+    set(&object, "keys", keys);
+    set(&object, "data", Uint8Array::from(&ix.data[..]));
+    object.into()
+}
+
+fn result_to_js(result: &VoteResult) -> JsValue {
+    let object = Object::new();
+    let kind = match result {
+        VoteResult::Pending => "pending",
+        VoteResult::Passed { tier } => {
+            set(&object, "tier", *tier);
+            "passed"
+        }
+        VoteResult::Failed => "failed",
+        VoteResult::Cancelled => "cancelled",
+        VoteResult::Expired => "expired",
+        VoteResult::Voided => "voided",
+        VoteResult::Chosen { option } => {
+            set(&object, "option", *option);
+            "chosen"
+        }
+        VoteResult::Vetoed => "vetoed",
+        VoteResult::Contested { tier } => {
+            set(&object, "tier", *tier);
+            "contested"
+        }
+    };
+    set(&object, "kind", kind);
+    object.into()
+}
+
+#[wasm_bindgen(js_name = programId)]
+pub fn program_id() -> String {
+    crate::ID.to_string()
+}
+
+#[wasm_bindgen(js_name = fastActionAddress)]
+pub fn fast_action_address(action_id: u64) -> String {
+    pda::fast_action(action_id).0.to_string()
+}
+
+#[wasm_bindgen(js_name = hiveActionAddress)]
+pub fn hive_action_address(hive: &str, action_id: u64) -> Result<String, JsError> {
+    Ok(pda::hive_action(&pubkey(hive)?, action_id).0.to_string())
+}
+
+#[wasm_bindgen(js_name = fastVoteAddress)]
+pub fn fast_vote_address(fast_action: &str, voter: &str) -> Result<String, JsError> {
+    Ok(pda::fast_vote(&pubkey(fast_action)?, &pubkey(voter)?).0.to_string())
+}
+
+#[wasm_bindgen(js_name = voteCreditsAddress)]
+pub fn vote_credits_address(agent: &str) -> Result<String, JsError> {
+    Ok(pda::vote_credits(&pubkey(agent)?).0.to_string())
+}
+
+#[wasm_bindgen(js_name = agentRecordAddress)]
+pub fn agent_record_address(agent: &str) -> Result<String, JsError> {
+    Ok(pda::agent_record(&pubkey(agent)?).0.to_string())
+}
+
+#[wasm_bindgen(js_name = globalConfigAddress)]
+pub fn global_config_address() -> String {
+    pda::global_config().0.to_string()
+}
+
+/// See `instructions::vote_fast`; send to the ephemeral rollup.
+#[wasm_bindgen(js_name = voteFast)]
+pub fn vote_fast(
+    voter: &str,
+    action_id: u64,
+    delegation_epoch: u32,
+    vote_value: bool,
+    voter_commitment: &[u8],
+) -> Result<JsValue, JsError> {
+    let ix = instructions::vote_fast(pubkey(voter)?, action_id, delegation_epoch, vote_value, hash(voter_commitment)?);
+    Ok(to_js(ix))
+}
+
+/// See `instructions::vote_fast_weighted`; send to the base layer.
+#[wasm_bindgen(js_name = voteFastWeighted)]
+#[allow(clippy::too_many_arguments)]
+pub fn vote_fast_weighted(
+    voter: &str,
+    action_id: u64,
+    delegation_epoch: u32,
+    vote_value: bool,
+    voter_commitment: &[u8],
+    weight_mint: &str,
+    voter_token_account: &str,
+    stake_lock_program: &str,
+) -> Result<JsValue, JsError> {
+    let ix = instructions::vote_fast_weighted(
+        pubkey(voter)?,
+        action_id,
+        delegation_epoch,
+        vote_value,
+        hash(voter_commitment)?,
+        (pubkey(weight_mint)?, pubkey(voter_token_account)?),
+        pubkey(stake_lock_program)?,
+    );
+    Ok(to_js(ix))
+}
+
+/// See `instructions::vote_fast_identity`.
+#[wasm_bindgen(js_name = voteFastIdentity)]
+pub fn vote_fast_identity(
+    voter: &str,
+    action_id: u64,
+    delegation_epoch: u32,
+    vote_value: bool,
+    voter_commitment: &[u8],
+    identity_asset: &str,
+) -> Result<JsValue, JsError> {
+    let ix = instructions::vote_fast_identity(
+        pubkey(voter)?,
+        action_id,
+        delegation_epoch,
+        vote_value,
+        hash(voter_commitment)?,
+        pubkey(identity_asset)?,
+    );
+    Ok(to_js(ix))
+}
+
+/// See `instructions::vote_options`; headcount actions only.
+#[wasm_bindgen(js_name = voteOptions)]
+pub fn vote_options(
+    voter: &str,
+    action_id: u64,
+    delegation_epoch: u32,
+    weights: Vec<u64>,
+    voter_commitment: &[u8],
+) -> Result<JsValue, JsError> {
+    let ix = instructions::vote_options(
+        pubkey(voter)?,
+        action_id,
+        delegation_epoch,
+        weights,
+        hash(voter_commitment)?,
+        None,
+    );
+    Ok(to_js(ix))
+}
+
+/// See `instructions::vote_ranked`.
+#[wasm_bindgen(js_name = voteRanked)]
+pub fn vote_ranked(
+    voter: &str,
+    action_id: u64,
+    delegation_epoch: u32,
+    ranking: Vec<u8>,
+    voter_commitment: &[u8],
+) -> Result<JsValue, JsError> {
+    let ix = instructions::vote_ranked(pubkey(voter)?, action_id, delegation_epoch, ranking, hash(voter_commitment)?);
+    Ok(to_js(ix))
+}
+
+/// Simulate it; the Standing is the transaction's return data.
+#[wasm_bindgen(js_name = getStanding)]
+pub fn get_standing(action_id: u64) -> JsValue {
+    to_js(instructions::get_standing(action_id))
+}
+
+/// Simulate it; the TallyPreview is the transaction's return data.
+#[wasm_bindgen(js_name = previewTally)]
+pub fn preview_tally(action_id: u64) -> JsValue {
+    to_js(instructions::preview_tally(action_id))
+}
+
+/// The fields a voter needs, not the whole account
+#[wasm_bindgen(js_name = decodeFastAction)]
+pub fn decode_fast_action(data: &[u8]) -> Result<JsValue, JsError> {
+    let action: FastAction = decode(data).map_err(|e| JsError::new(&e.to_string()))?;
+    let object = Object::new();
+    set(&object, "actionId", action.action_id);
+    set(&object, "actionHash", Uint8Array::from(&action.action_hash[..]));
+    set(&object, "creator", action.creator.to_string());
+    set(&object, "hive", action.hive.to_string());
+    let vote_mode = match action.vote_mode {
+        VoteMode::Headcount => "headcount",
+        VoteMode::TokenWeighted => "tokenWeighted",
+        VoteMode::Optimistic => "optimistic",
+    };
+    set(&object, "voteMode", vote_mode);
+    set(&object, "threshold", action.threshold);
+    set(&object, "optionCount", action.option_count);
+    set(&object, "ranked", action.ranked);
+    set(&object, "votesFor", action.votes_for);
+    set(&object, "votesAgainst", action.votes_against);
+    set(&object, "voteCount", action.vote_count);
+    set(&object, "weightFor", action.weight_for);
+    set(&object, "weightAgainst", action.weight_against);
+    set(&object, "createdSlot", action.created_slot);
+    set(&object, "deadlineSlot", action.deadline_slot);
+    set(&object, "delegationEpoch", action.delegation_epoch);
+    set(&object, "executed", action.executed);
+    set(&object, "result", result_to_js(&action.result));
+    set(&object, "finalizedSlot", action.finalized_slot);
+    Ok(object.into())
+}
+
+#[wasm_bindgen(js_name = decodeFastVote)]
+pub fn decode_fast_vote(data: &[u8]) -> Result<JsValue, JsError> {
+    let vote: FastVote = decode(data).map_err(|e| JsError::new(&e.to_string()))?;
+    let object = Object::new();
+    set(&object, "fastAction", vote.fast_action.to_string());
+    set(&object, "voter", vote.voter.to_string());
+    set(&object, "voteValue", vote.vote_value);
+    set(&object, "votedSlot", vote.voted_slot);
+    set(&object, "weight", vote.weight);
+    Ok(object.into())
+}
+
+#[wasm_bindgen(js_name = decodeVoteCredits)]
+pub fn decode_vote_credits(data: &[u8]) -> Result<JsValue, JsError> {
+    let credits: VoteCredits = decode(data).map_err(|e| JsError::new(&e.to_string()))?;
+    let object = Object::new();
+    set(&object, "agent", credits.agent.to_string());
+    set(&object, "epoch", credits.epoch);
+    set(&object, "spent", credits.spent);
+    Ok(object.into())
+}
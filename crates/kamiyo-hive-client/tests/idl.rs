@@ -0,0 +1,225 @@
+// The builders must produce what the program's IDL describes: its
+// discriminator, then one meta per IDL account in order with the same
+// signer and writable flags, at the address the account's seeds give.
+
+use std::collections::BTreeMap;
+
+use anchor_lang::idl::types::{IdlInstructionAccount, IdlInstructionAccountItem, IdlSeed};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use kamiyo_fast_voting::{
+    BallotChoice, DelegationShare, FAST_ACTION_SEED, ID, VOTE_COMPACT_DISCRIMINATOR, VOTE_KEY_SEED,
+};
+use kamiyo_hive_client::{instructions, pda};
+
+/// The IDL accounts of one of the program's `#[derive(Accounts)]` structs,
+/// with seeds resolved as `anchor build` resolves them
+macro_rules! idl_accounts {
+    ($accounts:ident) => {
+        kamiyo_fast_voting::$accounts::__anchor_private_gen_idl_accounts(&mut BTreeMap::new(), &mut BTreeMap::new())
+    };
+}
+
+fn sighash(name: &str) -> Vec<u8> {
+    hash(format!("global:{name}").as_bytes()).to_bytes()[..8].to_vec()
+}
+
+fn flatten(items: Vec<IdlInstructionAccountItem>) -> Vec<IdlInstructionAccount> {
+    items
+        .into_iter()
+        .flat_map(|item| match item {
+            IdlInstructionAccountItem::Single(account) => vec![account],
+            IdlInstructionAccountItem::Composite(accounts) => flatten(accounts.accounts),
+        })
+        .collect()
+}
+
+/// `known` holds the seeds the IDL names but the metas can't give: ids and
+/// fields of the accounts' data, such as `fast_action.hive`. Seeds read
+/// from an account's own data (`fast_action.hive_seed()`, `vote_key.key`)
+/// come out of the IDL as the account's own address, so `known` holds the
+/// address of such an account instead.
+fn check(ix: &Instruction, discriminator: &[u8], idl: Vec<IdlInstructionAccountItem>, known: &[(&str, Vec<u8>)]) {
+    assert_eq!(ix.program_id, ID);
+    assert!(ix.data.starts_with(discriminator), "discriminator");
+    let idl = flatten(idl);
+    assert!(ix.accounts.len() >= idl.len(), "{} metas for {} accounts", ix.accounts.len(), idl.len());
+
+    let seed = |seed: &IdlSeed| -> Vec<u8> {
+        let path = match seed {
+            IdlSeed::Const(seed) => return seed.value.clone(),
+            IdlSeed::Arg(seed) => &seed.path,
+            IdlSeed::Account(seed) => &seed.path,
+        };
+        if let Some((_, value)) = known.iter().find(|(name, _)| name == path) {
+            return value.clone();
+        }
+        let index = idl.iter().position(|account| account.name == *path);
+        let index = index.unwrap_or_else(|| panic!("unresolved seed {path}"));
+        ix.accounts[index].pubkey.to_bytes().to_vec()
+    };
+    for (account, meta) in idl.iter().zip(&ix.accounts) {
+        let name = &account.name;
+        if account.optional && meta.pubkey == ID {
+            assert!(!meta.is_signer && !meta.is_writable, "{name}");
+            continue;
+        }
+        assert_eq!(meta.is_signer, account.signer, "{name} signer");
+        assert_eq!(meta.is_writable, account.writable, "{name} writable");
+        if let Some(address) = account.address.as_ref().and_then(|address| address.parse::<Pubkey>().ok()) {
+            assert_eq!(meta.pubkey, address, "{name} address");
+        }
+        let own = |seed: &IdlSeed| matches!(seed, IdlSeed::Account(seed) if seed.path == *name);
+        if let Some(derived) = account.pda.as_ref().filter(|derived| derived.seeds.iter().any(own)) {
+            assert!(derived.program.is_none(), "{name}");
+            let (_, address) = known.iter().find(|(known, _)| known == name).expect("own seeds");
+            assert_eq!(meta.pubkey.as_ref(), address, "{name} address");
+        } else if let Some(derived) = &account.pda {
+            let seeds: Vec<Vec<u8>> = derived.seeds.iter().map(seed).collect();
+            let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+            let program = derived.program.as_ref().map_or(ID, |program| Pubkey::try_from(seed(program)).unwrap());
+            assert_eq!(meta.pubkey, Pubkey::find_program_address(&seeds, &program).0, "{name} seeds");
+        }
+    }
+}
+
+/// Action 7, unscoped: its hive seed is empty
+fn unscoped() -> Vec<(&'static str, Vec<u8>)> {
+    let action_id = 7u64.to_le_bytes();
+    let action = Pubkey::find_program_address(&[FAST_ACTION_SEED, &action_id], &ID).0;
+    vec![
+        ("fast_action", action.to_bytes().to_vec()),
+        ("fast_action.hive", vec![0; 32]),
+        ("fast_action.action_id", action_id.to_vec()),
+        ("action_id", action_id.to_vec()),
+    ]
+}
+
+#[test]
+fn vote_fast_matches() {
+    let voter = Pubkey::new_unique();
+    let ix = instructions::vote_fast(voter, 7, 3, true, [9; 32]);
+    check(&ix, &sighash("vote_fast"), idl_accounts!(VoteFast), &unscoped());
+
+    // action_id, vote_value, voter_commitment, delegation_epoch
+    let mut data = sighash("vote_fast");
+    data.extend(7u64.to_le_bytes());
+    data.push(1);
+    data.extend([9; 32]);
+    data.extend(3u32.to_le_bytes());
+    assert_eq!(ix.data, data);
+}
+
+#[test]
+fn vote_fast_by_vote_key_matches() {
+    let (agent, key) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let ix = instructions::signed_by_vote_key(instructions::vote_fast(agent, 7, 0, false, [0; 32]), &agent, &key);
+    check(&ix, &sighash("vote_fast"), idl_accounts!(VoteFast), &unscoped());
+    let signers: Vec<Pubkey> = ix.accounts.iter().filter(|meta| meta.is_signer).map(|meta| meta.pubkey).collect();
+    assert_eq!(signers, [key]);
+    // Still the agent's ballot
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == pda::fast_vote(&ix.accounts[0].pubkey, &agent).0));
+}
+
+#[test]
+fn vote_fast_for_hive_matches() {
+    let (voter, hive) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let ix = instructions::for_hive(instructions::vote_fast(voter, 7, 0, true, [0; 32]), &hive);
+    let mut known = unscoped();
+    known[1].1 = hive.to_bytes().to_vec();
+    check(&ix, &sighash("vote_fast"), idl_accounts!(VoteFast), &known);
+}
+
+#[test]
+fn vote_compact_matches() {
+    let voter = Pubkey::new_unique();
+    let ix = instructions::vote_compact(voter, 7, 0, BallotChoice::Option(2), None);
+    check(&ix, VOTE_COMPACT_DISCRIMINATOR, idl_accounts!(VoteFast), &unscoped());
+}
+
+#[test]
+fn vote_options_matches() {
+    let voter = Pubkey::new_unique();
+    let ix = instructions::vote_options(voter, 7, 0, vec![1, 0, 2], [0; 32], None);
+    check(&ix, &sighash("vote_options"), idl_accounts!(VoteOptions), &unscoped());
+
+    let weighted = Some((Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()));
+    let ix = instructions::vote_options(voter, 7, 0, vec![1, 0, 2], [0; 32], weighted);
+    check(&ix, &sighash("vote_options"), idl_accounts!(VoteOptions), &unscoped());
+}
+
+#[test]
+fn token_weighted_ballots_match() {
+    let voter = Pubkey::new_unique();
+    let tokens = (Pubkey::new_unique(), Pubkey::new_unique());
+    let program = Pubkey::new_unique();
+
+    let ix = instructions::vote_fast_weighted(voter, 7, 0, true, [0; 32], tokens, program);
+    check(&ix, &sighash("vote_fast_weighted"), idl_accounts!(VoteFastWeighted), &unscoped());
+    let ix = instructions::vote_fast_locked(voter, 7, 0, true, [0; 32], tokens, program);
+    check(&ix, &sighash("vote_fast_locked"), idl_accounts!(VoteFastLocked), &unscoped());
+    let ix = instructions::release_stake_lock(7, voter, program, tokens.1, tokens.0);
+    check(&ix, &sighash("release_stake_lock"), idl_accounts!(ReleaseStakeLock), &unscoped());
+}
+
+#[test]
+fn other_ballots_match() {
+    let voter = Pubkey::new_unique();
+    let ix = instructions::vote_fast_identity(voter, 7, 0, true, [0; 32], Pubkey::new_unique());
+    check(&ix, &sighash("vote_fast_identity"), idl_accounts!(VoteFastIdentity), &unscoped());
+    let ix = instructions::vote_fast_snapshot(voter, 7, 0, true, [0; 32], 100, vec![[1; 32]]);
+    check(&ix, &sighash("vote_fast_snapshot"), idl_accounts!(VoteFastSnapshot), &unscoped());
+    let group = Pubkey::new_unique();
+    let ix = instructions::vote_fast_group(voter, group, 7, 0, true, [0; 32]);
+    let mut known = unscoped();
+    known.push(("voter_group.group_key", group.to_bytes().to_vec()));
+    check(&ix, &sighash("vote_fast_group"), idl_accounts!(VoteFastGroup), &known);
+}
+
+#[test]
+fn agent_keys_match() {
+    let (agent, key) = (Pubkey::new_unique(), Pubkey::new_unique());
+    check(&instructions::register_agent(agent, 10), &sighash("register_agent"), idl_accounts!(RegisterAgent), &[]);
+    check(&instructions::set_vote_key(agent, key), &sighash("set_vote_key"), idl_accounts!(SetVoteKey), &[]);
+    let vote_key = Pubkey::find_program_address(&[VOTE_KEY_SEED, key.as_ref()], &ID).0;
+    let ix = instructions::clear_vote_key(agent, key);
+    check(&ix, &sighash("clear_vote_key"), idl_accounts!(ClearVoteKey), &[("vote_key", vote_key.to_bytes().to_vec())]);
+    let shares = vec![DelegationShare { delegate: key, bps: 5_000 }];
+    let ix = instructions::set_vote_delegation(agent, shares);
+    check(&ix, &sighash("set_vote_delegation"), idl_accounts!(SetVoteDelegation), &[]);
+}
+
+#[test]
+fn tally_and_commit_matches() {
+    let ix = instructions::tally_and_commit(Pubkey::new_unique(), 7, None, &[], false, Some(4));
+    check(&ix, &sighash("tally_and_commit"), idl_accounts!(TallyAndCommit), &unscoped());
+}
+
+/// Built with `event-cpi`, an emitting instruction signs its events through
+/// the event authority, then names the program for the self-CPI
+#[cfg(feature = "event-cpi")]
+#[test]
+fn events_go_through_the_event_authority() {
+    let ix = instructions::tally_and_commit(Pubkey::new_unique(), 7, None, &[], false, Some(4));
+    let idl = flatten(idl_accounts!(TallyAndCommit));
+    let names: Vec<&str> = idl.iter().rev().take(2).map(|account| account.name.as_str()).collect();
+    assert_eq!(names, ["program", "event_authority"]);
+
+    let tail = &ix.accounts[ix.accounts.len() - 2..];
+    assert_eq!(tail[0].pubkey, pda::event_authority().0);
+    assert_eq!(tail[1].pubkey, ID);
+    assert!(tail.iter().all(|meta| !meta.is_signer && !meta.is_writable));
+}
+
+/// Without `event-cpi` events are logged, and no instruction carries the
+/// event authority
+#[cfg(not(feature = "event-cpi"))]
+#[test]
+fn events_are_logged_without_event_cpi() {
+    let ix = instructions::tally_and_commit(Pubkey::new_unique(), 7, None, &[], false, Some(4));
+    let idl = flatten(idl_accounts!(TallyAndCommit));
+    assert!(idl.iter().all(|account| account.name != "event_authority"));
+    assert!(ix.accounts.iter().all(|meta| meta.pubkey != pda::event_authority().0));
+    assert_eq!(ix.accounts.len(), idl.len());
+}
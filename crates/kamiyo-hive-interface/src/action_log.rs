@@ -0,0 +1,62 @@
+// Action log: the last ACTION_LOG_LEN significant events on an action, kept
+// on the action itself so operators can trace it between checkpoints without
+// relying on RPC log retention. A fixed-size ring: once full, each entry
+// overwrites the oldest. Ballots are not logged, only what they cause
+// (quorum reached, the for share crossing the threshold).
+
+use anchor_lang::prelude::*;
+
+pub const ACTION_LOG_LEN: usize = 16;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LogTag {
+    /// Slot never written
+    #[default]
+    Empty,
+    /// Payload: the new delegation epoch
+    Delegated,
+    /// Payload: the new delegation epoch
+    Undelegated,
+    /// Payload: vote count at the checkpoint
+    Checkpointed,
+    /// Payload: vote count when the action's quorum was met
+    QuorumReached,
+    /// Payload: approval percentage after the ballot that moved it across the
+    /// threshold, in either direction
+    ThresholdCrossed,
+    /// Tallied, cancelled, expired, vetoed or voided. Payload: the result's
+    /// encoding as a little-endian u16
+    ResultRecorded,
+    /// Payload: the new deadline slot
+    DeadlineExtended,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct LogEntry {
+    pub slot: u64,    // 8
+    pub tag: LogTag,  // 1
+    pub payload: u64, // 8
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ActionLog {
+    pub entries: [LogEntry; ACTION_LOG_LEN], // 17 * ACTION_LOG_LEN
+    /// Entries ever written; the next goes at `appended % ACTION_LOG_LEN`
+    pub appended: u32,                        // 4
+}
+
+impl ActionLog {
+    pub const LEN: usize = 276;
+
+    pub fn push(&mut self, slot: u64, tag: LogTag, payload: u64) {
+        self.entries[self.appended as usize % ACTION_LOG_LEN] = LogEntry { slot, tag, payload };
+        self.appended = self.appended.wrapping_add(1);
+    }
+
+    /// Entries still held, oldest first
+    pub fn recent(&self) -> impl Iterator<Item = &LogEntry> {
+        let held = (self.appended as usize).min(ACTION_LOG_LEN);
+        let start = self.appended as usize % ACTION_LOG_LEN + ACTION_LOG_LEN - held;
+        (0..held).map(move |i| &self.entries[(start + i) % ACTION_LOG_LEN])
+    }
+}
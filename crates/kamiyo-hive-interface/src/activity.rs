@@ -0,0 +1,42 @@
+// Activity markers of hive members, and the epochs of silence that mark one
+// inactive
+
+use anchor_lang::prelude::*;
+
+pub const MEMBER_ACTIVITY_SEED: &[u8] = b"member_activity";
+
+/// Epochs since a member's last heartbeat before anyone may mark it inactive
+pub const INACTIVITY_EPOCHS: u64 = 8;
+
+#[account]
+pub struct MemberActivity {
+    pub hive: Pubkey,           // 32
+    pub member: Pubkey,         // 32
+    /// Epoch of the last heartbeat, or of the marker's creation
+    pub last_active_epoch: u64, // 8
+    pub bump: u8,               // 1
+}
+
+impl MemberActivity {
+    pub const LEN: usize = 81; // 8 disc + 73 fields
+}
+
+#[event]
+pub struct MemberHeartbeatRecorded {
+    pub hive: Pubkey,
+    pub member: Pubkey,
+    pub epoch: u64,
+    /// The member was marked inactive until this heartbeat
+    pub reactivated: bool,
+    /// Members now counted in the hive's electorate
+    pub electorate: u32,
+}
+
+#[event]
+pub struct MemberMarkedInactive {
+    pub hive: Pubkey,
+    pub member: Pubkey,
+    pub last_active_epoch: u64,
+    /// Members now counted in the hive's electorate
+    pub electorate: u32,
+}
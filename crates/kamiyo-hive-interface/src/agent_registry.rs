@@ -0,0 +1,139 @@
+// Registered agents and the classes ballots are capped and scaled by
+
+use anchor_lang::prelude::*;
+
+pub const AGENT_SEED: &[u8] = b"agent";
+pub const VOTE_KEY_SEED: &[u8] = b"vote_key";
+
+/// Number of AgentClass variants
+pub const AGENT_CLASSES: usize = 4;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum AgentClass {
+    #[default]
+    Unclassified,
+    Scout,
+    Worker,
+    Coordinator,
+}
+
+/// Per-class ballot limits of one action, set at creation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ClassLimit {
+    pub class: AgentClass,
+    /// Most ballots the class may cast (0 = unlimited)
+    pub max_votes: u32,
+    /// Token-weighted only: scales the class's ballot weight, in basis
+    /// points, before the per-voter cap (0 = unscaled)
+    pub weight_bps: u16,
+}
+
+/// Share of its weight a newly registered agent votes with: `floor_bps` in
+/// its registration epoch, rising linearly to full weight once it is
+/// `epochs` old
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct IdentityRamp {
+    /// Epochs until full weight; 0 disables the ramp
+    pub epochs: u16,    // 2
+    pub floor_bps: u16, // 2
+}
+
+impl IdentityRamp {
+    /// `weight` of an agent `age` epochs after registering
+    pub fn apply(&self, weight: u64, age: u64) -> u64 {
+        let epochs = self.epochs as u64;
+        if age >= epochs {
+            return weight;
+        }
+        let floor = self.floor_bps as u64;
+        let bps = floor + (10_000 - floor) * age / epochs;
+        (weight as u128 * bps as u128 / 10_000) as u64
+    }
+}
+
+#[account]
+pub struct AgentRecord {
+    pub agent: Pubkey,         // 32
+    /// Lamports bonded, on top of the record's rent
+    pub bond: u64,             // 8
+    pub registered_slot: u64,  // 8
+    pub bump: u8,              // 1
+    pub class: AgentClass,     // 1
+    /// Epoch the record was created in; 0 for records from before it was
+    /// kept, which the identity ramp counts as long registered
+    pub registered_epoch: u64, // 8
+}
+
+impl AgentRecord {
+    pub const LEN: usize = 66; // 8 disc + 58 fields
+    /// Size before `registered_epoch`; migrate_agent_record grows it
+    pub const LEGACY_LEN: usize = 58;
+
+    /// The AgentRecord at `info`, or none if the agent is not registered.
+    /// Reads legacy records as if already migrated
+    pub fn load(info: &AccountInfo) -> Result<Option<AgentRecord>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let mut data = info.try_borrow_data()?.to_vec();
+        if data.len() == Self::LEGACY_LEN {
+            data.resize(Self::LEN, 0);
+        }
+        Ok(Some(AgentRecord::try_deserialize(&mut &data[..])?))
+    }
+}
+
+/// A key the agent lets cast vote_fast ballots in its name. Ballots it signs
+/// are the agent's: same FastVote, same AgentRecord, same vote credits.
+#[account]
+pub struct VoteKey {
+    pub key: Pubkey,   // 32
+    pub agent: Pubkey, // 32
+    pub bump: u8,      // 1
+}
+
+impl VoteKey {
+    pub const LEN: usize = 73; // 8 disc + 65 fields
+
+    /// The VoteKey at `info`, or none if the key is not bound to an agent
+    pub fn load(info: &AccountInfo) -> Result<Option<VoteKey>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        Ok(Some(VoteKey::try_deserialize(&mut &info.try_borrow_data()?[..])?))
+    }
+}
+
+#[event]
+pub struct AgentRegistered {
+    pub agent: Pubkey,
+    /// Total bonded
+    pub bond: u64,
+}
+
+#[event]
+pub struct AgentEvicted {
+    pub agent: Pubkey,
+    /// Refunded with the record's rent
+    pub bond: u64,
+}
+
+#[event]
+pub struct AgentClassSet {
+    pub agent: Pubkey,
+    pub class: AgentClass,
+}
+
+#[event]
+pub struct VoteKeySet {
+    pub agent: Pubkey,
+    pub key: Pubkey,
+}
+
+#[event]
+pub struct VoteKeyCleared {
+    pub agent: Pubkey,
+    pub key: Pubkey,
+}
@@ -0,0 +1,29 @@
+// Amendments to an action's threshold, window or description before its first vote
+
+use anchor_lang::prelude::*;
+
+/// Hard cap on the window an amendment may set: ~1 hour at 400ms/slot. The
+/// config's `max_window_slots` may lower it
+pub const MAX_VOTING_WINDOW_SLOTS: u64 = 9_000;
+
+/// Partial amendment; `None` leaves the field untouched
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ActionAmendment {
+    pub threshold: Option<u8>,
+    /// New window, counted from the action's creation slot
+    pub window_slots: Option<u64>,
+    pub description_hash: Option<[u8; 32]>,
+}
+
+#[event]
+pub struct FastActionAmended {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub old_threshold: u8,
+    pub threshold: u8,
+    pub old_deadline_slot: u64,
+    pub deadline_slot: u64,
+    pub old_description_hash: [u8; 32],
+    pub description_hash: [u8; 32],
+}
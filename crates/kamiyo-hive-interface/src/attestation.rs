@@ -0,0 +1,59 @@
+// Validator co-signatures over a hive's tally results
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::VoteResult;
+
+const ATTESTATION_DOMAIN: &[u8] = b"kamiyo_result_attestation";
+
+pub const RESULT_ATTESTORS_SEED: &[u8] = b"result_attestors";
+
+pub const MAX_ATTESTORS: usize = 10;
+
+/// Validators whose co-signatures a hive's tallies need, and how many
+#[account]
+pub struct ResultAttestors {
+    pub hive: Pubkey,            // 32
+    pub validators: Vec<Pubkey>, // 4 + 32 * MAX_ATTESTORS
+    /// Co-signatures required; 0 = none
+    pub threshold: u8,           // 1
+    pub bump: u8,                // 1
+}
+
+impl ResultAttestors {
+    pub const LEN: usize = 366; // 8 disc + 358 fields
+}
+
+/// sha256("kamiyo_result_attestation" || action || votes_for || votes_against || result)
+///
+/// The result digest without the finalization slot, which is only known
+/// once the tally lands; attestors sign this ahead of tally_and_commit.
+/// Integers are little-endian; `result` is the two-byte `VoteResult::encode`.
+pub fn attestation_digest(action: &Pubkey, votes_for: u32, votes_against: u32, result: &VoteResult) -> [u8; 32] {
+    hashv(&[
+        ATTESTATION_DOMAIN,
+        action.as_ref(),
+        &votes_for.to_le_bytes(),
+        &votes_against.to_le_bytes(),
+        &result.encode(),
+    ])
+    .to_bytes()
+}
+
+#[event]
+pub struct ResultAttestorsSet {
+    pub hive: Pubkey,
+    pub validators: Vec<Pubkey>,
+    pub threshold: u8,
+    pub set_by: Pubkey,
+}
+
+#[event]
+pub struct ResultAttested {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub digest: [u8; 32],
+    /// The hive's validators that co-signed the digest
+    pub attestors: Vec<Pubkey>,
+}
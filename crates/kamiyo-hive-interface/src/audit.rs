@@ -0,0 +1,43 @@
+// TallyAudit, the page-by-page recount an audited action must pass
+
+use anchor_lang::prelude::*;
+
+use crate::FastAction;
+
+pub const TALLY_AUDIT_SEED: &[u8] = b"tally_audit";
+
+#[account]
+pub struct TallyAudit {
+    pub action: Pubkey,        // 32
+    pub vote_count: u32,       // 4
+    pub votes_for: u32,        // 4
+    pub votes_against: u32,    // 4
+    pub weight_for: u64,       // 8
+    pub weight_against: u64,   // 8
+    /// Highest FastVote address counted so far
+    pub last_vote: Pubkey,     // 32
+    pub bump: u8,              // 1
+}
+
+impl TallyAudit {
+    pub const LEN: usize = 101; // 8 disc + 93 fields
+
+    /// Whether the recount agrees with every counter on `action`
+    pub fn matches(&self, action: &FastAction) -> bool {
+        self.vote_count == action.vote_count
+            && self.votes_for == action.votes_for
+            && self.votes_against == action.votes_against
+            && self.weight_for == action.weight_for
+            && self.weight_against == action.weight_against
+    }
+}
+
+#[event]
+pub struct TallyPageVerified {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    /// Ballots recounted so far
+    pub verified: u32,
+    /// The recount now matches the action's counters
+    pub complete: bool,
+}
@@ -0,0 +1,12 @@
+// Transfers of the authority that may cancel and amend an action
+
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct ActionAuthorityTransferred {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub previous: Pubkey,
+    pub authority: Pubkey,
+}
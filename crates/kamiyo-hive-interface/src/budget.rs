@@ -0,0 +1,101 @@
+// Treasury spending: Budget payouts and the Streams they open
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+const BUDGET_DOMAIN: &[u8] = b"budget";
+const CANCEL_STREAM_DOMAIN: &[u8] = b"cancel_stream";
+
+pub const STREAM_SEED: &[u8] = b"stream";
+
+/// A payment from the treasury, vesting linearly from `start_slot` to
+/// `end_slot`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Budget {
+    pub recipient: Pubkey,
+    /// Default pays lamports
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub start_slot: u64,
+    pub end_slot: u64,
+}
+
+impl Budget {
+    /// The action hash a Budget action must carry to fund `self`:
+    /// sha256("budget" || borsh(self))
+    pub fn action_hash(&self) -> Result<[u8; 32]> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(hashv(&[BUDGET_DOMAIN, &data]).to_bytes())
+    }
+}
+
+#[account]
+pub struct Stream {
+    pub action: Pubkey,      // 32
+    pub recipient: Pubkey,   // 32
+    pub mint: Pubkey,        // 32
+    pub amount: u64,         // 8
+    pub withdrawn: u64,      // 8
+    pub start_slot: u64,     // 8
+    pub end_slot: u64,       // 8
+    /// Slot vesting stopped at (0 = running)
+    pub cancelled_slot: u64, // 8
+    pub bump: u8,            // 1
+}
+
+impl Stream {
+    pub const LEN: usize = 145; // 8 disc + 137 fields
+
+    /// Amount vested by `slot`, frozen at the cancellation slot
+    pub fn vested(&self, slot: u64) -> u64 {
+        let until = match self.cancelled_slot {
+            0 => slot,
+            cancelled => slot.min(cancelled),
+        };
+        if until <= self.start_slot {
+            0
+        } else if until >= self.end_slot {
+            self.amount
+        } else {
+            let elapsed = (until - self.start_slot) as u128;
+            let duration = (self.end_slot - self.start_slot) as u128;
+            (self.amount as u128 * elapsed / duration) as u64
+        }
+    }
+
+    /// The action hash a Budget action must carry to cancel `stream`:
+    /// sha256("cancel_stream" || stream)
+    pub fn cancel_hash(stream: &Pubkey) -> [u8; 32] {
+        hashv(&[CANCEL_STREAM_DOMAIN, stream.as_ref()]).to_bytes()
+    }
+}
+
+#[event]
+pub struct StreamOpened {
+    pub stream: Pubkey,
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub recipient: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub start_slot: u64,
+    pub end_slot: u64,
+}
+
+#[event]
+pub struct StreamWithdrawn {
+    pub stream: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub withdrawn: u64,
+}
+
+#[event]
+pub struct StreamCancelled {
+    pub stream: Pubkey,
+    pub cancel_action: Pubkey,
+    pub hive: Pubkey,
+    /// Unvested amount that will no longer be paid
+    pub forfeited: u64,
+}
@@ -0,0 +1,60 @@
+// Callbacks an action's result is pushed to, and the payload they receive
+
+use anchor_lang::prelude::*;
+
+use crate::VoteResult;
+
+pub const CALLBACK_REGISTRY_SEED: &[u8] = b"callbacks";
+
+pub const MAX_CALLBACKS: usize = 4;
+
+/// Anchor discriminator of `on_vote_result`: sha256("global:on_vote_result")[..8]
+pub const CALLBACK_DISCRIMINATOR: [u8; 8] = [117, 226, 7, 216, 217, 11, 76, 230];
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Callback {
+    pub program_id: Pubkey,      // 32
+    /// `accounts_hash` of the accounts passed after the action account
+    pub accounts_hash: [u8; 32], // 32
+    pub account_count: u8,       // 1
+}
+
+#[account]
+pub struct CallbackRegistry {
+    pub action: Pubkey,                       // 32
+    pub callbacks: [Callback; MAX_CALLBACKS], // 260
+    pub count: u8,                            // 1
+    pub dispatched: bool,                     // 1
+    pub bump: u8,                             // 1
+}
+
+impl CallbackRegistry {
+    pub const LEN: usize = 303; // 8 disc + 295 fields
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ResultPayload {
+    pub action: Pubkey,
+    pub action_id: u64,
+    pub action_hash: [u8; 32],
+    pub result: VoteResult,
+    pub finalized_slot: u64,
+    pub result_digest: [u8; 32],
+}
+
+#[event]
+pub struct CallbackRegistered {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub index: u8,
+    pub program_id: Pubkey,
+    pub accounts_hash: [u8; 32],
+}
+
+#[event]
+pub struct CallbacksDispatched {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub callbacks: u8,
+}
@@ -0,0 +1,22 @@
+// Intervals at which delegated actions checkpoint to the base layer
+
+use anchor_lang::prelude::*;
+
+/// Votes since the last checkpoint that allow another
+pub const CHECKPOINT_VOTE_INTERVAL: u32 = 50;
+
+/// Slots since the last checkpoint that allow another (~6s)
+pub const CHECKPOINT_SLOT_INTERVAL: u64 = 15;
+
+#[event]
+pub struct ActionCheckpointed {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub vote_count: u32,
+    pub votes_for: u32,
+    pub votes_against: u32,
+    pub weight_for: u64,
+    pub weight_against: u64,
+    pub slot: u64,
+}
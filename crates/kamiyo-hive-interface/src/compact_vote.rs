@@ -0,0 +1,3 @@
+/// vote_compact's one-byte discriminator. Dispatch matches discriminators by
+/// prefix in declaration order, so no other instruction's may start with it.
+pub const VOTE_COMPACT_DISCRIMINATOR: &[u8] = &[1];
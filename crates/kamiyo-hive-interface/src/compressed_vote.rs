@@ -0,0 +1,74 @@
+// Ballots stored as Light Protocol compressed accounts
+
+use anchor_lang::prelude::*;
+
+pub const COMPRESSED_BALLOTS_SEED: &[u8] = b"compressed_ballots";
+
+/// Signs every invoke_cpi; the seed the Light system program expects
+pub const LIGHT_CPI_AUTHORITY_SEED: &[u8] = b"cpi_authority";
+
+pub const LIGHT_SYSTEM_PROGRAM_ID: Pubkey = pubkey!("SySTEM1eSU2p4BGQfQpimFEWWSC1XDFeun3Nqzz3rT7");
+pub const LIGHT_ACCOUNT_COMPRESSION_PROGRAM_ID: Pubkey = pubkey!("compr6CUsB5m2jS4Y3831ztGSTnDpnKJTKS95d64XVq");
+
+/// Marks an action whose ballots are compressed
+#[account]
+pub struct CompressedBallots {
+    pub action: Pubkey,       // 32
+    pub bump: u8,             // 1
+    /// Light address tree every ballot's address is created in; a ballot
+    /// through any other tree would get a fresh address
+    pub address_tree: Pubkey, // 32
+}
+
+impl CompressedBallots {
+    pub const LEN: usize = 73; // 8 disc + 65 fields
+}
+
+/// Groth16 proof from the Light prover: new addresses are not yet in the tree
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CompressedProof {
+    pub a: [u8; 32],
+    pub b: [u8; 64],
+    pub c: [u8; 32],
+}
+
+/// A ballot address's non-inclusion proof and the address tree root it is
+/// against
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AddressProof {
+    pub proof: CompressedProof,
+    /// Index of that root in the address tree's root history
+    pub root_index: u16,
+}
+
+/// A compressed ballot as recounted: the FastVote fields its voter chose,
+/// and where it sits in its state tree. The rest of the FastVote is fixed:
+/// the action, no bump, a weight of 1 and no reason.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CompressedBallot {
+    pub voter: Pubkey,
+    pub voter_commitment: [u8; 32],
+    pub vote_value: bool,
+    pub voted_slot: u64,
+    pub leaf_index: u32,
+    /// Index of the state root the page's proof is against
+    pub root_index: u16,
+}
+
+#[event]
+pub struct CompressedBallotsEnabled {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub address_tree: Pubkey,
+}
+
+#[event]
+pub struct CompressedVoteCast {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub voter: Pubkey,
+    /// Compressed address of the ballot
+    pub address: [u8; 32],
+    pub state_tree: Pubkey,
+}
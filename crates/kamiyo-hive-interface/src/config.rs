@@ -0,0 +1,221 @@
+// GlobalConfig, the program-wide settings and their admin
+
+use anchor_lang::prelude::*;
+
+use crate::{FastVoteError, FeeRebate, IdentityRamp, MAX_VOTES_PER_ACTION, MAX_VOTING_WINDOW_SLOTS, VOTING_WINDOW_SLOTS, WEIGHT_EXT_ALL};
+
+pub const GLOBAL_CONFIG_SEED: &[u8] = b"global_config";
+
+/// Initial per-creator creation budget; 0 disables the limit
+pub const DEFAULT_MAX_ACTIONS_PER_EPOCH: u32 = 64;
+
+/// Initial share of each slash paid to its reporter: 10%
+pub const DEFAULT_WHISTLEBLOWER_BPS: u16 = 1_000;
+
+/// Largest reporter share, so a slashed party reporting itself still loses
+/// at least half
+pub const MAX_WHISTLEBLOWER_BPS: u16 = 5_000;
+
+#[account]
+pub struct GlobalConfig {
+    pub admin: Pubkey,                  // 32
+    pub weight_mint: Pubkey,            // 32
+    pub weight_extensions: u8,          // 1
+    pub transfer_hook_program: Pubkey,  // 32
+    pub bump: u8,                       // 1
+    pub max_actions_per_epoch: u32,     // 4
+    /// May veto tallied results during the dispute window; typically a
+    /// multisig. Default disables vetoes
+    pub guardian: Pubkey,               // 32
+    /// Ballots each agent may cast per epoch; 0 disables the limit
+    pub vote_credits_per_epoch: u32,    // 4
+    /// Lamports an agent must bond in its AgentRecord to vote; 0 lets
+    /// anyone vote
+    pub min_agent_bond: u64,            // 8
+    /// Share of every slash paid to whoever reported it, in basis points;
+    /// the rest goes to the treasury
+    pub whistleblower_bps: u16,         // 2
+    /// May post weight snapshots besides allowlisted TEE validators.
+    /// Default leaves it to the validators
+    pub snapshotter: Pubkey,            // 32
+    /// Longest window an amendment may set; 0 = MAX_VOTING_WINDOW_SLOTS
+    pub max_window_slots: u64,          // 8
+    /// Locks the stake behind ballots on stake-locked actions. Default
+    /// disables stake locks
+    pub stake_lock_program: Pubkey,     // 32
+    /// Low-priority actions a hive may have running at once, counted in its
+    /// ActiveActions index; 0 disables the cap
+    pub max_low_priority_actions: u32,  // 4
+    /// Most ballots any action may take; 0 = MAX_VOTES_PER_ACTION. Each
+    /// hive action's own cap is its electorate, within this
+    pub max_votes_per_action: u32,      // 4
+    /// Weight a newly registered agent votes with, ramping up over its
+    /// first epochs. Default disables the ramp
+    pub identity_ramp: IdentityRamp,    // 4
+    /// Creation fee refunded on high-turnout tallies. Default disables
+    /// rebates
+    pub fee_rebate: FeeRebate,          // 4
+    /// Actions a validator may host at once, counted in its
+    /// ValidatorRecord; 0 disables the cap
+    pub max_actions_per_validator: u32, // 4
+}
+
+impl GlobalConfig {
+    pub const LEN: usize = 248; // 8 disc + 240 fields
+    /// Sizes before `guardian`, `vote_credits_per_epoch`, `min_agent_bond`,
+    /// `whistleblower_bps`, `snapshotter`, `max_window_slots`,
+    /// `stake_lock_program`, `max_low_priority_actions`,
+    /// `max_votes_per_action`, `identity_ramp`, `fee_rebate` and
+    /// `max_actions_per_validator`; migrate_config grows them
+    pub const LEGACY_LENS: [usize; 12] = [110, 142, 146, 154, 156, 188, 196, 228, 232, 236, 240, 244];
+
+    /// Longest voting window in force
+    pub fn max_window(&self) -> u64 {
+        match self.max_window_slots {
+            0 => MAX_VOTING_WINDOW_SLOTS,
+            slots => slots,
+        }
+    }
+
+    /// Most ballots any action may take
+    pub fn max_votes(&self) -> u32 {
+        match self.max_votes_per_action {
+            0 => MAX_VOTES_PER_ACTION,
+            max => max,
+        }
+    }
+
+    pub fn apply(&mut self, update: ConfigUpdate) -> Result<()> {
+        if let Some(admin) = update.admin {
+            self.admin = admin;
+        }
+        if let Some(weight_mint) = update.weight_mint {
+            self.weight_mint = weight_mint;
+        }
+        if let Some(weight_extensions) = update.weight_extensions {
+            require!(weight_extensions & !WEIGHT_EXT_ALL == 0, FastVoteError::InvalidWeightExtensions);
+            self.weight_extensions = weight_extensions;
+        }
+        if let Some(transfer_hook_program) = update.transfer_hook_program {
+            self.transfer_hook_program = transfer_hook_program;
+        }
+        if let Some(max_actions_per_epoch) = update.max_actions_per_epoch {
+            self.max_actions_per_epoch = max_actions_per_epoch;
+        }
+        if let Some(guardian) = update.guardian {
+            self.guardian = guardian;
+        }
+        if let Some(vote_credits_per_epoch) = update.vote_credits_per_epoch {
+            self.vote_credits_per_epoch = vote_credits_per_epoch;
+        }
+        if let Some(min_agent_bond) = update.min_agent_bond {
+            self.min_agent_bond = min_agent_bond;
+        }
+        if let Some(whistleblower_bps) = update.whistleblower_bps {
+            require!(whistleblower_bps <= MAX_WHISTLEBLOWER_BPS, FastVoteError::InvalidWhistleblowerBounty);
+            self.whistleblower_bps = whistleblower_bps;
+        }
+        if let Some(snapshotter) = update.snapshotter {
+            self.snapshotter = snapshotter;
+        }
+        if let Some(max_window_slots) = update.max_window_slots {
+            // Never below the default window, never above the hard cap the
+            // delegation cooldown is sized for
+            require!(
+                max_window_slots == 0
+                    || (VOTING_WINDOW_SLOTS..=MAX_VOTING_WINDOW_SLOTS).contains(&max_window_slots),
+                FastVoteError::InvalidMaxWindow
+            );
+            self.max_window_slots = max_window_slots;
+        }
+        if let Some(stake_lock_program) = update.stake_lock_program {
+            self.stake_lock_program = stake_lock_program;
+        }
+        if let Some(max_low_priority_actions) = update.max_low_priority_actions {
+            self.max_low_priority_actions = max_low_priority_actions;
+        }
+        if let Some(max_votes_per_action) = update.max_votes_per_action {
+            self.max_votes_per_action = max_votes_per_action;
+        }
+        if let Some(identity_ramp) = update.identity_ramp {
+            require!(identity_ramp.floor_bps <= 10_000, FastVoteError::InvalidIdentityRamp);
+            self.identity_ramp = identity_ramp;
+        }
+        if let Some(fee_rebate) = update.fee_rebate {
+            require!(
+                fee_rebate.full_turnout_bps <= 10_000 && fee_rebate.rebate_bps <= 10_000,
+                FastVoteError::InvalidFeeRebate
+            );
+            self.fee_rebate = fee_rebate;
+        }
+        if let Some(max_actions_per_validator) = update.max_actions_per_validator {
+            self.max_actions_per_validator = max_actions_per_validator;
+        }
+        Ok(())
+    }
+
+    pub fn updated(&self) -> ConfigUpdated {
+        ConfigUpdated {
+            admin: self.admin,
+            weight_mint: self.weight_mint,
+            weight_extensions: self.weight_extensions,
+            transfer_hook_program: self.transfer_hook_program,
+            max_actions_per_epoch: self.max_actions_per_epoch,
+            guardian: self.guardian,
+            vote_credits_per_epoch: self.vote_credits_per_epoch,
+            min_agent_bond: self.min_agent_bond,
+            whistleblower_bps: self.whistleblower_bps,
+            snapshotter: self.snapshotter,
+            max_window_slots: self.max_window_slots,
+            stake_lock_program: self.stake_lock_program,
+            max_low_priority_actions: self.max_low_priority_actions,
+            max_votes_per_action: self.max_votes_per_action,
+            identity_ramp: self.identity_ramp,
+            fee_rebate: self.fee_rebate,
+            max_actions_per_validator: self.max_actions_per_validator,
+        }
+    }
+}
+
+/// Partial update; `None` leaves the field untouched
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ConfigUpdate {
+    pub admin: Option<Pubkey>,
+    pub weight_mint: Option<Pubkey>,
+    pub weight_extensions: Option<u8>,
+    pub transfer_hook_program: Option<Pubkey>,
+    pub max_actions_per_epoch: Option<u32>,
+    pub guardian: Option<Pubkey>,
+    pub vote_credits_per_epoch: Option<u32>,
+    pub min_agent_bond: Option<u64>,
+    pub whistleblower_bps: Option<u16>,
+    pub snapshotter: Option<Pubkey>,
+    pub max_window_slots: Option<u64>,
+    pub stake_lock_program: Option<Pubkey>,
+    pub max_low_priority_actions: Option<u32>,
+    pub max_votes_per_action: Option<u32>,
+    pub identity_ramp: Option<IdentityRamp>,
+    pub fee_rebate: Option<FeeRebate>,
+    pub max_actions_per_validator: Option<u32>,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub weight_mint: Pubkey,
+    pub weight_extensions: u8,
+    pub transfer_hook_program: Pubkey,
+    pub max_actions_per_epoch: u32,
+    pub guardian: Pubkey,
+    pub vote_credits_per_epoch: u32,
+    pub min_agent_bond: u64,
+    pub whistleblower_bps: u16,
+    pub snapshotter: Pubkey,
+    pub max_window_slots: u64,
+    pub stake_lock_program: Pubkey,
+    pub max_low_priority_actions: u32,
+    pub max_votes_per_action: u32,
+    pub identity_ramp: IdentityRamp,
+    pub fee_rebate: FeeRebate,
+    pub max_actions_per_validator: u32,
+}
@@ -0,0 +1,88 @@
+// GlobalConfig changes applied by a passed ConfigChange action
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::{ConfigUpdate, IdentityRamp};
+
+pub const CONFIG_CHANGE_SEED: &[u8] = b"config_change";
+
+/// Delay between finalization and apply_config_change: ~1 hour at 400ms/slot
+pub const CONFIG_CHANGE_TIMELOCK_SLOTS: u64 = 9_000;
+
+/// Lowest threshold a ConfigChange action may set
+pub const CONFIG_CHANGE_MIN_THRESHOLD: u8 = 51;
+
+/// One GlobalConfig field and its new value
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConfigChange {
+    Admin(Pubkey),
+    WeightMint(Pubkey),
+    WeightExtensions(u8),
+    TransferHookProgram(Pubkey),
+    MaxActionsPerEpoch(u32),
+    Guardian(Pubkey),
+    VoteCreditsPerEpoch(u32),
+    MinAgentBond(u64),
+    WhistleblowerBps(u16),
+    Snapshotter(Pubkey),
+    MaxWindowSlots(u64),
+    StakeLockProgram(Pubkey),
+    MaxLowPriorityActions(u32),
+    MaxVotesPerAction(u32),
+    IdentityRamp(IdentityRamp),
+}
+
+impl ConfigChange {
+    /// The action hash a ConfigChange action must carry to apply `self`:
+    /// sha256("config_change" || borsh(self))
+    pub fn action_hash(&self) -> Result<[u8; 32]> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(hashv(&[CONFIG_CHANGE_SEED, &data]).to_bytes())
+    }
+}
+
+impl From<ConfigChange> for ConfigUpdate {
+    fn from(change: ConfigChange) -> Self {
+        let mut update = ConfigUpdate::default();
+        match change {
+            ConfigChange::Admin(admin) => update.admin = Some(admin),
+            ConfigChange::WeightMint(weight_mint) => update.weight_mint = Some(weight_mint),
+            ConfigChange::WeightExtensions(extensions) => update.weight_extensions = Some(extensions),
+            ConfigChange::TransferHookProgram(program) => update.transfer_hook_program = Some(program),
+            ConfigChange::MaxActionsPerEpoch(max) => update.max_actions_per_epoch = Some(max),
+            ConfigChange::Guardian(guardian) => update.guardian = Some(guardian),
+            ConfigChange::VoteCreditsPerEpoch(credits) => update.vote_credits_per_epoch = Some(credits),
+            ConfigChange::MinAgentBond(bond) => update.min_agent_bond = Some(bond),
+            ConfigChange::WhistleblowerBps(bps) => update.whistleblower_bps = Some(bps),
+            ConfigChange::Snapshotter(snapshotter) => update.snapshotter = Some(snapshotter),
+            ConfigChange::MaxWindowSlots(slots) => update.max_window_slots = Some(slots),
+            ConfigChange::StakeLockProgram(program) => update.stake_lock_program = Some(program),
+            ConfigChange::MaxLowPriorityActions(max) => update.max_low_priority_actions = Some(max),
+            ConfigChange::MaxVotesPerAction(max) => update.max_votes_per_action = Some(max),
+            ConfigChange::IdentityRamp(ramp) => update.identity_ramp = Some(ramp),
+        }
+        update
+    }
+}
+
+/// Records an applied change; its `init` stops the action applying twice
+#[account]
+pub struct ConfigChangeReceipt {
+    pub action: Pubkey,    // 32
+    pub applied_slot: u64, // 8
+    pub bump: u8,          // 1
+}
+
+impl ConfigChangeReceipt {
+    pub const LEN: usize = 49; // 8 disc + 41 fields
+}
+
+#[event]
+pub struct ConfigChangeApplied {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub change: ConfigChange,
+}
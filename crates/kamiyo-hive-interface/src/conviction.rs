@@ -0,0 +1,123 @@
+// Conviction proposals and the stakes behind them
+
+use anchor_lang::prelude::*;
+
+pub const PPB: u128 = 1_000_000_000;
+
+/// `retention_ppb` compounded over `slots`, in parts per billion
+fn retention(retention_ppb: u32, mut slots: u64) -> u128 {
+    let (mut result, mut base) = (PPB, retention_ppb as u128);
+    while slots > 0 && result > 0 {
+        if slots & 1 == 1 {
+            result = result * base / PPB;
+        }
+        base = base * base / PPB;
+        slots >>= 1;
+    }
+    result
+}
+
+pub const CONVICTION_SEED: &[u8] = b"conviction";
+pub const CONVICTION_STAKE_SEED: &[u8] = b"conviction_stake";
+
+#[account]
+pub struct ConvictionProposal {
+    pub action_hash: [u8; 32],  // 32
+    pub creator: Pubkey,        // 32
+    pub hive: Pubkey,           // 32 (default = unscoped)
+    /// Conviction, in staked lamports, that executes the proposal
+    pub threshold: u64,         // 8
+    /// Share of the gap to the total staked kept per slot, in parts per billion
+    pub retention_ppb: u32,     // 4
+    pub total_staked: u64,      // 8
+    pub conviction: u64,        // 8
+    pub last_update_slot: u64,  // 8
+    pub executed: bool,         // 1
+    pub executed_slot: u64,     // 8
+    pub bump: u8,               // 1
+}
+
+impl ConvictionProposal {
+    pub const LEN: usize = 150; // 8 disc + 142 fields
+
+    /// Bring conviction up to `slot`. Returns true if this update executed
+    /// the proposal.
+    pub fn accrue(&mut self, slot: u64) -> bool {
+        let factor = retention(self.retention_ppb, slot.saturating_sub(self.last_update_slot));
+        let (total, current) = (self.total_staked as u128, self.conviction as u128);
+        self.conviction = if current < total {
+            total - (total - current) * factor / PPB
+        } else {
+            total + (current - total) * factor / PPB
+        } as u64;
+        self.last_update_slot = slot;
+
+        if self.executed || self.conviction < self.threshold {
+            return false;
+        }
+        self.executed = true;
+        self.executed_slot = slot;
+        true
+    }
+}
+
+#[account]
+pub struct ConvictionStake {
+    pub proposal: Pubkey, // 32
+    pub staker: Pubkey,   // 32
+    pub amount: u64,      // 8
+    pub bump: u8,         // 1
+}
+
+impl ConvictionStake {
+    pub const LEN: usize = 81; // 8 disc + 73 fields
+}
+
+#[event]
+pub struct ConvictionProposalCreated {
+    pub proposal: Pubkey,
+    pub action_hash: [u8; 32],
+    pub creator: Pubkey,
+    pub hive: Pubkey,
+    pub threshold: u64,
+    pub retention_ppb: u32,
+}
+
+#[event]
+pub struct ConvictionStaked {
+    pub proposal: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    pub total_staked: u64,
+    pub conviction: u64,
+}
+
+#[event]
+pub struct ConvictionWithdrawn {
+    pub proposal: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    pub total_staked: u64,
+    pub conviction: u64,
+}
+
+#[event]
+pub struct ConvictionExecuted {
+    pub proposal: Pubkey,
+    pub action_hash: [u8; 32],
+    pub conviction: u64,
+    pub total_staked: u64,
+    pub slot: u64,
+}
+
+impl ConvictionExecuted {
+    pub fn at(proposal: &Account<ConvictionProposal>, slot: u64) -> Self {
+        Self {
+            proposal: proposal.key(),
+            action_hash: proposal.action_hash,
+            conviction: proposal.conviction,
+            total_staked: proposal.total_staked,
+            slot,
+        }
+    }
+}
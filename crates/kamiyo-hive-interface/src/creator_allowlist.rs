@@ -0,0 +1,59 @@
+// Restricted creation: a hive's governance may limit who opens its actions
+// to an allowlist of coordinator agents
+
+use anchor_lang::prelude::*;
+
+pub const CREATOR_ALLOWLIST_SEED: &[u8] = b"creator_allowlist";
+
+/// Creators one allowlist holds; keeps CreatorAllowlist at a fixed size
+pub const MAX_ALLOWED_CREATORS: usize = 16;
+
+#[account]
+pub struct CreatorAllowlist {
+    pub hive: Pubkey,                                // 32
+    /// While set, only listed creators may open the hive's actions
+    pub restricted: bool,                            // 1
+    pub count: u8,                                   // 1
+    pub creators: [Pubkey; MAX_ALLOWED_CREATORS],    // 512
+    pub bump: u8,                                    // 1
+}
+
+impl CreatorAllowlist {
+    pub const LEN: usize = 555; // 8 disc + 547 fields
+
+    pub fn creators(&self) -> &[Pubkey] {
+        &self.creators[..self.count as usize]
+    }
+
+    /// Whether the allowlist stored at `info`, the hive's PDA, lets `creator`
+    /// open actions; a hive that never created one is open
+    pub fn allows(info: &AccountInfo, creator: &Pubkey) -> Result<bool> {
+        if info.data_is_empty() {
+            return Ok(true);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let allowlist = CreatorAllowlist::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(!allowlist.restricted || allowlist.creators().contains(creator))
+    }
+}
+
+#[event]
+pub struct CreatorRestrictionSet {
+    pub hive: Pubkey,
+    pub restricted: bool,
+    pub set_by: Pubkey,
+}
+
+#[event]
+pub struct CreatorAllowed {
+    pub hive: Pubkey,
+    pub creator: Pubkey,
+    pub allowed_by: Pubkey,
+}
+
+#[event]
+pub struct CreatorDisallowed {
+    pub hive: Pubkey,
+    pub creator: Pubkey,
+    pub disallowed_by: Pubkey,
+}
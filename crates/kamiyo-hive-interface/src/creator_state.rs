@@ -0,0 +1,42 @@
+// Per-creator creation accounting, used to rate limit action spam
+
+use anchor_lang::prelude::*;
+
+use crate::FastVoteError;
+
+pub const CREATOR_STATE_SEED: &[u8] = b"creator_state";
+
+#[account]
+pub struct CreatorState {
+    pub creator: Pubkey,       // 32
+    pub epoch: u64,            // 8
+    pub actions_in_epoch: u32, // 4
+    pub bump: u8,              // 1
+}
+
+impl CreatorState {
+    pub const LEN: usize = 53; // 8 disc + 45 fields
+
+    /// Count one more action against the creator's budget for `epoch`,
+    /// resetting the counter when the epoch rolls over. A
+    /// `max_actions_per_epoch` of 0 disables the limit.
+    pub fn charge(&mut self, max_actions_per_epoch: u32, epoch: u64) -> Result<()> {
+        if self.epoch != epoch {
+            self.epoch = epoch;
+            self.actions_in_epoch = 0;
+        }
+
+        if max_actions_per_epoch > 0 {
+            require!(
+                self.actions_in_epoch < max_actions_per_epoch,
+                FastVoteError::CreationRateLimited
+            );
+        }
+
+        self.actions_in_epoch = self
+            .actions_in_epoch
+            .checked_add(1)
+            .ok_or(FastVoteError::VoteOverflow)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+// The dispute window and evidence for challenging a tally
+
+use anchor_lang::prelude::*;
+
+use crate::VoteResult;
+
+/// Slots after finalization during which a result can be challenged (~5 min)
+pub const DISPUTE_WINDOW_SLOTS: u64 = 750;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Evidence {
+    /// The committed counters do not produce the committed result or digest
+    Counters,
+    /// FastVote records, passed as remaining accounts, that the counters
+    /// undercount or that were cast after the deadline
+    Votes,
+    /// A ballot the action's validator sequenced before the deadline that
+    /// has no FastVote; see challenge_exclusion
+    Excluded,
+    /// The action's resolved ActionMirror, passed as the remaining account,
+    /// holds the other validator's signed commit, which counted more ballots
+    /// than the one that stands
+    ConflictingCommit,
+}
+
+#[event]
+pub struct ResultChallenged {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub evidence: Evidence,
+    pub voided_result: VoteResult,
+    pub challenger: Pubkey,
+    /// Validator the action was delegated to; default if unpinned
+    pub validator: Pubkey,
+    pub slashed: u64,
+    /// Part of `slashed` paid to the challenger
+    pub bounty: u64,
+}
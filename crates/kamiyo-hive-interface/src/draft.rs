@@ -0,0 +1,68 @@
+// Proposal drafts, sponsored before they become actions
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::{ActionParams, FastVoteError};
+
+pub fn params_digest(params: &ActionParams) -> Result<[u8; 32]> {
+    let mut data = Vec::new();
+    params.serialize(&mut data)?;
+    Ok(hashv(&[&data]).to_bytes())
+}
+
+pub const PROPOSAL_DRAFT_SEED: &[u8] = b"proposal_draft";
+
+pub const MAX_SPONSORS: usize = 8;
+
+#[account]
+pub struct ProposalDraft {
+    pub creator: Pubkey,                  // 32
+    pub action_hash: [u8; 32],            // 32
+    /// sha256 of the serialized ActionParams the sponsors endorse
+    pub params_digest: [u8; 32],          // 32
+    pub sponsors: [Pubkey; MAX_SPONSORS], // 256
+    pub sponsor_count: u8,                // 1
+    /// Bit i set once `sponsors[i]` has signed
+    pub signed_mask: u8,                  // 1
+    pub required: u8,                     // 1
+    /// Action opened from the draft (default = not yet promoted)
+    pub action: Pubkey,                   // 32
+    pub bump: u8,                         // 1
+}
+
+impl ProposalDraft {
+    pub const LEN: usize = 396; // 8 disc + 388 fields
+
+    pub fn signatures(&self) -> u8 {
+        self.signed_mask.count_ones() as u8
+    }
+
+    /// Check the draft can open `action` with `params`, and mark it promoted.
+    pub fn promote(&mut self, creator: &Pubkey, params: &ActionParams, action: Pubkey) -> Result<()> {
+        require_keys_eq!(self.creator, *creator, FastVoteError::Unauthorized);
+        require!(self.action == Pubkey::default(), FastVoteError::DraftAlreadyPromoted);
+        require!(self.signatures() >= self.required, FastVoteError::DraftNotSponsored);
+        require!(params_digest(params)? == self.params_digest, FastVoteError::DraftParamsMismatch);
+        self.action = action;
+        Ok(())
+    }
+}
+
+#[event]
+pub struct ProposalDraftCreated {
+    pub draft: Pubkey,
+    pub creator: Pubkey,
+    pub action_hash: [u8; 32],
+    pub params_digest: [u8; 32],
+    pub sponsors: Vec<Pubkey>,
+    pub required: u8,
+}
+
+#[event]
+pub struct DraftSponsored {
+    pub draft: Pubkey,
+    pub sponsor: Pubkey,
+    pub signatures: u8,
+    pub required: u8,
+}
@@ -0,0 +1,80 @@
+// Curves weighting early token-weighted ballots
+
+use anchor_lang::prelude::*;
+
+use crate::{ActionKind, MAX_ACTION_KINDS};
+
+pub const EARLY_VOTE_CURVES_SEED: &[u8] = b"early_vote_curves";
+
+/// Largest bonus a curve may start at: 1.2x
+pub const MAX_EARLY_BONUS_BPS: u16 = 2_000;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CurveShape {
+    /// Bonus falls in proportion to the time left
+    Linear,
+    /// Bonus falls with the square of the time left, favouring the first
+    /// few slots
+    Quadratic,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct EarlyVoteCurve {
+    /// Extra weight at the window's start, in basis points (0 = off)
+    pub bonus_bps: u16,    // 2
+    pub shape: CurveShape, // 1
+}
+
+pub const DEFAULT_EARLY_VOTE_CURVE: EarlyVoteCurve = EarlyVoteCurve {
+    bonus_bps: MAX_EARLY_BONUS_BPS,
+    shape: CurveShape::Linear,
+};
+
+impl EarlyVoteCurve {
+    pub const OFF: EarlyVoteCurve = EarlyVoteCurve {
+        bonus_bps: 0,
+        shape: CurveShape::Linear,
+    };
+
+    /// `weight` cast at `slot` in a window from `start` to `end`
+    pub fn apply(&self, weight: u64, slot: u64, start: u64, end: u64) -> u64 {
+        if self.bonus_bps == 0 || slot >= end || end <= start {
+            return weight;
+        }
+        let window = (end - start) as u128;
+        let left = (end - slot.max(start)) as u128;
+        let bonus = match self.shape {
+            CurveShape::Linear => self.bonus_bps as u128 * left / window,
+            CurveShape::Quadratic => self.bonus_bps as u128 * left * left / (window * window),
+        };
+        (weight as u128 * (10_000 + bonus) / 10_000).min(u64::MAX as u128) as u64
+    }
+}
+
+/// Curves per ActionKind, indexed by its discriminant
+#[account]
+pub struct EarlyVoteCurves {
+    pub curves: [Option<EarlyVoteCurve>; MAX_ACTION_KINDS], // 4 * MAX_ACTION_KINDS
+    pub bump: u8,                                            // 1
+}
+
+impl EarlyVoteCurves {
+    pub const LEN: usize = 41; // 8 disc + 33 fields
+
+    /// Curve for `kind` stored at `info`, the EarlyVoteCurves PDA, or the
+    /// default if there is none.
+    pub fn curve_for(info: &AccountInfo, kind: ActionKind) -> Result<EarlyVoteCurve> {
+        if info.data_is_empty() {
+            return Ok(DEFAULT_EARLY_VOTE_CURVE);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let curves = EarlyVoteCurves::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(curves.curves[kind as usize].unwrap_or(DEFAULT_EARLY_VOTE_CURVE))
+    }
+}
+
+#[event]
+pub struct EarlyVoteCurveSet {
+    pub kind: ActionKind,
+    pub curve: Option<EarlyVoteCurve>,
+}
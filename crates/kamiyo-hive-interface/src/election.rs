@@ -0,0 +1,59 @@
+// Leader elections and the terms they install
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+pub const ELECTION_SEED: &[u8] = b"election";
+pub const LEADER_TERM_SEED: &[u8] = b"leader_term";
+
+/// Candidates and term an Election action votes on; option `i` elects
+/// `candidates[i]`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Election {
+    pub candidates: Vec<Pubkey>,
+    /// Slots the winner's term runs from installation
+    pub term_slots: u64,
+}
+
+impl Election {
+    /// The action hash an Election action must carry to install from `self`:
+    /// sha256("election" || borsh(self))
+    pub fn action_hash(&self) -> Result<[u8; 32]> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(hashv(&[ELECTION_SEED, &data]).to_bytes())
+    }
+}
+
+/// A hive's current leader
+#[account]
+pub struct LeaderTerm {
+    pub hive: Pubkey,        // 32
+    pub leader: Pubkey,      // 32
+    pub term_start: u64,     // 8
+    pub term_end: u64,       // 8
+    /// Election action that installed `leader`
+    pub action: Pubkey,      // 32
+    /// Finalization slot of that action; later elections only replace
+    /// earlier ones
+    pub elected_slot: u64,   // 8
+    pub bump: u8,            // 1
+}
+
+impl LeaderTerm {
+    pub const LEN: usize = 129; // 8 disc + 121 fields
+
+    /// Whether `key` leads the hive at `slot`
+    pub fn is_leader(&self, key: &Pubkey, slot: u64) -> bool {
+        self.leader == *key && (self.term_start..self.term_end).contains(&slot)
+    }
+}
+
+#[event]
+pub struct LeaderInstalled {
+    pub hive: Pubkey,
+    pub leader: Pubkey,
+    pub action: Pubkey,
+    pub term_start: u64,
+    pub term_end: u64,
+}
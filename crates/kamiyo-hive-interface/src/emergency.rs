@@ -0,0 +1,45 @@
+// ActionKind, and the short window and supermajority of Emergency actions
+
+use anchor_lang::prelude::*;
+
+/// Voting window for emergency actions: ~4 seconds at 400ms/slot
+pub const EMERGENCY_WINDOW_SLOTS: u64 = 10;
+
+/// Lowest threshold an emergency action may set
+pub const EMERGENCY_MIN_THRESHOLD: u8 = 80;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ActionKind {
+    Standard,
+    /// Short window and supermajority; see EMERGENCY_WINDOW_SLOTS
+    Emergency,
+    /// Changes a GlobalConfig field once passed; see config_change.rs
+    ConfigChange,
+    /// Upgrades a program once passed; see upgrade.rs
+    ProgramUpgrade,
+    /// Opens or cancels a treasury payment stream once passed; see budget.rs
+    Budget,
+    /// Installs the hive's leader once decided; see election.rs
+    Election,
+    /// Pushes a task to the winning assignee's queue once decided; see task.rs
+    TaskAssignment,
+}
+
+impl ActionKind {
+    /// Default voting window for actions of this kind
+    pub fn window_slots(self) -> u64 {
+        match self {
+            ActionKind::Emergency => EMERGENCY_WINDOW_SLOTS,
+            _ => crate::VOTING_WINDOW_SLOTS,
+        }
+    }
+}
+
+#[event]
+pub struct EmergencyActionCreated {
+    pub action: Pubkey,
+    pub action_id: u64,
+    pub hive: Pubkey,
+    pub threshold: u8,
+    pub deadline_slot: u64,
+}
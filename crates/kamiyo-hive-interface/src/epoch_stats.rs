@@ -0,0 +1,65 @@
+// Per-hive, per-epoch tallies of finalized actions
+
+use anchor_lang::prelude::*;
+
+pub const EPOCH_STATS_SEED: &[u8] = b"epoch_stats";
+
+/// ActionKind variants, counted in EpochStats::kinds by discriminant
+pub const ACTION_KINDS: usize = 7;
+
+#[account]
+pub struct EpochStats {
+    /// Default for actions outside any hive
+    pub hive: Pubkey,                  // 32
+    pub epoch: u64,                    // 8
+    pub finalized: u32,                // 4
+    /// Passed or Chosen
+    pub passed: u32,                   // 4
+    pub ballots: u64,                  // 8
+    /// Ballots and electorates of the actions with a known electorate, for
+    /// turnout
+    pub electorate_ballots: u64,       // 8
+    pub electorate: u64,               // 8
+    /// Actions whose QuorumReached was still in their log, and the slots
+    /// from creation to it, summed
+    pub quorum_samples: u32,           // 4
+    pub slots_to_quorum: u64,          // 8
+    /// Finalized actions per ActionKind
+    pub kinds: [u32; ACTION_KINDS],    // 28
+    pub bump: u8,                      // 1
+}
+
+impl EpochStats {
+    pub const LEN: usize = 121; // 8 disc + 113 fields
+
+    /// Ballots over electorate, in bps; 0 with no electorate recorded
+    pub fn turnout_bps(&self) -> u64 {
+        match self.electorate {
+            0 => 0,
+            electorate => (self.electorate_ballots as u128 * 10_000 / electorate as u128) as u64,
+        }
+    }
+
+    /// Share of finalized actions that passed, in bps
+    pub fn pass_rate_bps(&self) -> u64 {
+        match self.finalized {
+            0 => 0,
+            finalized => self.passed as u64 * 10_000 / finalized as u64,
+        }
+    }
+
+    /// Mean slots from creation to quorum over the sampled actions
+    pub fn avg_slots_to_quorum(&self) -> u64 {
+        match self.quorum_samples {
+            0 => 0,
+            samples => self.slots_to_quorum / samples as u64,
+        }
+    }
+}
+
+#[event]
+pub struct EpochStatsInitialized {
+    pub stats: Pubkey,
+    pub hive: Pubkey,
+    pub epoch: u64,
+}
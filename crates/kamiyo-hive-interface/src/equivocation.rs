@@ -0,0 +1,51 @@
+// Signed ballots and reports of agents signing two for one action
+
+use anchor_lang::prelude::*;
+
+pub const EQUIVOCATION_SEED: &[u8] = b"equivocation";
+
+/// Prefix of a signed ballot's message
+pub const BALLOT_DOMAIN: &[u8] = b"kamiyo_ballot";
+
+/// A ballot as the agent signs it off-chain
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Ballot {
+    pub action: Pubkey,
+    /// 1 for, 0 against; the option index on multi-option actions
+    pub choice: u8,
+}
+
+impl Ballot {
+    /// What the agent signs: "kamiyo_ballot" || borsh(self)
+    pub fn message(&self) -> Result<Vec<u8>> {
+        let mut message = BALLOT_DOMAIN.to_vec();
+        self.serialize(&mut message)?;
+        Ok(message)
+    }
+}
+
+/// One per agent per action, so an equivocation is slashed once
+#[account]
+pub struct EquivocationReport {
+    pub action: Pubkey,     // 32
+    pub agent: Pubkey,      // 32
+    pub reporter: Pubkey,   // 32
+    pub reported_slot: u64, // 8
+    pub amount: u64,        // 8
+    pub bump: u8,           // 1
+}
+
+impl EquivocationReport {
+    pub const LEN: usize = 121; // 8 disc + 113 fields
+}
+
+#[event]
+pub struct AgentEquivocated {
+    pub action: Pubkey,
+    pub agent: Pubkey,
+    pub reporter: Pubkey,
+    /// Bond slashed
+    pub amount: u64,
+    /// Part of `amount` paid to the reporter
+    pub bounty: u64,
+}
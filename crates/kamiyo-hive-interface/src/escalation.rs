@@ -0,0 +1,81 @@
+// Escalation of contested results: a hive may mark categories of action as
+// high-stakes, so a pass within a margin of the threshold goes to the slow
+// track for ratification instead of executing
+
+use anchor_lang::prelude::*;
+
+use crate::{ActionKind, FastAction, VoteMode, VoteResult};
+
+pub const CATEGORY_CONFIG_SEED: &[u8] = b"category_config";
+
+/// Margins one CategoryConfig holds, indexed by ActionKind; room for kinds
+/// added later keeps its layout fixed
+pub const CATEGORY_SLOTS: usize = 16;
+
+#[account]
+pub struct CategoryConfig {
+    pub hive: Pubkey,                             // 32
+    /// Percentage points above the threshold within which a pass of each
+    /// kind is contested; 0 leaves the kind to the fast track
+    pub contest_margins: [u8; CATEGORY_SLOTS],    // 16
+    pub bump: u8,                                 // 1
+}
+
+impl CategoryConfig {
+    pub const LEN: usize = 57; // 8 disc + 49 fields
+
+    /// Margin for `kind` stored at `info`, the hive's PDA; 0 if the hive
+    /// never created one
+    pub fn contest_margin(info: &AccountInfo, kind: ActionKind) -> Result<u8> {
+        if info.data_is_empty() {
+            return Ok(0);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let config = CategoryConfig::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(config.contest_margins[kind as usize])
+    }
+}
+
+/// The action's tallied result, Contested if it passed with an approval
+/// share under `threshold + margin`. Multi-option and optimistic actions are
+/// never contested: neither passes on an approval share.
+pub fn escalate(action: &FastAction, margin: u8) -> Result<VoteResult> {
+    Ok(match action.result {
+        VoteResult::Passed { tier }
+            if margin > 0
+                && action.option_count == 0
+                && action.vote_mode != VoteMode::Optimistic
+                && action.approval_bps()? < action.threshold_bps as u64 + margin as u64 * 100 =>
+        {
+            VoteResult::Contested { tier }
+        }
+        ref result => result.clone(),
+    })
+}
+
+#[event]
+pub struct ContestMarginSet {
+    pub hive: Pubkey,
+    pub kind: ActionKind,
+    pub margin: u8,
+    pub set_by: Pubkey,
+}
+
+#[event]
+pub struct ResultContested {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub approval_pct: u64,
+    pub threshold: u8,
+    pub margin: u8,
+}
+
+#[event]
+pub struct ContestResolved {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub slow_action: Pubkey,
+    pub result: VoteResult,
+}
@@ -0,0 +1,18 @@
+// Grace period before an undecided action can be expired
+
+use anchor_lang::prelude::*;
+
+/// Slots past the deadline before an unfinalized action may be closed (~10
+/// min); no sooner than force_undelegate may bring a delegated one back
+pub const EXPIRY_GRACE_SLOTS: u64 = 1_500;
+
+#[event]
+pub struct ActionExpired {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub creator: Pubkey,
+    /// Lamports returned to the creator
+    pub reclaimed: u64,
+    pub slot: u64,
+}
@@ -0,0 +1,40 @@
+// Deadline extensions co-signed by an action's voters
+
+use anchor_lang::prelude::*;
+
+/// Most extensions an ExtensionPolicy may allow
+pub const MAX_DEADLINE_EXTENSIONS: u8 = 3;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ExtensionPolicy {
+    /// Extensions the action may be granted (0 = deadline is fixed)
+    pub max_extensions: u8, // 1
+    /// Share of the ballots cast so far whose voters must co-sign, in basis
+    /// points; more than half
+    pub support_bps: u16,   // 2
+}
+
+impl ExtensionPolicy {
+    pub const OFF: ExtensionPolicy = ExtensionPolicy {
+        max_extensions: 0,
+        support_bps: 0,
+    };
+
+    /// Whether `supporters` of `vote_count` voters are enough
+    pub fn supported(&self, supporters: u32, vote_count: u32) -> bool {
+        supporters as u64 * 10_000 >= self.support_bps as u64 * vote_count as u64
+    }
+}
+
+#[event]
+pub struct DeadlineExtended {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub old_deadline_slot: u64,
+    pub deadline_slot: u64,
+    /// Extensions granted so far, this one included
+    pub extensions: u8,
+    /// Voters who co-signed, out of `vote_count`
+    pub supporters: u32,
+    pub vote_count: u32,
+}
@@ -0,0 +1,62 @@
+// Actions run across several hives and settled together
+
+use anchor_lang::prelude::*;
+
+use crate::VoteResult;
+
+pub const FEDERATED_ACTION_SEED: &[u8] = b"federated_action";
+
+/// Child actions, one per hive, a federated action can weigh
+pub const MAX_FEDERATED_HIVES: usize = 8;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct FederatedChild {
+    pub action: Pubkey, // 32
+    /// Hive the child is scoped to; checked against the action
+    pub hive: Pubkey,   // 32
+    pub weight: u16,    // 2
+}
+
+#[account]
+pub struct FederatedAction {
+    pub action_hash: [u8; 32],                            // 32
+    pub creator: Pubkey,                                  // 32
+    /// Percent of the total hive weight that must pass (1-100)
+    pub threshold: u8,                                    // 1
+    pub child_count: u8,                                  // 1
+    pub children: [FederatedChild; MAX_FEDERATED_HIVES],  // 66 * MAX_FEDERATED_HIVES
+    /// Weight of the children that passed; set on settlement
+    pub weight_passed: u32,                               // 4
+    pub result: VoteResult,                               // 2
+    pub settled_slot: u64,                                // 8
+    pub bump: u8,                                         // 1
+}
+
+impl FederatedAction {
+    pub const LEN: usize = 617; // 8 disc + 609 fields
+
+    pub fn children(&self) -> &[FederatedChild] {
+        &self.children[..self.child_count as usize]
+    }
+
+    pub fn total_weight(&self) -> u32 {
+        self.children().iter().map(|c| c.weight as u32).sum()
+    }
+}
+
+#[event]
+pub struct FederatedActionCreated {
+    pub federated_action: Pubkey,
+    pub action_hash: [u8; 32],
+    pub creator: Pubkey,
+    pub threshold: u8,
+    pub children: Vec<FederatedChild>,
+}
+
+#[event]
+pub struct FederatedActionSettled {
+    pub federated_action: Pubkey,
+    pub result: VoteResult,
+    pub weight_passed: u32,
+    pub total_weight: u32,
+}
@@ -0,0 +1,72 @@
+// Tallies of EVM hives brought in through Wormhole
+
+use anchor_lang::prelude::*;
+
+pub const FOREIGN_HIVE_SEED: &[u8] = b"foreign_hive";
+
+/// Wormhole core bridge; owns the PostedVAA accounts it has verified
+pub const WORMHOLE_CORE_BRIDGE_ID: Pubkey = pubkey!("worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth");
+
+/// Payload id of a foreign tally
+pub const FOREIGN_TALLY_PAYLOAD_ID: u8 = 1;
+
+/// An EVM hive contract whose tallies a local hive's actions accept
+#[account]
+pub struct ForeignHive {
+    pub hive: Pubkey,           // 32
+    /// Wormhole chain id of the contract's chain
+    pub chain: u16,             // 2
+    /// The contract's address, left-padded to 32 bytes
+    pub emitter: [u8; 32],      // 32
+    /// Most weight one of its tallies counts for on token-weighted actions
+    pub max_weight: u64,        // 8
+    pub bump: u8,               // 1
+}
+
+impl ForeignHive {
+    pub const LEN: usize = 83; // 8 disc + 75 fields
+}
+
+/// The VAA payload an EVM hive emits for a Solana action, big-endian as
+/// `abi.encodePacked(uint8 1, bytes32 action, bool stance, uint64 weight)`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ForeignTally {
+    pub action: Pubkey,
+    /// true for, false against
+    pub stance: bool,
+    pub weight: u64,
+}
+
+impl ForeignTally {
+    pub const LEN: usize = 42;
+
+    pub fn parse(payload: &[u8]) -> Option<ForeignTally> {
+        if payload.len() != Self::LEN || payload[0] != FOREIGN_TALLY_PAYLOAD_ID || payload[33] > 1 {
+            return None;
+        }
+        Some(ForeignTally {
+            action: Pubkey::try_from(&payload[1..33]).ok()?,
+            stance: payload[33] == 1,
+            weight: u64::from_be_bytes(payload[34..42].try_into().ok()?),
+        })
+    }
+}
+
+#[event]
+pub struct ForeignHiveRegistered {
+    pub hive: Pubkey,
+    pub chain: u16,
+    pub emitter: [u8; 32],
+    pub max_weight: u64,
+}
+
+#[event]
+pub struct ForeignTallySubmitted {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub foreign_hive: Pubkey,
+    pub sequence: u64,
+    pub stance: bool,
+    /// Weight counted, after the ForeignHive's cap
+    pub weight: u64,
+}
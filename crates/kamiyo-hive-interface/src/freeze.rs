@@ -0,0 +1,62 @@
+// Hive kill switch: a passed Emergency action of a hive may freeze its
+// action creation and voting until another one unfreezes it. The unfreeze
+// action itself stays open to creation and ballots throughout.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::{ActionKind, FastVoteError};
+
+const HIVE_FREEZE_DOMAIN: &[u8] = b"hive_freeze";
+
+pub const HIVE_FREEZE_SEED: &[u8] = b"hive_freeze";
+
+#[account]
+pub struct HiveFreeze {
+    pub hive: Pubkey,        // 32
+    pub frozen: bool,        // 1
+    /// The Emergency action that last froze or unfroze the hive
+    pub action: Pubkey,      // 32
+    /// Its finalized slot; an older action cannot overturn it
+    pub finalized_slot: u64, // 8
+    pub bump: u8,            // 1
+}
+
+impl HiveFreeze {
+    pub const LEN: usize = 82; // 8 disc + 74 fields
+
+    /// The action hash an Emergency action of `hive` must carry to freeze
+    /// it, or unfreeze it: sha256("hive_freeze" || hive || frozen)
+    pub fn action_hash(hive: &Pubkey, frozen: bool) -> [u8; 32] {
+        hashv(&[HIVE_FREEZE_DOMAIN, hive.as_ref(), &[frozen as u8]]).to_bytes()
+    }
+
+    /// Whether an action of `kind` carrying `action_hash` unfreezes `hive`
+    pub fn unfreezes(hive: &Pubkey, kind: ActionKind, action_hash: &[u8; 32]) -> bool {
+        kind == ActionKind::Emergency && *action_hash == Self::action_hash(hive, false)
+    }
+
+    /// Fails if the freeze stored at `info`, the hive's PDA, is set, unless
+    /// the action of `kind` carrying `action_hash` is the hive's unfreeze; a
+    /// hive that was never frozen is live
+    pub fn require_live(info: &AccountInfo, kind: ActionKind, action_hash: &[u8; 32]) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let freeze = HiveFreeze::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(
+            !freeze.frozen || Self::unfreezes(&freeze.hive, kind, action_hash),
+            FastVoteError::HiveFrozen
+        );
+        Ok(())
+    }
+}
+
+#[event]
+pub struct HiveFreezeSet {
+    pub hive: Pubkey,
+    pub frozen: bool,
+    pub action: Pubkey,
+    pub action_id: u64,
+}
@@ -0,0 +1,17 @@
+// Guardian vetoes during the dispute window
+
+use anchor_lang::prelude::*;
+
+use crate::VoteResult;
+
+pub const MAX_VETO_REASON_LEN: usize = 256;
+
+#[event]
+pub struct ActionVetoed {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub vetoed_result: VoteResult,
+    pub guardian: Pubkey,
+    pub reason: String,
+}
@@ -0,0 +1,46 @@
+// One live action per payload: ActionHashRecord PDAs keyed by action_hash
+
+use anchor_lang::prelude::*;
+
+use crate::{FastAction, FastVoteError};
+
+pub const ACTION_HASH_SEED: &[u8] = b"action_hash";
+
+/// Slots after the deadline before a hash frees up without proof of
+/// finalization, leaving room for the tally to land
+pub const HASH_REUSE_GRACE_SLOTS: u64 = 150;
+
+#[account]
+pub struct ActionHashRecord {
+    pub action_hash: [u8; 32], // 32
+    pub action: Pubkey,        // 32
+    pub expires_slot: u64,     // 8
+    pub bump: u8,              // 1
+}
+
+impl ActionHashRecord {
+    pub const LEN: usize = 81; // 8 disc + 73 fields
+
+    /// Point the record at `action`. Fails while a previous action for the same
+    /// hash is still live, unless that action is supplied and already finalized.
+    pub fn claim(
+        &mut self,
+        action_hash: [u8; 32],
+        action: Pubkey,
+        deadline_slot: u64,
+        previous_action: Option<&FastAction>,
+        current_slot: u64,
+    ) -> Result<()> {
+        if self.action != Pubkey::default() && current_slot <= self.expires_slot {
+            let finalized = previous_action.map(|prev| prev.executed).unwrap_or(false);
+            require!(finalized, FastVoteError::DuplicateActionHash);
+        }
+
+        self.action_hash = action_hash;
+        self.action = action;
+        self.expires_slot = deadline_slot
+            .checked_add(HASH_REUSE_GRACE_SLOTS)
+            .ok_or(FastVoteError::SlotOverflow)?;
+        Ok(())
+    }
+}
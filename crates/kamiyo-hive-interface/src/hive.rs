@@ -0,0 +1,78 @@
+// Hives: an agent team's accounts and their per-hive overrides
+
+use anchor_lang::prelude::*;
+
+use crate::{ApprovalRounding, CancellationPolicy, RoleMember, CATEGORY_SLOTS};
+
+pub const HIVE_SEED: &[u8] = b"hive";
+
+/// Settings a hive overrides for its own actions; 0 keeps the global value
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct HiveOverrides {
+    /// Actions one creator may open per epoch in this hive
+    pub max_actions_per_epoch: u32, // 4
+    /// Votes a new action needs when its params leave the quorum unset
+    pub default_quorum: u32,        // 4
+}
+
+#[account]
+pub struct Hive {
+    pub hive: Pubkey,              // 32
+    pub authority: Pubkey,         // 32
+    /// Hash of the hive's display name
+    pub name_hash: [u8; 32],       // 32
+    /// Merkle root of the hive's member registry, kept off-chain
+    pub member_root: [u8; 32],     // 32
+    pub overrides: HiveOverrides,  // 8
+    pub bump: u8,                  // 1
+}
+
+impl Hive {
+    pub const LEN: usize = 145; // 8 disc + 137 fields
+}
+
+/// Partial update; `None` fields are left unchanged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, PartialEq, Eq, Debug)]
+pub struct HiveUpdate {
+    pub authority: Option<Pubkey>,
+    pub member_root: Option<[u8; 32]>,
+    pub overrides: Option<HiveOverrides>,
+}
+
+/// Everything initialize_hive sets up a hive with
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct HiveInit {
+    pub name_hash: [u8; 32],
+    pub authority: Pubkey,
+    pub member_root: [u8; 32],
+    pub overrides: HiveOverrides,
+    /// Role members besides the hive key, which is always the first Admin
+    pub members: Vec<RoleMember>,
+    pub cancellation_policy: CancellationPolicy,
+    pub approval_rounding: ApprovalRounding,
+    /// Contest margins by ActionKind, as set_contest_margin sets them
+    pub contest_margins: [u8; CATEGORY_SLOTS],
+    /// Share of each creation fee in the hive diverted to its insurance pool
+    pub insurance_fee_bps: u16,
+}
+
+#[event]
+pub struct HiveCreated {
+    pub hive: Pubkey,
+    pub authority: Pubkey,
+    pub name_hash: [u8; 32],
+    pub hive_account: Pubkey,
+    /// The accounts below are default when create_hive made only the Hive
+    pub roles: Pubkey,
+    pub hive_config: Pubkey,
+    pub category_config: Pubkey,
+    pub insurance_pool: Pubkey,
+}
+
+#[event]
+pub struct HiveUpdated {
+    pub hive: Pubkey,
+    pub authority: Pubkey,
+    pub member_root: [u8; 32],
+    pub overrides: HiveOverrides,
+}
@@ -0,0 +1,98 @@
+// Per-hive settings: the cancellation policy and how approval is rounded
+
+use anchor_lang::prelude::*;
+
+use crate::FastAction;
+
+pub const HIVE_CONFIG_SEED: &[u8] = b"hive_config";
+
+/// When an action may still be cancelled. Every policy closes cancellation
+/// at least as early as `Standard`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CancellationPolicy {
+    /// Until the action reaches its quorum or half its window has passed
+    Standard,
+    /// Only before the first vote
+    BeforeFirstVote,
+    /// Never
+    Disabled,
+}
+
+impl CancellationPolicy {
+    pub fn allows(self, action: &FastAction, slot: u64) -> bool {
+        let window = action.deadline_slot.saturating_sub(action.created_slot);
+        let standard = action.vote_count < action.quorum && slot.saturating_sub(action.created_slot) <= window / 2;
+        match self {
+            CancellationPolicy::Standard => standard,
+            CancellationPolicy::BeforeFirstVote => standard && action.vote_count == 0,
+            CancellationPolicy::Disabled => false,
+        }
+    }
+}
+
+/// How a share is rounded to whole basis points when approval is tallied
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ApprovalRounding {
+    /// Down, as whole percentages were before basis points
+    #[default]
+    Floor,
+    /// To nearest, ties to even
+    HalfEven,
+    /// Up
+    Ceiling,
+}
+
+#[account]
+pub struct HiveConfig {
+    pub hive: Pubkey,                              // 32
+    pub cancellation_policy: CancellationPolicy,   // 1
+    pub bump: u8,                                  // 1
+    /// Copied into each action the hive creates
+    pub approval_rounding: ApprovalRounding,       // 1
+}
+
+impl HiveConfig {
+    pub const LEN: usize = 43; // 8 disc + 35 fields
+    /// Size before `approval_rounding`; migrate_hive_config grows it
+    pub const LEGACY_LEN: usize = 42;
+
+    /// The HiveConfig at `info`, or none if the hive never created one.
+    /// Reads legacy configs as if already migrated
+    pub fn load(info: &AccountInfo) -> Result<Option<HiveConfig>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let mut data = info.try_borrow_data()?.to_vec();
+        if data.len() == Self::LEGACY_LEN {
+            data.resize(Self::LEN, 0);
+        }
+        Ok(Some(HiveConfig::try_deserialize(&mut &data[..])?))
+    }
+
+    /// Policy stored at `info`, the hive's config PDA, or the default if the
+    /// hive never created one.
+    pub fn cancellation_policy(info: &AccountInfo) -> Result<CancellationPolicy> {
+        Ok(Self::load(info)?.map_or(CancellationPolicy::Standard, |c| c.cancellation_policy))
+    }
+
+    /// Rounding stored at `info`, the hive's config PDA, or Floor if the
+    /// hive never created one.
+    pub fn approval_rounding(info: &AccountInfo) -> Result<ApprovalRounding> {
+        Ok(Self::load(info)?.map_or(ApprovalRounding::Floor, |c| c.approval_rounding))
+    }
+}
+
+#[event]
+pub struct CancellationPolicySet {
+    pub hive: Pubkey,
+    pub policy: CancellationPolicy,
+    pub set_by: Pubkey,
+}
+
+#[event]
+pub struct ApprovalRoundingSet {
+    pub hive: Pubkey,
+    pub rounding: ApprovalRounding,
+    pub set_by: Pubkey,
+}
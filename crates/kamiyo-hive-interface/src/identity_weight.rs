@@ -0,0 +1,57 @@
+// Vote weight from the hive's agent-identity assets
+
+use anchor_lang::prelude::*;
+
+pub const MPL_CORE_PROGRAM_ID: Pubkey = pubkey!("CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d");
+
+pub const IDENTITY_WEIGHTS_SEED: &[u8] = b"identity_weights";
+pub const IDENTITY_CLAIM_SEED: &[u8] = b"identity_claim";
+
+/// Longest attribute key or value IdentityWeights stores
+pub const MAX_IDENTITY_ATTRIBUTE_LEN: usize = 32;
+pub const MAX_IDENTITY_TIERS: usize = 8;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct IdentityTier {
+    /// Attribute value, e.g. "gold"
+    pub value: String,
+    pub weight: u64,
+}
+
+/// How identity-weighted ballots are weighed: the value of `attribute` on
+/// the voter's asset in `collection`, looked up in `tiers`
+#[account]
+pub struct IdentityWeights {
+    pub collection: Pubkey,       // 32
+    pub attribute: String,        // 4 + MAX_IDENTITY_ATTRIBUTE_LEN
+    pub tiers: Vec<IdentityTier>, // 4 + (4 + MAX_IDENTITY_ATTRIBUTE_LEN + 8) * MAX_IDENTITY_TIERS
+    pub bump: u8,                 // 1
+}
+
+impl IdentityWeights {
+    pub const LEN: usize = 433; // 8 disc + 425 fields
+
+    /// Weight of an asset whose attribute is `value`; None when unmapped
+    pub fn weight_of(&self, value: &str) -> Option<u64> {
+        self.tiers.iter().find(|tier| tier.value == value).map(|tier| tier.weight)
+    }
+}
+
+/// Marks an identity asset as used on an action, so it cannot vote again
+/// from another wallet
+#[account]
+pub struct IdentityClaim {
+    pub voter: Pubkey, // 32
+    pub bump: u8,      // 1
+}
+
+impl IdentityClaim {
+    pub const LEN: usize = 41; // 8 disc + 33 fields
+}
+
+#[event]
+pub struct IdentityWeightsSet {
+    pub collection: Pubkey,
+    pub attribute: String,
+    pub tiers: Vec<IdentityTier>,
+}
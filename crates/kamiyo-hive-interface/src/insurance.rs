@@ -0,0 +1,94 @@
+// Hive insurance pools and the claims they pay on voided actions
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+const INSURANCE_CLAIM_DOMAIN: &[u8] = b"insurance_claim";
+
+pub const INSURANCE_POOL_SEED: &[u8] = b"insurance_pool";
+pub const INSURANCE_CLAIM_SEED: &[u8] = b"insurance_claim";
+
+#[account]
+pub struct InsurancePool {
+    pub hive: Pubkey,          // 32
+    /// Share of each creation fee in the hive diverted here, in bps
+    pub fee_bps: u16,          // 2
+    /// Fee cuts and deposits received
+    pub total_received: u64,   // 8
+    pub total_paid: u64,       // 8
+    pub claims_paid: u32,      // 4
+    pub bump: u8,              // 1
+}
+
+impl InsurancePool {
+    pub const LEN: usize = 63; // 8 disc + 55 fields
+}
+
+/// A claim for damage from `action`, an action of the hive later voided by
+/// a challenge; closed, to the claimant, once resolved
+#[account]
+pub struct InsuranceClaim {
+    pub hive: Pubkey,            // 32
+    pub action: Pubkey,          // 32
+    pub claimant: Pubkey,        // 32
+    pub amount: u64,             // 8
+    /// Hash of the claimant's off-chain account of the damage
+    pub evidence_hash: [u8; 32], // 32
+    pub filed_slot: u64,         // 8
+    pub bump: u8,                // 1
+}
+
+impl InsuranceClaim {
+    pub const LEN: usize = 153; // 8 disc + 145 fields
+
+    /// The action hash an Emergency action must carry to resolve the claim
+    /// at `claim`: sha256("insurance_claim" || claim || amount || filed_slot).
+    /// The filing slot keeps a resolution from carrying over to a claim
+    /// refiled at the same address.
+    pub fn resolution_hash(&self, claim: &Pubkey) -> [u8; 32] {
+        hashv(&[
+            INSURANCE_CLAIM_DOMAIN,
+            claim.as_ref(),
+            &self.amount.to_le_bytes(),
+            &self.filed_slot.to_le_bytes(),
+        ])
+        .to_bytes()
+    }
+}
+
+#[event]
+pub struct InsurancePoolInitialized {
+    pub hive: Pubkey,
+    pub fee_bps: u16,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct InsuranceFunded {
+    pub hive: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ClaimFiled {
+    pub claim: Pubkey,
+    pub hive: Pubkey,
+    pub action: Pubkey,
+    pub claimant: Pubkey,
+    pub amount: u64,
+    pub evidence_hash: [u8; 32],
+    /// Action hash of the Emergency action that decides the claim
+    pub resolution_hash: [u8; 32],
+}
+
+#[event]
+pub struct ClaimResolved {
+    pub claim: Pubkey,
+    pub hive: Pubkey,
+    pub claimant: Pubkey,
+    pub resolution_action: Pubkey,
+    pub approved: bool,
+    /// 0 unless approved
+    pub paid: u64,
+}
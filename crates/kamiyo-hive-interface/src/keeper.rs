@@ -0,0 +1,66 @@
+// Execution schedules keepers crank for a reward
+
+use anchor_lang::prelude::*;
+
+pub const EXECUTION_SCHEDULE_SEED: &[u8] = b"execution_schedule";
+
+/// Callback programs and accounts a schedule can hold in total
+pub const MAX_SCHEDULED_ACCOUNTS: usize = 24;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ScheduledAccount {
+    pub pubkey: Pubkey,    // 32
+    pub is_writable: bool, // 1
+}
+
+#[account]
+pub struct ExecutionSchedule {
+    pub action: Pubkey,                                       // 32
+    pub funder: Pubkey,                                       // 32
+    /// Lamports on top of rent, paid to the keeper with the rent
+    pub reward: u64,                                          // 8
+    pub account_count: u8,                                    // 1
+    /// execute_action's remaining accounts: for each callback, its program
+    /// and then its registered accounts
+    pub accounts: [ScheduledAccount; MAX_SCHEDULED_ACCOUNTS], // 792
+    pub bump: u8,                                             // 1
+}
+
+impl ExecutionSchedule {
+    pub const LEN: usize = 874; // 8 disc + 866 fields
+
+    pub fn remaining_accounts(&self) -> Vec<AccountMeta> {
+        self.accounts[..self.account_count as usize]
+            .iter()
+            .map(|a| AccountMeta {
+                pubkey: a.pubkey,
+                is_signer: false,
+                is_writable: a.is_writable,
+            })
+            .collect()
+    }
+}
+
+#[event]
+pub struct ExecutionScheduled {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub funder: Pubkey,
+    /// Total reward, including earlier top-ups
+    pub reward: u64,
+}
+
+#[event]
+pub struct ExecutionUnscheduled {
+    pub action: Pubkey,
+    pub funder: Pubkey,
+}
+
+#[event]
+pub struct KeeperPaid {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub keeper: Pubkey,
+    /// Reward plus the schedule's rent
+    pub amount: u64,
+}
@@ -0,0 +1,1211 @@
+//! Account, event and error types of the KAMIYO fast voting program, without
+//! its instructions. The program depends on this crate for them, so services
+//! that read its accounts or decode its events can depend on it as well and
+//! stay in step with the deployed layouts instead of copying definitions.
+//!
+//! Anchor's `#[account]` and `#[event]` derive the same discriminators here
+//! as in the program, and `ID` is the program's, so account owner checks hold.
+
+use anchor_lang::prelude::*;
+
+pub mod action_log;
+pub mod activity;
+pub mod agent_registry;
+pub mod amend;
+pub mod attestation;
+pub mod audit;
+pub mod authority;
+pub mod budget;
+pub mod callbacks;
+pub mod checkpoint;
+pub mod compact_vote;
+pub mod compressed_vote;
+pub mod config;
+pub mod config_change;
+pub mod conviction;
+pub mod creator_allowlist;
+pub mod creator_state;
+pub mod dispute;
+pub mod draft;
+pub mod early_vote;
+pub mod election;
+pub mod emergency;
+pub mod epoch_stats;
+pub mod equivocation;
+pub mod escalation;
+pub mod expiry;
+pub mod extension;
+pub mod federation;
+pub mod foreign_tally;
+pub mod freeze;
+pub mod guardian;
+pub mod hash_registry;
+pub mod hive;
+pub mod hive_config;
+pub mod identity_weight;
+pub mod insurance;
+pub mod keeper;
+pub mod liveness;
+pub mod market;
+pub mod membership;
+pub mod metadata;
+pub mod metrics;
+pub mod migration;
+pub mod optimistic;
+pub mod options;
+pub mod outcome;
+pub mod priority;
+pub mod privacy;
+pub mod ranked;
+pub mod redundancy;
+pub mod relayers;
+pub mod rejection;
+pub mod result_proof;
+pub mod rewards;
+pub mod roles;
+pub mod runoff;
+pub mod sealed;
+pub mod sequencing;
+pub mod slashing;
+pub mod staging;
+pub mod stake_lock;
+pub mod standing;
+pub mod standings_board;
+pub mod tags;
+pub mod tally;
+pub mod task;
+pub mod template;
+pub mod token_weight;
+pub mod upgrade;
+pub mod validators;
+pub mod vote_credits;
+pub mod vote_delegation;
+pub mod vote_reason;
+pub mod vote_receipt;
+pub mod vote_rewards;
+pub mod voter_group;
+pub mod weight_cap;
+pub mod weight_snapshot;
+pub mod weight_strategy;
+
+pub use action_log::*;
+pub use activity::*;
+pub use agent_registry::*;
+pub use amend::*;
+pub use attestation::*;
+pub use audit::*;
+pub use authority::*;
+pub use budget::*;
+pub use callbacks::*;
+pub use checkpoint::*;
+pub use compact_vote::*;
+pub use compressed_vote::*;
+pub use config::*;
+pub use config_change::*;
+pub use conviction::*;
+pub use creator_allowlist::*;
+pub use creator_state::*;
+pub use dispute::*;
+pub use draft::*;
+pub use early_vote::*;
+pub use election::*;
+pub use emergency::*;
+pub use epoch_stats::*;
+pub use equivocation::*;
+pub use escalation::*;
+pub use expiry::*;
+pub use extension::*;
+pub use federation::*;
+pub use foreign_tally::*;
+pub use freeze::*;
+pub use guardian::*;
+pub use hash_registry::*;
+pub use hive::*;
+pub use hive_config::*;
+pub use identity_weight::*;
+pub use insurance::*;
+pub use keeper::*;
+pub use liveness::*;
+pub use market::*;
+pub use membership::*;
+pub use metadata::*;
+pub use metrics::*;
+pub use migration::*;
+pub use optimistic::*;
+pub use options::*;
+pub use outcome::*;
+pub use priority::*;
+pub use privacy::*;
+pub use ranked::*;
+pub use redundancy::*;
+pub use relayers::*;
+pub use rejection::*;
+pub use result_proof::*;
+pub use rewards::*;
+pub use roles::*;
+pub use runoff::*;
+pub use sealed::*;
+pub use sequencing::*;
+pub use slashing::*;
+pub use staging::*;
+pub use stake_lock::*;
+pub use standing::*;
+pub use standings_board::*;
+pub use tags::*;
+pub use task::*;
+pub use template::*;
+pub use token_weight::*;
+pub use upgrade::*;
+pub use validators::*;
+pub use vote_credits::*;
+pub use vote_delegation::*;
+pub use vote_reason::*;
+pub use vote_receipt::*;
+pub use vote_rewards::*;
+pub use voter_group::*;
+pub use weight_cap::*;
+pub use weight_snapshot::*;
+pub use weight_strategy::*;
+
+declare_id!("AakwnBstczs5KC2jKPfBuFLQZADXrx4oPH8FtJbhPxwA");
+
+pub const FAST_ACTION_SEED: &[u8] = b"fast_action";
+pub const FAST_VOTE_SEED: &[u8] = b"fast_vote";
+pub const ACTION_COUNTER_SEED: &[u8] = b"action_counter";
+
+/// Voting window: ~30 seconds at 400ms/slot
+pub const VOTING_WINDOW_SLOTS: u64 = 75;
+
+/// Quorum requirement, unless the action sets an adaptive quorum
+pub const MIN_VOTES_FOR_QUORUM: u32 = 2;
+
+/// Max votes per action (prevents DoS via vote spam), unless the config
+/// sets its own
+pub const MAX_VOTES_PER_ACTION: u32 = 10_000;
+
+/// Graded tiers an action can declare above its base threshold
+pub const MAX_EXTRA_TIERS: usize = 3;
+
+/// Prerequisite actions that must pass before an action can be tallied
+pub const MAX_DEPENDENCIES: usize = 4;
+
+/// Voters an action may exclude from its ballot
+pub const MAX_EXCLUSIONS: usize = 4;
+
+/// Actions one create_actions_batch may open; the transaction size usually
+/// binds first
+pub const MAX_BATCH_ACTIONS: usize = 32;
+
+/// Layout changes are append-only; see `migration`
+#[account]
+pub struct FastAction {
+    pub version: u8,             // 1
+    pub action_id: u64,          // 8
+    pub action_hash: [u8; 32],   // 32
+    pub description_hash: [u8; 32], // 32
+    pub creator: Pubkey,         // 32
+    pub threshold: u8,           // 1
+    pub votes_for: u32,          // 4
+    pub votes_against: u32,      // 4
+    pub vote_count: u32,         // 4
+    pub created_slot: u64,       // 8
+    pub deadline_slot: u64,      // 8
+    pub executed: bool,          // 1
+    pub result: VoteResult,      // 2 (Passed carries its tier)
+    pub bump: u8,                // 1
+    pub vote_mode: VoteMode,     // 1
+    pub weight_for: u64,         // 8
+    pub weight_against: u64,     // 8
+    pub hive: Pubkey,            // 32
+    pub tier_thresholds: [u8; MAX_EXTRA_TIERS], // 3
+    pub dependency_count: u8,    // 1
+    pub dependencies: [Pubkey; MAX_DEPENDENCIES], // 128
+    pub finalized_slot: u64,     // 8
+    pub result_digest: [u8; 32], // 32
+    pub last_checkpoint_slot: u64, // 8
+    pub last_checkpoint_votes: u32, // 4
+    pub delegated_validator: Pubkey, // 32 (default = unpinned or never delegated)
+    pub session_recorded: bool,  // 1
+    /// Hive role members at creation; 0 for unscoped actions
+    pub electorate: u32,         // 4
+    /// Lamports held by the action for its creator (Optimistic mode)
+    pub bond: u64,               // 8
+    pub quorum: u32,             // 4
+    pub quorum_floor: u32,       // 4
+    pub quorum_grace_slots: u64, // 8
+    pub min_turnout_bps: u16,    // 2
+    pub option_count: u8,        // 1
+    pub option_weights: [u64; MAX_OPTIONS], // 64
+    pub ranked: bool,            // 1
+    /// Bit per option knocked out of a ranked-choice count
+    pub eliminated_mask: u8,     // 1
+    /// Option whose ballots are still being redistributed, or NO_ELIMINATION
+    pub pending_elimination: u8, // 1
+    pub round: u8,               // 1
+    /// Ranked ballots with no surviving choice left
+    pub exhausted_ballots: u32,  // 4
+    /// Failed multi-option action this one is a runoff of
+    pub parent_action: Pubkey,   // 32
+    /// Runoff spawned from this action, once it failed
+    pub runoff_action: Pubkey,   // 32
+    /// Price feed gating execution; default when ungated
+    pub market_feed: Pubkey,     // 32
+    pub market_program: Pubkey,  // 32
+    pub market_price_offset: u16, // 2
+    pub market_min_price: u64,   // 8
+    pub market_cleared: bool,    // 1
+    /// May cancel and amend; starts as `creator`
+    pub authority: Pubkey,       // 32
+    /// ProposalDraft the action was promoted from (default = created directly)
+    pub draft: Pubkey,           // 32
+    pub kind: ActionKind,        // 1
+    /// Most weight one token-weighted ballot counts (0 = uncapped)
+    pub max_weight_per_voter: u64, // 8
+    /// Ballots each AgentClass may cast (0 = unlimited)
+    pub class_max_votes: [u32; AGENT_CLASSES], // 16
+    /// Weight scale per AgentClass, in basis points (0 = unscaled)
+    pub class_weight_bps: [u16; AGENT_CLASSES], // 8
+    pub class_votes: [u32; AGENT_CLASSES], // 16
+    /// Bumped on every delegation and undelegation; ballots name the one
+    /// they were signed for
+    pub delegation_epoch: u32,   // 4
+    pub delegation_state: DelegationState, // 1
+    /// Tallied only after verify_tally_page, or verify_compressed_tally_page
+    /// for compressed ballots, has recounted every ballot
+    pub audited: bool,           // 1
+    /// Created through a Hive account, so `hive` is part of the address
+    pub hive_seeded: bool,       // 1
+    /// Multiplier early token-weighted ballots get; OFF unless asked for
+    pub early_vote_curve: EarlyVoteCurve, // 3
+    pub log: ActionLog,          // 276
+    /// Voters barred from this action's ballot, e.g. its subject
+    pub excluded_count: u8,      // 1
+    pub excluded: [Pubkey; MAX_EXCLUSIONS], // 128
+    /// How extend_deadline may push `deadline_slot`; OFF unless asked for
+    pub extension_policy: ExtensionPolicy, // 3
+    pub extensions: u8,          // 1
+    /// Slots the extensions granted so far added to the deadline
+    pub extended_slots: u64,     // 8
+    /// Token-weighted ballots weigh the voter's identity asset, through
+    /// vote_fast_identity, rather than a token balance
+    pub identity_weighted: bool, // 1
+    /// Only holders of an NFT in this collection may vote; default = open
+    pub member_collection: Pubkey, // 32
+    /// Stake lock program token-weighted ballots lock their stake through;
+    /// default = unlocked
+    pub stake_lock_program: Pubkey, // 32
+    /// Priority lane, LOW_PRIORITY the lowest
+    pub priority: u8,               // 1
+    /// Counted in its ActiveActions index and not yet retired from it
+    pub lane_counted: bool,         // 1
+    /// Most ballots the action takes: its electorate, within the config's
+    /// cap and no lower than its quorum
+    pub max_votes: u32,             // 4
+    /// Labels agents filter actions by; zero marks an unused slot, after
+    /// the used ones
+    pub tags: [[u8; 8]; MAX_TAGS],  // 32
+    /// Listed in a TagIndex page per tag and not yet retired from them
+    pub tags_indexed: bool,         // 1
+    /// Share of `snapshot_weight` that must vote, in basis points, in place
+    /// of the headcount quorum; 0 counts heads
+    pub quorum_weight_bps: u16,     // 2
+    /// Total weight of the action's WeightSnapshot; 0 until one is posted
+    pub snapshot_weight: u64,       // 8
+    /// Lamports charged on creation, the base of any fee rebate
+    pub creation_fee: u64,          // 8
+    /// Rebate earned at tally and not yet claimed
+    pub fee_rebate: u64,            // 8
+    pub privacy: PrivacyLevel,      // 1
+    /// Counted in its delegated validator's active_actions and not yet
+    /// released from it
+    pub validator_counted: bool,    // 1
+    /// Pass bar in basis points; `threshold` is it rounded up to a whole
+    /// percent, for the rules kept in percent
+    pub threshold_bps: u16,         // 2
+    /// How approval is rounded to basis points, from the hive's HiveConfig
+    /// at creation
+    pub approval_rounding: ApprovalRounding, // 1
+    /// Validator standing by through the action's ActionMirror; default
+    /// unless enable_redundancy named one
+    pub backup_validator: Pubkey,   // 32
+}
+
+impl FastAction {
+    pub const LEN: usize = 1361; // 8 disc + 1353 fields
+
+    /// The action's tags, in the order they were given
+    pub fn tag_list(&self) -> &[[u8; 8]] {
+        let count = self.tags.iter().take_while(|tag| **tag != [0u8; 8]).count();
+        &self.tags[..count]
+    }
+
+    /// For-share of a for/against action's ballots so far, in percent
+    pub fn approval_pct(&self) -> Result<u64> {
+        match self.vote_mode {
+            VoteMode::TokenWeighted => tally::approval_pct(self.weight_for, self.weight_against),
+            _ => tally::approval_pct(self.votes_for as u64, self.votes_against as u64),
+        }
+    }
+
+    /// For-share of a for/against action's ballots so far, in basis points
+    /// rounded per `approval_rounding`
+    pub fn approval_bps(&self) -> Result<u64> {
+        let (tally_for, tally_against) = match self.vote_mode {
+            VoteMode::TokenWeighted => (self.weight_for, self.weight_against),
+            _ => (self.votes_for as u64, self.votes_against as u64),
+        };
+        tally::approval_bps(tally_for, tally_against, self.approval_rounding)
+    }
+
+    /// Whether the for share meets the threshold; false before any ballot
+    pub fn passing(&self) -> bool {
+        self.approval_bps().is_ok_and(|bps| bps >= self.threshold_bps as u64)
+    }
+
+    pub fn excludes(&self, voter: &Pubkey) -> bool {
+        self.excluded[..self.excluded_count as usize].contains(voter)
+    }
+
+    /// Log the result just recorded
+    pub fn log_result(&mut self, slot: u64) {
+        let payload = u16::from_le_bytes(self.result.encode()) as u64;
+        self.log.push(slot, LogTag::ResultRecorded, payload);
+    }
+
+    /// Result a tally at `slot` records; fails while quorum, turnout or a
+    /// ranked count is still short
+    pub fn tallied_result(&self, slot: u64) -> Result<VoteResult> {
+        Ok(match self.vote_mode {
+            VoteMode::Optimistic => tally::optimistic_result(self.votes_against, self.electorate, self.threshold),
+            VoteMode::Headcount | VoteMode::TokenWeighted => {
+                let required = tally::required_quorum(
+                    self.quorum,
+                    self.quorum_floor,
+                    self.quorum_grace_slots,
+                    self.deadline_slot,
+                    slot,
+                );
+                if self.quorum_weight_bps > 0 {
+                    require!(self.snapshot_weight > 0, FastVoteError::WeightSnapshotRequired);
+                    require!(
+                        tally::weight_quorum_met(
+                            self.weight_for,
+                            self.weight_against,
+                            self.snapshot_weight,
+                            self.quorum_weight_bps
+                        ),
+                        FastVoteError::QuorumNotMet
+                    );
+                } else {
+                    require!(self.vote_count >= required, FastVoteError::QuorumNotMet);
+                }
+                require!(
+                    tally::turnout_met(self.vote_count, self.electorate, self.min_turnout_bps),
+                    FastVoteError::TurnoutNotMet
+                );
+                if self.ranked {
+                    require!(self.pending_elimination == NO_ELIMINATION, FastVoteError::RunoffUndecided);
+                    let options = &self.option_weights[..self.option_count as usize];
+                    let option = tally::runoff_winner(options, self.eliminated_mask)
+                        .ok_or(FastVoteError::RunoffUndecided)?;
+                    VoteResult::Chosen { option }
+                } else if self.option_count > 0 {
+                    let options = &self.option_weights[..self.option_count as usize];
+                    tally::choose_option(options, self.threshold_bps, self.approval_rounding)?
+                } else {
+                    tally::grade(self.approval_bps()?, self.threshold_bps, &self.tier_thresholds)
+                }
+            }
+        })
+    }
+
+    /// Seed between FAST_ACTION_SEED and the id in the action's address
+    pub fn hive_seed(&self) -> &[u8] {
+        if self.hive_seeded {
+            self.hive.as_ref()
+        } else {
+            &[]
+        }
+    }
+
+    pub fn check_delegation_epoch(&self, delegation_epoch: u32) -> Result<()> {
+        require!(delegation_epoch == self.delegation_epoch, FastVoteError::StaleDelegationEpoch);
+        Ok(())
+    }
+
+    /// `weight` scaled for a ballot cast by `class`
+    pub fn class_weight(&self, class: AgentClass, weight: u64) -> u64 {
+        match self.class_weight_bps[class as usize] {
+            0 => weight,
+            bps => (weight as u128 * bps as u128 / 10_000).min(u64::MAX as u128) as u64,
+        }
+    }
+
+    /// `weight` scaled for a ballot cast at `slot`
+    pub fn early_weight(&self, weight: u64, slot: u64) -> u64 {
+        self.early_vote_curve.apply(weight, slot, self.created_slot, self.deadline_slot)
+    }
+
+    /// `weight` clamped to the action's per-voter cap
+    pub fn capped_weight(&self, weight: u64) -> u64 {
+        match self.max_weight_per_voter {
+            0 => weight,
+            cap => weight.min(cap),
+        }
+    }
+}
+
+#[account]
+pub struct ActionCounter {
+    pub next_id: u64, // 8
+    pub bump: u8,     // 1
+}
+
+impl ActionCounter {
+    pub const LEN: usize = 17; // 8 disc + 9 fields
+}
+
+#[account]
+pub struct FastVote {
+    pub fast_action: Pubkey,     // 32
+    pub voter: Pubkey,           // 32
+    pub voter_commitment: [u8; 32], // 32
+    pub vote_value: bool,        // 1
+    pub voted_slot: u64,         // 8
+    pub bump: u8,                // 1
+    pub weight: u64,             // 8
+    /// reason_hash of the voter's reasoning, revealed after finalization;
+    /// zero when none was committed
+    pub reason_hash: [u8; 32],   // 32
+}
+
+impl FastVote {
+    pub const LEN: usize = 154; // 8 disc + 146 fields
+}
+
+/// How ballots are counted. Headcount is one-voter-one-vote; TokenWeighted
+/// reads the voter's balance of `GlobalConfig::weight_mint`. Optimistic
+/// actions pass provisionally at creation and ballots can only veto;
+/// `threshold` is then the percent of the electorate whose vetoes reverse it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VoteMode {
+    Headcount,
+    TokenWeighted,
+    Optimistic,
+}
+
+/// Where the action lives: on the base layer, or delegated to an ephemeral
+/// rollup validator
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DelegationState {
+    #[default]
+    Undelegated,
+    Delegated,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub enum VoteResult {
+    Pending,
+    /// `tier` counts the graded tiers met above the base threshold (0 = plain pass)
+    Passed { tier: u8 },
+    Failed,
+    Cancelled,
+    /// Never finalized; undelegated by force_undelegate
+    Expired,
+    /// Overturned by a successful challenge during the dispute window
+    Voided,
+    /// Multi-option action; `option` led with at least `threshold` percent
+    Chosen { option: u8 },
+    /// Overturned by the guardian during the dispute window
+    Vetoed,
+    /// Passed within its category's contest margin; becomes Passed once
+    /// the slow track ratifies it, or Failed if it does not
+    Contested { tier: u8 },
+}
+
+impl VoteResult {
+    /// Fixed-width encoding used in result digests: [variant, tier]
+    pub fn encode(&self) -> [u8; 2] {
+        match self {
+            VoteResult::Pending => [0, 0],
+            VoteResult::Passed { tier } => [1, *tier],
+            VoteResult::Failed => [2, 0],
+            VoteResult::Cancelled => [3, 0],
+            VoteResult::Expired => [4, 0],
+            VoteResult::Voided => [5, 0],
+            VoteResult::Chosen { option } => [6, *option],
+            VoteResult::Vetoed => [7, 0],
+            VoteResult::Contested { tier } => [8, *tier],
+        }
+    }
+
+    /// Decided by a tally rather than cancelled, expired, voided or vetoed
+    pub fn is_tallied(&self) -> bool {
+        matches!(
+            self,
+            VoteResult::Passed { .. } | VoteResult::Failed | VoteResult::Chosen { .. } | VoteResult::Contested { .. }
+        )
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ActionParams {
+    pub action_hash: [u8; 32],
+    pub threshold: u8,
+    pub description_hash: [u8; 32],
+    pub vote_mode: VoteMode,
+    /// Graded tiers above `threshold`, ascending; 0 marks an unused slot
+    pub tier_thresholds: [u8; MAX_EXTRA_TIERS],
+    /// Actions that must have passed before this one can be tallied
+    pub dependencies: Vec<Pubkey>,
+    /// None keeps the fixed MIN_VOTES_FOR_QUORUM
+    pub adaptive_quorum: Option<AdaptiveQuorum>,
+    /// Share of the hive's electorate that must vote, in basis points,
+    /// independent of the approval `threshold`; 0 disables
+    pub min_turnout_bps: u16,
+    /// 0 for a for/against vote; otherwise ballots go through vote_options
+    /// and `threshold` is the share of weight the leading option needs
+    pub option_count: u8,
+    /// Headcount option actions only: ballots rank options through
+    /// vote_ranked and the count runs as an instant runoff to a majority,
+    /// ignoring `threshold`
+    pub ranked: bool,
+    /// Experimental: a passed action also needs this market price to clear
+    /// before it is executable. For/against actions only
+    pub market_gate: Option<MarketGate>,
+    /// Emergency actions take a short window and need a supermajority in a
+    /// hive whose electorate includes the guardian; ConfigChange,
+    /// ProgramUpgrade and Budget actions need a token-weighted majority
+    pub kind: ActionKind,
+    /// Token-weighted only: most weight one ballot counts [default: the
+    /// kind's cap in WeightCaps, if any]
+    pub weight_cap: Option<WeightCap>,
+    /// Per-class ballot caps and weight scales; classes left out are
+    /// unlimited
+    pub class_limits: Vec<ClassLimit>,
+    /// For/against actions only: tally only once verify_tally_page has
+    /// recounted every FastVote and the recount matches the counters
+    pub audited: bool,
+    /// Token-weighted only: scale early ballots by the kind's early-vote
+    /// curve, decaying to 1.0x at the deadline
+    pub early_vote_bonus: bool,
+    /// Voters barred from the ballot, e.g. the agent the action concerns
+    pub exclusions: Vec<Pubkey>,
+    /// Headcount and token-weighted only: let extend_deadline push the
+    /// deadline when enough voters co-sign. None keeps it fixed
+    pub deadline_extension: Option<ExtensionPolicy>,
+    /// Token-weighted only: weigh ballots by the voter's identity asset
+    /// under IdentityWeights instead of a token balance
+    pub identity_weighted: bool,
+    /// Headcount and optimistic for/against actions only: take ballots
+    /// only from holders of an NFT in this Metaplex collection
+    pub member_collection: Option<Pubkey>,
+    /// Token-weighted only: ballots go through vote_fast_locked, which locks
+    /// the voter's stake until the action is final
+    pub lock_stake: bool,
+    /// Priority lane, below PRIORITY_LANES; DEFAULT_PRIORITY unless the
+    /// action is more or less urgent than most
+    pub priority: u8,
+    /// Up to MAX_TAGS distinct non-zero labels. Each is listed in a
+    /// TagIndex page passed after the creation accounts, in tag order
+    pub tags: Vec<[u8; 8]>,
+    /// Token-weighted for/against only: quorum as a share of the weight
+    /// snapshot's total, in basis points, replacing the headcount quorum.
+    /// The action then takes no live token-account ballots; 0 counts heads
+    pub quorum_weight_bps: u16,
+    /// What ballots disclose and when; anything but Public needs a
+    /// headcount for/against action that is not audited
+    pub privacy: PrivacyLevel,
+    /// Pass bar in basis points, which `threshold` must be rounded up from,
+    /// e.g. 6667 with 67; 0 takes `threshold` as a whole percent
+    pub threshold_bps: u16,
+}
+
+/// Required votes start at `quorum` at the deadline and decay linearly to
+/// `floor` over `grace_slots`, so quiet actions still resolve.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AdaptiveQuorum {
+    pub quorum: u32,
+    pub floor: u32,
+    pub grace_slots: u64,
+}
+
+#[event]
+pub struct FastActionCreated {
+    pub action: Pubkey,
+    pub action_id: u64,
+    pub action_hash: [u8; 32],
+    pub threshold: u8,
+    pub tier_thresholds: [u8; MAX_EXTRA_TIERS],
+    pub deadline_slot: u64,
+    pub vote_mode: VoteMode,
+    pub hive: Pubkey,
+    pub dependencies: Vec<Pubkey>,
+}
+
+#[event]
+pub struct FastVoteCast {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub voter_commitment: [u8; 32],
+    pub vote_count: u32,
+    pub weight: u64,
+}
+
+#[event]
+pub struct FastActionExecuted {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub votes_for: u32,
+    pub votes_against: u32,
+    pub weight_for: u64,
+    pub weight_against: u64,
+    pub result: VoteResult,
+    pub finalized_slot: u64,
+    pub result_digest: [u8; 32],
+}
+
+#[event]
+pub struct ActionDelegated {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    /// Default if unpinned
+    pub validator: Pubkey,
+    pub slot: u64,
+}
+
+/// Emitted when a tally or force_undelegate hands the action back to the
+/// base layer
+#[event]
+pub struct ActionUndelegated {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct DependencyBlocked {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub dependency: Pubkey,
+    pub index: u8,
+    pub dependency_result: VoteResult,
+}
+
+#[event]
+pub struct FastActionCancelled {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+}
+
+#[error_code]
+pub enum FastVoteError {
+    #[msg("Threshold must be 1-100, and a bps threshold must round up to it")]
+    InvalidThreshold,
+    #[msg("Action hash cannot be zero")]
+    InvalidActionHash,
+    #[msg("Slot calculation overflow")]
+    SlotOverflow,
+    #[msg("PDA does not match expected derivation")]
+    InvalidPda,
+    #[msg("Voting has ended")]
+    VotingEnded,
+    #[msg("Voting has not ended yet")]
+    VotingNotEnded,
+    #[msg("Action already executed")]
+    ActionAlreadyExecuted,
+    #[msg("Vote count overflow")]
+    VoteOverflow,
+    #[msg("Max votes reached for this action")]
+    MaxVotesReached,
+    #[msg("Voter commitment cannot be zero")]
+    InvalidVoterCommitment,
+    #[msg("Quorum not met")]
+    QuorumNotMet,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid MagicBlock program")]
+    InvalidMagicBlockProgram,
+    #[msg("Invalid MagicBlock context")]
+    InvalidMagicContext,
+    #[msg("Instruction does not match the action's vote mode")]
+    WrongVoteMode,
+    #[msg("Unknown weight extension flags")]
+    InvalidWeightExtensions,
+    #[msg("Mint is not the configured weight mint")]
+    InvalidWeightMint,
+    #[msg("Token account is not an initialized account of the weight mint owned by the voter")]
+    InvalidTokenAccount,
+    #[msg("Malformed Token-2022 extension data")]
+    InvalidTokenExtension,
+    #[msg("Mint transfer hook does not match the configured program")]
+    TransferHookMismatch,
+    #[msg("Token account is in the middle of a transfer")]
+    TokenAccountTransferring,
+    #[msg("Voter has no voting weight")]
+    NoVotingWeight,
+    #[msg("Roles account required for hive-scoped actions")]
+    MissingRoles,
+    #[msg("Roles account belongs to a different hive")]
+    InvalidRoles,
+    #[msg("Signer does not hold the required role")]
+    MissingRole,
+    #[msg("Roles account is full")]
+    RolesFull,
+    #[msg("Cannot revoke the last admin")]
+    LastAdmin,
+    #[msg("Tier thresholds must ascend above the threshold and stay within 100")]
+    InvalidTierThresholds,
+    #[msg("Too many dependencies")]
+    TooManyDependencies,
+    #[msg("Dependencies must be unique, non-zero, and not the action itself")]
+    InvalidDependency,
+    #[msg("Dependency account missing or out of order")]
+    MissingDependency,
+    #[msg("Dependency has not passed")]
+    DependencyNotPassed,
+    #[msg("Creator exceeded the action creation limit for this epoch")]
+    CreationRateLimited,
+    #[msg("Action ID counter overflow")]
+    ActionIdOverflow,
+    #[msg("An action for this hash is still live")]
+    DuplicateActionHash,
+    #[msg("Action uses an outdated layout; call migrate_action")]
+    ActionNeedsMigration,
+    #[msg("Action is already at the current version")]
+    AlreadyMigrated,
+    #[msg("Too few votes or slots since the last checkpoint")]
+    CheckpointTooSoon,
+    #[msg("Liveness timeout after the deadline has not passed")]
+    LivenessTimeoutNotReached,
+    #[msg("Validator is not in the registry")]
+    ValidatorNotRegistered,
+    #[msg("Validator is not allowlisted")]
+    ValidatorNotAllowed,
+    #[msg("Validator has not sent a recent heartbeat")]
+    ValidatorNotLive,
+    #[msg("Action has not been finalized")]
+    ActionNotFinalized,
+    #[msg("Session already recorded for this action")]
+    SessionAlreadyRecorded,
+    #[msg("Session was not hosted by a pinned validator or finalized late")]
+    SessionNotRewardable,
+    #[msg("No unclaimed sessions")]
+    NoRewards,
+    #[msg("Validator bond below the minimum")]
+    BondTooLow,
+    #[msg("Action was committed on time or never delegated to a validator")]
+    CommitNotMissed,
+    #[msg("Slash report is not pending")]
+    SlashNotPending,
+    #[msg("Dispute window is still open")]
+    DisputeWindowOpen,
+    #[msg("Dispute window has closed")]
+    DisputeWindowClosed,
+    #[msg("Evidence does not contradict the committed result")]
+    ResultNotFraudulent,
+    #[msg("Evidence accounts are invalid")]
+    InvalidEvidence,
+    #[msg("Dependency is still within its dispute window")]
+    DependencyNotSettled,
+    #[msg("Optimistic actions must be hive-scoped")]
+    OptimisticRequiresHive,
+    #[msg("Optimistic actions only accept vetoes")]
+    OptimisticVetoOnly,
+    #[msg("Action holds no bond")]
+    NoBond,
+    #[msg("Quorum floor must be between 1 and the quorum")]
+    InvalidQuorum,
+    #[msg("Turnout requires a hive-scoped, non-optimistic action and at most 10000 bps")]
+    InvalidTurnout,
+    #[msg("Too little of the electorate voted")]
+    TurnoutNotMet,
+    #[msg("Option count must be 2 to MAX_OPTIONS, without tiers or optimistic mode")]
+    InvalidOptionCount,
+    #[msg("Action is not a multi-option action")]
+    NotAnOptionAction,
+    #[msg("Multi-option actions take ballots through vote_options")]
+    OptionBallotRequired,
+    #[msg("Option weights must cover every option and sum to the voter's weight")]
+    InvalidOptionWeights,
+    #[msg("Token-weighted ballots need the config, weight mint and token account")]
+    MissingWeightAccounts,
+    #[msg("Ranked-choice requires a headcount multi-option action")]
+    InvalidRankedAction,
+    #[msg("Action is not a ranked-choice action")]
+    NotARankedAction,
+    #[msg("Ranking must list distinct valid options")]
+    InvalidRanking,
+    #[msg("Account is not a ranked ballot for this action")]
+    InvalidRankedBallot,
+    #[msg("An option already holds a majority")]
+    RunoffDecided,
+    #[msg("Ranked-choice count has not reached a majority")]
+    RunoffUndecided,
+    #[msg("Ranked-choice actions take ballots through vote_ranked")]
+    RankedBallotRequired,
+    #[msg("Only a failed, unranked multi-option action with two supported options can go to a runoff")]
+    RunoffNotRequired,
+    #[msg("Runoff already spawned for this action")]
+    RunoffAlreadySpawned,
+    #[msg("Conviction threshold must be positive and retention below one billion ppb")]
+    InvalidConvictionParams,
+    #[msg("Stake amount must be positive and at most the amount staked")]
+    InvalidStakeAmount,
+    #[msg("Market gate needs a feed and its program, on a for/against action")]
+    InvalidMarketGate,
+    #[msg("Action has no market gate")]
+    NoMarketGate,
+    #[msg("Market gate already cleared")]
+    MarketGateCleared,
+    #[msg("Feed is not the action's market or is too short")]
+    InvalidMarketFeed,
+    #[msg("Market price does not exceed the gate's bound")]
+    MarketPriceTooLow,
+    #[msg("Dependency's market gate has not cleared")]
+    MarketGateNotCleared,
+    #[msg("No guardian is configured")]
+    GuardianNotSet,
+    #[msg("Only passed or chosen results can be vetoed")]
+    NothingToVeto,
+    #[msg("Veto reason too long")]
+    VetoReasonTooLong,
+    #[msg("Authority cannot be the default pubkey")]
+    InvalidAuthority,
+    #[msg("Action can no longer be cancelled")]
+    CancellationClosed,
+    #[msg("Action already has votes")]
+    ActionHasVotes,
+    #[msg("Voting window must be between 1 slot and the maximum, ending in the future")]
+    InvalidVotingWindow,
+    #[msg("Sponsors must be 1 to 8 distinct keys, with 1 to all of them required")]
+    InvalidSponsors,
+    #[msg("Signer is not a sponsor of this draft")]
+    NotASponsor,
+    #[msg("Sponsor already signed this draft")]
+    AlreadySponsored,
+    #[msg("Draft lacks the required sponsor signatures")]
+    DraftNotSponsored,
+    #[msg("Draft was already promoted to an action")]
+    DraftAlreadyPromoted,
+    #[msg("Params differ from those the sponsors signed")]
+    DraftParamsMismatch,
+    #[msg("Emergency actions need a threshold of at least 80% and a for/against vote")]
+    InvalidEmergencyAction,
+    #[msg("Guardian must be a member of the hive")]
+    GuardianNotInElectorate,
+    #[msg("Description exceeds 256 bytes")]
+    DescriptionTooLong,
+    #[msg("Metadata URI exceeds 200 bytes")]
+    MetadataUriTooLong,
+    #[msg("Callback program cannot be this program or the default pubkey")]
+    InvalidCallback,
+    #[msg("Action already has 4 callbacks")]
+    TooManyCallbacks,
+    #[msg("Callback accounts do not match the registered program and accounts")]
+    InvalidCallbackAccounts,
+    #[msg("Callbacks were already executed")]
+    CallbacksDispatched,
+    #[msg("Config change actions need a token-weighted for/against vote with a threshold of at least 51%")]
+    InvalidConfigChangeAction,
+    #[msg("Config change does not match the action hash")]
+    ConfigChangeMismatch,
+    #[msg("Action has not passed and settled, or its market gate is closed")]
+    ActionNotExecutable,
+    #[msg("Timelock has not expired")]
+    TimelockActive,
+    #[msg("Program upgrade actions need a token-weighted for/against vote with a threshold of at least 51%")]
+    InvalidUpgradeAction,
+    #[msg("Program or buffer does not match the action hash")]
+    UpgradeMismatch,
+    #[msg("Budget actions need a token-weighted for/against vote with a threshold of at least 51%")]
+    InvalidBudgetAction,
+    #[msg("Budget or stream does not match the action hash")]
+    BudgetMismatch,
+    #[msg("Budget needs a recipient, a nonzero amount and an end after its start")]
+    InvalidBudget,
+    #[msg("Nothing has vested since the last withdrawal")]
+    NothingVested,
+    #[msg("Treasury cannot cover the payment")]
+    TreasuryInsufficient,
+    #[msg("Token streams need the mint, both token accounts and the token program")]
+    MissingTokenAccounts,
+    #[msg("Stream was already cancelled")]
+    StreamAlreadyCancelled,
+    #[msg("Weight caps need a token-weighted action, a nonzero maximum and at most 10000 bps")]
+    InvalidWeightCap,
+    #[msg("Voter has no vote credits left this epoch")]
+    VoteCreditsExhausted,
+    #[msg("Invalid vote delegation")]
+    InvalidDelegation,
+    #[msg("Vote delegation was changed too recently")]
+    DelegationCooldown,
+    #[msg("Voter has not bonded the minimum agent bond")]
+    AgentNotBonded,
+    #[msg("Agent bonds must be nonzero")]
+    InvalidAgentBond,
+    #[msg("Class limits must name each class once; weight scales need a token-weighted action")]
+    InvalidClassLimit,
+    #[msg("The voter's agent class has cast all the ballots this action allows it")]
+    ClassVoteCapReached,
+    #[msg("Ballot was signed for an earlier delegation of this action")]
+    StaleDelegationEpoch,
+    #[msg("Only for/against actions can be audited")]
+    InvalidAuditedTally,
+    #[msg("Tally pages must list the audited action's votes in ascending address order")]
+    InvalidTallyPage,
+    #[msg("The audited recount is missing or does not match the action's counters")]
+    TallyNotVerified,
+    #[msg("Hive account does not match the action's hive")]
+    InvalidHive,
+    #[msg("Federations need 2-8 distinct hive-scoped for/against actions, one per hive, each with nonzero weight")]
+    InvalidFederation,
+    #[msg("Every child action must be final before the federation settles")]
+    FederationNotSettled,
+    #[msg("Elections need a hive-scoped multi-option action that is not optimistic")]
+    InvalidElectionAction,
+    #[msg("Election does not match the action hash or its option count")]
+    ElectionMismatch,
+    #[msg("Elected leader has no agent record")]
+    LeaderNotRegistered,
+    #[msg("A later election already installed the hive's leader")]
+    StaleElection,
+    #[msg("Task assignments need a multi-option action that is not optimistic")]
+    InvalidTaskAssignment,
+    #[msg("Task does not match the action hash, its option count or the queue accounts")]
+    TaskAssignmentMismatch,
+    #[msg("Sealing needs a description hash, a ciphertext hash and an encryption key")]
+    InvalidSealedDescription,
+    #[msg("Description does not hash to the action's description hash")]
+    DescriptionMismatch,
+    #[msg("Description has already been revealed")]
+    DescriptionAlreadyRevealed,
+    #[msg("Early-vote bonuses need a token-weighted action and a curve of at most 20%")]
+    InvalidEarlyVoteCurve,
+    #[msg("Receipt tree must be set, and its URI prefix at most 64 bytes")]
+    InvalidReceiptTree,
+    #[msg("Vote rewards need an amount and a claim window")]
+    InvalidVoteRewards,
+    #[msg("Reward claims for this action have closed")]
+    RewardClaimsClosed,
+    #[msg("Rewards were already swept or claims are still open")]
+    RewardsNotSweepable,
+    #[msg("Ballot has no reward due")]
+    NoRewardDue,
+    #[msg("Ballots must be on the same action with different choices")]
+    BallotsDoNotConflict,
+    #[msg("Ballot signature was not verified by an ed25519 instruction in this transaction")]
+    MissingBallotSignature,
+    #[msg("Whistleblower share is at most 50%")]
+    InvalidWhistleblowerBounty,
+    #[msg("Weight snapshots need a token-weighted for/against action and a root")]
+    InvalidWeightSnapshot,
+    #[msg("Weight proof does not lead to the action's snapshot root")]
+    InvalidWeightProof,
+    #[msg("Action has a weight snapshot; vote with vote_fast_snapshot")]
+    WeightSnapshotPosted,
+    #[msg("Compressed ballots need a public headcount or optimistic for/against action and an address tree")]
+    InvalidCompressedBallots,
+    #[msg("Action takes compressed ballots; vote with vote_fast_compressed")]
+    CompressedBallotsOnly,
+    #[msg("Max window must be 0 or between the default window and MAX_VOTING_WINDOW_SLOTS")]
+    InvalidMaxWindow,
+    #[msg("Expiry grace after the deadline has not passed")]
+    ExpiryGraceNotReached,
+    #[msg("Template window must be 0 or, outside emergencies, between the default and max windows")]
+    InvalidTemplateWindow,
+    #[msg("create_from_template needs the template, and its callback registry if it has callbacks")]
+    MissingTemplate,
+    #[msg("Too many exclusions, or a duplicate or default key among them")]
+    InvalidExclusions,
+    #[msg("Voter is excluded from this action")]
+    VoterExcluded,
+    #[msg("Voter has already voted on this action")]
+    AlreadyVoted,
+    #[msg("Packed ballot is not a valid vote value or option")]
+    InvalidCompactBallot,
+    #[msg("An execution schedule pays a keeper; pass the keeper account")]
+    MissingKeeper,
+    #[msg("Action was not finalized as passed")]
+    ActionNotPassed,
+    #[msg("Extension policy must allow 1 to 3 extensions backed by more than half the voters")]
+    InvalidExtensionPolicy,
+    #[msg("Action has no deadline extensions left")]
+    ExtensionsExhausted,
+    #[msg("Extension must be positive and within one voting window")]
+    InvalidExtension,
+    #[msg("Too few of the action's voters co-signed the extension")]
+    ExtensionNotSupported,
+    #[msg("Identity weights need an attribute, 1 to 8 distinct tiers and keys of at most 32 bytes")]
+    InvalidIdentityWeights,
+    #[msg("Identity weighting needs a token-weighted for/against action")]
+    InvalidIdentityWeighting,
+    #[msg("Asset is not a Core asset of the identity collection held by the voter")]
+    InvalidIdentityAsset,
+    #[msg("Asset's identity attribute is missing, not set by its update authority, or has no weight")]
+    IdentityNotWeighted,
+    #[msg("Collection gating needs a headcount or optimistic for/against action")]
+    InvalidMemberCollection,
+    #[msg("Voter showed no NFT of the action's member collection")]
+    NotCollectionMember,
+    #[msg("Stake locks need a token-weighted for/against action and a configured stake lock program")]
+    InvalidStakeLock,
+    #[msg("Voter's stake is locked with another token account or lock program")]
+    StakeLockMismatch,
+    #[msg("Priority is not a lane")]
+    InvalidPriority,
+    #[msg("The low-priority cap is set, so actions must be counted in their ActiveActions index")]
+    ActiveActionsRequired,
+    #[msg("Too many low-priority actions are running")]
+    LowPriorityLaneFull,
+    #[msg("Action is not counted in a priority lane")]
+    ActionNotInLane,
+    #[msg("Batch must hold 1 to MAX_BATCH_ACTIONS actions, each with the batch creator's accounts")]
+    InvalidBatch,
+    #[msg("Insurance fee cut may not exceed 10000 bps")]
+    InvalidInsuranceFee,
+    #[msg("Not an insurance pool of the action's hive")]
+    InvalidInsurancePool,
+    #[msg("Claims need a non-zero amount against a voided action of a hive")]
+    InvalidClaim,
+    #[msg("Resolution must be an Emergency action of the claim's hive carrying its resolution hash")]
+    ClaimMismatch,
+    #[msg("Insurance pool cannot cover the claim")]
+    InsuranceInsufficient,
+    #[msg("EpochStats must be of the action's hive and the current or a later epoch")]
+    InvalidEpochStats,
+    #[msg("Member is not a member of the hive")]
+    NotHiveMember,
+    #[msg("Member was active within INACTIVITY_EPOCHS, or is already marked inactive")]
+    MemberStillActive,
+    #[msg("Strategies need a token-weighted, single-question, unlocked action without a weight snapshot")]
+    InvalidWeightStrategy,
+    #[msg("Strategy program returned no weight")]
+    StrategyReturnMissing,
+    #[msg("Attestors must be distinct validators, at most MAX_ATTESTORS, with a threshold no higher than their count")]
+    InvalidAttestors,
+    #[msg("Fewer of the hive's attestors co-signed the result than its threshold")]
+    AttestationMissing,
+    #[msg("Reason must be at most MAX_REASON_LEN bytes and match the ballot's non-zero reason hash")]
+    InvalidReason,
+    #[msg("Ballot already carries a reason hash")]
+    ReasonAlreadySet,
+    #[msg("Groups need 1 to MAX_GROUP_MEMBERS distinct members, a threshold within that count and a non-zero weight")]
+    InvalidVoterGroup,
+    #[msg("Foreign hives need a non-zero chain, emitter and weight cap")]
+    InvalidForeignHive,
+    #[msg("Not a verified VAA from the foreign hive carrying a tally for this action")]
+    InvalidForeignTally,
+    #[msg("Execution was not staged in an earlier slot with these remaining accounts")]
+    InvalidExecutionStage,
+    #[msg("Actions take up to MAX_TAGS distinct non-zero tags")]
+    InvalidTags,
+    #[msg("Each tag needs its TagIndex page, of the action's hive, with room, in tag order")]
+    InvalidTagIndex,
+    #[msg("Action is not listed in its tags' TagIndex pages")]
+    ActionNotTagged,
+    #[msg("Hive-scoped creation requires the hive's CreatorAllowlist account")]
+    CreatorAllowlistRequired,
+    #[msg("Hive restricts creation and the creator is not on its allowlist")]
+    CreatorNotAllowed,
+    #[msg("Creator allowlist is full")]
+    CreatorAllowlistFull,
+    #[msg("Creator is already on the allowlist")]
+    CreatorAlreadyAllowed,
+    #[msg("Identity ramp floor exceeds 10000 bps")]
+    InvalidIdentityRamp,
+    #[msg("Metrics account counts another hive's actions")]
+    MetricsHiveMismatch,
+    #[msg("Budget reaches the slow-track promotion threshold and was not promoted")]
+    PromotionRequired,
+    #[msg("Budget's slow vote has not passed or is still timelocked")]
+    SlowVotePending,
+    #[msg("Contest margin must be at most 100")]
+    InvalidContestMargin,
+    #[msg("Result is contested and awaits slow-track ratification")]
+    ContestUnresolved,
+    #[msg("Result is not contested")]
+    NotContested,
+    #[msg("Slow vote has not been decided or is still timelocked")]
+    RatificationPending,
+    #[msg("Weight quorum needs a plain token-weighted for/against action and at most 10000 bps")]
+    InvalidWeightQuorum,
+    #[msg("Weight quorum is measured against a weight snapshot, and none was posted")]
+    WeightSnapshotRequired,
+    #[msg("Fee rebate turnout and share are at most 10000 bps")]
+    InvalidFeeRebate,
+    #[msg("No fee rebate to claim")]
+    NoFeeRebate,
+    #[msg("Hive is frozen")]
+    HiveFrozen,
+    #[msg("Freeze action must be an Emergency action of the hive carrying its freeze hash")]
+    InvalidFreezeAction,
+    #[msg("A later action already set the hive's freeze")]
+    StaleFreezeAction,
+    #[msg("Hive actions need the hive's freeze PDA")]
+    HiveFreezeRequired,
+    #[msg("Standings board belongs to another hive")]
+    BoardHiveMismatch,
+    #[msg("Runtime invariant violated")]
+    InvariantViolated,
+    #[msg("Sequence batch must hold 1 to 12 ballots in received order, following the previous batch")]
+    InvalidSequenceBatch,
+    #[msg("Privacy level needs an unaudited headcount for/against action; AnonymousZk no voter restrictions")]
+    InvalidPrivacyLevel,
+    #[msg("Ballot path does not match the action's privacy level")]
+    PrivacyLevelMismatch,
+    #[msg("Reveal does not open the ballot's commitment")]
+    BallotCommitmentMismatch,
+    #[msg("Ballot has already been revealed")]
+    BallotAlreadyRevealed,
+    #[msg("Reveal window has closed")]
+    RevealWindowClosed,
+    #[msg("Commit-reveal ballots are still being revealed")]
+    RevealWindowOpen,
+    #[msg("Ballot verifier must be an executable program other than this one, allowed by the admin")]
+    InvalidBallotVerifier,
+    #[msg("Validator is hosting as many actions as the config allows; delegate to another")]
+    ValidatorAtCapacity,
+    #[msg("Action is not counted against a validator")]
+    ActionNotCounted,
+    #[msg("Action is still counted against its previous validator; release it first")]
+    ValidatorStillCounted,
+    #[msg("Action is counted against its validator, whose ValidatorRecord must be passed")]
+    ValidatorRecordRequired,
+    #[msg("Hive-scoped creation must pass the hive's HiveConfig PDA")]
+    HiveConfigRequired,
+    #[msg("Redundancy needs an undelegated public for/against action without ballots or audit")]
+    RedundancyUnsupported,
+    #[msg("A redundantly delegated action needs a pinned primary other than its backup")]
+    InvalidPrimaryValidator,
+    #[msg("Commit must carry the committing validator's signature over its tally")]
+    CommitSignatureMissing,
+    #[msg("Mirror already holds a commit")]
+    MirrorAlreadyCommitted,
+    #[msg("Mirror holds no commit")]
+    MirrorNotCommitted,
+    #[msg("Redundant commits already resolved")]
+    CommitAlreadyResolved,
+    #[msg("Hive members must be distinct, other than the hive key, and hold a role")]
+    InvalidHiveMembers,
+    #[msg("Ballot must be created in the address tree pinned for the action's compressed ballots")]
+    InvalidAddressTree,
+    #[msg("Anonymous ballots need the action's Hive account with a member root")]
+    MemberRootRequired,
+    #[msg("Key is already an agent, or another agent's vote key")]
+    VoteKeyInUse,
+    #[msg("Ballot's agent must be the voter, or the agent its vote key is bound to")]
+    AgentMismatch,
+    #[msg("Relayers must be distinct, at most MAX_RELAYERS, with a non-zero fallback")]
+    InvalidRelayers,
+    #[msg("Ballot must be submitted by one of the action's relayers")]
+    RelayerNotAllowed,
+    #[msg("Voter may submit its own ballot only once the fallback delay has passed")]
+    SelfSubmitNotDue,
+    #[msg("Dependency is still delegated; tally it and let it undelegate first")]
+    DependencyDelegated,
+    #[msg("A hive action needs its Hive account")]
+    HiveAccountRequired,
+}
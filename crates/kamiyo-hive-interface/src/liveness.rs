@@ -0,0 +1,25 @@
+// Recovery of actions stranded on an unresponsive ER validator
+
+use anchor_lang::prelude::*;
+
+/// Slots past the deadline before anyone may force undelegation (~10 min)
+pub const LIVENESS_TIMEOUT_SLOTS: u64 = 1_500;
+
+#[event]
+pub struct StaleDelegationsReaped {
+    pub reaped: u32,
+    /// Remaining accounts passed over
+    pub skipped: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ActionForceUndelegated {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    /// Validator the action was delegated to; default if unpinned
+    pub validator: Pubkey,
+    pub deadline_slot: u64,
+    pub slot: u64,
+}
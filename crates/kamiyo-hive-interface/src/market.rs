@@ -0,0 +1,24 @@
+// Price gates a passed action waits on before it executes
+
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MarketGate {
+    pub feed: Pubkey,
+    /// Program that must own `feed`
+    pub feed_program: Pubkey,
+    /// Byte offset of the price in `feed`'s data
+    pub price_offset: u16,
+    /// Price the feed must exceed, in the feed's own units
+    pub min_price: u64,
+}
+
+#[event]
+pub struct MarketGateCleared {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub feed: Pubkey,
+    pub price: u64,
+    pub min_price: u64,
+}
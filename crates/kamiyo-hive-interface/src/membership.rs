@@ -0,0 +1,6 @@
+// Collection-gated electorates: an action may take ballots only from holders
+// of an NFT in a given Metaplex collection
+
+use anchor_lang::prelude::*;
+
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
@@ -0,0 +1,31 @@
+// Descriptions and URIs attached to an action
+
+use anchor_lang::prelude::*;
+
+pub const ACTION_METADATA_SEED: &[u8] = b"action_metadata";
+
+pub const MAX_DESCRIPTION_LEN: usize = 256;
+pub const MAX_METADATA_URI_LEN: usize = 200;
+
+#[account]
+pub struct ActionMetadata {
+    pub action: Pubkey,      // 32
+    pub description: String, // 4 + len
+    pub uri: String,         // 4 + len
+    pub bump: u8,            // 1
+}
+
+impl ActionMetadata {
+    pub fn space(description: &str, uri: &str) -> usize {
+        8 + 32 + 4 + description.len() + 4 + uri.len() + 1
+    }
+}
+
+#[event]
+pub struct ActionMetadataSet {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub description: String,
+    pub uri: String,
+}
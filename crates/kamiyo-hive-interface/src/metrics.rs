@@ -0,0 +1,50 @@
+// Per-layer liveness counters, program-wide or for one hive
+
+use anchor_lang::prelude::*;
+
+pub const METRICS_SEED: &[u8] = b"metrics";
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MetricsLayer {
+    Base,
+    /// Created with a transaction sent to the ephemeral rollup, where it
+    /// stays
+    Er,
+}
+
+#[account]
+pub struct Metrics {
+    pub layer: MetricsLayer,     // 1
+    pub actions_created: u64,    // 8
+    pub actions_finalized: u64,  // 8
+    pub actions_expired: u64,    // 8
+    pub actions_cancelled: u64,  // 8
+    pub votes: u64,              // 8
+    pub last_activity_slot: u64, // 8
+    pub bump: u8,                // 1
+    /// The hive whose actions it counts; default for the program-wide
+    /// accounts, which count every action
+    pub hive: Pubkey, // 32
+}
+
+impl Metrics {
+    pub const LEN: usize = 90; // 8 disc + 82 fields
+    /// Before `hive`; such accounts are program-wide and are never grown
+    pub const LEGACY_LEN: usize = 58;
+
+    /// Reads legacy accounts as program-wide
+    pub fn decode(data: &[u8]) -> Result<Metrics> {
+        let mut data = data.to_vec();
+        if data.len() == Self::LEGACY_LEN {
+            data.resize(Self::LEN, 0);
+        }
+        Metrics::try_deserialize(&mut &data[..])
+    }
+}
+
+#[event]
+pub struct MetricsInitialized {
+    pub metrics: Pubkey,
+    pub layer: MetricsLayer,
+    pub hive: Pubkey,
+}
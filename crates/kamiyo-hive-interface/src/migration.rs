@@ -0,0 +1,50 @@
+// FastAction layout versions
+
+use anchor_lang::prelude::*;
+
+/// 1: `version` added
+/// 2: `last_checkpoint_slot`, `last_checkpoint_votes`
+/// 3: `delegated_validator`
+/// 4: `session_recorded`
+/// 5: `electorate`, `bond`
+/// 6: `quorum`, `quorum_floor`, `quorum_grace_slots`
+/// 7: `min_turnout_bps`
+/// 8: `option_count`, `option_weights`
+/// 9: `ranked`, `eliminated_mask`, `pending_elimination`, `round`, `exhausted_ballots`
+/// 10: `parent_action`, `runoff_action`
+/// 11: `market_feed`, `market_program`, `market_price_offset`, `market_min_price`, `market_cleared`
+/// 12: `authority`
+/// 13: `draft`
+/// 14: `kind`
+/// 15: `max_weight_per_voter`
+/// 16: `class_max_votes`, `class_weight_bps`, `class_votes`
+/// 17: `delegation_epoch`
+/// 18: `delegation_state`
+/// 19: `audited`
+/// 20: `hive_seeded`
+/// 21: `early_vote_curve`
+/// 22: `log`
+/// 23: `excluded_count`, `excluded`
+/// 24: `extension_policy`, `extensions`, `extended_slots`
+/// 25: `identity_weighted`
+/// 26: `member_collection`
+/// 27: `stake_lock_program`
+/// 28: `priority`, `lane_counted`
+/// 29: `max_votes`
+/// 30: `tags`, `tags_indexed`
+/// 31: `quorum_weight_bps`, `snapshot_weight`
+/// 32: `creation_fee`, `fee_rebate`
+/// 33: `privacy`
+/// 34: `validator_counted`
+/// 35: `threshold_bps`, `approval_rounding`
+/// 36: `backup_validator`
+pub const FAST_ACTION_VERSION: u8 = 36;
+
+#[event]
+pub struct FastActionMigrated {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub from_version: u8,
+    pub to_version: u8,
+}
@@ -0,0 +1,16 @@
+// Creator bonds of Optimistic actions
+
+use anchor_lang::prelude::*;
+
+/// Lamports an optimistic action's creator puts at stake
+pub const OPTIMISTIC_BOND: u64 = 10_000_000;
+
+#[event]
+pub struct BondReleased {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub amount: u64,
+    /// Vetoed: the bond went to the treasury
+    pub forfeited: bool,
+}
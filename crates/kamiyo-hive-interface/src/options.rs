@@ -0,0 +1,16 @@
+// Multi-option ballots
+
+use anchor_lang::prelude::*;
+
+/// Options an action can offer
+pub const MAX_OPTIONS: usize = 8;
+
+#[event]
+pub struct OptionVoteCast {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub voter_commitment: [u8; 32],
+    pub vote_count: u32,
+    /// Weight given to each option, in option order
+    pub weights: Vec<u64>,
+}
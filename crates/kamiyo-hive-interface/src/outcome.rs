@@ -0,0 +1,20 @@
+// What assert_action_passed returns to calling programs
+
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ActionPassed {
+    pub action: Pubkey,
+    pub action_id: u64,
+    pub action_hash: [u8; 32],
+    /// Default for actions outside a hive
+    pub hive: Pubkey,
+    /// Graded tiers met above the base threshold (0 = plain pass)
+    pub tier: u8,
+    pub finalized_slot: u64,
+    pub result_digest: [u8; 32],
+    /// Past the dispute window with any market gate cleared, i.e. as final
+    /// as execute_action requires. Until then a challenge or veto can still
+    /// overturn the result.
+    pub settled: bool,
+}
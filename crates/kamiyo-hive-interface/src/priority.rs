@@ -0,0 +1,37 @@
+// Priority lanes. Every action runs in a lane, LOW_PRIORITY the lowest, and
+// a hive's ActiveActions index counts its running actions per lane, so
+// validators and keepers sharing one ER session can commit the critical ones
+// first
+
+use anchor_lang::prelude::*;
+
+pub const ACTIVE_ACTIONS_SEED: &[u8] = b"active_actions";
+
+pub const PRIORITY_LANES: usize = 4;
+/// Lane capped by GlobalConfig::max_low_priority_actions
+pub const LOW_PRIORITY: u8 = 0;
+/// Lane of actions that don't pick one
+pub const DEFAULT_PRIORITY: u8 = 1;
+
+/// Running actions of a hive, or of unscoped actions, per priority lane.
+/// Counted at creation and retired once the action is final
+#[account]
+pub struct ActiveActions {
+    /// Default for actions outside any hive
+    pub hive: Pubkey,                  // 32
+    pub counts: [u32; PRIORITY_LANES], // 16
+    pub bump: u8,                      // 1
+}
+
+impl ActiveActions {
+    pub const LEN: usize = 57; // 8 disc + 49 fields
+}
+
+#[event]
+pub struct ActiveActionsUpdated {
+    pub hive: Pubkey,
+    pub action: Pubkey,
+    pub priority: u8,
+    /// Actions now running in the lane
+    pub active: u32,
+}
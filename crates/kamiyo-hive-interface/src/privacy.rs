@@ -0,0 +1,123 @@
+// Ballot privacy levels: what an action's ballots disclose and when, chosen
+// by its creator
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+const COMMITMENT_DOMAIN: &[u8] = b"ballot_commitment";
+
+pub const ANONYMOUS_BALLOTS_SEED: &[u8] = b"anonymous_ballots";
+pub const NULLIFIER_SEED: &[u8] = b"nullifier";
+pub const BALLOT_VERIFIER_SEED: &[u8] = b"ballot_verifier";
+
+/// Slots after the deadline in which commit-reveal ballots are opened
+pub const REVEAL_WINDOW_SLOTS: u64 = 75;
+
+/// Every level but Public takes headcount for/against actions only
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PrivacyLevel {
+    /// Each ballot's choice is recorded and counted as it is cast
+    #[default]
+    Public,
+    /// vote_fast records only a ballot_commitment and counts the ballot
+    /// toward quorum; the choice counts once revealed in the reveal window
+    CommitReveal,
+    /// The choice is counted inside the validator's TEE and kept out of the
+    /// ballot record and the standings board; the tally must be attested
+    Encrypted,
+    /// Ballots name a nullifier instead of the voter, with a proof of
+    /// membership in the hive's registry that an allowlisted verifier
+    /// program checks; cast through vote_anonymous
+    AnonymousZk,
+}
+
+/// What a commit-reveal ballot commits to:
+/// sha256("ballot_commitment" || action || voter || vote_value || salt)
+pub fn ballot_commitment(action: &Pubkey, voter: &Pubkey, vote_value: bool, salt: &[u8; 32]) -> [u8; 32] {
+    hashv(&[COMMITMENT_DOMAIN, action.as_ref(), voter.as_ref(), &[vote_value as u8], salt]).to_bytes()
+}
+
+/// Whether a recorded commitment opens to `commitment`. A truncated one, as
+/// vote_compact records it, is checked on its 16 bytes.
+pub fn commitment_matches(recorded: &[u8; 32], commitment: &[u8; 32]) -> bool {
+    if recorded[16..] == [0; 16] {
+        recorded[..16] == commitment[..16]
+    } else {
+        recorded == commitment
+    }
+}
+
+/// An AnonymousZk action's ballot verifier. A ballot's proof is checked by
+/// invoking `verifier` with no accounts and, as data, the public inputs
+/// `members_root || action || nullifier || vote_value` followed by the proof.
+#[account]
+pub struct AnonymousBallots {
+    pub action: Pubkey,          // 32
+    pub verifier: Pubkey,        // 32
+    /// The hive's member_root when ballots were enabled
+    pub members_root: [u8; 32],  // 32
+    pub bump: u8,                // 1
+}
+
+impl AnonymousBallots {
+    pub const LEN: usize = 105; // 8 disc + 97 fields
+}
+
+/// A ballot verifier program the admin has vetted. Only allowed verifiers
+/// can be named for an action, and a verifier disallowed later stops taking
+/// ballots.
+#[account]
+pub struct BallotVerifier {
+    pub verifier: Pubkey,        // 32
+    pub allowed: bool,           // 1
+    pub bump: u8,                // 1
+}
+
+impl BallotVerifier {
+    pub const LEN: usize = 42; // 8 disc + 34 fields
+}
+
+/// Spent nullifier of an anonymous ballot; its existence bars a second one
+#[account]
+pub struct Nullifier {
+    pub action: Pubkey,          // 32
+    pub nullifier: [u8; 32],     // 32
+    pub vote_value: bool,        // 1
+    pub bump: u8,                // 1
+}
+
+impl Nullifier {
+    pub const LEN: usize = 74; // 8 disc + 66 fields
+}
+
+#[event]
+pub struct BallotRevealed {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub voter: Pubkey,
+    pub vote_value: bool,
+}
+
+#[event]
+pub struct AnonymousBallotsEnabled {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub verifier: Pubkey,
+    pub members_root: [u8; 32],
+}
+
+#[event]
+pub struct BallotVerifierSet {
+    pub verifier: Pubkey,
+    pub allowed: bool,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct AnonymousVoteCast {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub nullifier: [u8; 32],
+    pub vote_value: bool,
+    pub vote_count: u32,
+}
@@ -0,0 +1,59 @@
+// Ranked-choice ballots and the instant runoff count
+
+use anchor_lang::prelude::*;
+
+use crate::MAX_OPTIONS;
+
+pub const RANKED_BALLOT_SEED: &[u8] = b"ranked_ballot";
+
+/// `pending_elimination` when no ballots are waiting to move
+pub const NO_ELIMINATION: u8 = u8::MAX;
+
+#[account]
+pub struct RankedBallot {
+    pub action: Pubkey,               // 32
+    pub voter: Pubkey,                // 32
+    pub ranking: [u8; MAX_OPTIONS],   // 8
+    pub len: u8,                      // 1
+    /// Index into `ranking` of the option currently holding this ballot
+    pub position: u8,                 // 1
+    pub bump: u8,                     // 1
+}
+
+impl RankedBallot {
+    pub const LEN: usize = 83; // 8 disc + 75 fields
+
+    pub fn current(&self) -> Option<u8> {
+        (self.position < self.len).then(|| self.ranking[self.position as usize])
+    }
+}
+
+#[event]
+pub struct RankedVoteCast {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub voter_commitment: [u8; 32],
+    pub vote_count: u32,
+    pub ranking: Vec<u8>,
+}
+
+#[event]
+pub struct OptionEliminated {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub round: u8,
+    pub option: u8,
+    /// Ballots that must move before the next round
+    pub ballots: u64,
+}
+
+#[event]
+pub struct BallotsRedistributed {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub round: u8,
+    pub eliminated: u8,
+    pub moved: u32,
+    /// Ballots still on the eliminated option
+    pub remaining: u64,
+}
@@ -0,0 +1,76 @@
+// Redundant delegation: a backup validator standing by for an action's
+// primary through a mirror PDA, with the first signed commit standing
+
+use anchor_lang::prelude::*;
+
+use crate::{DelegationState, VoteResult};
+
+pub const ACTION_MIRROR_SEED: &[u8] = b"action_mirror";
+
+/// A redundantly delegated action's stand-in on its backup validator. Until
+/// resolve_redundant_commit it holds the backup's commit, if any; after, the
+/// commit that lost, if any, for ConflictingCommit evidence.
+#[account]
+pub struct ActionMirror {
+    pub action: Pubkey,                    // 32
+    pub hive: Pubkey,                      // 32
+    pub backup: Pubkey,                    // 32
+    pub delegation_state: DelegationState, // 1
+    /// Validator whose signed commit is held below; default while none is
+    pub committed_by: Pubkey,              // 32
+    pub votes_for: u32,                    // 4
+    pub votes_against: u32,                // 4
+    pub weight_for: u64,                   // 8
+    pub weight_against: u64,               // 8
+    pub vote_count: u32,                   // 4
+    pub result: VoteResult,                // 2
+    pub committed_slot: u64,               // 8
+    /// attestation_digest the committing validator signed
+    pub digest: [u8; 32],                  // 32
+    pub resolved: bool,                    // 1
+    pub bump: u8,                          // 1
+}
+
+impl ActionMirror {
+    pub const LEN: usize = 209; // 8 disc + 201 fields
+}
+
+#[event]
+pub struct RedundancyEnabled {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub backup: Pubkey,
+    pub enabled_by: Pubkey,
+}
+
+#[event]
+pub struct MirrorDelegated {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub backup: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct MirrorCommitted {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub backup: Pubkey,
+    pub result: VoteResult,
+    pub digest: [u8; 32],
+    pub slot: u64,
+}
+
+#[event]
+pub struct RedundantCommitResolved {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    /// Validator whose commit stands
+    pub winner: Pubkey,
+    /// Validator whose later commit was set aside; default if only one
+    /// committed
+    pub loser: Pubkey,
+    /// Whether the two commits signed different tallies
+    pub conflicting: bool,
+    pub slot: u64,
+}
@@ -0,0 +1,51 @@
+// Reasons tally_and_commit turns a tally down
+
+use anchor_lang::prelude::*;
+
+use crate::FastVoteError;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TallyRejection {
+    AlreadyExecuted,
+    VotingNotEnded,
+    /// An audited action's tally pages are not all verified
+    TallyNotVerified,
+    QuorumNotMet,
+    TurnoutNotMet,
+    /// A ranked action still has a round to count
+    RunoffUndecided,
+}
+
+impl TallyRejection {
+    const ERRORS: [(FastVoteError, TallyRejection); 6] = [
+        (FastVoteError::ActionAlreadyExecuted, TallyRejection::AlreadyExecuted),
+        (FastVoteError::VotingNotEnded, TallyRejection::VotingNotEnded),
+        (FastVoteError::TallyNotVerified, TallyRejection::TallyNotVerified),
+        (FastVoteError::QuorumNotMet, TallyRejection::QuorumNotMet),
+        (FastVoteError::TurnoutNotMet, TallyRejection::TurnoutNotMet),
+        (FastVoteError::RunoffUndecided, TallyRejection::RunoffUndecided),
+    ];
+
+    /// None for failures that are not about the tally, e.g. a missing role
+    pub fn of(err: &Error) -> Option<Self> {
+        let Error::AnchorError(err) = err else {
+            return None;
+        };
+        Self::ERRORS
+            .iter()
+            .find(|(code, _)| u32::from(*code) == err.error_code_number)
+            .map(|(_, reason)| *reason)
+    }
+}
+
+#[event]
+pub struct TallyRejected {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub reason: TallyRejection,
+    pub vote_count: u32,
+    /// Votes required at `slot`, after any decay
+    pub quorum: u32,
+    pub slot: u64,
+    pub deadline_slot: u64,
+}
@@ -0,0 +1,77 @@
+// Relayers pinned to an action for ballots submitted on a voter's behalf
+
+use anchor_lang::prelude::*;
+
+pub const ACTION_RELAYERS_SEED: &[u8] = b"action_relayers";
+pub const SELF_SUBMIT_SEED: &[u8] = b"self_submit";
+
+/// Relayers one action may pin
+pub const MAX_RELAYERS: usize = 3;
+
+/// Only `relayers` may submit the action's relayed ballots, until a voter
+/// has waited `fallback_slots` for one to
+#[account]
+pub struct ActionRelayers {
+    pub action: Pubkey,                    // 32
+    pub relayers: [Pubkey; MAX_RELAYERS],  // 96
+    pub relayer_count: u8,                 // 1
+    /// Slots after request_self_submit before the voter may submit itself
+    pub fallback_slots: u64,               // 8
+    pub bump: u8,                          // 1
+}
+
+impl ActionRelayers {
+    pub const LEN: usize = 146; // 8 disc + 138 fields
+
+    pub fn relayers(&self) -> &[Pubkey] {
+        &self.relayers[..self.relayer_count as usize]
+    }
+
+    /// The ActionRelayers at `info`, or none if the action pins no relayer
+    pub fn load(info: &AccountInfo) -> Result<Option<ActionRelayers>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        Ok(Some(ActionRelayers::try_deserialize(&mut &info.try_borrow_data()?[..])?))
+    }
+}
+
+/// A voter's notice that its relayed ballot has not landed; from
+/// `requested_slot + fallback_slots` it may submit the ballot itself
+#[account]
+pub struct SelfSubmit {
+    pub action: Pubkey,      // 32
+    /// Key the ballot is cast under, e.g. a voter group's key
+    pub voter: Pubkey,       // 32
+    pub requested_slot: u64, // 8
+    pub bump: u8,            // 1
+}
+
+impl SelfSubmit {
+    pub const LEN: usize = 81; // 8 disc + 73 fields
+
+    /// The SelfSubmit at `info`, or none if the voter never asked
+    pub fn load(info: &AccountInfo) -> Result<Option<SelfSubmit>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        Ok(Some(SelfSubmit::try_deserialize(&mut &info.try_borrow_data()?[..])?))
+    }
+}
+
+#[event]
+pub struct ActionRelayersSet {
+    pub action: Pubkey,
+    pub relayers: Vec<Pubkey>,
+    pub fallback_slots: u64,
+}
+
+#[event]
+pub struct SelfSubmitRequested {
+    pub action: Pubkey,
+    pub voter: Pubkey,
+    /// First slot the voter may submit itself
+    pub due_slot: u64,
+}
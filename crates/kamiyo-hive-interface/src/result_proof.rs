@@ -0,0 +1,126 @@
+// Portable result proofs: a settled action's outcome in a fixed byte layout,
+// with validator signatures over it, for verifiers that cannot query Solana
+
+use anchor_lang::prelude::*;
+
+use crate::{attestation_digest, tally, VoteResult, MAX_ATTESTORS};
+
+pub const RESULT_PROOF_SEED: &[u8] = b"result_proof";
+
+pub const RESULT_PROOF_VERSION: u8 = 1;
+
+/// The hive's attestors and the validator that hosted the action, each at
+/// most once
+pub const MAX_PROOF_SIGNATURES: usize = MAX_ATTESTORS + 1;
+
+/// Encoded length without signatures
+const PROOF_HEADER_LEN: usize = 236;
+const PROOF_SIGNATURE_LEN: usize = 96;
+
+/// Longest encoded proof
+pub const MAX_RESULT_PROOF_LEN: usize = PROOF_HEADER_LEN + MAX_PROOF_SIGNATURES * PROOF_SIGNATURE_LEN;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ProofSignature {
+    pub validator: Pubkey,
+    /// ed25519 signature over the proof's attestation_digest
+    pub signature: [u8; 64],
+}
+
+/// Encoded by `encode`, all integers little-endian:
+///
+/// ```text
+/// version u8 | program_id [32] | action [32] | action_id u64 | action_hash [32] | hive [32]
+/// | votes_for u32 | votes_against u32 | weight_for u64 | weight_against u64 | result [2]
+/// | finalized_slot u64 | result_digest [32] | account_hash [32]
+/// | signature count u8 | (validator [32] | signature [64]) per signature
+/// ```
+///
+/// `result` is `VoteResult::encode`. A verifier recomputes `result_digest`
+/// from the fields before it, then checks each signature over
+/// `attestation_digest` against the validators it trusts.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ResultProof {
+    pub program_id: Pubkey,
+    pub action: Pubkey,
+    pub action_id: u64,
+    pub action_hash: [u8; 32],
+    pub hive: Pubkey,
+    pub votes_for: u32,
+    pub votes_against: u32,
+    pub weight_for: u64,
+    pub weight_against: u64,
+    pub result: VoteResult,
+    pub finalized_slot: u64,
+    pub result_digest: [u8; 32],
+    /// sha256 of the action account's data when the proof was exported
+    pub account_hash: [u8; 32],
+    pub signatures: Vec<ProofSignature>,
+}
+
+impl ResultProof {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(PROOF_HEADER_LEN + self.signatures.len() * PROOF_SIGNATURE_LEN);
+        out.push(RESULT_PROOF_VERSION);
+        out.extend_from_slice(self.program_id.as_ref());
+        out.extend_from_slice(self.action.as_ref());
+        out.extend_from_slice(&self.action_id.to_le_bytes());
+        out.extend_from_slice(&self.action_hash);
+        out.extend_from_slice(self.hive.as_ref());
+        out.extend_from_slice(&self.votes_for.to_le_bytes());
+        out.extend_from_slice(&self.votes_against.to_le_bytes());
+        out.extend_from_slice(&self.weight_for.to_le_bytes());
+        out.extend_from_slice(&self.weight_against.to_le_bytes());
+        out.extend_from_slice(&self.result.encode());
+        out.extend_from_slice(&self.finalized_slot.to_le_bytes());
+        out.extend_from_slice(&self.result_digest);
+        out.extend_from_slice(&self.account_hash);
+        out.push(self.signatures.len() as u8);
+        for signature in &self.signatures {
+            out.extend_from_slice(signature.validator.as_ref());
+            out.extend_from_slice(&signature.signature);
+        }
+        out
+    }
+
+    /// What the signatures sign: the result's attestation digest, as
+    /// attestors sign it for tally_and_commit
+    pub fn attestation_digest(&self) -> [u8; 32] {
+        attestation_digest(&self.action, self.votes_for, self.votes_against, &self.result)
+    }
+
+    /// Whether `result_digest` follows from the proof's fields
+    pub fn digest_matches(&self) -> bool {
+        let digest = tally::result_digest(
+            self.action_id,
+            &self.action_hash,
+            self.votes_for,
+            self.votes_against,
+            &self.result,
+            self.finalized_slot,
+        );
+        digest == self.result_digest
+    }
+}
+
+/// Holds an exported proof until its payer closes it
+#[account]
+pub struct ResultProofBuffer {
+    pub action: Pubkey, // 32
+    pub payer: Pubkey,  // 32
+    pub proof: Vec<u8>, // 4 + MAX_RESULT_PROOF_LEN
+    pub bump: u8,       // 1
+}
+
+impl ResultProofBuffer {
+    pub const LEN: usize = 1369; // 8 disc + 1361 fields
+}
+
+#[event]
+pub struct ResultProofExported {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    /// ResultProof::encode
+    pub proof: Vec<u8>,
+}
@@ -0,0 +1,109 @@
+// The validator reward pool, the creation fee that funds it, and the share of
+// that fee refunded to creators whose actions drew a high turnout
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+pub const REWARD_POOL_SEED: &[u8] = b"reward_pool";
+
+/// Slots past the deadline a session may finalize and still earn (~1 min)
+pub const ON_TIME_GRACE_SLOTS: u64 = 150;
+
+#[account]
+pub struct RewardPool {
+    /// Lamports charged per create_fast_action; 0 disables the fee
+    pub creation_fee: u64,     // 8
+    /// Recorded sessions not yet claimed, across all validators
+    pub pending_sessions: u64, // 8
+    pub total_paid: u64,       // 8
+    pub bump: u8,              // 1
+}
+
+impl RewardPool {
+    pub const LEN: usize = 33; // 8 disc + 25 fields
+
+    /// Pay the creation fee into the pool
+    pub fn charge<'info>(
+        pool: &Account<'info, RewardPool>,
+        creator: &Signer<'info>,
+        system_program: &Program<'info, System>,
+    ) -> Result<()> {
+        if pool.creation_fee == 0 {
+            return Ok(());
+        }
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: creator.to_account_info(),
+                    to: pool.to_account_info(),
+                },
+            ),
+            pool.creation_fee,
+        )
+    }
+}
+
+/// Share of its creation fee an action's creator gets back once it is
+/// tallied, scaled by turnout: `rebate_bps` of the fee at `full_turnout_bps`
+/// of the electorate or more, proportionally less below it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct FeeRebate {
+    /// Turnout earning the full rebate; 0 disables rebates
+    pub full_turnout_bps: u16, // 2
+    pub rebate_bps: u16,       // 2
+}
+
+impl FeeRebate {
+    /// Rebate on `fee` for `votes` cast out of `electorate`; nothing without
+    /// an electorate to measure turnout against
+    pub fn rebate(&self, fee: u64, votes: u32, electorate: u32) -> u64 {
+        if self.full_turnout_bps == 0 || electorate == 0 {
+            return 0;
+        }
+        let turnout_bps = (votes as u64 * 10_000 / electorate as u64).min(self.full_turnout_bps as u64);
+        (fee as u128 * self.rebate_bps as u128 * turnout_bps as u128
+            / (10_000 * self.full_turnout_bps as u128)) as u64
+    }
+}
+
+#[event]
+pub struct CreationFeeUpdated {
+    pub creation_fee: u64,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct ValidatorSessionRecorded {
+    pub validator: Pubkey,
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub sessions_hosted: u64,
+}
+
+#[event]
+pub struct ValidatorRewardsClaimed {
+    pub validator: Pubkey,
+    pub sessions: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct FeeRebateEarned {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub votes: u32,
+    pub electorate: u32,
+    pub amount: u64,
+}
+
+#[event]
+pub struct FeeRebatePaid {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub creator: Pubkey,
+    pub amount: u64,
+}
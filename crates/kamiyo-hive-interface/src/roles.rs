@@ -0,0 +1,98 @@
+// Hive roles and their members
+
+use anchor_lang::prelude::*;
+
+pub const ROLES_SEED: &[u8] = b"roles";
+
+/// Members per hive; keeps Roles at a fixed size
+pub const MAX_ROLE_MEMBERS: usize = 16;
+
+/// Bit of RoleMember::roles set while the member is marked inactive; not a
+/// Role, so it grants nothing
+pub const INACTIVE_MASK: u8 = 1 << 7;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    /// May open actions scoped to the hive
+    Creator,
+    /// May cancel any of the hive's actions, not just their own
+    Canceller,
+    /// May finalize the hive's actions
+    Tallier,
+    /// May grant and revoke roles
+    Admin,
+}
+
+impl Role {
+    pub fn mask(self) -> u8 {
+        match self {
+            Role::Creator => 1 << 0,
+            Role::Canceller => 1 << 1,
+            Role::Tallier => 1 << 2,
+            Role::Admin => 1 << 3,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RoleMember {
+    pub member: Pubkey, // 32
+    /// Role masks, and INACTIVE_MASK
+    pub roles: u8,      // 1
+}
+
+impl RoleMember {
+    pub fn is_active(&self) -> bool {
+        self.roles & INACTIVE_MASK == 0
+    }
+
+    /// Holds any role, active or not
+    pub fn has_roles(&self) -> bool {
+        self.roles & !INACTIVE_MASK != 0
+    }
+}
+
+#[account]
+pub struct Roles {
+    pub hive: Pubkey,              // 32
+    pub bump: u8,                  // 1
+    pub members: Vec<RoleMember>,  // 4 + 33 * MAX_ROLE_MEMBERS
+}
+
+impl Roles {
+    pub const LEN: usize = 8 + 32 + 1 + 4 + 33 * MAX_ROLE_MEMBERS;
+
+    pub fn has_role(&self, key: &Pubkey, role: Role) -> bool {
+        self.members
+            .iter()
+            .any(|m| m.member == *key && m.roles & role.mask() != 0)
+    }
+
+    /// Members counted in the electorate of the hive's actions
+    pub fn electorate(&self) -> u32 {
+        self.members.iter().filter(|m| m.is_active()).count() as u32
+    }
+
+    pub fn admin_count(&self) -> usize {
+        self.members
+            .iter()
+            .filter(|m| m.roles & Role::Admin.mask() != 0)
+            .count()
+    }
+}
+
+#[event]
+pub struct RoleGranted {
+    pub hive: Pubkey,
+    pub member: Pubkey,
+    pub role: Role,
+    pub granted_by: Pubkey,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub hive: Pubkey,
+    pub member: Pubkey,
+    pub role: Role,
+    pub revoked_by: Pubkey,
+}
@@ -0,0 +1,14 @@
+// Runoffs spawned from failed multi-option actions
+
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct RunoffSpawned {
+    pub parent: Pubkey,
+    pub runoff: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    /// Parent option indices left on the runoff ballot
+    pub options: [u8; 2],
+    pub deadline_slot: u64,
+}
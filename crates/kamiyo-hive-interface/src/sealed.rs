@@ -0,0 +1,36 @@
+// Descriptions sealed to a TEE validator's key until the action passes
+
+use anchor_lang::prelude::*;
+
+pub const SEALED_DESCRIPTION_SEED: &[u8] = b"sealed_description";
+
+#[account]
+pub struct SealedDescription {
+    pub action: Pubkey,              // 32
+    /// sha256 of the ciphertext, so the TEE can check what it was handed
+    pub ciphertext_hash: [u8; 32],   // 32
+    /// Key the description is encrypted to; its holder reveals
+    pub encryption_key: [u8; 32],    // 32
+    pub revealed_slot: u64,          // 8 (0 = sealed)
+    pub bump: u8,                    // 1
+}
+
+impl SealedDescription {
+    pub const LEN: usize = 113; // 8 disc + 105 fields
+}
+
+#[event]
+pub struct DescriptionSealed {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub ciphertext_hash: [u8; 32],
+    pub encryption_key: [u8; 32],
+}
+
+#[event]
+pub struct DescriptionRevealed {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub validator: Pubkey,
+    pub description: String,
+}
@@ -0,0 +1,75 @@
+// Ballot sequencing commitments: the validator hosting an action commits, a
+// batch at a time, to the order it received the action's ballots in, as a
+// hash chain a dispute can hold it to
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+const SEQUENCE_DOMAIN: &[u8] = b"ballot_sequence";
+
+pub const SEQUENCE_BATCH_SEED: &[u8] = b"sequence_batch";
+
+/// Ballots per posted batch, so a batch and its exclusion proof each fit
+/// one transaction
+pub const MAX_SEQUENCE_BATCH: usize = 12;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SequencedBallot {
+    pub voter: Pubkey,
+    /// Slot the validator received the ballot in
+    pub received_slot: u64,
+    /// sha256 of the ballot instruction's data
+    pub ballot_hash: [u8; 32],
+}
+
+impl SequencedBallot {
+    /// The chain head after this ballot of `action` follows `head`:
+    /// sha256("ballot_sequence" || head || action || voter || received_slot || ballot_hash)
+    pub fn link(&self, head: &[u8; 32], action: &Pubkey) -> [u8; 32] {
+        hashv(&[
+            SEQUENCE_DOMAIN,
+            head,
+            action.as_ref(),
+            self.voter.as_ref(),
+            &self.received_slot.to_le_bytes(),
+            &self.ballot_hash,
+        ])
+        .to_bytes()
+    }
+}
+
+/// The head of the chain extending `head` with `ballots`, in order
+pub fn sequence_head(head: [u8; 32], action: &Pubkey, ballots: &[SequencedBallot]) -> [u8; 32] {
+    ballots.iter().fold(head, |head, ballot| ballot.link(&head, action))
+}
+
+/// One posted batch of a validator's sequence for an action. Batches chain:
+/// each starts from the previous one's head, the first from zero.
+#[account]
+pub struct SequenceBatch {
+    pub action: Pubkey,        // 32
+    pub validator: Pubkey,     // 32
+    pub index: u32,            // 4
+    pub prev_head: [u8; 32],   // 32
+    pub head: [u8; 32],        // 32
+    pub count: u8,             // 1
+    /// Received slot of the batch's last ballot; the next batch's ballots
+    /// cannot be received earlier
+    pub through_slot: u64,     // 8
+    pub posted_slot: u64,      // 8
+    pub bump: u8,              // 1
+}
+
+impl SequenceBatch {
+    pub const LEN: usize = 158; // 8 disc + 150 fields
+}
+
+#[event]
+pub struct SequenceCommitted {
+    pub action: Pubkey,
+    pub validator: Pubkey,
+    pub index: u32,
+    pub head: [u8; 32],
+    pub count: u8,
+    pub through_slot: u64,
+}
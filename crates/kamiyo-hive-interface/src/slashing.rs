@@ -0,0 +1,77 @@
+// Reports of late or abandoned ER sessions, and the treasury slashed bonds go to
+
+use anchor_lang::prelude::*;
+
+pub const SLASH_REPORT_SEED: &[u8] = b"slash_report";
+pub const TREASURY_SEED: &[u8] = b"treasury";
+
+/// Finalizing this many slots past the deadline counts as a missed commit (~10 min)
+pub const LATE_COMMIT_SLOTS: u64 = 1_500;
+
+/// Slots a report stays open to governance before it can execute (~1 hour)
+pub const SLASH_DISPUTE_WINDOW_SLOTS: u64 = 9_000;
+
+/// Share of the bond slashed per missed commit, in basis points
+pub const SLASH_BPS: u64 = 1_000;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SlashStatus {
+    Pending,
+    /// Voided by governance during the dispute window
+    Voided,
+    Executed,
+}
+
+/// One per action, so a session can only be slashed once
+#[account]
+pub struct SlashReport {
+    pub action: Pubkey,      // 32
+    pub validator: Pubkey,   // 32
+    pub reporter: Pubkey,    // 32
+    pub reported_slot: u64,  // 8
+    pub status: SlashStatus, // 1
+    pub amount: u64,         // 8 (set on execution)
+    pub bump: u8,            // 1
+}
+
+impl SlashReport {
+    pub const LEN: usize = 122; // 8 disc + 114 fields
+}
+
+/// Receives slashed bonds
+#[account]
+pub struct Treasury {
+    pub total_slashed: u64, // 8
+    pub bump: u8,           // 1
+}
+
+impl Treasury {
+    pub const LEN: usize = 17; // 8 disc + 9 fields
+}
+
+#[event]
+pub struct MissedCommitReported {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub validator: Pubkey,
+    pub reporter: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct SlashVoided {
+    pub action: Pubkey,
+    pub validator: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct ValidatorSlashed {
+    pub action: Pubkey,
+    pub validator: Pubkey,
+    pub amount: u64,
+    /// Part of `amount` paid to the reporter
+    pub bounty: u64,
+    pub remaining_bond: u64,
+}
@@ -0,0 +1,29 @@
+// Execution stages that pin execute_action's accounts a slot ahead
+
+use anchor_lang::prelude::*;
+
+pub const EXECUTION_STAGE_SEED: &[u8] = b"execution_stage";
+
+#[account]
+pub struct ExecutionStage {
+    pub action: Pubkey,       // 32
+    pub executor: Pubkey,     // 32
+    /// `accounts_hash` of execute_action's remaining accounts, programs
+    /// included, in the order they will be passed
+    pub commitment: [u8; 32], // 32
+    pub staged_slot: u64,     // 8
+    pub bump: u8,             // 1
+}
+
+impl ExecutionStage {
+    pub const LEN: usize = 113; // 8 disc + 105 fields
+}
+
+#[event]
+pub struct ExecutionStaged {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub executor: Pubkey,
+    pub commitment: [u8; 32],
+    pub slot: u64,
+}
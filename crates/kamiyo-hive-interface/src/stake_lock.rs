@@ -0,0 +1,64 @@
+// Stake locks on token-weighted ballots, placed through the configured
+// stake lock program
+
+use anchor_lang::prelude::*;
+
+pub const STAKE_LOCK_SEED: &[u8] = b"stake_lock";
+pub const STAKE_LOCK_CLAIM_SEED: &[u8] = b"stake_lock_claim";
+/// Signs lock_stake and release_stake CPIs, so the stake lock program can
+/// tell them from anyone else's; with a token program as the lock program,
+/// the weight mint's freeze authority
+pub const STAKE_LOCK_AUTHORITY_SEED: &[u8] = b"stake_lock_authority";
+
+/// sha256("global:lock_stake")[..8]; accounts: lock authority (signer),
+/// voter, token account (writable), weight mint
+pub const LOCK_STAKE_DISCRIMINATOR: [u8; 8] = [111, 186, 175, 228, 49, 165, 27, 248];
+/// sha256("global:release_stake")[..8]; accounts as for lock_stake
+pub const RELEASE_STAKE_DISCRIMINATOR: [u8; 8] = [51, 5, 28, 250, 185, 168, 18, 53];
+
+/// A voter's lock, counted across the stake-locked actions it has ballots
+/// on that are not yet final
+#[account]
+pub struct StakeLock {
+    pub voter: Pubkey,         // 32
+    /// Lock program and token account the lock was placed with; fixed
+    /// while `active` > 0
+    pub program: Pubkey,       // 32
+    pub token_account: Pubkey, // 32
+    pub active: u32,           // 4
+    pub bump: u8,              // 1
+}
+
+impl StakeLock {
+    pub const LEN: usize = 109; // 8 disc + 101 fields
+}
+
+/// One ballot's share of its voter's StakeLock; closed, to the voter, when
+/// release_stake_lock gives the share back
+#[account]
+pub struct StakeLockClaim {
+    pub voter: Pubkey, // 32
+    pub bump: u8,      // 1
+}
+
+impl StakeLockClaim {
+    pub const LEN: usize = 41; // 8 disc + 33 fields
+}
+
+#[event]
+pub struct StakeLocked {
+    pub voter: Pubkey,
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub token_account: Pubkey,
+    /// Actions now holding the lock
+    pub active: u32,
+}
+
+#[event]
+pub struct StakeReleased {
+    pub voter: Pubkey,
+    pub action: Pubkey,
+    /// Actions still holding the lock; 0 once the stake is released
+    pub active: u32,
+}
@@ -0,0 +1,35 @@
+// Live standing of an action, returned by get_standing, and the result a
+// tally closing it now would record, returned by preview_tally
+
+use anchor_lang::prelude::*;
+
+use crate::{TallyRejection, VoteResult};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Standing {
+    /// Percent of the weight cast that backs the action, or on multi-option
+    /// actions the leading option; 0 before any votes
+    pub approval_pct: u8,
+    /// Slots left to vote; 0 once the deadline has passed
+    pub slots_remaining: u64,
+    pub vote_count: u32,
+    /// Votes a tally needs right now
+    pub required_votes: u32,
+    /// The recorded result once finalized; otherwise what a tally would
+    /// record if voting closed now, or Pending while quorum, turnout or a
+    /// ranked count is still short
+    pub projected: VoteResult,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct TallyPreview {
+    /// What tally_and_commit would record if the deadline were `slot`;
+    /// Pending when it would refuse
+    pub result: VoteResult,
+    /// Why it would refuse, e.g. QuorumNotMet; None when it would finalize
+    pub rejection: Option<TallyRejection>,
+    pub vote_count: u32,
+    /// Votes a tally at `slot` needs, after any quorum decay
+    pub required_votes: u32,
+    pub slot: u64,
+}
@@ -0,0 +1,82 @@
+// Per-hive standings board: the live tallies of a hive's most recent actions
+// in one fixed-layout account, so an agent can read them all at once instead
+// of polling each action
+
+use anchor_lang::prelude::*;
+
+use crate::VoteResult;
+
+pub const STANDINGS_BOARD_SEED: &[u8] = b"standings_board";
+
+pub const BOARD_SLOTS: usize = 16;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BoardStatus {
+    #[default]
+    Empty,
+    Open,
+    Passed,
+    Failed,
+    Contested,
+}
+
+impl BoardStatus {
+    /// The status of a finalized action with `result`; Chosen counts as
+    /// Passed
+    pub fn finalized(result: &VoteResult) -> Self {
+        match result {
+            VoteResult::Passed { .. } | VoteResult::Chosen { .. } => BoardStatus::Passed,
+            VoteResult::Contested { .. } => BoardStatus::Contested,
+            _ => BoardStatus::Failed,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct BoardEntry {
+    pub action: Pubkey,      // 32
+    pub action_id: u64,      // 8
+    pub status: BoardStatus, // 1
+    pub deadline_slot: u64,  // 8
+    pub votes_for: u32,      // 4
+    pub votes_against: u32,  // 4
+    pub vote_count: u32,     // 4
+    pub weight_for: u64,     // 8
+    pub weight_against: u64, // 8
+    /// Slot of the ballot or tally that last wrote the entry
+    pub updated_slot: u64,   // 8
+}
+
+impl BoardEntry {
+    pub const LEN: usize = 85;
+}
+
+/// Entries are written in place, each to the slot its action already holds,
+/// else to an empty one, else over the finalized entry written longest ago,
+/// else over the open entry of the oldest action, if the new one is newer.
+/// A finalized action never takes an open action's slot.
+#[account]
+pub struct StandingsBoard {
+    pub hive: Pubkey,                       // 32
+    pub bump: u8,                           // 1
+    pub entries: [BoardEntry; BOARD_SLOTS], // 1360
+}
+
+impl StandingsBoard {
+    pub const LEN: usize = 1401; // 8 disc + 1393 fields
+    /// Offset of the first entry in the account data, discriminator included
+    pub const ENTRIES: usize = 41;
+
+    /// The board's live entries, newest action first
+    pub fn open(&self) -> Vec<&BoardEntry> {
+        let mut open: Vec<_> = self.entries.iter().filter(|e| e.status == BoardStatus::Open).collect();
+        open.sort_by_key(|e| std::cmp::Reverse(e.action_id));
+        open
+    }
+}
+
+#[event]
+pub struct StandingsBoardInitialized {
+    pub board: Pubkey,
+    pub hive: Pubkey,
+}
@@ -0,0 +1,50 @@
+// Action tags and the paginated index of open actions per tag
+
+use anchor_lang::prelude::*;
+
+pub const TAG_INDEX_SEED: &[u8] = b"tag_index";
+
+/// Tags one action may carry
+pub const MAX_TAGS: usize = 4;
+
+/// Actions one TagIndex page holds
+pub const TAG_PAGE_LEN: usize = 32;
+
+/// One page of the open actions carrying `tag`, of a hive or of unscoped
+/// actions. Pages of a tag are numbered from 0 without gaps, so a reader
+/// walks them until the next is missing. Filled at creation and emptied
+/// once the action is final
+#[account]
+pub struct TagIndex {
+    /// Default for actions outside any hive
+    pub hive: Pubkey,                    // 32
+    pub tag: [u8; 8],                    // 8
+    pub page: u32,                       // 4
+    pub count: u8,                       // 1
+    pub actions: [Pubkey; TAG_PAGE_LEN], // 1024
+    pub bump: u8,                        // 1
+}
+
+impl TagIndex {
+    pub const LEN: usize = 1078; // 8 disc + 1070 fields
+
+    pub fn actions(&self) -> &[Pubkey] {
+        &self.actions[..self.count as usize]
+    }
+}
+
+#[event]
+pub struct ActionTagged {
+    pub hive: Pubkey,
+    pub action: Pubkey,
+    pub tag: [u8; 8],
+    pub page: u32,
+}
+
+#[event]
+pub struct ActionUntagged {
+    pub hive: Pubkey,
+    pub action: Pubkey,
+    pub tag: [u8; 8],
+    pub page: u32,
+}
@@ -0,0 +1,208 @@
+// Pure tally arithmetic, kept free of account types so it can be reused
+// off-chain and exercised without a validator
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::{ApprovalRounding, FastVoteError, VoteResult, MAX_EXTRA_TIERS};
+
+/// Integer approval percentage, floored. Errors if nobody voted. Display
+/// only; pass/fail is decided on `approval_bps`.
+pub fn approval_pct(tally_for: u64, tally_against: u64) -> Result<u64> {
+    let total = (tally_for as u128)
+        .checked_add(tally_against as u128)
+        .ok_or(FastVoteError::VoteOverflow)?;
+    require!(total > 0, FastVoteError::QuorumNotMet);
+
+    let pct = (tally_for as u128)
+        .checked_mul(100)
+        .ok_or(FastVoteError::VoteOverflow)?
+        / total;
+    Ok(pct as u64)
+}
+
+/// `numerator` / `denominator` in basis points, rounded per `rounding`
+pub fn share_bps(numerator: u128, denominator: u128, rounding: ApprovalRounding) -> u64 {
+    let scaled = numerator * 10_000;
+    let (bps, rem) = (scaled / denominator, scaled % denominator);
+    let up = match rounding {
+        ApprovalRounding::Floor => false,
+        ApprovalRounding::Ceiling => rem > 0,
+        ApprovalRounding::HalfEven => match (rem * 2).cmp(&denominator) {
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Equal => bps % 2 == 1,
+            std::cmp::Ordering::Greater => true,
+        },
+    };
+    (bps + up as u128) as u64
+}
+
+/// Approval in basis points, rounded per `rounding`. Errors if nobody voted.
+pub fn approval_bps(tally_for: u64, tally_against: u64, rounding: ApprovalRounding) -> Result<u64> {
+    let total = tally_for as u128 + tally_against as u128;
+    require!(total > 0, FastVoteError::QuorumNotMet);
+    Ok(share_bps(tally_for as u128, total, rounding))
+}
+
+/// An action's pass bar in basis points: `threshold_bps` if set, else the
+/// whole-percent `threshold`. A set one must round up to `threshold`.
+pub fn validate_threshold_bps(threshold: u8, threshold_bps: u16) -> Result<u16> {
+    let whole = threshold as u16 * 100;
+    if threshold_bps == 0 {
+        return Ok(whole);
+    }
+    require!(
+        threshold_bps <= whole && threshold_bps + 100 > whole,
+        FastVoteError::InvalidThreshold
+    );
+    Ok(threshold_bps)
+}
+
+/// Map an approval in basis points to a graded result. The base
+/// `threshold_bps` decides pass/fail; each configured tier, in whole
+/// percent, met on top of it raises the tier.
+pub fn grade(approval_bps: u64, threshold_bps: u16, tier_thresholds: &[u8; MAX_EXTRA_TIERS]) -> VoteResult {
+    if approval_bps < threshold_bps as u64 {
+        return VoteResult::Failed;
+    }
+
+    let tier = tier_thresholds
+        .iter()
+        .take_while(|t| **t != 0)
+        .filter(|t| approval_bps >= **t as u64 * 100)
+        .count() as u8;
+
+    VoteResult::Passed { tier }
+}
+
+/// Votes needed to tally at `slot`: `quorum` until the deadline, then falling
+/// linearly to `floor` over `grace_slots`, and `floor` after that.
+pub fn required_quorum(quorum: u32, floor: u32, grace_slots: u64, deadline_slot: u64, slot: u64) -> u32 {
+    let elapsed = slot.saturating_sub(deadline_slot);
+    if elapsed >= grace_slots {
+        return floor;
+    }
+    // u128: a long grace period times the quorum's span can exceed u64
+    let drop = (quorum - floor) as u128 * elapsed as u128 / grace_slots as u128;
+    quorum - drop as u32
+}
+
+/// Whether `votes` cast reach `min_turnout_bps` of the electorate
+pub fn turnout_met(votes: u32, electorate: u32, min_turnout_bps: u16) -> bool {
+    votes as u64 * 10_000 >= min_turnout_bps as u64 * electorate as u64
+}
+
+/// Whether the weight cast reaches `quorum_weight_bps` of the snapshot's
+/// `total_weight`
+pub fn weight_quorum_met(weight_for: u64, weight_against: u64, total_weight: u64, quorum_weight_bps: u16) -> bool {
+    (weight_for as u128 + weight_against as u128) * 10_000 >= quorum_weight_bps as u128 * total_weight as u128
+}
+
+/// Optimistic actions stand unless vetoes exceed `veto_pct` percent of the
+/// electorate snapshotted at creation.
+pub fn optimistic_result(vetoes: u32, electorate: u32, veto_pct: u8) -> VoteResult {
+    if vetoes as u64 * 100 > veto_pct as u64 * electorate as u64 {
+        VoteResult::Failed
+    } else {
+        VoteResult::Passed { tier: 0 }
+    }
+}
+
+/// Leading option of a multi-option tally, if its share of the weight cast,
+/// rounded per `rounding`, reaches `threshold_bps`. A tie for the lead fails.
+pub fn choose_option(option_weights: &[u64], threshold_bps: u16, rounding: ApprovalRounding) -> Result<VoteResult> {
+    let total: u128 = option_weights.iter().map(|w| *w as u128).sum();
+    require!(total > 0, FastVoteError::QuorumNotMet);
+
+    let lead = *option_weights.iter().max().unwrap_or(&0);
+    let mut leaders = option_weights.iter().enumerate().filter(|(_, w)| **w == lead);
+    let (option, _) = leaders.next().ok_or(FastVoteError::QuorumNotMet)?;
+    if leaders.next().is_some() {
+        return Ok(VoteResult::Failed);
+    }
+
+    Ok(if share_bps(lead as u128, total, rounding) >= threshold_bps as u64 {
+        VoteResult::Chosen { option: option as u8 }
+    } else {
+        VoteResult::Failed
+    })
+}
+
+/// Two leading options, by weight then lowest index. None unless both
+/// received weight.
+pub fn top_two(option_weights: &[u64]) -> Option<(u8, u8)> {
+    let mut ranked: Vec<_> = option_weights.iter().enumerate().filter(|(_, w)| **w > 0).collect();
+    ranked.sort_by_key(|(i, w)| (std::cmp::Reverse(**w), *i));
+    match ranked[..] {
+        [(first, _), (second, _), ..] => Some((first as u8, second as u8)),
+        _ => None,
+    }
+}
+
+/// Ranked-choice winner: the sole continuing option, or one holding a strict
+/// majority of the ballots still on continuing options.
+pub fn runoff_winner(option_weights: &[u64], eliminated_mask: u8) -> Option<u8> {
+    let continuing = || {
+        option_weights
+            .iter()
+            .enumerate()
+            .filter(move |(i, _)| eliminated_mask & (1 << i) == 0)
+    };
+    let active: u128 = continuing().map(|(_, w)| *w as u128).sum();
+    let (option, lead) = continuing().max_by_key(|(_, w)| **w)?;
+    (continuing().count() == 1 || *lead as u128 * 2 > active).then_some(option as u8)
+}
+
+/// Continuing option to eliminate next: the fewest ballots, the highest index
+/// on a tie. None once a single option remains.
+pub fn weakest_option(option_weights: &[u64], eliminated_mask: u8) -> Option<u8> {
+    let continuing: Vec<_> = option_weights
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| eliminated_mask & (1 << i) == 0)
+        .collect();
+    if continuing.len() < 2 {
+        return None;
+    }
+    let (option, _) = continuing.into_iter().min_by_key(|(i, w)| (**w, std::cmp::Reverse(*i)))?;
+    Some(option as u8)
+}
+
+/// Tiers must be strictly ascending above the base threshold, with unused
+/// slots (0) only at the end.
+pub fn validate_tiers(threshold: u8, tier_thresholds: &[u8; MAX_EXTRA_TIERS]) -> Result<()> {
+    let mut floor = threshold;
+    let mut ended = false;
+    for tier in tier_thresholds {
+        if *tier == 0 {
+            ended = true;
+            continue;
+        }
+        require!(!ended && *tier > floor && *tier <= 100, FastVoteError::InvalidTierThresholds);
+        floor = *tier;
+    }
+    Ok(())
+}
+
+/// sha256(action_id || action_hash || votes_for || votes_against || result || finalized_slot)
+///
+/// Integers are little-endian; `result` is the two-byte `VoteResult::encode`.
+/// Verifiers only need these six values, not the account layout.
+pub fn result_digest(
+    action_id: u64,
+    action_hash: &[u8; 32],
+    votes_for: u32,
+    votes_against: u32,
+    result: &VoteResult,
+    finalized_slot: u64,
+) -> [u8; 32] {
+    hashv(&[
+        &action_id.to_le_bytes(),
+        action_hash,
+        &votes_for.to_le_bytes(),
+        &votes_against.to_le_bytes(),
+        &result.encode(),
+        &finalized_slot.to_le_bytes(),
+    ])
+    .to_bytes()
+}
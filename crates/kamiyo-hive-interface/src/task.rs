@@ -0,0 +1,68 @@
+// Tasks a passed action assigns to agents
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+pub const TASK_ASSIGNMENT_SEED: &[u8] = b"task_assignment";
+
+/// Signs pushes into task queues
+pub const TASK_AUTHORITY_SEED: &[u8] = b"task_authority";
+
+pub const TASK_RECEIPT_SEED: &[u8] = b"task_receipt";
+
+/// Anchor discriminator of `push_task`: sha256("global:push_task")[..8]
+pub const PUSH_TASK_DISCRIMINATOR: [u8; 8] = [65, 176, 219, 131, 54, 131, 147, 57];
+
+/// Task, queue and candidates a TaskAssignment action votes on; option `i`
+/// assigns `assignees[i]`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct TaskAssignment {
+    pub queue_program: Pubkey,
+    pub queue: Pubkey,
+    pub task_hash: [u8; 32],
+    pub assignees: Vec<Pubkey>,
+    /// Slot the assignee must finish by
+    pub deadline_slot: u64,
+}
+
+impl TaskAssignment {
+    /// The action hash a TaskAssignment action must carry to push `self`:
+    /// sha256("task_assignment" || borsh(self))
+    pub fn action_hash(&self) -> Result<[u8; 32]> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(hashv(&[TASK_ASSIGNMENT_SEED, &data]).to_bytes())
+    }
+}
+
+/// What the queue program receives
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct TaskRecord {
+    pub action: Pubkey,
+    pub task_hash: [u8; 32],
+    pub assignee: Pubkey,
+    pub deadline_slot: u64,
+}
+
+/// Records a pushed task; its `init` stops the action pushing twice
+#[account]
+pub struct TaskReceipt {
+    pub action: Pubkey,   // 32
+    pub assignee: Pubkey, // 32
+    pub pushed_slot: u64, // 8
+    pub bump: u8,         // 1
+}
+
+impl TaskReceipt {
+    pub const LEN: usize = 81; // 8 disc + 73 fields
+}
+
+#[event]
+pub struct TaskPushed {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub queue: Pubkey,
+    pub task_hash: [u8; 32],
+    pub assignee: Pubkey,
+    pub deadline_slot: u64,
+}
@@ -0,0 +1,158 @@
+// Action templates and the arguments an action is created from one with
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    ActionKind, ActionParams, Callback, FastVoteError, GlobalConfig, PrivacyLevel, VoteMode, DEFAULT_PRIORITY,
+    MAX_CALLBACKS, MAX_EXTRA_TIERS, VOTING_WINDOW_SLOTS,
+};
+
+/// Emergency windows stay fixed; any other override must fit the config's
+/// max window
+fn validate_window(kind: ActionKind, window_slots: u64, config: &GlobalConfig) -> Result<()> {
+    if window_slots != 0 {
+        require!(
+            kind != ActionKind::Emergency && (VOTING_WINDOW_SLOTS..=config.max_window()).contains(&window_slots),
+            FastVoteError::InvalidTemplateWindow
+        );
+    }
+    Ok(())
+}
+
+pub const TEMPLATE_SEED: &[u8] = b"template";
+
+#[account]
+pub struct Template {
+    pub hive: Pubkey,                         // 32
+    pub template_id: u16,                     // 2
+    /// Category of the actions it opens
+    pub kind: ActionKind,                     // 1
+    pub vote_mode: VoteMode,                  // 1
+    pub threshold: u8,                        // 1
+    /// 0 takes the kind's default window
+    pub window_slots: u64,                    // 8
+    pub schema_hash: [u8; 32],                // 32
+    /// Registered on each action created from the template
+    pub callbacks: [Callback; MAX_CALLBACKS], // 260
+    pub callback_count: u8,                   // 1
+    pub bump: u8,                             // 1
+}
+
+impl Template {
+    pub const LEN: usize = 347; // 8 disc + 339 fields
+
+    /// Window of an action created now; rechecked against the config, whose
+    /// max window may have shrunk since the template was written
+    pub fn window(&self, config: &GlobalConfig) -> Result<u64> {
+        validate_window(self.kind, self.window_slots, config)?;
+        Ok(match self.window_slots {
+            0 => self.kind.window_slots(),
+            window => window,
+        })
+    }
+
+    pub fn callbacks(&self) -> &[Callback] {
+        &self.callbacks[..self.callback_count as usize]
+    }
+
+    pub fn instantiate(&self, args: TemplateArgs) -> ActionParams {
+        ActionParams {
+            action_hash: args.action_hash,
+            threshold: self.threshold,
+            description_hash: args.description_hash,
+            vote_mode: self.vote_mode,
+            tier_thresholds: [0; MAX_EXTRA_TIERS],
+            dependencies: args.dependencies,
+            adaptive_quorum: None,
+            min_turnout_bps: 0,
+            option_count: 0,
+            ranked: false,
+            market_gate: None,
+            kind: self.kind,
+            weight_cap: None,
+            class_limits: Vec::new(),
+            audited: false,
+            early_vote_bonus: false,
+            exclusions: args.exclusions,
+            deadline_extension: None,
+            identity_weighted: false,
+            member_collection: None,
+            lock_stake: false,
+            priority: DEFAULT_PRIORITY,
+            tags: Vec::new(),
+            quorum_weight_bps: 0,
+            privacy: PrivacyLevel::Public,
+            threshold_bps: 0,
+        }
+    }
+
+    pub fn write(&mut self, params: TemplateParams, config: &GlobalConfig) -> Result<()> {
+        let TemplateParams {
+            kind,
+            vote_mode,
+            threshold,
+            window_slots,
+            schema_hash,
+            callbacks,
+        } = params;
+        require!(threshold > 0 && threshold <= 100, FastVoteError::InvalidThreshold);
+        validate_window(kind, window_slots, config)?;
+        require!(callbacks.len() <= MAX_CALLBACKS, FastVoteError::TooManyCallbacks);
+        for callback in &callbacks {
+            require!(
+                callback.program_id != crate::ID && callback.program_id != Pubkey::default(),
+                FastVoteError::InvalidCallback
+            );
+        }
+
+        self.kind = kind;
+        self.vote_mode = vote_mode;
+        self.threshold = threshold;
+        self.window_slots = window_slots;
+        self.schema_hash = schema_hash;
+        self.callbacks = [Callback::default(); MAX_CALLBACKS];
+        self.callbacks[..callbacks.len()].copy_from_slice(&callbacks);
+        self.callback_count = callbacks.len() as u8;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TemplateParams {
+    pub kind: ActionKind,
+    pub vote_mode: VoteMode,
+    pub threshold: u8,
+    /// 0 takes the kind's default window
+    pub window_slots: u64,
+    pub schema_hash: [u8; 32],
+    pub callbacks: Vec<Callback>,
+}
+
+/// What varies between actions created from one template. Leads with the
+/// action hash, as ActionParams does, so both instructions share
+/// CreateFastAction.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TemplateArgs {
+    pub action_hash: [u8; 32],
+    pub description_hash: [u8; 32],
+    pub dependencies: Vec<Pubkey>,
+    pub exclusions: Vec<Pubkey>,
+}
+
+#[event]
+pub struct TemplateWritten {
+    pub template: Pubkey,
+    pub hive: Pubkey,
+    pub template_id: u16,
+    pub kind: ActionKind,
+    pub schema_hash: [u8; 32],
+    pub written_by: Pubkey,
+}
+
+#[event]
+pub struct ActionCreatedFromTemplate {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub template: Pubkey,
+    pub schema_hash: [u8; 32],
+}
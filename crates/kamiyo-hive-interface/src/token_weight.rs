@@ -0,0 +1,16 @@
+// Token programs and mint extensions token-weighted ballots accept
+
+use anchor_lang::prelude::*;
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Scale balances by accrued interest (InterestBearingConfig on the mint)
+pub const WEIGHT_EXT_INTEREST: u8 = 1 << 0;
+// 1 << 1 counted transfer fees withheld in the holder's account. Those
+// belong to the mint's withdraw authority, not the holder, so the flag is
+// retired: new configs may not set it and a config that has it is ignored.
+/// Require the mint's transfer hook to be the configured program and reject
+/// accounts caught mid-transfer (TransferHook / TransferHookAccount)
+pub const WEIGHT_EXT_TRANSFER_HOOK: u8 = 1 << 2;
+pub const WEIGHT_EXT_ALL: u8 = WEIGHT_EXT_INTEREST | WEIGHT_EXT_TRANSFER_HOOK;
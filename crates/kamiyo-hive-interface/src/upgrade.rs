@@ -0,0 +1,50 @@
+// Program upgrades approved by a passed ProgramUpgrade action
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+/// Signs upgrades; set it as the upgrade authority of governed programs
+pub const UPGRADE_AUTHORITY_SEED: &[u8] = b"upgrade_authority";
+
+pub const PROGRAM_UPGRADE_SEED: &[u8] = b"program_upgrade";
+
+/// Delay between finalization and execute_upgrade: ~1 day at 400ms/slot
+pub const UPGRADE_TIMELOCK_SLOTS: u64 = 216_000;
+
+/// Program and buffer a ProgramUpgrade action deploys
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ProgramUpgrade {
+    pub program: Pubkey,
+    pub buffer: Pubkey,
+}
+
+impl ProgramUpgrade {
+    /// The action hash a ProgramUpgrade action must carry to deploy `self`:
+    /// sha256("program_upgrade" || program || buffer)
+    pub fn action_hash(&self) -> [u8; 32] {
+        hashv(&[PROGRAM_UPGRADE_SEED, self.program.as_ref(), self.buffer.as_ref()]).to_bytes()
+    }
+}
+
+/// Records an executed upgrade; its `init` stops the action running twice
+#[account]
+pub struct UpgradeReceipt {
+    pub action: Pubkey,     // 32
+    pub program: Pubkey,    // 32
+    pub buffer: Pubkey,     // 32
+    pub upgraded_slot: u64, // 8
+    pub bump: u8,           // 1
+}
+
+impl UpgradeReceipt {
+    pub const LEN: usize = 113; // 8 disc + 105 fields
+}
+
+#[event]
+pub struct ProgramUpgraded {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub action_id: u64,
+    pub program: Pubkey,
+    pub buffer: Pubkey,
+}
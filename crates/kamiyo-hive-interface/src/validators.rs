@@ -0,0 +1,77 @@
+// ER validators registered to host delegated actions
+
+use anchor_lang::prelude::*;
+
+pub const VALIDATOR_SEED: &[u8] = b"validator";
+
+/// Lamports a validator must bond at registration; slashable for missed commits
+pub const MIN_VALIDATOR_BOND: u64 = 1_000_000_000;
+
+/// A validator with no heartbeat for this long is not live (~5 min)
+pub const HEARTBEAT_TIMEOUT_SLOTS: u64 = 750;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ValidatorStatus {
+    /// Registered, awaiting governance
+    Pending,
+    Allowed,
+    Denied,
+}
+
+#[account]
+pub struct ValidatorRecord {
+    pub validator: Pubkey,        // 32
+    pub status: ValidatorStatus,  // 1
+    pub registered_slot: u64,     // 8
+    pub last_heartbeat_slot: u64, // 8
+    pub bump: u8,                 // 1
+    pub sessions_hosted: u64,     // 8
+    pub unclaimed_sessions: u64,  // 8
+    pub rewards_claimed: u64,     // 8
+    /// Lamports held in this account above rent
+    pub bond: u64,                // 8
+    /// Actions delegated to it and not yet released
+    pub active_actions: u32,      // 4
+}
+
+impl ValidatorRecord {
+    pub const LEN: usize = 94; // 8 disc + 86 fields
+    /// Size before `active_actions`; migrate_validator_record grows it
+    pub const LEGACY_LEN: usize = 90;
+
+    pub fn is_live(&self, slot: u64) -> bool {
+        slot.saturating_sub(self.last_heartbeat_slot) <= HEARTBEAT_TIMEOUT_SLOTS
+    }
+}
+
+#[event]
+pub struct ValidatorRegistered {
+    pub validator: Pubkey,
+    pub bond: u64,
+    pub slot: u64,
+}
+
+/// The validator took its last action under the config's cap; further
+/// delegations to it are refused until one is released
+#[event]
+pub struct ValidatorSaturated {
+    pub validator: Pubkey,
+    pub action: Pubkey,
+    pub active_actions: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ValidatorReleased {
+    pub validator: Pubkey,
+    pub action: Pubkey,
+    /// Actions the validator still hosts
+    pub active_actions: u32,
+}
+
+#[event]
+pub struct ValidatorStatusChanged {
+    pub validator: Pubkey,
+    pub status: ValidatorStatus,
+    pub admin: Pubkey,
+}
@@ -0,0 +1,43 @@
+// Per-agent ballot budgets
+
+use anchor_lang::prelude::*;
+
+use crate::{FastVoteError, GlobalConfig};
+
+pub const VOTE_CREDITS_SEED: &[u8] = b"vote_credits";
+
+/// Credits one ballot spends
+pub const BALLOT_COST: u32 = 1;
+
+#[account]
+pub struct VoteCredits {
+    pub agent: Pubkey, // 32
+    pub epoch: u64,    // 8
+    pub spent: u32,    // 4
+    pub bump: u8,      // 1
+}
+
+impl VoteCredits {
+    pub const LEN: usize = 53; // 8 disc + 45 fields
+
+    /// Credits left in `epoch`; u32::MAX while the limit is disabled
+    pub fn remaining(&self, config: &GlobalConfig, epoch: u64) -> u32 {
+        match config.vote_credits_per_epoch {
+            0 => u32::MAX,
+            budget if self.epoch == epoch => budget.saturating_sub(self.spent),
+            budget => budget,
+        }
+    }
+
+    /// Spend `cost` credits in `epoch`, refilling them when the epoch rolls
+    /// over.
+    pub fn spend(&mut self, config: &GlobalConfig, epoch: u64, cost: u32) -> Result<()> {
+        require!(cost <= self.remaining(config, epoch), FastVoteError::VoteCreditsExhausted);
+        if self.epoch != epoch {
+            self.epoch = epoch;
+            self.spent = 0;
+        }
+        self.spent = self.spent.saturating_add(cost);
+        Ok(())
+    }
+}
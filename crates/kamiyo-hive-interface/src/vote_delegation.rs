@@ -0,0 +1,79 @@
+// Partial delegation of token weight across up to MAX_DELEGATES delegates
+
+use anchor_lang::prelude::*;
+
+use crate::MAX_VOTING_WINDOW_SLOTS;
+
+pub const VOTE_DELEGATION_SEED: &[u8] = b"vote_delegation";
+
+/// Delegates one delegator can split its weight across
+pub const MAX_DELEGATES: usize = 4;
+
+/// Least time between changes to a delegation
+pub const DELEGATION_COOLDOWN_SLOTS: u64 = MAX_VOTING_WINDOW_SLOTS + 1;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct DelegationShare {
+    pub delegate: Pubkey,
+    /// Share of the delegator's weight, in basis points
+    pub bps: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct DelegationSplit {
+    pub shares: [DelegationShare; MAX_DELEGATES], // 34 * MAX_DELEGATES
+    pub count: u8,                                 // 1
+}
+
+impl DelegationSplit {
+    pub fn shares(&self) -> &[DelegationShare] {
+        &self.shares[..self.count as usize]
+    }
+
+    /// Basis points the delegator keeps for its own ballot
+    pub fn retained_bps(&self) -> u16 {
+        10_000 - self.shares().iter().map(|s| s.bps).sum::<u16>()
+    }
+
+    pub fn bps_to(&self, delegate: &Pubkey) -> u16 {
+        self.shares().iter().find(|s| s.delegate == *delegate).map_or(0, |s| s.bps)
+    }
+}
+
+#[account]
+pub struct VoteDelegation {
+    pub delegator: Pubkey,         // 32
+    pub current: DelegationSplit,  // 137
+    /// Split replaced at `updated_slot`
+    pub previous: DelegationSplit, // 137
+    pub updated_slot: u64,         // 8
+    pub bump: u8,                  // 1
+}
+
+impl VoteDelegation {
+    pub const LEN: usize = 323; // 8 disc + 315 fields
+
+    /// Split in force when an action created at `created_slot` opened
+    pub fn split_at(&self, created_slot: u64) -> &DelegationSplit {
+        if self.updated_slot < created_slot {
+            &self.current
+        } else {
+            &self.previous
+        }
+    }
+
+    /// The VoteDelegation at `info`, or none if it was never created
+    pub fn load(info: &AccountInfo) -> Result<Option<VoteDelegation>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        Ok(Some(VoteDelegation::try_deserialize(&mut &info.try_borrow_data()?[..])?))
+    }
+}
+
+#[event]
+pub struct VoteDelegationSet {
+    pub delegator: Pubkey,
+    pub shares: Vec<DelegationShare>,
+}
@@ -0,0 +1,32 @@
+// Hashed reason memos attached to ballots
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+const REASON_DOMAIN: &[u8] = b"kamiyo_vote_reason";
+
+pub const MAX_REASON_LEN: usize = 512;
+
+/// sha256("kamiyo_vote_reason" || fast_vote || reason)
+///
+/// Binding the FastVote address keeps one ballot's reason from being
+/// replayed against another's hash.
+pub fn reason_hash(fast_vote: &Pubkey, reason: &str) -> [u8; 32] {
+    hashv(&[REASON_DOMAIN, fast_vote.as_ref(), reason.as_bytes()]).to_bytes()
+}
+
+#[event]
+pub struct VoteReasonSet {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub voter: Pubkey,
+    pub reason_hash: [u8; 32],
+}
+
+#[event]
+pub struct VoteReasonRevealed {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub voter: Pubkey,
+    pub reason: String,
+}
@@ -0,0 +1,57 @@
+// Compressed NFT receipts minted for ballots
+
+use anchor_lang::prelude::*;
+
+pub const RECEIPT_TREE_SEED: &[u8] = b"receipt_tree";
+pub const VOTE_RECEIPT_SEED: &[u8] = b"vote_receipt";
+
+/// Tree delegate of the receipt tree; signs every mint
+pub const RECEIPT_AUTHORITY_SEED: &[u8] = b"receipt_authority";
+
+pub const BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+pub const SPL_NOOP_PROGRAM_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+pub const SPL_ACCOUNT_COMPRESSION_PROGRAM_ID: Pubkey = pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+/// Keeps receipt URIs within Bubblegum's 200-byte limit
+pub const MAX_RECEIPT_URI_PREFIX_LEN: usize = 64;
+
+#[account]
+pub struct ReceiptTree {
+    pub merkle_tree: Pubkey,  // 32
+    /// Receipt URIs are this followed by the encoded vote
+    pub uri_prefix: String,   // 4 + MAX_RECEIPT_URI_PREFIX_LEN
+    pub bump: u8,             // 1
+}
+
+impl ReceiptTree {
+    pub const LEN: usize = 109; // 8 disc + 101 fields
+}
+
+/// Marks a FastVote whose receipt has been minted
+#[account]
+pub struct VoteReceipt {
+    pub fast_vote: Pubkey,  // 32
+    pub action: Pubkey,     // 32
+    pub voter: Pubkey,      // 32
+    pub minted_slot: u64,   // 8
+    pub bump: u8,           // 1
+}
+
+impl VoteReceipt {
+    pub const LEN: usize = 113; // 8 disc + 105 fields
+}
+
+#[event]
+pub struct ReceiptTreeSet {
+    pub merkle_tree: Pubkey,
+    pub uri_prefix: String,
+}
+
+#[event]
+pub struct VoteReceiptMinted {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub fast_vote: Pubkey,
+    pub voter: Pubkey,
+    pub merkle_tree: Pubkey,
+}
@@ -0,0 +1,87 @@
+// Reward pools paid out to an action's voters
+
+use anchor_lang::prelude::*;
+
+use crate::{FastAction, FastVote, VoteMode};
+
+pub const VOTE_REWARD_POOL_SEED: &[u8] = b"vote_reward_pool";
+pub const VOTE_REWARD_CLAIM_SEED: &[u8] = b"vote_reward_claim";
+
+#[account]
+pub struct VoteRewardPool {
+    pub action: Pubkey,     // 32
+    pub mint: Pubkey,       // 32
+    /// Token account the pool owns
+    pub vault: Pubkey,      // 32
+    pub amount: u64,        // 8
+    pub claimed: u64,       // 8
+    /// Slots claims stay open after finalization, or after the deadline if
+    /// the action never finalizes
+    pub claim_slots: u64,   // 8
+    pub swept: bool,        // 1
+    pub bump: u8,           // 1
+}
+
+impl VoteRewardPool {
+    pub const LEN: usize = 130; // 8 disc + 122 fields
+
+    /// Last slot a voter may claim in
+    pub fn claim_deadline(&self, action: &FastAction) -> u64 {
+        action.finalized_slot.max(action.deadline_slot).saturating_add(self.claim_slots)
+    }
+
+    /// Share of the pool `vote` earns
+    pub fn share(&self, action: &FastAction, vote: &FastVote) -> u64 {
+        let (weight, total) = match action.vote_mode {
+            VoteMode::TokenWeighted => {
+                let total = match action.option_count {
+                    0 => action.weight_for.saturating_add(action.weight_against),
+                    _ => action.option_weights.iter().fold(0u64, |sum, w| sum.saturating_add(*w)),
+                };
+                (vote.weight, total)
+            }
+            VoteMode::Headcount | VoteMode::Optimistic => (1, action.vote_count as u64),
+        };
+        match total {
+            0 => 0,
+            total => (self.amount as u128 * weight as u128 / total as u128) as u64,
+        }
+    }
+}
+
+/// Marks a FastVote whose reward has been claimed
+#[account]
+pub struct VoteRewardClaim {
+    pub fast_vote: Pubkey, // 32
+    pub amount: u64,       // 8
+    pub bump: u8,          // 1
+}
+
+impl VoteRewardClaim {
+    pub const LEN: usize = 49; // 8 disc + 41 fields
+}
+
+#[event]
+pub struct VoteRewardsOpened {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub claim_slots: u64,
+}
+
+#[event]
+pub struct VoteRewardClaimed {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub voter: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct VoteRewardsSwept {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    /// Unclaimed amount returned to the treasury
+    pub amount: u64,
+}
@@ -0,0 +1,39 @@
+// Committees voting as one identity under a threshold key
+
+use anchor_lang::prelude::*;
+
+pub const VOTER_GROUP_SEED: &[u8] = b"voter_group";
+
+pub const MAX_GROUP_MEMBERS: usize = 16;
+
+/// A committee whose members hold shares of `group_key`, a FROST (or other
+/// threshold) ed25519 key. Its ballots are signed with the aggregate key.
+#[account]
+pub struct VoterGroup {
+    pub group_key: Pubkey,     // 32
+    pub members: Vec<Pubkey>,  // 4 + 32 * MAX_GROUP_MEMBERS
+    /// Shares needed to sign, as the committee reports it; the signature
+    /// itself is what the program checks
+    pub threshold: u8,         // 1
+    /// Ballot weight on token-weighted actions
+    pub weight: u64,           // 8
+    pub registered_slot: u64,  // 8
+    pub bump: u8,              // 1
+}
+
+impl VoterGroup {
+    pub const LEN: usize = 574; // 8 disc + 566 fields
+}
+
+#[event]
+pub struct VoterGroupSet {
+    pub group_key: Pubkey,
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    pub weight: u64,
+}
+
+#[event]
+pub struct VoterGroupRemoved {
+    pub group_key: Pubkey,
+}
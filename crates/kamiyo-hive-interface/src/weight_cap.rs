@@ -0,0 +1,46 @@
+// Default per-voter weight caps by ActionKind
+
+use anchor_lang::prelude::*;
+
+use crate::ActionKind;
+
+pub const WEIGHT_CAPS_SEED: &[u8] = b"weight_caps";
+
+/// Room for every ActionKind, present and future
+pub const MAX_ACTION_KINDS: usize = 8;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WeightCap {
+    /// Most weight a single ballot counts
+    Absolute(u64),
+    /// Share of the weight mint's supply at creation, in basis points
+    SupplyBps(u16),
+}
+
+/// Default caps per ActionKind, indexed by its discriminant
+#[account]
+pub struct WeightCaps {
+    pub caps: [Option<WeightCap>; MAX_ACTION_KINDS], // 10 * MAX_ACTION_KINDS
+    pub bump: u8,                                     // 1
+}
+
+impl WeightCaps {
+    pub const LEN: usize = 89; // 8 disc + 81 fields
+
+    /// Default cap for `kind` stored at `info`, the WeightCaps PDA, or none if
+    /// it was never created.
+    pub fn default_for(info: &AccountInfo, kind: ActionKind) -> Result<Option<WeightCap>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let caps = WeightCaps::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(caps.caps[kind as usize])
+    }
+}
+
+#[event]
+pub struct WeightCapSet {
+    pub kind: ActionKind,
+    pub cap: Option<WeightCap>,
+}
@@ -0,0 +1,34 @@
+// Merkle roots of token weight that token-weighted ballots prove against
+
+use anchor_lang::prelude::*;
+
+pub const WEIGHT_SNAPSHOT_SEED: &[u8] = b"weight_snapshot";
+
+/// Deepest proof accepted: 2^32 voters
+pub const MAX_WEIGHT_PROOF_LEN: usize = 32;
+
+#[account]
+pub struct WeightSnapshot {
+    pub action: Pubkey,     // 32
+    pub root: [u8; 32],     // 32
+    /// Slot the weights were read at: the action's creation slot
+    pub snapshot_slot: u64, // 8
+    pub posted_by: Pubkey,  // 32
+    pub bump: u8,           // 1
+    /// Sum of the leaves' weights, as the poster attests
+    pub total_weight: u64,  // 8
+}
+
+impl WeightSnapshot {
+    pub const LEN: usize = 121; // 8 disc + 113 fields
+}
+
+#[event]
+pub struct WeightSnapshotPosted {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub root: [u8; 32],
+    pub snapshot_slot: u64,
+    pub total_weight: u64,
+    pub posted_by: Pubkey,
+}
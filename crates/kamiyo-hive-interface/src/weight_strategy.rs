@@ -0,0 +1,46 @@
+// Vote weight computed by an external strategy program
+
+use anchor_lang::prelude::*;
+
+pub const WEIGHT_STRATEGY_SEED: &[u8] = b"weight_strategy";
+
+/// Most params bytes a WeightStrategy forwards to its program
+pub const MAX_STRATEGY_PARAMS_LEN: usize = 64;
+
+/// sha256("global:compute_weight")[..8]; data continues with
+/// ComputeWeightArgs, accounts are the voter and the action, both read-only
+/// and unsigned, then the ballot's remaining accounts. Returns the weight as
+/// a u64 in return data.
+pub const COMPUTE_WEIGHT_DISCRIMINATOR: [u8; 8] = [102, 113, 59, 110, 22, 225, 154, 167];
+
+/// Arguments of a strategy program's compute_weight
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ComputeWeightArgs {
+    pub voter: Pubkey,
+    pub action: Pubkey,
+    pub params: Vec<u8>,
+}
+
+/// How ballots on `action` are weighed: by `program`'s compute_weight with
+/// `params`
+#[account]
+pub struct WeightStrategy {
+    pub action: Pubkey,  // 32
+    pub program: Pubkey, // 32
+    pub params: Vec<u8>, // 4 + MAX_STRATEGY_PARAMS_LEN
+    pub set_by: Pubkey,  // 32
+    pub bump: u8,        // 1
+}
+
+impl WeightStrategy {
+    pub const LEN: usize = 173; // 8 disc + 165 fields
+}
+
+#[event]
+pub struct WeightStrategySet {
+    pub action: Pubkey,
+    pub hive: Pubkey,
+    pub program: Pubkey,
+    pub params: Vec<u8>,
+    pub set_by: Pubkey,
+}
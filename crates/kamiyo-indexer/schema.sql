@@ -0,0 +1,68 @@
+-- kamiyo-indexer schema. Applied on startup; every statement is idempotent.
+--
+-- Pubkeys are base58 TEXT, 32-byte hashes are BYTEA, u64 token weights are
+-- NUMERIC so they never wrap. `source` is 'base' or 'er': the endpoint the
+-- row was last written from.
+
+-- Latest state of each FastAction account
+CREATE TABLE IF NOT EXISTS actions (
+    action          TEXT PRIMARY KEY,       -- FastAction PDA
+    action_id       BIGINT NOT NULL,
+    action_hash     BYTEA NOT NULL,
+    creator         TEXT NOT NULL,
+    hive            TEXT,                   -- NULL when not hive-scoped
+    vote_mode       TEXT NOT NULL,          -- 'headcount' | 'token_weighted' | 'optimistic'
+    threshold       SMALLINT NOT NULL,
+    votes_for       INTEGER NOT NULL,
+    votes_against   INTEGER NOT NULL,
+    vote_count      INTEGER NOT NULL,
+    weight_for      NUMERIC(20, 0) NOT NULL,
+    weight_against  NUMERIC(20, 0) NOT NULL,
+    created_slot    BIGINT NOT NULL,
+    deadline_slot   BIGINT NOT NULL,
+    executed        BOOLEAN NOT NULL,
+    result          TEXT NOT NULL,          -- 'pending' | 'passed' | 'failed' | 'cancelled' | 'expired' | 'voided' | 'chosen' | 'vetoed' | 'contested'
+    tier            SMALLINT,               -- tier when 'passed' or 'contested', option index when 'chosen'
+    finalized_slot  BIGINT,
+    result_digest   BYTEA,
+    parent_action   TEXT,                   -- failed action this is a runoff of
+    source          TEXT NOT NULL,
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+ALTER TABLE actions ADD COLUMN IF NOT EXISTS parent_action TEXT;
+
+CREATE INDEX IF NOT EXISTS actions_action_id ON actions (action_id);
+CREATE INDEX IF NOT EXISTS actions_hive ON actions (hive) WHERE hive IS NOT NULL;
+CREATE INDEX IF NOT EXISTS actions_parent ON actions (parent_action) WHERE parent_action IS NOT NULL;
+
+-- One row per FastVote account; votes are immutable once cast
+CREATE TABLE IF NOT EXISTS votes (
+    vote              TEXT PRIMARY KEY,     -- FastVote PDA
+    action            TEXT NOT NULL,
+    voter             TEXT NOT NULL,
+    voter_commitment  BYTEA NOT NULL,
+    vote_value        BOOLEAN NOT NULL,
+    weight            NUMERIC(20, 0) NOT NULL,
+    voted_slot        BIGINT NOT NULL,
+    source            TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS votes_action ON votes (action);
+CREATE INDEX IF NOT EXISTS votes_voter ON votes (voter, voted_slot DESC, vote DESC);
+
+-- Program events in log order. `data` is the borsh payload after the
+-- 8-byte discriminator, for consumers that need fields not normalized above.
+CREATE TABLE IF NOT EXISTS events (
+    signature   TEXT NOT NULL,
+    idx         INTEGER NOT NULL,           -- position among the tx's events
+    source      TEXT NOT NULL,
+    slot        BIGINT NOT NULL,
+    name        TEXT NOT NULL,              -- e.g. 'FastVoteCast'
+    action      TEXT,                       -- FastAction PDA, when the event has one
+    data        BYTEA NOT NULL,
+    seen_at     TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (signature, idx, source)
+);
+
+CREATE INDEX IF NOT EXISTS events_action ON events (action);
@@ -0,0 +1,671 @@
+// Decoding of program logs and account data into indexable records
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use kamiyo_hive_client::state::{decode, FastAction, FastVote};
+use kamiyo_hive_client::ID;
+use kamiyo_fast_voting::{
+    ActionAuthorityTransferred, ActionCheckpointed, ActionCreatedFromTemplate, ActionDelegated, ActionExpired,
+    ActionForceUndelegated, ActionMetadataSet, ActionRelayersSet, ActionUndelegated, ActionVetoed, AgentClassSet,
+    ActionTagged, ActionUntagged, ActiveActionsUpdated,
+    AgentEquivocated, AgentEvicted, AgentRegistered, AnonymousBallotsEnabled, AnonymousVoteCast, ApprovalRoundingSet,
+    BallotRevealed, BallotVerifierSet,
+    BallotsRedistributed, BondReleased, CallbackRegistered,
+    CallbacksDispatched, CancellationPolicySet, CompressedBallotsEnabled, CompressedVoteCast,
+    ClaimFiled, ClaimResolved,
+    ConfigChangeApplied, ConfigUpdated, ContestMarginSet, ContestResolved, ConvictionExecuted,
+    ConvictionProposalCreated, ConvictionStaked, ConvictionWithdrawn, CreationFeeUpdated, DependencyBlocked,
+    CreatorAllowed, CreatorDisallowed, CreatorRestrictionSet, DeadlineExtended,
+    DescriptionRevealed, DescriptionSealed, DraftSponsored, EarlyVoteCurveSet, EmergencyActionCreated,
+    EpochStatsInitialized,
+    ExecutionScheduled, ExecutionUnscheduled, FastActionAmended, FastActionCancelled, FastActionCreated,
+    ExecutionStaged,
+    FastActionExecuted, FastActionMigrated, FastVoteCast, FederatedActionCreated, FederatedActionSettled,
+    FeeRebateEarned, FeeRebatePaid, HiveCreated, HiveFreezeSet, HiveUpdated, KeeperPaid, LeaderInstalled,
+    ForeignHiveRegistered, ForeignTallySubmitted, IdentityWeightsSet, InsuranceFunded, InsurancePoolInitialized,
+    MarketGateCleared, MetricsInitialized, MirrorCommitted, MirrorDelegated,
+    MemberHeartbeatRecorded, MemberMarkedInactive,
+    MissedCommitReported, OptionEliminated, OptionVoteCast, ProgramUpgraded,
+    ProposalDraftCreated, RankedVoteCast, ReceiptTreeSet, RedundancyEnabled, RedundantCommitResolved,
+    ResultAttested, ResultAttestorsSet,
+    ResultChallenged, ResultContested,
+    ResultProofExported, RoleGranted, RoleRevoked,
+    RunoffSpawned, SelfSubmitRequested, SequenceCommitted, SlashVoided, StaleDelegationsReaped,
+    StakeLocked, StakeReleased,
+    StandingsBoardInitialized,
+    StreamCancelled, StreamOpened, StreamWithdrawn, TallyPageVerified, TallyRejected, TaskPushed, TemplateWritten,
+    ValidatorRegistered, ValidatorReleased, ValidatorRewardsClaimed, ValidatorSaturated, ValidatorSessionRecorded,
+    ValidatorSlashed, ValidatorStatusChanged,
+    VoteDelegationSet, VoteKeyCleared, VoteKeySet, VoteReceiptMinted, VoteRewardClaimed, VoteRewardsOpened,
+    VoteReasonRevealed, VoteReasonSet,
+    VoteRewardsSwept,
+    VoterGroupRemoved, VoterGroupSet,
+    WeightCapSet, WeightSnapshotPosted,
+    WeightStrategySet,
+};
+
+pub struct Event {
+    pub name: &'static str,
+    pub action: Option<Pubkey>,
+    /// Borsh payload after the discriminator
+    pub data: Vec<u8>,
+}
+
+impl Event {
+    /// Logged on the way to an error: the transaction fails, and the event is
+    /// its only record
+    pub fn reports_failure(&self) -> bool {
+        matches!(self.name, "DependencyBlocked" | "TallyRejected")
+    }
+}
+
+macro_rules! decode_event {
+    ($disc:expr, $data:expr, $($ty:ident => $action:expr),* $(,)?) => {{
+        let (disc, data): (&[u8], &[u8]) = ($disc, $data);
+        $(
+            if disc == $ty::DISCRIMINATOR {
+                let event = $ty::try_from_slice(data).ok()?;
+                let action: fn(&$ty) -> Option<Pubkey> = $action;
+                return Some(Event {
+                    name: stringify!($ty),
+                    action: action(&event),
+                    data: data.to_vec(),
+                });
+            }
+        )*
+        None
+    }};
+}
+
+fn decode_event(payload: &str) -> Option<Event> {
+    let bytes = STANDARD.decode(payload).ok()?;
+    if bytes.len() < 8 {
+        return None;
+    }
+    let (disc, data) = bytes.split_at(8);
+    decode_event!(disc, data,
+        FastActionCreated => |e| Some(e.action),
+        EmergencyActionCreated => |e| Some(e.action),
+        FastVoteCast => |e| Some(e.action),
+        CompressedBallotsEnabled => |e| Some(e.action),
+        CompressedVoteCast => |e| Some(e.action),
+        BallotRevealed => |e| Some(e.action),
+        AnonymousBallotsEnabled => |e| Some(e.action),
+        AnonymousVoteCast => |e| Some(e.action),
+        BallotVerifierSet => |_| None,
+        ActionRelayersSet => |e| Some(e.action),
+        SelfSubmitRequested => |e| Some(e.action),
+        OptionVoteCast => |e| Some(e.action),
+        RankedVoteCast => |e| Some(e.action),
+        OptionEliminated => |e| Some(e.action),
+        BallotsRedistributed => |e| Some(e.action),
+        RunoffSpawned => |e| Some(e.parent),
+        MarketGateCleared => |e| Some(e.action),
+        ActionVetoed => |e| Some(e.action),
+        FastActionExecuted => |e| Some(e.action),
+        DependencyBlocked => |e| Some(e.action),
+        TallyRejected => |e| Some(e.action),
+        FastActionCancelled => |e| Some(e.action),
+        FastActionAmended => |e| Some(e.action),
+        ActionMetadataSet => |e| Some(e.action),
+        DescriptionSealed => |e| Some(e.action),
+        DescriptionRevealed => |e| Some(e.action),
+        ActionAuthorityTransferred => |e| Some(e.action),
+        CallbackRegistered => |e| Some(e.action),
+        CallbacksDispatched => |e| Some(e.action),
+        LeaderInstalled => |e| Some(e.action),
+        TaskPushed => |e| Some(e.action),
+        ConfigChangeApplied => |e| Some(e.action),
+        ProgramUpgraded => |e| Some(e.action),
+        StreamOpened => |e| Some(e.action),
+        StreamCancelled => |e| Some(e.cancel_action),
+        FastActionMigrated => |e| Some(e.action),
+        ActionCheckpointed => |e| Some(e.action),
+        ActionForceUndelegated => |e| Some(e.action),
+        StaleDelegationsReaped => |_| None,
+        ActionExpired => |e| Some(e.action),
+        ActionCreatedFromTemplate => |e| Some(e.action),
+        ActionDelegated => |e| Some(e.action),
+        ActionUndelegated => |e| Some(e.action),
+        TallyPageVerified => |e| Some(e.action),
+        ValidatorSessionRecorded => |e| Some(e.action),
+        MissedCommitReported => |e| Some(e.action),
+        ResultChallenged => |e| Some(e.action),
+        SequenceCommitted => |e| Some(e.action),
+        ResultProofExported => |e| Some(e.action),
+        RedundancyEnabled => |e| Some(e.action),
+        MirrorDelegated => |e| Some(e.action),
+        MirrorCommitted => |e| Some(e.action),
+        RedundantCommitResolved => |e| Some(e.action),
+        BondReleased => |e| Some(e.action),
+        SlashVoided => |e| Some(e.action),
+        ValidatorSlashed => |e| Some(e.action),
+        ConfigUpdated => |_| None,
+        ConvictionProposalCreated => |_| None,
+        ConvictionStaked => |_| None,
+        ConvictionWithdrawn => |_| None,
+        ConvictionExecuted => |_| None,
+        RoleGranted => |_| None,
+        RoleRevoked => |_| None,
+        CancellationPolicySet => |_| None,
+        ApprovalRoundingSet => |_| None,
+        ContestMarginSet => |_| None,
+        ResultContested => |e| Some(e.action),
+        ContestResolved => |e| Some(e.action),
+        TemplateWritten => |_| None,
+        HiveCreated => |_| None,
+        HiveUpdated => |_| None,
+        HiveFreezeSet => |e| Some(e.action),
+        FederatedActionCreated => |_| None,
+        FederatedActionSettled => |_| None,
+        ProposalDraftCreated => |_| None,
+        DraftSponsored => |_| None,
+        StreamWithdrawn => |_| None,
+        ValidatorRegistered => |_| None,
+        ValidatorStatusChanged => |_| None,
+        ValidatorSaturated => |e| Some(e.action),
+        ValidatorReleased => |e| Some(e.action),
+        CreationFeeUpdated => |_| None,
+        ValidatorRewardsClaimed => |_| None,
+        FeeRebateEarned => |e| Some(e.action),
+        FeeRebatePaid => |e| Some(e.action),
+        WeightCapSet => |_| None,
+        MetricsInitialized => |_| None,
+        StandingsBoardInitialized => |_| None,
+        ExecutionScheduled => |e| Some(e.action),
+        ExecutionUnscheduled => |e| Some(e.action),
+        KeeperPaid => |e| Some(e.action),
+        WeightSnapshotPosted => |e| Some(e.action),
+        ReceiptTreeSet => |_| None,
+        VoteReceiptMinted => |e| Some(e.action),
+        VoteRewardsOpened => |e| Some(e.action),
+        VoteRewardClaimed => |e| Some(e.action),
+        VoteRewardsSwept => |e| Some(e.action),
+        EarlyVoteCurveSet => |_| None,
+        VoteDelegationSet => |_| None,
+        AgentRegistered => |_| None,
+        AgentEvicted => |_| None,
+        AgentEquivocated => |e| Some(e.action),
+        AgentClassSet => |_| None,
+        VoteKeySet => |_| None,
+        VoteKeyCleared => |_| None,
+        StakeLocked => |e| Some(e.action),
+        StakeReleased => |e| Some(e.action),
+        ActionTagged => |e| Some(e.action),
+        ActionUntagged => |e| Some(e.action),
+        ActiveActionsUpdated => |e| Some(e.action),
+        DeadlineExtended => |e| Some(e.action),
+        ExecutionStaged => |e| Some(e.action),
+        ResultAttested => |e| Some(e.action),
+        ResultAttestorsSet => |_| None,
+        VoteReasonSet => |e| Some(e.action),
+        VoteReasonRevealed => |e| Some(e.action),
+        WeightStrategySet => |e| Some(e.action),
+        ForeignHiveRegistered => |_| None,
+        ForeignTallySubmitted => |e| Some(e.action),
+        IdentityWeightsSet => |_| None,
+        VoterGroupSet => |_| None,
+        VoterGroupRemoved => |_| None,
+        CreatorRestrictionSet => |_| None,
+        CreatorAllowed => |_| None,
+        CreatorDisallowed => |_| None,
+        EpochStatsInitialized => |_| None,
+        InsurancePoolInitialized => |_| None,
+        InsuranceFunded => |_| None,
+        ClaimFiled => |e| Some(e.action),
+        ClaimResolved => |_| None,
+        MemberHeartbeatRecorded => |_| None,
+        MemberMarkedInactive => |_| None,
+    )
+}
+
+/// Events emitted by this program in a transaction's logs, in order.
+///
+/// Tracks the invocation stack so `Program data:` lines from other programs
+/// (including ones this program CPIs into) are ignored.
+pub fn events_from_logs(logs: &[String]) -> Vec<Event> {
+    let id = ID.to_string();
+    let mut stack: Vec<bool> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        if let Some(rest) = line.strip_prefix("Program ") {
+            if let Some(payload) = rest.strip_prefix("data: ") {
+                if stack.last() == Some(&true) {
+                    events.extend(decode_event(payload));
+                }
+                continue;
+            }
+            let mut words = rest.split(' ');
+            let program = words.next().unwrap_or_default();
+            match words.next() {
+                Some("invoke") => stack.push(program == id),
+                Some("success") | Some("failed:") => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    events
+}
+
+pub enum Record {
+    Action(Box<FastAction>),
+    Vote(FastVote),
+}
+
+/// Decode a program-owned account; other account types are skipped.
+pub fn record_from_account(data: &[u8]) -> Option<Record> {
+    if data.starts_with(FastAction::DISCRIMINATOR) {
+        decode(data).ok().map(|a| Record::Action(Box::new(a)))
+    } else if data.starts_with(FastVote::DISCRIMINATOR) {
+        decode(data).ok().map(Record::Vote)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::Event as _;
+    use kamiyo_fast_voting::{
+        ActionKind, AgentClass, ApprovalRounding, CancellationPolicy, ConfigChange, CurveShape, DelegationShare,
+        EarlyVoteCurve, Evidence, FederatedChild, FeeRebate, HiveOverrides, IdentityRamp, IdentityTier, MetricsLayer,
+        Role, TallyRejection, ValidatorStatus, VoteMode, VoteResult, WeightCap, MAX_EXTRA_TIERS,
+    };
+
+    use super::*;
+
+    /// One transaction's logs: every event the program emits, in the order
+    /// of `expected`, with a CPI into the token program halfway through
+    /// that logs data of its own
+    const LOGS: &str = include_str!("../fixtures/events.log");
+
+    fn key(n: u8) -> Pubkey {
+        Pubkey::new_from_array([n; 32])
+    }
+
+    fn logs() -> Vec<String> {
+        LOGS.lines().map(String::from).collect()
+    }
+
+    /// Each event's name, action and `Program data:` payload
+    macro_rules! events {
+        ($($ty:ident { $($field:ident: $value:expr),* $(,)? } => $action:expr),* $(,)?) => {
+            vec![$((stringify!($ty), $action, $ty { $($field: $value),* }.data())),*]
+        };
+    }
+
+    fn expected() -> Vec<(&'static str, Option<Pubkey>, Vec<u8>)> {
+        events![
+            FastActionCreated {
+                action: key(1), action_id: 2, action_hash: [3; 32], threshold: 4, tier_thresholds: [5; MAX_EXTRA_TIERS],
+                deadline_slot: 6, vote_mode: VoteMode::TokenWeighted, hive: key(7), dependencies: vec![key(8), key(9)],
+            } => Some(key(1)),
+            EmergencyActionCreated {
+                action: key(10), action_id: 11, hive: key(12), threshold: 13, deadline_slot: 14,
+            } => Some(key(10)),
+            FastVoteCast {
+                action: key(15), hive: key(16), voter_commitment: [17; 32], vote_count: 18, weight: 19,
+            } => Some(key(15)),
+            CompressedBallotsEnabled {
+                action: key(20), hive: key(21), action_id: 22, address_tree: key(23),
+            } => Some(key(20)),
+            CompressedVoteCast {
+                action: key(24), hive: key(25), voter: key(26), address: [27; 32], state_tree: key(28),
+            } => Some(key(24)),
+            BallotRevealed { action: key(29), hive: key(30), voter: key(31), vote_value: true } => Some(key(29)),
+            AnonymousBallotsEnabled {
+                action: key(32), hive: key(33), verifier: key(34), members_root: [35; 32],
+            } => Some(key(32)),
+            AnonymousVoteCast {
+                action: key(36), hive: key(37), nullifier: [38; 32], vote_value: true, vote_count: 39,
+            } => Some(key(36)),
+            BallotVerifierSet { verifier: key(40), allowed: true, admin: key(41) } => None,
+            ActionRelayersSet {
+                action: key(42), relayers: vec![key(43), key(44)], fallback_slots: 45,
+            } => Some(key(42)),
+            SelfSubmitRequested { action: key(46), voter: key(47), due_slot: 48 } => Some(key(46)),
+            OptionVoteCast {
+                action: key(49), hive: key(50), voter_commitment: [51; 32], vote_count: 52, weights: vec![53, 54],
+            } => Some(key(49)),
+            RankedVoteCast {
+                action: key(55), hive: key(56), voter_commitment: [57; 32], vote_count: 58, ranking: vec![59, 60],
+            } => Some(key(55)),
+            OptionEliminated { action: key(61), hive: key(62), round: 63, option: 64, ballots: 65 } => Some(key(61)),
+            BallotsRedistributed {
+                action: key(66), hive: key(67), round: 68, eliminated: 69, moved: 70, remaining: 71,
+            } => Some(key(66)),
+            RunoffSpawned {
+                parent: key(72), runoff: key(73), hive: key(74), action_id: 75, options: [76; 2], deadline_slot: 77,
+            } => Some(key(72)),
+            MarketGateCleared {
+                action: key(78), hive: key(79), action_id: 80, feed: key(81), price: 82, min_price: 83,
+            } => Some(key(78)),
+            ActionVetoed {
+                action: key(84), hive: key(85), action_id: 86, vetoed_result: VoteResult::Passed { tier: 2 },
+                guardian: key(87), reason: "reason".into(),
+            } => Some(key(84)),
+            FastActionExecuted {
+                action: key(88), hive: key(89), action_id: 90, votes_for: 91, votes_against: 92, weight_for: 93,
+                weight_against: 94, result: VoteResult::Passed { tier: 2 }, finalized_slot: 95, result_digest: [96; 32],
+            } => Some(key(88)),
+            DependencyBlocked {
+                action: key(97), hive: key(98), dependency: key(99), index: 100,
+                dependency_result: VoteResult::Passed { tier: 2 },
+            } => Some(key(97)),
+            TallyRejected {
+                action: key(101), hive: key(102), reason: TallyRejection::QuorumNotMet, vote_count: 103, quorum: 104,
+                slot: 105, deadline_slot: 106,
+            } => Some(key(101)),
+            FastActionCancelled { action: key(107), hive: key(108), action_id: 109 } => Some(key(107)),
+            FastActionAmended {
+                action: key(110), hive: key(111), action_id: 112, old_threshold: 113, threshold: 114,
+                old_deadline_slot: 115, deadline_slot: 116, old_description_hash: [117; 32],
+                description_hash: [118; 32],
+            } => Some(key(110)),
+            ActionMetadataSet {
+                action: key(119), hive: key(120), action_id: 121, description: "description".into(), uri: "uri".into(),
+            } => Some(key(119)),
+            DescriptionSealed {
+                action: key(122), hive: key(123), ciphertext_hash: [124; 32], encryption_key: [125; 32],
+            } => Some(key(122)),
+            DescriptionRevealed {
+                action: key(126), hive: key(127), validator: key(128), description: "description".into(),
+            } => Some(key(126)),
+            ActionAuthorityTransferred {
+                action: key(129), hive: key(130), action_id: 131, previous: key(132), authority: key(133),
+            } => Some(key(129)),
+            CallbackRegistered {
+                action: key(134), hive: key(135), index: 136, program_id: key(137), accounts_hash: [138; 32],
+            } => Some(key(134)),
+            CallbacksDispatched { action: key(139), hive: key(140), action_id: 141, callbacks: 142 } => Some(key(139)),
+            LeaderInstalled {
+                hive: key(143), leader: key(144), action: key(145), term_start: 146, term_end: 147,
+            } => Some(key(145)),
+            TaskPushed {
+                action: key(148), hive: key(149), queue: key(150), task_hash: [151; 32], assignee: key(152),
+                deadline_slot: 153,
+            } => Some(key(148)),
+            ConfigChangeApplied {
+                action: key(154), hive: key(155), action_id: 156, change: ConfigChange::MaxVotesPerAction(157),
+            } => Some(key(154)),
+            ProgramUpgraded {
+                action: key(158), hive: key(159), action_id: 160, program: key(161), buffer: key(162),
+            } => Some(key(158)),
+            StreamOpened {
+                stream: key(163), action: key(164), hive: key(165), recipient: key(166), mint: key(167), amount: 168,
+                start_slot: 169, end_slot: 170,
+            } => Some(key(164)),
+            StreamCancelled {
+                stream: key(171), cancel_action: key(172), hive: key(173), forfeited: 174,
+            } => Some(key(172)),
+            FastActionMigrated {
+                action: key(175), hive: key(176), action_id: 177, from_version: 178, to_version: 179,
+            } => Some(key(175)),
+            ActionCheckpointed {
+                action: key(180), hive: key(181), action_id: 182, vote_count: 183, votes_for: 184, votes_against: 185,
+                weight_for: 186, weight_against: 187, slot: 188,
+            } => Some(key(180)),
+            ActionForceUndelegated {
+                action: key(189), hive: key(190), action_id: 191, validator: key(192), deadline_slot: 193, slot: 194,
+            } => Some(key(189)),
+            StaleDelegationsReaped { reaped: 195, skipped: 196, slot: 197 } => None,
+            ActionExpired {
+                action: key(198), hive: key(199), action_id: 200, creator: key(1), reclaimed: 2, slot: 3,
+            } => Some(key(198)),
+            ActionCreatedFromTemplate {
+                action: key(4), hive: key(5), template: key(6), schema_hash: [7; 32],
+            } => Some(key(4)),
+            ActionDelegated { action: key(8), hive: key(9), validator: key(10), slot: 11 } => Some(key(8)),
+            ActionUndelegated { action: key(12), hive: key(13), slot: 14 } => Some(key(12)),
+            TallyPageVerified { action: key(15), hive: key(16), verified: 17, complete: true } => Some(key(15)),
+            ValidatorSessionRecorded {
+                validator: key(18), action: key(19), hive: key(20), action_id: 21, sessions_hosted: 22,
+            } => Some(key(19)),
+            MissedCommitReported {
+                action: key(23), hive: key(24), action_id: 25, validator: key(26), reporter: key(27), slot: 28,
+            } => Some(key(23)),
+            ResultChallenged {
+                action: key(29), hive: key(30), action_id: 31, evidence: Evidence::Excluded,
+                voided_result: VoteResult::Passed { tier: 2 }, challenger: key(32), validator: key(33), slashed: 34,
+                bounty: 35,
+            } => Some(key(29)),
+            SequenceCommitted {
+                action: key(36), validator: key(37), index: 38, head: [39; 32], count: 40, through_slot: 41,
+            } => Some(key(36)),
+            ResultProofExported { action: key(42), hive: key(43), action_id: 44, proof: vec![45, 46] } => Some(key(42)),
+            RedundancyEnabled { action: key(47), hive: key(48), backup: key(49), enabled_by: key(50) } => Some(key(47)),
+            MirrorDelegated { action: key(51), hive: key(52), backup: key(53), slot: 54 } => Some(key(51)),
+            MirrorCommitted {
+                action: key(55), hive: key(56), backup: key(57), result: VoteResult::Passed { tier: 2 },
+                digest: [58; 32], slot: 59,
+            } => Some(key(55)),
+            RedundantCommitResolved {
+                action: key(60), hive: key(61), winner: key(62), loser: key(63), conflicting: true, slot: 64,
+            } => Some(key(60)),
+            BondReleased {
+                action: key(65), hive: key(66), action_id: 67, amount: 68, forfeited: true,
+            } => Some(key(65)),
+            SlashVoided { action: key(69), validator: key(70), admin: key(71) } => Some(key(69)),
+            ValidatorSlashed {
+                action: key(72), validator: key(73), amount: 74, bounty: 75, remaining_bond: 76,
+            } => Some(key(72)),
+            ConfigUpdated {
+                admin: key(77), weight_mint: key(78), weight_extensions: 79, transfer_hook_program: key(80),
+                max_actions_per_epoch: 81, guardian: key(82), vote_credits_per_epoch: 83, min_agent_bond: 84,
+                whistleblower_bps: 85, snapshotter: key(86), max_window_slots: 87, stake_lock_program: key(88),
+                max_low_priority_actions: 89, max_votes_per_action: 90,
+                identity_ramp: IdentityRamp { epochs: 4, floor_bps: 2_500 },
+                fee_rebate: FeeRebate { full_turnout_bps: 8_000, rebate_bps: 5_000 }, max_actions_per_validator: 91,
+            } => None,
+            ConvictionProposalCreated {
+                proposal: key(92), action_hash: [93; 32], creator: key(94), hive: key(95), threshold: 96,
+                retention_ppb: 97,
+            } => None,
+            ConvictionStaked {
+                proposal: key(98), staker: key(99), amount: 100, total_staked: 101, conviction: 102,
+            } => None,
+            ConvictionWithdrawn {
+                proposal: key(103), staker: key(104), amount: 105, total_staked: 106, conviction: 107,
+            } => None,
+            ConvictionExecuted {
+                proposal: key(108), action_hash: [109; 32], conviction: 110, total_staked: 111, slot: 112,
+            } => None,
+            RoleGranted { hive: key(113), member: key(114), role: Role::Tallier, granted_by: key(115) } => None,
+            RoleRevoked { hive: key(116), member: key(117), role: Role::Tallier, revoked_by: key(118) } => None,
+            CancellationPolicySet {
+                hive: key(119), policy: CancellationPolicy::BeforeFirstVote, set_by: key(120),
+            } => None,
+            ApprovalRoundingSet { hive: key(121), rounding: ApprovalRounding::HalfEven, set_by: key(122) } => None,
+            ContestMarginSet { hive: key(123), kind: ActionKind::Budget, margin: 124, set_by: key(125) } => None,
+            ResultContested {
+                action: key(126), hive: key(127), action_id: 128, approval_pct: 129, threshold: 130, margin: 131,
+            } => Some(key(126)),
+            ContestResolved {
+                action: key(132), hive: key(133), action_id: 134, slow_action: key(135),
+                result: VoteResult::Passed { tier: 2 },
+            } => Some(key(132)),
+            TemplateWritten {
+                template: key(136), hive: key(137), template_id: 138, kind: ActionKind::Budget, schema_hash: [139; 32],
+                written_by: key(140),
+            } => None,
+            HiveCreated {
+                hive: key(141), authority: key(142), name_hash: [143; 32], hive_account: key(144), roles: key(145),
+                hive_config: key(146), category_config: key(147), insurance_pool: key(148),
+            } => None,
+            HiveUpdated {
+                hive: key(149), authority: key(150), member_root: [151; 32],
+                overrides: HiveOverrides { max_actions_per_epoch: 152, default_quorum: 153 },
+            } => None,
+            HiveFreezeSet { hive: key(154), frozen: true, action: key(155), action_id: 156 } => Some(key(155)),
+            FederatedActionCreated {
+                federated_action: key(157), action_hash: [158; 32], creator: key(159), threshold: 160,
+                children: vec![FederatedChild { action: key(161), hive: key(162), weight: 3 }],
+            } => None,
+            FederatedActionSettled {
+                federated_action: key(163), result: VoteResult::Passed { tier: 2 }, weight_passed: 164,
+                total_weight: 165,
+            } => None,
+            ProposalDraftCreated {
+                draft: key(166), creator: key(167), action_hash: [168; 32], params_digest: [169; 32],
+                sponsors: vec![key(170), key(171)], required: 172,
+            } => None,
+            DraftSponsored { draft: key(173), sponsor: key(174), signatures: 175, required: 176 } => None,
+            StreamWithdrawn { stream: key(177), recipient: key(178), amount: 179, withdrawn: 180 } => None,
+            ValidatorRegistered { validator: key(181), bond: 182, slot: 183 } => None,
+            ValidatorStatusChanged { validator: key(184), status: ValidatorStatus::Allowed, admin: key(185) } => None,
+            ValidatorSaturated {
+                validator: key(186), action: key(187), active_actions: 188, slot: 189,
+            } => Some(key(187)),
+            ValidatorReleased { validator: key(190), action: key(191), active_actions: 192 } => Some(key(191)),
+            CreationFeeUpdated { creation_fee: 193, admin: key(194) } => None,
+            ValidatorRewardsClaimed { validator: key(195), sessions: 196, amount: 197 } => None,
+            FeeRebateEarned {
+                action: key(198), hive: key(199), action_id: 200, votes: 1, electorate: 2, amount: 3,
+            } => Some(key(198)),
+            FeeRebatePaid { action: key(4), hive: key(5), action_id: 6, creator: key(7), amount: 8 } => Some(key(4)),
+            WeightCapSet { kind: ActionKind::Budget, cap: Some(WeightCap::SupplyBps(9)) } => None,
+            MetricsInitialized { metrics: key(10), layer: MetricsLayer::Er, hive: key(11) } => None,
+            StandingsBoardInitialized { board: key(12), hive: key(13) } => None,
+            ExecutionScheduled { action: key(14), hive: key(15), funder: key(16), reward: 17 } => Some(key(14)),
+            ExecutionUnscheduled { action: key(18), funder: key(19) } => Some(key(18)),
+            KeeperPaid { action: key(20), hive: key(21), keeper: key(22), amount: 23 } => Some(key(20)),
+            WeightSnapshotPosted {
+                action: key(24), hive: key(25), root: [26; 32], snapshot_slot: 27, total_weight: 28, posted_by: key(29),
+            } => Some(key(24)),
+            ReceiptTreeSet { merkle_tree: key(30), uri_prefix: "uri_prefix".into() } => None,
+            VoteReceiptMinted {
+                action: key(31), hive: key(32), fast_vote: key(33), voter: key(34), merkle_tree: key(35),
+            } => Some(key(31)),
+            VoteRewardsOpened {
+                action: key(36), hive: key(37), mint: key(38), amount: 39, claim_slots: 40,
+            } => Some(key(36)),
+            VoteRewardClaimed { action: key(41), hive: key(42), voter: key(43), amount: 44 } => Some(key(41)),
+            VoteRewardsSwept { action: key(45), hive: key(46), amount: 47 } => Some(key(45)),
+            EarlyVoteCurveSet {
+                kind: ActionKind::Budget, curve: Some(EarlyVoteCurve { bonus_bps: 500, shape: CurveShape::Quadratic }),
+            } => None,
+            VoteDelegationSet {
+                delegator: key(48), shares: vec![DelegationShare { delegate: key(49), bps: 2_500 }],
+            } => None,
+            AgentRegistered { agent: key(50), bond: 51 } => None,
+            AgentEvicted { agent: key(52), bond: 53 } => None,
+            AgentEquivocated {
+                action: key(54), agent: key(55), reporter: key(56), amount: 57, bounty: 58,
+            } => Some(key(54)),
+            AgentClassSet { agent: key(59), class: AgentClass::Worker } => None,
+            VoteKeySet { agent: key(60), key: key(61) } => None,
+            VoteKeyCleared { agent: key(62), key: key(63) } => None,
+            StakeLocked {
+                voter: key(64), action: key(65), hive: key(66), token_account: key(67), active: 68,
+            } => Some(key(65)),
+            StakeReleased { voter: key(69), action: key(70), active: 71 } => Some(key(70)),
+            ActionTagged { hive: key(72), action: key(73), tag: [74; 8], page: 75 } => Some(key(73)),
+            ActionUntagged { hive: key(76), action: key(77), tag: [78; 8], page: 79 } => Some(key(77)),
+            ActiveActionsUpdated { hive: key(80), action: key(81), priority: 82, active: 83 } => Some(key(81)),
+            DeadlineExtended {
+                action: key(84), hive: key(85), old_deadline_slot: 86, deadline_slot: 87, extensions: 88,
+                supporters: 89, vote_count: 90,
+            } => Some(key(84)),
+            ExecutionStaged {
+                action: key(91), hive: key(92), executor: key(93), commitment: [94; 32], slot: 95,
+            } => Some(key(91)),
+            ResultAttested {
+                action: key(96), hive: key(97), digest: [98; 32], attestors: vec![key(99), key(100)],
+            } => Some(key(96)),
+            ResultAttestorsSet {
+                hive: key(101), validators: vec![key(102), key(103)], threshold: 104, set_by: key(105),
+            } => None,
+            VoteReasonSet {
+                action: key(106), hive: key(107), voter: key(108), reason_hash: [109; 32],
+            } => Some(key(106)),
+            VoteReasonRevealed {
+                action: key(110), hive: key(111), voter: key(112), reason: "reason".into(),
+            } => Some(key(110)),
+            WeightStrategySet {
+                action: key(113), hive: key(114), program: key(115), params: vec![116, 117], set_by: key(118),
+            } => Some(key(113)),
+            ForeignHiveRegistered { hive: key(119), chain: 120, emitter: [121; 32], max_weight: 122 } => None,
+            ForeignTallySubmitted {
+                action: key(123), hive: key(124), foreign_hive: key(125), sequence: 126, stance: true, weight: 127,
+            } => Some(key(123)),
+            IdentityWeightsSet {
+                collection: key(128), attribute: "attribute".into(),
+                tiers: vec![IdentityTier { value: "gold".into(), weight: 129 }],
+            } => None,
+            VoterGroupSet {
+                group_key: key(130), members: vec![key(131), key(132)], threshold: 133, weight: 134,
+            } => None,
+            VoterGroupRemoved { group_key: key(135) } => None,
+            CreatorRestrictionSet { hive: key(136), restricted: true, set_by: key(137) } => None,
+            CreatorAllowed { hive: key(138), creator: key(139), allowed_by: key(140) } => None,
+            CreatorDisallowed { hive: key(141), creator: key(142), disallowed_by: key(143) } => None,
+            EpochStatsInitialized { stats: key(144), hive: key(145), epoch: 146 } => None,
+            InsurancePoolInitialized { hive: key(147), fee_bps: 148, authority: key(149) } => None,
+            InsuranceFunded { hive: key(150), funder: key(151), amount: 152 } => None,
+            ClaimFiled {
+                claim: key(153), hive: key(154), action: key(155), claimant: key(156), amount: 157,
+                evidence_hash: [158; 32], resolution_hash: [159; 32],
+            } => Some(key(155)),
+            ClaimResolved {
+                claim: key(160), hive: key(161), claimant: key(162), resolution_action: key(163), approved: true,
+                paid: 164,
+            } => None,
+            MemberHeartbeatRecorded {
+                hive: key(165), member: key(166), epoch: 167, reactivated: true, electorate: 168,
+            } => None,
+            MemberMarkedInactive { hive: key(169), member: key(170), last_active_epoch: 171, electorate: 172 } => None,
+        ]
+    }
+
+    #[test]
+    fn decodes_every_event() {
+        let events = events_from_logs(&logs());
+        let expected = expected();
+        assert_eq!(events.len(), expected.len());
+        for (event, (name, action, data)) in events.iter().zip(expected) {
+            assert_eq!(event.name, name);
+            assert_eq!(event.action, action, "{name}");
+            assert_eq!(event.data, data[8..], "{name}");
+        }
+    }
+
+    #[test]
+    fn decodes_fields() {
+        let events = events_from_logs(&logs());
+        let find = |name: &str| events.iter().find(|event| event.name == name).unwrap();
+
+        let vote = FastVoteCast::try_from_slice(&find("FastVoteCast").data).unwrap();
+        assert_eq!(vote.action, key(15));
+        assert_eq!(vote.hive, key(16));
+        assert_eq!(vote.voter_commitment, [17; 32]);
+        assert_eq!((vote.vote_count, vote.weight), (18, 19));
+
+        assert!(find("TallyRejected").reports_failure());
+        let rejected = TallyRejected::try_from_slice(&find("TallyRejected").data).unwrap();
+        assert_eq!(rejected.reason, TallyRejection::QuorumNotMet);
+        assert_eq!((rejected.vote_count, rejected.quorum), (103, 104));
+        assert!(!find("FastVoteCast").reports_failure());
+    }
+
+    #[test]
+    fn follows_the_invocation_stack() {
+        // The token program's line is a FastVoteCast payload of its own
+        let events = events_from_logs(&logs());
+        assert_eq!(events.iter().filter(|event| event.name == "FastVoteCast").count(), 1);
+
+        // Invoked by another program, this one's events still count
+        let caller = "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+        let mut nested = vec![format!("{caller} invoke [1]")];
+        nested.extend(logs().into_iter().skip(2));
+        nested.push(format!("{caller} success"));
+        assert_eq!(events_from_logs(&nested).len(), events.len());
+
+        // Nothing outside this program's invocation
+        assert!(events_from_logs(&logs()[3..]).is_empty());
+    }
+}
@@ -0,0 +1,49 @@
+//! Streams fast voting state from the base layer and the MagicBlock ER into
+//! Postgres. See README.md for configuration and schema.sql for the tables.
+
+mod decode;
+mod store;
+mod stream;
+
+use std::sync::Arc;
+
+use store::Store;
+
+/// Endpoint a record was observed on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    Base,
+    Er,
+}
+
+impl Source {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Source::Base => "base",
+            Source::Er => "er",
+        }
+    }
+}
+
+fn env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("{name} must be set"))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), tokio_postgres::Error> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let store = Arc::new(Store::connect(&env("KAMIYO_DATABASE_URL")).await?);
+    let endpoints = [
+        (Source::Base, env("KAMIYO_BASE_WS_URL")),
+        (Source::Er, env("KAMIYO_ER_WS_URL")),
+    ];
+
+    let mut tasks = Vec::new();
+    for (source, url) in endpoints {
+        tasks.push(tokio::spawn(stream::logs(store.clone(), source, url.clone())));
+        tasks.push(tokio::spawn(stream::accounts(store.clone(), source, url)));
+    }
+    futures_util::future::join_all(tasks).await;
+    Ok(())
+}
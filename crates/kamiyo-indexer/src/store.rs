@@ -0,0 +1,151 @@
+// Postgres writes. All writes are upserts so replays and reconnects are safe.
+
+use anchor_lang::prelude::Pubkey;
+use kamiyo_hive_client::state::{FastAction, FastVote, VoteMode, VoteResult};
+use log::error;
+use tokio_postgres::{Client, NoTls};
+
+use crate::decode::Event;
+use crate::Source;
+
+const SCHEMA: &str = include_str!("../schema.sql");
+
+pub struct Store {
+    client: Client,
+}
+
+fn optional_key(key: &Pubkey) -> Option<String> {
+    (*key != Pubkey::default()).then(|| key.to_string())
+}
+
+impl Store {
+    pub async fn connect(url: &str) -> Result<Self, tokio_postgres::Error> {
+        let (client, connection) = tokio_postgres::connect(url, NoTls).await?;
+        tokio::spawn(async move {
+            if let Err(err) = connection.await {
+                error!("postgres connection closed: {err}");
+            }
+        });
+        client.batch_execute(SCHEMA).await?;
+        Ok(Self { client })
+    }
+
+    /// Base and ER both report the same action while it moves between them;
+    /// never let an older view (fewer votes, or not yet finalized) overwrite
+    /// a newer one.
+    pub async fn upsert_action(&self, source: Source, address: &Pubkey, action: &FastAction) -> Result<(), tokio_postgres::Error> {
+        let vote_mode = match action.vote_mode {
+            VoteMode::Headcount => "headcount",
+            VoteMode::TokenWeighted => "token_weighted",
+            VoteMode::Optimistic => "optimistic",
+        };
+        let (result, tier) = match action.result {
+            VoteResult::Pending => ("pending", None),
+            VoteResult::Passed { tier } => ("passed", Some(tier as i16)),
+            VoteResult::Failed => ("failed", None),
+            VoteResult::Cancelled => ("cancelled", None),
+            VoteResult::Expired => ("expired", None),
+            VoteResult::Voided => ("voided", None),
+            VoteResult::Chosen { option } => ("chosen", Some(option as i16)),
+            VoteResult::Vetoed => ("vetoed", None),
+            VoteResult::Contested { tier } => ("contested", Some(tier as i16)),
+        };
+        let finalized = action.finalized_slot != 0;
+
+        self.client
+            .execute(
+                "INSERT INTO actions (action, action_id, action_hash, creator, hive, vote_mode, threshold,
+                    votes_for, votes_against, vote_count, weight_for, weight_against, created_slot,
+                    deadline_slot, executed, result, tier, finalized_slot, result_digest, parent_action, source)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11::TEXT::NUMERIC, $12::TEXT::NUMERIC,
+                    $13, $14, $15, $16, $17, $18, $19, $20, $21)
+                 ON CONFLICT (action) DO UPDATE SET
+                    votes_for = EXCLUDED.votes_for,
+                    votes_against = EXCLUDED.votes_against,
+                    vote_count = EXCLUDED.vote_count,
+                    weight_for = EXCLUDED.weight_for,
+                    weight_against = EXCLUDED.weight_against,
+                    executed = EXCLUDED.executed,
+                    result = EXCLUDED.result,
+                    tier = EXCLUDED.tier,
+                    finalized_slot = EXCLUDED.finalized_slot,
+                    result_digest = EXCLUDED.result_digest,
+                    source = EXCLUDED.source,
+                    updated_at = now()
+                 WHERE NOT actions.executed AND EXCLUDED.vote_count >= actions.vote_count",
+                &[
+                    &address.to_string(),
+                    &(action.action_id as i64),
+                    &action.action_hash.as_slice(),
+                    &action.creator.to_string(),
+                    &optional_key(&action.hive),
+                    &vote_mode,
+                    &(action.threshold as i16),
+                    &(action.votes_for as i32),
+                    &(action.votes_against as i32),
+                    &(action.vote_count as i32),
+                    &action.weight_for.to_string(),
+                    &action.weight_against.to_string(),
+                    &(action.created_slot as i64),
+                    &(action.deadline_slot as i64),
+                    &action.executed,
+                    &result,
+                    &tier,
+                    &finalized.then_some(action.finalized_slot as i64),
+                    &finalized.then_some(action.result_digest.as_slice()),
+                    &optional_key(&action.parent_action),
+                    &source.as_str(),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn upsert_vote(&self, source: Source, address: &Pubkey, vote: &FastVote) -> Result<(), tokio_postgres::Error> {
+        self.client
+            .execute(
+                "INSERT INTO votes (vote, action, voter, voter_commitment, vote_value, weight, voted_slot, source)
+                 VALUES ($1, $2, $3, $4, $5, $6::TEXT::NUMERIC, $7, $8)
+                 ON CONFLICT (vote) DO NOTHING",
+                &[
+                    &address.to_string(),
+                    &vote.fast_action.to_string(),
+                    &vote.voter.to_string(),
+                    &vote.voter_commitment.as_slice(),
+                    &vote.vote_value,
+                    &vote.weight.to_string(),
+                    &(vote.voted_slot as i64),
+                    &source.as_str(),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn insert_event(
+        &self,
+        source: Source,
+        signature: &str,
+        idx: usize,
+        slot: u64,
+        event: &Event,
+    ) -> Result<(), tokio_postgres::Error> {
+        self.client
+            .execute(
+                "INSERT INTO events (signature, idx, source, slot, name, action, data)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT DO NOTHING",
+                &[
+                    &signature,
+                    &(idx as i32),
+                    &source.as_str(),
+                    &(slot as i64),
+                    &event.name,
+                    &event.action.map(|a| a.to_string()),
+                    &event.data,
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+}
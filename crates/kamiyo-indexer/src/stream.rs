@@ -0,0 +1,105 @@
+// Websocket subscriptions. Each runs forever, reconnecting after errors.
+
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use futures_util::StreamExt;
+use kamiyo_hive_client::ID;
+use log::{info, warn};
+use solana_account_decoder_client_types::{UiAccountData, UiAccountEncoding};
+use solana_commitment_config::CommitmentConfig;
+use solana_pubsub_client::nonblocking::pubsub_client::PubsubClient;
+use solana_rpc_client_api::config::{
+    RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+};
+
+use crate::decode::{events_from_logs, record_from_account, Record};
+use crate::store::Store;
+use crate::Source;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+type StreamResult = Result<(), Box<dyn Error + Send + Sync>>;
+
+pub async fn logs(store: Arc<Store>, source: Source, url: String) {
+    loop {
+        match stream_logs(&store, source, &url).await {
+            Ok(()) => warn!("{} logs subscription ended", source.as_str()),
+            Err(err) => warn!("{} logs subscription failed: {err}", source.as_str()),
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+pub async fn accounts(store: Arc<Store>, source: Source, url: String) {
+    loop {
+        match stream_accounts(&store, source, &url).await {
+            Ok(()) => warn!("{} account subscription ended", source.as_str()),
+            Err(err) => warn!("{} account subscription failed: {err}", source.as_str()),
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn stream_logs(store: &Store, source: Source, url: &str) -> StreamResult {
+    let client = PubsubClient::new(url).await?;
+    let (mut stream, _unsubscribe) = client
+        .logs_subscribe(
+            RpcTransactionLogsFilter::Mentions(vec![ID.to_string()]),
+            RpcTransactionLogsConfig {
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )
+        .await?;
+    info!("{} logs subscribed at {url}", source.as_str());
+
+    while let Some(response) = stream.next().await {
+        let tx = response.value;
+        for (idx, event) in events_from_logs(&tx.logs).iter().enumerate() {
+            // Failed transactions still log, but only their failure reports happened
+            if tx.err.is_some() && !event.reports_failure() {
+                continue;
+            }
+            store
+                .insert_event(source, &tx.signature, idx, response.context.slot, event)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+async fn stream_accounts(store: &Store, source: Source, url: &str) -> StreamResult {
+    let client = PubsubClient::new(url).await?;
+    let (mut stream, _unsubscribe) = client
+        .program_subscribe(
+            &ID,
+            Some(RpcProgramAccountsConfig {
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+        )
+        .await?;
+    info!("{} accounts subscribed at {url}", source.as_str());
+
+    while let Some(response) = stream.next().await {
+        let keyed = response.value;
+        let UiAccountData::Binary(blob, UiAccountEncoding::Base64) = &keyed.account.data else {
+            continue;
+        };
+        let address: Pubkey = keyed.pubkey.parse()?;
+        match record_from_account(&STANDARD.decode(blob)?) {
+            Some(Record::Action(action)) => store.upsert_action(source, &address, &action).await?,
+            Some(Record::Vote(vote)) => store.upsert_vote(source, &address, &vote).await?,
+            None => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,410 @@
+// Routing and JSON responses. Every endpoint is a GET under /v1; list
+// endpoints take `limit` and `cursor` and return `next_cursor` while more
+// rows remain.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::body::Incoming;
+use hyper::header::CONTENT_TYPE;
+use hyper::{Method, Request, Response, StatusCode};
+use log::error;
+use serde::Serialize;
+
+use crate::cursor;
+use crate::store::Source;
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+const RESULTS: [&str; 8] = ["pending", "passed", "failed", "cancelled", "expired", "voided", "chosen", "vetoed"];
+
+#[derive(Serialize)]
+struct Page<T> {
+    items: Vec<T>,
+    next_cursor: Option<String>,
+}
+
+enum Error {
+    BadRequest(String),
+    NotFound,
+    Database(tokio_postgres::Error),
+}
+
+impl From<tokio_postgres::Error> for Error {
+    fn from(err: tokio_postgres::Error) -> Self {
+        Error::Database(err)
+    }
+}
+
+type Reply = Result<Response<Full<Bytes>>, Error>;
+
+pub async fn handle<S: Source>(store: Arc<S>, request: Request<Incoming>) -> Result<Response<Full<Bytes>>, Infallible> {
+    let query = params(request.uri().query());
+    Ok(respond(route(&*store, request.method(), request.uri().path(), &query).await))
+}
+
+fn params(query: Option<&str>) -> HashMap<String, String> {
+    query
+        .map(|q| form_urlencoded::parse(q.as_bytes()).into_owned().collect())
+        .unwrap_or_default()
+}
+
+async fn route(store: &impl Source, method: &Method, path: &str, query: &HashMap<String, String>) -> Reply {
+    let path: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match (method, path.as_slice()) {
+        (&Method::GET, ["v1", "actions"]) => list_actions(store, query).await,
+        (&Method::GET, ["v1", "actions", action]) => get_action(store, action).await,
+        (&Method::GET, ["v1", "voters", voter, "votes"]) => voter_votes(store, voter, query).await,
+        (&Method::GET, ["v1", "hives", hive, "turnout"]) => turnout(store, hive).await,
+        _ => Err(Error::NotFound),
+    }
+}
+
+fn respond(reply: Reply) -> Response<Full<Bytes>> {
+    reply.unwrap_or_else(|err| match err {
+        Error::BadRequest(msg) => error_response(StatusCode::BAD_REQUEST, &msg),
+        Error::NotFound => error_response(StatusCode::NOT_FOUND, "not found"),
+        Error::Database(err) => {
+            error!("query failed: {err}");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "query failed")
+        }
+    })
+}
+
+async fn list_actions(store: &impl Source, query: &HashMap<String, String>) -> Reply {
+    let status = query.get("status").map(String::as_str);
+    if let Some(status) = status.filter(|s| !RESULTS.contains(s)) {
+        return Err(Error::BadRequest(format!("unknown status {status}")));
+    }
+    let before = query
+        .get("cursor")
+        .map(|c| cursor::decode_action(c).ok_or_else(|| Error::BadRequest("bad cursor".to_string())))
+        .transpose()?;
+    let limit = limit(query)?;
+
+    let mut items = store.actions(status, query.get("hive").map(String::as_str), before, limit + 1).await?;
+    let next_cursor = more(&mut items, limit).then(|| cursor::encode_action(items[items.len() - 1].action_id));
+    json(&Page { items, next_cursor })
+}
+
+/// The action with its live standing: while it is delegated, the tally is
+/// the ER's latest
+async fn get_action(store: &impl Source, action: &str) -> Reply {
+    json(&store.action(action).await?.ok_or(Error::NotFound)?)
+}
+
+async fn voter_votes(store: &impl Source, voter: &str, query: &HashMap<String, String>) -> Reply {
+    let before = query
+        .get("cursor")
+        .map(|c| cursor::decode_vote(c).ok_or_else(|| Error::BadRequest("bad cursor".to_string())))
+        .transpose()?;
+    let limit = limit(query)?;
+
+    let mut items = store.voter_votes(voter, before, limit + 1).await?;
+    let next_cursor = more(&mut items, limit).then(|| {
+        let last = &items[items.len() - 1];
+        cursor::encode_vote(last.voted_slot, &last.vote)
+    });
+    json(&Page { items, next_cursor })
+}
+
+async fn turnout(store: &impl Source, hive: &str) -> Reply {
+    json(&store.turnout(hive).await?)
+}
+
+fn limit(query: &HashMap<String, String>) -> Result<i64, Error> {
+    match query.get("limit") {
+        None => Ok(DEFAULT_LIMIT),
+        Some(limit) => match limit.parse() {
+            Ok(limit @ 1..=MAX_LIMIT) => Ok(limit),
+            _ => Err(Error::BadRequest(format!("limit must be between 1 and {MAX_LIMIT}"))),
+        },
+    }
+}
+
+/// Lists fetch one row past `limit` to learn whether another page exists
+fn more<T>(items: &mut Vec<T>, limit: i64) -> bool {
+    let more = items.len() as i64 > limit;
+    items.truncate(limit as usize);
+    more
+}
+
+fn json<T: Serialize>(body: &T) -> Reply {
+    let body = serde_json::to_vec(body).expect("API types serialize");
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(body)))
+        .unwrap())
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response<Full<Bytes>> {
+    let body = serde_json::json!({ "error": msg }).to_string();
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(body)))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{Action, Turnout, VoterVote};
+    use http_body_util::BodyExt;
+    use serde_json::Value;
+
+    const HIVES: [&str; 2] = ["hive-a", "hive-b"];
+    const VOTER: &str = "voter-1";
+
+    /// Rows held in memory, filtered and ordered as Store's queries do
+    struct Fixture {
+        actions: Vec<Action>,
+        /// Each ballot with its voter
+        votes: Vec<(String, VoterVote)>,
+    }
+
+    impl Source for Fixture {
+        async fn actions(
+            &self,
+            result: Option<&str>,
+            hive: Option<&str>,
+            before: Option<i64>,
+            limit: i64,
+        ) -> Result<Vec<Action>, tokio_postgres::Error> {
+            let mut actions: Vec<Action> = self
+                .actions
+                .iter()
+                .filter(|a| result.is_none_or(|r| a.result == r))
+                .filter(|a| hive.is_none_or(|h| a.hive.as_deref() == Some(h)))
+                .filter(|a| before.is_none_or(|b| a.action_id < b))
+                .cloned()
+                .collect();
+            actions.sort_by_key(|a| std::cmp::Reverse(a.action_id));
+            actions.truncate(limit as usize);
+            Ok(actions)
+        }
+
+        async fn action(&self, address: &str) -> Result<Option<Action>, tokio_postgres::Error> {
+            Ok(self.actions.iter().find(|a| a.action == address).cloned())
+        }
+
+        async fn voter_votes(
+            &self,
+            voter: &str,
+            before: Option<(i64, String)>,
+            limit: i64,
+        ) -> Result<Vec<VoterVote>, tokio_postgres::Error> {
+            let mut votes: Vec<VoterVote> = self
+                .votes
+                .iter()
+                .filter(|(v, _)| v == voter)
+                .map(|(_, vote)| vote)
+                .filter(|v| before.as_ref().is_none_or(|b| (v.voted_slot, &v.vote) < (b.0, &b.1)))
+                .cloned()
+                .collect();
+            votes.sort_by(|a, b| (b.voted_slot, &b.vote).cmp(&(a.voted_slot, &a.vote)));
+            votes.truncate(limit as usize);
+            Ok(votes)
+        }
+
+        async fn turnout(&self, hive: &str) -> Result<Turnout, tokio_postgres::Error> {
+            let actions: Vec<&Action> = self.actions.iter().filter(|a| a.hive.as_deref() == Some(hive)).collect();
+            let mut voters: Vec<&String> = self
+                .votes
+                .iter()
+                .filter(|(_, v)| actions.iter().any(|a| a.action == v.action))
+                .map(|(voter, _)| voter)
+                .collect();
+            voters.sort();
+            voters.dedup();
+            let votes: i64 = actions.iter().map(|a| a.vote_count as i64).sum();
+            Ok(Turnout {
+                hive: hive.to_string(),
+                actions: actions.len() as i64,
+                decided: actions.iter().filter(|a| a.result != "pending").count() as i64,
+                passed: actions.iter().filter(|a| ["passed", "chosen"].contains(&a.result.as_str())).count() as i64,
+                votes,
+                voters: voters.len() as i64,
+                average_votes: if actions.is_empty() { 0.0 } else { votes as f64 / actions.len() as f64 },
+            })
+        }
+    }
+
+    fn action(action_id: i64, hive: &str, result: &str) -> Action {
+        Action {
+            action: format!("action-{action_id}"),
+            action_id,
+            action_hash: "00".repeat(32),
+            creator: "creator".to_string(),
+            hive: Some(hive.to_string()),
+            vote_mode: "headcount".to_string(),
+            threshold: 51,
+            votes_for: 2,
+            votes_against: 1,
+            vote_count: 3,
+            weight_for: "0".to_string(),
+            weight_against: "0".to_string(),
+            created_slot: action_id * 100,
+            deadline_slot: action_id * 100 + 50,
+            executed: false,
+            result: result.to_string(),
+            tier: None,
+            finalized_slot: None,
+            parent_action: None,
+            source: "base".to_string(),
+            updated_at: 0,
+        }
+    }
+
+    fn vote(vote: &str, action_id: i64, voted_slot: i64) -> VoterVote {
+        VoterVote {
+            vote: vote.to_string(),
+            action: format!("action-{action_id}"),
+            action_id: Some(action_id),
+            action_result: None,
+            vote_value: true,
+            weight: "1".to_string(),
+            voted_slot,
+        }
+    }
+
+    /// Twelve actions alternating between the hives, cycling through three
+    /// results; VOTER's ballots share slots so ties break on the address
+    fn fixture() -> Fixture {
+        let results = ["pending", "passed", "failed"];
+        let actions = (1..=12).map(|id| action(id, HIVES[id as usize % 2], results[id as usize % 3])).collect();
+        let ballots = [("v-07", 1, 110), ("v-03", 2, 210), ("v-05", 3, 210), ("v-01", 4, 210), ("v-09", 5, 330)];
+        let votes = ballots
+            .into_iter()
+            .map(|(address, action_id, slot)| (VOTER.to_string(), vote(address, action_id, slot)))
+            .chain([("voter-2".to_string(), vote("v-02", 2, 220))])
+            .collect();
+        Fixture { actions, votes }
+    }
+
+    async fn get(fixture: &Fixture, uri: &str) -> (StatusCode, Value) {
+        let (path, query) = uri.split_once('?').unwrap_or((uri, ""));
+        let response = respond(route(fixture, &Method::GET, path, &params(Some(query))).await);
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    /// Follows next_cursor from `uri` to the last page, returning `key` of
+    /// every item and the size of each page
+    async fn walk(fixture: &Fixture, uri: &str, key: &str) -> (Vec<Value>, Vec<usize>) {
+        let (mut keys, mut pages) = (Vec::new(), Vec::new());
+        let mut cursor: Option<String> = None;
+        loop {
+            let page_uri = match &cursor {
+                Some(cursor) => format!("{uri}&cursor={cursor}"),
+                None => uri.to_string(),
+            };
+            let (status, page) = get(fixture, &page_uri).await;
+            assert_eq!(status, StatusCode::OK, "{page}");
+            let items = page["items"].as_array().unwrap();
+            keys.extend(items.iter().map(|item| item[key].clone()));
+            pages.push(items.len());
+            match page["next_cursor"].as_str() {
+                Some(next) => cursor = Some(next.to_string()),
+                None => return (keys, pages),
+            }
+        }
+    }
+
+    fn ids(ids: impl IntoIterator<Item = i64>) -> Vec<Value> {
+        ids.into_iter().map(Value::from).collect()
+    }
+
+    #[tokio::test]
+    async fn actions_page_newest_first() {
+        let fixture = fixture();
+        let (keys, pages) = walk(&fixture, "/v1/actions?limit=5", "action_id").await;
+        assert_eq!(keys, ids((1..=12).rev()));
+        assert_eq!(pages, [5, 5, 2]);
+
+        // A full last page still ends the walk, without an empty page after it
+        let (keys, pages) = walk(&fixture, "/v1/actions?limit=4", "action_id").await;
+        assert_eq!(keys, ids((1..=12).rev()));
+        assert_eq!(pages, [4, 4, 4]);
+
+        let (_, page) = get(&fixture, "/v1/actions").await;
+        assert_eq!(page["items"].as_array().unwrap().len(), 12);
+        assert_eq!(page["next_cursor"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn actions_filter_by_status_and_hive() {
+        let fixture = fixture();
+        let (keys, _) = walk(&fixture, "/v1/actions?status=passed&limit=2", "action_id").await;
+        assert_eq!(keys, ids([10, 7, 4, 1]));
+
+        let (keys, _) = walk(&fixture, "/v1/actions?hive=hive-b&limit=2", "action_id").await;
+        assert_eq!(keys, ids([11, 9, 7, 5, 3, 1]));
+
+        // Filters combine, and every page keeps them
+        let (keys, pages) = walk(&fixture, "/v1/actions?status=passed&hive=hive-b&limit=1", "action_id").await;
+        assert_eq!(keys, ids([7, 1]));
+        assert_eq!(pages, [1, 1]);
+
+        let (keys, pages) = walk(&fixture, "/v1/actions?status=chosen", "action_id").await;
+        assert!(keys.is_empty());
+        assert_eq!(pages, [0]);
+    }
+
+    #[tokio::test]
+    async fn voter_votes_page_by_slot_then_address() {
+        let fixture = fixture();
+        let (keys, pages) = walk(&fixture, "/v1/voters/voter-1/votes?limit=2", "vote").await;
+        assert_eq!(keys, ["v-09", "v-05", "v-03", "v-01", "v-07"].map(Value::from));
+        assert_eq!(pages, [2, 2, 1]);
+
+        let (keys, _) = walk(&fixture, "/v1/voters/voter-3/votes?limit=2", "vote").await;
+        assert!(keys.is_empty());
+    }
+
+    #[tokio::test]
+    async fn bad_filters_and_pages_are_rejected() {
+        let fixture = fixture();
+        let vote_cursor = cursor::encode_vote(210, "v-05");
+        for uri in [
+            "/v1/actions?status=approved".to_string(),
+            "/v1/actions?limit=0".to_string(),
+            format!("/v1/actions?limit={}", MAX_LIMIT + 1),
+            "/v1/actions?limit=ten".to_string(),
+            "/v1/actions?cursor=not-a-cursor".to_string(),
+            format!("/v1/actions?cursor={vote_cursor}"),
+            format!("/v1/voters/voter-1/votes?cursor={}", cursor::encode_action(4)),
+        ] {
+            let (status, body) = get(&fixture, &uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+            assert!(body["error"].is_string(), "{uri}");
+        }
+        let (status, _) = get(&fixture, &format!("/v1/actions?limit={MAX_LIMIT}")).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn lookups_by_address() {
+        let fixture = fixture();
+        let (status, action) = get(&fixture, "/v1/actions/action-7").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!((action["action_id"].as_i64(), action["result"].as_str()), (Some(7), Some("passed")));
+
+        let (status, _) = get(&fixture, "/v1/actions/action-13").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = get(&fixture, "/v1/votes").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // hive-a holds the even actions: 4 and 10 passed, 2 and 8 failed
+        let (_, turnout) = get(&fixture, "/v1/hives/hive-a/turnout").await;
+        assert_eq!(turnout["actions"], 6);
+        assert_eq!(turnout["decided"], 4);
+        assert_eq!(turnout["passed"], 2);
+        assert_eq!(turnout["voters"], 2);
+    }
+}
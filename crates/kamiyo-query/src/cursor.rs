@@ -0,0 +1,29 @@
+// Opaque page cursors. A cursor is the sort key of the last row on a page,
+// base64url-encoded so clients treat it as a token rather than build one.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+/// Actions are listed by descending `action_id`
+pub fn encode_action(action_id: i64) -> String {
+    URL_SAFE_NO_PAD.encode(format!("a:{action_id}"))
+}
+
+pub fn decode_action(cursor: &str) -> Option<i64> {
+    decode(cursor)?.strip_prefix("a:")?.parse().ok()
+}
+
+/// Votes are listed by descending `(voted_slot, vote)`
+pub fn encode_vote(voted_slot: i64, vote: &str) -> String {
+    URL_SAFE_NO_PAD.encode(format!("v:{voted_slot}:{vote}"))
+}
+
+pub fn decode_vote(cursor: &str) -> Option<(i64, String)> {
+    let key = decode(cursor)?;
+    let (slot, vote) = key.strip_prefix("v:")?.split_once(':')?;
+    Some((slot.parse().ok()?, vote.to_string()))
+}
+
+fn decode(cursor: &str) -> Option<String> {
+    String::from_utf8(URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()
+}
@@ -0,0 +1,45 @@
+//! Read API over the kamiyo-indexer database, so frontends never query
+//! Postgres directly and the schema can change behind it. See README.md for
+//! the endpoints.
+
+mod api;
+mod cursor;
+mod store;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use log::{info, warn};
+use tokio::net::TcpListener;
+
+use store::Store;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+fn env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("{name} must be set"))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let store = Arc::new(Store::connect(&env("KAMIYO_DATABASE_URL")).await?);
+    let addr: SocketAddr = std::env::var("KAMIYO_QUERY_ADDR").unwrap_or_else(|_| DEFAULT_ADDR.to_string()).parse()?;
+    let listener = TcpListener::bind(addr).await?;
+    info!("listening on {addr}");
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let store = store.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |request| api::handle(store.clone(), request));
+            if let Err(err) = http1::Builder::new().serve_connection(TokioIo::new(stream), service).await {
+                warn!("connection failed: {err}");
+            }
+        });
+    }
+}
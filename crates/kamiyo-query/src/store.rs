@@ -0,0 +1,227 @@
+// Read-only queries against the indexer's tables. Rows are mapped into the
+// API's own types here, so a schema change stops at this file.
+
+use log::error;
+use serde::Serialize;
+use tokio_postgres::{Client, NoTls, Row};
+
+const ACTION_COLUMNS: &str = "action, action_id, encode(action_hash, 'hex'), creator, hive, vote_mode, threshold,
+    votes_for, votes_against, vote_count, weight_for::TEXT, weight_against::TEXT, created_slot, deadline_slot,
+    executed, result, tier, finalized_slot, parent_action, source, EXTRACT(EPOCH FROM updated_at)::BIGINT";
+
+/// Latest indexed state of an action. Token weights are strings: they are
+/// u64s, past what a JSON number holds exactly.
+#[derive(Clone, Serialize)]
+pub struct Action {
+    pub action: String,
+    pub action_id: i64,
+    pub action_hash: String,
+    pub creator: String,
+    pub hive: Option<String>,
+    pub vote_mode: String,
+    pub threshold: i16,
+    pub votes_for: i32,
+    pub votes_against: i32,
+    pub vote_count: i32,
+    pub weight_for: String,
+    pub weight_against: String,
+    pub created_slot: i64,
+    pub deadline_slot: i64,
+    pub executed: bool,
+    pub result: String,
+    /// Tier when passed, option index when chosen
+    pub tier: Option<i16>,
+    pub finalized_slot: Option<i64>,
+    pub parent_action: Option<String>,
+    /// Endpoint the state was last seen on, `base` or `er`
+    pub source: String,
+    /// Unix seconds
+    pub updated_at: i64,
+}
+
+impl From<Row> for Action {
+    fn from(row: Row) -> Self {
+        Action {
+            action: row.get(0),
+            action_id: row.get(1),
+            action_hash: row.get(2),
+            creator: row.get(3),
+            hive: row.get(4),
+            vote_mode: row.get(5),
+            threshold: row.get(6),
+            votes_for: row.get(7),
+            votes_against: row.get(8),
+            vote_count: row.get(9),
+            weight_for: row.get(10),
+            weight_against: row.get(11),
+            created_slot: row.get(12),
+            deadline_slot: row.get(13),
+            executed: row.get(14),
+            result: row.get(15),
+            tier: row.get(16),
+            finalized_slot: row.get(17),
+            parent_action: row.get(18),
+            source: row.get(19),
+            updated_at: row.get(20),
+        }
+    }
+}
+
+/// A ballot, with the result of the action it was cast on
+#[derive(Clone, Serialize)]
+pub struct VoterVote {
+    pub vote: String,
+    pub action: String,
+    /// None until the action itself is indexed
+    pub action_id: Option<i64>,
+    pub action_result: Option<String>,
+    pub vote_value: bool,
+    pub weight: String,
+    pub voted_slot: i64,
+}
+
+#[derive(Serialize)]
+pub struct Turnout {
+    pub hive: String,
+    pub actions: i64,
+    /// Actions no longer pending
+    pub decided: i64,
+    /// Actions passed or with a chosen option
+    pub passed: i64,
+    pub votes: i64,
+    pub voters: i64,
+    pub average_votes: f64,
+}
+
+/// The queries the API answers from. Store runs them against Postgres.
+pub trait Source {
+    /// Up to `limit` actions below `before`, newest first
+    async fn actions(
+        &self,
+        result: Option<&str>,
+        hive: Option<&str>,
+        before: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Action>, tokio_postgres::Error>;
+
+    async fn action(&self, address: &str) -> Result<Option<Action>, tokio_postgres::Error>;
+
+    /// Up to `limit` of a voter's ballots below `before`, latest first
+    async fn voter_votes(
+        &self,
+        voter: &str,
+        before: Option<(i64, String)>,
+        limit: i64,
+    ) -> Result<Vec<VoterVote>, tokio_postgres::Error>;
+
+    async fn turnout(&self, hive: &str) -> Result<Turnout, tokio_postgres::Error>;
+}
+
+pub struct Store {
+    client: Client,
+}
+
+impl Store {
+    pub async fn connect(url: &str) -> Result<Self, tokio_postgres::Error> {
+        let (client, connection) = tokio_postgres::connect(url, NoTls).await?;
+        tokio::spawn(async move {
+            if let Err(err) = connection.await {
+                error!("postgres connection closed: {err}");
+            }
+        });
+        Ok(Self { client })
+    }
+}
+
+impl Source for Store {
+    async fn actions(
+        &self,
+        result: Option<&str>,
+        hive: Option<&str>,
+        before: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Action>, tokio_postgres::Error> {
+        let rows = self
+            .client
+            .query(
+                &format!(
+                    "SELECT {ACTION_COLUMNS} FROM actions
+                     WHERE ($1::TEXT IS NULL OR result = $1)
+                       AND ($2::TEXT IS NULL OR hive = $2)
+                       AND ($3::BIGINT IS NULL OR action_id < $3)
+                     ORDER BY action_id DESC
+                     LIMIT $4"
+                ),
+                &[&result, &hive, &before, &limit],
+            )
+            .await?;
+        Ok(rows.into_iter().map(Action::from).collect())
+    }
+
+    async fn action(&self, address: &str) -> Result<Option<Action>, tokio_postgres::Error> {
+        let row = self
+            .client
+            .query_opt(&format!("SELECT {ACTION_COLUMNS} FROM actions WHERE action = $1"), &[&address])
+            .await?;
+        Ok(row.map(Action::from))
+    }
+
+    async fn voter_votes(
+        &self,
+        voter: &str,
+        before: Option<(i64, String)>,
+        limit: i64,
+    ) -> Result<Vec<VoterVote>, tokio_postgres::Error> {
+        let (before_slot, before_vote) = before.unzip();
+        let rows = self
+            .client
+            .query(
+                "SELECT v.vote, v.action, a.action_id, a.result, v.vote_value, v.weight::TEXT, v.voted_slot
+                 FROM votes v LEFT JOIN actions a ON a.action = v.action
+                 WHERE v.voter = $1
+                   AND ($2::BIGINT IS NULL OR (v.voted_slot, v.vote) < ($2, $3::TEXT))
+                 ORDER BY v.voted_slot DESC, v.vote DESC
+                 LIMIT $4",
+                &[&voter, &before_slot, &before_vote, &limit],
+            )
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| VoterVote {
+                vote: row.get(0),
+                action: row.get(1),
+                action_id: row.get(2),
+                action_result: row.get(3),
+                vote_value: row.get(4),
+                weight: row.get(5),
+                voted_slot: row.get(6),
+            })
+            .collect())
+    }
+
+    async fn turnout(&self, hive: &str) -> Result<Turnout, tokio_postgres::Error> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT COUNT(*),
+                        COUNT(*) FILTER (WHERE result <> 'pending'),
+                        COUNT(*) FILTER (WHERE result IN ('passed', 'chosen')),
+                        COALESCE(SUM(vote_count), 0)::BIGINT,
+                        (SELECT COUNT(DISTINCT v.voter) FROM votes v
+                         JOIN actions a ON a.action = v.action WHERE a.hive = $1)
+                 FROM actions WHERE hive = $1",
+                &[&hive],
+            )
+            .await?;
+        let (actions, votes): (i64, i64) = (row.get(0), row.get(3));
+        Ok(Turnout {
+            hive: hive.to_string(),
+            actions,
+            decided: row.get(1),
+            passed: row.get(2),
+            votes,
+            voters: row.get(4),
+            average_votes: if actions == 0 { 0.0 } else { votes as f64 / actions as f64 },
+        })
+    }
+}
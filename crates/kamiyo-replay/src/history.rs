@@ -0,0 +1,100 @@
+// An action's transaction history on one layer, oldest first. Failed
+// transactions are dropped: they changed nothing the replay has to reproduce.
+
+use anchor_lang::prelude::Pubkey;
+use solana_rpc_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_rpc_client_api::config::RpcTransactionConfig;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status_client_types::option_serializer::OptionSerializer;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+
+use crate::{Layer, ReplayResult};
+
+/// Most signatures getSignaturesForAddress returns per page
+const PAGE: usize = 1_000;
+
+pub struct HistoryTx {
+    pub layer: Layer,
+    pub signature: Signature,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub tx: VersionedTransaction,
+    /// Static keys, then the addresses loaded from lookup tables; lines up
+    /// with `pre_balances`
+    pub keys: Vec<Pubkey>,
+    pub pre_balances: Vec<u64>,
+    /// None when the node kept no logs or truncated them
+    pub logs: Option<Vec<String>>,
+}
+
+impl HistoryTx {
+    /// Programs of the transaction's top-level instructions
+    pub fn programs(&self) -> impl Iterator<Item = &Pubkey> {
+        let keys = self.tx.message.static_account_keys();
+        self.tx.message.instructions().iter().map(|ix| &keys[ix.program_id_index as usize])
+    }
+}
+
+/// Every successful transaction mentioning `address`, oldest first
+pub fn fetch(client: &RpcClient, layer: Layer, address: &Pubkey) -> ReplayResult<Vec<HistoryTx>> {
+    let mut signatures = Vec::new();
+    let mut before = None;
+    loop {
+        let page = client.get_signatures_for_address_with_config(
+            address,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                limit: Some(PAGE),
+                ..Default::default()
+            },
+        )?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Some(last.signature.parse()?);
+        signatures.extend(page.into_iter().filter(|s| s.err.is_none()).map(|s| s.signature));
+    }
+    signatures.reverse();
+
+    signatures.iter().map(|signature| transaction(client, layer, signature.parse()?)).collect()
+}
+
+fn transaction(client: &RpcClient, layer: Layer, signature: Signature) -> ReplayResult<HistoryTx> {
+    let confirmed = client.get_transaction_with_config(
+        &signature,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(client.commitment()),
+            max_supported_transaction_version: Some(0),
+        },
+    )?;
+    let tx = confirmed
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| format!("{signature}: undecodable transaction"))?;
+    let meta = confirmed.transaction.meta.ok_or_else(|| format!("{signature}: no status metadata"))?;
+
+    let mut keys = tx.message.static_account_keys().to_vec();
+    if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+        for address in loaded.writable.iter().chain(&loaded.readonly) {
+            keys.push(address.parse()?);
+        }
+    }
+    let logs = match meta.log_messages {
+        OptionSerializer::Some(logs) if !logs.iter().any(|l| l.starts_with("Log truncated")) => Some(logs),
+        _ => None,
+    };
+
+    Ok(HistoryTx {
+        layer,
+        signature,
+        slot: confirmed.slot,
+        block_time: confirmed.block_time,
+        tx,
+        keys,
+        pre_balances: meta.pre_balances,
+        logs,
+    })
+}
@@ -0,0 +1,207 @@
+//! Deterministic replay verifier for fast voting actions.
+//!
+//! Fetches every transaction that mentions an action from the base layer and
+//! the ER, replays them against the program binary in LiteSVM, and checks
+//! that the replayed action matches the state committed on chain byte for
+//! byte. See README.md.
+
+mod history;
+mod recount;
+mod replay;
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountDeserialize;
+use clap::Parser;
+use kamiyo_fast_voting::PrivacyLevel;
+use kamiyo_hive_client::state::FastAction;
+use kamiyo_hive_client::ID;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use replay::{Divergence, Replay};
+
+const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+const DEFAULT_ER_URL: &str = "https://devnet.magicblock.app";
+
+type ReplayResult<T = ()> = Result<T, Box<dyn Error>>;
+
+/// Chain a transaction or account is read from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layer {
+    Base,
+    Er,
+}
+
+impl Layer {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Layer::Base => "base",
+            Layer::Er => "er",
+        }
+    }
+}
+
+#[derive(Parser)]
+#[command(name = "kamiyo-replay", version, about)]
+struct Cli {
+    /// FastAction address
+    action: Pubkey,
+    /// Base layer RPC URL
+    #[arg(long, short = 'u', default_value = DEFAULT_RPC_URL)]
+    url: String,
+    /// Ephemeral rollup RPC URL
+    #[arg(long, default_value = DEFAULT_ER_URL)]
+    er_url: String,
+    /// Program binary to replay, e.g. a verifiable build [default: the
+    /// program deployed on the base layer]
+    #[arg(long)]
+    program: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Whether the replay reproduced the committed state
+fn run(cli: Cli) -> ReplayResult<bool> {
+    // Certify against finalized base state; the ER only confirms
+    let base_rpc = RpcClient::new_with_commitment(cli.url, CommitmentConfig::finalized());
+    let er_rpc = RpcClient::new_with_commitment(cli.er_url, CommitmentConfig::confirmed());
+
+    let program = match &cli.program {
+        Some(path) => std::fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?,
+        None => replay::program_elf(&base_rpc, &base_rpc.get_account(&ID)?)?.ok_or("program is not deployed")?,
+    };
+
+    let base = history::fetch(&base_rpc, Layer::Base, &cli.action)?;
+    let er = history::fetch(&er_rpc, Layer::Er, &cli.action)?;
+    println!("action      {}", cli.action);
+    println!("history     {} base, {} er transactions", base.len(), er.len());
+
+    let action_id = replay::created_id(&base, &cli.action);
+    let mut replay = Replay::new([&base_rpc, &er_rpc], &program, cli.action, action_id)?;
+    let divergence = replay.run(base, er)?;
+    println!("replayed    {} ({} validator transactions skipped)", replay.replayed, replay.skipped);
+    println!("seeded      {} accounts from current state", replay.seeded.len());
+    for seeded in &replay.seeded {
+        let note = if seeded.missing { " (closed since)" } else { "" };
+        println!("  {:<4}  {}{note}", seeded.layer.as_str(), seeded.address);
+    }
+
+    if let Some(Divergence { layer, signature, reason }) = divergence {
+        println!("diverged    {} {signature}", layer.as_str());
+        println!("{reason}");
+        return Ok(false);
+    }
+
+    let chain_base = account(&base_rpc, &cli.action)?;
+    let delegated = chain_base.as_ref().is_some_and(|a| a.owner == ephemeral_rollups_sdk::id());
+    let mut matched = compare("base", replay.action(Layer::Base), chain_base);
+    if delegated {
+        matched &= compare("er", replay.action(Layer::Er), account(&er_rpc, &cli.action)?);
+    }
+    let last = replay.action(if delegated { Layer::Er } else { Layer::Base });
+    if let Some(action) = last.and_then(|a| FastAction::try_deserialize(&mut &a.data[..]).ok()) {
+        matched &= recount(&action, &replay.ballots());
+    }
+    println!("{}", if matched { "verified" } else { "MISMATCH" });
+    Ok(matched)
+}
+
+fn account(rpc: &RpcClient, address: &Pubkey) -> ReplayResult<Option<Account>> {
+    Ok(rpc.get_account_with_commitment(address, rpc.commitment())?.value)
+}
+
+/// Prints the counters and result the action's ballots do not add up to.
+/// Private ballots leave no FastVote to recount.
+fn recount(action: &FastAction, ballots: &recount::Ballots) -> bool {
+    if action.privacy != PrivacyLevel::Public {
+        println!("recount     skipped, ballots are private");
+        return true;
+    }
+    let differences = recount::differences(action, &recount::recount(action, ballots));
+    if differences.is_empty() {
+        println!("recount     matches");
+        return true;
+    }
+    println!("recount     differs");
+    for difference in differences {
+        println!("  {difference}");
+    }
+    false
+}
+
+/// Prints the tally fields that differ, or the first differing byte when
+/// none of them does
+fn compare(layer: &str, replayed: Option<Account>, chain: Option<Account>) -> bool {
+    let (replayed, chain) = match (replayed, chain) {
+        (None, None) => {
+            println!("{layer:<11} closed, as on chain");
+            return true;
+        }
+        (Some(replayed), Some(chain)) => (replayed, chain),
+        (replayed, chain) => {
+            let state = |a: &Option<Account>| if a.is_some() { "open" } else { "closed" };
+            println!("{layer:<11} replay left the action {}, chain has it {}", state(&replayed), state(&chain));
+            return false;
+        }
+    };
+    if replayed.data == chain.data && replayed.owner == chain.owner {
+        println!("{layer:<11} matches");
+        return true;
+    }
+    println!("{layer:<11} differs");
+    if replayed.owner != chain.owner {
+        println!("  owner                  replay {}, chain {}", replayed.owner, chain.owner);
+    }
+    let decode = |data: &[u8]| FastAction::try_deserialize(&mut &data[..]).ok();
+    let mut named = false;
+    if let (Some(r), Some(c)) = (decode(&replayed.data), decode(&chain.data)) {
+        macro_rules! diff {
+            ($($field:ident),* $(,)?) => {$(
+                if r.$field != c.$field {
+                    println!("  {:<22} replay {:?}, chain {:?}", stringify!($field), r.$field, c.$field);
+                    named = true;
+                }
+            )*};
+        }
+        diff!(
+            vote_count,
+            votes_for,
+            votes_against,
+            weight_for,
+            weight_against,
+            option_weights,
+            exhausted_ballots,
+            class_votes,
+            executed,
+            result,
+            finalized_slot,
+            result_digest,
+            last_checkpoint_slot,
+            last_checkpoint_votes,
+            delegation_epoch,
+            delegation_state,
+        );
+    }
+    if !named {
+        if let Some(offset) = replayed.data.iter().zip(&chain.data).position(|(r, c)| r != c) {
+            println!("  first differing byte   {offset}");
+        } else if replayed.data.len() != chain.data.len() {
+            println!("  length                 replay {}, chain {}", replayed.data.len(), chain.data.len());
+        }
+    }
+    false
+}
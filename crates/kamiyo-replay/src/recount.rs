@@ -0,0 +1,316 @@
+// An independent recount of an action from its ballots. The replay shows
+// the program binary did what it does; the recount adds up the ballots the
+// replay left behind and decides them with the program's tally module, so
+// counters that drifted from their ballots are caught even when every
+// transaction replays.
+
+use kamiyo_fast_voting::{FastAction, FastVote, RankedBallot, VoteMode, VoteResult, MAX_OPTIONS, NO_ELIMINATION};
+use kamiyo_fast_voting::tally;
+
+/// The ballots cast on one action
+#[derive(Default)]
+pub struct Ballots {
+    pub votes: Vec<FastVote>,
+    /// Weight each multi-option ballot gave each option, from its
+    /// OptionVoteCast event; FastVote keeps only the total
+    pub options: Vec<Vec<u64>>,
+    /// Ranked-choice ballots; each also has its FastVote
+    pub ranked: Vec<RankedBallot>,
+}
+
+/// `action` with its counters recounted from `ballots`, and its result
+/// recomputed once it was tallied. A ranked action's runoff is rerun for as
+/// many rounds as the action has run. A result the ballots fall short of
+/// recounts as Pending.
+pub fn recount(action: &FastAction, ballots: &Ballots) -> FastAction {
+    let mut recounted = action.clone();
+    recounted.vote_count = ballots.votes.len() as u32;
+    recounted.votes_for = 0;
+    recounted.votes_against = 0;
+    recounted.weight_for = 0;
+    recounted.weight_against = 0;
+    recounted.option_weights = [0; MAX_OPTIONS];
+
+    if action.ranked {
+        runoff(&mut recounted, &ballots.ranked, action.round);
+    } else if action.option_count > 0 {
+        for weights in &ballots.options {
+            for (tally, weight) in recounted.option_weights.iter_mut().zip(weights) {
+                *tally += weight;
+            }
+        }
+    } else {
+        for vote in &ballots.votes {
+            let (votes, weight) = match vote.vote_value {
+                true => (&mut recounted.votes_for, &mut recounted.weight_for),
+                false => (&mut recounted.votes_against, &mut recounted.weight_against),
+            };
+            *votes += 1;
+            if action.vote_mode == VoteMode::TokenWeighted {
+                *weight += vote.weight;
+            }
+        }
+    }
+
+    // Cancelled, expired and overturned actions were not decided by their
+    // ballots
+    if matches!(
+        action.result,
+        VoteResult::Passed { .. } | VoteResult::Failed | VoteResult::Chosen { .. } | VoteResult::Contested { .. }
+    ) {
+        recounted.result = recounted.tallied_result(action.finalized_slot).unwrap_or(VoteResult::Pending);
+        // A pass within the contest margin is recorded as contested
+        if let (VoteResult::Contested { tier }, VoteResult::Passed { tier: passed }) = (&action.result, &recounted.result) {
+            if tier == passed {
+                recounted.result = action.result.clone();
+            }
+        }
+    }
+    recounted
+}
+
+/// Instant runoff from the rankings alone: every ballot goes to its first
+/// choice still standing, and each round drops the weakest option, until
+/// `rounds` have run or one option holds a majority. tally_round reaches
+/// the same counts by moving ballots in batches.
+fn runoff(action: &mut FastAction, ranked: &[RankedBallot], rounds: u8) {
+    let options = action.option_count as usize;
+    action.eliminated_mask = 0;
+    action.round = 0;
+    action.pending_elimination = NO_ELIMINATION;
+    loop {
+        action.option_weights = [0; MAX_OPTIONS];
+        action.exhausted_ballots = 0;
+        for ballot in ranked {
+            let standing = ballot.ranking[..ballot.len as usize]
+                .iter()
+                .find(|option| action.eliminated_mask & (1 << **option) == 0);
+            match standing {
+                Some(option) => action.option_weights[*option as usize] += 1,
+                None => action.exhausted_ballots += 1,
+            }
+        }
+        let weights = &action.option_weights[..options];
+        if action.round == rounds || tally::runoff_winner(weights, action.eliminated_mask).is_some() {
+            return;
+        }
+        let Some(weakest) = tally::weakest_option(weights, action.eliminated_mask) else {
+            return;
+        };
+        action.eliminated_mask |= 1 << weakest;
+        action.round += 1;
+    }
+}
+
+/// The counters and result `recounted` disagrees with `action` on
+pub fn differences(action: &FastAction, recounted: &FastAction) -> Vec<String> {
+    let mut differences = Vec::new();
+    macro_rules! diff {
+        ($($field:ident),* $(,)?) => {$(
+            if action.$field != recounted.$field {
+                differences.push(format!(
+                    "{:<22} action {:?}, ballots {:?}",
+                    stringify!($field),
+                    action.$field,
+                    recounted.$field
+                ));
+            }
+        )*};
+    }
+    diff!(
+        vote_count,
+        votes_for,
+        votes_against,
+        weight_for,
+        weight_against,
+        option_weights,
+        eliminated_mask,
+        round,
+        exhausted_ballots,
+        result,
+    );
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::{AccountDeserialize, Discriminator};
+    use kamiyo_fast_voting::ApprovalRounding;
+
+    const DEADLINE: u64 = 1_000;
+    const FINALIZED: u64 = 1_100;
+    const TIERS: [u8; 3] = [60, 75, 90];
+
+    /// A tallied action with a 50% threshold, tiers at 60, 75 and 90, and a
+    /// quorum of five; its counters are left for each test to record
+    fn action(vote_mode: VoteMode, result: VoteResult) -> FastAction {
+        let mut data = FastAction::DISCRIMINATOR.to_vec();
+        data.resize(FastAction::LEN, 0);
+        let mut action = FastAction::try_deserialize(&mut &data[..]).unwrap();
+        action.threshold = 50;
+        action.threshold_bps = 5_000;
+        action.tier_thresholds = TIERS;
+        action.vote_mode = vote_mode;
+        action.quorum = 5;
+        action.quorum_floor = 5;
+        action.deadline_slot = DEADLINE;
+        action.finalized_slot = FINALIZED;
+        action.max_votes = u32::MAX;
+        action.pending_elimination = NO_ELIMINATION;
+        action.result = result;
+        action
+    }
+
+    fn vote(vote_value: bool, weight: u64) -> FastVote {
+        FastVote {
+            fast_action: Pubkey::default(),
+            voter: Pubkey::new_unique(),
+            voter_commitment: [1; 32],
+            vote_value,
+            voted_slot: DEADLINE - 1,
+            bump: 0,
+            weight,
+            reason_hash: [0; 32],
+        }
+    }
+
+    fn ranked(ranking: &[u8]) -> RankedBallot {
+        let mut padded = [NO_ELIMINATION; MAX_OPTIONS];
+        padded[..ranking.len()].copy_from_slice(ranking);
+        RankedBallot {
+            action: Pubkey::default(),
+            voter: Pubkey::new_unique(),
+            ranking: padded,
+            len: ranking.len() as u8,
+            position: 0,
+            bump: 0,
+        }
+    }
+
+    /// Eight for and two against, by headcount
+    fn ballots() -> Ballots {
+        let votes = (0..10).map(|i| vote(i < 8, 1)).collect();
+        Ballots { votes, ..Default::default() }
+    }
+
+    /// Nine ballots over four options: 0 leads without a majority, 3 goes
+    /// first and its two ballots exhaust, then 2 on a tie with 1 hands its
+    /// two to 0
+    fn runoff_ballots() -> Ballots {
+        let rankings: [&[u8]; 9] = [&[0, 1], &[0, 1], &[0, 1], &[1, 2], &[1, 2], &[2, 0], &[2, 0], &[3], &[3]];
+        Ballots {
+            votes: rankings.iter().map(|_| vote(true, 1)).collect(),
+            ranked: rankings.iter().map(|ranking| ranked(ranking)).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn ranked_action(result: VoteResult, round: u8) -> FastAction {
+        let mut action = action(VoteMode::Headcount, result);
+        action.ranked = true;
+        action.option_count = 4;
+        action.vote_count = 9;
+        action.round = round;
+        action
+    }
+
+    #[test]
+    fn tiered_recount_matches_the_tally() {
+        let mut recorded = action(VoteMode::Headcount, VoteResult::Passed { tier: 2 });
+        (recorded.votes_for, recorded.votes_against, recorded.vote_count) = (8, 2, 10);
+
+        let recounted = recount(&recorded, &ballots());
+        assert_eq!(differences(&recorded, &recounted), Vec::<String>::new());
+        let approval = tally::approval_bps(8, 2, ApprovalRounding::Floor).unwrap();
+        assert_eq!(recounted.result, tally::grade(approval, 5_000, &TIERS));
+        assert_eq!(recounted.result, VoteResult::Passed { tier: 2 });
+    }
+
+    #[test]
+    fn token_weighted_recount_grades_by_weight() {
+        let mut recorded = action(VoteMode::TokenWeighted, VoteResult::Passed { tier: 1 });
+        (recorded.votes_for, recorded.votes_against, recorded.vote_count) = (2, 3, 5);
+        (recorded.weight_for, recorded.weight_against) = (650, 350);
+        let votes = vec![vote(true, 400), vote(true, 250), vote(false, 100), vote(false, 100), vote(false, 150)];
+
+        let recounted = recount(&recorded, &Ballots { votes, ..Default::default() });
+        assert_eq!(differences(&recorded, &recounted), Vec::<String>::new());
+        let approval = tally::approval_bps(650, 350, ApprovalRounding::Floor).unwrap();
+        assert_eq!(recounted.result, tally::grade(approval, 5_000, &TIERS));
+    }
+
+    #[test]
+    fn contested_pass_recounts_at_its_tier() {
+        let mut recorded = action(VoteMode::Headcount, VoteResult::Contested { tier: 2 });
+        (recorded.votes_for, recorded.votes_against, recorded.vote_count) = (8, 2, 10);
+
+        assert!(differences(&recorded, &recount(&recorded, &ballots())).is_empty());
+    }
+
+    #[test]
+    fn ranked_recount_runs_the_runoff() {
+        let mut recorded = ranked_action(VoteResult::Chosen { option: 0 }, 2);
+        recorded.option_weights[..4].copy_from_slice(&[5, 2, 0, 0]);
+        recorded.eliminated_mask = 0b1100;
+        recorded.exhausted_ballots = 2;
+
+        let recounted = recount(&recorded, &runoff_ballots());
+        assert_eq!(differences(&recorded, &recounted), Vec::<String>::new());
+        let winner = tally::runoff_winner(&recounted.option_weights[..4], recounted.eliminated_mask);
+        assert_eq!(recounted.result, VoteResult::Chosen { option: winner.unwrap() });
+        assert_eq!(recounted.result, VoteResult::Chosen { option: 0 });
+    }
+
+    #[test]
+    fn ranked_recount_stops_at_the_actions_round() {
+        let mut recorded = ranked_action(VoteResult::Pending, 1);
+        recorded.option_weights[..4].copy_from_slice(&[3, 2, 2, 0]);
+        recorded.eliminated_mask = 0b1000;
+        recorded.exhausted_ballots = 2;
+
+        assert!(differences(&recorded, &recount(&recorded, &runoff_ballots())).is_empty());
+    }
+
+    #[test]
+    fn option_recount_sums_each_ballot() {
+        let mut recorded = action(VoteMode::Headcount, VoteResult::Chosen { option: 0 });
+        recorded.option_count = 3;
+        recorded.vote_count = 5;
+        recorded.option_weights[..3].copy_from_slice(&[8, 5, 2]);
+        let ballots = Ballots {
+            votes: [6, 4, 3, 1, 1].map(|weight| vote(true, weight)).to_vec(),
+            options: vec![vec![5, 0, 1], vec![0, 4, 0], vec![3, 0, 0], vec![0, 0, 1], vec![0, 1, 0]],
+            ..Default::default()
+        };
+
+        let recounted = recount(&recorded, &ballots);
+        assert_eq!(differences(&recorded, &recounted), Vec::<String>::new());
+        let chosen = tally::choose_option(&[8, 5, 2], 5_000, ApprovalRounding::Floor).unwrap();
+        assert_eq!(recounted.result, chosen);
+    }
+
+    #[test]
+    fn drifted_counters_are_reported() {
+        let mut recorded = action(VoteMode::Headcount, VoteResult::Passed { tier: 3 });
+        (recorded.votes_for, recorded.votes_against, recorded.vote_count) = (9, 1, 10);
+
+        let fields: Vec<_> = differences(&recorded, &recount(&recorded, &ballots()))
+            .iter()
+            .map(|difference| difference.split(' ').next().unwrap().to_string())
+            .collect();
+        assert_eq!(fields, ["votes_for", "votes_against", "result"]);
+    }
+
+    #[test]
+    fn a_pass_short_of_quorum_recounts_as_pending() {
+        let mut recorded = action(VoteMode::Headcount, VoteResult::Passed { tier: 0 });
+        (recorded.votes_for, recorded.vote_count) = (3, 5);
+        let votes = (0..3).map(|_| vote(true, 1)).collect();
+
+        let recounted = recount(&recorded, &Ballots { votes, ..Default::default() });
+        assert_eq!(recounted.vote_count, 3);
+        assert_eq!(recounted.result, VoteResult::Pending);
+    }
+}
@@ -0,0 +1,445 @@
+// Off-chain re-execution of an action's history in two LiteSVM instances,
+// one per layer, both running the same program binary.
+//
+// Accounts the history did not create are seeded from their layer's current
+// state the first time a transaction touches them; wallets take their
+// balance from each transaction's own metadata. The delegation and magic
+// programs are stand-ins: delegate_action leaves the action on the base
+// layer for the replay to copy to the ER, and a commit scheduled on the ER
+// copies the ER state back, as the validator's commit transaction would.
+
+use std::collections::HashSet;
+
+use anchor_lang::prelude::{Clock, EpochSchedule, Pubkey};
+use anchor_lang::system_program;
+use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ephemeral_rollups_sdk::consts::MAGIC_PROGRAM_ID;
+use kamiyo_fast_voting::{ActionCounter, FastActionCreated, FastVote, OptionVoteCast, RankedBallot};
+use kamiyo_hive_client::{pda, ID};
+use litesvm::LiteSVM;
+use solana_loader_v3_interface::state::UpgradeableLoaderState;
+use solana_program_runtime::declare_process_instruction;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::Signature;
+use solana_sdk_ids::{bpf_loader, bpf_loader_upgradeable};
+
+use crate::history::HistoryTx;
+use crate::recount::Ballots;
+use crate::{Layer, ReplayResult};
+
+/// delegate_account's accounts on its CPI into the delegation program
+const DELEGATED_ACCOUNT: u16 = 1;
+const DELEGATE_BUFFER: u16 = 3;
+
+/// MagicBlockInstruction::ScheduleCommit and ScheduleCommitAndUndelegate
+const SCHEDULE_COMMIT: &[u8] = &[1, 0, 0, 0];
+const SCHEDULE_COMMIT_AND_UNDELEGATE: &[u8] = &[2, 0, 0, 0];
+
+/// Added to each fee payer's balance: LiteSVM charges per signature, the ER
+/// charges nothing
+const FEE_HEADROOM: u64 = LAMPORTS_PER_SOL / 1_000;
+
+// delegate_account has copied the action into its buffer and zeroed it; the
+// real program copies it back as it takes ownership
+declare_process_instruction!(MockDelegationProgram, 0, |invoke_context| {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    if instruction_context.get_number_of_instruction_accounts() <= DELEGATE_BUFFER {
+        return Ok(());
+    }
+    let data = instruction_context
+        .try_borrow_instruction_account(transaction_context, DELEGATE_BUFFER)?
+        .get_data()
+        .to_vec();
+    instruction_context
+        .try_borrow_instruction_account(transaction_context, DELEGATED_ACCOUNT)?
+        .set_data_from_slice(&data)
+});
+
+// Scheduling a commit changes nothing on the ER; Replay::step mirrors it
+declare_process_instruction!(MockMagicProgram, 0, |_invoke_context| { Ok(()) });
+
+/// Where the replay parted from the chain
+pub struct Divergence {
+    pub layer: Layer,
+    pub signature: Signature,
+    pub reason: String,
+}
+
+/// An account read from the chain rather than produced by the replay
+pub struct Seeded {
+    pub layer: Layer,
+    pub address: Pubkey,
+    /// Closed since; replayed as an empty account holding its old balance
+    pub missing: bool,
+}
+
+pub struct Replay<'a> {
+    base: LiteSVM,
+    er: LiteSVM,
+    rpc: [&'a RpcClient; 2],
+    action: Pubkey,
+    /// ActionCounter is rewound to this when seeded, so the creation replays
+    action_id: Option<u64>,
+    touched: [HashSet<Pubkey>; 2],
+    /// Weights of the option ballots replayed; see `Ballots::options`
+    options: Vec<Vec<u64>>,
+    pub seeded: Vec<Seeded>,
+    pub replayed: usize,
+    /// Validator transactions; see `bookkeeping`
+    pub skipped: usize,
+}
+
+impl<'a> Replay<'a> {
+    /// `rpc` is indexed by Layer. `action_id` comes from `created_id`.
+    pub fn new(rpc: [&'a RpcClient; 2], program: &[u8], action: Pubkey, action_id: Option<u64>) -> ReplayResult<Self> {
+        let vm = || -> ReplayResult<LiteSVM> {
+            let mut svm = LiteSVM::new()
+                .with_sigverify(false)
+                .with_blockhash_check(false)
+                .with_log_bytes_limit(None);
+            svm.add_program(ID, program)?;
+            Ok(svm)
+        };
+        let mut base = vm()?;
+        base.add_builtin(ephemeral_rollups_sdk::id(), MockDelegationProgram::vm);
+        let mut er = vm()?;
+        er.add_builtin(MAGIC_PROGRAM_ID, MockMagicProgram::vm);
+
+        Ok(Self {
+            base,
+            er,
+            rpc,
+            action,
+            action_id,
+            touched: Default::default(),
+            options: Vec::new(),
+            seeded: Vec::new(),
+            replayed: 0,
+            skipped: 0,
+        })
+    }
+
+    fn vm(&mut self, layer: Layer) -> &mut LiteSVM {
+        match layer {
+            Layer::Base => &mut self.base,
+            Layer::Er => &mut self.er,
+        }
+    }
+
+    /// The action as the replay left it on `layer`
+    pub fn action(&self, layer: Layer) -> Option<Account> {
+        let vm = match layer {
+            Layer::Base => &self.base,
+            Layer::Er => &self.er,
+        };
+        vm.get_account(&self.action).filter(|a| a.lamports > 0)
+    }
+
+    /// The ballots on the action the replay left on either layer
+    pub fn ballots(&self) -> Ballots {
+        let mut ballots = Ballots {
+            options: self.options.clone(),
+            ..Default::default()
+        };
+        for (vm, touched) in [&self.base, &self.er].into_iter().zip(&self.touched) {
+            for account in touched.iter().filter_map(|address| vm.get_account(address)) {
+                if account.owner != ID {
+                    continue;
+                }
+                let data = &mut &account.data[..];
+                if data.starts_with(FastVote::DISCRIMINATOR) {
+                    let vote = FastVote::try_deserialize(data).ok().filter(|v| v.fast_action == self.action);
+                    ballots.votes.extend(vote);
+                } else if data.starts_with(RankedBallot::DISCRIMINATOR) {
+                    let ballot = RankedBallot::try_deserialize(data).ok().filter(|b| b.action == self.action);
+                    ballots.ranked.extend(ballot);
+                }
+            }
+        }
+        ballots
+    }
+
+    fn delegated(&self) -> bool {
+        self.action(Layer::Er).is_some_and(|a| a.owner == ID)
+    }
+
+    /// Replays both histories interleaved: while the replayed action is
+    /// delegated the next ER transaction goes first, unless the next base
+    /// one is strictly older. Stops at the first transaction that fails or
+    /// emits other events than it did on chain.
+    pub fn run(&mut self, base: Vec<HistoryTx>, er: Vec<HistoryTx>) -> ReplayResult<Option<Divergence>> {
+        let (mut base, mut er) = (base.into_iter().peekable(), er.into_iter().peekable());
+        loop {
+            let from_er = match (base.peek(), er.peek()) {
+                (None, None) => return Ok(None),
+                (Some(_), None) => false,
+                (None, Some(_)) => true,
+                (Some(b), Some(e)) => {
+                    self.delegated() && !matches!((b.block_time, e.block_time), (Some(b), Some(e)) if b < e)
+                }
+            };
+            let tx = if from_er { er.next() } else { base.next() }.expect("peeked");
+            if let Some(divergence) = self.step(&tx)? {
+                return Ok(Some(divergence));
+            }
+        }
+    }
+
+    fn step(&mut self, tx: &HistoryTx) -> ReplayResult<Option<Divergence>> {
+        if bookkeeping(tx) {
+            self.skipped += 1;
+            return Ok(None);
+        }
+        self.prepare(tx)?;
+        let diverged = |reason: String| {
+            Ok(Some(Divergence {
+                layer: tx.layer,
+                signature: tx.signature,
+                reason,
+            }))
+        };
+
+        let meta = match self.vm(tx.layer).send_transaction(tx.tx.clone()) {
+            Ok(meta) => meta,
+            Err(failed) => return diverged(format!("{}\n{}", failed.err, failed.meta.pretty_logs())),
+        };
+        self.replayed += 1;
+        if let Some(logs) = &tx.logs {
+            let (chain, replayed) = (events(logs), events(&meta.logs));
+            if let Some(i) = (0..chain.len().max(replayed.len())).find(|i| chain.get(*i) != replayed.get(*i)) {
+                return diverged(format!(
+                    "event {i}: chain logged {}, replay {}",
+                    chain.get(i).unwrap_or(&"none"),
+                    replayed.get(i).unwrap_or(&"none"),
+                ));
+            }
+        }
+        let action = self.action;
+        let options = events(&meta.logs)
+            .into_iter()
+            .filter_map(decode::<OptionVoteCast>)
+            .filter(|cast| cast.action == action)
+            .map(|cast| cast.weights);
+        self.options.extend(options);
+
+        match tx.layer {
+            Layer::Base => self.mirror_delegation()?,
+            Layer::Er => {
+                let action = tx.keys.iter().position(|k| *k == self.action);
+                let scheduled: Vec<&[u8]> = meta
+                    .inner_instructions
+                    .iter()
+                    .flatten()
+                    .map(|inner| &inner.instruction)
+                    .filter(|ix| tx.keys[ix.program_id_index as usize] == MAGIC_PROGRAM_ID)
+                    .filter(|ix| action.is_some_and(|a| ix.accounts.contains(&(a as u8))))
+                    .map(|ix| &ix.data[..ix.data.len().min(4)])
+                    .collect();
+                let undelegate = scheduled.contains(&SCHEDULE_COMMIT_AND_UNDELEGATE);
+                if undelegate || scheduled.contains(&SCHEDULE_COMMIT) {
+                    self.mirror_commit(undelegate)?;
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Seeds what `tx` reads that the replay has not produced, and moves the
+    /// layer's clock to the transaction's slot
+    fn prepare(&mut self, tx: &HistoryTx) -> ReplayResult {
+        let layer = tx.layer;
+        if let Some(lookups) = tx.tx.message.address_table_lookups() {
+            for lookup in lookups {
+                self.seed(layer, &lookup.account_key, None)?;
+            }
+        }
+        for (address, pre_balance) in tx.keys.iter().zip(&tx.pre_balances) {
+            if *address != self.action {
+                self.seed(layer, address, Some(*pre_balance))?;
+            } else if layer == Layer::Base && *pre_balance > 0 && self.action(Layer::Base).is_none() {
+                return Err(format!("{}: the action predates its earliest transaction on record", tx.signature).into());
+            }
+        }
+
+        let vm = self.vm(layer);
+        let mut payer = vm.get_account(&tx.keys[0]).unwrap_or_default();
+        payer.lamports = tx.pre_balances[0] + FEE_HEADROOM;
+        vm.set_account(tx.keys[0], payer)?;
+
+        let mut clock = vm.get_sysvar::<Clock>();
+        clock.slot = tx.slot;
+        clock.epoch = vm.get_sysvar::<EpochSchedule>().get_epoch(tx.slot);
+        if let Some(block_time) = tx.block_time {
+            clock.unix_timestamp = block_time;
+        }
+        vm.set_sysvar(&clock);
+        Ok(())
+    }
+
+    /// `pre_balance` is the balance just before the transaction, None for
+    /// lookup tables. Wallets take it on every transaction; other accounts
+    /// are read from the chain on first touch, unless they did not exist yet.
+    fn seed(&mut self, layer: Layer, address: &Pubkey, pre_balance: Option<u64>) -> ReplayResult {
+        let existing = self.vm(layer).get_account(address);
+        if let (Some(mut wallet), Some(lamports)) = (existing.clone().filter(is_wallet), pre_balance) {
+            wallet.lamports = lamports;
+            self.vm(layer).set_account(*address, wallet)?;
+            return Ok(());
+        }
+        // Builtins, sysvars and what the replay created or closed
+        if existing.is_some() || !self.touched[layer as usize].insert(*address) || pre_balance == Some(0) {
+            return Ok(());
+        }
+
+        let rpc = self.rpc[layer as usize];
+        let account = rpc.get_account_with_commitment(address, rpc.commitment())?.value;
+        let missing = account.is_none();
+        match account {
+            Some(account) if account.executable => {
+                // Native programs LiteSVM lacks stay missing
+                if let Some(elf) = program_elf(rpc, &account)? {
+                    self.vm(layer).add_program(*address, &elf)?;
+                }
+            }
+            Some(mut account) => {
+                if let Some(lamports) = pre_balance {
+                    account.lamports = lamports;
+                }
+                if *address == pda::action_counter().0 {
+                    self.rewind_counter(&mut account)?;
+                }
+                self.vm(layer).set_account(*address, account)?;
+            }
+            None => {
+                let lamports = pre_balance.unwrap_or_default();
+                self.vm(layer).set_account(*address, Account { lamports, ..Default::default() })?;
+            }
+        }
+        self.seeded.push(Seeded {
+            layer,
+            address: *address,
+            missing,
+        });
+        Ok(())
+    }
+
+    /// The counter has moved past the action since it was created
+    fn rewind_counter(&self, account: &mut Account) -> ReplayResult {
+        let Some(action_id) = self.action_id else {
+            return Ok(());
+        };
+        let mut counter = ActionCounter::try_deserialize(&mut &account.data[..])?;
+        counter.next_id = action_id;
+        let mut data = Vec::with_capacity(account.data.len());
+        counter.try_serialize(&mut data)?;
+        data.resize(account.data.len(), 0);
+        account.data = data;
+        Ok(())
+    }
+
+    /// After a base transaction: if delegate_action handed the action to the
+    /// delegation program, the ER takes a copy owned by this program
+    fn mirror_delegation(&mut self) -> ReplayResult {
+        let Some(mut account) = self.action(Layer::Base) else {
+            return Ok(());
+        };
+        if account.owner == ephemeral_rollups_sdk::id() && !self.delegated() {
+            account.owner = ID;
+            self.er.set_account(self.action, account)?;
+        }
+        Ok(())
+    }
+
+    /// The commit the ER scheduled lands on the base layer; an undelegating
+    /// one also hands the action back to this program
+    fn mirror_commit(&mut self, undelegate: bool) -> ReplayResult {
+        let committed = self.action(Layer::Er).ok_or("commit scheduled for an action not on the ER")?;
+        let mut account = self.action(Layer::Base).unwrap_or_default();
+        account.data = committed.data;
+        if undelegate {
+            account.owner = ID;
+            self.er.set_account(self.action, Account::default())?;
+        }
+        self.base.set_account(self.action, account)?;
+        Ok(())
+    }
+}
+
+fn is_wallet(account: &Account) -> bool {
+    account.owner == system_program::ID && account.data.is_empty() && !account.executable
+}
+
+/// Validator transactions: the delegation program's commits on the base
+/// layer and the magic program's own on the ER. Their effect on the action
+/// is mirrored from the ER transaction that scheduled the commit.
+fn bookkeeping(tx: &HistoryTx) -> bool {
+    !tx.programs().any(|p| *p == ID)
+        && tx.programs().any(|p| *p == ephemeral_rollups_sdk::id() || *p == MAGIC_PROGRAM_ID)
+}
+
+/// `Program data:` payloads this program logged itself, in order; those of
+/// programs it invoked are left out
+fn events(logs: &[String]) -> Vec<&str> {
+    let id = ID.to_string();
+    let mut stack: Vec<bool> = Vec::new();
+    let mut events = Vec::new();
+    for line in logs {
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        if let Some(payload) = rest.strip_prefix("data: ") {
+            if stack.last() == Some(&true) {
+                events.push(payload);
+            }
+            continue;
+        }
+        let mut words = rest.split(' ');
+        let program = words.next().unwrap_or_default();
+        match words.next() {
+            Some("invoke") => stack.push(program == id),
+            Some("success") | Some("failed:") => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+/// An event of type `E` from its `Program data:` payload
+fn decode<E: AnchorDeserialize + Discriminator>(payload: &str) -> Option<E> {
+    let bytes = STANDARD.decode(payload).ok()?;
+    E::try_from_slice(bytes.strip_prefix(E::DISCRIMINATOR)?).ok()
+}
+
+/// The action's id, from the FastActionCreated event of its creation
+pub fn created_id(history: &[HistoryTx], action: &Pubkey) -> Option<u64> {
+    history
+        .iter()
+        .filter_map(|tx| tx.logs.as_deref())
+        .flat_map(events)
+        .filter_map(decode::<FastActionCreated>)
+        .find(|created| created.action == *action)
+        .map(|created| created.action_id)
+}
+
+/// The ELF a deployed program runs, from its ProgramData account for the
+/// upgradeable loader
+pub fn program_elf(rpc: &RpcClient, program: &Account) -> ReplayResult<Option<Vec<u8>>> {
+    if program.owner == bpf_loader_upgradeable::id() {
+        let UpgradeableLoaderState::Program { programdata_address } = bincode::deserialize(&program.data)? else {
+            return Ok(None);
+        };
+        let data = rpc.get_account(&programdata_address)?.data;
+        Ok(Some(data[UpgradeableLoaderState::size_of_programdata_metadata()..].to_vec()))
+    } else if program.owner == bpf_loader::id() {
+        Ok(Some(program.data.clone()))
+    } else {
+        Ok(None)
+    }
+}
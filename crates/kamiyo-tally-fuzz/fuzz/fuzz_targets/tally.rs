@@ -0,0 +1,8 @@
+#![no_main]
+
+use kamiyo_tally_fuzz::{check, Source};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    check::tally(&mut Source::new(data));
+});
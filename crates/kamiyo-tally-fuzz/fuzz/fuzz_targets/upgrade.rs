@@ -0,0 +1,8 @@
+#![no_main]
+
+use kamiyo_tally_fuzz::check;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    check::upgrade_any(data);
+});
@@ -0,0 +1,331 @@
+// The invariants. Each check panics on a violation, which proptest shrinks
+// and libFuzzer records as a crash.
+
+use anchor_lang::prelude::*;
+use anchor_lang::AccountDeserialize;
+use kamiyo_fast_voting::{
+    tally, upgrade, ActionView, AgentClass, ApprovalRounding, BallotChoice, FastAction, VoteMode, VoteResult,
+    FAST_ACTION_SEED, FAST_ACTION_VERSION, ID, MAX_EXTRA_TIERS, MAX_OPTIONS,
+};
+
+use crate::gen::{self, TestBallot};
+use crate::layout::{self, serialize};
+use crate::{model, Source};
+
+/// Floored and within 0..=100; an error exactly when nobody voted
+pub fn approval(tally_for: u64, tally_against: u64) {
+    let total = tally_for as u128 + tally_against as u128;
+    match tally::approval_pct(tally_for, tally_against) {
+        Ok(pct) => {
+            assert!(total > 0);
+            assert!(pct <= 100);
+            assert!(pct as u128 * total <= tally_for as u128 * 100);
+            assert!((pct as u128 + 1) * total > tally_for as u128 * 100);
+        }
+        Err(_) => assert_eq!(total, 0),
+    }
+}
+
+/// Whether `numerator` / `total` meets `threshold_bps` once rounded, decided
+/// on the exact fraction rather than through share_bps
+fn meets(numerator: u128, total: u128, threshold_bps: u64, rounding: ApprovalRounding) -> bool {
+    let (share, t) = (numerator * 10_000, threshold_bps as u128);
+    match rounding {
+        ApprovalRounding::Floor => share >= t * total,
+        ApprovalRounding::Ceiling => t == 0 || share > (t - 1) * total,
+        // A share exactly halfway below t rounds up to it when t is even
+        ApprovalRounding::HalfEven => {
+            t == 0 || 2 * share > (2 * t - 1) * total || (2 * share == (2 * t - 1) * total && t.is_multiple_of(2))
+        }
+    }
+}
+
+/// Within 0..=10_000, off the exact share by under a basis point (half of
+/// one to nearest), ordered Floor <= HalfEven <= Ceiling; an error exactly
+/// when nobody voted
+pub fn approval_rounded(tally_for: u64, tally_against: u64) {
+    let total = tally_for as u128 + tally_against as u128;
+    let share = tally_for as u128 * 10_000;
+    let rounded = |rounding| tally::approval_bps(tally_for, tally_against, rounding);
+    let (Ok(floor), Ok(half_even), Ok(ceiling)) = (
+        rounded(ApprovalRounding::Floor),
+        rounded(ApprovalRounding::HalfEven),
+        rounded(ApprovalRounding::Ceiling),
+    ) else {
+        assert_eq!(total, 0);
+        return;
+    };
+    assert!(ceiling <= 10_000);
+    assert!(floor <= half_even && half_even <= ceiling && ceiling <= floor + 1);
+    assert!(floor as u128 * total <= share && share < (floor as u128 + 1) * total);
+    assert_eq!(ceiling as u128 * total == share, floor == ceiling);
+    assert!((half_even as u128 * total).abs_diff(share) * 2 <= total);
+    if (half_even as u128 * total).abs_diff(share) * 2 == total {
+        assert!(half_even.is_multiple_of(2));
+    }
+    if let Ok(pct) = tally::approval_pct(tally_for, tally_against) {
+        assert_eq!(pct, floor / 100);
+    }
+}
+
+/// Passing takes the rounded share reaching the threshold exactly as the
+/// fraction does, so a 2/3 share meets 6667 only when rounded up; under
+/// Floor a whole-percent threshold passes as the floored percentage did, and
+/// at 10_000 only a unanimous tally passes. A higher approval never earns a
+/// lower tier.
+pub fn grading(
+    tally_for: u64,
+    tally_against: u64,
+    threshold_bps: u16,
+    rounding: ApprovalRounding,
+    tiers: [u8; MAX_EXTRA_TIERS],
+) {
+    let Ok(bps) = tally::approval_bps(tally_for, tally_against, rounding) else {
+        return;
+    };
+    let total = tally_for as u128 + tally_against as u128;
+    let tier_of = |bps| match tally::grade(bps, threshold_bps, &tiers) {
+        VoteResult::Passed { tier } => Some(tier),
+        VoteResult::Failed => None,
+        other => panic!("grade returned {other:?}"),
+    };
+    let tier = tier_of(bps);
+    assert_eq!(tier.is_some(), bps >= threshold_bps as u64);
+    assert_eq!(tier.is_some(), meets(tally_for as u128, total, threshold_bps as u64, rounding));
+    if rounding == ApprovalRounding::Floor {
+        if threshold_bps.is_multiple_of(100) && threshold_bps <= 10_000 {
+            let pct = tally::approval_pct(tally_for, tally_against).unwrap();
+            assert_eq!(tier.is_some(), pct >= threshold_bps as u64 / 100);
+        }
+        if threshold_bps == 10_000 {
+            assert_eq!(tier.is_some(), tally_against == 0);
+        }
+    }
+    let threshold = threshold_bps.div_ceil(100);
+    if threshold <= 100 && tally::validate_tiers(threshold as u8, &tiers).is_ok() {
+        let configured = tiers.iter().take_while(|t| **t != 0).count() as u8;
+        assert!(tier.unwrap_or(0) <= configured);
+        if bps < 10_000 {
+            assert!(tier_of(bps + 1) >= tier);
+        }
+    }
+}
+
+/// The bps threshold an action stores: `threshold` as a whole percent when
+/// unset, otherwise one that rounds up to it
+pub fn threshold_bps(threshold: u8, threshold_bps: u16) {
+    match tally::validate_threshold_bps(threshold, threshold_bps) {
+        Ok(stored) if threshold_bps == 0 => assert_eq!(stored, threshold as u16 * 100),
+        Ok(stored) => {
+            assert_eq!(stored, threshold_bps);
+            assert_eq!(stored.div_ceil(100), threshold as u16);
+        }
+        Err(_) => assert!(threshold_bps != 0 && threshold_bps.div_ceil(100) != threshold as u16),
+    }
+}
+
+/// Between the floor and the quorum, never rising as slots pass: the quorum
+/// until the deadline, the floor once the grace period is over. `floor` and
+/// `quorum` are ordered first, as create_fast_action requires.
+pub fn quorum_decay(quorum: u32, floor: u32, grace_slots: u64, deadline_slot: u64, slot: u64) {
+    let (floor, quorum) = (floor.min(quorum), floor.max(quorum));
+    let required = |slot| tally::required_quorum(quorum, floor, grace_slots, deadline_slot, slot);
+    let now = required(slot);
+    assert!(floor <= now && now <= quorum);
+    if slot <= deadline_slot && grace_slots > 0 {
+        assert_eq!(now, quorum);
+    }
+    if slot.saturating_sub(deadline_slot) >= grace_slots {
+        assert_eq!(now, floor);
+    }
+    if let Some(later) = slot.checked_add(1) {
+        assert!(required(later) <= now);
+    }
+}
+
+/// Exact at any electorate, and more votes never lose turnout
+pub fn turnout(votes: u32, electorate: u32, min_turnout_bps: u16) {
+    let met = tally::turnout_met(votes, electorate, min_turnout_bps);
+    assert_eq!(met, votes as u128 * 10_000 >= min_turnout_bps as u128 * electorate as u128);
+    if met && votes < u32::MAX {
+        assert!(tally::turnout_met(votes + 1, electorate, min_turnout_bps));
+    }
+}
+
+/// Met at no bps, exact without overflow, and more weight never loses it
+pub fn weight_quorum(weight_for: u64, weight_against: u64, total_weight: u64, quorum_weight_bps: u16) {
+    let met = tally::weight_quorum_met(weight_for, weight_against, total_weight, quorum_weight_bps);
+    let cast = weight_for as u128 + weight_against as u128;
+    assert_eq!(met, cast * 10_000 >= quorum_weight_bps as u128 * total_weight as u128);
+    if quorum_weight_bps == 0 {
+        assert!(met);
+    }
+    if met && weight_for < u64::MAX {
+        assert!(tally::weight_quorum_met(weight_for + 1, weight_against, total_weight, quorum_weight_bps));
+    }
+}
+
+/// A multi-option tally chooses the sole leader only when its rounded share
+/// meets the threshold; ranked counts never pick or eliminate a knocked-out
+/// option
+pub fn options(option_weights: &[u64], threshold_bps: u16, rounding: ApprovalRounding, eliminated_mask: u8) {
+    assert!(option_weights.len() <= MAX_OPTIONS);
+    let total: u128 = option_weights.iter().map(|w| *w as u128).sum();
+    let lead = option_weights.iter().max().copied().unwrap_or(0);
+    let leaders = option_weights.iter().filter(|w| **w == lead).count();
+    let met = meets(lead as u128, total, threshold_bps as u64, rounding);
+    match tally::choose_option(option_weights, threshold_bps, rounding) {
+        Ok(VoteResult::Chosen { option }) => {
+            assert_eq!(option_weights[option as usize], lead);
+            assert_eq!(leaders, 1);
+            assert!(met);
+        }
+        Ok(VoteResult::Failed) => assert!(leaders > 1 || !met),
+        Ok(other) => panic!("choose_option returned {other:?}"),
+        Err(_) => assert_eq!(total, 0),
+    }
+
+    let continuing: Vec<usize> = (0..option_weights.len())
+        .filter(|i| eliminated_mask & (1 << i) == 0)
+        .collect();
+    if let Some(winner) = tally::runoff_winner(option_weights, eliminated_mask) {
+        assert!(continuing.contains(&(winner as usize)));
+    }
+    match tally::weakest_option(option_weights, eliminated_mask) {
+        Some(weakest) => {
+            assert!(continuing.contains(&(weakest as usize)));
+            let weakest = option_weights[weakest as usize];
+            assert!(continuing.iter().all(|i| option_weights[*i] >= weakest));
+        }
+        None => assert!(continuing.len() < 2),
+    }
+    if let Some((first, second)) = tally::top_two(option_weights) {
+        assert_ne!(first, second);
+        assert!(option_weights[first as usize] >= option_weights[second as usize]);
+        assert!(option_weights[second as usize] > 0);
+    }
+}
+
+/// Scaling a token-weighted ballot: class weights of at most 100% never add
+/// weight, the per-voter cap always holds, and the early-vote bonus is never
+/// negative or over its rate
+pub fn weights(action: &FastAction, class: AgentClass, weight: u64, slot: u64) {
+    let scaled = action.class_weight(class, weight);
+    if action.class_weight_bps[class as usize] <= 10_000 {
+        assert!(scaled <= weight);
+    }
+    let capped = action.capped_weight(weight);
+    assert!(capped <= weight);
+    if action.max_weight_per_voter > 0 {
+        assert!(capped <= action.max_weight_per_voter);
+    }
+    // The curve squares the window; the program bounds windows well below this
+    if action.deadline_slot.saturating_sub(action.created_slot) <= u32::MAX as u64 {
+        let early = action.early_weight(weight, slot);
+        let most = weight as u128 * (10_000 + action.early_vote_curve.bonus_bps as u128) / 10_000;
+        assert!(early >= weight && early as u128 <= most);
+    }
+}
+
+/// Serializes to the length FastAction::LEN reserves, one less for a narrow
+/// `result`, and deserializes back to itself
+pub fn round_trip(action: &FastAction) {
+    let data = serialize(action);
+    assert_eq!(data.len(), FastAction::LEN - !layout::wide(&action.result) as usize);
+    let decoded = FastAction::try_deserialize(&mut &data[..]).expect("FastAction deserializes");
+    assert_eq!(serialize(&decoded), data);
+}
+
+/// Migrating an account stored at `version` keeps the fields that version
+/// had and gives the rest their defaults
+pub fn migration(action: &FastAction, version: u8) {
+    let stored = layout::stored(action, version);
+    let (from, migrated) = upgrade(&stored).expect("stored layout migrates");
+    assert_eq!(from, version);
+    assert_eq!(serialize(&migrated), serialize(&layout::migrated(action, version)));
+}
+
+/// Any bytes either migrate or are refused; never a panic
+pub fn upgrade_any(data: &[u8]) {
+    if let Ok((from, migrated)) = upgrade(data) {
+        assert!(from < FAST_ACTION_VERSION);
+        assert_eq!(migrated.version, FAST_ACTION_VERSION);
+    }
+}
+
+/// ActionView::cast and cast_option, run on the account bytes, against the
+/// model on the deserialized action: same outcome for every ballot and the
+/// same bytes after each. A refused ballot leaves both untouched, as the
+/// reverted transaction would.
+pub fn ballots(mut action: FastAction, ballots: &[TestBallot]) {
+    // ActionView tallies headcount only; vote_fast refuses token-weighted actions
+    if action.vote_mode == VoteMode::TokenWeighted {
+        action.vote_mode = VoteMode::Headcount;
+    }
+    action.version = FAST_ACTION_VERSION;
+    let hive_seed: &[u8] = if action.hive_seeded { action.hive.as_ref() } else { &[] };
+    let (key, bump) =
+        Pubkey::find_program_address(&[FAST_ACTION_SEED, hive_seed, &action.action_id.to_le_bytes()], &ID);
+    action.bump = bump;
+
+    let mut data = serialize(&action);
+    data.resize(FastAction::LEN, 0);
+    for ballot in ballots {
+        let before = data.clone();
+        let mut view = ActionView::load(&key, &ID, &mut data).expect("the action's own account loads");
+        assert_eq!(view.member_collection(), action.member_collection);
+        let cast = match ballot.choice {
+            BallotChoice::ForAgainst(vote_value) => {
+                view.cast(&ballot.voter, vote_value, &ballot.voter_commitment, ballot.slot)
+            }
+            BallotChoice::Option(option) => {
+                view.cast_option(&ballot.voter, option, &ballot.voter_commitment, ballot.slot)
+            }
+        };
+
+        let mut modelled = action.clone();
+        let expected = model::cast(&mut modelled, ballot);
+        assert_eq!(cast, expected, "{ballot:?}");
+        if expected.is_ok() {
+            action = modelled;
+        } else {
+            data = before;
+        }
+        let mut bytes = serialize(&action);
+        bytes.resize(FastAction::LEN, 0);
+        assert_eq!(data, bytes, "{ballot:?}");
+    }
+}
+
+/// Every tally check on values drawn from `src`
+pub fn tally(src: &mut Source) {
+    let (tally_for, tally_against) = (src.u64(), src.u64());
+    approval(tally_for, tally_against);
+    approval_rounded(tally_for, tally_against);
+    grading(tally_for, tally_against, src.u16(), gen::approval_rounding(src), src.bytes());
+    // Ballot counts as well, where the u32 tallies meet their limits
+    let (votes_for, votes_against) = (src.u32() as u64, src.u32() as u64);
+    let bar = (src.below(101) as u16 * 100).saturating_sub(src.below(100) as u16);
+    grading(votes_for, votes_against, bar, gen::approval_rounding(src), src.bytes());
+    threshold_bps(src.u8(), src.u16());
+    quorum_decay(src.u32(), src.u32(), src.u64(), src.u64(), src.u64());
+    turnout(src.u32(), src.u32(), src.u16());
+    weight_quorum(src.u64(), src.u64(), src.u64(), src.u16());
+    let count = src.below(MAX_OPTIONS as u8 + 1) as usize;
+    let option_weights: Vec<u64> = (0..count).map(|_| src.u64()).collect();
+    options(&option_weights, src.u16(), gen::approval_rounding(src), src.u8());
+}
+
+/// Every action check on an action drawn from `src`
+pub fn action(src: &mut Source) {
+    let action = gen::fast_action(src);
+    round_trip(&action);
+    migration(&action, src.below(FAST_ACTION_VERSION));
+    weights(&action, gen::agent_class(src), src.u64(), src.u64());
+    let action = gen::open_action(src);
+    let mut cast = Vec::new();
+    while !src.is_empty() && cast.len() < 16 {
+        cast.push(gen::ballot(src, &action));
+    }
+    ballots(action, &cast);
+}
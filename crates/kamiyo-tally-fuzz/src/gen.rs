@@ -0,0 +1,250 @@
+// Arbitrary values drawn from a Source. Fields the program keeps within
+// bounds on creation (counts of fixed arrays) stay within them; everything
+// else, counters included, takes any value, so overflow paths are reached.
+
+use anchor_lang::prelude::Pubkey;
+use kamiyo_fast_voting::{
+    ActionKind, ActionLog, AgentClass, ApprovalRounding, BallotChoice, CurveShape, DelegationState, EarlyVoteCurve,
+    ExtensionPolicy, FastAction, LogEntry, LogTag, PrivacyLevel, VoteMode, VoteResult, FAST_ACTION_VERSION,
+    MAX_DEPENDENCIES, MAX_DEADLINE_EXTENSIONS, MAX_EXCLUSIONS, MAX_OPTIONS, MAX_VOTES_PER_ACTION,
+};
+
+use crate::Source;
+
+pub fn vote_result(src: &mut Source) -> VoteResult {
+    match src.below(10) {
+        0 => VoteResult::Pending,
+        1 => VoteResult::Passed { tier: src.u8() },
+        2 => VoteResult::Failed,
+        3 => VoteResult::Cancelled,
+        4 => VoteResult::Expired,
+        5 => VoteResult::Voided,
+        6 => VoteResult::Chosen { option: src.u8() },
+        7 => VoteResult::Vetoed,
+        8 => VoteResult::Contested { tier: src.u8() },
+        _ => VoteResult::Pending,
+    }
+}
+
+pub fn vote_mode(src: &mut Source) -> VoteMode {
+    match src.below(3) {
+        0 => VoteMode::Headcount,
+        1 => VoteMode::TokenWeighted,
+        _ => VoteMode::Optimistic,
+    }
+}
+
+pub fn action_kind(src: &mut Source) -> ActionKind {
+    match src.below(8) {
+        0 => ActionKind::Standard,
+        1 => ActionKind::Emergency,
+        2 => ActionKind::ConfigChange,
+        3 => ActionKind::ProgramUpgrade,
+        4 => ActionKind::Budget,
+        5 => ActionKind::Election,
+        _ => ActionKind::TaskAssignment,
+    }
+}
+
+pub fn privacy_level(src: &mut Source) -> PrivacyLevel {
+    match src.below(4) {
+        0 => PrivacyLevel::Public,
+        1 => PrivacyLevel::CommitReveal,
+        2 => PrivacyLevel::Encrypted,
+        _ => PrivacyLevel::AnonymousZk,
+    }
+}
+
+pub fn approval_rounding(src: &mut Source) -> ApprovalRounding {
+    match src.below(3) {
+        0 => ApprovalRounding::Floor,
+        1 => ApprovalRounding::HalfEven,
+        _ => ApprovalRounding::Ceiling,
+    }
+}
+
+pub fn agent_class(src: &mut Source) -> AgentClass {
+    match src.below(4) {
+        0 => AgentClass::Unclassified,
+        1 => AgentClass::Scout,
+        2 => AgentClass::Worker,
+        _ => AgentClass::Coordinator,
+    }
+}
+
+fn log_tag(src: &mut Source) -> LogTag {
+    match src.below(7) {
+        0 => LogTag::Empty,
+        1 => LogTag::Delegated,
+        2 => LogTag::Undelegated,
+        3 => LogTag::Checkpointed,
+        4 => LogTag::QuorumReached,
+        5 => LogTag::ThresholdCrossed,
+        6 => LogTag::ResultRecorded,
+        _ => LogTag::DeadlineExtended,
+    }
+}
+
+fn action_log(src: &mut Source) -> ActionLog {
+    ActionLog {
+        entries: std::array::from_fn(|_| LogEntry {
+            slot: src.u64(),
+            tag: log_tag(src),
+            payload: src.u64(),
+        }),
+        appended: src.u32(),
+    }
+}
+
+/// A current-version action with every field drawn from `src`
+pub fn fast_action(src: &mut Source) -> FastAction {
+    FastAction {
+        version: FAST_ACTION_VERSION,
+        action_id: src.u64(),
+        action_hash: src.bytes(),
+        description_hash: src.bytes(),
+        creator: src.pubkey(),
+        threshold: src.u8(),
+        votes_for: src.u32(),
+        votes_against: src.u32(),
+        vote_count: src.u32(),
+        created_slot: src.u64(),
+        deadline_slot: src.u64(),
+        executed: src.bool(),
+        result: vote_result(src),
+        bump: src.u8(),
+        vote_mode: vote_mode(src),
+        weight_for: src.u64(),
+        weight_against: src.u64(),
+        hive: src.pubkey(),
+        tier_thresholds: src.bytes(),
+        dependency_count: src.below(MAX_DEPENDENCIES as u8 + 1),
+        dependencies: std::array::from_fn(|_| src.pubkey()),
+        finalized_slot: src.u64(),
+        result_digest: src.bytes(),
+        last_checkpoint_slot: src.u64(),
+        last_checkpoint_votes: src.u32(),
+        delegated_validator: src.pubkey(),
+        session_recorded: src.bool(),
+        electorate: src.u32(),
+        bond: src.u64(),
+        quorum: src.u32(),
+        quorum_floor: src.u32(),
+        quorum_grace_slots: src.u64(),
+        min_turnout_bps: src.u16(),
+        option_count: src.below(MAX_OPTIONS as u8 + 1),
+        option_weights: std::array::from_fn(|_| src.u64()),
+        ranked: src.bool(),
+        eliminated_mask: src.u8(),
+        pending_elimination: src.u8(),
+        round: src.u8(),
+        exhausted_ballots: src.u32(),
+        parent_action: src.pubkey(),
+        runoff_action: src.pubkey(),
+        market_feed: src.pubkey(),
+        market_program: src.pubkey(),
+        market_price_offset: src.u16(),
+        market_min_price: src.u64(),
+        market_cleared: src.bool(),
+        authority: src.pubkey(),
+        draft: src.pubkey(),
+        kind: action_kind(src),
+        max_weight_per_voter: src.u64(),
+        class_max_votes: std::array::from_fn(|_| src.u32()),
+        class_weight_bps: std::array::from_fn(|_| src.u16()),
+        class_votes: std::array::from_fn(|_| src.u32()),
+        delegation_epoch: src.u32(),
+        delegation_state: if src.bool() {
+            DelegationState::Delegated
+        } else {
+            DelegationState::Undelegated
+        },
+        audited: src.bool(),
+        hive_seeded: src.bool(),
+        early_vote_curve: EarlyVoteCurve {
+            bonus_bps: src.u16(),
+            shape: if src.bool() { CurveShape::Quadratic } else { CurveShape::Linear },
+        },
+        log: action_log(src),
+        excluded_count: src.below(MAX_EXCLUSIONS as u8 + 1),
+        excluded: std::array::from_fn(|_| src.pubkey()),
+        extension_policy: ExtensionPolicy {
+            max_extensions: src.below(MAX_DEADLINE_EXTENSIONS + 1),
+            support_bps: src.u16(),
+        },
+        extensions: src.below(MAX_DEADLINE_EXTENSIONS + 1),
+        extended_slots: src.u64(),
+        identity_weighted: src.bool(),
+        member_collection: src.pubkey(),
+        stake_lock_program: src.pubkey(),
+        priority: src.u8(),
+        lane_counted: src.bool(),
+        max_votes: src.u32(),
+        tags: std::array::from_fn(|_| src.bytes()),
+        tags_indexed: src.bool(),
+        quorum_weight_bps: src.u16(),
+        snapshot_weight: src.u64(),
+        creation_fee: src.u64(),
+        fee_rebate: src.u64(),
+        privacy: privacy_level(src),
+        validator_counted: src.bool(),
+        threshold_bps: src.u16(),
+        approval_rounding: approval_rounding(src),
+        backup_validator: src.pubkey(),
+    }
+}
+
+/// fast_action, mostly still open to ballots: unexecuted, under the ballot
+/// limit, with its quorum a few ballots off and tallies small enough for a
+/// ballot to move it across the threshold, or at the u32 limit
+pub fn open_action(src: &mut Source) -> FastAction {
+    let mut action = fast_action(src);
+    if src.below(8) != 0 {
+        action.executed = false;
+        action.vote_count %= MAX_VOTES_PER_ACTION;
+        action.quorum = action.vote_count.saturating_add(src.below(4) as u32);
+        action.threshold = src.below(101);
+        action.threshold_bps = (action.threshold as u16 * 100).saturating_sub(src.below(100) as u16);
+        action.ranked = false;
+        action.eliminated_mask &= src.u8() & src.u8();
+        (action.votes_for, action.votes_against) = match src.below(4) {
+            0 => (u32::MAX - src.below(2) as u32, u32::MAX - src.below(2) as u32),
+            _ => (src.below(8) as u32, src.below(8) as u32),
+        };
+    }
+    action
+}
+
+/// A ballot on `action`
+#[derive(Clone, Copy, Debug)]
+pub struct TestBallot {
+    pub voter: Pubkey,
+    pub choice: BallotChoice,
+    pub voter_commitment: [u8; 32],
+    pub slot: u64,
+}
+
+/// Mostly the kind of ballot the action takes; voters are sometimes on its
+/// exclusion list, commitments sometimes zero and slots land around the
+/// deadline, so each of the ballot checks is hit.
+pub fn ballot(src: &mut Source, action: &FastAction) -> TestBallot {
+    let voter = match src.below(4) {
+        0 => action.excluded[src.below(MAX_EXCLUSIONS as u8) as usize],
+        _ => src.pubkey(),
+    };
+    let options = (src.below(8) != 0).then_some(action.option_count);
+    let choice = match options {
+        Some(0) => BallotChoice::ForAgainst(src.bool()),
+        Some(count) => BallotChoice::Option(src.below(count)),
+        None if src.bool() => BallotChoice::ForAgainst(src.bool()),
+        None => BallotChoice::Option(src.below(MAX_OPTIONS as u8)),
+    };
+    let voter_commitment = if src.below(8) == 0 { [0; 32] } else { src.bytes() };
+    let slot = action.deadline_slot.saturating_add(src.below(3) as u64).saturating_sub(1);
+    TestBallot {
+        voter,
+        choice,
+        voter_commitment,
+        slot,
+    }
+}
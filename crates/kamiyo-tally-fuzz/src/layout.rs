@@ -0,0 +1,215 @@
+// FastAction accounts as each layout version stored them, and what
+// migration should turn them into. The lengths and defaults restate the
+// version list in `migration` independently, so the two check each other.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountSerialize;
+use kamiyo_fast_voting::{
+    ActionKind, ActionLog, ApprovalRounding, DelegationState, EarlyVoteCurve, ExtensionPolicy, FastAction,
+    PrivacyLevel, VoteResult, AGENT_CLASSES, DEFAULT_PRIORITY, FAST_ACTION_VERSION, MAX_EXCLUSIONS, MAX_OPTIONS,
+    MAX_TAGS, MAX_VOTES_PER_ACTION, MIN_VOTES_FOR_QUORUM, NO_ELIMINATION,
+};
+
+/// Account length of each version before the current one, discriminator
+/// included, with `result` at its two-byte width
+pub const VERSION_LENS: [usize; FAST_ACTION_VERSION as usize] = [
+    366,  // 0
+    367,  // 1: version
+    379,  // 2: checkpoints
+    411,  // 3: delegated_validator
+    412,  // 4: session_recorded
+    424,  // 5: electorate, bond
+    440,  // 6: adaptive quorum
+    442,  // 7: min_turnout_bps
+    507,  // 8: options
+    515,  // 9: ranked choice
+    579,  // 10: runoffs
+    654,  // 11: market gate
+    686,  // 12: authority
+    718,  // 13: draft
+    719,  // 14: kind
+    727,  // 15: max_weight_per_voter
+    767,  // 16: class limits
+    771,  // 17: delegation_epoch
+    772,  // 18: delegation_state
+    773,  // 19: audited
+    774,  // 20: hive_seeded
+    777,  // 21: early_vote_curve
+    1053, // 22: log
+    1182, // 23: exclusions
+    1194, // 24: deadline extensions
+    1195, // 25: identity_weighted
+    1227, // 26: member_collection
+    1259, // 27: stake_lock_program
+    1261, // 28: priority, lane_counted
+    1265, // 29: max_votes
+    1298, // 30: tags, tags_indexed
+    1308, // 31: weight quorum
+    1324, // 32: fee rebates
+    1325, // 33: privacy
+    1326, // 34: validator_counted
+    1329, // 35: threshold_bps, approval_rounding
+];
+
+/// Whether `result` takes two bytes in Borsh
+pub fn wide(result: &VoteResult) -> bool {
+    matches!(result, VoteResult::Passed { .. } | VoteResult::Chosen { .. } | VoteResult::Contested { .. })
+}
+
+pub fn serialize(action: &FastAction) -> Vec<u8> {
+    let mut data = Vec::with_capacity(FastAction::LEN);
+    action.try_serialize(&mut data).expect("FastAction serializes");
+    data
+}
+
+/// `action` as an account last written at `version`: the fields that
+/// version had, zero-padded to its length
+pub fn stored(action: &FastAction, version: u8) -> Vec<u8> {
+    let mut data = serialize(action);
+    let len = VERSION_LENS[version as usize];
+    if version == 0 {
+        data.remove(8);
+    } else {
+        data[8] = version;
+    }
+    data.truncate(len - !wide(&action.result) as usize);
+    data.resize(len, 0);
+    data
+}
+
+/// What migrating `stored(action, version)` must yield: `action` with the
+/// fields added after `version` at their migration defaults
+pub fn migrated(action: &FastAction, version: u8) -> FastAction {
+    let mut a = action.clone();
+    a.version = FAST_ACTION_VERSION;
+    if version < 2 {
+        a.last_checkpoint_slot = 0;
+        a.last_checkpoint_votes = 0;
+    }
+    if version < 3 {
+        a.delegated_validator = Pubkey::default();
+    }
+    if version < 4 {
+        a.session_recorded = false;
+    }
+    if version < 5 {
+        a.electorate = 0;
+        a.bond = 0;
+    }
+    if version < 6 {
+        a.quorum = MIN_VOTES_FOR_QUORUM;
+        a.quorum_floor = MIN_VOTES_FOR_QUORUM;
+        a.quorum_grace_slots = 0;
+    }
+    if version < 7 {
+        a.min_turnout_bps = 0;
+    }
+    if version < 8 {
+        a.option_count = 0;
+        a.option_weights = [0; MAX_OPTIONS];
+    }
+    if version < 9 {
+        a.ranked = false;
+        a.eliminated_mask = 0;
+        a.pending_elimination = NO_ELIMINATION;
+        a.round = 0;
+        a.exhausted_ballots = 0;
+    }
+    if version < 10 {
+        a.parent_action = Pubkey::default();
+        a.runoff_action = Pubkey::default();
+    }
+    if version < 11 {
+        a.market_feed = Pubkey::default();
+        a.market_program = Pubkey::default();
+        a.market_price_offset = 0;
+        a.market_min_price = 0;
+        a.market_cleared = false;
+    }
+    if version < 12 {
+        a.authority = a.creator;
+    }
+    if version < 13 {
+        a.draft = Pubkey::default();
+    }
+    if version < 14 {
+        a.kind = ActionKind::Standard;
+    }
+    if version < 15 {
+        a.max_weight_per_voter = 0;
+    }
+    if version < 16 {
+        a.class_max_votes = [0; AGENT_CLASSES];
+        a.class_weight_bps = [0; AGENT_CLASSES];
+        a.class_votes = [0; AGENT_CLASSES];
+    }
+    if version < 17 {
+        a.delegation_epoch = 0;
+    }
+    if version < 18 {
+        a.delegation_state = DelegationState::Undelegated;
+    }
+    if version < 19 {
+        a.audited = false;
+    }
+    if version < 20 {
+        a.hive_seeded = false;
+    }
+    if version < 21 {
+        a.early_vote_curve = EarlyVoteCurve::OFF;
+    }
+    if version < 22 {
+        a.log = ActionLog::default();
+    }
+    if version < 23 {
+        a.excluded_count = 0;
+        a.excluded = [Pubkey::default(); MAX_EXCLUSIONS];
+    }
+    if version < 24 {
+        a.extension_policy = ExtensionPolicy::OFF;
+        a.extensions = 0;
+        a.extended_slots = 0;
+    }
+    if version < 25 {
+        a.identity_weighted = false;
+    }
+    if version < 26 {
+        a.member_collection = Pubkey::default();
+    }
+    if version < 27 {
+        a.stake_lock_program = Pubkey::default();
+    }
+    if version < 28 {
+        a.priority = DEFAULT_PRIORITY;
+        a.lane_counted = false;
+    }
+    if version < 29 {
+        a.max_votes = MAX_VOTES_PER_ACTION;
+    }
+    if version < 30 {
+        a.tags = [[0u8; 8]; MAX_TAGS];
+        a.tags_indexed = false;
+    }
+    if version < 31 {
+        a.quorum_weight_bps = 0;
+        a.snapshot_weight = 0;
+    }
+    if version < 32 {
+        a.creation_fee = 0;
+        a.fee_rebate = 0;
+    }
+    if version < 33 {
+        a.privacy = PrivacyLevel::Public;
+    }
+    if version < 34 {
+        a.validator_counted = false;
+    }
+    if version < 35 {
+        a.threshold_bps = a.threshold as u16 * 100;
+        a.approval_rounding = ApprovalRounding::Floor;
+    }
+    if version < 36 {
+        a.backup_validator = Pubkey::default();
+    }
+    a
+}
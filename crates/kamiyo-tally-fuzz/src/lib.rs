@@ -0,0 +1,20 @@
+//! Property tests and fuzz targets for fast voting's tally arithmetic and
+//! FastAction layouts.
+//!
+//! - [`source`]: turns raw bytes into values, so proptest and cargo-fuzz
+//!   drive the same generators
+//! - [`gen`]: arbitrary actions and ballots
+//! - [`layout`]: accounts as each FastAction version stored them
+//! - [`model`]: the Borsh-level ballot path ActionView must reproduce
+//! - [`check`]: the invariants, as panicking checks
+//!
+//! `cargo test -p kamiyo-tally-fuzz` runs them under proptest; the targets in
+//! `fuzz/` run them under libFuzzer with `cargo +nightly fuzz run <target>`.
+
+pub mod check;
+pub mod gen;
+pub mod layout;
+pub mod model;
+pub mod source;
+
+pub use source::Source;
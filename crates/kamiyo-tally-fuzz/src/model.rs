@@ -0,0 +1,56 @@
+// The ballot path on the deserialized FastAction, as open_ballot,
+// record_vote and vote_options take it for a weight-1 ballot. ActionView
+// must leave the account bytes exactly as serializing this would.
+
+use anchor_lang::prelude::*;
+use kamiyo_fast_voting::{BallotChoice, FastAction, FastVoteError, LogTag, VoteMode};
+
+use crate::gen::TestBallot;
+
+/// Returns the new vote count, as ActionView::cast and cast_option do
+pub fn cast(action: &mut FastAction, ballot: &TestBallot) -> Result<u32> {
+    match ballot.choice {
+        BallotChoice::ForAgainst(vote_value) => {
+            require!(action.option_count == 0, FastVoteError::OptionBallotRequired);
+            let passing_before = action.passing();
+            let votes = open(action, ballot)?;
+            if vote_value {
+                action.votes_for = action.votes_for.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+            } else {
+                action.votes_against = action.votes_against.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+            }
+            if action.vote_mode != VoteMode::Optimistic && action.passing() != passing_before {
+                let approval = action.approval_pct().unwrap_or_default();
+                action.log.push(ballot.slot, LogTag::ThresholdCrossed, approval);
+            }
+            Ok(votes)
+        }
+        BallotChoice::Option(option) => {
+            require!(action.option_count > 0, FastVoteError::NotAnOptionAction);
+            require!(!action.ranked, FastVoteError::RankedBallotRequired);
+            require!(
+                option < action.option_count && action.eliminated_mask & (1 << option) == 0,
+                FastVoteError::InvalidOptionWeights
+            );
+            let votes = open(action, ballot)?;
+            let weight = &mut action.option_weights[option as usize];
+            *weight = weight.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+            Ok(votes)
+        }
+    }
+}
+
+fn open(action: &mut FastAction, ballot: &TestBallot) -> Result<u32> {
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(ballot.slot <= action.deadline_slot, FastVoteError::VotingEnded);
+    require!(action.vote_count < action.max_votes, FastVoteError::MaxVotesReached);
+    require!(ballot.voter_commitment != [0u8; 32], FastVoteError::InvalidVoterCommitment);
+    require!(!action.excludes(&ballot.voter), FastVoteError::VoterExcluded);
+
+    action.vote_count = action.vote_count.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+    let votes = action.vote_count;
+    if votes == action.quorum {
+        action.log.push(ballot.slot, LogTag::QuorumReached, votes as u64);
+    }
+    Ok(votes)
+}
@@ -0,0 +1,55 @@
+// Byte source for the generators. Reads past the end yield zeros, so every
+// input, however short, maps to some value.
+
+use anchor_lang::prelude::Pubkey;
+
+pub struct Source<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Source<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        let mut out = [0u8; N];
+        let n = N.min(self.data.len());
+        out[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        out
+    }
+
+    pub fn u8(&mut self) -> u8 {
+        self.bytes::<1>()[0]
+    }
+
+    pub fn u16(&mut self) -> u16 {
+        u16::from_le_bytes(self.bytes())
+    }
+
+    pub fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.bytes())
+    }
+
+    pub fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.bytes())
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.u8() & 1 == 1
+    }
+
+    pub fn pubkey(&mut self) -> Pubkey {
+        Pubkey::new_from_array(self.bytes())
+    }
+
+    /// Uniform enough below `n` for small `n`
+    pub fn below(&mut self, n: u8) -> u8 {
+        self.u8() % n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
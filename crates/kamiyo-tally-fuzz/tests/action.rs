@@ -0,0 +1,54 @@
+// FastAction layouts and the zero-copy ballot path under proptest, on
+// actions generated from random bytes.
+
+use kamiyo_fast_voting::FAST_ACTION_VERSION;
+use kamiyo_tally_fuzz::layout::VERSION_LENS;
+use kamiyo_tally_fuzz::{check, gen, Source};
+use proptest::prelude::*;
+
+/// Enough bytes for an action and a run of ballots
+const SEED_LEN: usize = 4096;
+
+fn seed() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(any::<u8>(), SEED_LEN)
+}
+
+#[test]
+fn version_lengths_only_grow_to_the_current_one() {
+    assert!(VERSION_LENS.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(*VERSION_LENS.last().unwrap() < kamiyo_fast_voting::FastAction::LEN);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn actions_round_trip(seed in seed()) {
+        check::round_trip(&gen::fast_action(&mut Source::new(&seed)));
+    }
+
+    #[test]
+    fn every_version_migrates(seed in seed(), version in 0..FAST_ACTION_VERSION) {
+        check::migration(&gen::fast_action(&mut Source::new(&seed)), version);
+    }
+
+    #[test]
+    fn migration_never_panics(data in prop::collection::vec(any::<u8>(), 0..1300)) {
+        check::upgrade_any(&data);
+    }
+
+    #[test]
+    fn weights_scale_within_bounds(seed in seed(), weight in any::<u64>(), slot in any::<u64>()) {
+        let mut src = Source::new(&seed);
+        let action = gen::fast_action(&mut src);
+        check::weights(&action, gen::agent_class(&mut src), weight, slot);
+    }
+
+    #[test]
+    fn ballots_match_the_model(seed in seed()) {
+        let mut src = Source::new(&seed);
+        let action = gen::open_action(&mut src);
+        let ballots: Vec<_> = (0..16).map(|_| gen::ballot(&mut src, &action)).collect();
+        check::ballots(action, &ballots);
+    }
+}
@@ -0,0 +1,134 @@
+// Tally arithmetic under proptest. Values lean towards the edges (zero, one,
+// the type's maximum and around the u32 ballot limits), where rounding and
+// overflow go wrong; small tallies, where basis-point boundaries fall, are
+// checked exhaustively.
+
+use kamiyo_fast_voting::{tally, ApprovalRounding, MAX_OPTIONS};
+use kamiyo_tally_fuzz::check;
+use proptest::prelude::*;
+
+const ROUNDINGS: [ApprovalRounding; 3] =
+    [ApprovalRounding::Floor, ApprovalRounding::HalfEven, ApprovalRounding::Ceiling];
+
+fn edgy_u64() -> impl Strategy<Value = u64> {
+    prop_oneof![
+        Just(0u64),
+        Just(1),
+        Just(u64::MAX),
+        Just(u32::MAX as u64),
+        0..=200u64,
+        any::<u32>().prop_map(u64::from),
+        any::<u64>(),
+    ]
+}
+
+fn edgy_u32() -> impl Strategy<Value = u32> {
+    prop_oneof![Just(0u32), Just(1), Just(u32::MAX), 0..=200u32, any::<u32>()]
+}
+
+fn threshold() -> impl Strategy<Value = u8> {
+    prop_oneof![Just(100u8), Just(99), Just(1), 0..=100u8, any::<u8>()]
+}
+
+fn threshold_bps() -> impl Strategy<Value = u16> {
+    prop_oneof![
+        Just(10_000u16),
+        Just(6_667),
+        Just(5_000),
+        Just(1),
+        threshold().prop_map(|t| t as u16 * 100),
+        0..=10_000u16,
+        any::<u16>(),
+    ]
+}
+
+fn rounding() -> impl Strategy<Value = ApprovalRounding> {
+    prop::sample::select(ROUNDINGS.to_vec())
+}
+
+/// Every tally of up to 120 ballots against each threshold around its own
+/// approval, under each rounding: the boundaries small hives actually hit
+#[test]
+fn small_tallies_meet_thresholds_exactly() {
+    for tally_for in 0..=120u64 {
+        for tally_against in 0..=120u64 {
+            check::approval_rounded(tally_for, tally_against);
+            for rounding in ROUNDINGS {
+                let Ok(bps) = tally::approval_bps(tally_for, tally_against, rounding) else {
+                    continue;
+                };
+                for bar in [bps.saturating_sub(1), bps, bps + 1] {
+                    check::grading(tally_for, tally_against, bar as u16, rounding, [0; 3]);
+                }
+                for threshold in 1..=100u16 {
+                    check::grading(tally_for, tally_against, threshold * 100, rounding, [0; 3]);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn threshold_bps_rounds_up_to_the_whole_percent() {
+    for threshold in 0..=u8::MAX {
+        for threshold_bps in 0..=10_100u16 {
+            check::threshold_bps(threshold, threshold_bps);
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(4096))]
+
+    #[test]
+    fn approval_is_floored_and_bounded(tally_for in edgy_u64(), tally_against in edgy_u64()) {
+        check::approval(tally_for, tally_against);
+    }
+
+    #[test]
+    fn rounded_approval_is_within_a_basis_point(tally_for in edgy_u64(), tally_against in edgy_u64()) {
+        check::approval_rounded(tally_for, tally_against);
+    }
+
+    #[test]
+    fn grading_follows_the_rounded_share(
+        tally_for in edgy_u64(),
+        tally_against in edgy_u64(),
+        threshold_bps in threshold_bps(),
+        rounding in rounding(),
+        tiers in any::<[u8; 3]>(),
+    ) {
+        check::grading(tally_for, tally_against, threshold_bps, rounding, tiers);
+    }
+
+    #[test]
+    fn unanimity_alone_passes_at_100_floored(votes_for in 1..=u32::MAX, votes_against in 0..=3u32) {
+        check::grading(votes_for as u64, votes_against as u64, 10_000, ApprovalRounding::Floor, [0; 3]);
+    }
+
+    #[test]
+    fn quorum_decays_between_quorum_and_floor(
+        quorum in edgy_u32(),
+        floor in edgy_u32(),
+        grace_slots in edgy_u64(),
+        deadline_slot in edgy_u64(),
+        slot in edgy_u64(),
+    ) {
+        check::quorum_decay(quorum, floor, grace_slots, deadline_slot, slot);
+    }
+
+    #[test]
+    fn turnout_is_exact(votes in edgy_u32(), electorate in edgy_u32(), min_turnout_bps in any::<u16>()) {
+        check::turnout(votes, electorate, min_turnout_bps);
+    }
+
+    #[test]
+    fn options_choose_and_eliminate_consistently(
+        option_weights in prop::collection::vec(edgy_u64(), 0..=MAX_OPTIONS),
+        threshold_bps in threshold_bps(),
+        rounding in rounding(),
+        eliminated_mask in any::<u8>(),
+    ) {
+        check::options(&option_weights, threshold_bps, rounding, eliminated_mask);
+    }
+}
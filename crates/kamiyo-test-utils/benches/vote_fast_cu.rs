@@ -0,0 +1,68 @@
+//! Compute units vote_fast spends on the ephemeral rollup, next to the
+//! VOTE_FAST_CU_TARGET it was designed for. Needs the program binary, like
+//! the harness: `anchor build && cargo bench -p kamiyo-test-utils`.
+//!
+//! Each case casts ballots from fresh voters on one delegated action and
+//! reports the spread; a ballot that creates the voter's VoteCredits is
+//! measured apart from one that only updates it.
+//!
+//! The target is not asserted: no SBF run of the current hot path, with its
+//! freeze, membership, class-cap, credits, metrics and standings-board
+//! writes, has been recorded yet. Pin a budget from a measured run.
+
+use kamiyo_hive_client::instructions;
+use kamiyo_test_utils::{fast_action, Harness};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+/// What a steady-state vote_fast was designed to spend
+const VOTE_FAST_CU_TARGET: u64 = 10_000;
+
+const BALLOTS: u64 = 32;
+
+fn main() {
+    let mut harness = Harness::new();
+    let voters: Vec<_> = (0..BALLOTS).map(|_| harness.funded_keypair()).collect();
+
+    // First ballot of each voter: VoteCredits is created alongside the vote
+    let first = cast_all(&mut harness, 0, &voters);
+    // Same voters on a second action: VoteCredits only updated
+    let steady = cast_all(&mut harness, 1, &voters);
+
+    report("first ballot", &first);
+    report("steady state", &steady);
+    let worst = *steady.iter().max().unwrap();
+    if worst > VOTE_FAST_CU_TARGET {
+        println!("steady state over its {VOTE_FAST_CU_TARGET} CU target by {} CU", worst - VOTE_FAST_CU_TARGET);
+    }
+}
+
+fn cast_all(harness: &mut Harness, action_id: u64, voters: &[Keypair]) -> Vec<u64> {
+    let slot = harness.slot();
+    harness.put_action(&fast_action(action_id).window(slot, slot + 10_000).build());
+    harness.delegate(action_id);
+
+    voters
+        .iter()
+        .enumerate()
+        .map(|(i, voter)| {
+            let ix = instructions::vote_fast(voter.pubkey(), action_id, 0, i % 3 != 0, [1; 32]);
+            let meta = harness
+                .send_er(&[ix], &[voter])
+                .unwrap_or_else(|e| panic!("ballot {i} failed: {}", e.meta.pretty_logs()));
+            meta.compute_units_consumed
+        })
+        .collect()
+}
+
+fn report(case: &str, units: &[u64]) {
+    let mut sorted = units.to_vec();
+    sorted.sort_unstable();
+    println!(
+        "{case:<13} min {:>6} CU  median {:>6} CU  max {:>6} CU  ({} ballots)",
+        sorted[0],
+        sorted[sorted.len() / 2],
+        sorted[sorted.len() - 1],
+        sorted.len()
+    );
+}
@@ -0,0 +1,300 @@
+// Populated account values for seeding a harness without replaying
+// instructions
+
+use anchor_lang::prelude::Pubkey;
+use kamiyo_fast_voting::{
+    ActionKind, ActionLog, AdaptiveQuorum, ApprovalRounding, DelegationState, EarlyVoteCurve, ExtensionPolicy,
+    FastAction, FastVote, MarketGate, PrivacyLevel, VoteMode, VoteResult, AGENT_CLASSES, DEFAULT_PRIORITY,
+    FAST_ACTION_VERSION, MAX_DEPENDENCIES, MAX_EXCLUSIONS, MAX_EXTRA_TIERS, MAX_OPTIONS, MAX_TAGS,
+    MAX_VOTES_PER_ACTION, MIN_VOTES_FOR_QUORUM, NO_ELIMINATION,
+};
+use kamiyo_hive_client::pda;
+
+pub struct FastActionBuilder {
+    action: FastAction,
+}
+
+/// An open headcount action with a 50% threshold and a 75-slot window
+/// starting at slot 0.
+pub fn fast_action(action_id: u64) -> FastActionBuilder {
+    FastActionBuilder {
+        action: FastAction {
+            version: FAST_ACTION_VERSION,
+            action_id,
+            action_hash: [1; 32],
+            description_hash: [0; 32],
+            creator: Pubkey::default(),
+            threshold: 50,
+            votes_for: 0,
+            votes_against: 0,
+            vote_count: 0,
+            created_slot: 0,
+            deadline_slot: kamiyo_fast_voting::VOTING_WINDOW_SLOTS,
+            executed: false,
+            result: VoteResult::Pending,
+            bump: pda::fast_action(action_id).1,
+            vote_mode: VoteMode::Headcount,
+            weight_for: 0,
+            weight_against: 0,
+            hive: Pubkey::default(),
+            tier_thresholds: [0; MAX_EXTRA_TIERS],
+            dependency_count: 0,
+            dependencies: [Pubkey::default(); MAX_DEPENDENCIES],
+            finalized_slot: 0,
+            result_digest: [0; 32],
+            last_checkpoint_slot: 0,
+            last_checkpoint_votes: 0,
+            delegated_validator: Pubkey::default(),
+            session_recorded: false,
+            electorate: 0,
+            bond: 0,
+            quorum: MIN_VOTES_FOR_QUORUM,
+            quorum_floor: MIN_VOTES_FOR_QUORUM,
+            quorum_grace_slots: 0,
+            min_turnout_bps: 0,
+            option_count: 0,
+            option_weights: [0; MAX_OPTIONS],
+            ranked: false,
+            eliminated_mask: 0,
+            pending_elimination: NO_ELIMINATION,
+            round: 0,
+            exhausted_ballots: 0,
+            parent_action: Pubkey::default(),
+            runoff_action: Pubkey::default(),
+            market_feed: Pubkey::default(),
+            market_program: Pubkey::default(),
+            market_price_offset: 0,
+            market_min_price: 0,
+            market_cleared: false,
+            authority: Pubkey::default(),
+            draft: Pubkey::default(),
+            kind: ActionKind::Standard,
+            max_weight_per_voter: 0,
+            class_max_votes: [0; AGENT_CLASSES],
+            class_weight_bps: [0; AGENT_CLASSES],
+            class_votes: [0; AGENT_CLASSES],
+            delegation_epoch: 0,
+            delegation_state: DelegationState::Undelegated,
+            audited: false,
+            hive_seeded: false,
+            early_vote_curve: EarlyVoteCurve::OFF,
+            log: ActionLog::default(),
+            excluded_count: 0,
+            excluded: [Pubkey::default(); MAX_EXCLUSIONS],
+            extension_policy: ExtensionPolicy::OFF,
+            extensions: 0,
+            extended_slots: 0,
+            identity_weighted: false,
+            member_collection: Pubkey::default(),
+            stake_lock_program: Pubkey::default(),
+            priority: DEFAULT_PRIORITY,
+            lane_counted: false,
+            max_votes: MAX_VOTES_PER_ACTION,
+            tags: [[0u8; 8]; MAX_TAGS],
+            tags_indexed: false,
+            quorum_weight_bps: 0,
+            snapshot_weight: 0,
+            creation_fee: 0,
+            fee_rebate: 0,
+            privacy: PrivacyLevel::Public,
+            validator_counted: false,
+            threshold_bps: 5_000,
+            approval_rounding: ApprovalRounding::Floor,
+            backup_validator: Pubkey::default(),
+        },
+    }
+}
+
+impl FastActionBuilder {
+    pub fn action_hash(mut self, action_hash: [u8; 32]) -> Self {
+        self.action.action_hash = action_hash;
+        self
+    }
+
+    pub fn creator(mut self, creator: Pubkey) -> Self {
+        self.action.creator = creator;
+        self.action.authority = creator;
+        self
+    }
+
+    pub fn threshold(mut self, threshold: u8) -> Self {
+        self.action.threshold = threshold;
+        self.action.threshold_bps = threshold as u16 * 100;
+        self
+    }
+
+    /// A basis-point pass bar, with `threshold` rounded up from it
+    pub fn threshold_bps(mut self, threshold_bps: u16) -> Self {
+        self.action.threshold = threshold_bps.div_ceil(100) as u8;
+        self.action.threshold_bps = threshold_bps;
+        self
+    }
+
+    pub fn rounding(mut self, rounding: ApprovalRounding) -> Self {
+        self.action.approval_rounding = rounding;
+        self
+    }
+
+    pub fn tiers(mut self, tier_thresholds: [u8; MAX_EXTRA_TIERS]) -> Self {
+        self.action.tier_thresholds = tier_thresholds;
+        self
+    }
+
+    /// Headcount votes; `vote_count` follows
+    pub fn votes(mut self, votes_for: u32, votes_against: u32) -> Self {
+        self.action.votes_for = votes_for;
+        self.action.votes_against = votes_against;
+        self.action.vote_count = votes_for + votes_against;
+        self
+    }
+
+    /// Switches to token-weighted mode
+    pub fn weights(mut self, weight_for: u64, weight_against: u64) -> Self {
+        self.action.vote_mode = VoteMode::TokenWeighted;
+        self.action.weight_for = weight_for;
+        self.action.weight_against = weight_against;
+        self
+    }
+
+    pub fn adaptive_quorum(mut self, quorum: AdaptiveQuorum) -> Self {
+        self.action.quorum = quorum.quorum;
+        self.action.quorum_floor = quorum.floor;
+        self.action.quorum_grace_slots = quorum.grace_slots;
+        self
+    }
+
+    /// Quorum counted in weight against a posted snapshot's total
+    pub fn weight_quorum(mut self, quorum_weight_bps: u16, snapshot_weight: u64) -> Self {
+        self.action.quorum_weight_bps = quorum_weight_bps;
+        self.action.snapshot_weight = snapshot_weight;
+        self
+    }
+
+    pub fn privacy(mut self, privacy: PrivacyLevel) -> Self {
+        self.action.privacy = privacy;
+        self
+    }
+
+    pub fn turnout(mut self, min_turnout_bps: u16, electorate: u32) -> Self {
+        self.action.min_turnout_bps = min_turnout_bps;
+        self.action.electorate = electorate;
+        self
+    }
+
+    /// Multi-option action with these per-option tallies; `vote_count` is
+    /// left to the caller
+    pub fn options(mut self, option_weights: &[u64]) -> Self {
+        self.action.option_count = option_weights.len() as u8;
+        self.action.option_weights = [0; MAX_OPTIONS];
+        self.action.option_weights[..option_weights.len()].copy_from_slice(option_weights);
+        self
+    }
+
+    /// Ranked-choice count over the options, with the `eliminated_mask`
+    /// options already knocked out
+    pub fn ranked(mut self, eliminated_mask: u8) -> Self {
+        self.action.ranked = true;
+        self.action.eliminated_mask = eliminated_mask;
+        self.action.round = eliminated_mask.count_ones() as u8;
+        self
+    }
+
+    pub fn emergency(mut self) -> Self {
+        self.action.kind = ActionKind::Emergency;
+        self
+    }
+
+    pub fn market_gate(mut self, gate: MarketGate) -> Self {
+        self.action.market_feed = gate.feed;
+        self.action.market_program = gate.feed_program;
+        self.action.market_price_offset = gate.price_offset;
+        self.action.market_min_price = gate.min_price;
+        self
+    }
+
+    pub fn window(mut self, created_slot: u64, deadline_slot: u64) -> Self {
+        self.action.created_slot = created_slot;
+        self.action.deadline_slot = deadline_slot;
+        self
+    }
+
+    pub fn hive(mut self, hive: Pubkey) -> Self {
+        self.action.hive = hive;
+        self
+    }
+
+    pub fn dependencies(mut self, dependencies: &[Pubkey]) -> Self {
+        assert!(dependencies.len() <= MAX_DEPENDENCIES, "too many dependencies");
+        self.action.dependency_count = dependencies.len() as u8;
+        self.action.dependencies = [Pubkey::default(); MAX_DEPENDENCIES];
+        self.action.dependencies[..dependencies.len()].copy_from_slice(dependencies);
+        self
+    }
+
+    /// Marks the action finalized; the digest is computed as tally would
+    pub fn finalized(mut self, result: VoteResult, finalized_slot: u64) -> Self {
+        let a = &mut self.action;
+        a.result_digest = kamiyo_fast_voting::tally::result_digest(
+            a.action_id,
+            &a.action_hash,
+            a.votes_for,
+            a.votes_against,
+            &result,
+            finalized_slot,
+        );
+        a.executed = true;
+        a.result = result;
+        a.finalized_slot = finalized_slot;
+        self
+    }
+
+    pub fn build(self) -> FastAction {
+        self.action
+    }
+}
+
+pub struct FastVoteBuilder {
+    vote: FastVote,
+}
+
+/// A headcount vote in favour, cast at slot 0
+pub fn fast_vote(fast_action: Pubkey, voter: Pubkey) -> FastVoteBuilder {
+    FastVoteBuilder {
+        vote: FastVote {
+            fast_action,
+            voter,
+            voter_commitment: [0; 32],
+            vote_value: true,
+            voted_slot: 0,
+            bump: pda::fast_vote(&fast_action, &voter).1,
+            weight: 1,
+            reason_hash: [0; 32],
+        },
+    }
+}
+
+impl FastVoteBuilder {
+    pub fn value(mut self, vote_value: bool) -> Self {
+        self.vote.vote_value = vote_value;
+        self
+    }
+
+    pub fn weight(mut self, weight: u64) -> Self {
+        self.vote.weight = weight;
+        self
+    }
+
+    pub fn commitment(mut self, voter_commitment: [u8; 32]) -> Self {
+        self.vote.voter_commitment = voter_commitment;
+        self
+    }
+
+    pub fn voted_slot(mut self, voted_slot: u64) -> Self {
+        self.vote.voted_slot = voted_slot;
+        self
+    }
+
+    pub fn build(self) -> FastVote {
+        self.vote
+    }
+}
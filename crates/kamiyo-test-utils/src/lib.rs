@@ -0,0 +1,236 @@
+//! LiteSVM fixtures for integration-testing against fast voting.
+//!
+//! [`Harness`] runs two VMs: `base` for the Solana base layer and `er` for
+//! the MagicBlock ephemeral rollup. Delegation is simulated by moving the
+//! FastAction account between them ([`Harness::delegate`],
+//! [`Harness::commit`]); the magic program on the ER is a no-op builtin, so
+//! `tally_and_commit` runs unmodified. `delegate_action` itself is not
+//! supported, as there is no delegation program to CPI into.
+//!
+//! Needs the program binary from `anchor build`; see [`program_path`].
+
+// TransactionResult is litesvm's own type; pass it through unboxed
+#![allow(clippy::result_large_err)]
+
+pub mod builders;
+
+use std::path::{Path, PathBuf};
+
+use anchor_lang::prelude::{Clock, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{AccountDeserialize, AccountSerialize};
+use ephemeral_rollups_sdk::consts::{MAGIC_CONTEXT_ID, MAGIC_PROGRAM_ID};
+use kamiyo_fast_voting::{
+    FastAction, FastVote, FeeRebate, GlobalConfig, IdentityRamp, DEFAULT_MAX_ACTIONS_PER_EPOCH,
+    DEFAULT_WHISTLEBLOWER_BPS, ID,
+};
+use kamiyo_hive_client::pda;
+use litesvm::types::TransactionResult;
+use litesvm::LiteSVM;
+use solana_program_runtime::declare_process_instruction;
+use solana_sdk::account::Account;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+
+pub use builders::{fast_action, fast_vote};
+
+declare_process_instruction!(MockMagicProgram, 0, |_invoke_context| { Ok(()) });
+
+/// `KAMIYO_FAST_VOTING_SO` if set, else the workspace's `target/deploy`
+pub fn program_path() -> PathBuf {
+    std::env::var_os("KAMIYO_FAST_VOTING_SO")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy/kamiyo_fast_voting.so")
+        })
+}
+
+pub struct Harness {
+    pub base: LiteSVM,
+    pub er: LiteSVM,
+    /// Fee payer for every transaction, funded on both layers
+    pub payer: Keypair,
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        Self::with_program(program_path())
+    }
+
+    /// Loads the program on both layers and seeds a default GlobalConfig
+    /// administered by `payer`. `initialize_config` cannot run here: LiteSVM
+    /// loads programs without a ProgramData account.
+    pub fn with_program(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let load = |svm: &mut LiteSVM| {
+            svm.add_program_from_file(ID, path)
+                .unwrap_or_else(|e| panic!("loading {}: {e}", path.display()))
+        };
+
+        let mut base = LiteSVM::new();
+        load(&mut base);
+
+        let mut er = LiteSVM::new();
+        load(&mut er);
+        er.add_builtin(MAGIC_PROGRAM_ID, MockMagicProgram::vm);
+        er.set_account(
+            MAGIC_CONTEXT_ID,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                owner: MAGIC_PROGRAM_ID,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let payer = Keypair::new();
+        base.airdrop(&payer.pubkey(), 100 * LAMPORTS_PER_SOL).unwrap();
+        er.airdrop(&payer.pubkey(), 100 * LAMPORTS_PER_SOL).unwrap();
+
+        let mut harness = Self { base, er, payer };
+        harness.put_config(&GlobalConfig {
+            admin: harness.payer.pubkey(),
+            weight_mint: Pubkey::default(),
+            weight_extensions: 0,
+            transfer_hook_program: Pubkey::default(),
+            bump: pda::global_config().1,
+            max_actions_per_epoch: DEFAULT_MAX_ACTIONS_PER_EPOCH,
+            guardian: Pubkey::default(),
+            vote_credits_per_epoch: 0,
+            min_agent_bond: 0,
+            whistleblower_bps: DEFAULT_WHISTLEBLOWER_BPS,
+            snapshotter: Pubkey::default(),
+            max_window_slots: 0,
+            stake_lock_program: Pubkey::default(),
+            max_low_priority_actions: 0,
+            max_votes_per_action: 0,
+            identity_ramp: IdentityRamp::default(),
+            fee_rebate: FeeRebate::default(),
+            max_actions_per_validator: 0,
+        });
+        harness
+    }
+
+    /// A new keypair with 10 SOL on both layers
+    pub fn funded_keypair(&mut self) -> Keypair {
+        let keypair = Keypair::new();
+        self.base.airdrop(&keypair.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+        self.er.airdrop(&keypair.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+        keypair
+    }
+
+    pub fn send_base(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> TransactionResult {
+        send(&mut self.base, &self.payer, instructions, signers)
+    }
+
+    pub fn send_er(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> TransactionResult {
+        send(&mut self.er, &self.payer, instructions, signers)
+    }
+
+    /// On both layers, so weighted votes on the ER can read it
+    pub fn put_config(&mut self, config: &GlobalConfig) {
+        let address = pda::global_config().0;
+        put(&mut self.base, address, config, GlobalConfig::LEN);
+        put(&mut self.er, address, config, GlobalConfig::LEN);
+    }
+
+    /// Writes an undelegated action to the base layer
+    pub fn put_action(&mut self, action: &FastAction) -> Pubkey {
+        let address = pda::fast_action(action.action_id).0;
+        put(&mut self.base, address, action, FastAction::LEN);
+        address
+    }
+
+    /// Votes live on the ER, where they are cast
+    pub fn put_vote(&mut self, vote: &FastVote) -> Pubkey {
+        let address = pda::fast_vote(&vote.fast_action, &vote.voter).0;
+        put(&mut self.er, address, vote, FastVote::LEN);
+        address
+    }
+
+    /// The action as seen wherever it is live: the ER while delegated, else
+    /// the base layer.
+    pub fn fast_action(&self, action_id: u64) -> Option<FastAction> {
+        let address = pda::fast_action(action_id).0;
+        let account = match self.base.get_account(&address) {
+            Some(account) if account.owner == ID => account,
+            _ => self.er.get_account(&address)?,
+        };
+        FastAction::try_deserialize(&mut &account.data[..]).ok()
+    }
+
+    /// Mirror `delegate_action`: the ER gets a live copy and the base account
+    /// is handed to the delegation program.
+    pub fn delegate(&mut self, action_id: u64) {
+        let address = pda::fast_action(action_id).0;
+        let mut account = self.base.get_account(&address).expect("action not on base layer");
+        assert_eq!(account.owner, ID, "action already delegated");
+
+        self.er.set_account(address, account.clone()).unwrap();
+        account.owner = ephemeral_rollups_sdk::id();
+        self.base.set_account(address, account).unwrap();
+    }
+
+    /// Mirror the commit scheduled by `tally_and_commit` (or a manual
+    /// undelegation): ER state lands on the base layer, owned by the program
+    /// again, and the ER copy is dropped.
+    pub fn commit(&mut self, action_id: u64) {
+        let address = pda::fast_action(action_id).0;
+        let account = self.er.get_account(&address).expect("action not on ER");
+        self.base.set_account(address, account).unwrap();
+        self.er.set_account(address, Account::default()).unwrap();
+    }
+
+    pub fn slot(&self) -> u64 {
+        self.base.get_sysvar::<Clock>().slot
+    }
+
+    /// Advance both layers' clocks together
+    pub fn warp_to_slot(&mut self, slot: u64) {
+        self.base.warp_to_slot(slot);
+        self.er.warp_to_slot(slot);
+    }
+
+    pub fn warp_slots(&mut self, slots: u64) {
+        self.warp_to_slot(self.slot() + slots);
+    }
+
+    /// First slot at which the action can be tallied
+    pub fn warp_past_deadline(&mut self, action_id: u64) {
+        let action = self.fast_action(action_id).expect("unknown action");
+        self.warp_to_slot(action.deadline_slot + 1);
+    }
+}
+
+fn send(svm: &mut LiteSVM, payer: &Keypair, instructions: &[Instruction], signers: &[&Keypair]) -> TransactionResult {
+    let mut all = vec![payer];
+    all.extend(signers.iter().copied().filter(|s| s.pubkey() != payer.pubkey()));
+    let tx = Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), &all, svm.latest_blockhash());
+    let result = svm.send_transaction(tx);
+    // Identical instructions can be resent without tripping AlreadyProcessed
+    svm.expire_blockhash();
+    result
+}
+
+/// Write a program-owned, rent-exempt account holding `value`
+pub fn put<T: AccountSerialize>(svm: &mut LiteSVM, address: Pubkey, value: &T, len: usize) {
+    let mut data = Vec::with_capacity(len);
+    value.try_serialize(&mut data).unwrap();
+    data.resize(len, 0);
+    let account = Account {
+        lamports: svm.minimum_balance_for_rent_exemption(len),
+        data,
+        owner: ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    svm.set_account(address, account).unwrap();
+}
@@ -0,0 +1,103 @@
+// Fraud proofs against tallied results. The base layer can't tally on its
+// own, so the finalized actions are seeded as the ER would commit them.
+// Needs the program binary, like the harness: `anchor build && cargo test
+// -p kamiyo-test-utils -- --ignored`.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountDeserialize;
+use kamiyo_fast_voting::{
+    sequence_head, Evidence, SequenceBatch, SequencedBallot, ValidatorRecord, ValidatorStatus, VoteResult, SLASH_BPS,
+};
+use kamiyo_hive_client::{instructions, pda};
+use kamiyo_test_utils::{fast_action, put, Harness};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signer::Signer;
+
+const FINALIZED: u64 = 100;
+
+#[test]
+#[ignore = "needs the program binary"]
+fn a_miscounted_result_is_voided() {
+    let mut h = Harness::new();
+    // Three ballots cast, two counted
+    let mut action = fast_action(0).votes(2, 0).finalized(VoteResult::Passed { tier: 0 }, FINALIZED).build();
+    action.vote_count = 3;
+    h.put_action(&action);
+    h.warp_to_slot(FINALIZED + 1);
+
+    let challenger = h.funded_keypair();
+    let ix = instructions::challenge_result(challenger.pubkey(), 0, None, Evidence::Counters, &[]);
+    h.send_base(&[ix], &[&challenger]).unwrap_or_else(|e| panic!("{}", e.meta.pretty_logs()));
+
+    assert_eq!(h.fast_action(0).unwrap().result, VoteResult::Voided);
+}
+
+#[test]
+#[ignore = "needs the program binary"]
+fn an_honest_result_stands() {
+    let mut h = Harness::new();
+    h.put_action(&fast_action(0).votes(2, 0).finalized(VoteResult::Passed { tier: 0 }, FINALIZED).build());
+    h.warp_to_slot(FINALIZED + 1);
+
+    let challenger = h.funded_keypair();
+    let ix = instructions::challenge_result(challenger.pubkey(), 0, None, Evidence::Counters, &[]);
+    let err = h.send_base(&[ix], &[&challenger]).unwrap_err();
+    assert!(err.meta.logs.iter().any(|log| log.contains("ResultNotFraudulent")));
+
+    assert_eq!(h.fast_action(0).unwrap().result, VoteResult::Passed { tier: 0 });
+}
+
+#[test]
+#[ignore = "needs the program binary"]
+fn a_sequenced_ballot_left_out_is_voided() {
+    let mut h = Harness::new();
+    let validator = Pubkey::new_unique();
+    let mut action = fast_action(0).votes(2, 0).finalized(VoteResult::Passed { tier: 0 }, FINALIZED).build();
+    action.delegated_validator = validator;
+    let address = h.put_action(&action);
+
+    let bond = LAMPORTS_PER_SOL;
+    let (record, bump) = pda::validator_record(&validator);
+    let value = ValidatorRecord {
+        validator,
+        status: ValidatorStatus::Allowed,
+        registered_slot: 0,
+        last_heartbeat_slot: 0,
+        bump,
+        sessions_hosted: 0,
+        unclaimed_sessions: 0,
+        rewards_claimed: 0,
+        bond,
+        active_actions: 0,
+    };
+    put(&mut h.base, record, &value, ValidatorRecord::LEN);
+    let mut account = h.base.get_account(&record).unwrap();
+    account.lamports += bond;
+    h.base.set_account(record, account).unwrap();
+
+    // Received before the deadline, with no FastVote behind it
+    let ballots = vec![SequencedBallot { voter: Pubkey::new_unique(), received_slot: 10, ballot_hash: [5; 32] }];
+    let (batch, bump) = pda::sequence_batch(&address, &validator, 0);
+    let value = SequenceBatch {
+        action: address,
+        validator,
+        index: 0,
+        prev_head: [0; 32],
+        head: sequence_head([0; 32], &address, &ballots),
+        count: 1,
+        through_slot: 10,
+        posted_slot: 20,
+        bump,
+    };
+    put(&mut h.base, batch, &value, SequenceBatch::LEN);
+    h.warp_to_slot(FINALIZED + 1);
+
+    let challenger = h.funded_keypair();
+    let ix = instructions::challenge_exclusion(challenger.pubkey(), 0, validator, 0, ballots, 0);
+    h.send_base(&[ix], &[&challenger]).unwrap_or_else(|e| panic!("{}", e.meta.pretty_logs()));
+
+    assert_eq!(h.fast_action(0).unwrap().result, VoteResult::Voided);
+    let account = h.base.get_account(&record).unwrap();
+    let slashed = ValidatorRecord::try_deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(slashed.bond, bond - bond * SLASH_BPS / 10_000);
+}
@@ -0,0 +1,51 @@
+// export_result_proof on a settled action, seeded as the ER would commit it.
+// Needs the program binary, like the harness: `anchor build && cargo test
+// -p kamiyo-test-utils -- --ignored`.
+
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::AccountDeserialize;
+use kamiyo_fast_voting::{ResultProof, ResultProofBuffer, VoteResult, DISPUTE_WINDOW_SLOTS, ID};
+use kamiyo_hive_client::{instructions, pda};
+use kamiyo_test_utils::{fast_action, Harness};
+use solana_sdk::signer::Signer;
+
+const FINALIZED: u64 = 100;
+
+#[test]
+#[ignore = "needs the program binary"]
+fn a_settled_result_is_exported() {
+    let mut h = Harness::new();
+    let action = fast_action(0).votes(3, 1).finalized(VoteResult::Passed { tier: 0 }, FINALIZED).build();
+    let address = h.put_action(&action);
+    h.warp_to_slot(FINALIZED + DISPUTE_WINDOW_SLOTS + 1);
+    let data = h.base.get_account(&address).unwrap().data;
+
+    let payer = h.funded_keypair();
+    let ix = instructions::export_result_proof(payer.pubkey(), 0, None);
+    h.send_base(&[ix], &[&payer]).unwrap_or_else(|e| panic!("{}", e.meta.pretty_logs()));
+
+    // No attestors and no hosting validator: an unsigned proof
+    let expected = ResultProof {
+        program_id: ID,
+        action: address,
+        action_id: 0,
+        action_hash: action.action_hash,
+        hive: action.hive,
+        votes_for: 3,
+        votes_against: 1,
+        weight_for: 0,
+        weight_against: 0,
+        result: VoteResult::Passed { tier: 0 },
+        finalized_slot: FINALIZED,
+        result_digest: action.result_digest,
+        account_hash: hash(&data).to_bytes(),
+        signatures: Vec::new(),
+    };
+    assert!(expected.digest_matches());
+
+    let account = h.base.get_account(&pda::result_proof(&address, &payer.pubkey()).0).unwrap();
+    let buffer = ResultProofBuffer::try_deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(buffer.action, address);
+    assert_eq!(buffer.payer, payer.pubkey());
+    assert_eq!(buffer.proof, expected.encode());
+}
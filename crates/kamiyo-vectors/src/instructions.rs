@@ -0,0 +1,71 @@
+// Instruction data whose encoding is not plain Anchor: vote_compact's
+// one-byte ballot and short commitment, across every choice and the
+// delegation epoch and action id edges, and the commitment a commit-reveal
+// ballot sends. The scenarios cover the Anchor encoded instructions.
+
+use kamiyo_fast_voting::{ballot_commitment, BallotChoice, MAX_OPTIONS};
+use kamiyo_hive_client::{instructions, pda};
+use serde_json::{json, Value};
+
+use crate::json;
+use crate::VOTER;
+
+fn choice(choice: &BallotChoice) -> Value {
+    match choice {
+        BallotChoice::ForAgainst(vote_value) => json!({ "ForAgainst": vote_value }),
+        BallotChoice::Option(option) => json!({ "Option": option }),
+    }
+}
+
+fn vote_compact() -> Value {
+    let choices = [
+        BallotChoice::ForAgainst(false),
+        BallotChoice::ForAgainst(true),
+        BallotChoice::Option(0),
+        BallotChoice::Option(1),
+        BallotChoice::Option(MAX_OPTIONS as u8 - 1),
+    ];
+    let mut vectors = Vec::new();
+    for ballot in &choices {
+        for (action_id, delegation_epoch, commitment) in
+            [(0, 0, None), (1, 1, Some([0xab; 16])), (u64::MAX, u32::MAX, Some([0xff; 16]))]
+        {
+            let ix = instructions::vote_compact(VOTER, action_id, delegation_epoch, *ballot, commitment);
+            vectors.push(json!({
+                "voter": json::key(&VOTER),
+                "action_id": json::u64(action_id),
+                "delegation_epoch": delegation_epoch,
+                "choice": choice(ballot),
+                "ballot": ballot.pack(),
+                "commitment": commitment.map(|c| json::hex(&c)),
+                "instruction": json::instruction(&ix),
+            }));
+        }
+    }
+    Value::Array(vectors)
+}
+
+/// Sent as the voter commitment of a CommitReveal ballot, or its first 16
+/// bytes through vote_compact
+fn commit_reveal() -> Value {
+    let action = pda::fast_action(0).0;
+    [(false, [0; 32]), (true, [0; 32]), (true, [0xff; 32])]
+        .iter()
+        .map(|(vote_value, salt)| {
+            json!({
+                "action": json::key(&action),
+                "voter": json::key(&VOTER),
+                "vote_value": vote_value,
+                "salt": json::hex(salt),
+                "commitment": json::hex(&ballot_commitment(&action, &VOTER, *vote_value, salt)),
+            })
+        })
+        .collect()
+}
+
+pub fn fixture() -> Value {
+    json!({
+        "vote_compact": vote_compact(),
+        "commit_reveal": commit_reveal(),
+    })
+}
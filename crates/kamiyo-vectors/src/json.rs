@@ -0,0 +1,188 @@
+// How values are written in the fixtures. Bytes are lowercase hex, keys
+// base58, u64 and wider as decimal strings so JS readers keep every bit,
+// smaller integers as numbers, and enums by variant name with their fields
+// alongside.
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::AccountSerialize;
+use kamiyo_fast_voting::{ActionParams, FastAction, FastVote, VoteResult, WeightCap};
+use serde_json::{json, Value};
+
+pub fn hex(bytes: &[u8]) -> Value {
+    Value::String(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+pub fn key(key: &Pubkey) -> Value {
+    Value::String(key.to_string())
+}
+
+pub fn u64(value: u64) -> Value {
+    Value::String(value.to_string())
+}
+
+pub fn u64s(values: &[u64]) -> Value {
+    values.iter().map(|v| u64(*v)).collect()
+}
+
+pub fn keys(keys: &[Pubkey]) -> Value {
+    keys.iter().map(key).collect()
+}
+
+pub fn result(result: &VoteResult) -> Value {
+    match result {
+        VoteResult::Passed { tier } => json!({ "Passed": { "tier": tier } }),
+        VoteResult::Chosen { option } => json!({ "Chosen": { "option": option } }),
+        VoteResult::Contested { tier } => json!({ "Contested": { "tier": tier } }),
+        unit => Value::String(format!("{unit:?}")),
+    }
+}
+
+/// `Ok` as `{"ok": ...}`, an error as `{"error": <FastVoteError name>}`
+pub fn outcome<T>(outcome: anchor_lang::Result<T>, ok: impl FnOnce(T) -> Value) -> Value {
+    match outcome {
+        Ok(value) => json!({ "ok": ok(value) }),
+        Err(err) => json!({ "error": error_name(&err) }),
+    }
+}
+
+pub fn error_name(err: &anchor_lang::error::Error) -> String {
+    match err {
+        anchor_lang::error::Error::AnchorError(e) => e.error_name.clone(),
+        anchor_lang::error::Error::ProgramError(e) => e.program_error.to_string(),
+    }
+}
+
+fn account_meta(meta: &AccountMeta) -> Value {
+    json!({
+        "pubkey": key(&meta.pubkey),
+        "is_signer": meta.is_signer,
+        "is_writable": meta.is_writable,
+    })
+}
+
+pub fn instruction(ix: &Instruction) -> Value {
+    json!({
+        "program_id": key(&ix.program_id),
+        "accounts": ix.accounts.iter().map(account_meta).collect::<Value>(),
+        "data": hex(&ix.data),
+    })
+}
+
+fn weight_cap(cap: &WeightCap) -> Value {
+    match cap {
+        WeightCap::Absolute(weight) => json!({ "Absolute": u64(*weight) }),
+        WeightCap::SupplyBps(bps) => json!({ "SupplyBps": bps }),
+    }
+}
+
+/// Every field of `params`, under its Rust name
+pub fn params(params: &ActionParams) -> Value {
+    json!({
+        "action_hash": hex(&params.action_hash),
+        "threshold": params.threshold,
+        "description_hash": hex(&params.description_hash),
+        "vote_mode": format!("{:?}", params.vote_mode),
+        "tier_thresholds": params.tier_thresholds,
+        "dependencies": keys(&params.dependencies),
+        "adaptive_quorum": params.adaptive_quorum.map(|q| json!({
+            "quorum": q.quorum,
+            "floor": q.floor,
+            "grace_slots": u64(q.grace_slots),
+        })),
+        "min_turnout_bps": params.min_turnout_bps,
+        "option_count": params.option_count,
+        "ranked": params.ranked,
+        "market_gate": params.market_gate.as_ref().map(|g| json!({
+            "feed": key(&g.feed),
+            "feed_program": key(&g.feed_program),
+            "price_offset": g.price_offset,
+            "min_price": u64(g.min_price),
+        })),
+        "kind": format!("{:?}", params.kind),
+        "weight_cap": params.weight_cap.as_ref().map(weight_cap),
+        "class_limits": params.class_limits.iter().map(|l| json!({
+            "class": format!("{:?}", l.class),
+            "max_votes": l.max_votes,
+            "weight_bps": l.weight_bps,
+        })).collect::<Value>(),
+        "audited": params.audited,
+        "early_vote_bonus": params.early_vote_bonus,
+        "exclusions": keys(&params.exclusions),
+        "deadline_extension": params.deadline_extension.map(|e| json!({
+            "max_extensions": e.max_extensions,
+            "support_bps": e.support_bps,
+        })),
+        "identity_weighted": params.identity_weighted,
+        "member_collection": params.member_collection.as_ref().map(key),
+        "lock_stake": params.lock_stake,
+        "priority": params.priority,
+        "tags": params.tags.iter().map(|t| hex(t)).collect::<Value>(),
+        "quorum_weight_bps": params.quorum_weight_bps,
+        "privacy": format!("{:?}", params.privacy),
+        "threshold_bps": params.threshold_bps,
+    })
+}
+
+/// Account data as stored on chain: discriminator first, zero-padded to
+/// `len` since a narrow `result` serializes a byte short
+pub fn account_data<T: AccountSerialize>(account: &T, len: usize) -> Vec<u8> {
+    let mut data = Vec::with_capacity(len);
+    account.try_serialize(&mut data).expect("account serializes");
+    data.resize(len, 0);
+    data
+}
+
+/// A FastAction's stored bytes, with the tally fields decoded alongside to
+/// help locate a mismatch
+pub fn action(address: &Pubkey, action: &FastAction) -> Value {
+    json!({
+        "address": key(address),
+        "data": hex(&account_data(action, FastAction::LEN)),
+        "fields": {
+            "action_id": u64(action.action_id),
+            "bump": action.bump,
+            "threshold": action.threshold,
+            "threshold_bps": action.threshold_bps,
+            "approval_rounding": format!("{:?}", action.approval_rounding),
+            "vote_mode": format!("{:?}", action.vote_mode),
+            "tier_thresholds": action.tier_thresholds,
+            "votes_for": action.votes_for,
+            "votes_against": action.votes_against,
+            "vote_count": action.vote_count,
+            "weight_for": u64(action.weight_for),
+            "weight_against": u64(action.weight_against),
+            "deadline_slot": u64(action.deadline_slot),
+            "electorate": action.electorate,
+            "quorum": action.quorum,
+            "quorum_floor": action.quorum_floor,
+            "quorum_grace_slots": u64(action.quorum_grace_slots),
+            "min_turnout_bps": action.min_turnout_bps,
+            "quorum_weight_bps": action.quorum_weight_bps,
+            "snapshot_weight": u64(action.snapshot_weight),
+            "option_count": action.option_count,
+            "option_weights": u64s(&action.option_weights),
+            "ranked": action.ranked,
+            "eliminated_mask": action.eliminated_mask,
+            "result": result(&action.result),
+            "privacy": format!("{:?}", action.privacy),
+        },
+    })
+}
+
+pub fn vote(address: &Pubkey, vote: &FastVote) -> Value {
+    json!({
+        "address": key(address),
+        "data": hex(&account_data(vote, FastVote::LEN)),
+        "fields": {
+            "fast_action": key(&vote.fast_action),
+            "voter": key(&vote.voter),
+            "voter_commitment": hex(&vote.voter_commitment),
+            "vote_value": vote.vote_value,
+            "voted_slot": u64(vote.voted_slot),
+            "bump": vote.bump,
+            "weight": u64(vote.weight),
+            "reason_hash": hex(&vote.reason_hash),
+        },
+    })
+}
@@ -0,0 +1,51 @@
+//! Canonical test vectors for fast voting clients written in other
+//! languages.
+//!
+//! Each fixture is generated from the program's own types and the Rust
+//! client's builders, so a TS or Python encoder that reproduces them byte
+//! for byte agrees with the program:
+//!
+//! - [`pdas`]: addresses with their seeds
+//! - [`scenarios`]: actions across the vote modes on threshold, quorum and
+//!   overflow boundaries, with their instructions, accounts and tallies
+//! - [`tally`]: the pure tally functions on edge inputs
+//! - [`instructions`]: vote_compact's packed encoding
+//!
+//! [`json`] describes how values are written. Instruction account lists are
+//! those of a program built without `event-cpi`.
+
+pub mod instructions;
+pub mod json;
+pub mod pdas;
+pub mod scenarios;
+pub mod tally;
+
+use anchor_lang::prelude::Pubkey;
+use serde_json::Value;
+
+pub const CREATOR: Pubkey = Pubkey::new_from_array([1; 32]);
+pub const VOTER: Pubkey = Pubkey::new_from_array([2; 32]);
+pub const PAYER: Pubkey = Pubkey::new_from_array([3; 32]);
+pub const MINT: Pubkey = Pubkey::new_from_array([4; 32]);
+pub const TOKEN_ACCOUNT: Pubkey = Pubkey::new_from_array([5; 32]);
+pub const HIVE: Pubkey = Pubkey::new_from_array([6; 32]);
+pub const COMMITMENT: [u8; 32] = [7; 32];
+pub const TAG: [u8; 8] = *b"trading\0";
+
+/// Every fixture, by file name
+pub fn fixtures() -> Vec<(&'static str, Value)> {
+    vec![
+        ("instructions.json", instructions::fixture()),
+        ("pdas.json", pdas::fixture()),
+        ("scenarios.json", scenarios::fixture()),
+        ("tally.json", tally::fixture()),
+    ]
+}
+
+/// A fixture as written to disk: pretty-printed, keys sorted, newline
+/// terminated
+pub fn render(fixture: &Value) -> String {
+    let mut text = serde_json::to_string_pretty(fixture).expect("fixtures serialize");
+    text.push('\n');
+    text
+}
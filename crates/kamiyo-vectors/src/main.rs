@@ -0,0 +1,53 @@
+//! Writes the fast voting test vectors as JSON fixtures, or checks that
+//! fixtures on disk are current. See README.md.
+
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Parser;
+
+type VectorsResult<T = ()> = Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(name = "kamiyo-vectors", version, about)]
+struct Cli {
+    /// Directory the fixtures are written to
+    #[arg(long, short = 'o', default_value = "vectors")]
+    out: PathBuf,
+    /// Compare with the fixtures in the directory instead of writing them
+    #[arg(long)]
+    check: bool,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Whether every fixture was written, or with `--check` matched
+fn run(cli: Cli) -> VectorsResult<bool> {
+    if !cli.check {
+        fs::create_dir_all(&cli.out)?;
+    }
+    let mut current = true;
+    for (name, fixture) in kamiyo_vectors::fixtures() {
+        let path = cli.out.join(name);
+        let text = kamiyo_vectors::render(&fixture);
+        if !cli.check {
+            fs::write(&path, text)?;
+            println!("wrote {}", path.display());
+        } else if fs::read_to_string(&path).ok().as_deref() != Some(text.as_str()) {
+            println!("stale: {}", path.display());
+            current = false;
+        }
+    }
+    Ok(current)
+}
@@ -0,0 +1,99 @@
+// Program-derived addresses from fixed inputs, with the seeds spelled out so
+// a client can check its derivation one seed at a time. Each is derived
+// here from the seeds and checked against the client's `pda` function, so
+// the two cannot drift apart.
+
+use anchor_lang::prelude::Pubkey;
+use kamiyo_fast_voting::{
+    MetricsLayer, ACTION_COUNTER_SEED, ACTION_MIRROR_SEED, ACTION_RELAYERS_SEED, AGENT_SEED, ANONYMOUS_BALLOTS_SEED,
+    BALLOT_VERIFIER_SEED, COMPRESSED_BALLOTS_SEED, CREATOR_ALLOWLIST_SEED, EPOCH_STATS_SEED, FAST_ACTION_SEED,
+    FAST_VOTE_SEED, GLOBAL_CONFIG_SEED, HIVE_FREEZE_SEED, HIVE_SEED, ID, METRICS_SEED, NULLIFIER_SEED,
+    RESULT_PROOF_SEED, ROLES_SEED, SELF_SUBMIT_SEED, STANDINGS_BOARD_SEED, TAG_INDEX_SEED, VOTE_CREDITS_SEED,
+    VOTE_KEY_SEED,
+};
+use kamiyo_hive_client::pda;
+use serde_json::{json, Value};
+
+use crate::json;
+use crate::{HIVE, PAYER, TAG, VOTER};
+
+fn derive(name: &str, seeds: &[&[u8]], client: (Pubkey, u8)) -> Value {
+    let (address, bump) = Pubkey::find_program_address(seeds, &ID);
+    assert_eq!((address, bump), client, "{name}: seeds disagree with the client");
+    json!({
+        "name": name,
+        "seeds": seeds.iter().map(|seed| json::hex(seed)).collect::<Value>(),
+        "address": json::key(&address),
+        "bump": bump,
+    })
+}
+
+pub fn fixture() -> Value {
+    let action = pda::fast_action(0).0;
+    let mut vectors = vec![
+        derive("global_config", &[GLOBAL_CONFIG_SEED], pda::global_config()),
+        derive("action_counter", &[ACTION_COUNTER_SEED], pda::action_counter()),
+        derive("event_authority", &[b"__event_authority"], pda::event_authority()),
+        derive("hive", &[HIVE_SEED, HIVE.as_ref()], pda::hive(&HIVE)),
+        derive("roles", &[ROLES_SEED, HIVE.as_ref()], pda::roles(&HIVE)),
+        derive("creator_allowlist", &[CREATOR_ALLOWLIST_SEED, HIVE.as_ref()], pda::creator_allowlist(&HIVE)),
+        derive("hive_freeze", &[HIVE_FREEZE_SEED, HIVE.as_ref()], pda::hive_freeze(&HIVE)),
+        derive("agent_record", &[AGENT_SEED, VOTER.as_ref()], pda::agent_record(&VOTER)),
+        derive("vote_credits", &[VOTE_CREDITS_SEED, VOTER.as_ref()], pda::vote_credits(&VOTER)),
+        derive("vote_key", &[VOTE_KEY_SEED, VOTER.as_ref()], pda::vote_key(&VOTER)),
+        derive("fast_vote", &[FAST_VOTE_SEED, action.as_ref(), VOTER.as_ref()], pda::fast_vote(&action, &VOTER)),
+        derive("compressed_ballots", &[COMPRESSED_BALLOTS_SEED, action.as_ref()], pda::compressed_ballots(&action)),
+        derive("anonymous_ballots", &[ANONYMOUS_BALLOTS_SEED, action.as_ref()], pda::anonymous_ballots(&action)),
+        derive("ballot_verifier", &[BALLOT_VERIFIER_SEED, PAYER.as_ref()], pda::ballot_verifier(&PAYER)),
+        derive("nullifier", &[NULLIFIER_SEED, action.as_ref(), &[0xff; 32]], pda::nullifier(&action, &[0xff; 32])),
+        derive("action_mirror", &[ACTION_MIRROR_SEED, action.as_ref()], pda::action_mirror(&action)),
+        derive("action_relayers", &[ACTION_RELAYERS_SEED, action.as_ref()], pda::action_relayers(&action)),
+        derive(
+            "self_submit",
+            &[SELF_SUBMIT_SEED, action.as_ref(), VOTER.as_ref()],
+            pda::self_submit(&action, &VOTER),
+        ),
+        derive("metrics_er", &[METRICS_SEED, &[MetricsLayer::Er as u8]], pda::metrics(MetricsLayer::Er)),
+        derive(
+            "hive_metrics_base",
+            &[METRICS_SEED, &[MetricsLayer::Base as u8], HIVE.as_ref()],
+            pda::hive_metrics(MetricsLayer::Base, &HIVE),
+        ),
+        derive(
+            "result_proof",
+            &[RESULT_PROOF_SEED, action.as_ref(), PAYER.as_ref()],
+            pda::result_proof(&action, &PAYER),
+        ),
+        derive("standings_board", &[STANDINGS_BOARD_SEED, HIVE.as_ref()], pda::standings_board(&HIVE)),
+        derive(
+            "tag_index",
+            &[TAG_INDEX_SEED, &[], &TAG, &0u32.to_le_bytes()],
+            pda::tag_index(None, &TAG, 0),
+        ),
+        derive(
+            "hive_tag_index",
+            &[TAG_INDEX_SEED, HIVE.as_ref(), &TAG, &u32::MAX.to_le_bytes()],
+            pda::tag_index(Some(&HIVE), &TAG, u32::MAX),
+        ),
+        derive(
+            "epoch_stats",
+            &[EPOCH_STATS_SEED, HIVE.as_ref(), &u64::MAX.to_le_bytes()],
+            pda::epoch_stats(&HIVE, u64::MAX),
+        ),
+    ];
+    // Action ids at the u64 edges, unscoped and hive-scoped
+    for id in [0, 1, u64::MAX] {
+        let le = id.to_le_bytes();
+        vectors.push(derive(&format!("fast_action_{id}"), &[FAST_ACTION_SEED, &le], pda::fast_action(id)));
+        vectors.push(derive(
+            &format!("hive_action_{id}"),
+            &[FAST_ACTION_SEED, HIVE.as_ref(), &le],
+            pda::hive_action(&HIVE, id),
+        ));
+    }
+
+    json!({
+        "program_id": json::key(&ID),
+        "pdas": vectors,
+    })
+}
@@ -0,0 +1,224 @@
+// Whole actions across the vote modes, each sitting on a threshold, quorum
+// or overflow boundary. A scenario carries the create, ballot and tally
+// instructions a client would send for it, the action as stored just before
+// the tally, one ballot account, and what the tally records: the result and
+// its digest, or the error it fails with.
+
+use anchor_lang::solana_program::instruction::Instruction;
+use kamiyo_fast_voting::{
+    ActionKind, ActionParams, AdaptiveQuorum, ApprovalRounding, FastAction, VoteMode, MIN_VOTES_FOR_QUORUM,
+    TOKEN_PROGRAM_ID, VOTING_WINDOW_SLOTS,
+};
+use kamiyo_hive_client::{instructions, pda};
+use kamiyo_test_utils::{fast_action, fast_vote};
+use serde_json::{json, Value};
+
+use crate::json;
+use crate::{COMMITMENT, CREATOR, MINT, PAYER, TOKEN_ACCOUNT, VOTER};
+
+/// Every scenario is tallied one slot past its deadline
+pub const TALLY_SLOT: u64 = VOTING_WINDOW_SLOTS + 1;
+
+struct Scenario {
+    name: &'static str,
+    action: FastAction,
+}
+
+fn scenario(name: &'static str, action: FastAction) -> Scenario {
+    Scenario { name, action }
+}
+
+/// An optimistic action over `electorate` members, reversed once vetoes
+/// exceed `veto_pct` percent of them
+fn optimistic(action_id: u64, veto_pct: u8, electorate: u32, vetoes: u32) -> FastAction {
+    let mut action = fast_action(action_id).creator(CREATOR).threshold(veto_pct).build();
+    action.vote_mode = VoteMode::Optimistic;
+    action.electorate = electorate;
+    action.votes_against = vetoes;
+    action.vote_count = vetoes;
+    action
+}
+
+/// A multi-option action, `vote_count` one ballot per unit of weight up to
+/// u32::MAX
+fn options(action_id: u64, threshold: u8, weights: &[u64]) -> FastAction {
+    let mut action = fast_action(action_id).creator(CREATOR).threshold(threshold).options(weights).build();
+    action.vote_count = weights.iter().fold(0u64, |n, w| n.saturating_add(*w)).min(u32::MAX as u64) as u32;
+    action
+}
+
+fn scenarios() -> Vec<Scenario> {
+    let headcount = |id| fast_action(id).creator(CREATOR);
+    vec![
+        scenario("headcount_at_threshold", headcount(0).threshold(50).votes(1, 1).build()),
+        scenario("headcount_below_threshold", headcount(1).threshold(51).votes(1, 1).build()),
+        scenario("headcount_unanimous", headcount(2).threshold(100).votes(3, 0).build()),
+        scenario("headcount_two_thirds_floored", headcount(24).threshold_bps(6667).votes(2, 1).build()),
+        scenario(
+            "headcount_two_thirds_half_even",
+            headcount(25).threshold_bps(6667).rounding(ApprovalRounding::HalfEven).votes(2, 1).build(),
+        ),
+        scenario(
+            "headcount_two_thirds_ceiling",
+            headcount(26).threshold_bps(6667).rounding(ApprovalRounding::Ceiling).votes(2, 1).build(),
+        ),
+        scenario("headcount_top_tier", headcount(3).tiers([60, 75, 90]).votes(9, 1).build()),
+        scenario("headcount_middle_tier", headcount(4).tiers([60, 75, 90]).votes(3, 1).build()),
+        scenario("headcount_quorum_short", headcount(5).votes(1, 0).build()),
+        scenario(
+            "adaptive_quorum_at_floor",
+            headcount(6)
+                .adaptive_quorum(AdaptiveQuorum { quorum: 10, floor: 2, grace_slots: 1 })
+                .votes(2, 0)
+                .build(),
+        ),
+        scenario(
+            "adaptive_quorum_short_in_grace",
+            headcount(7)
+                .adaptive_quorum(AdaptiveQuorum { quorum: 10, floor: 2, grace_slots: 4 })
+                .votes(7, 0)
+                .build(),
+        ),
+        scenario("turnout_met", headcount(8).turnout(5000, 10).votes(5, 0).build()),
+        scenario("turnout_short", headcount(9).turnout(5000, 10).votes(4, 0).build()),
+        scenario("headcount_u32_edge", headcount(u64::MAX).votes(u32::MAX - 1, 1).build()),
+        scenario("token_weighted_u64_tie", headcount(10).votes(1, 1).weights(u64::MAX, u64::MAX).build()),
+        scenario("token_weighted_dust_for", headcount(11).threshold(1).votes(1, 1).weights(1, u64::MAX).build()),
+        scenario("token_weighted_dust_against", headcount(12).threshold(100).votes(1, 1).weights(u64::MAX, 1).build()),
+        scenario("weight_quorum_met", headcount(21).votes(1, 1).weights(30, 20).weight_quorum(5000, 100).build()),
+        scenario("weight_quorum_short", headcount(22).votes(2, 1).weights(30, 19).weight_quorum(5000, 100).build()),
+        scenario("weight_quorum_no_snapshot", headcount(23).votes(1, 1).weights(30, 20).weight_quorum(5000, 0).build()),
+        scenario("optimistic_vetoes_at_limit", optimistic(13, 30, 10, 3)),
+        scenario("optimistic_vetoes_over_limit", optimistic(14, 30, 10, 4)),
+        scenario("options_at_threshold", options(15, 60, &[6, 4])),
+        scenario("options_tied", options(16, 0, &[5, 5, 0])),
+        scenario("options_below_threshold", options(17, 60, &[5, 4, 1])),
+        scenario("options_token_weighted_u64_edge", {
+            let mut action = options(18, 50, &[u64::MAX, u64::MAX - 1]);
+            action.vote_mode = VoteMode::TokenWeighted;
+            action.vote_count = 2;
+            action
+        }),
+        scenario("ranked_undecided", {
+            let mut action = options(19, 0, &[4, 3, 2]);
+            action.ranked = true;
+            action
+        }),
+        scenario("ranked_after_elimination", {
+            let mut action = fast_action(20).creator(CREATOR).threshold(0).options(&[4, 3, 2]).ranked(0b100).build();
+            action.vote_count = 9;
+            action
+        }),
+    ]
+}
+
+/// The params create_fast_action takes for `action`
+fn params(action: &FastAction) -> ActionParams {
+    let adaptive = action.quorum != MIN_VOTES_FOR_QUORUM || action.quorum_floor != MIN_VOTES_FOR_QUORUM;
+    ActionParams {
+        action_hash: action.action_hash,
+        threshold: action.threshold,
+        description_hash: action.description_hash,
+        vote_mode: action.vote_mode,
+        tier_thresholds: action.tier_thresholds,
+        dependencies: Vec::new(),
+        adaptive_quorum: adaptive.then_some(AdaptiveQuorum {
+            quorum: action.quorum,
+            floor: action.quorum_floor,
+            grace_slots: action.quorum_grace_slots,
+        }),
+        min_turnout_bps: action.min_turnout_bps,
+        option_count: action.option_count,
+        ranked: action.ranked,
+        market_gate: None,
+        kind: ActionKind::Standard,
+        weight_cap: None,
+        class_limits: Vec::new(),
+        audited: false,
+        early_vote_bonus: false,
+        exclusions: Vec::new(),
+        deadline_extension: None,
+        identity_weighted: false,
+        member_collection: None,
+        lock_stake: false,
+        priority: action.priority,
+        tags: Vec::new(),
+        quorum_weight_bps: action.quorum_weight_bps,
+        privacy: action.privacy,
+        threshold_bps: action.threshold_bps,
+    }
+}
+
+/// A ballot of the kind the action takes, from VOTER
+fn ballot(action: &FastAction) -> Instruction {
+    let id = action.action_id;
+    let weighted = action.vote_mode == VoteMode::TokenWeighted;
+    if action.ranked {
+        instructions::vote_ranked(VOTER, id, 0, (0..action.option_count).collect(), COMMITMENT)
+    } else if action.option_count > 0 {
+        let mut weights = vec![0; action.option_count as usize];
+        weights[0] = 1;
+        let weight_accounts = weighted.then_some((MINT, TOKEN_ACCOUNT, TOKEN_PROGRAM_ID));
+        instructions::vote_options(VOTER, id, 0, weights, COMMITMENT, weight_accounts)
+    } else if action.quorum_weight_bps > 0 {
+        // Weight quorum actions only take snapshot ballots; a lone leaf
+        // proves itself
+        instructions::vote_fast_snapshot(VOTER, id, 0, true, COMMITMENT, 1, Vec::new())
+    } else if weighted {
+        // Live balances lock through the token program itself, frozen by
+        // the lock authority
+        instructions::vote_fast_weighted(VOTER, id, 0, true, COMMITMENT, (MINT, TOKEN_ACCOUNT), TOKEN_PROGRAM_ID)
+    } else {
+        // An optimistic ballot can only veto
+        instructions::vote_fast(VOTER, id, 0, action.vote_mode != VoteMode::Optimistic, COMMITMENT)
+    }
+}
+
+fn fixture_of(scenario: &Scenario) -> Value {
+    let action = &scenario.action;
+    let (address, _) = pda::fast_action(action.action_id);
+    let (vote_address, _) = pda::fast_vote(&address, &VOTER);
+    let vote = fast_vote(address, VOTER)
+        .value(action.vote_mode != VoteMode::Optimistic)
+        .commitment(COMMITMENT)
+        .voted_slot(action.created_slot)
+        .build();
+    let params = params(action);
+    let create = instructions::create_fast_action(CREATOR, action.action_id, params.clone(), None, None, None);
+    let tally = instructions::tally_and_commit(PAYER, action.action_id, None, &[], false, None);
+
+    let expected = match action.tallied_result(TALLY_SLOT) {
+        Ok(result) => json!({
+            "result": json::result(&result),
+            "result_digest": json::hex(&kamiyo_fast_voting::tally::result_digest(
+                action.action_id,
+                &action.action_hash,
+                action.votes_for,
+                action.votes_against,
+                &result,
+                TALLY_SLOT,
+            )),
+        }),
+        Err(err) => json!({ "error": json::error_name(&err) }),
+    };
+
+    json!({
+        "name": scenario.name,
+        "params": json::params(&params),
+        "instructions": {
+            "create": json::instruction(&create),
+            "ballot": json::instruction(&ballot(action)),
+            "tally": json::instruction(&tally),
+        },
+        "action": json::action(&address, action),
+        "vote": json::vote(&vote_address, &vote),
+        "expected": expected,
+    })
+}
+
+pub fn fixture() -> Value {
+    json!({
+        "tally_slot": json::u64(TALLY_SLOT),
+        "scenarios": scenarios().iter().map(fixture_of).collect::<Value>(),
+    })
+}
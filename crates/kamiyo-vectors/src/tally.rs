@@ -0,0 +1,435 @@
+// The pure tally functions on boundary and overflow inputs. Clients that
+// preview results or verify digests off-chain reimplement these, and the
+// u64 and u32 edges are where a port to doubles or 32-bit arithmetic goes
+// wrong first.
+
+use anchor_lang::prelude::Pubkey;
+use kamiyo_fast_voting::tally;
+use kamiyo_fast_voting::{ApprovalRounding, ProofSignature, ResultProof, VoteResult, MAX_EXTRA_TIERS};
+use kamiyo_hive_client::pda;
+use serde_json::{json, Value};
+
+use crate::json;
+use crate::HIVE;
+
+const TIERS: [u8; MAX_EXTRA_TIERS] = [60, 75, 90];
+const NO_TIERS: [u8; MAX_EXTRA_TIERS] = [0; MAX_EXTRA_TIERS];
+const ROUNDINGS: [ApprovalRounding; 3] =
+    [ApprovalRounding::Floor, ApprovalRounding::HalfEven, ApprovalRounding::Ceiling];
+
+fn approval_pct() -> Value {
+    let cases = [
+        (0, 0),
+        (1, 0),
+        (0, 1),
+        (1, 2),
+        (2, 1),
+        (1, 1),
+        (u64::MAX, 0),
+        (u64::MAX, u64::MAX),
+        (u64::MAX, 1),
+        (1, u64::MAX),
+    ];
+    cases
+        .iter()
+        .map(|(tally_for, tally_against)| {
+            json!({
+                "tally_for": json::u64(*tally_for),
+                "tally_against": json::u64(*tally_against),
+                "expected": json::outcome(tally::approval_pct(*tally_for, *tally_against), json::u64),
+            })
+        })
+        .collect()
+}
+
+/// Each case under every rounding: thirds, exact halves of a basis point
+/// with an odd and an even floor, and the u64 edges
+fn approval_bps() -> Value {
+    let cases = [
+        (0, 0),
+        (1, 0),
+        (0, 1),
+        (1, 2),
+        (2, 1),
+        (1, 1),
+        (1, 19_999),
+        (3, 19_997),
+        (2, 19_999),
+        (19_999, 1),
+        (u64::MAX, 0),
+        (u64::MAX, u64::MAX),
+        (u64::MAX, 1),
+        (1, u64::MAX),
+    ];
+    cases
+        .iter()
+        .flat_map(|(tally_for, tally_against)| {
+            ROUNDINGS.iter().map(move |rounding| {
+                json!({
+                    "tally_for": json::u64(*tally_for),
+                    "tally_against": json::u64(*tally_against),
+                    "rounding": format!("{rounding:?}"),
+                    "expected": json::outcome(tally::approval_bps(*tally_for, *tally_against, *rounding), json::u64),
+                })
+            })
+        })
+        .collect()
+}
+
+fn validate_threshold_bps() -> Value {
+    let cases = [(67, 0), (67, 6667), (67, 6601), (67, 6600), (67, 6701), (100, 10_000), (1, 1), (1, 101)];
+    cases
+        .iter()
+        .map(|(threshold, threshold_bps)| {
+            json!({
+                "threshold": threshold,
+                "threshold_bps": threshold_bps,
+                "expected": json::outcome(tally::validate_threshold_bps(*threshold, *threshold_bps), |b| json!(b)),
+            })
+        })
+        .collect()
+}
+
+fn grade() -> Value {
+    let cases = [
+        (0, 0, NO_TIERS),
+        (4_999, 5_000, NO_TIERS),
+        (5_000, 5_000, NO_TIERS),
+        (10_000, 10_000, NO_TIERS),
+        (9_999, 10_000, NO_TIERS),
+        (6_666, 6_667, NO_TIERS),
+        (6_667, 6_667, NO_TIERS),
+        (5_999, 5_000, TIERS),
+        (6_000, 5_000, TIERS),
+        (7_499, 5_000, TIERS),
+        (7_500, 5_000, TIERS),
+        (9_000, 5_000, TIERS),
+        (10_000, 5_000, [60, 0, 0]),
+        (4_999, 5_000, TIERS),
+    ];
+    cases
+        .iter()
+        .map(|(bps, threshold_bps, tiers)| {
+            json!({
+                "approval_bps": json::u64(*bps),
+                "threshold_bps": threshold_bps,
+                "tier_thresholds": tiers,
+                "expected": json::result(&tally::grade(*bps, *threshold_bps, tiers)),
+            })
+        })
+        .collect()
+}
+
+fn validate_tiers() -> Value {
+    let cases = [
+        (50, TIERS),
+        (50, NO_TIERS),
+        (50, [50, 0, 0]),
+        (50, [60, 60, 0]),
+        (50, [60, 0, 70]),
+        (50, [75, 60, 0]),
+        (99, [100, 0, 0]),
+        (99, [101, 0, 0]),
+    ];
+    cases
+        .iter()
+        .map(|(threshold, tiers)| {
+            json!({
+                "threshold": threshold,
+                "tier_thresholds": tiers,
+                "expected": json::outcome(tally::validate_tiers(*threshold, tiers), |()| Value::Null),
+            })
+        })
+        .collect()
+}
+
+fn required_quorum() -> Value {
+    let cases = [
+        (10, 2, 0, 75, 75),
+        (10, 2, 100, 75, 0),
+        (10, 2, 100, 75, 75),
+        (10, 2, 100, 75, 125),
+        (10, 2, 100, 75, 174),
+        (10, 2, 100, 75, 175),
+        (10, 10, 100, 75, 125),
+        (u32::MAX, 0, u64::MAX, 0, u64::MAX - 1),
+        (u32::MAX, 0, u64::MAX, u64::MAX, u64::MAX),
+        (u32::MAX, 1, 3, 0, 1),
+    ];
+    cases
+        .iter()
+        .map(|(quorum, floor, grace_slots, deadline_slot, slot)| {
+            json!({
+                "quorum": quorum,
+                "floor": floor,
+                "grace_slots": json::u64(*grace_slots),
+                "deadline_slot": json::u64(*deadline_slot),
+                "slot": json::u64(*slot),
+                "expected": tally::required_quorum(*quorum, *floor, *grace_slots, *deadline_slot, *slot),
+            })
+        })
+        .collect()
+}
+
+fn turnout_met() -> Value {
+    let cases = [
+        (5, 10, 5000),
+        (4, 10, 5000),
+        (0, 0, 10_000),
+        (0, 10, 0),
+        (1, 3, 3334),
+        (1, 3, 3333),
+        (u32::MAX, u32::MAX, 10_000),
+        (u32::MAX - 1, u32::MAX, 10_000),
+        (1, u32::MAX, u16::MAX),
+    ];
+    cases
+        .iter()
+        .map(|(votes, electorate, min_turnout_bps)| {
+            json!({
+                "votes": votes,
+                "electorate": electorate,
+                "min_turnout_bps": min_turnout_bps,
+                "expected": tally::turnout_met(*votes, *electorate, *min_turnout_bps),
+            })
+        })
+        .collect()
+}
+
+fn weight_quorum_met() -> Value {
+    let cases = [
+        (30, 20, 100, 5000),
+        (30, 19, 100, 5000),
+        (0, 0, 0, 10_000),
+        (0, 0, 100, 0),
+        (1, 0, 3, 3334),
+        (1, 0, 3, 3333),
+        (u64::MAX, u64::MAX, u64::MAX, 10_000),
+        (u64::MAX - 1, 0, u64::MAX, 10_000),
+        (1, 0, u64::MAX, u16::MAX),
+    ];
+    cases
+        .iter()
+        .map(|(weight_for, weight_against, total_weight, quorum_weight_bps)| {
+            json!({
+                "weight_for": json::u64(*weight_for),
+                "weight_against": json::u64(*weight_against),
+                "total_weight": json::u64(*total_weight),
+                "quorum_weight_bps": quorum_weight_bps,
+                "expected": tally::weight_quorum_met(*weight_for, *weight_against, *total_weight, *quorum_weight_bps),
+            })
+        })
+        .collect()
+}
+
+fn optimistic_result() -> Value {
+    let cases = [
+        (3, 10, 30),
+        (4, 10, 30),
+        (0, 0, 0),
+        (1, 0, 0),
+        (0, 10, 0),
+        (u32::MAX, u32::MAX, 100),
+        (u32::MAX, u32::MAX, 99),
+        (u32::MAX, u32::MAX, u8::MAX),
+    ];
+    cases
+        .iter()
+        .map(|(vetoes, electorate, veto_pct)| {
+            json!({
+                "vetoes": vetoes,
+                "electorate": electorate,
+                "veto_pct": veto_pct,
+                "expected": json::result(&tally::optimistic_result(*vetoes, *electorate, *veto_pct)),
+            })
+        })
+        .collect()
+}
+
+fn choose_option() -> Value {
+    let cases: [(&[u64], u16, ApprovalRounding); 12] = [
+        (&[], 0, ApprovalRounding::Floor),
+        (&[0, 0], 0, ApprovalRounding::Floor),
+        (&[6, 4], 6_000, ApprovalRounding::Floor),
+        (&[6, 4], 6_100, ApprovalRounding::Floor),
+        (&[5, 5], 0, ApprovalRounding::Floor),
+        (&[5, 4, 1], 6_000, ApprovalRounding::Floor),
+        (&[2, 1], 6_667, ApprovalRounding::Floor),
+        (&[2, 1], 6_667, ApprovalRounding::HalfEven),
+        (&[2, 1], 6_667, ApprovalRounding::Ceiling),
+        (&[0, 0, 0, 0, 0, 0, 0, 1], 10_000, ApprovalRounding::Floor),
+        (&[u64::MAX, u64::MAX - 1], 5_000, ApprovalRounding::Floor),
+        (&[u64::MAX; 8], 0, ApprovalRounding::Floor),
+    ];
+    cases
+        .iter()
+        .map(|(weights, threshold_bps, rounding)| {
+            json!({
+                "option_weights": json::u64s(weights),
+                "threshold_bps": threshold_bps,
+                "rounding": format!("{rounding:?}"),
+                "expected": json::outcome(tally::choose_option(weights, *threshold_bps, *rounding), |r| {
+                    json::result(&r)
+                }),
+            })
+        })
+        .collect()
+}
+
+fn top_two() -> Value {
+    let cases: [&[u64]; 5] = [&[3, 5, 5], &[0, 4, 0], &[], &[1, 1, 1], &[u64::MAX, 0, u64::MAX - 1]];
+    cases
+        .iter()
+        .map(|weights| {
+            json!({
+                "option_weights": json::u64s(weights),
+                "expected": tally::top_two(weights).map(|(first, second)| json!([first, second])),
+            })
+        })
+        .collect()
+}
+
+fn runoff() -> Value {
+    let cases: [(&[u64], u8); 8] = [
+        (&[4, 3, 2], 0),
+        (&[4, 3, 2], 0b100),
+        (&[4, 3, 3], 0),
+        (&[5, 3, 2], 0),
+        (&[0, 1], 0b10),
+        (&[1, 1], 0),
+        (&[u64::MAX, u64::MAX, 1], 0b100),
+        (&[u64::MAX, u64::MAX - 1], 0),
+    ];
+    cases
+        .iter()
+        .map(|(weights, eliminated_mask)| {
+            json!({
+                "option_weights": json::u64s(weights),
+                "eliminated_mask": eliminated_mask,
+                "runoff_winner": tally::runoff_winner(weights, *eliminated_mask),
+                "weakest_option": tally::weakest_option(weights, *eliminated_mask),
+            })
+        })
+        .collect()
+}
+
+fn result_encoding() -> Value {
+    let results = [
+        VoteResult::Pending,
+        VoteResult::Passed { tier: 0 },
+        VoteResult::Passed { tier: 3 },
+        VoteResult::Failed,
+        VoteResult::Cancelled,
+        VoteResult::Expired,
+        VoteResult::Voided,
+        VoteResult::Chosen { option: 0 },
+        VoteResult::Chosen { option: 7 },
+        VoteResult::Vetoed,
+        VoteResult::Contested { tier: 1 },
+    ];
+    results
+        .iter()
+        .map(|result| {
+            let mut borsh = Vec::new();
+            anchor_lang::AnchorSerialize::serialize(result, &mut borsh).expect("VoteResult serializes");
+            json!({
+                "result": json::result(result),
+                "encode": json::hex(&result.encode()),
+                "borsh": json::hex(&borsh),
+            })
+        })
+        .collect()
+}
+
+fn result_digest() -> Value {
+    let cases = [
+        (0, [0; 32], 0, 0, VoteResult::Failed, 0),
+        (7, [1; 32], 2, 0, VoteResult::Passed { tier: 0 }, 76),
+        (7, [1; 32], 9, 1, VoteResult::Passed { tier: 3 }, 76),
+        (42, [0xab; 32], 3, 3, VoteResult::Chosen { option: 2 }, 1_000_000),
+        (u64::MAX, [0xff; 32], u32::MAX, u32::MAX, VoteResult::Vetoed, u64::MAX),
+    ];
+    cases
+        .iter()
+        .map(|(action_id, action_hash, votes_for, votes_against, result, finalized_slot)| {
+            json!({
+                "action_id": json::u64(*action_id),
+                "action_hash": json::hex(action_hash),
+                "votes_for": votes_for,
+                "votes_against": votes_against,
+                "result": json::result(result),
+                "finalized_slot": json::u64(*finalized_slot),
+                "expected": json::hex(&tally::result_digest(
+                    *action_id,
+                    action_hash,
+                    *votes_for,
+                    *votes_against,
+                    result,
+                    *finalized_slot,
+                )),
+            })
+        })
+        .collect()
+}
+
+/// Verifiers off Solana parse the encoding by offset, so one proof with no
+/// signatures and one with two pin the layout down
+fn result_proof() -> Value {
+    let action = pda::fast_action(7).0;
+    let result = VoteResult::Passed { tier: 1 };
+    let proof = ResultProof {
+        program_id: kamiyo_fast_voting::ID,
+        action,
+        action_id: 7,
+        action_hash: [1; 32],
+        hive: HIVE,
+        votes_for: 9,
+        votes_against: 1,
+        weight_for: u64::MAX,
+        weight_against: 0,
+        result: result.clone(),
+        finalized_slot: 76,
+        result_digest: tally::result_digest(7, &[1; 32], 9, 1, &result, 76),
+        account_hash: [0xab; 32],
+        signatures: Vec::new(),
+    };
+    let signed = ResultProof {
+        signatures: vec![
+            ProofSignature { validator: Pubkey::new_from_array([8; 32]), signature: [0x11; 64] },
+            ProofSignature { validator: Pubkey::new_from_array([9; 32]), signature: [0x22; 64] },
+        ],
+        ..proof.clone()
+    };
+    [proof, signed]
+        .iter()
+        .map(|proof| {
+            json!({
+                "action": json::key(&proof.action),
+                "signatures": proof.signatures.len(),
+                "digest_matches": proof.digest_matches(),
+                "attestation_digest": json::hex(&proof.attestation_digest()),
+                "encode": json::hex(&proof.encode()),
+            })
+        })
+        .collect()
+}
+
+pub fn fixture() -> Value {
+    json!({
+        "approval_pct": approval_pct(),
+        "approval_bps": approval_bps(),
+        "validate_threshold_bps": validate_threshold_bps(),
+        "grade": grade(),
+        "validate_tiers": validate_tiers(),
+        "required_quorum": required_quorum(),
+        "turnout_met": turnout_met(),
+        "weight_quorum_met": weight_quorum_met(),
+        "optimistic_result": optimistic_result(),
+        "choose_option": choose_option(),
+        "top_two": top_two(),
+        "runoff": runoff(),
+        "result_encoding": result_encoding(),
+        "result_digest": result_digest(),
+        "result_proof": result_proof(),
+    })
+}
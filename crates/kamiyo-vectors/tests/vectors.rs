@@ -0,0 +1,22 @@
+// The checked-in fixtures must be what the current program and client
+// generate; regenerate with `cargo run -p kamiyo-vectors -- -o
+// crates/kamiyo-vectors/vectors` after a layout or encoding change.
+
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn checked_in_fixtures_are_current() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("vectors");
+    for (name, fixture) in kamiyo_vectors::fixtures() {
+        let on_disk = fs::read_to_string(dir.join(name)).unwrap_or_default();
+        assert!(on_disk == kamiyo_vectors::render(&fixture), "{name} is stale; regenerate it");
+    }
+}
+
+#[test]
+fn fixtures_are_deterministic() {
+    let first: Vec<String> = kamiyo_vectors::fixtures().iter().map(|(_, f)| kamiyo_vectors::render(f)).collect();
+    let second: Vec<String> = kamiyo_vectors::fixtures().iter().map(|(_, f)| kamiyo_vectors::render(f)).collect();
+    assert_eq!(first, second);
+}
@@ -0,0 +1,77 @@
+//! CPI interface to the KAMIYO fast voting program, for programs that need
+//! to know whether an action passed.
+//!
+//! [`assert_action_passed`] invokes the program's `assert_action_passed`
+//! instruction, which fails unless the action was finalized as Passed, and
+//! decodes the [`ActionPassed`] it returns. Nothing here reads FastAction,
+//! so callers are unaffected by changes to its layout, and the crate does
+//! not pull in Anchor or the program itself.
+//!
+//! ```ignore
+//! let passed = kamiyo_voting_interface::assert_action_passed(&voting_program, &action)?;
+//! if !passed.settled {
+//!     return Err(MyError::NotFinal.into());
+//! }
+//! ```
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::account_info::AccountInfo;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program::{get_return_data, invoke};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey;
+use solana_program::pubkey::Pubkey;
+
+/// The fast voting program
+pub const ID: Pubkey = pubkey!("AakwnBstczs5KC2jKPfBuFLQZADXrx4oPH8FtJbhPxwA");
+
+/// Anchor discriminator of `assert_action_passed`:
+/// sha256("global:assert_action_passed")[..8]
+pub const ASSERT_ACTION_PASSED_DISCRIMINATOR: [u8; 8] = [95, 16, 248, 124, 251, 146, 189, 209];
+
+/// What `assert_action_passed` returns for a passed action
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ActionPassed {
+    pub action: Pubkey,
+    pub action_id: u64,
+    pub action_hash: [u8; 32],
+    /// Default for actions outside a hive
+    pub hive: Pubkey,
+    /// Graded tiers met above the base threshold (0 = plain pass)
+    pub tier: u8,
+    pub finalized_slot: u64,
+    pub result_digest: [u8; 32],
+    /// Past the dispute window with any market gate cleared. Until then a
+    /// challenge or veto can still overturn the result, so callers acting on
+    /// it irreversibly should require this.
+    pub settled: bool,
+}
+
+/// `assert_action_passed` for the FastAction at `action`
+pub fn assert_action_passed_ix(action: Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: vec![AccountMeta::new_readonly(action, false)],
+        data: ASSERT_ACTION_PASSED_DISCRIMINATOR.to_vec(),
+    }
+}
+
+/// Invokes `assert_action_passed` on `action`. The call, and so the caller's
+/// instruction, fails unless the action passed; the program checks the
+/// account is a FastAction at its own address. `program` must be the fast
+/// voting program's account.
+pub fn assert_action_passed<'a>(
+    program: &AccountInfo<'a>,
+    action: &AccountInfo<'a>,
+) -> Result<ActionPassed, ProgramError> {
+    if *program.key != ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    invoke(&assert_action_passed_ix(*action.key), &[action.clone(), program.clone()])?;
+    match get_return_data() {
+        Some((program_id, data)) if program_id == ID => {
+            ActionPassed::try_from_slice(&data).map_err(|_| ProgramError::InvalidAccountData)
+        }
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
@@ -0,0 +1,50 @@
+// The interface's copies of the program's ID, discriminator and return type
+// must stay in step with the program.
+
+use anchor_lang::{AnchorSerialize, Discriminator};
+use borsh::BorshDeserialize;
+use kamiyo_voting_interface::{ActionPassed, ASSERT_ACTION_PASSED_DISCRIMINATOR, ID};
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn program_id_matches() {
+    assert_eq!(ID.to_bytes(), kamiyo_fast_voting::ID.to_bytes());
+}
+
+#[test]
+fn discriminator_matches() {
+    assert_eq!(
+        &ASSERT_ACTION_PASSED_DISCRIMINATOR[..],
+        kamiyo_fast_voting::instruction::AssertActionPassed::DISCRIMINATOR
+    );
+}
+
+#[test]
+fn return_data_decodes() {
+    let returned = kamiyo_fast_voting::ActionPassed {
+        action: kamiyo_fast_voting::ID,
+        action_id: 42,
+        action_hash: [7; 32],
+        hive: Default::default(),
+        tier: 2,
+        finalized_slot: 1_000,
+        result_digest: [9; 32],
+        settled: true,
+    };
+    let data = returned.try_to_vec().unwrap();
+
+    let decoded = ActionPassed::try_from_slice(&data).unwrap();
+    assert_eq!(
+        decoded,
+        ActionPassed {
+            action: ID,
+            action_id: 42,
+            action_hash: [7; 32],
+            hive: Pubkey::default(),
+            tier: 2,
+            finalized_slot: 1_000,
+            result_digest: [9; 32],
+            settled: true,
+        }
+    );
+}
@@ -0,0 +1,231 @@
+// KAMIYO Escrow - agent agreements settled by a fast vote
+//
+// Two agents lock lamports against a FastAction: if the action passes, the
+// escrow pays out to party A; if it fails or expires, to party B. The
+// outcome is read from the action account itself, so the fast voting
+// program needs no knowledge of escrows and settlement is permissionless.
+//
+// An action is final for an escrow once a tallied result is past the
+// dispute window, or once it was marked Expired. The expiry sweep closes
+// expired actions, so an action account found closed also settles as
+// Expired: it was a live FastAction when the escrow opened, and nothing else
+// closes one. Cancelled, voided and vetoed actions decide nothing, and both
+// parties get their deposits back. Escrows are opened on the base layer, and
+// settle only once the action is back there.
+
+// anchor 0.31 IDL handlers still call AccountInfo::realloc
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use kamiyo_hive_interface::{FastAction, VoteResult, DISPUTE_WINDOW_SLOTS, FAST_ACTION_VERSION};
+
+declare_id!("C1a5LCERdA2epNa8xBWNpNnssKQBrzXdNCP5uXiVPgjb");
+
+pub const ESCROW_SEED: &[u8] = b"escrow";
+
+#[program]
+pub mod kamiyo_escrow {
+    use super::*;
+
+    /// Lock both parties' deposits against an undecided binary action; both
+    /// must sign. Party A pays the escrow's rent and gets it back on
+    /// settlement
+    pub fn open_escrow(ctx: Context<OpenEscrow>, amount_a: u64, amount_b: u64) -> Result<()> {
+        require!(amount_a.checked_add(amount_b).is_some_and(|total| total > 0), EscrowError::InvalidAmount);
+        require_keys_neq!(ctx.accounts.party_a.key(), ctx.accounts.party_b.key(), EscrowError::InvalidParties);
+
+        let info = &ctx.accounts.fast_action;
+        require_keys_eq!(*info.owner, kamiyo_hive_interface::ID, EscrowError::InvalidAction);
+        let action = FastAction::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(action.version == FAST_ACTION_VERSION, EscrowError::InvalidAction);
+        require!(action.option_count == 0, EscrowError::InvalidAction);
+        require!(!action.executed, EscrowError::ActionAlreadyFinal);
+
+        for (party, amount) in [(&ctx.accounts.party_a, amount_a), (&ctx.accounts.party_b, amount_b)] {
+            if amount == 0 {
+                continue;
+            }
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: party.to_account_info(),
+                        to: ctx.accounts.escrow.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.fast_action = info.key();
+        escrow.party_a = ctx.accounts.party_a.key();
+        escrow.party_b = ctx.accounts.party_b.key();
+        escrow.amount_a = amount_a;
+        escrow.amount_b = amount_b;
+        escrow.opened_slot = Clock::get()?.slot;
+        escrow.bump = ctx.bumps.escrow;
+
+        emit!(EscrowOpened {
+            escrow: escrow.key(),
+            action: escrow.fast_action,
+            action_id: action.action_id,
+            party_a: escrow.party_a,
+            party_b: escrow.party_b,
+            amount_a,
+            amount_b,
+        });
+
+        Ok(())
+    }
+
+    /// Pay out by the action's outcome and close the escrow; permissionless
+    pub fn settle_escrow(ctx: Context<SettleEscrow>) -> Result<()> {
+        let outcome = outcome(&ctx.accounts.fast_action, Clock::get()?.slot)?;
+
+        let escrow = &ctx.accounts.escrow;
+        let total = escrow.amount_a + escrow.amount_b;
+        let (to_a, to_b) = match outcome {
+            EscrowOutcome::Passed => (total, 0),
+            EscrowOutcome::Failed | EscrowOutcome::Expired => (0, total),
+            EscrowOutcome::Refunded => (escrow.amount_a, escrow.amount_b),
+        };
+        **escrow.to_account_info().try_borrow_mut_lamports()? -= total;
+        **ctx.accounts.party_a.try_borrow_mut_lamports()? += to_a;
+        **ctx.accounts.party_b.try_borrow_mut_lamports()? += to_b;
+
+        emit!(EscrowSettled {
+            escrow: escrow.key(),
+            action: escrow.fast_action,
+            outcome,
+            to_a,
+            to_b,
+        });
+
+        Ok(())
+    }
+}
+
+/// How `info`, the escrow's action, settles it at `slot`
+fn outcome(info: &AccountInfo, slot: u64) -> Result<EscrowOutcome> {
+    if info.data_is_empty() && *info.owner == system_program::ID {
+        return Ok(EscrowOutcome::Expired);
+    }
+    // Delegated actions are owned by the delegation program until committed
+    require_keys_eq!(*info.owner, kamiyo_hive_interface::ID, EscrowError::ActionNotFinal);
+    let action = FastAction::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    require!(action.executed, EscrowError::ActionNotFinal);
+
+    let settled = slot > action.finalized_slot.saturating_add(DISPUTE_WINDOW_SLOTS);
+    match action.result {
+        VoteResult::Expired => Ok(EscrowOutcome::Expired),
+        VoteResult::Passed { .. } if settled => Ok(EscrowOutcome::Passed),
+        VoteResult::Failed if settled => Ok(EscrowOutcome::Failed),
+        VoteResult::Cancelled | VoteResult::Voided | VoteResult::Vetoed => Ok(EscrowOutcome::Refunded),
+        _ => err!(EscrowError::ActionNotFinal),
+    }
+}
+
+#[account]
+pub struct Escrow {
+    pub fast_action: Pubkey, // 32
+    /// Paid if the action passes
+    pub party_a: Pubkey,     // 32
+    /// Paid if it fails or expires
+    pub party_b: Pubkey,     // 32
+    pub amount_a: u64,       // 8
+    pub amount_b: u64,       // 8
+    pub opened_slot: u64,    // 8
+    pub bump: u8,            // 1
+}
+
+impl Escrow {
+    pub const LEN: usize = 129; // 8 disc + 121 fields
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EscrowOutcome {
+    Passed,
+    Failed,
+    Expired,
+    /// Cancelled, voided or vetoed; each party gets its deposit back
+    Refunded,
+}
+
+#[event]
+pub struct EscrowOpened {
+    pub escrow: Pubkey,
+    pub action: Pubkey,
+    pub action_id: u64,
+    pub party_a: Pubkey,
+    pub party_b: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+#[event]
+pub struct EscrowSettled {
+    pub escrow: Pubkey,
+    pub action: Pubkey,
+    pub outcome: EscrowOutcome,
+    pub to_a: u64,
+    pub to_b: u64,
+}
+
+#[error_code]
+pub enum EscrowError {
+    #[msg("Deposits must be non-zero in total")]
+    InvalidAmount,
+    #[msg("Escrow parties must differ")]
+    InvalidParties,
+    #[msg("Not a current binary fast voting action")]
+    InvalidAction,
+    #[msg("Action is already final")]
+    ActionAlreadyFinal,
+    #[msg("Action is not final, or still in its dispute window")]
+    ActionNotFinal,
+}
+
+#[derive(Accounts)]
+pub struct OpenEscrow<'info> {
+    #[account(
+        init,
+        payer = party_a,
+        space = Escrow::LEN,
+        seeds = [ESCROW_SEED, fast_action.key().as_ref(), party_a.key().as_ref(), party_b.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+    /// CHECK: Must be an undecided FastAction owned by the fast voting
+    /// program; checked in open_escrow
+    pub fast_action: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub party_a: Signer<'info>,
+    #[account(mut)]
+    pub party_b: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, escrow.fast_action.as_ref(), escrow.party_a.as_ref(), escrow.party_b.as_ref()],
+        bump = escrow.bump,
+        has_one = fast_action,
+        has_one = party_a,
+        has_one = party_b,
+        close = party_a
+    )]
+    pub escrow: Account<'info, Escrow>,
+    /// CHECK: The escrow's action, possibly closed by the expiry sweep; read
+    /// in settle_escrow
+    pub fast_action: UncheckedAccount<'info>,
+    /// CHECK: The escrow's party A; receives the rent
+    #[account(mut)]
+    pub party_a: UncheckedAccount<'info>,
+    /// CHECK: The escrow's party B
+    #[account(mut)]
+    pub party_b: UncheckedAccount<'info>,
+}
@@ -0,0 +1,139 @@
+// Member activity. A hive's electorate is its Roles members, so members who
+// stopped taking part still count against percentage-based quorums and
+// turnout. Each member may keep a MemberActivity marker on the base layer,
+// refreshed by member_heartbeat. Once a marker is INACTIVITY_EPOCHS old,
+// anyone may mark the member inactive, leaving it out of the electorate of
+// actions created from then on, until its next heartbeat reactivates it.
+// Inactive members keep their roles.
+//
+// Ballots are cast on the rollup and cannot touch base-layer markers, so
+// voting alone does not count as activity. A member without a marker cannot
+// be marked inactive; anyone may start one with track_member_activity, which
+// sets the clock running from the current epoch.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    FastVoteError, MemberActivity, MemberHeartbeatRecorded, MemberMarkedInactive, Roles, INACTIVE_MASK,
+    INACTIVITY_EPOCHS, MEMBER_ACTIVITY_SEED, ROLES_SEED,
+};
+
+fn require_member(roles: &Roles, member: &Pubkey) -> Result<()> {
+    require!(roles.members.iter().any(|m| m.member == *member), FastVoteError::NotHiveMember);
+    Ok(())
+}
+
+/// Permissionless; `payer` covers the marker
+pub fn process_track_member_activity(ctx: Context<TrackMemberActivity>, member: Pubkey) -> Result<()> {
+    require_member(&ctx.accounts.roles, &member)?;
+
+    let activity = &mut ctx.accounts.member_activity;
+    activity.hive = ctx.accounts.roles.hive;
+    activity.member = member;
+    activity.last_active_epoch = Clock::get()?.epoch;
+    activity.bump = ctx.bumps.member_activity;
+    Ok(())
+}
+
+pub fn process_member_heartbeat(ctx: Context<MemberHeartbeat>) -> Result<()> {
+    let member = ctx.accounts.member.key();
+    let epoch = Clock::get()?.epoch;
+
+    let roles = &mut ctx.accounts.roles;
+    let entry = roles
+        .members
+        .iter_mut()
+        .find(|m| m.member == member)
+        .ok_or(FastVoteError::NotHiveMember)?;
+    let reactivated = !entry.is_active();
+    entry.roles &= !INACTIVE_MASK;
+
+    let activity = &mut ctx.accounts.member_activity;
+    activity.hive = roles.hive;
+    activity.member = member;
+    activity.last_active_epoch = epoch;
+    activity.bump = ctx.bumps.member_activity;
+
+    emit_event!(ctx, MemberHeartbeatRecorded {
+        hive: ctx.accounts.roles.hive,
+        member,
+        epoch,
+        reactivated,
+        electorate: ctx.accounts.roles.electorate(),
+    });
+
+    Ok(())
+}
+
+/// Permissionless
+pub fn process_mark_inactive(ctx: Context<MarkInactive>) -> Result<()> {
+    let activity = &ctx.accounts.member_activity;
+    let silent_until = activity.last_active_epoch.saturating_add(INACTIVITY_EPOCHS);
+    require!(Clock::get()?.epoch >= silent_until, FastVoteError::MemberStillActive);
+
+    let roles = &mut ctx.accounts.roles;
+    let entry = roles
+        .members
+        .iter_mut()
+        .find(|m| m.member == activity.member)
+        .ok_or(FastVoteError::NotHiveMember)?;
+    require!(entry.is_active(), FastVoteError::MemberStillActive);
+    entry.roles |= INACTIVE_MASK;
+
+    emit_event!(ctx, MemberMarkedInactive {
+        hive: ctx.accounts.roles.hive,
+        member: ctx.accounts.member_activity.member,
+        last_active_epoch: ctx.accounts.member_activity.last_active_epoch,
+        electorate: ctx.accounts.roles.electorate(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct TrackMemberActivity<'info> {
+    #[account(seeds = [ROLES_SEED, roles.hive.as_ref()], bump = roles.bump)]
+    pub roles: Account<'info, Roles>,
+    #[account(
+        init,
+        payer = payer,
+        space = MemberActivity::LEN,
+        seeds = [MEMBER_ACTIVITY_SEED, roles.hive.as_ref(), member.as_ref()],
+        bump
+    )]
+    pub member_activity: Account<'info, MemberActivity>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct MemberHeartbeat<'info> {
+    #[account(mut, seeds = [ROLES_SEED, roles.hive.as_ref()], bump = roles.bump)]
+    pub roles: Account<'info, Roles>,
+    #[account(
+        init_if_needed,
+        payer = member,
+        space = MemberActivity::LEN,
+        seeds = [MEMBER_ACTIVITY_SEED, roles.hive.as_ref(), member.key().as_ref()],
+        bump
+    )]
+    pub member_activity: Account<'info, MemberActivity>,
+    #[account(mut)]
+    pub member: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct MarkInactive<'info> {
+    #[account(mut, seeds = [ROLES_SEED, roles.hive.as_ref()], bump = roles.bump)]
+    pub roles: Account<'info, Roles>,
+    #[account(
+        seeds = [MEMBER_ACTIVITY_SEED, roles.hive.as_ref(), member_activity.member.as_ref()],
+        bump = member_activity.bump
+    )]
+    pub member_activity: Account<'info, MemberActivity>,
+}
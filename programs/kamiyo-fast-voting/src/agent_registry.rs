@@ -0,0 +1,353 @@
+// Agent registry. Once GlobalConfig::min_agent_bond is set, every ballot
+// needs the voter's AgentRecord to hold at least that many lamports, so each
+// voting identity ties up capital and minting thousands of them to get past
+// per-action vote caps gets expensive. Bonds stay in the record until the
+// config admin evicts the agent, which closes the record and refunds it.
+//
+// The config admin also sorts agents into classes. An action may cap how
+// many ballots each class casts and scale the weight of its token-weighted
+// ballots, so one class cannot drown out another by numbers alone. Voters
+// without a record count as Unclassified.
+//
+// Against Sybil swarms of fresh identities, the config may also set an
+// identity ramp: token-weighted ballots of an agent count a floor share of
+// their weight in its registration epoch, rising to full weight over the
+// ramp's epochs. Voters without a record count as registered this epoch.
+// Headcount ballots count one each and are not ramped.
+//
+// An agent may bind vote keys, e.g. a hot key held by its runtime, with
+// set_vote_key. A vote_fast ballot signed by a vote key is the agent's: its
+// FastVote, AgentRecord and vote credits are derived from the agent, not the
+// signer, so the agent votes once whichever of its keys signs. A vote key
+// cannot register or be classed as an agent of its own while bound, and an
+// agent cannot be bound as another's key; so where a bond is required a vote
+// key has no ballot of its own on any path. Vote delegation is separate: it
+// moves weight, not ballots, and a delegate votes as itself.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+
+use crate::{
+    AgentClass, AgentClassSet, AgentEvicted, AgentRecord, AgentRegistered, ClassLimit, FastAction, FastVoteError,
+    GlobalConfig, VoteKey, VoteKeyCleared, VoteKeySet, VoteMode, AGENT_CLASSES, AGENT_SEED, GLOBAL_CONFIG_SEED,
+    VOTE_KEY_SEED,
+};
+
+/// The agent a ballot signed by `signer` counts for: the agent it is bound
+/// to if `vote_key`, its VoteKey PDA, exists, else the signer itself
+pub fn identity_of(vote_key: &AccountInfo, signer: &Pubkey) -> Result<Pubkey> {
+    Ok(VoteKey::load(vote_key)?.map_or(*signer, |k| k.agent))
+}
+
+/// Fails unless the voter behind `record`, its AgentRecord PDA, has bonded
+/// enough to vote
+pub fn require_eligible(record: &AccountInfo, config: &GlobalConfig) -> Result<()> {
+    if config.min_agent_bond == 0 {
+        return Ok(());
+    }
+    let bond = AgentRecord::load(record)?.map_or(0, |r| r.bond);
+    require!(bond >= config.min_agent_bond, FastVoteError::AgentNotBonded);
+    Ok(())
+}
+
+/// `weight` ramped by the age of the agent whose AgentRecord PDA is
+/// `record`, at `epoch`
+pub fn aged_weight(record: &AccountInfo, config: &GlobalConfig, weight: u64, epoch: u64) -> Result<u64> {
+    if config.identity_ramp.epochs == 0 {
+        return Ok(weight);
+    }
+    let registered = AgentRecord::load(record)?.map_or(epoch, |r| r.registered_epoch);
+    Ok(config.identity_ramp.apply(weight, epoch.saturating_sub(registered)))
+}
+
+/// Class of the agent whose AgentRecord PDA is `record`; Unclassified if
+/// it has none
+pub fn class_of(record: &AccountInfo) -> Result<AgentClass> {
+    Ok(AgentRecord::load(record)?.map_or(AgentClass::Unclassified, |r| r.class))
+}
+
+/// Check `record`'s class against the action's cap for it and count the
+/// ballot; returns the class, for FastAction::class_weight.
+pub fn count_class_vote(action: &mut FastAction, record: &AccountInfo) -> Result<AgentClass> {
+    let class = class_of(record)?;
+    let index = class as usize;
+    let max_votes = action.class_max_votes[index];
+    require!(
+        max_votes == 0 || action.class_votes[index] < max_votes,
+        FastVoteError::ClassVoteCapReached
+    );
+    action.class_votes[index] = action.class_votes[index].checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+    Ok(class)
+}
+
+/// Validate `limits` for a new action; returns its (max_votes, weight_bps)
+/// per class.
+pub fn resolve_class_limits(
+    limits: &[ClassLimit],
+    vote_mode: VoteMode,
+) -> Result<([u32; AGENT_CLASSES], [u16; AGENT_CLASSES])> {
+    let mut max_votes = [0; AGENT_CLASSES];
+    let mut weight_bps = [0; AGENT_CLASSES];
+    for (i, limit) in limits.iter().enumerate() {
+        require!(
+            !limits[..i].iter().any(|l| l.class == limit.class)
+                && (limit.weight_bps == 0 || vote_mode == VoteMode::TokenWeighted),
+            FastVoteError::InvalidClassLimit
+        );
+        max_votes[limit.class as usize] = limit.max_votes;
+        weight_bps[limit.class as usize] = limit.weight_bps;
+    }
+    Ok((max_votes, weight_bps))
+}
+
+/// Registers the signer on first use; later calls top up its bond.
+pub fn process_register_agent(ctx: Context<RegisterAgent>, amount: u64) -> Result<()> {
+    require!(amount > 0, FastVoteError::InvalidAgentBond);
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.agent.to_account_info(),
+                to: ctx.accounts.agent_record.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let record = &mut ctx.accounts.agent_record;
+    if record.agent == Pubkey::default() {
+        record.agent = ctx.accounts.agent.key();
+        record.registered_slot = Clock::get()?.slot;
+        record.registered_epoch = Clock::get()?.epoch;
+        record.bump = ctx.bumps.agent_record;
+    }
+    record.bond = record.bond.checked_add(amount).ok_or(FastVoteError::InvalidAgentBond)?;
+
+    emit_event!(ctx, AgentRegistered {
+        agent: record.agent,
+        bond: record.bond,
+    });
+
+    Ok(())
+}
+
+/// Config admin only. Closes the record; the bond and rent go back to the
+/// agent.
+pub fn process_evict_agent(ctx: Context<EvictAgent>) -> Result<()> {
+    emit_event!(ctx, AgentEvicted {
+        agent: ctx.accounts.agent.key(),
+        bond: ctx.accounts.agent_record.bond,
+    });
+
+    Ok(())
+}
+
+/// Config admin only. Records the class of an agent that has not
+/// registered yet, so classes can be assigned before bonds are required.
+pub fn process_set_agent_class(ctx: Context<SetAgentClass>, class: AgentClass) -> Result<()> {
+    let record = &mut ctx.accounts.agent_record;
+    if record.agent == Pubkey::default() {
+        record.agent = ctx.accounts.agent.key();
+        record.registered_slot = Clock::get()?.slot;
+        record.registered_epoch = Clock::get()?.epoch;
+        record.bump = ctx.bumps.agent_record;
+    }
+    record.class = class;
+
+    emit_event!(ctx, AgentClassSet {
+        agent: record.agent,
+        class,
+    });
+
+    Ok(())
+}
+
+/// Binds `key` to the signing agent, which must be registered. The key
+/// co-signs, so no agent can claim another's key, and must not be an agent
+/// itself.
+pub fn process_set_vote_key(ctx: Context<SetVoteKey>) -> Result<()> {
+    let vote_key = &mut ctx.accounts.vote_key;
+    vote_key.key = ctx.accounts.key.key();
+    vote_key.agent = ctx.accounts.agent.key();
+    vote_key.bump = ctx.bumps.vote_key;
+
+    emit_event!(ctx, VoteKeySet {
+        agent: vote_key.agent,
+        key: vote_key.key,
+    });
+
+    Ok(())
+}
+
+/// Unbinds a vote key; the agent gets the rent back. Ballots it already
+/// signed stay the agent's.
+pub fn process_clear_vote_key(ctx: Context<ClearVoteKey>) -> Result<()> {
+    emit_event!(ctx, VoteKeyCleared {
+        agent: ctx.accounts.agent.key(),
+        key: ctx.accounts.vote_key.key,
+    });
+
+    Ok(())
+}
+
+/// Permissionless: grows an AgentRecord created before `registered_epoch`
+/// was kept. The field starts at 0, so the identity ramp treats the agent as
+/// long registered.
+pub fn process_migrate_agent_record(ctx: Context<MigrateAgentRecord>) -> Result<()> {
+    let info = ctx.accounts.agent_record.to_account_info();
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    require!(info.data_len() == AgentRecord::LEGACY_LEN, FastVoteError::AlreadyMigrated);
+    require!(
+        info.try_borrow_data()?.starts_with(AgentRecord::DISCRIMINATOR),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+
+    let rent = Rent::get()?.minimum_balance(AgentRecord::LEN);
+    let shortfall = rent.saturating_sub(info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    info.realloc(AgentRecord::LEN, true)?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RegisterAgent<'info> {
+    #[account(
+        init_if_needed,
+        payer = agent,
+        space = AgentRecord::LEN,
+        seeds = [AGENT_SEED, agent.key().as_ref()],
+        bump
+    )]
+    pub agent_record: Account<'info, AgentRecord>,
+    /// CHECK: The agent's VoteKey PDA; a bound vote key cannot register
+    #[account(
+        seeds = [VOTE_KEY_SEED, agent.key().as_ref()],
+        bump,
+        constraint = vote_key.data_is_empty() @ FastVoteError::VoteKeyInUse
+    )]
+    pub vote_key: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub agent: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct EvictAgent<'info> {
+    #[account(
+        mut,
+        seeds = [AGENT_SEED, agent.key().as_ref()],
+        bump = agent_record.bump,
+        close = agent
+    )]
+    pub agent_record: Account<'info, AgentRecord>,
+    /// CHECK: Receives the refund; bound to the record by its seeds
+    #[account(mut)]
+    pub agent: UncheckedAccount<'info>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump, has_one = admin @ FastVoteError::Unauthorized)]
+    pub config: Account<'info, GlobalConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetAgentClass<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = AgentRecord::LEN,
+        seeds = [AGENT_SEED, agent.key().as_ref()],
+        bump
+    )]
+    pub agent_record: Account<'info, AgentRecord>,
+    /// CHECK: Only keys the record
+    pub agent: UncheckedAccount<'info>,
+    /// CHECK: The agent's VoteKey PDA; a bound vote key cannot be classed
+    #[account(
+        seeds = [VOTE_KEY_SEED, agent.key().as_ref()],
+        bump,
+        constraint = vote_key.data_is_empty() @ FastVoteError::VoteKeyInUse
+    )]
+    pub vote_key: UncheckedAccount<'info>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump, has_one = admin @ FastVoteError::Unauthorized)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetVoteKey<'info> {
+    #[account(
+        init,
+        payer = agent,
+        space = VoteKey::LEN,
+        seeds = [VOTE_KEY_SEED, key.key().as_ref()],
+        bump
+    )]
+    pub vote_key: Account<'info, VoteKey>,
+    /// The agent binding the key; must be registered
+    #[account(seeds = [AGENT_SEED, agent.key().as_ref()], bump = agent_record.bump)]
+    pub agent_record: Account<'info, AgentRecord>,
+    /// CHECK: The key's own AgentRecord PDA; an agent cannot be a vote key
+    #[account(
+        seeds = [AGENT_SEED, key.key().as_ref()],
+        bump,
+        constraint = key_record.data_is_empty() @ FastVoteError::VoteKeyInUse
+    )]
+    pub key_record: UncheckedAccount<'info>,
+    /// CHECK: The agent's own VoteKey PDA; a vote key cannot bind keys
+    #[account(
+        seeds = [VOTE_KEY_SEED, agent.key().as_ref()],
+        bump,
+        constraint = agent_vote_key.data_is_empty() @ FastVoteError::VoteKeyInUse
+    )]
+    pub agent_vote_key: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub agent: Signer<'info>,
+    pub key: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ClearVoteKey<'info> {
+    #[account(
+        mut,
+        seeds = [VOTE_KEY_SEED, vote_key.key.as_ref()],
+        bump = vote_key.bump,
+        has_one = agent @ FastVoteError::Unauthorized,
+        close = agent
+    )]
+    pub vote_key: Account<'info, VoteKey>,
+    #[account(mut)]
+    pub agent: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAgentRecord<'info> {
+    /// CHECK: The legacy layout does not deserialize as AgentRecord; owner,
+    /// size and discriminator are checked in process_migrate_agent_record
+    #[account(mut, seeds = [AGENT_SEED, agent.key().as_ref()], bump)]
+    pub agent_record: UncheckedAccount<'info>,
+    /// CHECK: Only keys the record
+    pub agent: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,102 @@
+// Amendments for actions created with a wrong threshold, window or
+// description. Only the action authority may amend, and only before the
+// first vote. Delegated actions cannot be amended: on the base layer their
+// account belongs to the delegation program, and the ER refuses writes to
+// the undelegated hash record.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    tally, ActionAmendment, ActionHashRecord, ActionKind, FastAction, FastActionAmended, FastVoteError,
+    GlobalConfig, VoteMode, ACTION_HASH_SEED, CONFIG_CHANGE_MIN_THRESHOLD, EMERGENCY_MIN_THRESHOLD,
+    EMERGENCY_WINDOW_SLOTS, FAST_ACTION_SEED, FAST_ACTION_VERSION, GLOBAL_CONFIG_SEED, HASH_REUSE_GRACE_SLOTS,
+};
+
+pub fn process_amend_action(ctx: Context<AmendAction>, amendment: ActionAmendment) -> Result<()> {
+    let action = &mut ctx.accounts.fast_action;
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(action.vote_count == 0, FastVoteError::ActionHasVotes);
+
+    let old_threshold = action.threshold;
+    let old_deadline_slot = action.deadline_slot;
+    let old_description_hash = action.description_hash;
+
+    if let Some(threshold) = amendment.threshold {
+        require!(threshold > 0 && threshold <= 100, FastVoteError::InvalidThreshold);
+        match action.kind {
+            ActionKind::Standard | ActionKind::Election | ActionKind::TaskAssignment => {}
+            ActionKind::Emergency => {
+                require!(threshold >= EMERGENCY_MIN_THRESHOLD, FastVoteError::InvalidEmergencyAction)
+            }
+            ActionKind::ConfigChange | ActionKind::ProgramUpgrade | ActionKind::Budget => require!(
+                threshold >= CONFIG_CHANGE_MIN_THRESHOLD,
+                FastVoteError::InvalidConfigChangeAction
+            ),
+        }
+        tally::validate_tiers(threshold, &action.tier_thresholds)?;
+        action.threshold = threshold;
+        action.threshold_bps = threshold as u16 * 100;
+    }
+    if let Some(window_slots) = amendment.window_slots {
+        let max_window = match action.kind {
+            ActionKind::Emergency => EMERGENCY_WINDOW_SLOTS,
+            _ => ctx.accounts.config.max_window(),
+        };
+        require!(
+            window_slots > 0 && window_slots <= max_window,
+            FastVoteError::InvalidVotingWindow
+        );
+        let deadline_slot = action
+            .created_slot
+            .checked_add(window_slots)
+            .ok_or(FastVoteError::SlotOverflow)?;
+        require!(deadline_slot > Clock::get()?.slot, FastVoteError::InvalidVotingWindow);
+        action.deadline_slot = deadline_slot;
+
+        // The hash stays claimed for as long as the action can be voted on
+        ctx.accounts.action_hash_record.expires_slot = deadline_slot
+            .checked_add(HASH_REUSE_GRACE_SLOTS)
+            .ok_or(FastVoteError::SlotOverflow)?;
+    }
+    if let Some(description_hash) = amendment.description_hash {
+        action.description_hash = description_hash;
+    }
+
+    emit_event!(ctx, FastActionAmended {
+        action: action.key(),
+        hive: action.hive,
+        action_id: action.action_id,
+        old_threshold,
+        threshold: action.threshold,
+        old_deadline_slot,
+        deadline_slot: action.deadline_slot,
+        old_description_hash,
+        description_hash: action.description_hash,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AmendAction<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        has_one = authority @ FastVoteError::Unauthorized,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration,
+        constraint = fast_action.vote_mode != VoteMode::Optimistic @ FastVoteError::WrongVoteMode
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        mut,
+        seeds = [ACTION_HASH_SEED, fast_action.action_hash.as_ref()],
+        bump = action_hash_record.bump,
+        constraint = action_hash_record.action == fast_action.key() @ FastVoteError::DuplicateActionHash
+    )]
+    pub action_hash_record: Account<'info, ActionHashRecord>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,109 @@
+// Result attestation. A tally is run by whichever TEE validator holds the
+// action, so a hive trusting results to one validator trusts that validator
+// alone. A hive may instead name a set of validators and require k of them
+// to co-sign each result: tally_and_commit then only finalizes if the same
+// transaction carries ed25519 program instructions in which at least k of
+// them signed the result's attestation digest.
+//
+// The digest leaves out the finalization slot, which is only fixed once the
+// tally lands, so attestors sign what preview_tally reports ahead of the
+// transaction. As with cancellation policies, tally_and_commit passes the
+// hive's ResultAttestors PDA whether or not it exists, so a tallier cannot
+// skip the check by leaving it out.
+
+use anchor_lang::prelude::*;
+
+use crate::equivocation::verified_signatures;
+use crate::{
+    attestation_digest, FastAction, FastVoteError, ResultAttested, ResultAttestors, ResultAttestorsSet, Role, Roles,
+    MAX_ATTESTORS, RESULT_ATTESTORS_SEED, ROLES_SEED,
+};
+
+/// Checks the attestors' co-signatures over `action`'s just-tallied result
+/// against `info`, the hive's ResultAttestors PDA; None if the hive requires
+/// none
+pub fn require_attested(
+    info: &AccountInfo,
+    instructions: &AccountInfo,
+    action: &Account<FastAction>,
+) -> Result<Option<ResultAttested>> {
+    if info.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let attestors = ResultAttestors::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    if attestors.threshold == 0 {
+        return Ok(None);
+    }
+
+    let digest = attestation_digest(&action.key(), action.votes_for, action.votes_against, &action.result);
+    let verified = verified_signatures(instructions)?;
+    let signed: Vec<Pubkey> = attestors
+        .validators
+        .iter()
+        .filter(|validator| verified.iter().any(|(signer, message)| signer == *validator && *message == digest))
+        .copied()
+        .collect();
+    require!(signed.len() >= attestors.threshold as usize, FastVoteError::AttestationMissing);
+
+    Ok(Some(ResultAttested {
+        action: action.key(),
+        hive: action.hive,
+        digest,
+        attestors: signed,
+    }))
+}
+
+/// Hive admin only; an empty set or a zero threshold turns attestation off
+pub fn process_set_result_attestors(
+    ctx: Context<SetResultAttestors>,
+    validators: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    require!(ctx.accounts.roles.has_role(&admin, Role::Admin), FastVoteError::MissingRole);
+    require!(
+        validators.len() <= MAX_ATTESTORS && threshold as usize <= validators.len(),
+        FastVoteError::InvalidAttestors
+    );
+    for (i, validator) in validators.iter().enumerate() {
+        require!(
+            *validator != Pubkey::default() && !validators[..i].contains(validator),
+            FastVoteError::InvalidAttestors
+        );
+    }
+
+    let attestors = &mut ctx.accounts.result_attestors;
+    attestors.hive = ctx.accounts.roles.hive;
+    attestors.validators = validators.clone();
+    attestors.threshold = threshold;
+    attestors.bump = ctx.bumps.result_attestors;
+
+    emit_event!(ctx, ResultAttestorsSet {
+        hive: ctx.accounts.roles.hive,
+        validators,
+        threshold,
+        set_by: admin,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetResultAttestors<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = ResultAttestors::LEN,
+        seeds = [RESULT_ATTESTORS_SEED, roles.hive.as_ref()],
+        bump
+    )]
+    pub result_attestors: Account<'info, ResultAttestors>,
+    #[account(seeds = [ROLES_SEED, roles.hive.as_ref()], bump = roles.bump)]
+    pub roles: Account<'info, Roles>,
+    /// Must hold Role::Admin in the hive
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
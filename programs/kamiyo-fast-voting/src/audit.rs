@@ -0,0 +1,95 @@
+// Audited tallies. tally_and_commit trusts the counters on FastAction; an
+// action created with `audited` set is only tallied once verify_tally_page
+// has recounted every FastVote into its TallyAudit and the recount matches.
+// Pages must list votes in ascending address order, across pages too, so no
+// ballot is counted twice. For/against actions only: option ballots do not
+// record their split.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    FastAction, FastVote, FastVoteError, TallyAudit, TallyPageVerified, VoteMode, FAST_ACTION_SEED,
+    FAST_ACTION_VERSION, TALLY_AUDIT_SEED,
+};
+
+/// Fails unless an audited `action` has a complete, matching recount
+pub fn require_verified(action: &FastAction, audit: Option<&Account<TallyAudit>>) -> Result<()> {
+    if !action.audited {
+        return Ok(());
+    }
+    let audit = audit.ok_or(FastVoteError::TallyNotVerified)?;
+    require!(audit.matches(action), FastVoteError::TallyNotVerified);
+    Ok(())
+}
+
+/// Permissionless once voting has ended. Remaining accounts are the page's
+/// FastVote accounts.
+pub fn process_verify_tally_page(ctx: Context<VerifyTallyPage>) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(action.audited, FastVoteError::InvalidTallyPage);
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(Clock::get()?.slot > action.deadline_slot, FastVoteError::VotingNotEnded);
+
+    let action_key = action.key();
+    let audit = &mut ctx.accounts.tally_audit;
+    if audit.action == Pubkey::default() {
+        audit.action = action_key;
+        audit.bump = ctx.bumps.tally_audit;
+    }
+
+    for info in ctx.remaining_accounts {
+        require!(info.key() > audit.last_vote, FastVoteError::InvalidTallyPage);
+        require_keys_eq!(*info.owner, crate::ID, FastVoteError::InvalidTallyPage);
+        let vote = FastVote::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require_keys_eq!(vote.fast_action, action_key, FastVoteError::InvalidTallyPage);
+
+        audit.last_vote = info.key();
+        audit.vote_count = audit.vote_count.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+        if vote.vote_value {
+            audit.votes_for = audit.votes_for.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+        } else {
+            audit.votes_against = audit.votes_against.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+        }
+        if action.vote_mode == VoteMode::TokenWeighted {
+            if vote.vote_value {
+                audit.weight_for = audit.weight_for.checked_add(vote.weight).ok_or(FastVoteError::VoteOverflow)?;
+            } else {
+                audit.weight_against = audit
+                    .weight_against
+                    .checked_add(vote.weight)
+                    .ok_or(FastVoteError::VoteOverflow)?;
+            }
+        }
+    }
+
+    emit_event!(ctx, TallyPageVerified {
+        action: action_key,
+        hive: action.hive,
+        verified: audit.vote_count,
+        complete: audit.matches(action),
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct VerifyTallyPage<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = TallyAudit::LEN,
+        seeds = [TALLY_AUDIT_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub tally_audit: Account<'info, TallyAudit>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,40 @@
+// Action authority: who may cancel or amend an action. Starts as the creator
+// and can be handed on, e.g. when creator agents rotate or to the hive's
+// governance PDA. `creator` itself never changes.
+
+use anchor_lang::prelude::*;
+
+use crate::{ActionAuthorityTransferred, FastAction, FastVoteError, FAST_ACTION_SEED, FAST_ACTION_VERSION};
+
+pub fn process_transfer_action_authority(ctx: Context<TransferActionAuthority>, new_authority: Pubkey) -> Result<()> {
+    require!(new_authority != Pubkey::default(), FastVoteError::InvalidAuthority);
+    let action = &mut ctx.accounts.fast_action;
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+
+    let previous = action.authority;
+    action.authority = new_authority;
+
+    emit_event!(ctx, ActionAuthorityTransferred {
+        action: action.key(),
+        hive: action.hive,
+        action_id: action.action_id,
+        previous,
+        authority: new_authority,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct TransferActionAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        has_one = authority @ FastVoteError::Unauthorized,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,59 @@
+// Batched creation. A coordinator opening one vote per sub-task at the start
+// of a planning round would otherwise send a transaction per action and wait
+// on each. create_actions_batch takes a list of ActionParams and, as
+// remaining accounts, one set of create_fast_action accounts per entry, in
+// create_fast_action's order. Each set is checked and each action created
+// exactly as create_fast_action would, ids included: every action takes the
+// ActionCounter's next id in turn, so a batch's ids are consecutive from the
+// counter's next_id, and each emits its own FastActionCreated.
+//
+// The transaction size bounds a batch. Accounts shared by the entries are
+// listed once, leaving the action, its hash record and its params per entry.
+// A tagged entry's TagIndex pages follow its set, one per tag in tag order.
+
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::*;
+
+use crate::{create_action, ActionParams, CreateFastAction, CreateFastActionBumps, FastVoteError, MAX_BATCH_ACTIONS};
+
+pub fn process_create_actions_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateActionsBatch<'info>>,
+    batch: Vec<ActionParams>,
+) -> Result<()> {
+    require!((1..=MAX_BATCH_ACTIONS).contains(&batch.len()), FastVoteError::InvalidBatch);
+
+    let mut accounts = ctx.remaining_accounts;
+    for params in batch {
+        let mut bumps = CreateFastActionBumps::default();
+        let mut reallocs = BTreeSet::new();
+        // CreateFastAction's instruction args are the leading action hash
+        let mut create = CreateFastAction::try_accounts(
+            ctx.program_id,
+            &mut accounts,
+            &params.action_hash,
+            &mut bumps,
+            &mut reallocs,
+        )?;
+        require_keys_eq!(create.creator.key(), ctx.accounts.creator.key(), FastVoteError::InvalidBatch);
+
+        require!(accounts.len() >= params.tags.len(), FastVoteError::InvalidBatch);
+        let (pages, rest) = accounts.split_at(params.tags.len());
+        accounts = rest;
+
+        let window_slots = params.kind.window_slots();
+        let mut create_ctx = Context::new(ctx.program_id, &mut create, pages, bumps);
+        create_action(&mut create_ctx, params, window_slots)?;
+        create.exit(ctx.program_id)?;
+    }
+    require!(accounts.is_empty(), FastVoteError::InvalidBatch);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateActionsBatch<'info> {
+    /// Creator of every action in the batch
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
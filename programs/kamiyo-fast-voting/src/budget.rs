@@ -0,0 +1,296 @@
+// Budgets. A Budget action commits to a payment from the treasury through
+// its action hash; once it passes and settles, open_stream records a Stream
+// that vests the amount linearly between the schedule's slots, and anyone may
+// crank withdraw_stream to pay the vested part to the recipient. A second
+// Budget action committing to the stream's cancel_hash stops further
+// vesting; whatever vested before the cancellation stays claimable.
+//
+// Default mint pays lamports from the Treasury PDA; any other mint pays from
+// a token account the treasury owns, through TransferChecked.
+//
+// Budgets reaching the slow-track promotion threshold must also pass a slow
+// vote: open_stream reads the slow voting program's config and the action's
+// promotion, and waits for the promotion to pass and clear its timelock.
+// Until the slow track is configured every Budget opens on its fast vote.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use kamiyo_slow_voting::{SlowAction, SlowConfig, SLOW_ACTION_SEED, SLOW_CONFIG_SEED};
+
+use crate::config_change::is_token_majority;
+use crate::{
+    market, ActionKind, Budget, FastAction, FastVoteError, GlobalConfig, Stream, StreamCancelled, StreamOpened,
+    StreamWithdrawn, Treasury, VoteMode, FAST_ACTION_SEED, FAST_ACTION_VERSION, STREAM_SEED, TOKEN_2022_PROGRAM_ID,
+    TOKEN_PROGRAM_ID, TREASURY_SEED,
+};
+
+// spl-token TransferChecked and the Mint.decimals offset
+const TRANSFER_CHECKED: u8 = 12;
+const MINT_DECIMALS_OFFSET: usize = 44;
+
+/// TransferChecked of `amount` from `source`, signed by `authority`; pass
+/// the authority's seeds when it is one of our PDAs
+pub(crate) fn transfer_checked<'info>(
+    token_program: &AccountInfo<'info>,
+    source: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    require!(
+        token_program.key() == TOKEN_PROGRAM_ID || token_program.key() == TOKEN_2022_PROGRAM_ID,
+        FastVoteError::InvalidTokenAccount
+    );
+    let decimals = *mint
+        .try_borrow_data()?
+        .get(MINT_DECIMALS_OFFSET)
+        .ok_or(FastVoteError::InvalidTokenAccount)?;
+
+    let mut data = vec![TRANSFER_CHECKED];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(decimals);
+    invoke_signed(
+        &Instruction {
+            program_id: token_program.key(),
+            accounts: vec![
+                AccountMeta::new(source.key(), false),
+                AccountMeta::new_readonly(mint.key(), false),
+                AccountMeta::new(destination.key(), false),
+                AccountMeta::new_readonly(authority.key(), true),
+            ],
+            data,
+        },
+        &[
+            source.clone(),
+            mint.clone(),
+            destination.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Budget constraints on top of the usual creation checks
+pub fn validate_budget(threshold: u8, vote_mode: VoteMode, option_count: u8, config: &GlobalConfig) -> Result<()> {
+    require!(
+        is_token_majority(threshold, vote_mode, option_count, config),
+        FastVoteError::InvalidBudgetAction
+    );
+    Ok(())
+}
+
+/// Whether `budget` may open on `action`'s fast vote alone, or else its
+/// promotion to a slow vote has passed and cleared its timelock
+fn check_slow_track(
+    config: &AccountInfo,
+    promotion: &AccountInfo,
+    action: &Pubkey,
+    budget: &Budget,
+    slot: u64,
+) -> Result<()> {
+    if !promotion.data_is_empty() {
+        require_keys_eq!(*promotion.owner, kamiyo_slow_voting::ID, FastVoteError::SlowVotePending);
+        let promotion = SlowAction::try_deserialize(&mut &promotion.try_borrow_data()?[..])?;
+        require!(
+            promotion.fast_action == *action && promotion.is_executable(slot),
+            FastVoteError::SlowVotePending
+        );
+        return Ok(());
+    }
+    if config.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*config.owner, kamiyo_slow_voting::ID, FastVoteError::PromotionRequired);
+    let settings = SlowConfig::try_deserialize(&mut &config.try_borrow_data()?[..])?.settings;
+    require!(
+        settings.promotion_threshold == 0
+            || budget.mint != settings.promotion_mint
+            || budget.amount < settings.promotion_threshold,
+        FastVoteError::PromotionRequired
+    );
+    Ok(())
+}
+
+/// Permissionless, on the base layer; `payer` covers the stream account.
+pub fn process_open_stream(ctx: Context<OpenStream>, budget: Budget) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(budget.action_hash()? == action.action_hash, FastVoteError::BudgetMismatch);
+    require!(
+        budget.recipient != Pubkey::default() && budget.amount > 0 && budget.end_slot > budget.start_slot,
+        FastVoteError::InvalidBudget
+    );
+    let slot = Clock::get()?.slot;
+    require!(market::is_executable(action, slot), FastVoteError::ActionNotExecutable);
+    check_slow_track(&ctx.accounts.slow_config, &ctx.accounts.slow_action, &action.key(), &budget, slot)?;
+
+    let stream = &mut ctx.accounts.stream;
+    stream.action = action.key();
+    stream.recipient = budget.recipient;
+    stream.mint = budget.mint;
+    stream.amount = budget.amount;
+    stream.withdrawn = 0;
+    stream.start_slot = budget.start_slot;
+    stream.end_slot = budget.end_slot;
+    stream.cancelled_slot = 0;
+    stream.bump = ctx.bumps.stream;
+
+    emit_event!(ctx, StreamOpened {
+        stream: stream.key(),
+        action: stream.action,
+        hive: action.hive,
+        recipient: budget.recipient,
+        mint: budget.mint,
+        amount: budget.amount,
+        start_slot: budget.start_slot,
+        end_slot: budget.end_slot,
+    });
+
+    Ok(())
+}
+
+/// Permissionless: pays whatever has vested to the recipient.
+pub fn process_withdraw_stream(ctx: Context<WithdrawStream>) -> Result<()> {
+    let stream = &mut ctx.accounts.stream;
+    let due = stream.vested(Clock::get()?.slot).saturating_sub(stream.withdrawn);
+    require!(due > 0, FastVoteError::NothingVested);
+    stream.withdrawn += due;
+
+    let treasury = ctx.accounts.treasury.to_account_info();
+    if stream.mint == Pubkey::default() {
+        let reserve = Rent::get()?.minimum_balance(treasury.data_len());
+        require!(
+            treasury.lamports().saturating_sub(reserve) >= due,
+            FastVoteError::TreasuryInsufficient
+        );
+        **treasury.try_borrow_mut_lamports()? -= due;
+        **ctx.accounts.recipient.try_borrow_mut_lamports()? += due;
+    } else {
+        let (Some(mint), Some(source), Some(destination), Some(token_program)) = (
+            ctx.accounts.mint.as_ref(),
+            ctx.accounts.treasury_token_account.as_ref(),
+            ctx.accounts.recipient_token_account.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+        ) else {
+            return err!(FastVoteError::MissingTokenAccounts);
+        };
+        require_keys_eq!(mint.key(), stream.mint, FastVoteError::InvalidTokenAccount);
+
+        // The token program checks the source belongs to the treasury and the
+        // destination holds the mint; the recipient's ownership is ours
+        require!(
+            destination.try_borrow_data()?.get(32..64) == Some(stream.recipient.as_ref()),
+            FastVoteError::InvalidTokenAccount
+        );
+
+        let seeds: &[&[u8]] = &[TREASURY_SEED, &[ctx.accounts.treasury.bump]];
+        transfer_checked(token_program, source, mint, destination, &treasury, due, &[seeds])?;
+    }
+
+    emit_event!(ctx, StreamWithdrawn {
+        stream: stream.key(),
+        recipient: stream.recipient,
+        amount: due,
+        withdrawn: stream.withdrawn,
+    });
+
+    Ok(())
+}
+
+/// Permissionless, once the cancelling Budget action has passed and settled.
+pub fn process_cancel_stream(ctx: Context<CancelStream>) -> Result<()> {
+    let stream_key = ctx.accounts.stream.key();
+    let cancel_action = &ctx.accounts.cancel_action;
+    require!(
+        cancel_action.action_hash == Stream::cancel_hash(&stream_key),
+        FastVoteError::BudgetMismatch
+    );
+    let slot = Clock::get()?.slot;
+    require!(market::is_executable(cancel_action, slot), FastVoteError::ActionNotExecutable);
+
+    let stream = &mut ctx.accounts.stream;
+    require!(stream.cancelled_slot == 0, FastVoteError::StreamAlreadyCancelled);
+    stream.cancelled_slot = slot.max(1);
+
+    emit_event!(ctx, StreamCancelled {
+        stream: stream_key,
+        cancel_action: cancel_action.key(),
+        hive: cancel_action.hive,
+        forfeited: stream.amount - stream.vested(slot),
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct OpenStream<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration,
+        constraint = fast_action.kind == ActionKind::Budget @ FastVoteError::InvalidBudgetAction
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = payer,
+        space = Stream::LEN,
+        seeds = [STREAM_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub stream: Account<'info, Stream>,
+    /// CHECK: The slow voting program's config, if it is set up
+    #[account(seeds = [SLOW_CONFIG_SEED], bump, seeds::program = kamiyo_slow_voting::ID)]
+    pub slow_config: UncheckedAccount<'info>,
+    /// CHECK: The action's promotion to a slow vote, if it was promoted
+    #[account(
+        seeds = [SLOW_ACTION_SEED, fast_action.key().as_ref()],
+        bump,
+        seeds::program = kamiyo_slow_voting::ID
+    )]
+    pub slow_action: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct WithdrawStream<'info> {
+    #[account(mut, seeds = [STREAM_SEED, stream.action.as_ref()], bump = stream.bump)]
+    pub stream: Account<'info, Stream>,
+    #[account(mut, seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    /// CHECK: Receives lamport streams
+    #[account(mut, address = stream.recipient @ FastVoteError::Unauthorized)]
+    pub recipient: UncheckedAccount<'info>,
+    /// CHECK: Token streams only: the stream's mint
+    pub mint: Option<UncheckedAccount<'info>>,
+    /// CHECK: Token streams only: source owned by the treasury
+    #[account(mut)]
+    pub treasury_token_account: Option<UncheckedAccount<'info>>,
+    /// CHECK: Token streams only: owned by the recipient
+    #[account(mut)]
+    pub recipient_token_account: Option<UncheckedAccount<'info>>,
+    /// CHECK: Token streams only: SPL Token or Token-2022
+    pub token_program: Option<UncheckedAccount<'info>>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CancelStream<'info> {
+    #[account(mut, seeds = [STREAM_SEED, stream.action.as_ref()], bump = stream.bump)]
+    pub stream: Account<'info, Stream>,
+    #[account(
+        seeds = [FAST_ACTION_SEED, cancel_action.hive_seed(), &cancel_action.action_id.to_le_bytes()],
+        bump = cancel_action.bump,
+        constraint = cancel_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration,
+        constraint = cancel_action.kind == ActionKind::Budget @ FastVoteError::InvalidBudgetAction
+    )]
+    pub cancel_action: Account<'info, FastAction>,
+}
@@ -0,0 +1,214 @@
+// Result callbacks. An action's authority may register up to MAX_CALLBACKS
+// downstream programs before the action is finalized; once the result has
+// settled, execute_action calls each of them with the result so they can
+// react without polling. Each entry pins its account list by hash, so
+// whoever cranks execute_action cannot swap in other accounts, and the
+// executor must have staged the call a slot earlier (see staging).
+//
+// Callees receive the action account first, then their registered accounts,
+// with instruction data CALLBACK_DISCRIMINATOR followed by a borsh
+// ResultPayload; an Anchor program can implement this as
+// `on_vote_result(payload: ResultPayload)`. The payload is a convenience:
+// callees should read the result from the action account.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke;
+
+use crate::dispute::is_settled;
+use crate::keeper::pay_keeper;
+use crate::staging::check_stage;
+use crate::{
+    market, Callback, CallbackRegistered, CallbackRegistry, CallbacksDispatched, ExecutionSchedule, ExecutionStage,
+    FastAction, FastVoteError, ResultPayload, VoteResult, CALLBACK_DISCRIMINATOR, CALLBACK_REGISTRY_SEED,
+    EXECUTION_SCHEDULE_SEED, EXECUTION_STAGE_SEED, FAST_ACTION_SEED, FAST_ACTION_VERSION, MAX_CALLBACKS,
+};
+
+/// sha256 over each account's key followed by 1 if writable, else 0
+pub fn accounts_hash(accounts: &[AccountMeta]) -> [u8; 32] {
+    let entries: Vec<[u8; 33]> = accounts
+        .iter()
+        .map(|meta| {
+            let mut entry = [0u8; 33];
+            entry[..32].copy_from_slice(meta.pubkey.as_ref());
+            entry[32] = meta.is_writable as u8;
+            entry
+        })
+        .collect();
+    let slices: Vec<&[u8]> = entries.iter().map(|e| &e[..]).collect();
+    hashv(&slices).to_bytes()
+}
+
+pub fn process_register_callback(
+    ctx: Context<RegisterCallback>,
+    program_id: Pubkey,
+    accounts_hash: [u8; 32],
+    account_count: u8,
+) -> Result<()> {
+    require!(!ctx.accounts.fast_action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(
+        program_id != crate::ID && program_id != Pubkey::default(),
+        FastVoteError::InvalidCallback
+    );
+
+    let registry = &mut ctx.accounts.registry;
+    require!((registry.count as usize) < MAX_CALLBACKS, FastVoteError::TooManyCallbacks);
+    registry.action = ctx.accounts.fast_action.key();
+    registry.bump = ctx.bumps.registry;
+    let index = registry.count;
+    registry.callbacks[index as usize] = Callback {
+        program_id,
+        accounts_hash,
+        account_count,
+    };
+    registry.count += 1;
+
+    emit_event!(ctx, CallbackRegistered {
+        action: registry.action,
+        hive: ctx.accounts.fast_action.hive,
+        index,
+        program_id,
+        accounts_hash,
+    });
+
+    Ok(())
+}
+
+/// Once, after the result settles, by an executor that staged these
+/// remaining accounts in an earlier slot. They hold, for each callback in
+/// order, its program followed by its registered accounts. The stage's rent
+/// goes back to the executor; with the action's ExecutionSchedule, `keeper`
+/// is paid from it.
+pub fn process_execute_action<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteAction<'info>>) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    let slot = Clock::get()?.slot;
+    require!(is_settled(action, slot), FastVoteError::DisputeWindowOpen);
+    require!(
+        !matches!(action.result, VoteResult::Contested { .. }),
+        FastVoteError::ContestUnresolved
+    );
+    if matches!(action.result, VoteResult::Passed { .. }) {
+        require!(market::is_executable(action, slot), FastVoteError::MarketGateNotCleared);
+    }
+    check_stage(&ctx.accounts.stage, ctx.remaining_accounts, slot)?;
+    let registry = &mut ctx.accounts.registry;
+    require!(!registry.dispatched, FastVoteError::CallbacksDispatched);
+    registry.dispatched = true;
+
+    let mut data = CALLBACK_DISCRIMINATOR.to_vec();
+    ResultPayload {
+        action: action.key(),
+        action_id: action.action_id,
+        action_hash: action.action_hash,
+        result: action.result.clone(),
+        finalized_slot: action.finalized_slot,
+        result_digest: action.result_digest,
+    }
+    .serialize(&mut data)?;
+
+    let action_info = action.to_account_info();
+    let mut remaining = ctx.remaining_accounts;
+    for callback in &registry.callbacks[..registry.count as usize] {
+        let count = callback.account_count as usize;
+        require!(remaining.len() > count, FastVoteError::InvalidCallbackAccounts);
+        let (program, rest) = remaining.split_first().unwrap();
+        let (accounts, rest) = rest.split_at(count);
+        remaining = rest;
+        require_keys_eq!(program.key(), callback.program_id, FastVoteError::InvalidCallbackAccounts);
+
+        let mut metas = vec![AccountMeta::new_readonly(action_info.key(), false)];
+        let registered: Vec<AccountMeta> = accounts
+            .iter()
+            .map(|info| AccountMeta {
+                pubkey: info.key(),
+                is_signer: false,
+                is_writable: info.is_writable,
+            })
+            .collect();
+        require!(
+            accounts_hash(&registered) == callback.accounts_hash,
+            FastVoteError::InvalidCallbackAccounts
+        );
+        metas.extend(registered);
+
+        let mut infos = vec![action_info.clone()];
+        infos.extend(accounts.iter().cloned());
+        infos.push(program.clone());
+        invoke(
+            &Instruction {
+                program_id: callback.program_id,
+                accounts: metas,
+                data: data.clone(),
+            },
+            &infos,
+        )?;
+    }
+
+    emit_event!(ctx, CallbacksDispatched {
+        action: action.key(),
+        hive: action.hive,
+        action_id: action.action_id,
+        callbacks: registry.count,
+    });
+    if let Some(schedule) = &ctx.accounts.schedule {
+        let paid = pay_keeper(schedule, action.hive, ctx.accounts.keeper.as_ref())?;
+        emit_event!(ctx, paid);
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RegisterCallback<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        has_one = authority @ FastVoteError::Unauthorized,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = CallbackRegistry::LEN,
+        seeds = [CALLBACK_REGISTRY_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, CallbackRegistry>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ExecuteAction<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        mut,
+        seeds = [CALLBACK_REGISTRY_SEED, fast_action.key().as_ref()],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, CallbackRegistry>,
+    #[account(
+        mut,
+        close = executor,
+        seeds = [EXECUTION_STAGE_SEED, fast_action.key().as_ref(), executor.key().as_ref()],
+        bump = stage.bump
+    )]
+    pub stage: Account<'info, ExecutionStage>,
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    #[account(mut, seeds = [EXECUTION_SCHEDULE_SEED, fast_action.key().as_ref()], bump = schedule.bump)]
+    pub schedule: Option<Account<'info, ExecutionSchedule>>,
+    /// CHECK: Receives the schedule's balance; any account the sender names
+    #[account(mut)]
+    pub keeper: Option<UncheckedAccount<'info>>,
+}
@@ -0,0 +1,74 @@
+// Intermediate commits from the ephemeral rollup, so a crashed ER session
+// loses at most one interval of votes
+
+use anchor_lang::prelude::*;
+use ephemeral_rollups_sdk::consts::{MAGIC_CONTEXT_ID, MAGIC_PROGRAM_ID};
+use ephemeral_rollups_sdk::ephem::commit_accounts;
+
+use crate::{
+    ActionCheckpointed, FastAction, FastVoteError, LogTag, CHECKPOINT_SLOT_INTERVAL, CHECKPOINT_VOTE_INTERVAL,
+    FAST_ACTION_SEED, FAST_ACTION_VERSION,
+};
+
+/// Permissionless. Commits the action's current state to the base layer
+/// without undelegating, once enough votes or slots have passed.
+pub fn process_checkpoint(ctx: Context<Checkpoint>) -> Result<()> {
+    let action = &mut ctx.accounts.fast_action;
+    let slot = Clock::get()?.slot;
+
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+
+    let votes_since = action.vote_count.saturating_sub(action.last_checkpoint_votes);
+    let slots_since = slot.saturating_sub(action.last_checkpoint_slot);
+    require!(
+        votes_since >= CHECKPOINT_VOTE_INTERVAL || slots_since >= CHECKPOINT_SLOT_INTERVAL,
+        FastVoteError::CheckpointTooSoon
+    );
+
+    action.last_checkpoint_slot = slot;
+    action.last_checkpoint_votes = action.vote_count;
+    let votes = action.vote_count as u64;
+    action.log.push(slot, LogTag::Checkpointed, votes);
+    action.exit(&crate::ID)?;
+
+    commit_accounts(
+        &ctx.accounts.payer,
+        vec![&action.to_account_info()],
+        &ctx.accounts.magic_context,
+        &ctx.accounts.magic_program,
+    )?;
+
+    emit_event!(ctx, ActionCheckpointed {
+        action: action.key(),
+        hive: action.hive,
+        action_id: action.action_id,
+        vote_count: action.vote_count,
+        votes_for: action.votes_for,
+        votes_against: action.votes_against,
+        weight_for: action.weight_for,
+        weight_against: action.weight_against,
+        slot,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct Checkpoint<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: MagicBlock context - validated via address constraint
+    #[account(mut, address = MAGIC_CONTEXT_ID @ FastVoteError::InvalidMagicContext)]
+    pub magic_context: AccountInfo<'info>,
+    /// CHECK: MagicBlock program - validated via address constraint
+    #[account(address = MAGIC_PROGRAM_ID @ FastVoteError::InvalidMagicBlockProgram)]
+    pub magic_program: AccountInfo<'info>,
+}
@@ -0,0 +1,80 @@
+// Compact ballot encoding. vote_fast spends 53 bytes of instruction data on
+// an 8-byte discriminator, the action id, a bool and a full commitment;
+// vote_compact carries the same ballot in 7:
+//
+//   [VOTE_COMPACT_DISCRIMINATOR] [ballot: u8] [delegation_epoch: u32]
+//   [commitment: Option<[u8; 16]>]
+//
+// The action id is dropped (the action's address already fixes it), the vote
+// value and option index share one byte, and the commitment is either 16
+// bytes or left out. With the shared accounts in an address lookup table a
+// ballot then costs about 20 bytes of a transaction; see the client's
+// `vote_lookup_addresses`.
+//
+// The ballot itself takes tally_region's path unchanged.
+
+use anchor_lang::prelude::*;
+
+use crate::{tally_region, FastVoteError, VoteFast, MAX_OPTIONS};
+
+/// Bit 0 of a packed ballot: the vote value
+const VOTE_BIT: u8 = 0b1;
+/// Bits 1-4: the option index plus one, or 0 for a for/against ballot
+const OPTION_SHIFT: u32 = 1;
+const OPTION_MASK: u8 = 0b1_1110;
+
+/// A ballot as vote_compact packs it into one byte
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BallotChoice {
+    ForAgainst(bool),
+    /// Headcount multi-option actions only
+    Option(u8),
+}
+
+impl BallotChoice {
+    /// An option index must be below MAX_OPTIONS
+    pub fn pack(self) -> u8 {
+        match self {
+            BallotChoice::ForAgainst(vote_value) => vote_value as u8,
+            BallotChoice::Option(option) => (option + 1) << OPTION_SHIFT,
+        }
+    }
+
+    pub fn unpack(ballot: u8) -> Result<Self> {
+        require!(ballot & !(VOTE_BIT | OPTION_MASK) == 0, FastVoteError::InvalidCompactBallot);
+        match (ballot & OPTION_MASK) >> OPTION_SHIFT {
+            0 => Ok(BallotChoice::ForAgainst(ballot & VOTE_BIT != 0)),
+            option => {
+                require!(
+                    ballot & VOTE_BIT == 0 && option as usize <= MAX_OPTIONS,
+                    FastVoteError::InvalidCompactBallot
+                );
+                Ok(BallotChoice::Option(option - 1))
+            }
+        }
+    }
+}
+
+/// The commitment a compact ballot records: a truncated one zero-extended, or
+/// the voter's own key when it carries none
+pub fn expand_commitment(voter: &Pubkey, commitment: Option<[u8; 16]>) -> [u8; 32] {
+    match commitment {
+        Some(truncated) => {
+            let mut commitment = [0u8; 32];
+            commitment[..16].copy_from_slice(&truncated);
+            commitment
+        }
+        None => voter.to_bytes(),
+    }
+}
+
+pub fn process_vote_compact(
+    ctx: Context<VoteFast>,
+    ballot: u8,
+    delegation_epoch: u32,
+    commitment: Option<[u8; 16]>,
+) -> Result<()> {
+    let choice = BallotChoice::unpack(ballot)?;
+    let voter_commitment = expand_commitment(&ctx.accounts.voter.key(), commitment);
+    tally_region::cast_ballot(ctx, None, choice, voter_commitment, delegation_epoch)
+}
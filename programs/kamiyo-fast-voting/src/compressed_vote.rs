@@ -0,0 +1,606 @@
+// Compressed ballots. A FastVote PDA costs its voter rent for as long as
+// the account lives, which adds up on actions with thousands of agents. An
+// action's authority may instead switch it, before the first vote, to ZK-
+// compressed ballots: vote_fast_compressed counts the ballot exactly as
+// vote_fast would, then writes the FastVote as a compressed account through
+// the Light system program rather than into a PDA. The compressed address is
+// derived from the same seeds as the FastVote PDA in the address tree pinned
+// when ballots were enabled, so the tree's non-inclusion proof is what stops
+// a second ballot. As there, the seeds name the agent, not the key that
+// signed, so an agent's vote keys share one address; the CompressedBallots marker makes vote_fast turn the
+// action away, so the two sets never mix.
+//
+// Enabling compressed ballots also marks the action audited: it is tallied
+// only once verify_compressed_tally_page has recounted every ballot into its
+// TallyAudit, as verify_tally_page does for PDA ballots. Each page passes its
+// ballots as inputs to invoke_cpi with one validity proof, so Light checks
+// they are in the state tree, and writes them back unchanged. Pages list
+// ballots in ascending address order, across pages too, so none is counted
+// twice.
+//
+// Scope. Light runs on the base layer only, so compressed actions are voted
+// undelegated. Public headcount and optimistic for/against actions only:
+// weighted ballots still read token accounts and option ballots keep their
+// own records. Ballots can be read back from any Photon indexer by address.
+//
+// The Light SDK is not a dependency; invoke_cpi is built by hand below, in
+// the layout of light-system-program's InstructionDataInvokeCpi.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::keccak::hashv;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::Discriminator;
+
+use crate::{
+    agent_registry, freeze, metrics, record_vote, vote_credits, AddressProof, CompressedBallot, CompressedBallots,
+    CompressedBallotsEnabled, CompressedProof, CompressedVoteCast, Counter, FastAction, FastVote, FastVoteError,
+    GlobalConfig, PrivacyLevel, TallyAudit, TallyPageVerified, VoteCredits, VoteMode, AGENT_SEED,
+    COMPRESSED_BALLOTS_SEED, FAST_ACTION_SEED, FAST_ACTION_VERSION, FAST_VOTE_SEED, GLOBAL_CONFIG_SEED,
+    HIVE_FREEZE_SEED, LIGHT_ACCOUNT_COMPRESSION_PROGRAM_ID, LIGHT_CPI_AUTHORITY_SEED, LIGHT_SYSTEM_PROGRAM_ID,
+    SPL_NOOP_PROGRAM_ID, TALLY_AUDIT_SEED, VOTE_CREDITS_SEED, VOTE_KEY_SEED,
+};
+
+/// Anchor discriminator of the Light system program's `invoke_cpi`
+const INVOKE_CPI_DISCRIMINATOR: [u8; 8] = [49, 212, 191, 129, 39, 194, 43, 196];
+
+// Indexes into invoke_cpi's remaining accounts: after a ballot the address
+// tree, its queue and the state tree; after a recount page the state tree
+// and its nullifier queue
+const ADDRESS_TREE_INDEX: u8 = 0;
+const ADDRESS_QUEUE_INDEX: u8 = 1;
+const STATE_TREE_INDEX: u8 = 2;
+const RECOUNT_TREE_INDEX: u8 = 0;
+const RECOUNT_QUEUE_INDEX: u8 = 1;
+
+#[derive(AnchorSerialize)]
+struct NewAddressParams {
+    seed: [u8; 32],
+    address_queue_account_index: u8,
+    address_merkle_tree_account_index: u8,
+    address_merkle_tree_root_index: u16,
+}
+
+#[derive(AnchorSerialize)]
+struct CompressedAccountData {
+    discriminator: [u8; 8],
+    data: Vec<u8>,
+    data_hash: [u8; 32],
+}
+
+#[derive(AnchorSerialize)]
+struct CompressedAccount {
+    owner: Pubkey,
+    lamports: u64,
+    address: Option<[u8; 32]>,
+    data: Option<CompressedAccountData>,
+}
+
+#[derive(AnchorSerialize)]
+struct PackedMerkleContext {
+    merkle_tree_pubkey_index: u8,
+    nullifier_queue_pubkey_index: u8,
+    leaf_index: u32,
+    prove_by_index: bool,
+}
+
+#[derive(AnchorSerialize)]
+struct InputCompressedAccount {
+    compressed_account: CompressedAccount,
+    merkle_context: PackedMerkleContext,
+    root_index: u16,
+    read_only: bool,
+}
+
+#[derive(AnchorSerialize)]
+struct OutputCompressedAccount {
+    compressed_account: CompressedAccount,
+    merkle_tree_index: u8,
+}
+
+/// InstructionDataInvokeCpi with the fields this program never sets written
+/// as empty: no lamports moved, no CPI context.
+#[derive(AnchorSerialize)]
+struct InvokeCpi {
+    proof: Option<CompressedProof>,
+    new_address_params: Vec<NewAddressParams>,
+    input_compressed_accounts: Vec<InputCompressedAccount>,
+    output_compressed_accounts: Vec<OutputCompressedAccount>,
+    relay_fee: Option<u64>,
+    compress_or_decompress_lamports: Option<u64>,
+    is_compress: bool,
+    cpi_context: Option<u8>,
+}
+
+/// Keccak of `bytes`, truncated into the BN254 field as Light hashes are
+pub fn hashv_to_bn254(bytes: &[&[u8]]) -> [u8; 32] {
+    let mut hash = hashv(bytes).to_bytes();
+    hash[0] = 0;
+    hash
+}
+
+/// Address seed of `voter`'s compressed ballot on `action`
+pub fn compressed_vote_seed(action: &Pubkey, voter: &Pubkey) -> [u8; 32] {
+    hashv_to_bn254(&[crate::ID.as_ref(), FAST_VOTE_SEED, action.as_ref(), voter.as_ref()])
+}
+
+/// Compressed address of `voter`'s ballot on `action` in `address_tree`
+pub fn compressed_vote_address(address_tree: &Pubkey, action: &Pubkey, voter: &Pubkey) -> [u8; 32] {
+    hashv_to_bn254(&[address_tree.as_ref(), &compressed_vote_seed(action, voter)])
+}
+
+/// The compressed account holding `vote` at `address`
+fn ballot_account(address: [u8; 32], vote: &FastVote) -> Result<CompressedAccount> {
+    let data = vote.try_to_vec()?;
+    Ok(CompressedAccount {
+        owner: crate::ID,
+        lamports: 0,
+        address: Some(address),
+        data: Some(CompressedAccountData {
+            discriminator: FastVote::DISCRIMINATOR.try_into().unwrap(),
+            data_hash: hashv_to_bn254(&[&data]),
+            data,
+        }),
+    })
+}
+
+/// Accounts every invoke_cpi takes ahead of its trees
+struct LightAccounts<'a, 'info> {
+    fee_payer: &'a AccountInfo<'info>,
+    cpi_authority: &'a AccountInfo<'info>,
+    cpi_authority_bump: u8,
+    registered_program_pda: &'a AccountInfo<'info>,
+    noop_program: &'a AccountInfo<'info>,
+    account_compression_authority: &'a AccountInfo<'info>,
+    account_compression_program: &'a AccountInfo<'info>,
+    self_program: &'a AccountInfo<'info>,
+    system_program: &'a AccountInfo<'info>,
+    light_system_program: &'a AccountInfo<'info>,
+}
+
+/// Send `instruction` to the Light system program as this program, with
+/// `trees` as its remaining accounts
+fn invoke_light<'info>(
+    accounts: LightAccounts<'_, 'info>,
+    trees: &[AccountInfo<'info>],
+    instruction: &InvokeCpi,
+) -> Result<()> {
+    let mut data = INVOKE_CPI_DISCRIMINATOR.to_vec();
+    instruction.try_to_vec()?.serialize(&mut data)?;
+
+    let light = accounts.light_system_program.key();
+    let mut metas = vec![
+        AccountMeta::new(accounts.fee_payer.key(), true),
+        AccountMeta::new_readonly(accounts.cpi_authority.key(), true),
+        AccountMeta::new_readonly(accounts.registered_program_pda.key(), false),
+        AccountMeta::new_readonly(accounts.noop_program.key(), false),
+        AccountMeta::new_readonly(accounts.account_compression_authority.key(), false),
+        AccountMeta::new_readonly(accounts.account_compression_program.key(), false),
+        AccountMeta::new_readonly(crate::ID, false),
+        // Unused optional accounts are passed as the Light system program
+        AccountMeta::new_readonly(light, false),
+        AccountMeta::new_readonly(light, false),
+        AccountMeta::new_readonly(accounts.system_program.key(), false),
+        AccountMeta::new_readonly(light, false),
+    ];
+    metas.extend(trees.iter().map(|t| AccountMeta::new(t.key(), false)));
+    let mut infos = vec![
+        accounts.fee_payer.clone(),
+        accounts.cpi_authority.clone(),
+        accounts.registered_program_pda.clone(),
+        accounts.noop_program.clone(),
+        accounts.account_compression_authority.clone(),
+        accounts.account_compression_program.clone(),
+        accounts.self_program.clone(),
+        accounts.system_program.clone(),
+        accounts.light_system_program.clone(),
+    ];
+    infos.extend_from_slice(trees);
+
+    invoke_signed(
+        &Instruction {
+            program_id: light,
+            accounts: metas,
+            data,
+        },
+        &infos,
+        &[&[LIGHT_CPI_AUTHORITY_SEED, &[accounts.cpi_authority_bump]]],
+    )?;
+    Ok(())
+}
+
+/// Action authority only, before the first vote. Light checks
+/// `address_tree` at the first ballot.
+pub fn process_enable_compressed_ballots(ctx: Context<EnableCompressedBallots>, address_tree: Pubkey) -> Result<()> {
+    let action = &mut ctx.accounts.fast_action;
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(action.vote_count == 0, FastVoteError::ActionHasVotes);
+    require!(
+        action.vote_mode != VoteMode::TokenWeighted
+            && action.option_count == 0
+            && action.member_collection == Pubkey::default()
+            && action.privacy == PrivacyLevel::Public
+            && address_tree != Pubkey::default(),
+        FastVoteError::InvalidCompressedBallots
+    );
+    action.audited = true;
+
+    let marker = &mut ctx.accounts.compressed_ballots;
+    marker.action = action.key();
+    marker.bump = ctx.bumps.compressed_ballots;
+    marker.address_tree = address_tree;
+
+    emit_event!(ctx, CompressedBallotsEnabled {
+        action: marker.action,
+        hive: action.hive,
+        action_id: action.action_id,
+        address_tree,
+    });
+
+    Ok(())
+}
+
+/// vote_fast for an action with compressed ballots. `proof` shows the
+/// ballot's address is new in the address tree.
+pub fn process_vote_fast_compressed(
+    ctx: Context<VoteFastCompressed>,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+    delegation_epoch: u32,
+    proof: AddressProof,
+) -> Result<()> {
+    ctx.accounts.fast_action.check_delegation_epoch(delegation_epoch)?;
+    freeze::require_live(&ctx.accounts.hive_freeze, &ctx.accounts.fast_action)?;
+    if ctx.accounts.fast_action.vote_mode == VoteMode::Optimistic {
+        require!(!vote_value, FastVoteError::OptimisticVetoOnly);
+    }
+
+    // The agent's, whichever of its keys signed
+    let voter = ctx.accounts.agent.key();
+    agent_registry::require_eligible(&ctx.accounts.agent_record, &ctx.accounts.config)?;
+    agent_registry::count_class_vote(&mut ctx.accounts.fast_action, &ctx.accounts.agent_record)?;
+    vote_credits::charge_ballot(
+        &mut ctx.accounts.vote_credits,
+        voter,
+        ctx.bumps.vote_credits,
+        &ctx.accounts.config,
+    )?;
+
+    let action_key = ctx.accounts.fast_action.key();
+    let mut vote = FastVote {
+        fast_action: Pubkey::default(),
+        voter: Pubkey::default(),
+        voter_commitment: [0; 32],
+        vote_value: false,
+        voted_slot: 0,
+        bump: 0,
+        weight: 0,
+        reason_hash: [0; 32],
+    };
+    let event = record_vote(
+        &mut ctx.accounts.fast_action,
+        &mut vote,
+        voter,
+        // No PDA behind a compressed ballot
+        0,
+        vote_value,
+        voter_commitment,
+        1,
+    )?;
+    metrics::record(&ctx.accounts.metrics, &ctx.accounts.fast_action.hive, Counter::Vote, Clock::get()?.slot)?;
+
+    let address_tree = ctx.accounts.address_tree.key();
+    let address = compressed_vote_address(&address_tree, &action_key, &voter);
+    let instruction = InvokeCpi {
+        proof: Some(proof.proof),
+        new_address_params: vec![NewAddressParams {
+            seed: compressed_vote_seed(&action_key, &voter),
+            address_queue_account_index: ADDRESS_QUEUE_INDEX,
+            address_merkle_tree_account_index: ADDRESS_TREE_INDEX,
+            address_merkle_tree_root_index: proof.root_index,
+        }],
+        input_compressed_accounts: Vec::new(),
+        output_compressed_accounts: vec![OutputCompressedAccount {
+            compressed_account: ballot_account(address, &vote)?,
+            merkle_tree_index: STATE_TREE_INDEX,
+        }],
+        relay_fee: None,
+        compress_or_decompress_lamports: None,
+        is_compress: false,
+        cpi_context: None,
+    };
+
+    let accounts = &ctx.accounts;
+    invoke_light(
+        LightAccounts {
+            fee_payer: &accounts.voter.to_account_info(),
+            cpi_authority: &accounts.cpi_authority.to_account_info(),
+            cpi_authority_bump: ctx.bumps.cpi_authority,
+            registered_program_pda: &accounts.registered_program_pda.to_account_info(),
+            noop_program: &accounts.noop_program.to_account_info(),
+            account_compression_authority: &accounts.account_compression_authority.to_account_info(),
+            account_compression_program: &accounts.account_compression_program.to_account_info(),
+            self_program: &accounts.self_program.to_account_info(),
+            system_program: &accounts.system_program.to_account_info(),
+            light_system_program: &accounts.light_system_program.to_account_info(),
+        },
+        &[
+            accounts.address_tree.to_account_info(),
+            accounts.address_queue.to_account_info(),
+            accounts.state_tree.to_account_info(),
+        ],
+        &instruction,
+    )?;
+
+    emit_event!(ctx, event);
+    emit_event!(ctx, CompressedVoteCast {
+        action: action_key,
+        hive: ctx.accounts.fast_action.hive,
+        voter,
+        address,
+        state_tree: ctx.accounts.state_tree.key(),
+    });
+
+    Ok(())
+}
+
+/// Permissionless once voting has ended: recount a page of the action's
+/// compressed ballots, all in `state_tree`, against one validity proof
+pub fn process_verify_compressed_tally_page(
+    ctx: Context<VerifyCompressedTallyPage>,
+    ballots: Vec<CompressedBallot>,
+    proof: CompressedProof,
+) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(Clock::get()?.slot > action.deadline_slot, FastVoteError::VotingNotEnded);
+    require!(!ballots.is_empty(), FastVoteError::InvalidTallyPage);
+
+    let action_key = action.key();
+    let address_tree = ctx.accounts.compressed_ballots.address_tree;
+    let audit = &mut ctx.accounts.tally_audit;
+    if audit.action == Pubkey::default() {
+        audit.action = action_key;
+        audit.bump = ctx.bumps.tally_audit;
+    }
+
+    let mut inputs = Vec::with_capacity(ballots.len());
+    let mut outputs = Vec::with_capacity(ballots.len());
+    for ballot in &ballots {
+        let address = compressed_vote_address(&address_tree, &action_key, &ballot.voter);
+        require!(
+            Pubkey::new_from_array(address) > audit.last_vote,
+            FastVoteError::InvalidTallyPage
+        );
+        let vote = FastVote {
+            fast_action: action_key,
+            voter: ballot.voter,
+            voter_commitment: ballot.voter_commitment,
+            vote_value: ballot.vote_value,
+            voted_slot: ballot.voted_slot,
+            bump: 0,
+            weight: 1,
+            reason_hash: [0; 32],
+        };
+
+        audit.last_vote = Pubkey::new_from_array(address);
+        audit.vote_count = audit.vote_count.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+        if vote.vote_value {
+            audit.votes_for = audit.votes_for.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+        } else {
+            audit.votes_against = audit.votes_against.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+        }
+
+        inputs.push(InputCompressedAccount {
+            compressed_account: ballot_account(address, &vote)?,
+            merkle_context: PackedMerkleContext {
+                merkle_tree_pubkey_index: RECOUNT_TREE_INDEX,
+                nullifier_queue_pubkey_index: RECOUNT_QUEUE_INDEX,
+                leaf_index: ballot.leaf_index,
+                prove_by_index: false,
+            },
+            root_index: ballot.root_index,
+            read_only: false,
+        });
+        // Written back as it was, so the ballot stays readable at its address
+        outputs.push(OutputCompressedAccount {
+            compressed_account: ballot_account(address, &vote)?,
+            merkle_tree_index: RECOUNT_TREE_INDEX,
+        });
+    }
+
+    let instruction = InvokeCpi {
+        proof: Some(proof),
+        new_address_params: Vec::new(),
+        input_compressed_accounts: inputs,
+        output_compressed_accounts: outputs,
+        relay_fee: None,
+        compress_or_decompress_lamports: None,
+        is_compress: false,
+        cpi_context: None,
+    };
+    let accounts = &ctx.accounts;
+    invoke_light(
+        LightAccounts {
+            fee_payer: &accounts.payer.to_account_info(),
+            cpi_authority: &accounts.cpi_authority.to_account_info(),
+            cpi_authority_bump: ctx.bumps.cpi_authority,
+            registered_program_pda: &accounts.registered_program_pda.to_account_info(),
+            noop_program: &accounts.noop_program.to_account_info(),
+            account_compression_authority: &accounts.account_compression_authority.to_account_info(),
+            account_compression_program: &accounts.account_compression_program.to_account_info(),
+            self_program: &accounts.self_program.to_account_info(),
+            system_program: &accounts.system_program.to_account_info(),
+            light_system_program: &accounts.light_system_program.to_account_info(),
+        },
+        &[accounts.state_tree.to_account_info(), accounts.nullifier_queue.to_account_info()],
+        &instruction,
+    )?;
+
+    let action = &ctx.accounts.fast_action;
+    emit_event!(ctx, TallyPageVerified {
+        action: action_key,
+        hive: action.hive,
+        verified: ctx.accounts.tally_audit.vote_count,
+        complete: ctx.accounts.tally_audit.matches(action),
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct EnableCompressedBallots<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        has_one = authority @ FastVoteError::Unauthorized,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = authority,
+        space = CompressedBallots::LEN,
+        seeds = [COMPRESSED_BALLOTS_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub compressed_ballots: Account<'info, CompressedBallots>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(action_id: u64)]
+pub struct VoteFastCompressed<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        seeds = [COMPRESSED_BALLOTS_SEED, fast_action.key().as_ref()],
+        bump = compressed_ballots.bump
+    )]
+    pub compressed_ballots: Account<'info, CompressedBallots>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoteCredits::LEN,
+        seeds = [VOTE_CREDITS_SEED, agent.key().as_ref()],
+        bump
+    )]
+    pub vote_credits: Account<'info, VoteCredits>,
+    /// CHECK: The agent's AgentRecord PDA; read in agent_registry if it exists
+    #[account(seeds = [AGENT_SEED, agent.key().as_ref()], bump)]
+    pub agent_record: UncheckedAccount<'info>,
+    /// CHECK: The voter's VoteKey PDA; if it exists the voter signs for the
+    /// agent it names
+    #[account(seeds = [VOTE_KEY_SEED, voter.key().as_ref()], bump)]
+    pub vote_key: UncheckedAccount<'info>,
+    /// CHECK: The agent the ballot is cast for: the voter itself, or the
+    /// agent its vote key is bound to
+    #[account(
+        constraint = agent.key() == agent_registry::identity_of(&vote_key, voter.key)? @ FastVoteError::AgentMismatch
+    )]
+    pub agent: UncheckedAccount<'info>,
+    /// Signs and pays; the agent itself or one of its vote keys
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// CHECK: Signs invoke_cpi; holds nothing
+    #[account(seeds = [LIGHT_CPI_AUTHORITY_SEED], bump)]
+    pub cpi_authority: UncheckedAccount<'info>,
+    /// CHECK: This program's registration with account compression; checked by Light
+    pub registered_program_pda: UncheckedAccount<'info>,
+    /// CHECK: SPL noop
+    #[account(address = SPL_NOOP_PROGRAM_ID)]
+    pub noop_program: UncheckedAccount<'info>,
+    /// CHECK: Light's account compression authority; checked by Light
+    pub account_compression_authority: UncheckedAccount<'info>,
+    /// CHECK: Light account compression
+    #[account(address = LIGHT_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub account_compression_program: UncheckedAccount<'info>,
+    /// CHECK: This program, as the invoking program
+    #[account(address = crate::ID)]
+    pub self_program: UncheckedAccount<'info>,
+    /// CHECK: The address tree pinned for the action; checked by Light
+    #[account(mut, address = compressed_ballots.address_tree @ FastVoteError::InvalidAddressTree)]
+    pub address_tree: UncheckedAccount<'info>,
+    /// CHECK: That tree's queue; checked by Light
+    #[account(mut)]
+    pub address_queue: UncheckedAccount<'info>,
+    /// CHECK: State tree the ballot is appended to; checked by Light
+    #[account(mut)]
+    pub state_tree: UncheckedAccount<'info>,
+    /// CHECK: Light system program
+    #[account(address = LIGHT_SYSTEM_PROGRAM_ID)]
+    pub light_system_program: UncheckedAccount<'info>,
+    /// CHECK: The ER's Metrics PDA, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
+    /// CHECK: The HiveFreeze PDA of the action's hive, whether or not it
+    /// exists; read in freeze::require_live
+    #[account(seeds = [HIVE_FREEZE_SEED, fast_action.hive.as_ref()], bump)]
+    pub hive_freeze: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct VerifyCompressedTallyPage<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        seeds = [COMPRESSED_BALLOTS_SEED, fast_action.key().as_ref()],
+        bump = compressed_ballots.bump
+    )]
+    pub compressed_ballots: Account<'info, CompressedBallots>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = TallyAudit::LEN,
+        seeds = [TALLY_AUDIT_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub tally_audit: Account<'info, TallyAudit>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Signs invoke_cpi; holds nothing
+    #[account(seeds = [LIGHT_CPI_AUTHORITY_SEED], bump)]
+    pub cpi_authority: UncheckedAccount<'info>,
+    /// CHECK: This program's registration with account compression; checked by Light
+    pub registered_program_pda: UncheckedAccount<'info>,
+    /// CHECK: SPL noop
+    #[account(address = SPL_NOOP_PROGRAM_ID)]
+    pub noop_program: UncheckedAccount<'info>,
+    /// CHECK: Light's account compression authority; checked by Light
+    pub account_compression_authority: UncheckedAccount<'info>,
+    /// CHECK: Light account compression
+    #[account(address = LIGHT_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub account_compression_program: UncheckedAccount<'info>,
+    /// CHECK: This program, as the invoking program
+    #[account(address = crate::ID)]
+    pub self_program: UncheckedAccount<'info>,
+    /// CHECK: State tree holding the page's ballots; checked by Light
+    #[account(mut)]
+    pub state_tree: UncheckedAccount<'info>,
+    /// CHECK: That tree's nullifier queue; checked by Light
+    #[account(mut)]
+    pub nullifier_queue: UncheckedAccount<'info>,
+    /// CHECK: Light system program
+    #[account(address = LIGHT_SYSTEM_PROGRAM_ID)]
+    pub light_system_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
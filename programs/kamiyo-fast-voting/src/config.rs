@@ -0,0 +1,150 @@
+// Program-wide configuration PDA, owned by the upgrade authority at init
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+
+use crate::program::KamiyoFastVoting;
+use crate::{
+    ConfigUpdate, ConfigUpdated, FastVoteError, FeeRebate, GlobalConfig, IdentityRamp, DEFAULT_MAX_ACTIONS_PER_EPOCH,
+    DEFAULT_WHISTLEBLOWER_BPS, GLOBAL_CONFIG_SEED, WEIGHT_EXT_ALL,
+};
+
+pub fn process_initialize_config(
+    ctx: Context<InitializeConfig>,
+    weight_mint: Pubkey,
+    weight_extensions: u8,
+    transfer_hook_program: Pubkey,
+) -> Result<()> {
+    require!(weight_extensions & !WEIGHT_EXT_ALL == 0, FastVoteError::InvalidWeightExtensions);
+
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.admin.key();
+    config.weight_mint = weight_mint;
+    config.weight_extensions = weight_extensions;
+    config.transfer_hook_program = transfer_hook_program;
+    config.bump = ctx.bumps.config;
+    config.max_actions_per_epoch = DEFAULT_MAX_ACTIONS_PER_EPOCH;
+    config.guardian = Pubkey::default();
+    config.vote_credits_per_epoch = 0;
+    config.min_agent_bond = 0;
+    config.whistleblower_bps = DEFAULT_WHISTLEBLOWER_BPS;
+    config.snapshotter = Pubkey::default();
+    config.max_window_slots = 0;
+    config.stake_lock_program = Pubkey::default();
+    config.max_low_priority_actions = 0;
+    config.max_votes_per_action = 0;
+    config.identity_ramp = IdentityRamp::default();
+    config.fee_rebate = FeeRebate::default();
+    config.max_actions_per_validator = 0;
+
+    emit_event!(ctx, ConfigUpdated {
+        admin: config.admin,
+        weight_mint,
+        weight_extensions,
+        transfer_hook_program,
+        max_actions_per_epoch: config.max_actions_per_epoch,
+        guardian: config.guardian,
+        vote_credits_per_epoch: config.vote_credits_per_epoch,
+        min_agent_bond: config.min_agent_bond,
+        whistleblower_bps: config.whistleblower_bps,
+        snapshotter: config.snapshotter,
+        max_window_slots: config.max_window_slots,
+        stake_lock_program: config.stake_lock_program,
+        max_low_priority_actions: config.max_low_priority_actions,
+        max_votes_per_action: config.max_votes_per_action,
+        identity_ramp: config.identity_ramp,
+        fee_rebate: config.fee_rebate,
+        max_actions_per_validator: config.max_actions_per_validator,
+    });
+
+    Ok(())
+}
+
+pub fn process_update_config(ctx: Context<UpdateConfig>, update: ConfigUpdate) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.apply(update)?;
+
+    emit_event!(ctx, config.updated());
+
+    Ok(())
+}
+
+/// Permissionless: grows a config created before its latest fields existed.
+/// New fields start zeroed, so vetoes, vote credits, agent bonds,
+/// whistleblower bounties, the snapshotter, stake locks, the low-priority
+/// cap, the identity ramp and fee rebates stay disabled until the admin sets
+/// them, and
+/// the window and ballot caps stay at MAX_VOTING_WINDOW_SLOTS and
+/// MAX_VOTES_PER_ACTION.
+pub fn process_migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+    let info = ctx.accounts.config.to_account_info();
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    require!(GlobalConfig::LEGACY_LENS.contains(&info.data_len()), FastVoteError::AlreadyMigrated);
+    require!(
+        info.try_borrow_data()?.starts_with(GlobalConfig::DISCRIMINATOR),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+
+    let rent = Rent::get()?.minimum_balance(GlobalConfig::LEN);
+    let shortfall = rent.saturating_sub(info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    info.realloc(GlobalConfig::LEN, true)?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = GlobalConfig::LEN,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(constraint = fast_voting_program.programdata_address()? == Some(program_data.key()) @ FastVoteError::Unauthorized)]
+    pub fast_voting_program: Program<'info, KamiyoFastVoting>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ FastVoteError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ FastVoteError::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// CHECK: The legacy layout does not deserialize as GlobalConfig; owner,
+    /// size and discriminator are checked in process_migrate_config
+    #[account(mut, seeds = [GLOBAL_CONFIG_SEED], bump)]
+    pub config: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
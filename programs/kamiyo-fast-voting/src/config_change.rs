@@ -0,0 +1,98 @@
+// Self-hosted configuration. A ConfigChange action commits to one config
+// field and its new value through its action hash; once the action passes,
+// settles and sits out CONFIG_CHANGE_TIMELOCK_SLOTS, anyone may apply the
+// change. The passed action stands in for the admin signature, so the admin
+// key can eventually be handed to governance itself.
+//
+// Only token-weighted majority votes qualify: unscoped headcount votes are
+// open to anyone with a keypair.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    market, ActionKind, ConfigChange, ConfigChangeApplied, ConfigChangeReceipt, FastAction, FastVoteError,
+    GlobalConfig, VoteMode, CONFIG_CHANGE_MIN_THRESHOLD, CONFIG_CHANGE_SEED, CONFIG_CHANGE_TIMELOCK_SLOTS,
+    FAST_ACTION_SEED, FAST_ACTION_VERSION, GLOBAL_CONFIG_SEED,
+};
+
+/// A token-weighted for/against vote with a majority threshold: the bar for
+/// actions that move config, code or funds
+pub fn is_token_majority(threshold: u8, vote_mode: VoteMode, option_count: u8, config: &GlobalConfig) -> bool {
+    threshold >= CONFIG_CHANGE_MIN_THRESHOLD
+        && vote_mode == VoteMode::TokenWeighted
+        && option_count == 0
+        && config.weight_mint != Pubkey::default()
+}
+
+/// ConfigChange constraints on top of the usual creation checks
+pub fn validate_config_change(
+    threshold: u8,
+    vote_mode: VoteMode,
+    option_count: u8,
+    config: &GlobalConfig,
+) -> Result<()> {
+    require!(
+        is_token_majority(threshold, vote_mode, option_count, config),
+        FastVoteError::InvalidConfigChangeAction
+    );
+    Ok(())
+}
+
+/// Permissionless, on the base layer; `payer` covers the receipt.
+pub fn process_apply_config_change(ctx: Context<ApplyConfigChange>, change: ConfigChange) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(
+        change.action_hash()? == action.action_hash,
+        FastVoteError::ConfigChangeMismatch
+    );
+    let slot = Clock::get()?.slot;
+    require!(market::is_executable(action, slot), FastVoteError::ActionNotExecutable);
+    require!(
+        slot >= action.finalized_slot.saturating_add(CONFIG_CHANGE_TIMELOCK_SLOTS),
+        FastVoteError::TimelockActive
+    );
+
+    let config = &mut ctx.accounts.config;
+    config.apply(change.into())?;
+    let updated = config.updated();
+
+    let receipt = &mut ctx.accounts.receipt;
+    receipt.action = action.key();
+    receipt.applied_slot = slot;
+    receipt.bump = ctx.bumps.receipt;
+
+    emit_event!(ctx, ConfigChangeApplied {
+        action: receipt.action,
+        hive: action.hive,
+        action_id: action.action_id,
+        change,
+    });
+    emit_event!(ctx, updated);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ApplyConfigChange<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration,
+        constraint = fast_action.kind == ActionKind::ConfigChange @ FastVoteError::InvalidConfigChangeAction
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(mut, seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(
+        init,
+        payer = payer,
+        space = ConfigChangeReceipt::LEN,
+        seeds = [CONFIG_CHANGE_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, ConfigChangeReceipt>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,220 @@
+// Conviction mode for standing priorities. Agents stake lamports behind a
+// proposal; its conviction moves toward the total staked each slot, keeping
+// `retention_ppb` of the remaining gap, so support builds up while held and
+// decays once withdrawn. There is no deadline: the proposal executes the
+// first time an update finds conviction at or above its threshold.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::{
+    ConvictionExecuted, ConvictionProposal, ConvictionProposalCreated, ConvictionStake, ConvictionStaked,
+    ConvictionWithdrawn, FastVoteError, Role, Roles, CONVICTION_SEED, CONVICTION_STAKE_SEED, PPB, ROLES_SEED,
+};
+
+pub fn process_create_conviction_proposal(
+    ctx: Context<CreateConvictionProposal>,
+    action_hash: [u8; 32],
+    threshold: u64,
+    retention_ppb: u32,
+) -> Result<()> {
+    require!(action_hash != [0u8; 32], FastVoteError::InvalidActionHash);
+    require!(
+        threshold > 0 && retention_ppb > 0 && (retention_ppb as u128) < PPB,
+        FastVoteError::InvalidConvictionParams
+    );
+
+    // Hive-scoped proposals require the Creator role, as actions do
+    let hive = match ctx.accounts.roles.as_ref() {
+        Some(roles) => {
+            require!(
+                roles.has_role(&ctx.accounts.creator.key(), Role::Creator),
+                FastVoteError::MissingRole
+            );
+            roles.hive
+        }
+        None => Pubkey::default(),
+    };
+
+    let slot = Clock::get()?.slot;
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.action_hash = action_hash;
+    proposal.creator = ctx.accounts.creator.key();
+    proposal.hive = hive;
+    proposal.threshold = threshold;
+    proposal.retention_ppb = retention_ppb;
+    proposal.total_staked = 0;
+    proposal.conviction = 0;
+    proposal.last_update_slot = slot;
+    proposal.executed = false;
+    proposal.executed_slot = 0;
+    proposal.bump = ctx.bumps.proposal;
+
+    emit_event!(ctx, ConvictionProposalCreated {
+        proposal: proposal.key(),
+        action_hash,
+        creator: proposal.creator,
+        hive,
+        threshold,
+        retention_ppb,
+    });
+
+    Ok(())
+}
+
+pub fn process_stake_conviction(ctx: Context<StakeConviction>, amount: u64) -> Result<()> {
+    require!(amount > 0, FastVoteError::InvalidStakeAmount);
+    require!(!ctx.accounts.proposal.executed, FastVoteError::ActionAlreadyExecuted);
+    let slot = Clock::get()?.slot;
+    // Conviction up to now counts only the earlier stake
+    let executed = ctx.accounts.proposal.accrue(slot);
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.staker.to_account_info(),
+                to: ctx.accounts.proposal.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let stake = &mut ctx.accounts.stake;
+    stake.proposal = ctx.accounts.proposal.key();
+    stake.staker = ctx.accounts.staker.key();
+    stake.amount = stake.amount.checked_add(amount).ok_or(FastVoteError::VoteOverflow)?;
+    stake.bump = ctx.bumps.stake;
+
+    let staker = stake.staker;
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.total_staked = proposal.total_staked.checked_add(amount).ok_or(FastVoteError::VoteOverflow)?;
+    let staked = ConvictionStaked {
+        proposal: proposal.key(),
+        staker,
+        amount,
+        total_staked: proposal.total_staked,
+        conviction: proposal.conviction,
+    };
+    let crossed = executed.then(|| ConvictionExecuted::at(proposal, slot));
+
+    emit_event!(ctx, staked);
+    if let Some(crossed) = crossed {
+        emit_event!(ctx, crossed);
+    }
+
+    Ok(())
+}
+
+/// Allowed at any time; after execution this just reclaims the stake.
+pub fn process_withdraw_conviction(ctx: Context<WithdrawConviction>, amount: u64) -> Result<()> {
+    let stake = &mut ctx.accounts.stake;
+    require!(amount > 0 && amount <= stake.amount, FastVoteError::InvalidStakeAmount);
+
+    let slot = Clock::get()?.slot;
+    let proposal = &mut ctx.accounts.proposal;
+    let crossed = proposal.accrue(slot).then(|| ConvictionExecuted::at(proposal, slot));
+
+    **proposal.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.staker.to_account_info().try_borrow_mut_lamports()? += amount;
+    stake.amount -= amount;
+    proposal.total_staked -= amount;
+    let withdrawn = ConvictionWithdrawn {
+        proposal: proposal.key(),
+        staker: stake.staker,
+        amount,
+        total_staked: proposal.total_staked,
+        conviction: proposal.conviction,
+    };
+
+    if let Some(crossed) = crossed {
+        emit_event!(ctx, crossed);
+    }
+    emit_event!(ctx, withdrawn);
+
+    Ok(())
+}
+
+/// Permissionless crank: accrues conviction and executes the proposal if it
+/// has crossed its threshold.
+pub fn process_update_conviction(ctx: Context<UpdateConviction>) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let proposal = &mut ctx.accounts.proposal;
+    require!(!proposal.executed, FastVoteError::ActionAlreadyExecuted);
+    if proposal.accrue(slot) {
+        emit_event!(ctx, ConvictionExecuted::at(proposal, slot));
+    }
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(action_hash: [u8; 32])]
+pub struct CreateConvictionProposal<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = ConvictionProposal::LEN,
+        seeds = [CONVICTION_SEED, action_hash.as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, ConvictionProposal>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    /// Present for hive-scoped proposals; creator must hold Role::Creator
+    #[account(seeds = [ROLES_SEED, roles.hive.as_ref()], bump = roles.bump)]
+    pub roles: Option<Account<'info, Roles>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct StakeConviction<'info> {
+    #[account(
+        mut,
+        seeds = [CONVICTION_SEED, proposal.action_hash.as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, ConvictionProposal>,
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = ConvictionStake::LEN,
+        seeds = [CONVICTION_STAKE_SEED, proposal.key().as_ref(), staker.key().as_ref()],
+        bump
+    )]
+    pub stake: Account<'info, ConvictionStake>,
+    #[account(mut)]
+    pub staker: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct WithdrawConviction<'info> {
+    #[account(
+        mut,
+        seeds = [CONVICTION_SEED, proposal.action_hash.as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, ConvictionProposal>,
+    #[account(
+        mut,
+        seeds = [CONVICTION_STAKE_SEED, proposal.key().as_ref(), staker.key().as_ref()],
+        bump = stake.bump
+    )]
+    pub stake: Account<'info, ConvictionStake>,
+    #[account(mut)]
+    pub staker: Signer<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct UpdateConviction<'info> {
+    #[account(
+        mut,
+        seeds = [CONVICTION_SEED, proposal.action_hash.as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, ConvictionProposal>,
+}
@@ -0,0 +1,129 @@
+// Restricted creator mode. A hive's authority, its governance, may switch
+// the hive to restricted creation, after which only the coordinators on its
+// CreatorAllowlist may open its actions; holding Role::Creator is still
+// required on top. create_fast_action takes the allowlist PDA whenever it
+// takes Roles, so a creator cannot dodge the restriction by leaving it out.
+// A hive that never created one stays open to every Creator.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    CreatorAllowed, CreatorAllowlist, CreatorDisallowed, CreatorRestrictionSet, FastVoteError, Hive, Roles,
+    CREATOR_ALLOWLIST_SEED, HIVE_SEED, MAX_ALLOWED_CREATORS,
+};
+
+/// Seed after CREATOR_ALLOWLIST_SEED for the hive of `roles`; empty without
+pub fn allowlist_seed<'a>(roles: &'a Option<Account<Roles>>) -> &'a [u8] {
+    roles.as_ref().map_or(&[], |r| r.hive.as_ref())
+}
+
+/// Checks `creator` against the allowlist of the hive of `roles`, if the
+/// action is hive-scoped
+pub fn check_creator(
+    roles: &Option<Account<Roles>>,
+    allowlist: &Option<UncheckedAccount>,
+    creator: &Pubkey,
+) -> Result<()> {
+    if roles.is_none() {
+        return Ok(());
+    }
+    let allowlist = allowlist.as_ref().ok_or(FastVoteError::CreatorAllowlistRequired)?;
+    require!(CreatorAllowlist::allows(allowlist, creator)?, FastVoteError::CreatorNotAllowed);
+    Ok(())
+}
+
+/// Hive authority only; creates the allowlist on first use
+pub fn process_set_creator_restriction(ctx: Context<SetCreatorRestriction>, restricted: bool) -> Result<()> {
+    let allowlist = &mut ctx.accounts.creator_allowlist;
+    allowlist.hive = ctx.accounts.hive_account.hive;
+    allowlist.restricted = restricted;
+    allowlist.bump = ctx.bumps.creator_allowlist;
+
+    emit_event!(ctx, CreatorRestrictionSet {
+        hive: allowlist.hive,
+        restricted,
+        set_by: ctx.accounts.authority.key(),
+    });
+
+    Ok(())
+}
+
+/// Hive authority only
+pub fn process_allow_creator(ctx: Context<ManageCreatorAllowlist>, creator: Pubkey) -> Result<()> {
+    let allowlist = &mut ctx.accounts.creator_allowlist;
+    require!(!allowlist.creators().contains(&creator), FastVoteError::CreatorAlreadyAllowed);
+    let count = allowlist.count as usize;
+    require!(count < MAX_ALLOWED_CREATORS, FastVoteError::CreatorAllowlistFull);
+    allowlist.creators[count] = creator;
+    allowlist.count += 1;
+
+    emit_event!(ctx, CreatorAllowed {
+        hive: allowlist.hive,
+        creator,
+        allowed_by: ctx.accounts.authority.key(),
+    });
+
+    Ok(())
+}
+
+/// Hive authority only
+pub fn process_disallow_creator(ctx: Context<ManageCreatorAllowlist>, creator: Pubkey) -> Result<()> {
+    let allowlist = &mut ctx.accounts.creator_allowlist;
+    let position = allowlist
+        .creators()
+        .iter()
+        .position(|listed| *listed == creator)
+        .ok_or(FastVoteError::CreatorNotAllowed)?;
+    let last = allowlist.count as usize - 1;
+    allowlist.creators[position] = allowlist.creators[last];
+    allowlist.creators[last] = Pubkey::default();
+    allowlist.count -= 1;
+
+    emit_event!(ctx, CreatorDisallowed {
+        hive: allowlist.hive,
+        creator,
+        disallowed_by: ctx.accounts.authority.key(),
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetCreatorRestriction<'info> {
+    #[account(
+        seeds = [HIVE_SEED, hive_account.hive.as_ref()],
+        bump = hive_account.bump,
+        has_one = authority @ FastVoteError::Unauthorized
+    )]
+    pub hive_account: Account<'info, Hive>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = CreatorAllowlist::LEN,
+        seeds = [CREATOR_ALLOWLIST_SEED, hive_account.hive.as_ref()],
+        bump
+    )]
+    pub creator_allowlist: Account<'info, CreatorAllowlist>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ManageCreatorAllowlist<'info> {
+    #[account(
+        seeds = [HIVE_SEED, hive_account.hive.as_ref()],
+        bump = hive_account.bump,
+        has_one = authority @ FastVoteError::Unauthorized
+    )]
+    pub hive_account: Account<'info, Hive>,
+    #[account(
+        mut,
+        seeds = [CREATOR_ALLOWLIST_SEED, hive_account.hive.as_ref()],
+        bump = creator_allowlist.bump
+    )]
+    pub creator_allowlist: Account<'info, CreatorAllowlist>,
+    pub authority: Signer<'info>,
+}
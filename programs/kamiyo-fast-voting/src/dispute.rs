@@ -0,0 +1,260 @@
+// Dispute window on tallied results. For DISPUTE_WINDOW_SLOTS after
+// finalization anyone may submit a fraud proof; evidence is checked on-chain,
+// so a bad challenge just reverts. A good one voids the result and slashes the
+// losing side, the validator that committed it; the challenger takes the
+// whistleblower share and the treasury the rest. A ballot the validator
+// sequenced but left out of the tally is proven through challenge_exclusion
+// instead; see sequencing. On a redundantly delegated action, the other
+// validator's set-aside commit can be evidence too; see redundancy.
+
+use anchor_lang::prelude::*;
+use std::collections::BTreeSet;
+
+use crate::slashing::pay_slash;
+use crate::{
+    attestation_digest, tally, ActionMirror, Evidence, FastAction, FastVote, FastVoteError, GlobalConfig,
+    PrivacyLevel, ResultChallenged, Treasury, ValidatorRecord, VoteMode, VoteResult, ACTION_MIRROR_SEED,
+    DISPUTE_WINDOW_SLOTS, FAST_ACTION_SEED, FAST_ACTION_VERSION, GLOBAL_CONFIG_SEED, NO_ELIMINATION, SLASH_BPS,
+    TREASURY_SEED, VALIDATOR_SEED,
+};
+
+/// Finalized by a tally and past its dispute window. Expired, cancelled and
+/// voided actions never settle.
+pub fn is_settled(action: &FastAction, slot: u64) -> bool {
+    action.executed
+        && action.result.is_tallied()
+        && slot > action.finalized_slot.saturating_add(DISPUTE_WINDOW_SLOTS)
+}
+
+fn counters_inconsistent(action: &FastAction) -> bool {
+    let ballots = match action.option_count {
+        0 => action.votes_for.saturating_add(action.votes_against),
+        _ => action.vote_count,
+    };
+    if action.vote_count != ballots {
+        return true;
+    }
+
+    let expected = match action.vote_mode {
+        VoteMode::Optimistic => tally::optimistic_result(action.votes_against, action.electorate, action.threshold),
+        VoteMode::Headcount | VoteMode::TokenWeighted => {
+            let required = tally::required_quorum(
+                action.quorum,
+                action.quorum_floor,
+                action.quorum_grace_slots,
+                action.deadline_slot,
+                action.finalized_slot,
+            );
+            if action.vote_count < required
+                || !tally::turnout_met(action.vote_count, action.electorate, action.min_turnout_bps)
+            {
+                return true;
+            }
+            if action.ranked {
+                let options = &action.option_weights[..action.option_count as usize];
+                match tally::runoff_winner(options, action.eliminated_mask) {
+                    Some(option) if action.pending_elimination == NO_ELIMINATION => VoteResult::Chosen { option },
+                    _ => return true,
+                }
+            } else if action.option_count > 0 {
+                let options = &action.option_weights[..action.option_count as usize];
+                match tally::choose_option(options, action.threshold_bps, action.approval_rounding) {
+                    Ok(result) => result,
+                    Err(_) => return true,
+                }
+            } else {
+                let (tally_for, tally_against) = match action.vote_mode {
+                    VoteMode::TokenWeighted => (action.weight_for, action.weight_against),
+                    _ => (action.votes_for as u64, action.votes_against as u64),
+                };
+                match tally::approval_bps(tally_for, tally_against, action.approval_rounding) {
+                    Ok(bps) => tally::grade(bps, action.threshold_bps, &action.tier_thresholds),
+                    Err(_) => return true,
+                }
+            }
+        }
+    };
+    let digest = tally::result_digest(
+        action.action_id,
+        &action.action_hash,
+        action.votes_for,
+        action.votes_against,
+        &action.result,
+        action.finalized_slot,
+    );
+    expected != action.result || digest != action.result_digest
+}
+
+fn votes_inconsistent(action: &Account<FastAction>, vote_infos: &[AccountInfo]) -> Result<bool> {
+    require!(!vote_infos.is_empty(), FastVoteError::InvalidEvidence);
+
+    let mut voters = BTreeSet::new();
+    let (mut count_for, mut count_against) = (0u64, 0u64);
+    let (mut weight_for, mut weight_against) = (0u64, 0u64);
+    for info in vote_infos {
+        require_keys_eq!(*info.owner, crate::ID, FastVoteError::InvalidEvidence);
+        let vote = FastVote::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require_keys_eq!(vote.fast_action, action.key(), FastVoteError::InvalidEvidence);
+        require!(voters.insert(vote.voter), FastVoteError::InvalidEvidence);
+
+        if vote.voted_slot > action.deadline_slot {
+            return Ok(true);
+        }
+        // A commit-reveal ballot never revealed counts for neither side
+        if action.privacy == PrivacyLevel::CommitReveal && vote.weight == 0 {
+            continue;
+        }
+        if vote.vote_value {
+            count_for += 1;
+            weight_for = weight_for.saturating_add(vote.weight);
+        } else {
+            count_against += 1;
+            weight_against = weight_against.saturating_add(vote.weight);
+        }
+    }
+
+    if action.option_count > 0 {
+        // Records keep each ballot's total weight, not its split
+        let cast = action.option_weights.iter().sum::<u64>() + action.exhausted_ballots as u64;
+        return Ok(weight_for > cast);
+    }
+    let undercounted = count_for > action.votes_for as u64 || count_against > action.votes_against as u64;
+    let underweighted = action.vote_mode == VoteMode::TokenWeighted
+        && (weight_for > action.weight_for || weight_against > action.weight_against);
+    Ok(undercounted || underweighted)
+}
+
+/// Whether the action's resolved mirror holds a rival commit, signed by the
+/// other validator, that saw more ballots than the one that stands
+fn commit_undercounted(action: &Account<FastAction>, mirror_infos: &[AccountInfo]) -> Result<bool> {
+    let info = mirror_infos.first().ok_or(FastVoteError::InvalidEvidence)?;
+    let (expected, _) = Pubkey::find_program_address(&[ACTION_MIRROR_SEED, action.key().as_ref()], &crate::ID);
+    require_keys_eq!(info.key(), expected, FastVoteError::InvalidEvidence);
+    require_keys_eq!(*info.owner, crate::ID, FastVoteError::InvalidEvidence);
+    let mirror = ActionMirror::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    require!(
+        mirror.resolved && mirror.committed_by != Pubkey::default(),
+        FastVoteError::InvalidEvidence
+    );
+
+    let digest = attestation_digest(&action.key(), action.votes_for, action.votes_against, &action.result);
+    Ok(mirror.digest != digest && mirror.vote_count > action.vote_count)
+}
+
+/// Fails unless `action` has a tallied result still inside its dispute
+/// window
+pub(crate) fn require_disputable(action: &FastAction, slot: u64) -> Result<()> {
+    require!(
+        action.executed && action.result.is_tallied(),
+        FastVoteError::ActionNotFinalized
+    );
+    let closes = action
+        .finalized_slot
+        .checked_add(DISPUTE_WINDOW_SLOTS)
+        .ok_or(FastVoteError::SlotOverflow)?;
+    require!(slot <= closes, FastVoteError::DisputeWindowClosed);
+    Ok(())
+}
+
+/// Voids the successfully challenged `action` and slashes the validator it
+/// was delegated to, if any, paying `challenger` the whistleblower share;
+/// returns the voided result, the amount slashed and the bounty
+pub(crate) fn void_result(
+    action: &mut Account<FastAction>,
+    validator_record: Option<&mut Account<ValidatorRecord>>,
+    treasury: &mut Account<Treasury>,
+    treasury_bump: u8,
+    config: &GlobalConfig,
+    challenger: &AccountInfo,
+) -> Result<(VoteResult, u64, u64)> {
+    let (mut slashed, mut bounty) = (0, 0);
+    if action.delegated_validator != Pubkey::default() {
+        let record = validator_record.ok_or(FastVoteError::ValidatorNotRegistered)?;
+        slashed = (record.bond as u128 * SLASH_BPS as u128 / 10_000) as u64;
+        treasury.bump = treasury_bump;
+        bounty = pay_slash(&record.to_account_info(), challenger, treasury, slashed, config.whistleblower_bps)?;
+        record.bond -= slashed;
+    }
+
+    let voided = action.result.clone();
+    action.result = VoteResult::Voided;
+    action.log_result(Clock::get()?.slot);
+    Ok((voided, slashed, bounty))
+}
+
+pub fn process_challenge_result<'info>(
+    ctx: Context<'_, '_, '_, 'info, ChallengeResult<'info>>,
+    evidence: Evidence,
+) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let action = &ctx.accounts.fast_action;
+    require_disputable(action, slot)?;
+
+    let fraudulent = match evidence {
+        Evidence::Counters => counters_inconsistent(action),
+        // Encrypted ballots' records hold no choice to recount
+        Evidence::Votes if action.privacy == PrivacyLevel::Encrypted => return err!(FastVoteError::InvalidEvidence),
+        Evidence::Votes => votes_inconsistent(action, ctx.remaining_accounts)?,
+        // Proven through challenge_exclusion, with its sequence batch
+        Evidence::Excluded => return err!(FastVoteError::InvalidEvidence),
+        Evidence::ConflictingCommit => commit_undercounted(action, ctx.remaining_accounts)?,
+    };
+    require!(fraudulent, FastVoteError::ResultNotFraudulent);
+
+    let accounts = &mut *ctx.accounts;
+    let (voided, slashed, bounty) = void_result(
+        &mut accounts.fast_action,
+        accounts.validator_record.as_mut(),
+        &mut accounts.treasury,
+        ctx.bumps.treasury,
+        &accounts.config,
+        &accounts.challenger,
+    )?;
+
+    let action = &ctx.accounts.fast_action;
+    emit_event!(ctx, ResultChallenged {
+        action: action.key(),
+        hive: action.hive,
+        action_id: action.action_id,
+        evidence,
+        voided_result: voided,
+        challenger: ctx.accounts.challenger.key(),
+        validator: action.delegated_validator,
+        slashed,
+        bounty,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ChallengeResult<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    /// Required when the action was delegated to a pinned validator
+    #[account(
+        mut,
+        seeds = [VALIDATOR_SEED, fast_action.delegated_validator.as_ref()],
+        bump = validator_record.bump
+    )]
+    pub validator_record: Option<Account<'info, ValidatorRecord>>,
+    #[account(
+        init_if_needed,
+        payer = challenger,
+        space = Treasury::LEN,
+        seeds = [TREASURY_SEED],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
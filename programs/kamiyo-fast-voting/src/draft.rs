@@ -0,0 +1,105 @@
+// Co-sponsored creation. A creator stages an action's params in a
+// ProposalDraft naming up to MAX_SPONSORS agents; once `required` of them
+// have signed, create_fast_action accepts the draft and opens the action
+// with exactly those params. The action keeps a link to its draft so
+// executors can insist on sponsorship for high-impact payloads.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    params_digest, ActionParams, DraftSponsored, FastVoteError, ProposalDraft, ProposalDraftCreated, MAX_SPONSORS,
+    PROPOSAL_DRAFT_SEED,
+};
+
+pub fn process_create_draft(
+    ctx: Context<CreateDraft>,
+    params: ActionParams,
+    sponsors: Vec<Pubkey>,
+    required: u8,
+) -> Result<()> {
+    require!(params.action_hash != [0u8; 32], FastVoteError::InvalidActionHash);
+    require!(
+        sponsors.len() <= MAX_SPONSORS && required > 0 && required as usize <= sponsors.len(),
+        FastVoteError::InvalidSponsors
+    );
+    for (i, sponsor) in sponsors.iter().enumerate() {
+        require!(
+            *sponsor != Pubkey::default() && !sponsors[..i].contains(sponsor),
+            FastVoteError::InvalidSponsors
+        );
+    }
+
+    let draft = &mut ctx.accounts.draft;
+    draft.creator = ctx.accounts.creator.key();
+    draft.action_hash = params.action_hash;
+    draft.params_digest = params_digest(&params)?;
+    draft.sponsors = [Pubkey::default(); MAX_SPONSORS];
+    draft.sponsors[..sponsors.len()].copy_from_slice(&sponsors);
+    draft.sponsor_count = sponsors.len() as u8;
+    draft.signed_mask = 0;
+    draft.required = required;
+    draft.action = Pubkey::default();
+    draft.bump = ctx.bumps.draft;
+
+    emit_event!(ctx, ProposalDraftCreated {
+        draft: draft.key(),
+        creator: draft.creator,
+        action_hash: draft.action_hash,
+        params_digest: draft.params_digest,
+        sponsors,
+        required,
+    });
+
+    Ok(())
+}
+
+pub fn process_sponsor_draft(ctx: Context<SponsorDraft>) -> Result<()> {
+    let draft = &mut ctx.accounts.draft;
+    require!(draft.action == Pubkey::default(), FastVoteError::DraftAlreadyPromoted);
+
+    let sponsor = ctx.accounts.sponsor.key();
+    let index = draft.sponsors[..draft.sponsor_count as usize]
+        .iter()
+        .position(|s| *s == sponsor)
+        .ok_or(FastVoteError::NotASponsor)?;
+    require!(draft.signed_mask & (1 << index) == 0, FastVoteError::AlreadySponsored);
+    draft.signed_mask |= 1 << index;
+
+    emit_event!(ctx, DraftSponsored {
+        draft: draft.key(),
+        sponsor,
+        signatures: draft.signatures(),
+        required: draft.required,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(params: ActionParams)]
+pub struct CreateDraft<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = ProposalDraft::LEN,
+        seeds = [PROPOSAL_DRAFT_SEED, creator.key().as_ref(), params.action_hash.as_ref()],
+        bump
+    )]
+    pub draft: Account<'info, ProposalDraft>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SponsorDraft<'info> {
+    #[account(
+        mut,
+        seeds = [PROPOSAL_DRAFT_SEED, draft.creator.as_ref(), draft.action_hash.as_ref()],
+        bump = draft.bump
+    )]
+    pub draft: Account<'info, ProposalDraft>,
+    pub sponsor: Signer<'info>,
+}
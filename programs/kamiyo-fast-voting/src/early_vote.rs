@@ -0,0 +1,68 @@
+// Early-vote multipliers, to reward decisiveness in sub-minute windows. A
+// token-weighted action created with `early_vote_bonus` set scales each
+// ballot by a factor that decays from 1 + bonus at creation to 1.0x at the
+// deadline, computed from the ballot's voted_slot. The curve is set per
+// ActionKind in the EarlyVoteCurves PDA and copied onto the action at
+// creation, so later changes do not move open votes; kinds without one use
+// DEFAULT_EARLY_VOTE_CURVE, 1.2x decaying linearly.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    ActionKind, EarlyVoteCurve, EarlyVoteCurveSet, EarlyVoteCurves, FastVoteError, GlobalConfig, VoteMode,
+    EARLY_VOTE_CURVES_SEED, GLOBAL_CONFIG_SEED, MAX_EARLY_BONUS_BPS,
+};
+
+/// Curve a new action records; OFF unless it asked for the bonus, which
+/// needs a token-weighted action
+pub fn resolve_curve(enabled: bool, curve: EarlyVoteCurve, vote_mode: VoteMode) -> Result<EarlyVoteCurve> {
+    if !enabled {
+        return Ok(EarlyVoteCurve::OFF);
+    }
+    require!(vote_mode == VoteMode::TokenWeighted, FastVoteError::InvalidEarlyVoteCurve);
+    Ok(curve)
+}
+
+fn validate(curve: EarlyVoteCurve) -> Result<()> {
+    require!(
+        curve.bonus_bps > 0 && curve.bonus_bps <= MAX_EARLY_BONUS_BPS,
+        FastVoteError::InvalidEarlyVoteCurve
+    );
+    Ok(())
+}
+
+/// Config admin only. `None` restores the default for `kind`.
+pub fn process_set_early_vote_curve(
+    ctx: Context<SetEarlyVoteCurve>,
+    kind: ActionKind,
+    curve: Option<EarlyVoteCurve>,
+) -> Result<()> {
+    if let Some(curve) = curve {
+        validate(curve)?;
+    }
+    let curves = &mut ctx.accounts.early_vote_curves;
+    curves.curves[kind as usize] = curve;
+    curves.bump = ctx.bumps.early_vote_curves;
+
+    emit_event!(ctx, EarlyVoteCurveSet { kind, curve });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetEarlyVoteCurve<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = EarlyVoteCurves::LEN,
+        seeds = [EARLY_VOTE_CURVES_SEED],
+        bump
+    )]
+    pub early_vote_curves: Account<'info, EarlyVoteCurves>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump, has_one = admin @ FastVoteError::Unauthorized)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
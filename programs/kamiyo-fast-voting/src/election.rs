@@ -0,0 +1,89 @@
+// Leader elections. An Election action is a hive-scoped multi-option action
+// whose options are candidate agents; its action hash commits to the
+// candidate list and the term length. Once the winning option settles,
+// anyone may install the winner, which writes the hive's LeaderTerm PDA.
+// Other programs read LeaderTerm to authorize the hive's coordinator.
+
+use anchor_lang::prelude::*;
+
+use crate::dispute::is_settled;
+use crate::{
+    ActionKind, AgentRecord, Election, FastAction, FastVoteError, LeaderInstalled, LeaderTerm, VoteMode, VoteResult,
+    AGENT_SEED, FAST_ACTION_SEED, FAST_ACTION_VERSION, LEADER_TERM_SEED,
+};
+
+/// Election constraints on top of the usual creation checks
+pub fn validate_election(option_count: u8, vote_mode: VoteMode, hive: &Pubkey) -> Result<()> {
+    require!(
+        option_count >= 2 && vote_mode != VoteMode::Optimistic && *hive != Pubkey::default(),
+        FastVoteError::InvalidElectionAction
+    );
+    Ok(())
+}
+
+/// Permissionless, on the base layer, once the winning option has settled.
+/// `agent_record` must be the winner's.
+pub fn process_install_leader(ctx: Context<InstallLeader>, election: Election) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(election.action_hash()? == action.action_hash, FastVoteError::ElectionMismatch);
+    require!(
+        election.candidates.len() == action.option_count as usize && election.term_slots > 0,
+        FastVoteError::ElectionMismatch
+    );
+    let VoteResult::Chosen { option } = action.result else {
+        return err!(FastVoteError::ActionNotExecutable);
+    };
+    let slot = Clock::get()?.slot;
+    require!(is_settled(action, slot), FastVoteError::DisputeWindowOpen);
+
+    let leader = election.candidates[option as usize];
+    require_keys_eq!(ctx.accounts.agent_record.agent, leader, FastVoteError::LeaderNotRegistered);
+
+    let term = &mut ctx.accounts.leader_term;
+    require!(
+        term.hive == Pubkey::default() || action.finalized_slot > term.elected_slot,
+        FastVoteError::StaleElection
+    );
+    term.hive = action.hive;
+    term.leader = leader;
+    term.term_start = slot;
+    term.term_end = slot.checked_add(election.term_slots).ok_or(FastVoteError::SlotOverflow)?;
+    term.action = action.key();
+    term.elected_slot = action.finalized_slot;
+    term.bump = ctx.bumps.leader_term;
+
+    emit_event!(ctx, LeaderInstalled {
+        hive: term.hive,
+        leader,
+        action: term.action,
+        term_start: term.term_start,
+        term_end: term.term_end,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct InstallLeader<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration,
+        constraint = fast_action.kind == ActionKind::Election @ FastVoteError::InvalidElectionAction
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = LeaderTerm::LEN,
+        seeds = [LEADER_TERM_SEED, fast_action.hive.as_ref()],
+        bump
+    )]
+    pub leader_term: Account<'info, LeaderTerm>,
+    #[account(seeds = [AGENT_SEED, agent_record.agent.as_ref()], bump = agent_record.bump)]
+    pub agent_record: Account<'info, AgentRecord>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,29 @@
+// Emergency actions for incident response: pausing a strategy, revoking a
+// key. They resolve in a few seconds instead of the usual window, so in
+// exchange they need a supermajority, a plain for/against hive vote, and a
+// hive whose electorate includes the guardian, who can then always weigh in.
+
+use anchor_lang::prelude::*;
+
+use crate::{FastVoteError, GlobalConfig, Roles, VoteMode, EMERGENCY_MIN_THRESHOLD};
+
+/// Emergency constraints on top of the usual creation checks
+pub fn validate(
+    threshold: u8,
+    vote_mode: VoteMode,
+    option_count: u8,
+    config: &GlobalConfig,
+    roles: Option<&Account<Roles>>,
+) -> Result<()> {
+    require!(
+        threshold >= EMERGENCY_MIN_THRESHOLD && vote_mode != VoteMode::Optimistic && option_count == 0,
+        FastVoteError::InvalidEmergencyAction
+    );
+    require!(config.guardian != Pubkey::default(), FastVoteError::GuardianNotSet);
+    let roles = roles.ok_or(FastVoteError::MissingRoles)?;
+    require!(
+        roles.members.iter().any(|m| m.member == config.guardian),
+        FastVoteError::GuardianNotInElectorate
+    );
+    Ok(())
+}
@@ -0,0 +1,90 @@
+// Epoch statistics. Tuning windows and quorums wants numbers like turnout
+// and pass rate per hive over time, which would otherwise take a full
+// reindex of every action. An EpochStats PDA per hive and epoch accumulates
+// them as tally_and_commit finalizes the hive's actions: results, ballots
+// against the electorate, how long quorum took and which kinds of action
+// were decided.
+//
+// Like Metrics, the accounts are optional and counted only if created.
+// Anyone may create one for the current or a later epoch, on the layer the
+// hive's actions finalize on; the epoch is that layer's clock epoch at
+// finalization. Time to quorum is read from the action log, so actions
+// whose QuorumReached entry was overwritten, or that set no quorum, are
+// left out of it.
+
+use anchor_lang::prelude::*;
+
+use crate::{EpochStats, EpochStatsInitialized, FastAction, FastVoteError, LogTag, VoteResult, EPOCH_STATS_SEED};
+
+/// Counts the just-finalized `action` on the EpochStats passed as `stats`,
+/// if one was passed and it exists
+pub fn record_finalized(stats: &Option<UncheckedAccount>, action: &FastAction, epoch: u64) -> Result<()> {
+    let Some(info) = stats else {
+        return Ok(());
+    };
+    if info.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let mut data = info.try_borrow_mut_data()?;
+    let mut stats = EpochStats::try_deserialize(&mut &data[..])?;
+    require!(
+        stats.hive == action.hive && stats.epoch == epoch,
+        FastVoteError::InvalidEpochStats
+    );
+
+    stats.finalized = stats.finalized.saturating_add(1);
+    if matches!(action.result, VoteResult::Passed { .. } | VoteResult::Chosen { .. }) {
+        stats.passed = stats.passed.saturating_add(1);
+    }
+    stats.ballots = stats.ballots.saturating_add(action.vote_count as u64);
+    if action.electorate > 0 {
+        stats.electorate_ballots = stats.electorate_ballots.saturating_add(action.vote_count as u64);
+        stats.electorate = stats.electorate.saturating_add(action.electorate as u64);
+    }
+    if let Some(quorum) = action.log.recent().filter(|e| e.tag == LogTag::QuorumReached).last() {
+        stats.quorum_samples = stats.quorum_samples.saturating_add(1);
+        stats.slots_to_quorum = stats
+            .slots_to_quorum
+            .saturating_add(quorum.slot.saturating_sub(action.created_slot));
+    }
+    let kind = &mut stats.kinds[action.kind as usize];
+    *kind = kind.saturating_add(1);
+
+    stats.try_serialize(&mut &mut data[..])
+}
+
+/// Permissionless; `hive` is default for actions outside any hive
+pub fn process_initialize_epoch_stats(ctx: Context<InitializeEpochStats>, hive: Pubkey, epoch: u64) -> Result<()> {
+    require!(epoch >= Clock::get()?.epoch, FastVoteError::InvalidEpochStats);
+
+    let stats = &mut ctx.accounts.epoch_stats;
+    stats.hive = hive;
+    stats.epoch = epoch;
+    stats.bump = ctx.bumps.epoch_stats;
+
+    emit_event!(ctx, EpochStatsInitialized {
+        stats: stats.key(),
+        hive,
+        epoch,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(hive: Pubkey, epoch: u64)]
+pub struct InitializeEpochStats<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = EpochStats::LEN,
+        seeds = [EPOCH_STATS_SEED, hive.as_ref(), &epoch.to_le_bytes()],
+        bump
+    )]
+    pub epoch_stats: Account<'info, EpochStats>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
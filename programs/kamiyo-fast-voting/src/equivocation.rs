@@ -0,0 +1,161 @@
+// Equivocation. Agents sign their ballots off-chain before they reach the
+// rollup; an agent that signs two different choices on one action has
+// equivocated, and the TEE sees both. Anyone holding the two signed ballots
+// may report them: the transaction carries ed25519 program instructions
+// verifying both signatures, which report_equivocation finds through the
+// instructions sysvar. The proof is self-contained, so there is no dispute
+// window; the agent's whole registry bond is slashed at once.
+//
+// The same signed Ballot is how a voter group casts its vote, submitted on
+// its behalf by a member or relayer; see voter_group, and relayers for
+// pinning who may submit it.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked, ID as INSTRUCTIONS_SYSVAR_ID,
+};
+
+use crate::slashing::pay_slash;
+use crate::{
+    AgentEquivocated, AgentRecord, Ballot, EquivocationReport, FastVoteError, GlobalConfig, Treasury, AGENT_SEED,
+    EQUIVOCATION_SEED, GLOBAL_CONFIG_SEED, TREASURY_SEED,
+};
+
+// ed25519 program instruction layout: a count and a padding byte, then per
+// signature seven u16 offsets; u16::MAX as an instruction index means the
+// data lives in the ed25519 instruction itself.
+const ED25519_HEADER_LEN: usize = 2;
+const ED25519_OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+
+fn read_u16(data: &[u8], at: usize) -> Result<usize> {
+    let bytes = data.get(at..at + 2).ok_or(FastVoteError::MissingBallotSignature)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+}
+
+/// Signer, signature and message of one verified ed25519 signature
+pub(crate) type SignedMessage = (Pubkey, [u8; SIGNATURE_LEN], Vec<u8>);
+
+/// Signatures verified by the ed25519 instructions before this one. Only
+/// signatures whose data sits in the ed25519 instruction itself count.
+pub(crate) fn verified_signed_messages(sysvar: &AccountInfo) -> Result<Vec<SignedMessage>> {
+    let mut verified = Vec::new();
+    let current = load_current_index_checked(sysvar)?;
+    for index in 0..current {
+        let ix = load_instruction_at_checked(index as usize, sysvar)?;
+        if ix.program_id != ed25519_program::ID {
+            continue;
+        }
+        let data = &ix.data;
+        let count = *data.first().ok_or(FastVoteError::MissingBallotSignature)? as usize;
+        for i in 0..count {
+            let at = ED25519_HEADER_LEN + i * ED25519_OFFSETS_LEN;
+            let field = |n: usize| read_u16(data, at + 2 * n);
+            let (signature, signature_ix, pubkey, pubkey_ix) = (field(0)?, field(1)?, field(2)?, field(3)?);
+            let (message, message_len, message_ix) = (field(4)?, field(5)?, field(6)?);
+            if [signature_ix, pubkey_ix, message_ix].iter().any(|&ix| ix != u16::MAX as usize) {
+                continue;
+            }
+            // The ed25519 program has already checked these offsets
+            let signer = Pubkey::try_from(&data[pubkey..pubkey + PUBKEY_LEN])
+                .map_err(|_| FastVoteError::MissingBallotSignature)?;
+            let signature: [u8; SIGNATURE_LEN] = data[signature..signature + SIGNATURE_LEN]
+                .try_into()
+                .map_err(|_| FastVoteError::MissingBallotSignature)?;
+            verified.push((signer, signature, data[message..message + message_len].to_vec()));
+        }
+    }
+    Ok(verified)
+}
+
+/// (signer, message) pairs verified by the ed25519 instructions before this
+/// one; see verified_signed_messages
+pub(crate) fn verified_signatures(sysvar: &AccountInfo) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    Ok(verified_signed_messages(sysvar)?
+        .into_iter()
+        .map(|(signer, _, message)| (signer, message))
+        .collect())
+}
+
+/// Permissionless. The reporter pays for the report account.
+pub fn process_report_equivocation(ctx: Context<ReportEquivocation>, first: Ballot, second: Ballot) -> Result<()> {
+    require!(
+        first.action == second.action && first.choice != second.choice,
+        FastVoteError::BallotsDoNotConflict
+    );
+    let agent = ctx.accounts.agent.key();
+    let verified = verified_signatures(&ctx.accounts.instructions)?;
+    for ballot in [first, second] {
+        let message = ballot.message()?;
+        require!(
+            verified.iter().any(|(signer, signed)| *signer == agent && *signed == message),
+            FastVoteError::MissingBallotSignature
+        );
+    }
+
+    let record = &mut ctx.accounts.agent_record;
+    let amount = record.bond;
+    ctx.accounts.treasury.bump = ctx.bumps.treasury;
+    let bounty = pay_slash(
+        &record.to_account_info(),
+        &ctx.accounts.reporter,
+        &mut ctx.accounts.treasury,
+        amount,
+        ctx.accounts.config.whistleblower_bps,
+    )?;
+    record.bond = 0;
+
+    let report = &mut ctx.accounts.report;
+    report.action = first.action;
+    report.agent = agent;
+    report.reporter = ctx.accounts.reporter.key();
+    report.reported_slot = Clock::get()?.slot;
+    report.amount = amount;
+    report.bump = ctx.bumps.report;
+
+    emit_event!(ctx, AgentEquivocated {
+        action: report.action,
+        agent,
+        reporter: report.reporter,
+        amount,
+        bounty,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(first: Ballot)]
+pub struct ReportEquivocation<'info> {
+    #[account(
+        init,
+        payer = reporter,
+        space = EquivocationReport::LEN,
+        seeds = [EQUIVOCATION_SEED, first.action.as_ref(), agent.key().as_ref()],
+        bump
+    )]
+    pub report: Account<'info, EquivocationReport>,
+    #[account(mut, seeds = [AGENT_SEED, agent.key().as_ref()], bump = agent_record.bump)]
+    pub agent_record: Account<'info, AgentRecord>,
+    /// CHECK: The equivocating agent; bound to its record by the seeds
+    pub agent: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = reporter,
+        space = Treasury::LEN,
+        seeds = [TREASURY_SEED],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// CHECK: The instructions sysvar
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
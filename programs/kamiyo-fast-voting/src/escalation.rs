@@ -0,0 +1,114 @@
+// Contested results. A hive's Admin sets, per ActionKind, a margin in
+// percentage points above the threshold; tally_and_commit records a pass of
+// that kind whose approval falls within the margin as Contested rather than
+// Passed. Nothing gates on Contested, and execute_action refuses it, until
+// the slow voting program's promote_contested_action opens a slow vote on it
+// and ratify_contested carries that vote's result back: Passed once the slow
+// vote passes and clears its timelock, Failed if it does not pass.
+//
+// tally_and_commit passes the hive's CategoryConfig PDA whether or not it
+// exists, so a tallier cannot dodge the rule by leaving it out.
+
+use anchor_lang::prelude::*;
+use kamiyo_slow_voting::{SlowAction, SLOW_ACTION_SEED};
+
+use crate::{
+    ActionKind, CategoryConfig, ContestMarginSet, ContestResolved, FastAction, FastVoteError, Role, Roles,
+    VoteResult, CATEGORY_CONFIG_SEED, FAST_ACTION_SEED, FAST_ACTION_VERSION, ROLES_SEED,
+};
+
+/// Hive Admin only; creates the config on first use
+pub fn process_set_contest_margin(ctx: Context<SetContestMargin>, kind: ActionKind, margin: u8) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    require!(ctx.accounts.roles.has_role(&admin, Role::Admin), FastVoteError::MissingRole);
+    require!(margin <= 100, FastVoteError::InvalidContestMargin);
+
+    let config = &mut ctx.accounts.category_config;
+    config.hive = ctx.accounts.roles.hive;
+    config.contest_margins[kind as usize] = margin;
+    config.bump = ctx.bumps.category_config;
+
+    emit_event!(ctx, ContestMarginSet {
+        hive: config.hive,
+        kind,
+        margin,
+        set_by: admin,
+    });
+
+    Ok(())
+}
+
+/// Permissionless, on the base layer, once the action's slow vote is
+/// decided
+pub fn process_ratify_contested(ctx: Context<RatifyContested>) -> Result<()> {
+    let VoteResult::Contested { tier } = ctx.accounts.fast_action.result else {
+        return err!(FastVoteError::NotContested);
+    };
+
+    let info = &ctx.accounts.slow_action;
+    require!(!info.data_is_empty(), FastVoteError::RatificationPending);
+    require_keys_eq!(*info.owner, kamiyo_slow_voting::ID, FastVoteError::RatificationPending);
+    let slow = SlowAction::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    require_keys_eq!(slow.fast_action, ctx.accounts.fast_action.key(), FastVoteError::RatificationPending);
+
+    let slot = Clock::get()?.slot;
+    let result = if slow.is_executable(slot) {
+        VoteResult::Passed { tier }
+    } else if matches!(slow.result, VoteResult::Pending | VoteResult::Passed { .. }) {
+        return err!(FastVoteError::RatificationPending);
+    } else {
+        VoteResult::Failed
+    };
+
+    let action = &mut ctx.accounts.fast_action;
+    action.result = result;
+    action.log_result(slot);
+
+    emit_event!(ctx, ContestResolved {
+        action: action.key(),
+        hive: action.hive,
+        action_id: action.action_id,
+        slow_action: info.key(),
+        result: action.result.clone(),
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetContestMargin<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = CategoryConfig::LEN,
+        seeds = [CATEGORY_CONFIG_SEED, roles.hive.as_ref()],
+        bump
+    )]
+    pub category_config: Account<'info, CategoryConfig>,
+    #[account(seeds = [ROLES_SEED, roles.hive.as_ref()], bump = roles.bump)]
+    pub roles: Account<'info, Roles>,
+    /// Must hold Role::Admin in the hive
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RatifyContested<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    /// CHECK: The action's slow vote, opened by promote_contested_action
+    #[account(
+        seeds = [SLOW_ACTION_SEED, fast_action.key().as_ref()],
+        bump,
+        seeds::program = kamiyo_slow_voting::ID
+    )]
+    pub slow_action: UncheckedAccount<'info>,
+}
@@ -0,0 +1,124 @@
+// Expiry sweep. Actions nobody finalized would otherwise hold their rent
+// forever. Once the expiry grace after the deadline has passed, anyone may
+// close such an action and return its rent to the creator. A delegated one
+// must first come back through force_undelegate, which waits as long. One
+// still counted in a priority lane, listed under its tags or counted by its
+// validator leaves them here, as retire_action, retire_tags and
+// release_validator cannot reach a closed action; its TagIndex pages are
+// then passed as remaining accounts.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    metrics, priority, tags, tally, validators, ActionExpired, ActiveActionsUpdated, Counter, FastAction,
+    FastVoteError, ValidatorRecord, ValidatorReleased, VoteResult, ACTIVE_ACTIONS_SEED, EXPIRY_GRACE_SLOTS,
+    FAST_ACTION_SEED, FAST_ACTION_VERSION, VALIDATOR_SEED,
+};
+
+pub(crate) fn mark_expired(action: &mut FastAction, slot: u64) {
+    action.result = VoteResult::Expired;
+    action.executed = true;
+    action.finalized_slot = slot;
+    action.result_digest = tally::result_digest(
+        action.action_id,
+        &action.action_hash,
+        action.votes_for,
+        action.votes_against,
+        &action.result,
+        action.finalized_slot,
+    );
+    action.log_result(slot);
+}
+
+/// Permissionless, on the base layer. Closes an action that was never
+/// finalized, or was forced back as Expired; its rent and any bond go to the
+/// creator.
+pub fn process_expire_action(ctx: Context<ExpireAction>) -> Result<()> {
+    let action = &mut ctx.accounts.fast_action;
+    let slot = Clock::get()?.slot;
+
+    require!(
+        !action.executed || action.result == VoteResult::Expired,
+        FastVoteError::ActionAlreadyExecuted
+    );
+    let expires_slot = action
+        .deadline_slot
+        .checked_add(EXPIRY_GRACE_SLOTS)
+        .ok_or(FastVoteError::SlotOverflow)?;
+    require!(slot > expires_slot, FastVoteError::ExpiryGraceNotReached);
+
+    if !action.executed {
+        mark_expired(action, slot);
+        metrics::record(&ctx.accounts.metrics, &action.hive, Counter::Expired, slot)?;
+    }
+    let lane = priority::retire_closing(action, &ctx.accounts.active_actions)?;
+    let released = validators::release_closing(action, ctx.accounts.validator_record.as_deref_mut())?;
+    let priority = action.priority;
+    let key = action.key();
+    let untagged = match action.tags_indexed {
+        true => tags::retire(action, key, ctx.remaining_accounts)?,
+        false => Vec::new(),
+    };
+
+    emit_event!(ctx, ActionExpired {
+        action: action.key(),
+        hive: action.hive,
+        action_id: action.action_id,
+        creator: action.creator,
+        reclaimed: action.to_account_info().lamports(),
+        slot,
+    });
+    if let Some((hive, active)) = lane {
+        emit_event!(ctx, ActiveActionsUpdated {
+            hive,
+            action: ctx.accounts.fast_action.key(),
+            priority,
+            active,
+        });
+    }
+    if let (Some(active_actions), Some(record)) = (released, &ctx.accounts.validator_record) {
+        emit_event!(ctx, ValidatorReleased {
+            validator: record.validator,
+            action: ctx.accounts.fast_action.key(),
+            active_actions,
+        });
+    }
+    for event in untagged {
+        emit_event!(ctx, event);
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ExpireAction<'info> {
+    #[account(
+        mut,
+        close = creator,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        has_one = creator,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    /// CHECK: Receives the rent; bound to the action by has_one
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+    /// CHECK: The action's ActiveActions PDA; required while the action is
+    /// counted in a priority lane
+    #[account(mut, seeds = [ACTIVE_ACTIONS_SEED, fast_action.hive_seed()], bump)]
+    pub active_actions: Option<UncheckedAccount<'info>>,
+    /// CHECK: The base layer's Metrics PDA, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
+    /// Registry entry of the action's validator; required while the action
+    /// is counted against it
+    #[account(
+        mut,
+        seeds = [VALIDATOR_SEED, fast_action.delegated_validator.as_ref()],
+        bump = validator_record.bump
+    )]
+    pub validator_record: Option<Account<'info, ValidatorRecord>>,
+}
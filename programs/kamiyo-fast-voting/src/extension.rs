@@ -0,0 +1,113 @@
+// Deadline extensions, for when a window turns out too short for a decision
+// that matters. An action created with an ExtensionPolicy may have its
+// deadline pushed by up to one voting window, at most `max_extensions`
+// times, when its authority asks and voters holding at least `support_bps`
+// of the ballots cast so far co-sign the request. Co-signers pass their
+// FastVote and sign the transaction, so a compressed ballot, which has no
+// FastVote, cannot back one.
+//
+// The action's hash stays claimed until HASH_REUSE_GRACE_SLOTS past the
+// deadline it was created with, and the ER cannot renew the claim, so all
+// extensions together stay within that grace.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    DeadlineExtended, ExtensionPolicy, FastAction, FastVote, FastVoteError, LogTag, VoteMode, FAST_ACTION_SEED,
+    FAST_ACTION_VERSION, HASH_REUSE_GRACE_SLOTS, MAX_DEADLINE_EXTENSIONS,
+};
+
+/// Policy a new action records; OFF unless it asked for one, which an
+/// optimistic action cannot
+pub fn resolve_policy(policy: Option<ExtensionPolicy>, vote_mode: VoteMode) -> Result<ExtensionPolicy> {
+    let Some(policy) = policy else {
+        return Ok(ExtensionPolicy::OFF);
+    };
+    require!(
+        vote_mode != VoteMode::Optimistic
+            && policy.max_extensions > 0
+            && policy.max_extensions <= MAX_DEADLINE_EXTENSIONS
+            && policy.support_bps > 5_000
+            && policy.support_bps <= 10_000,
+        FastVoteError::InvalidExtensionPolicy
+    );
+    Ok(policy)
+}
+
+/// Action authority only, before the deadline. Remaining accounts are
+/// (FastVote, voter) pairs in ascending FastVote address order, each voter
+/// signing the transaction.
+pub fn process_extend_deadline(ctx: Context<ExtendDeadline>, slots: u64) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    let slot = Clock::get()?.slot;
+    require!(slot <= action.deadline_slot, FastVoteError::VotingEnded);
+    require!(
+        action.extensions < action.extension_policy.max_extensions,
+        FastVoteError::ExtensionsExhausted
+    );
+
+    let window = action.deadline_slot - action.created_slot - action.extended_slots;
+    let extended_slots = action.extended_slots.checked_add(slots).ok_or(FastVoteError::SlotOverflow)?;
+    require!(
+        slots > 0 && slots <= window && extended_slots <= HASH_REUSE_GRACE_SLOTS,
+        FastVoteError::InvalidExtension
+    );
+
+    let action_key = action.key();
+    let mut supporters = 0u32;
+    let mut last_vote = Pubkey::default();
+    for pair in ctx.remaining_accounts.chunks(2) {
+        let [vote_info, voter] = pair else {
+            return err!(FastVoteError::ExtensionNotSupported);
+        };
+        require!(vote_info.key() > last_vote, FastVoteError::ExtensionNotSupported);
+        require_keys_eq!(*vote_info.owner, crate::ID, FastVoteError::ExtensionNotSupported);
+        let vote = FastVote::try_deserialize(&mut &vote_info.try_borrow_data()?[..])?;
+        require_keys_eq!(vote.fast_action, action_key, FastVoteError::ExtensionNotSupported);
+        require!(
+            voter.is_signer && voter.key() == vote.voter,
+            FastVoteError::ExtensionNotSupported
+        );
+        last_vote = vote_info.key();
+        supporters += 1;
+    }
+    require!(
+        action.extension_policy.supported(supporters, action.vote_count),
+        FastVoteError::ExtensionNotSupported
+    );
+
+    let action = &mut ctx.accounts.fast_action;
+    let old_deadline_slot = action.deadline_slot;
+    action.deadline_slot = old_deadline_slot.checked_add(slots).ok_or(FastVoteError::SlotOverflow)?;
+    action.extended_slots = extended_slots;
+    action.extensions += 1;
+    let deadline_slot = action.deadline_slot;
+    action.log.push(slot, LogTag::DeadlineExtended, deadline_slot);
+
+    emit_event!(ctx, DeadlineExtended {
+        action: action_key,
+        hive: action.hive,
+        old_deadline_slot,
+        deadline_slot,
+        extensions: action.extensions,
+        supporters,
+        vote_count: action.vote_count,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ExtendDeadline<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        has_one = authority @ FastVoteError::Unauthorized,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    pub authority: Signer<'info>,
+}
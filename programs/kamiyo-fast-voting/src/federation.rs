@@ -0,0 +1,142 @@
+// Federated actions, for decisions that span hives. Each hive votes on its
+// own child action; the FederatedAction weighs the children's final results
+// by hive and passes if the weight of the children that passed reaches
+// `threshold` percent of the total. Settlement waits until every child is
+// final: settled past its dispute window, or cancelled, expired, voided or
+// vetoed, which count as not passing.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    dispute, market, FastAction, FastVoteError, FederatedAction, FederatedActionCreated, FederatedActionSettled,
+    FederatedChild, VoteResult, FEDERATED_ACTION_SEED, MAX_FEDERATED_HIVES,
+};
+
+/// The child FastAction at `info`, which must be `expected`
+fn load_child(info: &AccountInfo, expected: &Pubkey) -> Result<FastAction> {
+    require_keys_eq!(info.key(), *expected, FastVoteError::InvalidFederation);
+    require_keys_eq!(*info.owner, crate::ID, FastVoteError::InvalidFederation);
+    let data = info.try_borrow_data()?;
+    require!(data.len() == FastAction::LEN, FastVoteError::ActionNeedsMigration);
+    FastAction::try_deserialize(&mut &data[..])
+}
+
+/// Remaining accounts are the children's FastAction accounts, in order.
+pub fn process_create_federated_action(
+    ctx: Context<CreateFederatedAction>,
+    action_hash: [u8; 32],
+    threshold: u8,
+    children: Vec<FederatedChild>,
+) -> Result<()> {
+    require!(action_hash != [0u8; 32], FastVoteError::InvalidActionHash);
+    require!(threshold > 0 && threshold <= 100, FastVoteError::InvalidThreshold);
+    require!(
+        (2..=MAX_FEDERATED_HIVES).contains(&children.len()) && ctx.remaining_accounts.len() == children.len(),
+        FastVoteError::InvalidFederation
+    );
+    for (i, (child, info)) in children.iter().zip(ctx.remaining_accounts).enumerate() {
+        let action = load_child(info, &child.action)?;
+        require!(
+            child.weight > 0
+                && child.hive != Pubkey::default()
+                && action.hive == child.hive
+                && action.option_count == 0
+                && !children[..i].iter().any(|c| c.action == child.action || c.hive == child.hive),
+            FastVoteError::InvalidFederation
+        );
+    }
+
+    let federated = &mut ctx.accounts.federated_action;
+    federated.action_hash = action_hash;
+    federated.creator = ctx.accounts.creator.key();
+    federated.threshold = threshold;
+    federated.child_count = children.len() as u8;
+    federated.children = [FederatedChild::default(); MAX_FEDERATED_HIVES];
+    federated.children[..children.len()].copy_from_slice(&children);
+    federated.weight_passed = 0;
+    federated.result = VoteResult::Pending;
+    federated.settled_slot = 0;
+    federated.bump = ctx.bumps.federated_action;
+
+    emit_event!(ctx, FederatedActionCreated {
+        federated_action: federated.key(),
+        action_hash,
+        creator: federated.creator,
+        threshold,
+        children,
+    });
+
+    Ok(())
+}
+
+/// Permissionless once every child is final. Remaining accounts are the
+/// children's FastAction accounts, in order.
+pub fn process_settle_federated_action(ctx: Context<SettleFederatedAction>) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let federated = &mut ctx.accounts.federated_action;
+    require!(federated.result == VoteResult::Pending, FastVoteError::ActionAlreadyExecuted);
+    require!(
+        ctx.remaining_accounts.len() == federated.child_count as usize,
+        FastVoteError::InvalidFederation
+    );
+
+    let mut weight_passed = 0u32;
+    for (child, info) in federated.children().iter().zip(ctx.remaining_accounts) {
+        let action = load_child(info, &child.action)?;
+        match action.result {
+            VoteResult::Cancelled | VoteResult::Expired | VoteResult::Voided | VoteResult::Vetoed => {}
+            // A contested child settles once the slow track resolves it
+            VoteResult::Pending | VoteResult::Contested { .. } => return err!(FastVoteError::FederationNotSettled),
+            VoteResult::Passed { .. } | VoteResult::Failed | VoteResult::Chosen { .. } => {
+                require!(dispute::is_settled(&action, slot), FastVoteError::FederationNotSettled);
+                if matches!(action.result, VoteResult::Passed { .. }) {
+                    require!(market::is_executable(&action, slot), FastVoteError::FederationNotSettled);
+                    weight_passed += child.weight as u32;
+                }
+            }
+        }
+    }
+
+    let total_weight = federated.total_weight();
+    let passed = weight_passed as u64 * 100 >= federated.threshold as u64 * total_weight as u64;
+    federated.weight_passed = weight_passed;
+    federated.result = if passed { VoteResult::Passed { tier: 0 } } else { VoteResult::Failed };
+    federated.settled_slot = slot;
+
+    emit_event!(ctx, FederatedActionSettled {
+        federated_action: federated.key(),
+        result: federated.result.clone(),
+        weight_passed,
+        total_weight,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(action_hash: [u8; 32])]
+pub struct CreateFederatedAction<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = FederatedAction::LEN,
+        seeds = [FEDERATED_ACTION_SEED, action_hash.as_ref()],
+        bump
+    )]
+    pub federated_action: Account<'info, FederatedAction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SettleFederatedAction<'info> {
+    #[account(
+        mut,
+        seeds = [FEDERATED_ACTION_SEED, federated_action.action_hash.as_ref()],
+        bump = federated_action.bump
+    )]
+    pub federated_action: Account<'info, FederatedAction>,
+}
@@ -0,0 +1,197 @@
+// Foreign tallies. A hive's actions can take part in decisions that also
+// run on EVM chains: the hive admin registers an EVM hive contract as a
+// ForeignHive, and the contract publishes its aggregated stance and weight
+// on a Solana action through Wormhole. Once the guardians' signatures have
+// been posted to the core bridge, anyone may submit the PostedVAA and the
+// tally is folded in before finalization as one more voter: a FastVote
+// keyed by the ForeignHive PDA, so each foreign hive counts once per action.
+// On token-weighted actions it carries the tally's weight, capped at the
+// ForeignHive's `max_weight`.
+//
+// The PostedVAA is parsed by hand so the program doesn't pull in a Wormhole
+// SDK. It lives on the base layer, so a delegated action only takes foreign
+// tallies once it has been undelegated.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    freeze, metrics, record_vote, Counter, FastAction, FastVote, FastVoteError, ForeignHive, ForeignHiveRegistered,
+    ForeignTally, ForeignTallySubmitted, Role, Roles, VoteMode, FAST_ACTION_SEED, FAST_ACTION_VERSION, FAST_VOTE_SEED,
+    FOREIGN_HIVE_SEED, HIVE_FREEZE_SEED, ROLES_SEED, WORMHOLE_CORE_BRIDGE_ID,
+};
+
+/// PostedVAA account layout: the discriminator, then consistency level,
+/// timestamp, signature set, guardian set index and nonce before these
+const POSTED_VAA_DISCRIMINATOR: &[u8; 4] = b"vaa\x01";
+const SEQUENCE_OFFSET: usize = 49;
+const EMITTER_CHAIN_OFFSET: usize = 57;
+const EMITTER_ADDRESS_OFFSET: usize = 59;
+const PAYLOAD_OFFSET: usize = 91;
+
+/// A verified VAA's (sequence, emitter chain, emitter address, payload)
+fn read_posted_vaa(info: &AccountInfo) -> Result<(u64, u16, [u8; 32], Vec<u8>)> {
+    require_keys_eq!(*info.owner, WORMHOLE_CORE_BRIDGE_ID, FastVoteError::InvalidForeignTally);
+    let data = info.try_borrow_data()?;
+    require!(
+        data.len() >= PAYLOAD_OFFSET + 4 && data[..4] == POSTED_VAA_DISCRIMINATOR[..],
+        FastVoteError::InvalidForeignTally
+    );
+    let sequence = u64::from_le_bytes(data[SEQUENCE_OFFSET..SEQUENCE_OFFSET + 8].try_into().unwrap());
+    let chain = u16::from_le_bytes([data[EMITTER_CHAIN_OFFSET], data[EMITTER_CHAIN_OFFSET + 1]]);
+    let emitter: [u8; 32] = data[EMITTER_ADDRESS_OFFSET..PAYLOAD_OFFSET].try_into().unwrap();
+    let len = u32::from_le_bytes(data[PAYLOAD_OFFSET..PAYLOAD_OFFSET + 4].try_into().unwrap()) as usize;
+    let payload = data
+        .get(PAYLOAD_OFFSET + 4..PAYLOAD_OFFSET + 4 + len)
+        .ok_or(FastVoteError::InvalidForeignTally)?;
+    Ok((sequence, chain, emitter, payload.to_vec()))
+}
+
+/// Hive admin only; `emitter` is the EVM contract's address, left-padded
+pub fn process_register_foreign_hive(
+    ctx: Context<RegisterForeignHive>,
+    chain: u16,
+    emitter: [u8; 32],
+    max_weight: u64,
+) -> Result<()> {
+    require!(ctx.accounts.roles.has_role(&ctx.accounts.admin.key(), Role::Admin), FastVoteError::MissingRole);
+    require!(chain != 0 && emitter != [0u8; 32] && max_weight > 0, FastVoteError::InvalidForeignHive);
+
+    let foreign = &mut ctx.accounts.foreign_hive;
+    foreign.hive = ctx.accounts.roles.hive;
+    foreign.chain = chain;
+    foreign.emitter = emitter;
+    foreign.max_weight = max_weight;
+    foreign.bump = ctx.bumps.foreign_hive;
+
+    emit_event!(ctx, ForeignHiveRegistered {
+        hive: foreign.hive,
+        chain,
+        emitter,
+        max_weight,
+    });
+
+    Ok(())
+}
+
+/// Permissionless, on the base layer, while the action is open
+pub fn process_submit_foreign_tally(ctx: Context<SubmitForeignTally>) -> Result<()> {
+    let foreign = &ctx.accounts.foreign_hive;
+    let action = &ctx.accounts.fast_action;
+    require_keys_eq!(action.hive, foreign.hive, FastVoteError::InvalidForeignTally);
+    freeze::require_live(&ctx.accounts.hive_freeze, action)?;
+    let weighted = match action.vote_mode {
+        VoteMode::Headcount => false,
+        VoteMode::TokenWeighted => {
+            require!(
+                !action.identity_weighted
+                    && action.stake_lock_program == Pubkey::default()
+                    && action.quorum_weight_bps == 0,
+                FastVoteError::WrongVoteMode
+            );
+            true
+        }
+        VoteMode::Optimistic => return err!(FastVoteError::WrongVoteMode),
+    };
+
+    let (sequence, chain, emitter, payload) = read_posted_vaa(&ctx.accounts.posted_vaa)?;
+    let tally = ForeignTally::parse(&payload).ok_or(FastVoteError::InvalidForeignTally)?;
+    require!(
+        chain == foreign.chain && emitter == foreign.emitter && tally.action == action.key(),
+        FastVoteError::InvalidForeignTally
+    );
+    let weight = if weighted {
+        let weight = tally.weight.min(foreign.max_weight);
+        require!(weight > 0, FastVoteError::InvalidForeignTally);
+        weight
+    } else {
+        1
+    };
+
+    let foreign_key = foreign.key();
+    let event = record_vote(
+        &mut ctx.accounts.fast_action,
+        &mut ctx.accounts.fast_vote,
+        foreign_key,
+        ctx.bumps.fast_vote,
+        tally.stance,
+        // The VAA's address stands in for a commitment: unique and non-zero
+        ctx.accounts.posted_vaa.key().to_bytes(),
+        weight,
+    )?;
+    metrics::record(&ctx.accounts.metrics, &ctx.accounts.fast_action.hive, Counter::Vote, Clock::get()?.slot)?;
+    emit_event!(ctx, event);
+    emit_event!(ctx, ForeignTallySubmitted {
+        action: ctx.accounts.fast_action.key(),
+        hive: ctx.accounts.fast_action.hive,
+        foreign_hive: foreign_key,
+        sequence,
+        stance: tally.stance,
+        weight,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(chain: u16, emitter: [u8; 32])]
+pub struct RegisterForeignHive<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = ForeignHive::LEN,
+        seeds = [FOREIGN_HIVE_SEED, roles.hive.as_ref(), &chain.to_le_bytes(), emitter.as_ref()],
+        bump
+    )]
+    pub foreign_hive: Account<'info, ForeignHive>,
+    #[account(seeds = [ROLES_SEED, roles.hive.as_ref()], bump = roles.bump)]
+    pub roles: Account<'info, Roles>,
+    /// Must hold Role::Admin in the hive
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SubmitForeignTally<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = payer,
+        space = FastVote::LEN,
+        seeds = [FAST_VOTE_SEED, fast_action.key().as_ref(), foreign_hive.key().as_ref()],
+        bump
+    )]
+    pub fast_vote: Account<'info, FastVote>,
+    #[account(
+        seeds = [
+            FOREIGN_HIVE_SEED,
+            foreign_hive.hive.as_ref(),
+            &foreign_hive.chain.to_le_bytes(),
+            foreign_hive.emitter.as_ref()
+        ],
+        bump = foreign_hive.bump
+    )]
+    pub foreign_hive: Account<'info, ForeignHive>,
+    /// CHECK: The core bridge's PostedVAA; owner and layout checked in
+    /// read_posted_vaa
+    pub posted_vaa: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: The base layer's Metrics PDA, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
+    /// CHECK: The HiveFreeze PDA of the action's hive, whether or not it
+    /// exists; read in freeze::require_live
+    #[account(seeds = [HIVE_FREEZE_SEED, fast_action.hive.as_ref()], bump)]
+    pub hive_freeze: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
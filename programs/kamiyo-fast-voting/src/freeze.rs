@@ -0,0 +1,94 @@
+// Hive kill switch. An Emergency action of a hive commits to freezing or
+// unfreezing it through its action hash; once the action passes and
+// settles, anyone may apply it. The passed action stands in for an executor
+// signature, as with ConfigChange. While the HiveFreeze is set, the hive's
+// actions can neither be created nor voted on, except for the Emergency
+// action that unfreezes it; other hives are untouched.
+//
+// Every creation and ballot of a hive action takes the hive's HiveFreeze PDA
+// whether or not it exists, so the freeze cannot be dodged by leaving it out.
+// A freeze only ever yields to an action finalized after the one that set
+// it, so a stale action cannot be replayed over a newer decision.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    market, ActionKind, FastAction, FastVoteError, HiveFreeze, HiveFreezeSet, FAST_ACTION_SEED, FAST_ACTION_VERSION,
+    HIVE_FREEZE_SEED,
+};
+
+/// Fails if the HiveFreeze `info` of `action`'s hive is set, unless
+/// `action` is the hive's unfreeze
+pub fn require_live(info: &AccountInfo, action: &FastAction) -> Result<()> {
+    HiveFreeze::require_live(info, action.kind, &action.action_hash)
+}
+
+/// Checks the HiveFreeze `info` of `hive` on a ballot through vote_fast,
+/// whose action is not deserialized and so cannot seed the account
+pub fn check_vote_fast(
+    info: &Option<UncheckedAccount>,
+    hive: &Pubkey,
+    kind: ActionKind,
+    action_hash: &[u8; 32],
+) -> Result<()> {
+    if *hive == Pubkey::default() {
+        return Ok(());
+    }
+    let info = info.as_ref().ok_or(FastVoteError::HiveFreezeRequired)?;
+    let (address, _) = Pubkey::find_program_address(&[HIVE_FREEZE_SEED, hive.as_ref()], &crate::ID);
+    require_keys_eq!(info.key(), address, ErrorCode::ConstraintSeeds);
+    HiveFreeze::require_live(info, kind, action_hash)
+}
+
+/// Permissionless, on the base layer; `payer` covers the freeze on first
+/// use
+pub fn process_set_hive_frozen(ctx: Context<SetHiveFrozen>, frozen: bool) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(
+        action.action_hash == HiveFreeze::action_hash(&action.hive, frozen),
+        FastVoteError::InvalidFreezeAction
+    );
+    let slot = Clock::get()?.slot;
+    require!(market::is_executable(action, slot), FastVoteError::ActionNotExecutable);
+
+    let freeze = &mut ctx.accounts.hive_freeze;
+    require!(action.finalized_slot > freeze.finalized_slot, FastVoteError::StaleFreezeAction);
+    freeze.hive = action.hive;
+    freeze.frozen = frozen;
+    freeze.action = action.key();
+    freeze.finalized_slot = action.finalized_slot;
+    freeze.bump = ctx.bumps.hive_freeze;
+
+    emit_event!(ctx, HiveFreezeSet {
+        hive: freeze.hive,
+        frozen,
+        action: freeze.action,
+        action_id: action.action_id,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetHiveFrozen<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration,
+        constraint = fast_action.kind == ActionKind::Emergency @ FastVoteError::InvalidFreezeAction,
+        constraint = fast_action.hive != Pubkey::default() @ FastVoteError::InvalidFreezeAction
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = HiveFreeze::LEN,
+        seeds = [HIVE_FREEZE_SEED, fast_action.hive.as_ref()],
+        bump
+    )]
+    pub hive_freeze: Account<'info, HiveFreeze>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,64 @@
+// Guardian veto: a last-resort brake on compromised agent swarms. During a
+// tallied result's dispute window the guardian recorded in GlobalConfig,
+// typically a multisig, may overturn it with a stated reason.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    ActionVetoed, FastAction, FastVoteError, GlobalConfig, VoteResult, DISPUTE_WINDOW_SLOTS, FAST_ACTION_SEED,
+    FAST_ACTION_VERSION, GLOBAL_CONFIG_SEED, MAX_VETO_REASON_LEN,
+};
+
+pub fn process_veto_action(ctx: Context<VetoAction>, reason: String) -> Result<()> {
+    require!(reason.len() <= MAX_VETO_REASON_LEN, FastVoteError::VetoReasonTooLong);
+
+    let action = &mut ctx.accounts.fast_action;
+    require!(
+        action.executed
+            && matches!(
+                action.result,
+                VoteResult::Passed { .. } | VoteResult::Chosen { .. } | VoteResult::Contested { .. }
+            ),
+        FastVoteError::NothingToVeto
+    );
+    let closes = action
+        .finalized_slot
+        .checked_add(DISPUTE_WINDOW_SLOTS)
+        .ok_or(FastVoteError::SlotOverflow)?;
+    require!(Clock::get()?.slot <= closes, FastVoteError::DisputeWindowClosed);
+
+    let vetoed = action.result.clone();
+    action.result = VoteResult::Vetoed;
+    action.log_result(Clock::get()?.slot);
+
+    emit_event!(ctx, ActionVetoed {
+        action: action.key(),
+        hive: action.hive,
+        action_id: action.action_id,
+        vetoed_result: vetoed,
+        guardian: ctx.accounts.guardian.key(),
+        reason,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct VetoAction<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump = config.bump,
+        constraint = config.guardian != Pubkey::default() @ FastVoteError::GuardianNotSet,
+        constraint = config.guardian == guardian.key() @ FastVoteError::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub guardian: Signer<'info>,
+}
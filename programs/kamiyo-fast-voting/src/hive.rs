@@ -0,0 +1,219 @@
+// Hives registered with one deployment, so independent swarms can share it
+// without sharing actions. A hive's key signs create_hive; after that its
+// authority manages the member registry root and config overrides. Actions
+// created through the Hive account are seeded with the hive key, so two
+// swarms never contend for the same action addresses. Actions scoped only by
+// Roles, and every action created before hives existed, keep their unscoped
+// addresses: FastAction::hive_seed is empty for them.
+//
+// initialize_hive is create_hive with the rest of a new hive's accounts
+// opened alongside, in one instruction: its Roles, HiveConfig,
+// CategoryConfig and insurance pool, set from one HiveInit. It announces the
+// lot with a single HiveCreated rather than the events the separate setters
+// would emit; the roles it grants are read from the Roles account.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    CategoryConfig, FastVoteError, Hive, HiveConfig, HiveCreated, HiveInit, HiveOverrides, HiveUpdate, HiveUpdated,
+    InsurancePool, Role, RoleMember, Roles, CATEGORY_CONFIG_SEED, HIVE_CONFIG_SEED, HIVE_SEED, INSURANCE_POOL_SEED,
+    MAX_ROLE_MEMBERS, ROLES_SEED,
+};
+
+/// Seed between FAST_ACTION_SEED and the id of an action created through
+/// `hive`; empty when it is not
+pub fn action_seed<'a>(hive: &'a Option<Account<Hive>>) -> &'a [u8] {
+    hive.as_ref().map_or(&[], |h| h.hive.as_ref())
+}
+
+pub fn process_create_hive(ctx: Context<CreateHive>, name_hash: [u8; 32], authority: Pubkey) -> Result<()> {
+    require!(authority != Pubkey::default(), FastVoteError::InvalidHive);
+
+    let hive = &mut ctx.accounts.hive_account;
+    hive.hive = ctx.accounts.hive.key();
+    hive.authority = authority;
+    hive.name_hash = name_hash;
+    hive.member_root = [0; 32];
+    hive.overrides = HiveOverrides::default();
+    hive.bump = ctx.bumps.hive_account;
+
+    emit_event!(ctx, HiveCreated {
+        hive: hive.hive,
+        authority,
+        name_hash,
+        hive_account: hive.key(),
+        roles: Pubkey::default(),
+        hive_config: Pubkey::default(),
+        category_config: Pubkey::default(),
+        insurance_pool: Pubkey::default(),
+    });
+
+    Ok(())
+}
+
+pub fn process_initialize_hive(ctx: Context<InitializeHive>, init: HiveInit) -> Result<()> {
+    require!(init.authority != Pubkey::default(), FastVoteError::InvalidHive);
+    require!(init.members.len() < MAX_ROLE_MEMBERS, FastVoteError::RolesFull);
+    require!(init.contest_margins.iter().all(|&m| m <= 100), FastVoteError::InvalidContestMargin);
+    require!(init.insurance_fee_bps <= 10_000, FastVoteError::InvalidInsuranceFee);
+    let key = ctx.accounts.hive.key();
+    for (i, m) in init.members.iter().enumerate() {
+        require!(
+            m.member != key && m.has_roles() && init.members[..i].iter().all(|o| o.member != m.member),
+            FastVoteError::InvalidHiveMembers
+        );
+    }
+
+    let hive = &mut ctx.accounts.hive_account;
+    hive.hive = key;
+    hive.authority = init.authority;
+    hive.name_hash = init.name_hash;
+    hive.member_root = init.member_root;
+    hive.overrides = init.overrides;
+    hive.bump = ctx.bumps.hive_account;
+
+    let roles = &mut ctx.accounts.roles;
+    roles.hive = key;
+    roles.bump = ctx.bumps.roles;
+    roles.members = vec![RoleMember {
+        member: key,
+        roles: Role::Admin.mask(),
+    }];
+    roles.members.extend_from_slice(&init.members);
+
+    let config = &mut ctx.accounts.hive_config;
+    config.hive = key;
+    config.cancellation_policy = init.cancellation_policy;
+    config.approval_rounding = init.approval_rounding;
+    config.bump = ctx.bumps.hive_config;
+
+    let categories = &mut ctx.accounts.category_config;
+    categories.hive = key;
+    categories.contest_margins = init.contest_margins;
+    categories.bump = ctx.bumps.category_config;
+
+    let pool = &mut ctx.accounts.insurance_pool;
+    pool.hive = key;
+    pool.fee_bps = init.insurance_fee_bps;
+    pool.total_received = 0;
+    pool.total_paid = 0;
+    pool.claims_paid = 0;
+    pool.bump = ctx.bumps.insurance_pool;
+
+    emit_event!(ctx, HiveCreated {
+        hive: key,
+        authority: init.authority,
+        name_hash: init.name_hash,
+        hive_account: ctx.accounts.hive_account.key(),
+        roles: ctx.accounts.roles.key(),
+        hive_config: ctx.accounts.hive_config.key(),
+        category_config: ctx.accounts.category_config.key(),
+        insurance_pool: ctx.accounts.insurance_pool.key(),
+    });
+
+    Ok(())
+}
+
+/// Hive authority only
+pub fn process_update_hive(ctx: Context<UpdateHive>, update: HiveUpdate) -> Result<()> {
+    let hive = &mut ctx.accounts.hive_account;
+    if let Some(authority) = update.authority {
+        require!(authority != Pubkey::default(), FastVoteError::InvalidHive);
+        hive.authority = authority;
+    }
+    if let Some(member_root) = update.member_root {
+        hive.member_root = member_root;
+    }
+    if let Some(overrides) = update.overrides {
+        hive.overrides = overrides;
+    }
+
+    emit_event!(ctx, HiveUpdated {
+        hive: hive.hive,
+        authority: hive.authority,
+        member_root: hive.member_root,
+        overrides: hive.overrides,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CreateHive<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = Hive::LEN,
+        seeds = [HIVE_SEED, hive.key().as_ref()],
+        bump
+    )]
+    pub hive_account: Account<'info, Hive>,
+    pub hive: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct InitializeHive<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = Hive::LEN,
+        seeds = [HIVE_SEED, hive.key().as_ref()],
+        bump
+    )]
+    pub hive_account: Box<Account<'info, Hive>>,
+    #[account(
+        init,
+        payer = payer,
+        space = Roles::LEN,
+        seeds = [ROLES_SEED, hive.key().as_ref()],
+        bump
+    )]
+    pub roles: Box<Account<'info, Roles>>,
+    #[account(
+        init,
+        payer = payer,
+        space = HiveConfig::LEN,
+        seeds = [HIVE_CONFIG_SEED, hive.key().as_ref()],
+        bump
+    )]
+    pub hive_config: Box<Account<'info, HiveConfig>>,
+    #[account(
+        init,
+        payer = payer,
+        space = CategoryConfig::LEN,
+        seeds = [CATEGORY_CONFIG_SEED, hive.key().as_ref()],
+        bump
+    )]
+    pub category_config: Box<Account<'info, CategoryConfig>>,
+    #[account(
+        init,
+        payer = payer,
+        space = InsurancePool::LEN,
+        seeds = [INSURANCE_POOL_SEED, hive.key().as_ref()],
+        bump
+    )]
+    pub insurance_pool: Box<Account<'info, InsurancePool>>,
+    /// Becomes the first Admin, as in initialize_roles
+    pub hive: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct UpdateHive<'info> {
+    #[account(
+        mut,
+        seeds = [HIVE_SEED, hive_account.hive.as_ref()],
+        bump = hive_account.bump,
+        has_one = authority @ FastVoteError::Unauthorized
+    )]
+    pub hive_account: Account<'info, Hive>,
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,130 @@
+// Per-hive settings, kept apart from Roles so its layout can grow. A hive
+// without a HiveConfig gets the defaults; cancel_action and hive-scoped
+// creation pass the PDA either way, so an authority cannot dodge a stricter
+// policy by leaving it out. Configs from before approval_rounding are read
+// as Floor and go through migrate_hive_config before they can be set again.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+
+use crate::{
+    ApprovalRounding, ApprovalRoundingSet, CancellationPolicy, CancellationPolicySet, FastVoteError, HiveConfig, Role,
+    Roles, HIVE_CONFIG_SEED, ROLES_SEED,
+};
+
+pub fn process_set_cancellation_policy(ctx: Context<SetCancellationPolicy>, policy: CancellationPolicy) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    require!(ctx.accounts.roles.has_role(&admin, Role::Admin), FastVoteError::MissingRole);
+
+    let config = &mut ctx.accounts.hive_config;
+    config.hive = ctx.accounts.roles.hive;
+    config.cancellation_policy = policy;
+    config.bump = ctx.bumps.hive_config;
+
+    emit_event!(ctx, CancellationPolicySet {
+        hive: config.hive,
+        policy,
+        set_by: admin,
+    });
+
+    Ok(())
+}
+
+pub fn process_set_approval_rounding(ctx: Context<SetApprovalRounding>, rounding: ApprovalRounding) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    require!(ctx.accounts.roles.has_role(&admin, Role::Admin), FastVoteError::MissingRole);
+
+    let config = &mut ctx.accounts.hive_config;
+    config.hive = ctx.accounts.roles.hive;
+    config.approval_rounding = rounding;
+    config.bump = ctx.bumps.hive_config;
+
+    emit_event!(ctx, ApprovalRoundingSet {
+        hive: config.hive,
+        rounding,
+        set_by: admin,
+    });
+
+    Ok(())
+}
+
+/// Permissionless: the payer only covers the extra rent.
+pub fn process_migrate_hive_config(ctx: Context<MigrateHiveConfig>) -> Result<()> {
+    let info = ctx.accounts.hive_config.to_account_info();
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    require!(info.data_len() == HiveConfig::LEGACY_LEN, FastVoteError::AlreadyMigrated);
+    require!(
+        info.try_borrow_data()?.starts_with(HiveConfig::DISCRIMINATOR),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+
+    let rent = Rent::get()?.minimum_balance(HiveConfig::LEN);
+    let shortfall = rent.saturating_sub(info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    info.realloc(HiveConfig::LEN, true)?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetCancellationPolicy<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = HiveConfig::LEN,
+        seeds = [HIVE_CONFIG_SEED, roles.hive.as_ref()],
+        bump
+    )]
+    pub hive_config: Account<'info, HiveConfig>,
+    #[account(seeds = [ROLES_SEED, roles.hive.as_ref()], bump = roles.bump)]
+    pub roles: Account<'info, Roles>,
+    /// Must hold Role::Admin in the hive
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetApprovalRounding<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = HiveConfig::LEN,
+        seeds = [HIVE_CONFIG_SEED, roles.hive.as_ref()],
+        bump
+    )]
+    pub hive_config: Account<'info, HiveConfig>,
+    #[account(seeds = [ROLES_SEED, roles.hive.as_ref()], bump = roles.bump)]
+    pub roles: Account<'info, Roles>,
+    /// Must hold Role::Admin in the hive
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateHiveConfig<'info> {
+    /// CHECK: The legacy layout does not deserialize as HiveConfig; owner,
+    /// size and discriminator are checked in process_migrate_hive_config
+    #[account(mut, seeds = [HIVE_CONFIG_SEED, hive.key().as_ref()], bump)]
+    pub hive_config: UncheckedAccount<'info>,
+    /// CHECK: Only keys the config
+    pub hive: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
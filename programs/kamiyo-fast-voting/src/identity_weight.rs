@@ -0,0 +1,301 @@
+// Vote weight from agent-identity assets
+//
+// The hive's identity NFTs are Metaplex Core assets in one collection, each
+// carrying an Attributes plugin (tier, specialization, ...). An
+// identity-weighted action weighs a ballot by the value of the configured
+// attribute on the voter's asset, mapped through the IdentityWeights tiers.
+// The asset is parsed by hand so the program doesn't pull mpl-core into the
+// ER build, and the attribute only counts when the plugin's authority is the
+// asset's update authority: an owner-managed copy would let holders pick
+// their own tier. Token Metadata NFTs keep their attributes off-chain in the
+// JSON at the URI, so they cannot be weighed here.
+//
+// Each asset votes once per action: the IdentityClaim PDA is keyed by the
+// asset, so passing it to a second wallet does not buy another ballot.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    agent_registry, freeze, metrics, record_vote, vote_credits, Counter, FastAction, FastVote, FastVoteError,
+    GlobalConfig, IdentityClaim, IdentityTier, IdentityWeights, IdentityWeightsSet, VoteCredits, VoteMode, AGENT_SEED,
+    FAST_ACTION_SEED, FAST_ACTION_VERSION, FAST_VOTE_SEED, GLOBAL_CONFIG_SEED, HIVE_FREEZE_SEED, IDENTITY_CLAIM_SEED,
+    IDENTITY_WEIGHTS_SEED, MAX_IDENTITY_ATTRIBUTE_LEN, MAX_IDENTITY_TIERS, MPL_CORE_PROGRAM_ID, VOTE_CREDITS_SEED,
+};
+
+// mpl-core Key discriminants
+const KEY_ASSET_V1: u8 = 1;
+const KEY_PLUGIN_HEADER_V1: u8 = 3;
+const KEY_PLUGIN_REGISTRY_V1: u8 = 4;
+
+// PluginType / Plugin variant of Attributes
+const PLUGIN_ATTRIBUTES: u8 = 6;
+
+// mpl-core UpdateAuthority
+#[derive(AnchorDeserialize)]
+enum CoreUpdateAuthority {
+    None,
+    Address(#[allow(dead_code)] Pubkey),
+    Collection(Pubkey),
+}
+
+// mpl-core Authority
+#[derive(AnchorDeserialize, PartialEq)]
+enum CoreAuthority {
+    None,
+    Owner,
+    UpdateAuthority,
+    Address(#[allow(dead_code)] Pubkey),
+}
+
+// BaseAssetV1; the plugin header, if any, follows it directly
+#[derive(AnchorDeserialize)]
+struct CoreAsset {
+    key: u8,
+    owner: Pubkey,
+    update_authority: CoreUpdateAuthority,
+    _name: String,
+    _uri: String,
+    _seq: Option<u64>,
+}
+
+#[derive(AnchorDeserialize)]
+struct CorePluginHeader {
+    key: u8,
+    registry_offset: u64,
+}
+
+// PluginRegistryV1 without the external plugin records that follow
+#[derive(AnchorDeserialize)]
+struct CorePluginRegistry {
+    key: u8,
+    records: Vec<CoreRegistryRecord>,
+}
+
+#[derive(AnchorDeserialize)]
+struct CoreRegistryRecord {
+    plugin_type: u8,
+    authority: CoreAuthority,
+    offset: u64,
+}
+
+#[derive(AnchorDeserialize)]
+struct CoreAttribute {
+    key: String,
+    value: String,
+}
+
+/// Weight `weights` gives the identity `asset` held by `voter`
+pub fn read_identity_weight(asset: &AccountInfo, voter: &Pubkey, weights: &IdentityWeights) -> Result<u64> {
+    require_keys_eq!(*asset.owner, MPL_CORE_PROGRAM_ID, FastVoteError::InvalidIdentityAsset);
+    let data = asset.try_borrow_data()?;
+    let (owner, collection, header) = read_core_asset(&data).ok_or(FastVoteError::InvalidIdentityAsset)?;
+    require!(
+        owner == *voter && collection == weights.collection,
+        FastVoteError::InvalidIdentityAsset
+    );
+
+    let value = find_attribute(&data, header, &weights.attribute).ok_or(FastVoteError::IdentityNotWeighted)?;
+    let weight = weights.weight_of(&value).ok_or(FastVoteError::IdentityNotWeighted)?;
+    require!(weight > 0, FastVoteError::IdentityNotWeighted);
+    Ok(weight)
+}
+
+/// Holder and collection of a Core asset in a collection, and where its
+/// plugin header would start; the caller checks the account's owner
+pub(crate) fn read_core_asset(data: &[u8]) -> Option<(Pubkey, Pubkey, usize)> {
+    let mut rest = data;
+    let base = CoreAsset::deserialize(&mut rest).ok()?;
+    match base.update_authority {
+        CoreUpdateAuthority::Collection(collection) if base.key == KEY_ASSET_V1 => {
+            Some((base.owner, collection, data.len() - rest.len()))
+        }
+        _ => None,
+    }
+}
+
+/// Value of the update-authority-managed attribute `key`; `header` is where
+/// the plugin header would start
+fn find_attribute(data: &[u8], header: usize, key: &str) -> Option<String> {
+    let header = CorePluginHeader::deserialize(&mut data.get(header..)?).ok()?;
+    if header.key != KEY_PLUGIN_HEADER_V1 {
+        return None;
+    }
+    let registry =
+        CorePluginRegistry::deserialize(&mut data.get(usize::try_from(header.registry_offset).ok()?..)?).ok()?;
+    if registry.key != KEY_PLUGIN_REGISTRY_V1 {
+        return None;
+    }
+    let record = registry.records.iter().find(|record| record.plugin_type == PLUGIN_ATTRIBUTES)?;
+    if record.authority != CoreAuthority::UpdateAuthority {
+        return None;
+    }
+
+    let mut plugin = data.get(usize::try_from(record.offset).ok()?..)?;
+    if u8::deserialize(&mut plugin).ok()? != PLUGIN_ATTRIBUTES {
+        return None;
+    }
+    let attributes = Vec::<CoreAttribute>::deserialize(&mut plugin).ok()?;
+    attributes.into_iter().find(|attribute| attribute.key == key).map(|attribute| attribute.value)
+}
+
+fn validate(attribute: &str, tiers: &[IdentityTier]) -> Result<()> {
+    let valid_str = |s: &str| !s.is_empty() && s.len() <= MAX_IDENTITY_ATTRIBUTE_LEN;
+    require!(
+        valid_str(attribute) && (1..=MAX_IDENTITY_TIERS).contains(&tiers.len()),
+        FastVoteError::InvalidIdentityWeights
+    );
+    for (i, tier) in tiers.iter().enumerate() {
+        require!(
+            valid_str(&tier.value) && tier.weight > 0 && tiers[..i].iter().all(|other| other.value != tier.value),
+            FastVoteError::InvalidIdentityWeights
+        );
+    }
+    Ok(())
+}
+
+/// Config admin only; replaces the collection, attribute and tiers
+pub fn process_set_identity_weights(
+    ctx: Context<SetIdentityWeights>,
+    collection: Pubkey,
+    attribute: String,
+    tiers: Vec<IdentityTier>,
+) -> Result<()> {
+    require!(collection != Pubkey::default(), FastVoteError::InvalidIdentityWeights);
+    validate(&attribute, &tiers)?;
+
+    let weights = &mut ctx.accounts.identity_weights;
+    weights.collection = collection;
+    weights.attribute = attribute.clone();
+    weights.tiers = tiers.clone();
+    weights.bump = ctx.bumps.identity_weights;
+
+    emit_event!(ctx, IdentityWeightsSet { collection, attribute, tiers });
+
+    Ok(())
+}
+
+/// Ballot on an identity-weighted action, weighed by the voter's identity
+/// asset
+pub fn process_vote_fast_identity(
+    ctx: Context<VoteFastIdentity>,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+    delegation_epoch: u32,
+) -> Result<()> {
+    ctx.accounts.fast_action.check_delegation_epoch(delegation_epoch)?;
+    freeze::require_live(&ctx.accounts.hive_freeze, &ctx.accounts.fast_action)?;
+    require!(
+        ctx.accounts.fast_action.vote_mode == VoteMode::TokenWeighted && ctx.accounts.fast_action.identity_weighted,
+        FastVoteError::WrongVoteMode
+    );
+    let voter = ctx.accounts.voter.key();
+    let weight = read_identity_weight(&ctx.accounts.identity_asset, &voter, &ctx.accounts.identity_weights)?;
+
+    let claim = &mut ctx.accounts.identity_claim;
+    claim.voter = voter;
+    claim.bump = ctx.bumps.identity_claim;
+
+    agent_registry::require_eligible(&ctx.accounts.agent_record, &ctx.accounts.config)?;
+    let class = agent_registry::count_class_vote(&mut ctx.accounts.fast_action, &ctx.accounts.agent_record)?;
+    let clock = Clock::get()?;
+    let weight = agent_registry::aged_weight(&ctx.accounts.agent_record, &ctx.accounts.config, weight, clock.epoch)?;
+    let action = &ctx.accounts.fast_action;
+    let weight = action.early_weight(weight, clock.slot);
+    let weight = action.capped_weight(action.class_weight(class, weight));
+    vote_credits::charge_ballot(
+        &mut ctx.accounts.vote_credits,
+        voter,
+        ctx.bumps.vote_credits,
+        &ctx.accounts.config,
+    )?;
+
+    let event = record_vote(
+        &mut ctx.accounts.fast_action,
+        &mut ctx.accounts.fast_vote,
+        voter,
+        ctx.bumps.fast_vote,
+        vote_value,
+        voter_commitment,
+        weight,
+    )?;
+    metrics::record(&ctx.accounts.metrics, &ctx.accounts.fast_action.hive, Counter::Vote, Clock::get()?.slot)?;
+    emit_event!(ctx, event);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetIdentityWeights<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = IdentityWeights::LEN,
+        seeds = [IDENTITY_WEIGHTS_SEED],
+        bump
+    )]
+    pub identity_weights: Account<'info, IdentityWeights>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump, has_one = admin @ FastVoteError::Unauthorized)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(action_id: u64)]
+pub struct VoteFastIdentity<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = voter,
+        space = FastVote::LEN,
+        seeds = [FAST_VOTE_SEED, fast_action.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub fast_vote: Account<'info, FastVote>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoteCredits::LEN,
+        seeds = [VOTE_CREDITS_SEED, voter.key().as_ref()],
+        bump
+    )]
+    pub vote_credits: Account<'info, VoteCredits>,
+    #[account(seeds = [IDENTITY_WEIGHTS_SEED], bump = identity_weights.bump)]
+    pub identity_weights: Account<'info, IdentityWeights>,
+    /// CHECK: Core asset; owner program, holder, collection and attributes
+    /// parsed in read_identity_weight
+    pub identity_asset: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = voter,
+        space = IdentityClaim::LEN,
+        seeds = [IDENTITY_CLAIM_SEED, fast_action.key().as_ref(), identity_asset.key().as_ref()],
+        bump
+    )]
+    pub identity_claim: Account<'info, IdentityClaim>,
+    /// CHECK: The voter's AgentRecord PDA; read in agent_registry if it exists
+    #[account(seeds = [AGENT_SEED, voter.key().as_ref()], bump)]
+    pub agent_record: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// CHECK: The ER's Metrics PDA, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
+    /// CHECK: The HiveFreeze PDA of the action's hive, whether or not it
+    /// exists; read in freeze::require_live
+    #[account(seeds = [HIVE_FREEZE_SEED, fast_action.hive.as_ref()], bump)]
+    pub hive_freeze: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
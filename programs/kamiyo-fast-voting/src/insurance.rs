@@ -0,0 +1,248 @@
+// Insurance. A passed action can be acted on before its dispute window
+// closes, and a challenge that later voids it leaves whoever relied on it
+// out of pocket. A hive may keep an insurance pool for them: its authority
+// sets the share of each creation fee in the hive diverted from the reward
+// pool to the insurance pool, and anyone may add to it directly.
+//
+// Anyone damaged by a voided action of the hive files a claim for an amount,
+// committing to an off-chain account of the damage. The hive decides it by
+// an Emergency action carrying the claim's resolution hash: once that action
+// settles, resolve_claim pays the claim if it passed, or rejects it if it
+// failed. Either way the claim closes, so a rejected claimant may refile.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::dispute::is_settled;
+use crate::{
+    market, ActionKind, ClaimFiled, ClaimResolved, FastAction, FastVoteError, Hive, InsuranceClaim, InsuranceFunded,
+    InsurancePool, InsurancePoolInitialized, RewardPool, VoteResult, FAST_ACTION_SEED, FAST_ACTION_VERSION, HIVE_SEED,
+    INSURANCE_CLAIM_SEED, INSURANCE_POOL_SEED,
+};
+
+/// Diverts `hive`'s cut of the creation fee just charged into `index`, its
+/// insurance pool, if one was passed and it exists
+pub fn take_cut(reward_pool: &Account<RewardPool>, index: &Option<UncheckedAccount>, hive: Pubkey) -> Result<()> {
+    let Some(info) = index else {
+        return Ok(());
+    };
+    if info.data_is_empty() || reward_pool.creation_fee == 0 {
+        return Ok(());
+    }
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let mut pool = InsurancePool::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    require!(hive != Pubkey::default() && pool.hive == hive, FastVoteError::InvalidInsurancePool);
+
+    let cut = (reward_pool.creation_fee as u128 * pool.fee_bps as u128 / 10_000) as u64;
+    **reward_pool.to_account_info().try_borrow_mut_lamports()? -= cut;
+    **info.try_borrow_mut_lamports()? += cut;
+    pool.total_received = pool.total_received.saturating_add(cut);
+    pool.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+}
+
+/// Hive authority only
+pub fn process_initialize_insurance_pool(ctx: Context<InitializeInsurancePool>, fee_bps: u16) -> Result<()> {
+    require!(fee_bps <= 10_000, FastVoteError::InvalidInsuranceFee);
+
+    let pool = &mut ctx.accounts.insurance_pool;
+    pool.hive = ctx.accounts.hive_account.hive;
+    pool.fee_bps = fee_bps;
+    pool.total_received = 0;
+    pool.total_paid = 0;
+    pool.claims_paid = 0;
+    pool.bump = ctx.bumps.insurance_pool;
+
+    emit_event!(ctx, InsurancePoolInitialized {
+        hive: pool.hive,
+        fee_bps,
+        authority: ctx.accounts.authority.key(),
+    });
+
+    Ok(())
+}
+
+pub fn process_fund_insurance(ctx: Context<FundInsurance>, amount: u64) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.funder.to_account_info(),
+                to: ctx.accounts.insurance_pool.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    let pool = &mut ctx.accounts.insurance_pool;
+    pool.total_received = pool.total_received.saturating_add(amount);
+
+    emit_event!(ctx, InsuranceFunded {
+        hive: pool.hive,
+        funder: ctx.accounts.funder.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Base layer; the claimant pays for the claim account
+pub fn process_file_claim(ctx: Context<FileClaim>, amount: u64, evidence_hash: [u8; 32]) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(
+        amount > 0 && action.result == VoteResult::Voided && action.hive != Pubkey::default(),
+        FastVoteError::InvalidClaim
+    );
+
+    let claim = &mut ctx.accounts.claim;
+    claim.hive = action.hive;
+    claim.action = action.key();
+    claim.claimant = ctx.accounts.claimant.key();
+    claim.amount = amount;
+    claim.evidence_hash = evidence_hash;
+    claim.filed_slot = Clock::get()?.slot;
+    claim.bump = ctx.bumps.claim;
+
+    emit_event!(ctx, ClaimFiled {
+        claim: claim.key(),
+        hive: claim.hive,
+        action: claim.action,
+        claimant: claim.claimant,
+        amount,
+        evidence_hash,
+        resolution_hash: claim.resolution_hash(&claim.key()),
+    });
+
+    Ok(())
+}
+
+/// Permissionless, once the resolution action has settled
+pub fn process_resolve_claim(ctx: Context<ResolveClaim>) -> Result<()> {
+    let claim = &ctx.accounts.claim;
+    let resolution = &ctx.accounts.resolution_action;
+    require!(
+        resolution.action_hash == claim.resolution_hash(&claim.key()),
+        FastVoteError::ClaimMismatch
+    );
+
+    let slot = Clock::get()?.slot;
+    let approved = match resolution.result {
+        VoteResult::Passed { .. } => {
+            require!(market::is_executable(resolution, slot), FastVoteError::ActionNotExecutable);
+            true
+        }
+        VoteResult::Failed => {
+            require!(is_settled(resolution, slot), FastVoteError::DisputeWindowOpen);
+            false
+        }
+        _ => return err!(FastVoteError::ActionNotFinalized),
+    };
+
+    let paid = if approved { claim.amount } else { 0 };
+    if approved {
+        let pool_info = ctx.accounts.insurance_pool.to_account_info();
+        let reserve = Rent::get()?.minimum_balance(pool_info.data_len());
+        require!(
+            pool_info.lamports().saturating_sub(reserve) >= paid,
+            FastVoteError::InsuranceInsufficient
+        );
+        **pool_info.try_borrow_mut_lamports()? -= paid;
+        **ctx.accounts.claimant.try_borrow_mut_lamports()? += paid;
+
+        let pool = &mut ctx.accounts.insurance_pool;
+        pool.total_paid = pool.total_paid.saturating_add(paid);
+        pool.claims_paid = pool.claims_paid.saturating_add(1);
+    }
+
+    emit_event!(ctx, ClaimResolved {
+        claim: ctx.accounts.claim.key(),
+        hive: ctx.accounts.claim.hive,
+        claimant: ctx.accounts.claim.claimant,
+        resolution_action: ctx.accounts.resolution_action.key(),
+        approved,
+        paid,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct InitializeInsurancePool<'info> {
+    #[account(
+        seeds = [HIVE_SEED, hive_account.hive.as_ref()],
+        bump = hive_account.bump,
+        has_one = authority @ FastVoteError::Unauthorized
+    )]
+    pub hive_account: Account<'info, Hive>,
+    #[account(
+        init,
+        payer = authority,
+        space = InsurancePool::LEN,
+        seeds = [INSURANCE_POOL_SEED, hive_account.hive.as_ref()],
+        bump
+    )]
+    pub insurance_pool: Account<'info, InsurancePool>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct FundInsurance<'info> {
+    #[account(mut, seeds = [INSURANCE_POOL_SEED, insurance_pool.hive.as_ref()], bump = insurance_pool.bump)]
+    pub insurance_pool: Account<'info, InsurancePool>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct FileClaim<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    /// The claim's hive must keep a pool
+    #[account(seeds = [INSURANCE_POOL_SEED, fast_action.hive.as_ref()], bump = insurance_pool.bump)]
+    pub insurance_pool: Account<'info, InsurancePool>,
+    #[account(
+        init,
+        payer = claimant,
+        space = InsuranceClaim::LEN,
+        seeds = [INSURANCE_CLAIM_SEED, fast_action.key().as_ref(), claimant.key().as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, InsuranceClaim>,
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ResolveClaim<'info> {
+    #[account(
+        mut,
+        seeds = [INSURANCE_CLAIM_SEED, claim.action.as_ref(), claim.claimant.as_ref()],
+        bump = claim.bump,
+        has_one = claimant,
+        close = claimant
+    )]
+    pub claim: Account<'info, InsuranceClaim>,
+    #[account(mut, seeds = [INSURANCE_POOL_SEED, claim.hive.as_ref()], bump = insurance_pool.bump)]
+    pub insurance_pool: Account<'info, InsurancePool>,
+    #[account(
+        seeds = [FAST_ACTION_SEED, resolution_action.hive_seed(), &resolution_action.action_id.to_le_bytes()],
+        bump = resolution_action.bump,
+        constraint = resolution_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration,
+        constraint = resolution_action.kind == ActionKind::Emergency @ FastVoteError::ClaimMismatch,
+        constraint = resolution_action.hive == claim.hive @ FastVoteError::ClaimMismatch
+    )]
+    pub resolution_action: Account<'info, FastAction>,
+    /// CHECK: The claim's claimant; receives the payout and the claim's rent
+    #[account(mut)]
+    pub claimant: UncheckedAccount<'info>,
+}
@@ -0,0 +1,100 @@
+// Runtime invariant checks, compiled in by the `audit` feature for devnet and
+// staging deployments; the mainnet build leaves them out and keeps the lean
+// hot path. Each spends compute the program otherwise saves by trusting its
+// own bookkeeping:
+//
+// - vote_fast decodes the whole action after ActionView's in-place writes,
+//   so an offset that drifted from FastAction's layout shows up as a failed
+//   decode, a moved address or inconsistent counters;
+// - tally_and_commit recomputes the result digest it just wrote, and
+//   recounts any FastVote accounts passed after the prerequisite actions:
+//   a partial recount may not exceed the counters, and one covering every
+//   ballot must match them.
+//
+// A failed check is a program bug rather than a bad request, and aborts the
+// transaction with InvariantViolated.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    tally, FastAction, FastVote, FastVoteError, PrivacyLevel, VoteMode, FAST_ACTION_SEED, FAST_VOTE_SEED,
+};
+
+/// The counters and address of `action`, stored at `key`, agree
+pub fn check_action(key: &Pubkey, action: &FastAction) -> Result<()> {
+    let address = Pubkey::create_program_address(
+        &[FAST_ACTION_SEED, action.hive_seed(), &action.action_id.to_le_bytes(), &[action.bump]],
+        &crate::ID,
+    );
+    require!(address == Ok(*key), FastVoteError::InvariantViolated);
+    let cast = action.votes_for.checked_add(action.votes_against);
+    require!(cast.is_some_and(|cast| cast <= action.vote_count), FastVoteError::InvariantViolated);
+    Ok(())
+}
+
+/// check_action on the action stored in `info`, decoded in full
+pub fn check_account(info: &AccountInfo) -> Result<()> {
+    let action = FastAction::try_deserialize(&mut &info.try_borrow_data()?[..])
+        .map_err(|_| FastVoteError::InvariantViolated)?;
+    check_action(info.key, &action)
+}
+
+/// The result digest of the finalized `action` recomputes
+pub fn check_digest(action: &FastAction) -> Result<()> {
+    let digest = tally::result_digest(
+        action.action_id,
+        &action.action_hash,
+        action.votes_for,
+        action.votes_against,
+        &action.result,
+        action.finalized_slot,
+    );
+    require!(digest == action.result_digest, FastVoteError::InvariantViolated);
+    Ok(())
+}
+
+/// Recounts `ballots`, distinct FastVote accounts of the for/against
+/// `action` at `key` in ascending address order, against its counters.
+/// Private ballots' records do not all hold their choice and are not recounted.
+pub fn check_ballots(key: &Pubkey, action: &FastAction, ballots: &[AccountInfo]) -> Result<()> {
+    if ballots.is_empty() || action.option_count > 0 || action.privacy != PrivacyLevel::Public {
+        return Ok(());
+    }
+    let weighted = action.vote_mode == VoteMode::TokenWeighted;
+    let (mut votes_for, mut votes_against, mut weight_for, mut weight_against) = (0u32, 0u32, 0u64, 0u64);
+    let mut last = Pubkey::default();
+    for info in ballots {
+        require!(info.key() > last, FastVoteError::InvariantViolated);
+        last = info.key();
+        require_keys_eq!(*info.owner, crate::ID, FastVoteError::InvariantViolated);
+        let vote = FastVote::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require_keys_eq!(vote.fast_action, *key, FastVoteError::InvariantViolated);
+        let address = Pubkey::create_program_address(
+            &[FAST_VOTE_SEED, key.as_ref(), vote.voter.as_ref(), &[vote.bump]],
+            &crate::ID,
+        );
+        require!(address == Ok(info.key()), FastVoteError::InvariantViolated);
+
+        let weight = if weighted { vote.weight } else { 0 };
+        if vote.vote_value {
+            votes_for += 1;
+            weight_for = weight_for.saturating_add(weight);
+        } else {
+            votes_against += 1;
+            weight_against = weight_against.saturating_add(weight);
+        }
+    }
+
+    let within = votes_for <= action.votes_for
+        && votes_against <= action.votes_against
+        && weight_for <= action.weight_for
+        && weight_against <= action.weight_against;
+    require!(within, FastVoteError::InvariantViolated);
+    if ballots.len() == action.vote_count as usize {
+        let matches = votes_for == action.votes_for
+            && votes_against == action.votes_against
+            && (!weighted || (weight_for == action.weight_for && weight_against == action.weight_against));
+        require!(matches, FastVoteError::InvariantViolated);
+    }
+    Ok(())
+}
@@ -0,0 +1,162 @@
+// Keeper-cranked execution. A hive that does not run its own cranker posts
+// an ExecutionSchedule for a finalized action: the accounts execute_action
+// needs, checked against the callback registry, and a lamport reward. Any
+// keeper scanning for schedules can then build the call from the schedule
+// alone, stage it and send it once the result settles; execute_action pays
+// it the schedule's whole balance, reward and rent, when it is passed along.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::{
+    accounts_hash, CallbackRegistry, ExecutionSchedule, ExecutionScheduled, ExecutionUnscheduled, FastAction,
+    FastVoteError, KeeperPaid, ScheduledAccount, CALLBACK_REGISTRY_SEED, EXECUTION_SCHEDULE_SEED, FAST_ACTION_SEED,
+    FAST_ACTION_VERSION, MAX_SCHEDULED_ACCOUNTS,
+};
+
+/// `accounts` must be exactly what execute_action checks against `registry`
+fn check_accounts(registry: &CallbackRegistry, accounts: &[ScheduledAccount]) -> Result<()> {
+    let mut remaining = accounts;
+    for callback in &registry.callbacks[..registry.count as usize] {
+        let count = callback.account_count as usize;
+        require!(remaining.len() > count, FastVoteError::InvalidCallbackAccounts);
+        let (program, rest) = remaining.split_first().unwrap();
+        let (registered, rest) = rest.split_at(count);
+        remaining = rest;
+        require!(
+            program.pubkey == callback.program_id && !program.is_writable,
+            FastVoteError::InvalidCallbackAccounts
+        );
+        let metas: Vec<AccountMeta> = registered
+            .iter()
+            .map(|a| AccountMeta {
+                pubkey: a.pubkey,
+                is_signer: false,
+                is_writable: a.is_writable,
+            })
+            .collect();
+        require!(
+            accounts_hash(&metas) == callback.accounts_hash,
+            FastVoteError::InvalidCallbackAccounts
+        );
+    }
+    require!(remaining.is_empty(), FastVoteError::InvalidCallbackAccounts);
+    Ok(())
+}
+
+/// Once the action is finalized, so no callback can be added after. The
+/// funder may call it again to replace the accounts or add to the reward.
+pub fn process_schedule_execution(
+    ctx: Context<ScheduleExecution>,
+    reward: u64,
+    accounts: Vec<ScheduledAccount>,
+) -> Result<()> {
+    require!(ctx.accounts.fast_action.executed, FastVoteError::ActionNotFinalized);
+    require!(!ctx.accounts.registry.dispatched, FastVoteError::CallbacksDispatched);
+    require!(accounts.len() <= MAX_SCHEDULED_ACCOUNTS, FastVoteError::InvalidCallbackAccounts);
+    check_accounts(&ctx.accounts.registry, &accounts)?;
+
+    let funder = ctx.accounts.funder.key();
+    let schedule = &mut ctx.accounts.schedule;
+    if schedule.funder == Pubkey::default() {
+        schedule.action = ctx.accounts.fast_action.key();
+        schedule.funder = funder;
+        schedule.bump = ctx.bumps.schedule;
+    }
+    require_keys_eq!(schedule.funder, funder, FastVoteError::Unauthorized);
+    schedule.reward = schedule.reward.checked_add(reward).ok_or(FastVoteError::VoteOverflow)?;
+    schedule.account_count = accounts.len() as u8;
+    schedule.accounts = [ScheduledAccount::default(); MAX_SCHEDULED_ACCOUNTS];
+    schedule.accounts[..accounts.len()].copy_from_slice(&accounts);
+
+    if reward > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.funder.to_account_info(),
+                    to: ctx.accounts.schedule.to_account_info(),
+                },
+            ),
+            reward,
+        )?;
+    }
+
+    emit_event!(ctx, ExecutionScheduled {
+        action: ctx.accounts.schedule.action,
+        hive: ctx.accounts.fast_action.hive,
+        funder,
+        reward: ctx.accounts.schedule.reward,
+    });
+
+    Ok(())
+}
+
+/// Funder only. Closes the schedule, e.g. for an action that will never
+/// settle or was executed without it.
+pub fn process_cancel_execution(ctx: Context<CancelExecution>) -> Result<()> {
+    emit_event!(ctx, ExecutionUnscheduled {
+        action: ctx.accounts.schedule.action,
+        funder: ctx.accounts.funder.key(),
+    });
+
+    Ok(())
+}
+
+/// Called by execute_action after dispatch: pays `keeper` and closes the
+/// schedule.
+pub(crate) fn pay_keeper<'info>(
+    schedule: &Account<'info, ExecutionSchedule>,
+    hive: Pubkey,
+    keeper: Option<&UncheckedAccount<'info>>,
+) -> Result<KeeperPaid> {
+    let keeper = keeper.ok_or(FastVoteError::MissingKeeper)?;
+    let amount = schedule.to_account_info().lamports();
+    schedule.close(keeper.to_account_info())?;
+    Ok(KeeperPaid {
+        action: schedule.action,
+        hive,
+        keeper: keeper.key(),
+        amount,
+    })
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ScheduleExecution<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(seeds = [CALLBACK_REGISTRY_SEED, fast_action.key().as_ref()], bump = registry.bump)]
+    pub registry: Account<'info, CallbackRegistry>,
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = ExecutionSchedule::LEN,
+        seeds = [EXECUTION_SCHEDULE_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub schedule: Account<'info, ExecutionSchedule>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CancelExecution<'info> {
+    #[account(
+        mut,
+        close = funder,
+        seeds = [EXECUTION_SCHEDULE_SEED, schedule.action.as_ref()],
+        bump = schedule.bump,
+        has_one = funder @ FastVoteError::Unauthorized
+    )]
+    pub schedule: Account<'info, ExecutionSchedule>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+}
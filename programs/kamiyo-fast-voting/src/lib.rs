@@ -1,256 +1,2136 @@
 // KAMIYO Fast Voting - TEE-based real-time agent voting
 // MagicBlock Private Ephemeral Rollups for sub-50ms latency
 
+// anchor 0.31 IDL handlers still call AccountInfo::realloc
+#![allow(deprecated)]
+
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::ID as INSTRUCTIONS_SYSVAR_ID;
+#[cfg(not(feature = "no-er"))]
 use ephemeral_rollups_sdk::anchor::{delegate, ephemeral};
+#[cfg(not(feature = "no-er"))]
 use ephemeral_rollups_sdk::consts::{MAGIC_CONTEXT_ID, MAGIC_PROGRAM_ID};
+#[cfg(not(feature = "no-er"))]
 use ephemeral_rollups_sdk::cpi::DelegateConfig;
+#[cfg(not(feature = "no-er"))]
 use ephemeral_rollups_sdk::ephem::commit_and_undelegate_accounts;
 
-declare_id!("AakwnBstczs5KC2jKPfBuFLQZADXrx4oPH8FtJbhPxwA");
+/// Emit through `emit_cpi!` when built with `event-cpi` so indexers can read
+/// events from inner instructions instead of truncatable logs; `emit!` otherwise.
+/// The handler's `Context` must be passed as `$ctx`.
+macro_rules! emit_event {
+    ($ctx:ident, $event:expr) => {{
+        let event = $event;
+        #[cfg(feature = "event-cpi")]
+        {
+            let ctx = &$ctx;
+            anchor_lang::prelude::emit_cpi!(event);
+        }
+        #[cfg(not(feature = "event-cpi"))]
+        anchor_lang::prelude::emit!(event);
+    }};
+}
+
+pub mod activity;
+pub mod agent_registry;
+pub mod amend;
+pub mod attestation;
+pub mod audit;
+pub mod authority;
+pub mod batch;
+pub mod budget;
+pub mod callbacks;
+#[cfg(not(feature = "no-er"))]
+pub mod checkpoint;
+pub mod compact_vote;
+pub mod compressed_vote;
+pub mod config;
+pub mod config_change;
+pub mod conviction;
+pub mod creator_allowlist;
+pub mod dispute;
+pub mod draft;
+pub mod early_vote;
+pub mod election;
+pub mod emergency;
+pub mod epoch_stats;
+pub mod equivocation;
+pub mod escalation;
+pub mod expiry;
+pub mod extension;
+pub mod federation;
+pub mod foreign_tally;
+pub mod freeze;
+pub mod guardian;
+pub mod hive;
+pub mod hive_config;
+pub mod identity_weight;
+pub mod insurance;
+#[cfg(feature = "audit")]
+pub mod invariants;
+pub mod keeper;
+#[cfg(not(feature = "no-er"))]
+pub mod liveness;
+pub mod market;
+pub mod membership;
+pub mod metadata;
+pub mod metrics;
+pub mod migration;
+pub mod optimistic;
+pub mod options;
+pub mod outcome;
+pub mod priority;
+pub mod privacy;
+pub mod ranked;
+#[cfg(not(feature = "no-er"))]
+pub mod redundancy;
+pub mod relayers;
+pub mod rejection;
+pub mod result_proof;
+pub mod rewards;
+pub mod roles;
+pub mod runoff;
+pub mod sealed;
+pub mod sequencing;
+pub mod slashing;
+pub mod staging;
+pub mod stake_lock;
+pub mod standing;
+pub mod standings_board;
+pub mod tags;
+pub mod tally_region;
+pub mod task;
+pub mod template;
+pub mod token_weight;
+pub mod upgrade;
+pub mod validators;
+pub mod vote_credits;
+pub mod vote_delegation;
+pub mod vote_reason;
+pub mod vote_receipt;
+pub mod vote_rewards;
+pub mod voter_group;
+pub mod weight_cap;
+pub mod weight_snapshot;
+pub mod weight_strategy;
+
+// Accounts, events, seeds and errors live in kamiyo-hive-interface
+pub use kamiyo_hive_interface::*;
+
+pub use activity::*;
+pub use agent_registry::*;
+pub use amend::*;
+pub use attestation::*;
+pub use audit::*;
+pub use authority::*;
+pub use batch::*;
+pub use budget::*;
+pub use callbacks::*;
+#[cfg(not(feature = "no-er"))]
+pub use checkpoint::*;
+pub use compact_vote::*;
+pub use compressed_vote::*;
+pub use config::*;
+pub use config_change::*;
+pub use conviction::*;
+pub use creator_allowlist::*;
+pub use dispute::*;
+pub use draft::*;
+pub use early_vote::*;
+pub use election::*;
+pub use emergency::*;
+pub use epoch_stats::*;
+pub use equivocation::*;
+pub use escalation::*;
+pub use expiry::*;
+pub use extension::*;
+pub use federation::*;
+pub use foreign_tally::*;
+pub use freeze::*;
+pub use guardian::*;
+pub use hive::*;
+pub use hive_config::*;
+pub use identity_weight::*;
+pub use insurance::*;
+pub use keeper::*;
+#[cfg(not(feature = "no-er"))]
+pub use liveness::*;
+pub use market::*;
+pub use membership::*;
+pub use metadata::*;
+pub use metrics::*;
+pub use migration::*;
+pub use optimistic::*;
+pub use options::*;
+pub use outcome::*;
+pub use priority::*;
+pub use privacy::*;
+pub use ranked::*;
+#[cfg(not(feature = "no-er"))]
+pub use redundancy::*;
+pub use relayers::*;
+pub use rejection::*;
+pub use result_proof::*;
+pub use rewards::*;
+pub use roles::*;
+pub use runoff::*;
+pub use sealed::*;
+pub use sequencing::*;
+pub use slashing::*;
+pub use staging::*;
+pub use stake_lock::*;
+pub use standing::*;
+pub use standings_board::*;
+pub use tags::*;
+pub use tally_region::*;
+pub use task::*;
+pub use template::*;
+pub use upgrade::*;
+pub use validators::*;
+pub use vote_delegation::*;
+pub use vote_reason::*;
+pub use vote_receipt::*;
+pub use vote_rewards::*;
+pub use voter_group::*;
+pub use weight_cap::*;
+pub use weight_snapshot::*;
+pub use weight_strategy::*;
+
+declare_id!("AakwnBstczs5KC2jKPfBuFLQZADXrx4oPH8FtJbhPxwA");
+
+// `no-er` builds a plain base-layer program for validators without MagicBlock:
+// no delegation, and tally_and_commit finalizes in place
+#[cfg_attr(not(feature = "no-er"), ephemeral)]
+#[program]
+pub mod kamiyo_fast_voting {
+    use super::*;
+
+    /// Action IDs come from the global ActionCounter; clients derive the
+    /// action PDA from `action_counter.next_id` and retry if it moved.
+    pub fn create_fast_action(mut ctx: Context<CreateFastAction>, params: ActionParams) -> Result<()> {
+        let window_slots = params.kind.window_slots();
+        create_action(&mut ctx, params, window_slots)
+    }
+
+    /// Open several actions at once, from one set of create_fast_action
+    /// accounts per entry passed as remaining accounts; ids run consecutively
+    /// from the counter's next_id
+    pub fn create_actions_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateActionsBatch<'info>>,
+        batch: Vec<ActionParams>,
+    ) -> Result<()> {
+        batch::process_create_actions_batch(ctx, batch)
+    }
+
+    /// Open an action from a hive template, supplying only the fields that vary
+    pub fn create_from_template(ctx: Context<CreateFastAction>, args: TemplateArgs) -> Result<()> {
+        template::process_create_from_template(ctx, args)
+    }
+
+    /// A pinned validator's count of hosted actions must be under the
+    /// config's cap; see validators. An action with a backup validator must
+    /// be pinned to another one; see redundancy.
+    #[cfg(not(feature = "no-er"))]
+    pub fn delegate_action(ctx: Context<DelegateAction>, action_id: u64) -> Result<()> {
+        let validator = ctx.accounts.validator.as_ref().map(|v| v.key());
+        if let Some(validator) = &validator {
+            require_live_validator(ctx.accounts.validator_record.as_ref(), validator, Clock::get()?.slot)?;
+        }
+
+        // Remember who serves the action, for force_undelegate's event
+        let (hive_seed, hive, saturated) = {
+            let mut data = ctx.accounts.pda.try_borrow_mut_data()?;
+            let mut action = FastAction::try_deserialize(&mut &data[..])?;
+            require!(action.version == FAST_ACTION_VERSION, FastVoteError::ActionNeedsMigration);
+            require!(
+                action.backup_validator == Pubkey::default()
+                    || validator.is_some_and(|v| v != action.backup_validator),
+                FastVoteError::InvalidPrimaryValidator
+            );
+            let record = validator.and(ctx.accounts.validator_record.as_deref_mut());
+            let saturated = validators::count_action(&mut action, record, &ctx.accounts.config)?;
+            action.delegated_validator = validator.unwrap_or_default();
+            action.delegation_epoch = action.delegation_epoch.wrapping_add(1);
+            action.delegation_state = DelegationState::Delegated;
+            action.log.push(Clock::get()?.slot, LogTag::Delegated, action.delegation_epoch as u64);
+            action.try_serialize(&mut &mut data[..])?;
+            (action.hive_seed().to_vec(), action.hive, saturated)
+        };
+
+        // Verify PDA matches expected derivation
+        let (expected_pda, _) = Pubkey::find_program_address(
+            &[FAST_ACTION_SEED, &hive_seed, &action_id.to_le_bytes()],
+            &crate::ID,
+        );
+        require!(ctx.accounts.pda.key() == expected_pda, FastVoteError::InvalidPda);
+
+        emit_event!(ctx, ActionDelegated {
+            action: expected_pda,
+            hive,
+            validator: validator.unwrap_or_default(),
+            slot: Clock::get()?.slot,
+        });
+        if let Some(record) = ctx.accounts.validator_record.as_ref().filter(|_| saturated) {
+            emit_event!(ctx, ValidatorSaturated {
+                validator: record.validator,
+                action: expected_pda,
+                active_actions: record.active_actions,
+                slot: Clock::get()?.slot,
+            });
+        }
+
+        ctx.accounts.delegate_pda(
+            &ctx.accounts.payer,
+            &[FAST_ACTION_SEED, &hive_seed, &action_id.to_le_bytes()],
+            DelegateConfig {
+                validator,
+                ..Default::default()
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// `delegation_epoch` must match the action's, so a signed ballot cannot
+    /// be replayed once the action has been delegated or undelegated again.
+    /// A collection-gated action takes the voter's NFT as remaining
+    /// accounts; see membership. A vote key's ballot is its agent's; see
+    /// agent_registry.
+    pub fn vote_fast(
+        ctx: Context<VoteFast>,
+        action_id: u64,
+        vote_value: bool,
+        voter_commitment: [u8; 32],
+        delegation_epoch: u32,
+    ) -> Result<()> {
+        tally_region::process_vote_fast(ctx, action_id, vote_value, voter_commitment, delegation_epoch)
+    }
+
+    /// vote_fast in its compact encoding, for batching ballots into one
+    /// transaction: `ballot` is a packed BallotChoice, which may also pick an
+    /// option on a headcount multi-option action, and `commitment` a
+    /// truncated voter commitment. See compact_vote.
+    #[instruction(discriminator = VOTE_COMPACT_DISCRIMINATOR)]
+    pub fn vote_compact(
+        ctx: Context<VoteFast>,
+        ballot: u8,
+        delegation_epoch: u32,
+        commitment: Option<[u8; 16]>,
+    ) -> Result<()> {
+        compact_vote::process_vote_compact(ctx, ballot, delegation_epoch, commitment)
+    }
+
+    /// Token-weighted ballot on the voter's live balance, which stays locked
+    /// through the config's stake lock program until the action is final;
+    /// see stake_lock. Base layer only. Delegated weight is not claimable
+    /// here; a weight snapshot counts it
+    pub fn vote_fast_weighted(
+        ctx: Context<VoteFastWeighted>,
+        _action_id: u64,
+        vote_value: bool,
+        voter_commitment: [u8; 32],
+        delegation_epoch: u32,
+    ) -> Result<()> {
+        ctx.accounts.fast_action.check_delegation_epoch(delegation_epoch)?;
+        freeze::require_live(&ctx.accounts.hive_freeze, &ctx.accounts.fast_action)?;
+        require!(
+            ctx.accounts.fast_action.vote_mode == VoteMode::TokenWeighted,
+            FastVoteError::WrongVoteMode
+        );
+        require!(!ctx.accounts.fast_action.identity_weighted, FastVoteError::WrongVoteMode);
+        require!(
+            ctx.accounts.fast_action.stake_lock_program == Pubkey::default(),
+            FastVoteError::WrongVoteMode
+        );
+        require!(ctx.accounts.weight_snapshot.data_is_empty(), FastVoteError::WeightSnapshotPosted);
+        require!(ctx.accounts.weight_strategy.data_is_empty(), FastVoteError::WrongVoteMode);
+        require!(ctx.accounts.fast_action.quorum_weight_bps == 0, FastVoteError::WeightSnapshotRequired);
+        let program = ctx.accounts.config.stake_lock_program;
+        require!(program != Pubkey::default(), FastVoteError::InvalidStakeLock);
+        require!(ctx.remaining_accounts.is_empty(), FastVoteError::InvalidDelegation);
+
+        let voter = ctx.accounts.voter.key();
+        let balance = token_weight::read_locked_token_weight(
+            &ctx.accounts.voter_token_account,
+            &ctx.accounts.weight_mint,
+            &voter,
+            &ctx.accounts.config,
+            Clock::get()?.unix_timestamp,
+        )?;
+        let weight =
+            vote_delegation::retained_share(&ctx.accounts.fast_action, &ctx.accounts.vote_delegation, balance)?;
+        require!(weight > 0, FastVoteError::NoVotingWeight);
+        let token_account = ctx.accounts.voter_token_account.key();
+        let first = stake_lock::take_share(
+            &mut ctx.accounts.stake_lock,
+            ctx.bumps.stake_lock,
+            &mut ctx.accounts.stake_lock_claim,
+            ctx.bumps.stake_lock_claim,
+            voter,
+            program,
+            token_account,
+        )?;
+        if first {
+            stake_lock::invoke_hook(
+                true,
+                &ctx.accounts.stake_lock_program,
+                &ctx.accounts.lock_authority,
+                ctx.bumps.lock_authority,
+                &ctx.accounts.voter.to_account_info(),
+                &ctx.accounts.voter_token_account,
+                &ctx.accounts.weight_mint,
+            )?;
+        }
+        let active = ctx.accounts.stake_lock.active;
+
+        agent_registry::require_eligible(&ctx.accounts.agent_record, &ctx.accounts.config)?;
+        let class = agent_registry::count_class_vote(&mut ctx.accounts.fast_action, &ctx.accounts.agent_record)?;
+        let clock = Clock::get()?;
+        let weight =
+            agent_registry::aged_weight(&ctx.accounts.agent_record, &ctx.accounts.config, weight, clock.epoch)?;
+        let action = &ctx.accounts.fast_action;
+        let weight = action.early_weight(weight, clock.slot);
+        let weight = action.capped_weight(action.class_weight(class, weight));
+        vote_credits::charge_ballot(
+            &mut ctx.accounts.vote_credits,
+            voter,
+            ctx.bumps.vote_credits,
+            &ctx.accounts.config,
+        )?;
+
+        let event = record_vote(
+            &mut ctx.accounts.fast_action,
+            &mut ctx.accounts.fast_vote,
+            voter,
+            ctx.bumps.fast_vote,
+            vote_value,
+            voter_commitment,
+            weight,
+        )?;
+        metrics::record(&ctx.accounts.metrics, &ctx.accounts.fast_action.hive, Counter::Vote, Clock::get()?.slot)?;
+        emit_event!(ctx, event);
+        emit_event!(ctx, StakeLocked {
+            voter,
+            action: ctx.accounts.fast_action.key(),
+            hive: ctx.accounts.fast_action.hive,
+            token_account,
+            active,
+        });
+
+        Ok(())
+    }
+
+    /// Token-weighted ballot on an action with a weight snapshot; `proof`
+    /// leads from the voter's (voter, weight) leaf to the snapshot's root
+    pub fn vote_fast_snapshot(
+        ctx: Context<VoteFastSnapshot>,
+        _action_id: u64,
+        vote_value: bool,
+        voter_commitment: [u8; 32],
+        delegation_epoch: u32,
+        weight: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        weight_snapshot::process_vote_fast_snapshot(ctx, vote_value, voter_commitment, delegation_epoch, weight, proof)
+    }
+
+    /// Ballot on an identity-weighted action; weight comes from the
+    /// configured attribute of the voter's Core identity asset
+    pub fn vote_fast_identity(
+        ctx: Context<VoteFastIdentity>,
+        _action_id: u64,
+        vote_value: bool,
+        voter_commitment: [u8; 32],
+        delegation_epoch: u32,
+    ) -> Result<()> {
+        identity_weight::process_vote_fast_identity(ctx, vote_value, voter_commitment, delegation_epoch)
+    }
+
+    /// Ballot of a registered voter group, signed by its threshold key in
+    /// an ed25519 instruction earlier in the transaction; anyone may submit
+    /// it, or only the action's pinned relayers; see relayers
+    pub fn vote_fast_group(
+        ctx: Context<VoteFastGroup>,
+        _action_id: u64,
+        vote_value: bool,
+        voter_commitment: [u8; 32],
+        delegation_epoch: u32,
+    ) -> Result<()> {
+        voter_group::process_vote_fast_group(ctx, vote_value, voter_commitment, delegation_epoch)
+    }
+
+    /// Register a threshold-key voter group, or replace its members,
+    /// threshold and weight; config admin only
+    pub fn set_voter_group(
+        ctx: Context<SetVoterGroup>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+        weight: u64,
+    ) -> Result<()> {
+        voter_group::process_set_voter_group(ctx, members, threshold, weight)
+    }
+
+    /// Remove a voter group; config admin only
+    pub fn remove_voter_group(ctx: Context<RemoveVoterGroup>) -> Result<()> {
+        voter_group::process_remove_voter_group(ctx)
+    }
+
+    /// Take relayed ballots, such as voter groups', only from `relayers`;
+    /// action authority only, before the first ballot
+    pub fn set_action_relayers(
+        ctx: Context<SetActionRelayers>,
+        relayers: Vec<Pubkey>,
+        fallback_slots: u64,
+    ) -> Result<()> {
+        relayers::process_set_action_relayers(ctx, relayers, fallback_slots)
+    }
+
+    /// Start the delay after which a voter group's members may submit its
+    /// ballot themselves; group members only
+    pub fn request_self_submit(ctx: Context<RequestSelfSubmit>) -> Result<()> {
+        relayers::process_request_self_submit(ctx)
+    }
+
+    /// Accept tallies from an EVM hive contract on the hive's actions; hive
+    /// admin only
+    pub fn register_foreign_hive(
+        ctx: Context<RegisterForeignHive>,
+        chain: u16,
+        emitter: [u8; 32],
+        max_weight: u64,
+    ) -> Result<()> {
+        foreign_tally::process_register_foreign_hive(ctx, chain, emitter, max_weight)
+    }
+
+    /// Fold a foreign hive's tally, from a Wormhole PostedVAA, into an open
+    /// action as one voter; permissionless, on the base layer
+    pub fn submit_foreign_tally(ctx: Context<SubmitForeignTally>) -> Result<()> {
+        foreign_tally::process_submit_foreign_tally(ctx)
+    }
+
+    /// Ballot on an action with a weight strategy; the weight comes from the
+    /// strategy program's compute_weight, which is passed the remaining
+    /// accounts
+    pub fn vote_fast_strategy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, VoteFastStrategy<'info>>,
+        _action_id: u64,
+        vote_value: bool,
+        voter_commitment: [u8; 32],
+        delegation_epoch: u32,
+    ) -> Result<()> {
+        weight_strategy::process_vote_fast_strategy(ctx, vote_value, voter_commitment, delegation_epoch)
+    }
+
+    /// Token-weighted ballot on a stake-locked action; the voter's token
+    /// account is locked through the action's stake lock program until the
+    /// action is final
+    pub fn vote_fast_locked(
+        ctx: Context<VoteFastLocked>,
+        _action_id: u64,
+        vote_value: bool,
+        voter_commitment: [u8; 32],
+        delegation_epoch: u32,
+    ) -> Result<()> {
+        stake_lock::process_vote_fast_locked(ctx, vote_value, voter_commitment, delegation_epoch)
+    }
+
+    /// Give back a finalized action's share of a voter's stake lock;
+    /// releases the stake when no other action holds it. Permissionless
+    pub fn release_stake_lock(ctx: Context<ReleaseStakeLock>) -> Result<()> {
+        stake_lock::process_release_stake_lock(ctx)
+    }
+
+    /// Set the identity collection, the attribute weighed and its tiers;
+    /// config admin only
+    pub fn set_identity_weights(
+        ctx: Context<SetIdentityWeights>,
+        collection: Pubkey,
+        attribute: String,
+        tiers: Vec<IdentityTier>,
+    ) -> Result<()> {
+        identity_weight::process_set_identity_weights(ctx, collection, attribute, tiers)
+    }
+
+    /// Post the Merkle root of an action's voter weights and their total
+    /// before its first vote; the config's snapshotter or an allowlisted
+    /// validator only
+    pub fn post_weight_snapshot(
+        ctx: Context<PostWeightSnapshot>,
+        root: [u8; 32],
+        total_weight: u64,
+    ) -> Result<()> {
+        weight_snapshot::process_post_weight_snapshot(ctx, root, total_weight)
+    }
+
+    /// Weigh an action's ballots by a strategy program with `params`,
+    /// before its first vote; action authority only
+    pub fn set_weight_strategy(ctx: Context<SetWeightStrategy>, params: Vec<u8>) -> Result<()> {
+        weight_strategy::process_set_weight_strategy(ctx, params)
+    }
+
+    /// Store the action's ballots as ZK-compressed accounts in
+    /// `address_tree` instead of FastVote PDAs; action authority only,
+    /// before the first vote
+    pub fn enable_compressed_ballots(ctx: Context<EnableCompressedBallots>, address_tree: Pubkey) -> Result<()> {
+        compressed_vote::process_enable_compressed_ballots(ctx, address_tree)
+    }
+
+    /// vote_fast for an action with compressed ballots, on the base layer.
+    /// `proof` comes from the Light prover for the ballot's new address.
+    pub fn vote_fast_compressed(
+        ctx: Context<VoteFastCompressed>,
+        _action_id: u64,
+        vote_value: bool,
+        voter_commitment: [u8; 32],
+        delegation_epoch: u32,
+        proof: AddressProof,
+    ) -> Result<()> {
+        compressed_vote::process_vote_fast_compressed(ctx, vote_value, voter_commitment, delegation_epoch, proof)
+    }
+
+    /// Recount a page of a compressed action's ballots, in ascending address
+    /// order, against a Light validity proof over them
+    pub fn verify_compressed_tally_page(
+        ctx: Context<VerifyCompressedTallyPage>,
+        ballots: Vec<CompressedBallot>,
+        proof: CompressedProof,
+    ) -> Result<()> {
+        compressed_vote::process_verify_compressed_tally_page(ctx, ballots, proof)
+    }
+
+    /// Open a commit-reveal ballot in the reveal window after the deadline,
+    /// counting its choice; see privacy
+    pub fn reveal_ballot(ctx: Context<RevealBallot>, vote_value: bool, salt: [u8; 32]) -> Result<()> {
+        privacy::process_reveal_ballot(ctx, vote_value, salt)
+    }
+
+    /// Allow, or disallow, a program as a verifier of anonymous ballots;
+    /// admin only
+    pub fn set_ballot_verifier(ctx: Context<SetBallotVerifier>, allowed: bool) -> Result<()> {
+        privacy::process_set_ballot_verifier(ctx, allowed)
+    }
+
+    /// Name an AnonymousZk action's ballot verifier, one the admin allows,
+    /// and fix its members root to the hive's; action authority only, before
+    /// the first vote
+    pub fn enable_anonymous_ballots(ctx: Context<EnableAnonymousBallots>) -> Result<()> {
+        privacy::process_enable_anonymous_ballots(ctx)
+    }
+
+    /// Ballot on an AnonymousZk action under a fresh nullifier, with the
+    /// verifier's proof that it belongs to an eligible voter
+    pub fn vote_anonymous(
+        ctx: Context<VoteAnonymous>,
+        nullifier: [u8; 32],
+        vote_value: bool,
+        proof: Vec<u8>,
+    ) -> Result<()> {
+        privacy::process_vote_anonymous(ctx, nullifier, vote_value, proof)
+    }
+
+    /// Prerequisite actions, if any, are passed as remaining accounts in
+    /// declaration order. Each must be tallied and undelegated first: one
+    /// still delegated elsewhere reads as the delegation program's account
+    /// and fails with DependencyDelegated. A hive with result attestors
+    /// needs their ed25519 signatures earlier in the transaction; see
+    /// attestation. So does an action with a backup validator, its
+    /// primary's; see redundancy.
+    pub fn tally_and_commit(ctx: Context<TallyAndCommit>) -> Result<()> {
+        let hive = ctx.accounts.fast_action.hive;
+        if hive != Pubkey::default() {
+            require_role(ctx.accounts.roles.as_ref(), &hive, &ctx.accounts.payer.key(), Role::Tallier)?;
+        }
+
+        if let Some(blocked) = check_dependencies(&ctx.accounts.fast_action, ctx.remaining_accounts)? {
+            emit_event!(ctx, blocked);
+            return err!(FastVoteError::DependencyNotPassed);
+        }
+
+        let clock = Clock::get()?;
+        let tallied = tally_checked(&ctx.accounts.fast_action, ctx.accounts.tally_audit.as_ref(), clock.slot);
+        #[cfg(feature = "rejection-events")]
+        if let Err(err) = &tallied {
+            if let Some(rejected) = rejected(&ctx.accounts.fast_action, err, clock.slot) {
+                emit_event!(ctx, rejected);
+            }
+        }
+
+        ctx.accounts.fast_action.result = tallied?;
+        let margin = CategoryConfig::contest_margin(&ctx.accounts.category_config, ctx.accounts.fast_action.kind)?;
+        let escalated = escalate(&ctx.accounts.fast_action, margin)?;
+        if escalated != ctx.accounts.fast_action.result {
+            let action = &ctx.accounts.fast_action;
+            emit_event!(ctx, ResultContested {
+                action: action.key(),
+                hive: action.hive,
+                action_id: action.action_id,
+                approval_pct: action.approval_pct()?,
+                threshold: action.threshold,
+                margin,
+            });
+            ctx.accounts.fast_action.result = escalated;
+        }
+        let attested = attestation::require_attested(
+            &ctx.accounts.result_attestors,
+            &ctx.accounts.instructions,
+            &ctx.accounts.fast_action,
+        )?;
+        privacy::require_finalizable(&ctx.accounts.fast_action, attested.is_some(), clock.slot)?;
+
+        let action = &mut ctx.accounts.fast_action;
+        action.executed = true;
+        action.finalized_slot = clock.slot;
+        action.result_digest = tally::result_digest(
+            action.action_id,
+            &action.action_hash,
+            action.votes_for,
+            action.votes_against,
+            &action.result,
+            action.finalized_slot,
+        );
+        action.log_result(clock.slot);
+        action.fee_rebate =
+            ctx.accounts.config.fee_rebate.rebate(action.creation_fee, action.vote_count, action.electorate);
+        metrics::record(&ctx.accounts.metrics, &action.hive, Counter::Finalized, clock.slot)?;
+        epoch_stats::record_finalized(&ctx.accounts.epoch_stats, action, clock.epoch)?;
+        let entry = standings_board::entry_of(action.key(), action, clock.slot);
+        standings_board::post(&ctx.accounts.standings_board, &action.hive, &entry)?;
+        #[cfg(feature = "audit")]
+        {
+            invariants::check_action(&action.key(), action)?;
+            invariants::check_digest(action)?;
+            let ballots = &ctx.remaining_accounts[action.dependency_count as usize..];
+            invariants::check_ballots(&action.key(), action, ballots)?;
+        }
+
+        #[cfg(not(feature = "no-er"))]
+        {
+            if action.backup_validator != Pubkey::default() {
+                let digest = attestation_digest(&action.key(), action.votes_for, action.votes_against, &action.result);
+                redundancy::require_signed_commit(&ctx.accounts.instructions, &action.delegated_validator, &digest)?;
+            }
+            action.delegation_epoch = action.delegation_epoch.wrapping_add(1);
+            action.delegation_state = DelegationState::Undelegated;
+            let epoch = action.delegation_epoch as u64;
+            action.log.push(clock.slot, LogTag::Undelegated, epoch);
+            action.exit(&crate::ID)?;
+
+            commit_and_undelegate_accounts(
+                &ctx.accounts.payer,
+                vec![&action.to_account_info()],
+                &ctx.accounts.magic_context,
+                &ctx.accounts.magic_program,
+            )?;
+        }
+
+        emit_event!(ctx, FastActionExecuted {
+            action: action.key(),
+            hive: action.hive,
+            action_id: action.action_id,
+            votes_for: action.votes_for,
+            votes_against: action.votes_against,
+            weight_for: action.weight_for,
+            weight_against: action.weight_against,
+            result: action.result.clone(),
+            finalized_slot: action.finalized_slot,
+            result_digest: action.result_digest,
+        });
+        if let Some(attested) = attested {
+            emit_event!(ctx, attested);
+        }
+        if ctx.accounts.fast_action.fee_rebate > 0 {
+            let action = &ctx.accounts.fast_action;
+            emit_event!(ctx, FeeRebateEarned {
+                action: action.key(),
+                hive: action.hive,
+                action_id: action.action_id,
+                votes: action.vote_count,
+                electorate: action.electorate,
+                amount: action.fee_rebate,
+            });
+        }
+        #[cfg(not(feature = "no-er"))]
+        emit_event!(ctx, ActionUndelegated {
+            action: ctx.accounts.fast_action.key(),
+            hive: ctx.accounts.fast_action.hive,
+            slot: clock.slot,
+        });
+
+        Ok(())
+    }
+
+    /// Recount a page of an audited action's FastVote accounts, passed as
+    /// remaining accounts in ascending address order
+    pub fn verify_tally_page(ctx: Context<VerifyTallyPage>) -> Result<()> {
+        audit::process_verify_tally_page(ctx)
+    }
+
+    /// Read-only: the action's live standing, as return data
+    pub fn get_standing(ctx: Context<GetStanding>, _action_id: u64) -> Result<Standing> {
+        standing::process_get_standing(ctx)
+    }
+
+    /// Read-only: what a tally would record if voting closed now, as return
+    /// data
+    pub fn preview_tally(ctx: Context<PreviewTally>, _action_id: u64) -> Result<TallyPreview> {
+        standing::process_preview_tally(ctx)
+    }
+
+    /// For CPI callers: fails unless the action passed, and returns
+    /// ActionPassed as return data. See kamiyo-voting-interface.
+    pub fn assert_action_passed(ctx: Context<AssertActionPassed>) -> Result<ActionPassed> {
+        outcome::process_assert_action_passed(ctx)
+    }
+
+    pub fn cancel_action(ctx: Context<CancelAction>, _action_id: u64) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        let action = &mut ctx.accounts.fast_action;
+        require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+        let policy = HiveConfig::cancellation_policy(&ctx.accounts.hive_config)?;
+        let slot = Clock::get()?.slot;
+        require!(policy.allows(action, slot), FastVoteError::CancellationClosed);
+
+        // The action authority can always cancel; hive Cancellers can cancel any hive action
+        if authority != action.authority {
+            require!(action.hive != Pubkey::default(), FastVoteError::Unauthorized);
+            require_role(ctx.accounts.roles.as_ref(), &action.hive, &authority, Role::Canceller)?;
+        }
+
+        action.executed = true;
+        action.result = VoteResult::Cancelled;
+        action.log_result(slot);
+        metrics::record(&ctx.accounts.metrics, &action.hive, Counter::Cancelled, slot)?;
+
+        emit_event!(ctx, FastActionCancelled {
+            action: action.key(),
+            hive: action.hive,
+            action_id: action.action_id,
+        });
+
+        Ok(())
+    }
+
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        weight_mint: Pubkey,
+        weight_extensions: u8,
+        transfer_hook_program: Pubkey,
+    ) -> Result<()> {
+        config::process_initialize_config(ctx, weight_mint, weight_extensions, transfer_hook_program)
+    }
+
+    pub fn update_config(ctx: Context<UpdateConfig>, update: ConfigUpdate) -> Result<()> {
+        config::process_update_config(ctx, update)
+    }
+
+    /// Bond the signer as a voting agent, or top up its bond
+    pub fn register_agent(ctx: Context<RegisterAgent>, amount: u64) -> Result<()> {
+        agent_registry::process_register_agent(ctx, amount)
+    }
+
+    /// Config admin only: deregister an agent and refund its bond
+    pub fn evict_agent(ctx: Context<EvictAgent>) -> Result<()> {
+        agent_registry::process_evict_agent(ctx)
+    }
+
+    /// Config admin only: set the class an agent votes as
+    pub fn set_agent_class(ctx: Context<SetAgentClass>, class: AgentClass) -> Result<()> {
+        agent_registry::process_set_agent_class(ctx, class)
+    }
+
+    /// Grow an AgentRecord created before its registration epoch was kept
+    pub fn migrate_agent_record(ctx: Context<MigrateAgentRecord>) -> Result<()> {
+        agent_registry::process_migrate_agent_record(ctx)
+    }
+
+    /// Let `key`, co-signing, cast vote_fast ballots as the signing agent
+    pub fn set_vote_key(ctx: Context<SetVoteKey>) -> Result<()> {
+        agent_registry::process_set_vote_key(ctx)
+    }
+
+    /// Unbind one of the signing agent's vote keys
+    pub fn clear_vote_key(ctx: Context<ClearVoteKey>) -> Result<()> {
+        agent_registry::process_clear_vote_key(ctx)
+    }
+
+    /// Split the signer's token weight across delegates, keeping the rest
+    pub fn set_vote_delegation(ctx: Context<SetVoteDelegation>, shares: Vec<DelegationShare>) -> Result<()> {
+        vote_delegation::process_set_vote_delegation(ctx, shares)
+    }
+
+    /// Create `layer`'s Metrics PDA, on that layer; config admin only
+    pub fn initialize_metrics(ctx: Context<InitializeMetrics>, layer: MetricsLayer) -> Result<()> {
+        metrics::process_initialize_metrics(ctx, layer)
+    }
+
+    /// Create `layer`'s Metrics PDA for the hive's actions alone, on that
+    /// layer; hive authority only
+    pub fn initialize_hive_metrics(ctx: Context<InitializeHiveMetrics>, layer: MetricsLayer) -> Result<()> {
+        metrics::process_initialize_hive_metrics(ctx, layer)
+    }
+
+    /// Create the hive's standings board, on the ephemeral rollup; hive
+    /// authority only
+    pub fn initialize_standings_board(ctx: Context<InitializeStandingsBoard>) -> Result<()> {
+        standings_board::process_initialize_standings_board(ctx)
+    }
+
+    /// Create the statistics of `hive`'s actions finalized in `epoch`, on the
+    /// layer they finalize on; permissionless
+    pub fn initialize_epoch_stats(ctx: Context<InitializeEpochStats>, hive: Pubkey, epoch: u64) -> Result<()> {
+        epoch_stats::process_initialize_epoch_stats(ctx, hive, epoch)
+    }
+
+    /// Set or clear the default per-voter weight cap for an ActionKind
+    pub fn set_weight_cap(ctx: Context<SetWeightCap>, kind: ActionKind, cap: Option<WeightCap>) -> Result<()> {
+        weight_cap::process_set_weight_cap(ctx, kind, cap)
+    }
+
+    /// Set or restore the default early-vote curve for an ActionKind
+    pub fn set_early_vote_curve(
+        ctx: Context<SetEarlyVoteCurve>,
+        kind: ActionKind,
+        curve: Option<EarlyVoteCurve>,
+    ) -> Result<()> {
+        early_vote::process_set_early_vote_curve(ctx, kind, curve)
+    }
+
+    /// Apply the config change a passed ConfigChange action voted for, once
+    /// its timelock expires; permissionless
+    pub fn apply_config_change(ctx: Context<ApplyConfigChange>, change: ConfigChange) -> Result<()> {
+        config_change::process_apply_config_change(ctx, change)
+    }
+
+    /// Deploy the buffer a passed ProgramUpgrade action voted for, once its
+    /// timelock expires; permissionless
+    pub fn execute_upgrade(ctx: Context<ExecuteUpgrade>, upgrade: ProgramUpgrade) -> Result<()> {
+        upgrade::process_execute_upgrade(ctx, upgrade)
+    }
+
+    /// Start the treasury payment stream a passed Budget action voted for;
+    /// permissionless
+    pub fn open_stream(ctx: Context<OpenStream>, budget: Budget) -> Result<()> {
+        budget::process_open_stream(ctx, budget)
+    }
+
+    /// Pay a stream's vested amount to its recipient; permissionless
+    pub fn withdraw_stream(ctx: Context<WithdrawStream>) -> Result<()> {
+        budget::process_withdraw_stream(ctx)
+    }
+
+    /// Stop a stream's vesting once a Budget action committing to its
+    /// cancel_hash has passed; permissionless
+    pub fn cancel_stream(ctx: Context<CancelStream>) -> Result<()> {
+        budget::process_cancel_stream(ctx)
+    }
+
+    pub fn initialize_roles(ctx: Context<InitializeRoles>) -> Result<()> {
+        roles::process_initialize_roles(ctx)
+    }
+
+    pub fn grant_role(ctx: Context<ManageRoles>, member: Pubkey, role: Role) -> Result<()> {
+        roles::process_grant_role(ctx, member, role)
+    }
+
+    pub fn revoke_role(ctx: Context<ManageRoles>, member: Pubkey, role: Role) -> Result<()> {
+        roles::process_revoke_role(ctx, member, role)
+    }
+
+    /// Start `member`'s activity marker at the current epoch; permissionless
+    pub fn track_member_activity(ctx: Context<TrackMemberActivity>, member: Pubkey) -> Result<()> {
+        activity::process_track_member_activity(ctx, member)
+    }
+
+    /// Refresh the signer's activity marker, counting it in the hive's
+    /// electorate again if it was marked inactive
+    pub fn member_heartbeat(ctx: Context<MemberHeartbeat>) -> Result<()> {
+        activity::process_member_heartbeat(ctx)
+    }
+
+    /// Leave a member silent for INACTIVITY_EPOCHS out of the electorate of
+    /// new actions; permissionless
+    pub fn mark_inactive(ctx: Context<MarkInactive>) -> Result<()> {
+        activity::process_mark_inactive(ctx)
+    }
+
+    /// Commit the live tally to the base layer without undelegating
+    #[cfg(not(feature = "no-er"))]
+    pub fn checkpoint(ctx: Context<Checkpoint>) -> Result<()> {
+        checkpoint::process_checkpoint(ctx)
+    }
+
+    /// Undelegate an action nobody finalized, once the liveness timeout
+    /// after its deadline has passed
+    #[cfg(not(feature = "no-er"))]
+    pub fn force_undelegate(ctx: Context<ForceUndelegate>) -> Result<()> {
+        liveness::process_force_undelegate(ctx)
+    }
+
+    /// Name a backup validator for an action, creating the mirror PDA it
+    /// will hold; action authority only, before delegation
+    #[cfg(not(feature = "no-er"))]
+    pub fn enable_redundancy(ctx: Context<EnableRedundancy>, backup: Pubkey) -> Result<()> {
+        redundancy::process_enable_redundancy(ctx, backup)
+    }
+
+    /// Delegate an action's mirror to its backup validator
+    #[cfg(not(feature = "no-er"))]
+    pub fn delegate_mirror(ctx: Context<DelegateMirror>) -> Result<()> {
+        redundancy::process_delegate_mirror(ctx)
+    }
+
+    /// The backup's commit of an action its primary left unfinalized past
+    /// the liveness timeout; the transaction must carry the backup's ed25519
+    /// signature over the tally
+    #[cfg(not(feature = "no-er"))]
+    pub fn commit_mirror(ctx: Context<CommitMirror>) -> Result<()> {
+        redundancy::process_commit_mirror(ctx)
+    }
+
+    /// Keep whichever of an action's primary and backup commits came first;
+    /// permissionless
+    #[cfg(not(feature = "no-er"))]
+    pub fn resolve_redundant_commit(ctx: Context<ResolveRedundantCommit>) -> Result<()> {
+        redundancy::process_resolve_redundant_commit(ctx)
+    }
+
+    /// force_undelegate every stale action among the remaining accounts in
+    /// one commit; permissionless, for keepers
+    #[cfg(not(feature = "no-er"))]
+    pub fn reap_stale_delegations<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReapStaleDelegations<'info>>,
+    ) -> Result<()> {
+        liveness::process_reap_stale_delegations(ctx)
+    }
+
+    /// Close an action nobody finalized, once the expiry grace after its
+    /// deadline has passed; rent goes back to the creator
+    pub fn expire_action(ctx: Context<ExpireAction>) -> Result<()> {
+        expiry::process_expire_action(ctx)
+    }
+
+    /// Create the ActiveActions index of a hive, or of unscoped actions
+    /// without one
+    pub fn initialize_active_actions(ctx: Context<InitializeActiveActions>) -> Result<()> {
+        priority::process_initialize_active_actions(ctx)
+    }
+
+    /// Take a final action out of its priority lane
+    pub fn retire_action(ctx: Context<RetireAction>) -> Result<()> {
+        priority::process_retire_action(ctx)
+    }
+
+    /// Create a page of a tag's index, of a hive or of unscoped actions
+    /// without one; after the page before it
+    pub fn initialize_tag_index(ctx: Context<InitializeTagIndex>, tag: [u8; 8], page: u32) -> Result<()> {
+        tags::process_initialize_tag_index(ctx, tag, page)
+    }
+
+    /// Take a final action out of its tags' index pages
+    pub fn retire_tags(ctx: Context<RetireTags>) -> Result<()> {
+        tags::process_retire_tags(ctx)
+    }
+
+    /// Register a TEE validator, bonding at least MIN_VALIDATOR_BOND lamports
+    pub fn register_validator(ctx: Context<RegisterValidator>, bond: u64) -> Result<()> {
+        validators::process_register_validator(ctx, bond)
+    }
+
+    pub fn validator_heartbeat(ctx: Context<ValidatorHeartbeat>) -> Result<()> {
+        validators::process_validator_heartbeat(ctx)
+    }
+
+    pub fn set_validator_status(ctx: Context<SetValidatorStatus>, status: ValidatorStatus) -> Result<()> {
+        validators::process_set_validator_status(ctx, status)
+    }
+
+    /// Take an action back on the base layer off its validator's count of
+    /// hosted actions
+    pub fn release_validator(ctx: Context<ReleaseValidator>) -> Result<()> {
+        validators::process_release_validator(ctx)
+    }
+
+    /// Grow a ValidatorRecord created before hosted actions were counted
+    pub fn migrate_validator_record(ctx: Context<MigrateValidatorRecord>) -> Result<()> {
+        validators::process_migrate_validator_record(ctx)
+    }
+
+    /// Upgrade an action stored in an older layout to the current one
+    pub fn migrate_action(ctx: Context<MigrateAction>, action_id: u64) -> Result<()> {
+        migration::process_migrate_action(ctx, action_id)
+    }
+
+    /// Admin sets the lamport fee charged on action creation
+    pub fn set_creation_fee(ctx: Context<SetCreationFee>, creation_fee: u64) -> Result<()> {
+        rewards::process_set_creation_fee(ctx, creation_fee)
+    }
+
+    /// Credit the validator that hosted a finalized action's session
+    pub fn record_session(ctx: Context<RecordSession>) -> Result<()> {
+        rewards::process_record_session(ctx)
+    }
+
+    /// Validator withdraws its share of the reward pool
+    pub fn claim_validator_rewards(ctx: Context<ClaimValidatorRewards>) -> Result<()> {
+        rewards::process_claim_validator_rewards(ctx)
+    }
+
+    /// Pay a tallied action's creator the fee rebate its turnout earned
+    pub fn claim_fee_rebate(ctx: Context<ClaimFeeRebate>) -> Result<()> {
+        rewards::process_claim_fee_rebate(ctx)
+    }
+
+    /// Report a hosted session that expired or was committed late
+    pub fn report_missed_commit(ctx: Context<ReportMissedCommit>) -> Result<()> {
+        slashing::process_report_missed_commit(ctx)
+    }
+
+    /// Admin voids a report during its dispute window
+    pub fn void_slash(ctx: Context<VoidSlash>) -> Result<()> {
+        slashing::process_void_slash(ctx)
+    }
+
+    /// Slash the reported validator's bond once the dispute window has passed
+    pub fn execute_slash(ctx: Context<ExecuteSlash>) -> Result<()> {
+        slashing::process_execute_slash(ctx)
+    }
+
+    /// Slash an agent's whole bond for signing two different choices on one
+    /// action; the transaction must verify both signatures with ed25519
+    /// program instructions. Permissionless
+    pub fn report_equivocation(ctx: Context<ReportEquivocation>, first: Ballot, second: Ballot) -> Result<()> {
+        equivocation::process_report_equivocation(ctx, first, second)
+    }
+
+    /// Create a hive's insurance pool, diverting `fee_bps` of each creation
+    /// fee in the hive to it; hive authority only
+    pub fn initialize_insurance_pool(ctx: Context<InitializeInsurancePool>, fee_bps: u16) -> Result<()> {
+        insurance::process_initialize_insurance_pool(ctx, fee_bps)
+    }
+
+    /// Add lamports to a hive's insurance pool
+    pub fn fund_insurance(ctx: Context<FundInsurance>, amount: u64) -> Result<()> {
+        insurance::process_fund_insurance(ctx, amount)
+    }
+
+    /// Claim `amount` from the hive's insurance pool for damage from a voided
+    /// action; decided by an Emergency action carrying the claim's
+    /// resolution hash
+    pub fn file_claim(ctx: Context<FileClaim>, amount: u64, evidence_hash: [u8; 32]) -> Result<()> {
+        insurance::process_file_claim(ctx, amount, evidence_hash)
+    }
+
+    /// Pay or reject a claim once its resolution action settles; permissionless
+    pub fn resolve_claim(ctx: Context<ResolveClaim>) -> Result<()> {
+        insurance::process_resolve_claim(ctx)
+    }
+
+    /// Freeze or unfreeze a hive's action creation and voting as a passed
+    /// Emergency action of the hive voted for; permissionless
+    pub fn set_hive_frozen(ctx: Context<SetHiveFrozen>, frozen: bool) -> Result<()> {
+        freeze::process_set_hive_frozen(ctx, frozen)
+    }
+
+    /// Void a finalized result during its dispute window with a fraud proof
+    pub fn challenge_result<'info>(
+        ctx: Context<'_, '_, '_, 'info, ChallengeResult<'info>>,
+        evidence: Evidence,
+    ) -> Result<()> {
+        dispute::process_challenge_result(ctx, evidence)
+    }
+
+    /// Commit to the order `action`'s ballots were received in, as batch
+    /// `index` of the pinned validator's sequence; on the base layer
+    pub fn post_sequence_commitment(
+        ctx: Context<PostSequenceCommitment>,
+        action: Pubkey,
+        index: u32,
+        ballots: Vec<SequencedBallot>,
+    ) -> Result<()> {
+        sequencing::process_post_sequence_commitment(ctx, action, index, ballots)
+    }
+
+    /// Void a finalized result during its dispute window with a ballot the
+    /// validator sequenced before the deadline but left out of the tally
+    pub fn challenge_exclusion(
+        ctx: Context<ChallengeExclusion>,
+        index: u32,
+        ballots: Vec<SequencedBallot>,
+        position: u8,
+    ) -> Result<()> {
+        sequencing::process_challenge_exclusion(ctx, index, ballots, position)
+    }
+
+    /// Write a settled action's result, with validator signatures over it,
+    /// into a portable proof for verifiers off Solana
+    pub fn export_result_proof(ctx: Context<ExportResultProof>) -> Result<()> {
+        result_proof::process_export_result_proof(ctx)
+    }
+
+    /// Close an exported proof's buffer, returning its rent; payer only
+    pub fn close_result_proof(ctx: Context<CloseResultProof>) -> Result<()> {
+        result_proof::process_close_result_proof(ctx)
+    }
+
+    /// Split the voter's weight across a multi-option action's options;
+    /// delegated weight is claimed as in vote_fast_weighted
+    pub fn vote_options(
+        ctx: Context<VoteOptions>,
+        _action_id: u64,
+        weights: Vec<u64>,
+        voter_commitment: [u8; 32],
+        delegation_epoch: u32,
+    ) -> Result<()> {
+        ctx.accounts.fast_action.check_delegation_epoch(delegation_epoch)?;
+        options::process_vote_options(ctx, weights, voter_commitment)
+    }
+
+    /// Cast a ranked ballot on a ranked-choice action
+    pub fn vote_ranked(
+        ctx: Context<VoteRanked>,
+        _action_id: u64,
+        ranking: Vec<u8>,
+        voter_commitment: [u8; 32],
+        delegation_epoch: u32,
+    ) -> Result<()> {
+        ctx.accounts.fast_action.check_delegation_epoch(delegation_epoch)?;
+        ranked::process_vote_ranked(ctx, ranking, voter_commitment)
+    }
+
+    /// Advance a ranked-choice count by one elimination or one batch of ballots
+    pub fn tally_round<'info>(ctx: Context<'_, '_, '_, 'info, TallyRound<'info>>) -> Result<()> {
+        ranked::process_tally_round(ctx)
+    }
+
+    /// Open a runoff between the two leading options of a failed multi-option action
+    pub fn spawn_runoff(ctx: Context<SpawnRunoff>) -> Result<()> {
+        runoff::process_spawn_runoff(ctx)
+    }
+
+    /// spawn_runoff and delegate the runoff to the parent's validator in one
+    /// instruction
+    #[cfg(not(feature = "no-er"))]
+    pub fn spawn_and_delegate(ctx: Context<SpawnAndDelegate>) -> Result<()> {
+        runoff::process_spawn_and_delegate(ctx)
+    }
+
+    /// Open a conviction-mode proposal for a standing priority
+    pub fn create_conviction_proposal(
+        ctx: Context<CreateConvictionProposal>,
+        action_hash: [u8; 32],
+        threshold: u64,
+        retention_ppb: u32,
+    ) -> Result<()> {
+        conviction::process_create_conviction_proposal(ctx, action_hash, threshold, retention_ppb)
+    }
+
+    /// Stake lamports behind a conviction proposal
+    pub fn stake_conviction(ctx: Context<StakeConviction>, amount: u64) -> Result<()> {
+        conviction::process_stake_conviction(ctx, amount)
+    }
+
+    /// Withdraw staked lamports; the proposal's conviction starts decaying
+    pub fn withdraw_conviction(ctx: Context<WithdrawConviction>, amount: u64) -> Result<()> {
+        conviction::process_withdraw_conviction(ctx, amount)
+    }
+
+    /// Accrue a proposal's conviction and execute it once past its threshold
+    pub fn update_conviction(ctx: Context<UpdateConviction>) -> Result<()> {
+        conviction::process_update_conviction(ctx)
+    }
+
+    /// Open a decision spanning hives, weighing one hive-scoped child action
+    /// per hive; pass the children's FastAction accounts as remaining
+    /// accounts
+    pub fn create_federated_action(
+        ctx: Context<CreateFederatedAction>,
+        action_hash: [u8; 32],
+        threshold: u8,
+        children: Vec<FederatedChild>,
+    ) -> Result<()> {
+        federation::process_create_federated_action(ctx, action_hash, threshold, children)
+    }
+
+    /// Install the winner of a settled Election action as the hive's leader
+    pub fn install_leader(ctx: Context<InstallLeader>, election: Election) -> Result<()> {
+        election::process_install_leader(ctx, election)
+    }
+
+    /// Push the task a settled TaskAssignment action voted for into its
+    /// queue; remaining accounts go on to the queue program
+    pub fn push_task<'info>(ctx: Context<'_, '_, '_, 'info, PushTask<'info>>, task: TaskAssignment) -> Result<()> {
+        task::process_push_task(ctx, task)
+    }
+
+    /// Record a federated action's result once every child is final
+    pub fn settle_federated_action(ctx: Context<SettleFederatedAction>) -> Result<()> {
+        federation::process_settle_federated_action(ctx)
+    }
+
+    /// Mark a passed, market-gated action executable once its feed clears the bound
+    pub fn clear_market_gate(ctx: Context<ClearMarketGate>) -> Result<()> {
+        market::process_clear_market_gate(ctx)
+    }
+
+    /// Guardian-only: overturn a tallied result during its dispute window
+    pub fn veto_action(ctx: Context<VetoAction>, reason: String) -> Result<()> {
+        guardian::process_veto_action(ctx, reason)
+    }
 
-pub const FAST_ACTION_SEED: &[u8] = b"fast_action";
-pub const FAST_VOTE_SEED: &[u8] = b"fast_vote";
+    /// Grow a GlobalConfig created before its latest fields
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        config::process_migrate_config(ctx)
+    }
 
-/// Voting window: ~30 seconds at 400ms/slot
-const VOTING_WINDOW_SLOTS: u64 = 75;
+    /// Hand an action's cancel and amend rights to another key
+    pub fn transfer_action_authority(ctx: Context<TransferActionAuthority>, new_authority: Pubkey) -> Result<()> {
+        authority::process_transfer_action_authority(ctx, new_authority)
+    }
 
-/// Quorum requirement
-const MIN_VOTES_FOR_QUORUM: u32 = 2;
+    /// Attach a short description and URI to an action before its first vote
+    pub fn set_metadata(ctx: Context<SetMetadata>, description: String, uri: String) -> Result<()> {
+        metadata::process_set_metadata(ctx, description, uri)
+    }
 
-/// Max votes per action (prevents DoS via vote spam)
-const MAX_VOTES_PER_ACTION: u32 = 10_000;
+    /// Record that the action's description is encrypted to a TEE key;
+    /// authority only, before the first vote
+    pub fn seal_description(
+        ctx: Context<SealDescription>,
+        ciphertext_hash: [u8; 32],
+        encryption_key: [u8; 32],
+    ) -> Result<()> {
+        sealed::process_seal_description(ctx, ciphertext_hash, encryption_key)
+    }
 
-#[ephemeral]
-#[program]
-pub mod kamiyo_fast_voting {
-    use super::*;
+    /// Record the Bubblegum tree vote receipts are minted into
+    pub fn set_receipt_tree(ctx: Context<SetReceiptTree>, merkle_tree: Pubkey, uri_prefix: String) -> Result<()> {
+        vote_receipt::process_set_receipt_tree(ctx, merkle_tree, uri_prefix)
+    }
 
-    pub fn create_fast_action(
-        ctx: Context<CreateFastAction>,
-        action_id: u64,
-        action_hash: [u8; 32],
+    /// Mint a ballot's compressed NFT receipt to its voter once the result
+    /// has settled; permissionless
+    pub fn mint_receipt(ctx: Context<MintReceipt>) -> Result<()> {
+        vote_receipt::process_mint_receipt(ctx)
+    }
+
+    /// Commit the hash of the voter's reasoning onto its ballot while the
+    /// action is open; once per ballot
+    pub fn set_vote_reason(ctx: Context<SetVoteReason>, reason_hash: [u8; 32]) -> Result<()> {
+        vote_reason::process_set_vote_reason(ctx, reason_hash)
+    }
+
+    /// Reveal a ballot's reasoning against its committed hash once the
+    /// action is finalized; permissionless
+    pub fn reveal_reason(ctx: Context<RevealReason>, reason: String) -> Result<()> {
+        vote_reason::process_reveal_reason(ctx, reason)
+    }
+
+    /// Fund a participation reward pool for the action; authority only,
+    /// before the first vote
+    pub fn open_vote_rewards(ctx: Context<OpenVoteRewards>, amount: u64, claim_slots: u64) -> Result<()> {
+        vote_rewards::process_open_vote_rewards(ctx, amount, claim_slots)
+    }
+
+    /// Pay a ballot its share of the action's reward pool once the action
+    /// is finalized; permissionless
+    pub fn claim_vote_reward(ctx: Context<ClaimVoteReward>) -> Result<()> {
+        vote_rewards::process_claim_vote_reward(ctx)
+    }
+
+    /// Return unclaimed rewards to the treasury once claims have closed;
+    /// permissionless
+    pub fn sweep_vote_rewards(ctx: Context<SweepVoteRewards>) -> Result<()> {
+        vote_rewards::process_sweep_vote_rewards(ctx)
+    }
+
+    /// Publish a sealed description once the action has passed and settled;
+    /// the TEE validator only, checked against `description_hash`
+    pub fn reveal_description(ctx: Context<RevealDescription>, description: String) -> Result<()> {
+        sealed::process_reveal_description(ctx, description)
+    }
+
+    /// Stage an action's params for co-sponsorship; create_fast_action opens
+    /// it once `required` of `sponsors` have signed
+    pub fn create_draft(
+        ctx: Context<CreateDraft>,
+        params: ActionParams,
+        sponsors: Vec<Pubkey>,
+        required: u8,
+    ) -> Result<()> {
+        draft::process_create_draft(ctx, params, sponsors, required)
+    }
+
+    pub fn sponsor_draft(ctx: Context<SponsorDraft>) -> Result<()> {
+        draft::process_sponsor_draft(ctx)
+    }
+
+    /// Fix an action's threshold, window or description before anyone votes
+    pub fn amend_action(ctx: Context<AmendAction>, amendment: ActionAmendment) -> Result<()> {
+        amend::process_amend_action(ctx, amendment)
+    }
+
+    /// Push an open action's deadline by up to one window; its voters
+    /// co-sign as (FastVote, voter) remaining accounts
+    pub fn extend_deadline(ctx: Context<ExtendDeadline>, slots: u64) -> Result<()> {
+        extension::process_extend_deadline(ctx, slots)
+    }
+
+    /// Tighten or relax when the hive's actions may be cancelled; hive Admin only
+    pub fn set_cancellation_policy(ctx: Context<SetCancellationPolicy>, policy: CancellationPolicy) -> Result<()> {
+        hive_config::process_set_cancellation_policy(ctx, policy)
+    }
+
+    /// Round approval for actions the hive creates from now on; hive Admin
+    /// only
+    pub fn set_approval_rounding(ctx: Context<SetApprovalRounding>, rounding: ApprovalRounding) -> Result<()> {
+        hive_config::process_set_approval_rounding(ctx, rounding)
+    }
+
+    /// Grow a HiveConfig created before approval rounding was kept
+    pub fn migrate_hive_config(ctx: Context<MigrateHiveConfig>) -> Result<()> {
+        hive_config::process_migrate_hive_config(ctx)
+    }
+
+    /// Contest passes of `kind` whose approval falls within `margin`
+    /// percentage points of the threshold (0 = never); hive Admin only
+    pub fn set_contest_margin(ctx: Context<SetContestMargin>, kind: ActionKind, margin: u8) -> Result<()> {
+        escalation::process_set_contest_margin(ctx, kind, margin)
+    }
+
+    /// Carry a contested action's slow vote back to it once decided.
+    /// Permissionless, on the base layer
+    pub fn ratify_contested(ctx: Context<RatifyContested>) -> Result<()> {
+        escalation::process_ratify_contested(ctx)
+    }
+
+    /// Require `threshold` of `validators` to co-sign each of the hive's
+    /// tally results; hive Admin only
+    pub fn set_result_attestors(
+        ctx: Context<SetResultAttestors>,
+        validators: Vec<Pubkey>,
         threshold: u8,
-        description_hash: [u8; 32],
     ) -> Result<()> {
-        require!(threshold > 0 && threshold <= 100, FastVoteError::InvalidThreshold);
-        require!(action_hash != [0u8; 32], FastVoteError::InvalidActionHash);
+        attestation::process_set_result_attestors(ctx, validators, threshold)
+    }
 
-        let action = &mut ctx.accounts.fast_action;
-        let clock = Clock::get()?;
+    /// Write a new action template for the hive; hive Admin only
+    pub fn create_template(ctx: Context<CreateTemplate>, template_id: u16, params: TemplateParams) -> Result<()> {
+        template::process_create_template(ctx, template_id, params)
+    }
 
-        let deadline_slot = clock.slot
-            .checked_add(VOTING_WINDOW_SLOTS)
-            .ok_or(FastVoteError::SlotOverflow)?;
-
-        action.action_id = action_id;
-        action.action_hash = action_hash;
-        action.description_hash = description_hash;
-        action.creator = ctx.accounts.creator.key();
-        action.threshold = threshold;
-        action.votes_for = 0;
-        action.votes_against = 0;
-        action.vote_count = 0;
-        action.created_slot = clock.slot;
-        action.deadline_slot = deadline_slot;
-        action.executed = false;
-        action.result = VoteResult::Pending;
-        action.bump = ctx.bumps.fast_action;
-
-        emit!(FastActionCreated {
-            action: action.key(),
-            action_id,
-            action_hash,
-            threshold,
-            deadline_slot,
-        });
+    /// Rewrite a template; actions already created from it keep their shape
+    pub fn update_template(ctx: Context<UpdateTemplate>, params: TemplateParams) -> Result<()> {
+        template::process_update_template(ctx, params)
+    }
 
-        Ok(())
+    /// Register the signing hive; its actions may then be created under it
+    pub fn create_hive(ctx: Context<CreateHive>, name_hash: [u8; 32], authority: Pubkey) -> Result<()> {
+        hive::process_create_hive(ctx, name_hash, authority)
     }
 
-    pub fn delegate_action(ctx: Context<DelegateAction>, action_id: u64) -> Result<()> {
-        // Verify PDA matches expected derivation
-        let (expected_pda, _) = Pubkey::find_program_address(
-            &[FAST_ACTION_SEED, &action_id.to_le_bytes()],
-            &crate::ID,
-        );
-        require!(ctx.accounts.pda.key() == expected_pda, FastVoteError::InvalidPda);
+    /// Register the signing hive with its roles, config, category margins
+    /// and insurance pool in one instruction
+    pub fn initialize_hive(ctx: Context<InitializeHive>, init: HiveInit) -> Result<()> {
+        hive::process_initialize_hive(ctx, init)
+    }
 
-        let validator = ctx.accounts.validator.as_ref().map(|v| v.key());
+    /// Change a hive's authority, member registry root or overrides; hive
+    /// authority only
+    pub fn update_hive(ctx: Context<UpdateHive>, update: HiveUpdate) -> Result<()> {
+        hive::process_update_hive(ctx, update)
+    }
 
-        ctx.accounts.delegate_pda(
-            &ctx.accounts.payer,
-            &[FAST_ACTION_SEED, &action_id.to_le_bytes()],
-            DelegateConfig {
-                validator,
-                ..Default::default()
-            },
-        )?;
+    /// Limit, or reopen, creation of the hive's actions to its creator
+    /// allowlist; hive authority only
+    pub fn set_creator_restriction(ctx: Context<SetCreatorRestriction>, restricted: bool) -> Result<()> {
+        creator_allowlist::process_set_creator_restriction(ctx, restricted)
+    }
 
-        Ok(())
+    /// Add a coordinator to the hive's creator allowlist; hive authority only
+    pub fn allow_creator(ctx: Context<ManageCreatorAllowlist>, creator: Pubkey) -> Result<()> {
+        creator_allowlist::process_allow_creator(ctx, creator)
     }
 
-    pub fn vote_fast(
-        ctx: Context<VoteFast>,
-        _action_id: u64,
-        vote_value: bool,
-        voter_commitment: [u8; 32],
+    /// Remove a coordinator from the hive's creator allowlist; hive authority only
+    pub fn disallow_creator(ctx: Context<ManageCreatorAllowlist>, creator: Pubkey) -> Result<()> {
+        creator_allowlist::process_disallow_creator(ctx, creator)
+    }
+
+    /// Register a program to be called with the action's result; authority
+    /// only, up to MAX_CALLBACKS per action
+    pub fn register_callback(
+        ctx: Context<RegisterCallback>,
+        program_id: Pubkey,
+        accounts_hash: [u8; 32],
+        account_count: u8,
     ) -> Result<()> {
-        let action = &mut ctx.accounts.fast_action;
-        let clock = Clock::get()?;
+        callbacks::process_register_callback(ctx, program_id, accounts_hash, account_count)
+    }
 
-        require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
-        require!(clock.slot <= action.deadline_slot, FastVoteError::VotingEnded);
-        require!(action.vote_count < MAX_VOTES_PER_ACTION, FastVoteError::MaxVotesReached);
-        require!(voter_commitment != [0u8; 32], FastVoteError::InvalidVoterCommitment);
-
-        let vote = &mut ctx.accounts.fast_vote;
-        vote.fast_action = action.key();
-        vote.voter = ctx.accounts.voter.key();
-        vote.voter_commitment = voter_commitment;
-        vote.vote_value = vote_value;
-        vote.voted_slot = clock.slot;
-        vote.bump = ctx.bumps.fast_vote;
+    /// Commit to execute_action's remaining accounts a slot before sending
+    /// it; any executor
+    pub fn stage_execution(ctx: Context<StageExecution>, commitment: [u8; 32]) -> Result<()> {
+        staging::process_stage_execution(ctx, commitment)
+    }
 
-        if vote_value {
-            action.votes_for = action.votes_for.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
-        } else {
-            action.votes_against = action.votes_against.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
-        }
-        action.vote_count = action.vote_count.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+    /// Call every registered callback once the result has settled, with the
+    /// accounts the executor staged
+    pub fn execute_action<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteAction<'info>>) -> Result<()> {
+        callbacks::process_execute_action(ctx)
+    }
 
-        emit!(FastVoteCast {
-            action: action.key(),
-            voter_commitment,
-            vote_count: action.vote_count,
-        });
+    /// Post the accounts execute_action needs and a reward for the keeper
+    /// that sends it; once the action is finalized
+    pub fn schedule_execution(
+        ctx: Context<ScheduleExecution>,
+        reward: u64,
+        accounts: Vec<ScheduledAccount>,
+    ) -> Result<()> {
+        keeper::process_schedule_execution(ctx, reward, accounts)
+    }
 
-        Ok(())
+    /// Close an execution schedule, returning its balance; funder only
+    pub fn cancel_execution(ctx: Context<CancelExecution>) -> Result<()> {
+        keeper::process_cancel_execution(ctx)
     }
 
-    pub fn tally_and_commit(ctx: Context<TallyAndCommit>) -> Result<()> {
-        let action = &mut ctx.accounts.fast_action;
-        let clock = Clock::get()?;
+    /// Return or forfeit an optimistic action's bond once it has settled
+    pub fn release_bond(ctx: Context<ReleaseBond>) -> Result<()> {
+        optimistic::process_release_bond(ctx)
+    }
+}
 
-        require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
-        require!(clock.slot > action.deadline_slot, FastVoteError::VotingNotEnded);
-        require!(action.vote_count >= MIN_VOTES_FOR_QUORUM, FastVoteError::QuorumNotMet);
+/// Creation shared by create_fast_action and create_from_template; the
+/// action's deadline is `window_slots` from now.
+pub(crate) fn create_action(
+    ctx: &mut Context<CreateFastAction>,
+    params: ActionParams,
+    window_slots: u64,
+) -> Result<()> {
+    // A sponsored draft must carry exactly these params
+    let fast_action_key = ctx.accounts.fast_action.key();
+    if let Some(draft) = ctx.accounts.draft.as_mut() {
+        draft.promote(&ctx.accounts.creator.key(), &params, fast_action_key)?;
+    }
 
-        let total_votes = action.votes_for
-            .checked_add(action.votes_against)
-            .ok_or(FastVoteError::VoteOverflow)?;
+    let ActionParams {
+        action_hash,
+        threshold,
+        description_hash,
+        vote_mode,
+        tier_thresholds,
+        dependencies,
+        adaptive_quorum,
+        min_turnout_bps,
+        option_count,
+        ranked,
+        market_gate,
+        kind,
+        weight_cap,
+        class_limits,
+        audited,
+        early_vote_bonus,
+        exclusions,
+        deadline_extension,
+        identity_weighted,
+        member_collection,
+        lock_stake,
+        priority,
+        tags,
+        quorum_weight_bps,
+        privacy,
+        threshold_bps,
+    } = params;
 
-        require!(total_votes > 0, FastVoteError::QuorumNotMet);
+    require!(threshold > 0 && threshold <= 100, FastVoteError::InvalidThreshold);
+    // An optimistic threshold is a veto share of the electorate, kept whole
+    require!(
+        threshold_bps == 0 || vote_mode != VoteMode::Optimistic,
+        FastVoteError::InvalidThreshold
+    );
+    let threshold_bps = tally::validate_threshold_bps(threshold, threshold_bps)?;
+    require!(action_hash != [0u8; 32], FastVoteError::InvalidActionHash);
+    tally::validate_tiers(threshold, &tier_thresholds)?;
 
-        let approval_pct = (action.votes_for as u64)
-            .checked_mul(100)
-            .ok_or(FastVoteError::VoteOverflow)?
-            .checked_div(total_votes as u64)
-            .ok_or(FastVoteError::VoteOverflow)?;
+    let overrides = ctx.accounts.hive_account.as_ref().map_or(HiveOverrides::default(), |h| h.overrides);
+    let default_quorum = match overrides.default_quorum {
+        0 => MIN_VOTES_FOR_QUORUM,
+        quorum => quorum,
+    };
+    weight_snapshot::validate_weight_quorum(
+        quorum_weight_bps,
+        vote_mode,
+        option_count,
+        adaptive_quorum.is_some(),
+        identity_weighted,
+        lock_stake,
+    )?;
+    let quorum = adaptive_quorum.unwrap_or(AdaptiveQuorum {
+        quorum: default_quorum,
+        floor: default_quorum,
+        grace_slots: 0,
+    });
+    require!(
+        quorum.floor >= 1 && quorum.floor <= quorum.quorum && quorum.quorum <= ctx.accounts.config.max_votes(),
+        FastVoteError::InvalidQuorum
+    );
 
-        action.result = if approval_pct >= action.threshold as u64 {
-            VoteResult::Passed
-        } else {
-            VoteResult::Failed
-        };
-        action.executed = true;
+    require!(dependencies.len() <= MAX_DEPENDENCIES, FastVoteError::TooManyDependencies);
+    for (i, dependency) in dependencies.iter().enumerate() {
+        require!(
+            *dependency != Pubkey::default()
+                && *dependency != fast_action_key
+                && !dependencies[..i].contains(dependency),
+            FastVoteError::InvalidDependency
+        );
+    }
+    require!(exclusions.len() <= MAX_EXCLUSIONS, FastVoteError::InvalidExclusions);
+    for (i, excluded) in exclusions.iter().enumerate() {
+        require!(
+            *excluded != Pubkey::default() && !exclusions[..i].contains(excluded),
+            FastVoteError::InvalidExclusions
+        );
+    }
 
-        action.exit(&crate::ID)?;
+    // Hive-scoped creation requires the Creator role; unscoped actions stay permissionless
+    let hive = match ctx.accounts.roles.as_ref() {
+        Some(roles) => {
+            require!(
+                roles.has_role(&ctx.accounts.creator.key(), Role::Creator),
+                FastVoteError::MissingRole
+            );
+            roles.hive
+        }
+        None => Pubkey::default(),
+    };
+    creator_allowlist::check_creator(
+        &ctx.accounts.roles,
+        &ctx.accounts.creator_allowlist,
+        &ctx.accounts.creator.key(),
+    )?;
+    if ctx.accounts.roles.is_some() {
+        let freeze = ctx.accounts.hive_freeze.as_ref().ok_or(FastVoteError::HiveFreezeRequired)?;
+        HiveFreeze::require_live(freeze, kind, &action_hash)?;
+    }
+    let approval_rounding = match ctx.accounts.roles {
+        Some(_) => {
+            let hive_config = ctx.accounts.hive_config.as_ref().ok_or(FastVoteError::HiveConfigRequired)?;
+            HiveConfig::approval_rounding(hive_config)?
+        }
+        None => ApprovalRounding::Floor,
+    };
+    // A hive action is always seeded under its hive, so hive_seeded can't
+    // be dropped by leaving the account out
+    match ctx.accounts.hive_account.as_ref() {
+        Some(hive_account) => require_keys_eq!(hive_account.hive, hive, FastVoteError::InvalidHive),
+        None => require!(hive == Pubkey::default(), FastVoteError::HiveAccountRequired),
+    }
 
-        commit_and_undelegate_accounts(
-            &ctx.accounts.payer,
-            vec![&action.to_account_info()],
-            &ctx.accounts.magic_context,
-            &ctx.accounts.magic_program,
-        )?;
+    let electorate = ctx.accounts.roles.as_ref().map_or(0, |r| r.electorate());
+    if min_turnout_bps > 0 {
+        require!(
+            min_turnout_bps <= 10_000 && electorate > 0 && vote_mode != VoteMode::Optimistic,
+            FastVoteError::InvalidTurnout
+        );
+    }
+    if option_count > 0 {
+        require!(
+            (2..=MAX_OPTIONS as u8).contains(&option_count)
+                && vote_mode != VoteMode::Optimistic
+                && tier_thresholds == [0; MAX_EXTRA_TIERS],
+            FastVoteError::InvalidOptionCount
+        );
+    }
+    if ranked {
+        require!(
+            option_count > 0 && vote_mode == VoteMode::Headcount,
+            FastVoteError::InvalidRankedAction
+        );
+    }
+    require!(!audited || option_count == 0, FastVoteError::InvalidAuditedTally);
+    let restricted = member_collection.is_some() || !exclusions.is_empty() || !class_limits.is_empty();
+    privacy::validate_level(privacy, vote_mode, option_count, audited, restricted)?;
+    require!(
+        !identity_weighted || (vote_mode == VoteMode::TokenWeighted && option_count == 0),
+        FastVoteError::InvalidIdentityWeighting
+    );
+    if let Some(gate) = market_gate {
+        require!(
+            gate.feed != Pubkey::default() && gate.feed_program != Pubkey::default() && option_count == 0,
+            FastVoteError::InvalidMarketGate
+        );
+    }
+    // Kind minimums are whole percents the bps bar itself must reach
+    let floor_pct = (threshold_bps / 100) as u8;
+    match kind {
+        ActionKind::Standard => {}
+        ActionKind::Emergency => emergency::validate(
+            floor_pct,
+            vote_mode,
+            option_count,
+            &ctx.accounts.config,
+            ctx.accounts.roles.as_ref(),
+        )?,
+        ActionKind::ConfigChange => {
+            config_change::validate_config_change(floor_pct, vote_mode, option_count, &ctx.accounts.config)?
+        }
+        ActionKind::ProgramUpgrade => {
+            upgrade::validate_upgrade(floor_pct, vote_mode, option_count, &ctx.accounts.config)?
+        }
+        ActionKind::Budget => budget::validate_budget(floor_pct, vote_mode, option_count, &ctx.accounts.config)?,
+        ActionKind::Election => election::validate_election(option_count, vote_mode, &hive)?,
+        ActionKind::TaskAssignment => task::validate_task_assignment(option_count, vote_mode)?,
+    }
+    let max_weight_per_voter = weight_cap::resolve(
+        weight_cap,
+        WeightCaps::default_for(&ctx.accounts.weight_caps, kind)?,
+        vote_mode,
+        ctx.accounts.weight_mint.as_deref(),
+    )?;
+    let early_vote_curve = early_vote::resolve_curve(
+        early_vote_bonus,
+        EarlyVoteCurves::curve_for(&ctx.accounts.early_vote_curves, kind)?,
+        vote_mode,
+    )?;
+    let extension_policy = extension::resolve_policy(deadline_extension, vote_mode)?;
+    let member_collection = membership::resolve_collection(member_collection, vote_mode, option_count)?;
+    let stake_lock_program =
+        stake_lock::resolve_program(lock_stake, vote_mode, option_count, identity_weighted, &ctx.accounts.config)?;
+    let (class_max_votes, class_weight_bps) = agent_registry::resolve_class_limits(&class_limits, vote_mode)?;
+    if vote_mode == VoteMode::Optimistic {
+        require!(electorate > 0, FastVoteError::OptimisticRequiresHive);
+        require!(tier_thresholds == [0; MAX_EXTRA_TIERS], FastVoteError::InvalidTierThresholds);
+        optimistic::post_bond(&ctx.accounts.fast_action, &ctx.accounts.creator, &ctx.accounts.system_program)?;
+    }
 
-        emit!(FastActionExecuted {
-            action: action.key(),
-            action_id: action.action_id,
-            votes_for: action.votes_for,
-            votes_against: action.votes_against,
-            result: action.result.clone(),
-        });
+    let clock = Clock::get()?;
 
-        Ok(())
+    let creator_state = &mut ctx.accounts.creator_state;
+    if creator_state.creator == Pubkey::default() {
+        creator_state.creator = ctx.accounts.creator.key();
+        creator_state.bump = ctx.bumps.creator_state;
     }
+    let max_actions_per_epoch = match overrides.max_actions_per_epoch {
+        0 => ctx.accounts.config.max_actions_per_epoch,
+        max => max,
+    };
+    creator_state.charge(max_actions_per_epoch, clock.epoch)?;
 
-    pub fn cancel_action(ctx: Context<CancelAction>, _action_id: u64) -> Result<()> {
-        let action = &mut ctx.accounts.fast_action;
-        require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    reward_pool.bump = ctx.bumps.reward_pool;
+    RewardPool::charge(reward_pool, &ctx.accounts.creator, &ctx.accounts.system_program)?;
+    insurance::take_cut(&ctx.accounts.reward_pool, &ctx.accounts.insurance_pool, hive)?;
 
-        action.executed = true;
-        action.result = VoteResult::Cancelled;
+    let counter = &mut ctx.accounts.action_counter;
+    let action_id = counter.next_id;
+    counter.next_id = action_id.checked_add(1).ok_or(FastVoteError::ActionIdOverflow)?;
+    counter.bump = ctx.bumps.action_counter;
 
-        emit!(FastActionCancelled {
-            action: action.key(),
-            action_id: action.action_id,
-        });
+    let deadline_slot = clock.slot
+        .checked_add(window_slots)
+        .ok_or(FastVoteError::SlotOverflow)?;
 
-        Ok(())
+    let hash_record = &mut ctx.accounts.action_hash_record;
+    if hash_record.action == Pubkey::default() {
+        hash_record.bump = ctx.bumps.action_hash_record;
+    }
+    hash_record.claim(
+        action_hash,
+        fast_action_key,
+        deadline_slot,
+        ctx.accounts.previous_action.as_deref(),
+        clock.slot,
+    )?;
+
+    let action = &mut ctx.accounts.fast_action;
+
+    action.version = FAST_ACTION_VERSION;
+    action.action_id = action_id;
+    action.action_hash = action_hash;
+    action.description_hash = description_hash;
+    action.creator = ctx.accounts.creator.key();
+    action.hive = hive;
+    action.threshold = threshold;
+    action.tier_thresholds = tier_thresholds;
+    action.dependency_count = dependencies.len() as u8;
+    action.dependencies = [Pubkey::default(); MAX_DEPENDENCIES];
+    action.dependencies[..dependencies.len()].copy_from_slice(&dependencies);
+    action.votes_for = 0;
+    action.votes_against = 0;
+    action.vote_count = 0;
+    action.vote_mode = vote_mode;
+    action.weight_for = 0;
+    action.weight_against = 0;
+    action.created_slot = clock.slot;
+    action.deadline_slot = deadline_slot;
+    action.executed = false;
+    action.result = match vote_mode {
+        VoteMode::Optimistic => VoteResult::Passed { tier: 0 },
+        VoteMode::Headcount | VoteMode::TokenWeighted => VoteResult::Pending,
+    };
+    action.bump = ctx.bumps.fast_action;
+    action.last_checkpoint_slot = clock.slot;
+    action.last_checkpoint_votes = 0;
+    action.delegated_validator = Pubkey::default();
+    action.session_recorded = false;
+    action.electorate = electorate;
+    action.quorum = quorum.quorum;
+    action.quorum_floor = quorum.floor;
+    action.quorum_grace_slots = quorum.grace_slots;
+    action.min_turnout_bps = min_turnout_bps;
+    action.option_count = option_count;
+    action.option_weights = [0; MAX_OPTIONS];
+    action.ranked = ranked;
+    action.eliminated_mask = 0;
+    action.pending_elimination = NO_ELIMINATION;
+    action.round = 0;
+    action.exhausted_ballots = 0;
+    action.parent_action = Pubkey::default();
+    action.runoff_action = Pubkey::default();
+    let gate = market_gate.unwrap_or(MarketGate {
+        feed: Pubkey::default(),
+        feed_program: Pubkey::default(),
+        price_offset: 0,
+        min_price: 0,
+    });
+    action.market_feed = gate.feed;
+    action.market_program = gate.feed_program;
+    action.market_price_offset = gate.price_offset;
+    action.market_min_price = gate.min_price;
+    action.market_cleared = false;
+    action.authority = ctx.accounts.creator.key();
+    action.draft = ctx.accounts.draft.as_ref().map_or(Pubkey::default(), |d| d.key());
+    action.kind = kind;
+    action.max_weight_per_voter = max_weight_per_voter;
+    action.class_max_votes = class_max_votes;
+    action.class_weight_bps = class_weight_bps;
+    action.class_votes = [0; AGENT_CLASSES];
+    action.delegation_epoch = 0;
+    action.delegation_state = DelegationState::Undelegated;
+    action.audited = audited;
+    action.hive_seeded = ctx.accounts.hive_account.is_some();
+    action.early_vote_curve = early_vote_curve;
+    action.log = ActionLog::default();
+    action.excluded_count = exclusions.len() as u8;
+    action.excluded = [Pubkey::default(); MAX_EXCLUSIONS];
+    action.excluded[..exclusions.len()].copy_from_slice(&exclusions);
+    action.extension_policy = extension_policy;
+    action.extensions = 0;
+    action.extended_slots = 0;
+    action.identity_weighted = identity_weighted;
+    action.member_collection = member_collection;
+    action.stake_lock_program = stake_lock_program;
+    action.priority = priority;
+    action.lane_counted = false;
+    action.tags = tags::pack(&tags)?;
+    action.tags_indexed = false;
+    action.quorum_weight_bps = quorum_weight_bps;
+    action.snapshot_weight = 0;
+    action.creation_fee = ctx.accounts.reward_pool.creation_fee;
+    action.fee_rebate = 0;
+    action.privacy = privacy;
+    action.validator_counted = false;
+    action.threshold_bps = threshold_bps;
+    action.approval_rounding = approval_rounding;
+    action.backup_validator = Pubkey::default();
+    let tagged = tags::index(action, fast_action_key, ctx.remaining_accounts)?;
+    action.max_votes = match electorate {
+        0 => ctx.accounts.config.max_votes(),
+        electorate => electorate.min(ctx.accounts.config.max_votes()).max(quorum.quorum),
+    };
+    let lane = priority::count(action, &ctx.accounts.active_actions, &ctx.accounts.config)?;
+    action.bond = match vote_mode {
+        VoteMode::Optimistic => OPTIMISTIC_BOND,
+        VoteMode::Headcount | VoteMode::TokenWeighted => 0,
+    };
+    metrics::record(&ctx.accounts.metrics, &action.hive, Counter::Created, clock.slot)?;
+
+    emit_event!(ctx, FastActionCreated {
+        action: action.key(),
+        action_id,
+        action_hash,
+        threshold,
+        tier_thresholds,
+        deadline_slot,
+        vote_mode,
+        hive,
+        dependencies,
+    });
+    if let Some((hive, active)) = lane {
+        emit_event!(ctx, ActiveActionsUpdated {
+            hive,
+            action: fast_action_key,
+            priority,
+            active,
+        });
+    }
+    for event in tagged {
+        emit_event!(ctx, event);
+    }
+    if kind == ActionKind::Emergency {
+        emit_event!(ctx, EmergencyActionCreated {
+            action: fast_action_key,
+            action_id,
+            hive,
+            threshold,
+            deadline_slot,
+        });
     }
-}
 
-#[account]
-pub struct FastAction {
-    pub action_id: u64,          // 8
-    pub action_hash: [u8; 32],   // 32
-    pub description_hash: [u8; 32], // 32
-    pub creator: Pubkey,         // 32
-    pub threshold: u8,           // 1
-    pub votes_for: u32,          // 4
-    pub votes_against: u32,      // 4
-    pub vote_count: u32,         // 4
-    pub created_slot: u64,       // 8
-    pub deadline_slot: u64,      // 8
-    pub executed: bool,          // 1
-    pub result: VoteResult,      // 1 + 1 padding
-    pub bump: u8,                // 1
+    Ok(())
 }
 
-impl FastAction {
-    pub const LEN: usize = 145; // 8 disc + 136 fields + 1 padding
+/// Returns the first dependency that has not passed, if any.
+fn check_dependencies(
+    action: &Account<FastAction>,
+    dependency_infos: &[AccountInfo],
+) -> Result<Option<DependencyBlocked>> {
+    let count = action.dependency_count as usize;
+    require!(dependency_infos.len() >= count, FastVoteError::MissingDependency);
+    let slot = Clock::get()?.slot;
+
+    for (index, (expected, info)) in action.dependencies[..count]
+        .iter()
+        .zip(dependency_infos)
+        .enumerate()
+    {
+        require_keys_eq!(info.key(), *expected, FastVoteError::MissingDependency);
+        // A delegated dependency's committed copy is not trusted: dependencies
+        // are arbitrary keys, and the delegation program holds other
+        // programs' accounts too
+        #[cfg(not(feature = "no-er"))]
+        require_keys_neq!(*info.owner, ephemeral_rollups_sdk::id(), FastVoteError::DependencyDelegated);
+        require_keys_eq!(*info.owner, crate::ID, FastVoteError::MissingDependency);
+        let data = info.try_borrow_data()?;
+        require!(data.len() == FastAction::LEN, FastVoteError::ActionNeedsMigration);
+        let dependency = FastAction::try_deserialize(&mut &data[..])?;
+
+        if !matches!(dependency.result, VoteResult::Passed { .. }) {
+            return Ok(Some(DependencyBlocked {
+                action: action.key(),
+                hive: action.hive,
+                dependency: *expected,
+                index: index as u8,
+                dependency_result: dependency.result.clone(),
+            }));
+        }
+        require!(dispute::is_settled(&dependency, slot), FastVoteError::DependencyNotSettled);
+        require!(market::is_executable(&dependency, slot), FastVoteError::MarketGateNotCleared);
+    }
+
+    Ok(None)
 }
 
-#[account]
-pub struct FastVote {
-    pub fast_action: Pubkey,     // 32
-    pub voter: Pubkey,           // 32
-    pub voter_commitment: [u8; 32], // 32
-    pub vote_value: bool,        // 1
-    pub voted_slot: u64,         // 8
-    pub bump: u8,                // 1
+/// Checks shared by every ballot type; fills the vote record and counts it.
+/// A record that is already filled is the agent's earlier ballot.
+fn open_ballot(
+    action: &mut Account<FastAction>,
+    vote: &mut FastVote,
+    voter: Pubkey,
+    bump: u8,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+    weight: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(vote.fast_action == Pubkey::default(), FastVoteError::AlreadyVoted);
+    require!(voter_commitment != [0u8; 32], FastVoteError::InvalidVoterCommitment);
+    require!(!action.excludes(&voter), FastVoteError::VoterExcluded);
+    admit_ballot(action, clock.slot)?;
+
+    vote.fast_action = action.key();
+    vote.voter = voter;
+    vote.voter_commitment = voter_commitment;
+    vote.vote_value = vote_value;
+    vote.voted_slot = clock.slot;
+    vote.weight = weight;
+    vote.bump = bump;
+    Ok(())
 }
 
-impl FastVote {
-    pub const LEN: usize = 114; // 8 disc + 106 fields
+/// Count a ballot toward the action's vote count, whoever cast it; returns
+/// the new count
+fn admit_ballot(action: &mut FastAction, slot: u64) -> Result<u32> {
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(slot <= action.deadline_slot, FastVoteError::VotingEnded);
+    require!(action.vote_count < action.max_votes, FastVoteError::MaxVotesReached);
+
+    action.vote_count = action.vote_count.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+    let votes = action.vote_count;
+    if votes == action.quorum {
+        action.log.push(slot, LogTag::QuorumReached, votes as u64);
+    }
+    Ok(votes)
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
-pub enum VoteResult {
-    Pending,
-    Passed,
-    Failed,
-    Cancelled,
+fn record_vote(
+    action: &mut Account<FastAction>,
+    vote: &mut FastVote,
+    voter: Pubkey,
+    bump: u8,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+    weight: u64,
+) -> Result<FastVoteCast> {
+    require!(action.option_count == 0, FastVoteError::OptionBallotRequired);
+    require!(action.privacy == PrivacyLevel::Public, FastVoteError::PrivacyLevelMismatch);
+    let passing_before = action.passing();
+    open_ballot(action, vote, voter, bump, vote_value, voter_commitment, weight)?;
+
+    if vote_value {
+        action.votes_for = action.votes_for.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+    } else {
+        action.votes_against = action.votes_against.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+    }
+
+    if action.vote_mode == VoteMode::TokenWeighted {
+        if vote_value {
+            action.weight_for = action.weight_for.checked_add(weight).ok_or(FastVoteError::VoteOverflow)?;
+        } else {
+            action.weight_against = action.weight_against.checked_add(weight).ok_or(FastVoteError::VoteOverflow)?;
+        }
+    }
+
+    if action.vote_mode != VoteMode::Optimistic && action.passing() != passing_before {
+        let approval = action.approval_pct().unwrap_or_default();
+        action.log.push(vote.voted_slot, LogTag::ThresholdCrossed, approval);
+    }
+
+    Ok(FastVoteCast {
+        action: action.key(),
+        hive: action.hive,
+        voter_commitment,
+        vote_count: action.vote_count,
+        weight,
+    })
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-#[instruction(action_id: u64)]
+// Shared by create_fast_action and create_from_template, whose ActionParams
+// and TemplateArgs both lead with the action hash
+#[instruction(action_hash: [u8; 32])]
 pub struct CreateFastAction<'info> {
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = ActionCounter::LEN,
+        seeds = [ACTION_COUNTER_SEED],
+        bump
+    )]
+    pub action_counter: Account<'info, ActionCounter>,
+    /// Seeds the action's address with the hive key and applies the hive's
+    /// overrides; required with `roles`, of the same hive
+    #[account(seeds = [HIVE_SEED, hive_account.hive.as_ref()], bump = hive_account.bump)]
+    pub hive_account: Option<Account<'info, Hive>>,
     #[account(
         init,
         payer = creator,
         space = FastAction::LEN,
-        seeds = [FAST_ACTION_SEED, &action_id.to_le_bytes()],
+        seeds = [FAST_ACTION_SEED, hive::action_seed(&hive_account), &action_counter.next_id.to_le_bytes()],
         bump
     )]
     pub fast_action: Account<'info, FastAction>,
     #[account(mut)]
     pub creator: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = CreatorState::LEN,
+        seeds = [CREATOR_STATE_SEED, creator.key().as_ref()],
+        bump
+    )]
+    pub creator_state: Account<'info, CreatorState>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = ActionHashRecord::LEN,
+        seeds = [ACTION_HASH_SEED, action_hash.as_ref()],
+        bump
+    )]
+    pub action_hash_record: Account<'info, ActionHashRecord>,
+    /// Last action recorded for this hash; lets a finalized action's hash be
+    /// reused before its record expires
+    #[account(
+        address = action_hash_record.action @ FastVoteError::DuplicateActionHash,
+        constraint = previous_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub previous_action: Option<Account<'info, FastAction>>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// Present for hive-scoped actions; creator must hold Role::Creator
+    #[account(seeds = [ROLES_SEED, roles.hive.as_ref()], bump = roles.bump)]
+    pub roles: Option<Account<'info, Roles>>,
+    /// CHECK: The CreatorAllowlist PDA of the hive of `roles`, whether or not
+    /// it exists; required with `roles`
+    #[account(seeds = [CREATOR_ALLOWLIST_SEED, creator_allowlist::allowlist_seed(&roles)], bump)]
+    pub creator_allowlist: Option<UncheckedAccount<'info>>,
+    /// CHECK: The HiveFreeze PDA of the hive of `roles`, whether or not it
+    /// exists; required with `roles`
+    #[account(seeds = [HIVE_FREEZE_SEED, creator_allowlist::allowlist_seed(&roles)], bump)]
+    pub hive_freeze: Option<UncheckedAccount<'info>>,
+    /// CHECK: The HiveConfig PDA of the hive of `roles`, whether or not it
+    /// exists; required with `roles`, for its approval rounding
+    #[account(seeds = [HIVE_CONFIG_SEED, creator_allowlist::allowlist_seed(&roles)], bump)]
+    pub hive_config: Option<UncheckedAccount<'info>>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = RewardPool::LEN,
+        seeds = [REWARD_POOL_SEED],
+        bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+    /// Sponsored draft holding these params; promoted by this creation
+    #[account(
+        mut,
+        seeds = [PROPOSAL_DRAFT_SEED, creator.key().as_ref(), action_hash.as_ref()],
+        bump = draft.bump
+    )]
+    pub draft: Option<Account<'info, ProposalDraft>>,
+    /// CHECK: The WeightCaps PDA; read in create_fast_action if it exists
+    #[account(seeds = [WEIGHT_CAPS_SEED], bump)]
+    pub weight_caps: UncheckedAccount<'info>,
+    /// CHECK: The EarlyVoteCurves PDA; read in create_fast_action if it exists
+    #[account(seeds = [EARLY_VOTE_CURVES_SEED], bump)]
+    pub early_vote_curves: UncheckedAccount<'info>,
+    /// CHECK: The configured weight mint; needed for supply-relative caps
+    #[account(address = config.weight_mint @ FastVoteError::InvalidWeightMint)]
+    pub weight_mint: Option<UncheckedAccount<'info>>,
+    /// create_from_template only: the template the action is created from
+    #[account(
+        seeds = [TEMPLATE_SEED, template.hive.as_ref(), &template.template_id.to_le_bytes()],
+        bump = template.bump
+    )]
+    pub template: Option<Account<'info, Template>>,
+    /// create_from_template only, when the template has default callbacks
+    #[account(
+        init,
+        payer = creator,
+        space = CallbackRegistry::LEN,
+        seeds = [CALLBACK_REGISTRY_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub callback_registry: Option<Account<'info, CallbackRegistry>>,
+    /// CHECK: The ActiveActions PDA the action is counted in, if it exists;
+    /// required once the config caps low-priority actions
+    #[account(mut, seeds = [ACTIVE_ACTIONS_SEED, hive::action_seed(&hive_account)], bump)]
+    pub active_actions: Option<UncheckedAccount<'info>>,
+    /// CHECK: The action's hive's InsurancePool, taking its cut of the
+    /// creation fee if it exists; checked in insurance::take_cut
+    #[account(mut)]
+    pub insurance_pool: Option<UncheckedAccount<'info>>,
+    /// CHECK: The base layer's Metrics PDA, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
     pub system_program: Program<'info, System>,
 }
 
+#[cfg(not(feature = "no-er"))]
 #[delegate]
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 #[instruction(action_id: u64)]
 pub struct DelegateAction<'info> {
@@ -259,17 +2139,79 @@ pub struct DelegateAction<'info> {
     pub pda: AccountInfo<'info>,
     #[account(mut)]
     pub payer: Signer<'info>,
-    /// CHECK: Optional TEE validator pubkey
+    /// CHECK: Optional TEE validator pubkey; must be allowlisted and live
     pub validator: Option<AccountInfo<'info>>,
+    /// Registry entry of `validator`; required when pinning one
+    #[account(mut, seeds = [VALIDATOR_SEED, validator_record.validator.as_ref()], bump = validator_record.bump)]
+    pub validator_record: Option<Account<'info, ValidatorRecord>>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-#[instruction(action_id: u64)]
 pub struct VoteFast<'info> {
+    /// CHECK: The action voted on; checked and updated in place by
+    /// tally_region::ActionView
+    #[account(mut)]
+    pub fast_action: UncheckedAccount<'info>,
+    /// CHECK: The ballot's FastVote PDA; created by vote_fast once the ballot
+    /// is accepted
+    #[account(mut, seeds = [FAST_VOTE_SEED, fast_action.key().as_ref(), agent.key().as_ref()], bump)]
+    pub fast_vote: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoteCredits::LEN,
+        seeds = [VOTE_CREDITS_SEED, agent.key().as_ref()],
+        bump
+    )]
+    pub vote_credits: Account<'info, VoteCredits>,
+    /// CHECK: The agent's AgentRecord PDA; read in agent_registry if it exists
+    #[account(seeds = [AGENT_SEED, agent.key().as_ref()], bump)]
+    pub agent_record: UncheckedAccount<'info>,
+    /// CHECK: The voter's VoteKey PDA; if it exists the voter signs for the
+    /// agent it names
+    #[account(seeds = [VOTE_KEY_SEED, voter.key().as_ref()], bump)]
+    pub vote_key: UncheckedAccount<'info>,
+    /// CHECK: The agent the ballot is cast for: the voter itself, or the
+    /// agent its vote key is bound to
+    #[account(
+        constraint = agent.key() == agent_registry::identity_of(&vote_key, voter.key)? @ FastVoteError::AgentMismatch
+    )]
+    pub agent: UncheckedAccount<'info>,
+    /// Signs and pays; the agent itself or one of its vote keys
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// CHECK: The action's CompressedBallots PDA; PDA ballots are refused once
+    /// it exists
+    #[account(seeds = [COMPRESSED_BALLOTS_SEED, fast_action.key().as_ref()], bump)]
+    pub compressed_ballots: UncheckedAccount<'info>,
+    /// CHECK: The ER's Metrics PDA, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
+    /// CHECK: The HiveFreeze PDA of the action's hive, whether or not it
+    /// exists; required for hive actions, checked in freeze::check_vote_fast
+    pub hive_freeze: Option<UncheckedAccount<'info>>,
+    /// CHECK: The StandingsBoard PDA of the action's hive; written in
+    /// standings_board::post if it exists
+    #[account(mut)]
+    pub standings_board: Option<UncheckedAccount<'info>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(action_id: u64)]
+pub struct VoteFastWeighted<'info> {
     #[account(
         mut,
-        seeds = [FAST_ACTION_SEED, &action_id.to_le_bytes()],
-        bump = fast_action.bump
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
     )]
     pub fast_action: Account<'info, FastAction>,
     #[account(
@@ -280,101 +2222,147 @@ pub struct VoteFast<'info> {
         bump
     )]
     pub fast_vote: Account<'info, FastVote>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoteCredits::LEN,
+        seeds = [VOTE_CREDITS_SEED, voter.key().as_ref()],
+        bump
+    )]
+    pub vote_credits: Account<'info, VoteCredits>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = StakeLock::LEN,
+        seeds = [STAKE_LOCK_SEED, voter.key().as_ref()],
+        bump
+    )]
+    pub stake_lock: Account<'info, StakeLock>,
+    #[account(
+        init,
+        payer = voter,
+        space = StakeLockClaim::LEN,
+        seeds = [STAKE_LOCK_CLAIM_SEED, fast_action.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub stake_lock_claim: Account<'info, StakeLockClaim>,
+    /// CHECK: Signs lock_stake; holds nothing
+    #[account(seeds = [STAKE_LOCK_AUTHORITY_SEED], bump)]
+    pub lock_authority: UncheckedAccount<'info>,
+    /// CHECK: The config's stake lock program
+    #[account(address = config.stake_lock_program @ FastVoteError::InvalidStakeLock)]
+    pub stake_lock_program: UncheckedAccount<'info>,
+    /// CHECK: The voter's AgentRecord PDA; read in agent_registry if it exists
+    #[account(seeds = [AGENT_SEED, voter.key().as_ref()], bump)]
+    pub agent_record: UncheckedAccount<'info>,
     #[account(mut)]
     pub voter: Signer<'info>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// CHECK: Must be the configured weight mint; layout parsed in token_weight
+    #[account(address = config.weight_mint @ FastVoteError::InvalidWeightMint)]
+    pub weight_mint: AccountInfo<'info>,
+    /// CHECK: Token or Token-2022 account; mint/owner/state validated in
+    /// token_weight, locked by the stake lock program
+    #[account(mut)]
+    pub voter_token_account: AccountInfo<'info>,
+    /// CHECK: The voter's VoteDelegation PDA; read in vote_delegation if it exists
+    #[account(seeds = [VOTE_DELEGATION_SEED, voter.key().as_ref()], bump)]
+    pub vote_delegation: UncheckedAccount<'info>,
+    /// CHECK: The action's WeightSnapshot PDA; live balances are refused once
+    /// it exists
+    #[account(seeds = [WEIGHT_SNAPSHOT_SEED, fast_action.key().as_ref()], bump)]
+    pub weight_snapshot: UncheckedAccount<'info>,
+    /// CHECK: The action's WeightStrategy PDA; live balances are refused
+    /// once it exists
+    #[account(seeds = [WEIGHT_STRATEGY_SEED, fast_action.key().as_ref()], bump)]
+    pub weight_strategy: UncheckedAccount<'info>,
+    /// CHECK: The base layer's Metrics PDA, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
+    /// CHECK: The HiveFreeze PDA of the action's hive, whether or not it
+    /// exists; read in freeze::require_live
+    #[account(seeds = [HIVE_FREEZE_SEED, fast_action.hive.as_ref()], bump)]
+    pub hive_freeze: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct TallyAndCommit<'info> {
     #[account(
         mut,
-        seeds = [FAST_ACTION_SEED, &fast_action.action_id.to_le_bytes()],
-        bump = fast_action.bump
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
     )]
     pub fast_action: Account<'info, FastAction>,
     #[account(mut)]
     pub payer: Signer<'info>,
     /// CHECK: MagicBlock context - validated via address constraint
-    #[account(address = MAGIC_CONTEXT_ID @ FastVoteError::InvalidMagicContext)]
+    #[cfg(not(feature = "no-er"))]
+    #[account(mut, address = MAGIC_CONTEXT_ID @ FastVoteError::InvalidMagicContext)]
     pub magic_context: AccountInfo<'info>,
     /// CHECK: MagicBlock program - validated via address constraint
+    #[cfg(not(feature = "no-er"))]
     #[account(address = MAGIC_PROGRAM_ID @ FastVoteError::InvalidMagicBlockProgram)]
     pub magic_program: AccountInfo<'info>,
+    /// Required for hive-scoped actions; payer must hold Role::Tallier
+    #[account(seeds = [ROLES_SEED, roles.hive.as_ref()], bump = roles.bump)]
+    pub roles: Option<Account<'info, Roles>>,
+    /// Required for audited actions
+    #[account(seeds = [TALLY_AUDIT_SEED, fast_action.key().as_ref()], bump = tally_audit.bump)]
+    pub tally_audit: Option<Account<'info, TallyAudit>>,
+    /// CHECK: The ER's Metrics PDA, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
+    /// CHECK: The EpochStats PDA of the action's hive and the current epoch;
+    /// counted in epoch_stats::record_finalized if it exists
+    #[account(mut)]
+    pub epoch_stats: Option<UncheckedAccount<'info>>,
+    /// CHECK: The action hive's ResultAttestors PDA, which may not exist;
+    /// read in attestation::require_attested
+    #[account(seeds = [RESULT_ATTESTORS_SEED, fast_action.hive.as_ref()], bump)]
+    pub result_attestors: UncheckedAccount<'info>,
+    /// CHECK: The instructions sysvar, for the attestors' signatures
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions: UncheckedAccount<'info>,
+    /// CHECK: The action hive's CategoryConfig PDA, which may not exist;
+    /// read in CategoryConfig::contest_margin
+    #[account(seeds = [CATEGORY_CONFIG_SEED, fast_action.hive.as_ref()], bump)]
+    pub category_config: UncheckedAccount<'info>,
+    /// For the fee rebate schedule
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// CHECK: The StandingsBoard PDA of the action's hive; written in
+    /// standings_board::post if it exists
+    #[account(mut)]
+    pub standings_board: Option<UncheckedAccount<'info>>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 #[instruction(action_id: u64)]
 pub struct CancelAction<'info> {
     #[account(
         mut,
-        seeds = [FAST_ACTION_SEED, &action_id.to_le_bytes()],
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &action_id.to_le_bytes()],
         bump = fast_action.bump,
-        constraint = fast_action.creator == creator.key() @ FastVoteError::Unauthorized
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
     )]
     pub fast_action: Account<'info, FastAction>,
-    pub creator: Signer<'info>,
-}
-
-#[event]
-pub struct FastActionCreated {
-    pub action: Pubkey,
-    pub action_id: u64,
-    pub action_hash: [u8; 32],
-    pub threshold: u8,
-    pub deadline_slot: u64,
-}
-
-#[event]
-pub struct FastVoteCast {
-    pub action: Pubkey,
-    pub voter_commitment: [u8; 32],
-    pub vote_count: u32,
-}
-
-#[event]
-pub struct FastActionExecuted {
-    pub action: Pubkey,
-    pub action_id: u64,
-    pub votes_for: u32,
-    pub votes_against: u32,
-    pub result: VoteResult,
-}
-
-#[event]
-pub struct FastActionCancelled {
-    pub action: Pubkey,
-    pub action_id: u64,
-}
-
-#[error_code]
-pub enum FastVoteError {
-    #[msg("Threshold must be 1-100")]
-    InvalidThreshold,
-    #[msg("Action hash cannot be zero")]
-    InvalidActionHash,
-    #[msg("Slot calculation overflow")]
-    SlotOverflow,
-    #[msg("PDA does not match expected derivation")]
-    InvalidPda,
-    #[msg("Voting has ended")]
-    VotingEnded,
-    #[msg("Voting has not ended yet")]
-    VotingNotEnded,
-    #[msg("Action already executed")]
-    ActionAlreadyExecuted,
-    #[msg("Vote count overflow")]
-    VoteOverflow,
-    #[msg("Max votes reached for this action")]
-    MaxVotesReached,
-    #[msg("Voter commitment cannot be zero")]
-    InvalidVoterCommitment,
-    #[msg("Quorum not met")]
-    QuorumNotMet,
-    #[msg("Unauthorized")]
-    Unauthorized,
-    #[msg("Invalid MagicBlock program")]
-    InvalidMagicBlockProgram,
-    #[msg("Invalid MagicBlock context")]
-    InvalidMagicContext,
+    /// Action authority, or a hive member holding Role::Canceller
+    pub authority: Signer<'info>,
+    #[account(seeds = [ROLES_SEED, roles.hive.as_ref()], bump = roles.bump)]
+    pub roles: Option<Account<'info, Roles>>,
+    /// CHECK: The action hive's config PDA, which may not exist; read in
+    /// HiveConfig::cancellation_policy
+    #[account(seeds = [HIVE_CONFIG_SEED, fast_action.hive.as_ref()], bump)]
+    pub hive_config: UncheckedAccount<'info>,
+    /// CHECK: The Metrics PDA, or the hive's, of the layer holding the action;
+    /// counted in metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
 }
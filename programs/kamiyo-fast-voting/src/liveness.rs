@@ -0,0 +1,164 @@
+// Escape hatch for actions that were delegated but never finalized, e.g.
+// quorum was missed or nobody tallied before the validator stopped serving.
+// The action comes back Expired; expire_action can then close it.
+// reap_stale_delegations does the same for a batch, for keepers that sweep
+// the rollup on a schedule.
+
+use anchor_lang::prelude::*;
+use ephemeral_rollups_sdk::consts::{MAGIC_CONTEXT_ID, MAGIC_PROGRAM_ID};
+use ephemeral_rollups_sdk::ephem::commit_and_undelegate_accounts;
+
+use crate::expiry::mark_expired;
+use crate::{
+    metrics, ActionForceUndelegated, ActionUndelegated, Counter, DelegationState, FastAction, FastVoteError, LogTag,
+    StaleDelegationsReaped, FAST_ACTION_SEED, FAST_ACTION_VERSION, LIVENESS_TIMEOUT_SLOTS,
+};
+
+/// Permissionless. Marks the action Expired and hands it back to the base
+/// layer, naming the validator it was delegated to.
+pub fn process_force_undelegate(ctx: Context<ForceUndelegate>) -> Result<()> {
+    let action = &mut ctx.accounts.fast_action;
+    let slot = Clock::get()?.slot;
+
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(slot > unlock_slot(action)?, FastVoteError::LivenessTimeoutNotReached);
+
+    abort(action, slot);
+    metrics::record(&ctx.accounts.metrics, &action.hive, Counter::Expired, slot)?;
+    action.exit(&crate::ID)?;
+
+    commit_and_undelegate_accounts(
+        &ctx.accounts.payer,
+        vec![&action.to_account_info()],
+        &ctx.accounts.magic_context,
+        &ctx.accounts.magic_program,
+    )?;
+
+    let key = action.key();
+    let hive = action.hive;
+    emit_event!(ctx, ActionForceUndelegated {
+        action: key,
+        hive,
+        action_id: action.action_id,
+        validator: action.delegated_validator,
+        deadline_slot: action.deadline_slot,
+        slot,
+    });
+    emit_event!(ctx, ActionUndelegated { action: key, hive, slot });
+
+    Ok(())
+}
+
+/// Permissionless. Remaining accounts are delegated actions; each one
+/// force_undelegate would accept is expired and handed back in one commit.
+/// Others (finalized, still in their timeout, or not at the current version)
+/// are skipped, so a keeper may pass whatever its scan found.
+pub fn process_reap_stale_delegations<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ReapStaleDelegations<'info>>,
+) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let mut reaped = Vec::new();
+    for info in ctx.remaining_accounts {
+        if !info.is_writable || info.data_len() != FastAction::LEN {
+            continue;
+        }
+        let mut action = Account::<FastAction>::try_from(info)?;
+        if action.version != FAST_ACTION_VERSION
+            || action.executed
+            || action.delegation_state != DelegationState::Delegated
+            || slot <= unlock_slot(&action)?
+        {
+            continue;
+        }
+        abort(&mut action, slot);
+        metrics::record(&ctx.accounts.metrics, &action.hive, Counter::Expired, slot)?;
+        action.exit(&crate::ID)?;
+        reaped.push(action);
+    }
+
+    let skipped = (ctx.remaining_accounts.len() - reaped.len()) as u32;
+    if !reaped.is_empty() {
+        commit_and_undelegate_accounts(
+            &ctx.accounts.payer,
+            reaped.iter().map(|a| a.as_ref()).collect(),
+            &ctx.accounts.magic_context,
+            &ctx.accounts.magic_program,
+        )?;
+    }
+
+    for action in &reaped {
+        emit_event!(ctx, ActionForceUndelegated {
+            action: action.key(),
+            hive: action.hive,
+            action_id: action.action_id,
+            validator: action.delegated_validator,
+            deadline_slot: action.deadline_slot,
+            slot,
+        });
+        emit_event!(ctx, ActionUndelegated { action: action.key(), hive: action.hive, slot });
+    }
+    emit_event!(ctx, StaleDelegationsReaped {
+        reaped: reaped.len() as u32,
+        skipped,
+        slot,
+    });
+
+    Ok(())
+}
+
+/// Slot after which a stranded action may be undelegated
+pub(crate) fn unlock_slot(action: &FastAction) -> Result<u64> {
+    Ok(action
+        .deadline_slot
+        .checked_add(LIVENESS_TIMEOUT_SLOTS)
+        .ok_or(FastVoteError::SlotOverflow)?)
+}
+
+fn abort(action: &mut FastAction, slot: u64) {
+    mark_expired(action, slot);
+    action.delegation_epoch = action.delegation_epoch.wrapping_add(1);
+    action.delegation_state = DelegationState::Undelegated;
+    let epoch = action.delegation_epoch as u64;
+    action.log.push(slot, LogTag::Undelegated, epoch);
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ForceUndelegate<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: MagicBlock context - validated via address constraint
+    #[account(mut, address = MAGIC_CONTEXT_ID @ FastVoteError::InvalidMagicContext)]
+    pub magic_context: AccountInfo<'info>,
+    /// CHECK: MagicBlock program - validated via address constraint
+    #[account(address = MAGIC_PROGRAM_ID @ FastVoteError::InvalidMagicBlockProgram)]
+    pub magic_program: AccountInfo<'info>,
+    /// CHECK: The ER's Metrics PDA, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ReapStaleDelegations<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: MagicBlock context - validated via address constraint
+    #[account(mut, address = MAGIC_CONTEXT_ID @ FastVoteError::InvalidMagicContext)]
+    pub magic_context: AccountInfo<'info>,
+    /// CHECK: MagicBlock program - validated via address constraint
+    #[account(address = MAGIC_PROGRAM_ID @ FastVoteError::InvalidMagicBlockProgram)]
+    pub magic_program: AccountInfo<'info>,
+    /// CHECK: The ER's Metrics PDA, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
+}
@@ -0,0 +1,68 @@
+// Experimental market gating. A gated action that passes is not executable
+// until a price feed — a prediction market, or an oracle on one — is seen
+// above the action's bound. Feeds differ in layout, so the gate names the
+// owning program and the offset of a little-endian u64 price in the feed.
+
+use anchor_lang::prelude::*;
+
+use crate::dispute::is_settled;
+use crate::{FastAction, FastVoteError, MarketGateCleared, VoteResult, FAST_ACTION_SEED, FAST_ACTION_VERSION};
+
+/// Passed, settled, and clear of its market gate if it has one
+pub fn is_executable(action: &FastAction, slot: u64) -> bool {
+    matches!(action.result, VoteResult::Passed { .. })
+        && is_settled(action, slot)
+        && (action.market_feed == Pubkey::default() || action.market_cleared)
+}
+
+fn read_price(feed: &AccountInfo, offset: u16) -> Result<u64> {
+    let data = feed.try_borrow_data()?;
+    let start = offset as usize;
+    let bytes = data.get(start..start + 8).ok_or(FastVoteError::InvalidMarketFeed)?;
+    Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+/// Permissionless, on the base layer. May be retried until the price clears.
+pub fn process_clear_market_gate(ctx: Context<ClearMarketGate>) -> Result<()> {
+    let action = &mut ctx.accounts.fast_action;
+    require!(action.market_feed != Pubkey::default(), FastVoteError::NoMarketGate);
+    require!(!action.market_cleared, FastVoteError::MarketGateCleared);
+    require!(
+        matches!(action.result, VoteResult::Passed { .. }),
+        FastVoteError::ActionNotFinalized
+    );
+    require!(is_settled(action, Clock::get()?.slot), FastVoteError::DisputeWindowOpen);
+
+    let feed = &ctx.accounts.feed;
+    require_keys_eq!(feed.key(), action.market_feed, FastVoteError::InvalidMarketFeed);
+    require_keys_eq!(*feed.owner, action.market_program, FastVoteError::InvalidMarketFeed);
+    let price = read_price(feed, action.market_price_offset)?;
+    require!(price > action.market_min_price, FastVoteError::MarketPriceTooLow);
+    action.market_cleared = true;
+
+    emit_event!(ctx, MarketGateCleared {
+        action: action.key(),
+        hive: action.hive,
+        action_id: action.action_id,
+        feed: feed.key(),
+        price,
+        min_price: action.market_min_price,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ClearMarketGate<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    /// CHECK: Must be the action's market feed, owned by its feed program;
+    /// checked in process_clear_market_gate
+    pub feed: UncheckedAccount<'info>,
+}
@@ -0,0 +1,118 @@
+// Collection-gated electorates. An action with a member collection takes
+// vote_fast ballots only from voters who show an NFT of it, passed as
+// remaining accounts:
+//
+// - a Core asset: [asset], whose update authority is the collection; or
+// - a Token Metadata NFT: [token account, metadata], the metadata's
+//   collection verified and the token account the voter's, holding the mint.
+//
+// Only MetadataV1 accounts of the Token Metadata program are accepted, and
+// the program creates those at the mint's metadata PDA alone, so matching the
+// mint stands in for re-deriving the address. Fungible tokens in a
+// collection don't count as membership.
+//
+// Gating is for headcount and optimistic for/against actions, the ballots
+// vote_fast and vote_compact take; the other ballot paths are closed to them
+// by vote mode or option count.
+
+use anchor_lang::prelude::*;
+
+use crate::identity_weight::read_core_asset;
+use crate::{
+    FastVoteError, VoteMode, MPL_CORE_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_METADATA_PROGRAM_ID,
+    TOKEN_PROGRAM_ID,
+};
+
+// spl-token Account layout
+const ACCOUNT_MINT_OFFSET: usize = 0;
+const ACCOUNT_OWNER_OFFSET: usize = 32;
+const ACCOUNT_AMOUNT_OFFSET: usize = 64;
+const ACCOUNT_STATE_OFFSET: usize = 108;
+// Initialized or, as programmable NFTs always are, Frozen
+const ACCOUNT_STATES_HELD: [u8; 2] = [1, 2];
+
+// mpl-token-metadata Key::MetadataV1
+const KEY_METADATA_V1: u8 = 4;
+// TokenStandard::FungibleAsset, TokenStandard::Fungible
+const FUNGIBLE_STANDARDS: [u8; 2] = [1, 2];
+
+// Metadata up to its collection; strings as raw bytes, as they are padded
+#[derive(AnchorDeserialize)]
+struct Metadata {
+    key: u8,
+    _update_authority: Pubkey,
+    mint: Pubkey,
+    _name: Vec<u8>,
+    _symbol: Vec<u8>,
+    _uri: Vec<u8>,
+    _seller_fee_basis_points: u16,
+    _creators: Option<Vec<Creator>>,
+    _primary_sale_happened: bool,
+    _is_mutable: bool,
+    _edition_nonce: Option<u8>,
+    token_standard: Option<u8>,
+    collection: Option<Collection>,
+}
+
+#[derive(AnchorDeserialize)]
+struct Creator {
+    _address: Pubkey,
+    _verified: bool,
+    _share: u8,
+}
+
+#[derive(AnchorDeserialize)]
+struct Collection {
+    verified: bool,
+    key: Pubkey,
+}
+
+/// Checks a new action's member collection; None leaves it open
+pub fn resolve_collection(collection: Option<Pubkey>, vote_mode: VoteMode, option_count: u8) -> Result<Pubkey> {
+    let Some(collection) = collection else {
+        return Ok(Pubkey::default());
+    };
+    require!(
+        collection != Pubkey::default() && vote_mode != VoteMode::TokenWeighted && option_count == 0,
+        FastVoteError::InvalidMemberCollection
+    );
+    Ok(collection)
+}
+
+/// `voter` holds an NFT of `collection`, shown by `accounts`
+pub fn require_member(collection: &Pubkey, voter: &Pubkey, accounts: &[AccountInfo]) -> Result<()> {
+    let member = match accounts {
+        [asset, ..] if *asset.owner == MPL_CORE_PROGRAM_ID => read_core_asset(&asset.try_borrow_data()?)
+            .is_some_and(|(owner, asset_collection, _)| owner == *voter && asset_collection == *collection),
+        [token_account, metadata, ..] => holds_nft(token_account, metadata, collection, voter)?,
+        _ => false,
+    };
+    require!(member, FastVoteError::NotCollectionMember);
+    Ok(())
+}
+
+fn holds_nft(token_account: &AccountInfo, metadata: &AccountInfo, collection: &Pubkey, voter: &Pubkey) -> Result<bool> {
+    let token_program = *token_account.owner;
+    if !(token_program == TOKEN_PROGRAM_ID || token_program == TOKEN_2022_PROGRAM_ID)
+        || *metadata.owner != TOKEN_METADATA_PROGRAM_ID
+    {
+        return Ok(false);
+    }
+    let Ok(nft) = Metadata::deserialize(&mut &metadata.try_borrow_data()?[..]) else {
+        return Ok(false);
+    };
+    let verified = nft.key == KEY_METADATA_V1
+        && nft.collection.is_some_and(|c| c.verified && c.key == *collection)
+        && !nft.token_standard.is_some_and(|standard| FUNGIBLE_STANDARDS.contains(&standard));
+
+    let data = token_account.try_borrow_data()?;
+    let Some(account) = data.get(..ACCOUNT_STATE_OFFSET + 1) else {
+        return Ok(false);
+    };
+    let amount = u64::from_le_bytes(account[ACCOUNT_AMOUNT_OFFSET..ACCOUNT_AMOUNT_OFFSET + 8].try_into().unwrap());
+    Ok(verified
+        && account[ACCOUNT_MINT_OFFSET..ACCOUNT_MINT_OFFSET + 32] == nft.mint.to_bytes()
+        && account[ACCOUNT_OWNER_OFFSET..ACCOUNT_OWNER_OFFSET + 32] == voter.to_bytes()
+        && amount > 0
+        && ACCOUNT_STATES_HELD.contains(&account[ACCOUNT_STATE_OFFSET]))
+}
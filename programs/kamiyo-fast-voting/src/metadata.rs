@@ -0,0 +1,111 @@
+// Human-readable action metadata. `description_hash` alone leaves clients
+// hunting for text that often disappears off-chain, so an action may carry a
+// short description and a URI in a companion PDA. The account is sized to
+// its contents and resized on every update; like amendments, updates stop
+// at the first vote.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::{
+    ActionMetadata, ActionMetadataSet, FastAction, FastVoteError, ACTION_METADATA_SEED, FAST_ACTION_SEED,
+    FAST_ACTION_VERSION, MAX_DESCRIPTION_LEN, MAX_METADATA_URI_LEN,
+};
+
+pub fn process_set_metadata(ctx: Context<SetMetadata>, description: String, uri: String) -> Result<()> {
+    require!(description.len() <= MAX_DESCRIPTION_LEN, FastVoteError::DescriptionTooLong);
+    require!(uri.len() <= MAX_METADATA_URI_LEN, FastVoteError::MetadataUriTooLong);
+    let action = &ctx.accounts.fast_action;
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(action.vote_count == 0, FastVoteError::ActionHasVotes);
+
+    let metadata = ActionMetadata {
+        action: action.key(),
+        description,
+        uri,
+        bump: ctx.bumps.metadata,
+    };
+    let space = ActionMetadata::space(&metadata.description, &metadata.uri);
+    let rent = Rent::get()?.minimum_balance(space);
+
+    let info = ctx.accounts.metadata.to_account_info();
+    let authority = ctx.accounts.authority.to_account_info();
+    let system = ctx.accounts.system_program.to_account_info();
+    let shortfall = rent.saturating_sub(info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system.clone(),
+                system_program::Transfer {
+                    from: authority.clone(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    if *info.owner == system_program::ID {
+        let action_key = action.key();
+        let seeds: &[&[u8]] = &[ACTION_METADATA_SEED, action_key.as_ref(), &[metadata.bump]];
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system.clone(),
+                system_program::Allocate {
+                    account_to_allocate: info.clone(),
+                },
+                &[seeds],
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system,
+                system_program::Assign {
+                    account_to_assign: info.clone(),
+                },
+                &[seeds],
+            ),
+            &crate::ID,
+        )?;
+    } else {
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        info.realloc(space, false)?;
+        // Shorter text frees rent; hand it back
+        let excess = info.lamports().saturating_sub(rent);
+        if excess > 0 {
+            **info.try_borrow_mut_lamports()? -= excess;
+            **authority.try_borrow_mut_lamports()? += excess;
+        }
+    }
+    metadata.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+    emit_event!(ctx, ActionMetadataSet {
+        action: metadata.action,
+        hive: ctx.accounts.fast_action.hive,
+        action_id: ctx.accounts.fast_action.action_id,
+        description: metadata.description,
+        uri: metadata.uri,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetMetadata<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        has_one = authority @ FastVoteError::Unauthorized,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    /// CHECK: The action's ActionMetadata PDA; created or resized in
+    /// process_set_metadata
+    #[account(mut, seeds = [ACTION_METADATA_SEED, fast_action.key().as_ref()], bump)]
+    pub metadata: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
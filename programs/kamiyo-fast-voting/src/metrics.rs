@@ -0,0 +1,138 @@
+// Program-wide liveness counters, one Metrics PDA per layer, so an operator
+// can watch a single account instead of indexing every transaction. The
+// base layer counts creations, expiries and cancellations there; the ER
+// counts ballots, finalizations and forced expiries. Instructions take the
+// PDA as an optional account and count only if the admin created it, and
+// every one that does write-locks it, so busy layers trade some ballot
+// parallelism for the counters.
+//
+// A hive's authority may also create Metrics PDAs of its own, one per layer,
+// counting only its actions. They go in the same optional slot, so a
+// tenant's traffic need not contend for the program-wide lock, and an
+// indexer serving many hives finds each one's counters, like its events,
+// by the hive key.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    FastVoteError, GlobalConfig, Hive, Metrics, MetricsInitialized, MetricsLayer, GLOBAL_CONFIG_SEED, HIVE_SEED,
+    METRICS_SEED,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Counter {
+    Created,
+    Finalized,
+    Expired,
+    Cancelled,
+    Vote,
+}
+
+/// Counts `counter` at `slot` on the Metrics account passed as `metrics`,
+/// if one was passed and it exists; a hive's own must be `hive`'s
+pub fn record(metrics: &Option<UncheckedAccount>, hive: &Pubkey, counter: Counter, slot: u64) -> Result<()> {
+    let Some(info) = metrics else {
+        return Ok(());
+    };
+    if info.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let mut data = info.try_borrow_mut_data()?;
+    let mut metrics = Metrics::decode(&data)?;
+    require!(
+        metrics.hive == Pubkey::default() || metrics.hive == *hive,
+        FastVoteError::MetricsHiveMismatch
+    );
+    let count = match counter {
+        Counter::Created => &mut metrics.actions_created,
+        Counter::Finalized => &mut metrics.actions_finalized,
+        Counter::Expired => &mut metrics.actions_expired,
+        Counter::Cancelled => &mut metrics.actions_cancelled,
+        Counter::Vote => &mut metrics.votes,
+    };
+    *count = count.saturating_add(1);
+    metrics.last_activity_slot = slot;
+    // Legacy accounts take back only the bytes they hold; `hive` is unset
+    let mut updated = Vec::with_capacity(Metrics::LEN);
+    metrics.try_serialize(&mut updated)?;
+    let len = data.len();
+    data.copy_from_slice(&updated[..len]);
+    Ok(())
+}
+
+/// Config admin only; send to the layer the Metrics account counts.
+pub fn process_initialize_metrics(ctx: Context<InitializeMetrics>, layer: MetricsLayer) -> Result<()> {
+    let metrics = &mut ctx.accounts.metrics;
+    metrics.layer = layer;
+    metrics.last_activity_slot = Clock::get()?.slot;
+    metrics.bump = ctx.bumps.metrics;
+    metrics.hive = Pubkey::default();
+
+    emit_event!(ctx, MetricsInitialized {
+        metrics: metrics.key(),
+        layer,
+        hive: metrics.hive,
+    });
+
+    Ok(())
+}
+
+/// Hive authority only; send to the layer the Metrics account counts.
+pub fn process_initialize_hive_metrics(ctx: Context<InitializeHiveMetrics>, layer: MetricsLayer) -> Result<()> {
+    let metrics = &mut ctx.accounts.metrics;
+    metrics.layer = layer;
+    metrics.last_activity_slot = Clock::get()?.slot;
+    metrics.bump = ctx.bumps.metrics;
+    metrics.hive = ctx.accounts.hive_account.hive;
+
+    emit_event!(ctx, MetricsInitialized {
+        metrics: metrics.key(),
+        layer,
+        hive: metrics.hive,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(layer: MetricsLayer)]
+pub struct InitializeMetrics<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = Metrics::LEN,
+        seeds = [METRICS_SEED, &[layer as u8]],
+        bump
+    )]
+    pub metrics: Account<'info, Metrics>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump, has_one = admin @ FastVoteError::Unauthorized)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(layer: MetricsLayer)]
+pub struct InitializeHiveMetrics<'info> {
+    #[account(
+        seeds = [HIVE_SEED, hive_account.hive.as_ref()],
+        bump = hive_account.bump,
+        has_one = authority @ FastVoteError::Unauthorized
+    )]
+    pub hive_account: Account<'info, Hive>,
+    #[account(
+        init,
+        payer = authority,
+        space = Metrics::LEN,
+        seeds = [METRICS_SEED, &[layer as u8], hive_account.hive.as_ref()],
+        bump
+    )]
+    pub metrics: Account<'info, Metrics>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
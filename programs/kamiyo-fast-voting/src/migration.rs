@@ -0,0 +1,228 @@
+// FastAction layout versioning. Fields are only ever appended: bump
+// FAST_ACTION_VERSION and new fields start zeroed on migration, unless
+// `upgrade` gives them a default.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+
+use crate::{
+    ActionKind, ActionLog, ApprovalRounding, DelegationState, EarlyVoteCurve, ExtensionPolicy, FastAction,
+    FastActionMigrated, FastVoteError, PrivacyLevel, VoteMode, VoteResult, AGENT_CLASSES, DEFAULT_PRIORITY,
+    FAST_ACTION_SEED, FAST_ACTION_VERSION, MAX_DEPENDENCIES, MAX_EXCLUSIONS, MAX_EXTRA_TIERS, MAX_OPTIONS, MAX_TAGS,
+    MAX_VOTES_PER_ACTION, MIN_VOTES_FOR_QUORUM, NO_ELIMINATION,
+};
+
+/// Layout before `version` existed, recognised by its length
+#[derive(AnchorDeserialize)]
+pub struct FastActionV0 {
+    pub action_id: u64,
+    pub action_hash: [u8; 32],
+    pub description_hash: [u8; 32],
+    pub creator: Pubkey,
+    pub threshold: u8,
+    pub votes_for: u32,
+    pub votes_against: u32,
+    pub vote_count: u32,
+    pub created_slot: u64,
+    pub deadline_slot: u64,
+    pub executed: bool,
+    pub result: VoteResult,
+    pub bump: u8,
+    pub vote_mode: VoteMode,
+    pub weight_for: u64,
+    pub weight_against: u64,
+    pub hive: Pubkey,
+    pub tier_thresholds: [u8; MAX_EXTRA_TIERS],
+    pub dependency_count: u8,
+    pub dependencies: [Pubkey; MAX_DEPENDENCIES],
+    pub finalized_slot: u64,
+    pub result_digest: [u8; 32],
+}
+
+impl FastActionV0 {
+    pub const LEN: usize = 366;
+
+    fn upgrade(self) -> FastAction {
+        FastAction {
+            version: FAST_ACTION_VERSION,
+            action_id: self.action_id,
+            action_hash: self.action_hash,
+            description_hash: self.description_hash,
+            creator: self.creator,
+            threshold: self.threshold,
+            votes_for: self.votes_for,
+            votes_against: self.votes_against,
+            vote_count: self.vote_count,
+            created_slot: self.created_slot,
+            deadline_slot: self.deadline_slot,
+            executed: self.executed,
+            result: self.result,
+            bump: self.bump,
+            vote_mode: self.vote_mode,
+            weight_for: self.weight_for,
+            weight_against: self.weight_against,
+            hive: self.hive,
+            tier_thresholds: self.tier_thresholds,
+            dependency_count: self.dependency_count,
+            dependencies: self.dependencies,
+            finalized_slot: self.finalized_slot,
+            result_digest: self.result_digest,
+            last_checkpoint_slot: 0,
+            last_checkpoint_votes: 0,
+            delegated_validator: Pubkey::default(),
+            session_recorded: false,
+            electorate: 0,
+            bond: 0,
+            quorum: MIN_VOTES_FOR_QUORUM,
+            quorum_floor: MIN_VOTES_FOR_QUORUM,
+            quorum_grace_slots: 0,
+            min_turnout_bps: 0,
+            option_count: 0,
+            option_weights: [0; MAX_OPTIONS],
+            ranked: false,
+            eliminated_mask: 0,
+            pending_elimination: NO_ELIMINATION,
+            round: 0,
+            exhausted_ballots: 0,
+            parent_action: Pubkey::default(),
+            runoff_action: Pubkey::default(),
+            market_feed: Pubkey::default(),
+            market_program: Pubkey::default(),
+            market_price_offset: 0,
+            market_min_price: 0,
+            market_cleared: false,
+            authority: self.creator,
+            draft: Pubkey::default(),
+            kind: ActionKind::Standard,
+            max_weight_per_voter: 0,
+            class_max_votes: [0; AGENT_CLASSES],
+            class_weight_bps: [0; AGENT_CLASSES],
+            class_votes: [0; AGENT_CLASSES],
+            delegation_epoch: 0,
+            delegation_state: DelegationState::Undelegated,
+            audited: false,
+            hive_seeded: false,
+            early_vote_curve: EarlyVoteCurve::OFF,
+            log: ActionLog::default(),
+            excluded_count: 0,
+            excluded: [Pubkey::default(); MAX_EXCLUSIONS],
+            extension_policy: ExtensionPolicy::OFF,
+            extensions: 0,
+            extended_slots: 0,
+            identity_weighted: false,
+            member_collection: Pubkey::default(),
+            stake_lock_program: Pubkey::default(),
+            priority: DEFAULT_PRIORITY,
+            lane_counted: false,
+            max_votes: MAX_VOTES_PER_ACTION,
+            tags: [[0u8; 8]; MAX_TAGS],
+            tags_indexed: false,
+            quorum_weight_bps: 0,
+            snapshot_weight: 0,
+            creation_fee: 0,
+            fee_rebate: 0,
+            privacy: PrivacyLevel::Public,
+            validator_counted: false,
+            threshold_bps: self.threshold as u16 * 100,
+            approval_rounding: ApprovalRounding::Floor,
+            backup_validator: Pubkey::default(),
+        }
+    }
+}
+
+/// Decode any stored layout into the current one. Returns the stored version.
+pub fn upgrade(data: &[u8]) -> Result<(u8, FastAction)> {
+    require!(
+        data.starts_with(FastAction::DISCRIMINATOR),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+
+    if data.len() == FastActionV0::LEN {
+        let legacy = FastActionV0::deserialize(&mut &data[8..])?;
+        return Ok((0, legacy.upgrade()));
+    }
+
+    let version = *data.get(8).ok_or(ErrorCode::AccountDidNotDeserialize)?;
+    require!(version < FAST_ACTION_VERSION, FastVoteError::AlreadyMigrated);
+
+    // Versioned layouts only append fields, which start zeroed
+    let mut padded = data.to_vec();
+    padded.resize(FastAction::LEN, 0);
+    let mut action = FastAction::try_deserialize(&mut &padded[..])?;
+    action.version = FAST_ACTION_VERSION;
+    // Zero is not a usable quorum; older actions keep the fixed one
+    if version < 6 {
+        action.quorum = MIN_VOTES_FOR_QUORUM;
+        action.quorum_floor = MIN_VOTES_FOR_QUORUM;
+    }
+    if version < 9 {
+        action.pending_elimination = NO_ELIMINATION;
+    }
+    if version < 12 {
+        action.authority = action.creator;
+    }
+    // Lane 0 is the capped one; older actions run in the default lane
+    if version < 28 {
+        action.priority = DEFAULT_PRIORITY;
+    }
+    // Older actions keep the fixed ballot cap
+    if version < 29 {
+        action.max_votes = MAX_VOTES_PER_ACTION;
+    }
+    // Whole-percent thresholds carry over exactly, floored as they were
+    if version < 35 {
+        action.threshold_bps = action.threshold as u16 * 100;
+    }
+    Ok((version, action))
+}
+
+/// Permissionless: the payer only covers the extra rent.
+pub fn process_migrate_action(ctx: Context<MigrateAction>, _action_id: u64) -> Result<()> {
+    let info = ctx.accounts.fast_action.to_account_info();
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+
+    let (from_version, action) = upgrade(&info.try_borrow_data()?)?;
+
+    let rent = Rent::get()?.minimum_balance(FastAction::LEN);
+    let shortfall = rent.saturating_sub(info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    info.realloc(FastAction::LEN, false)?;
+    action.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+    emit_event!(ctx, FastActionMigrated {
+        action: info.key(),
+        hive: action.hive,
+        action_id: action.action_id,
+        from_version,
+        to_version: FAST_ACTION_VERSION,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(action_id: u64)]
+pub struct MigrateAction<'info> {
+    /// CHECK: Older layouts do not deserialize as FastAction; owner and
+    /// discriminator are checked in process_migrate_action. Only unscoped
+    /// addresses: no layout before 20 could be hive-seeded
+    #[account(mut, seeds = [FAST_ACTION_SEED, &action_id.to_le_bytes()], bump)]
+    pub fast_action: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,93 @@
+// Optimistic mode: the creator bonds the action, which stands unless vetoed.
+// The bond returns to the creator once the action settles, and is forfeited
+// to the treasury if the hive reversed it.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::dispute::is_settled;
+use crate::{
+    BondReleased, FastAction, FastVoteError, Treasury, VoteResult, FAST_ACTION_SEED, FAST_ACTION_VERSION,
+    OPTIMISTIC_BOND, TREASURY_SEED,
+};
+
+/// Move the bond from the creator into the action account
+pub fn post_bond<'info>(
+    action: &Account<'info, FastAction>,
+    creator: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: creator.to_account_info(),
+                to: action.to_account_info(),
+            },
+        ),
+        OPTIMISTIC_BOND,
+    )
+}
+
+/// Permissionless. A vetoed action's bond goes to the treasury; otherwise it
+/// returns to the creator. Tallied results must be past their dispute window.
+pub fn process_release_bond(ctx: Context<ReleaseBond>) -> Result<()> {
+    let action = &mut ctx.accounts.fast_action;
+    require!(action.bond > 0, FastVoteError::NoBond);
+    require!(action.executed, FastVoteError::ActionNotFinalized);
+    require!(
+        !action.result.is_tallied() || is_settled(action, Clock::get()?.slot),
+        FastVoteError::DisputeWindowOpen
+    );
+
+    let amount = action.bond;
+    let forfeited = action.result == VoteResult::Failed;
+    let to = if forfeited {
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.bump = ctx.bumps.treasury;
+        treasury.total_slashed = treasury.total_slashed.saturating_add(amount);
+        treasury.to_account_info()
+    } else {
+        ctx.accounts.creator.to_account_info()
+    };
+    **action.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **to.try_borrow_mut_lamports()? += amount;
+    action.bond = 0;
+
+    emit_event!(ctx, BondReleased {
+        action: action.key(),
+        hive: action.hive,
+        action_id: action.action_id,
+        amount,
+        forfeited,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ReleaseBond<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        has_one = creator,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    /// CHECK: Refund destination, bound by has_one
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = Treasury::LEN,
+        seeds = [TREASURY_SEED],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,250 @@
+// Multi-option ballots. Each ballot splits the voter's weight across the
+// action's options: headcount voters hold 1 and so pick a single option,
+// token-weighted voters may divide their balance, e.g. 60/40. A balance is
+// counted live, so its ballot locks it as vote_fast_weighted's does; see
+// stake_lock. Headcount ballots count once per agent, whichever of its
+// vote keys signs; token-weighted ones are the token holder's own.
+
+use anchor_lang::prelude::*;
+
+use crate::agent_registry;
+use crate::stake_lock;
+use crate::token_weight;
+use crate::vote_credits;
+use crate::vote_delegation;
+use crate::{
+    freeze, metrics, open_ballot, Counter, FastAction, FastVote, FastVoteError, GlobalConfig, OptionVoteCast,
+    StakeLock, StakeLockClaim, StakeLocked, VoteCredits, VoteMode, AGENT_SEED, FAST_ACTION_SEED, FAST_ACTION_VERSION,
+    FAST_VOTE_SEED, GLOBAL_CONFIG_SEED, HIVE_FREEZE_SEED, STAKE_LOCK_AUTHORITY_SEED, STAKE_LOCK_CLAIM_SEED,
+    STAKE_LOCK_SEED, VOTE_CREDITS_SEED, VOTE_DELEGATION_SEED, VOTE_KEY_SEED,
+};
+
+pub fn process_vote_options(
+    ctx: Context<VoteOptions>,
+    weights: Vec<u64>,
+    voter_commitment: [u8; 32],
+) -> Result<()> {
+    freeze::require_live(&ctx.accounts.hive_freeze, &ctx.accounts.fast_action)?;
+    let option_count = ctx.accounts.fast_action.option_count as usize;
+    require!(option_count > 0, FastVoteError::NotAnOptionAction);
+    require!(!ctx.accounts.fast_action.ranked, FastVoteError::RankedBallotRequired);
+    require!(weights.len() == option_count, FastVoteError::InvalidOptionWeights);
+
+    // The agent's, whichever of its keys signed
+    let agent = ctx.accounts.agent.key();
+    agent_registry::require_eligible(&ctx.accounts.agent_record, &ctx.accounts.config)?;
+    let class = agent_registry::count_class_vote(&mut ctx.accounts.fast_action, &ctx.accounts.agent_record)?;
+
+    let mut locked = None;
+    let total = match ctx.accounts.fast_action.vote_mode {
+        VoteMode::Headcount => 1,
+        VoteMode::TokenWeighted => {
+            let config = &ctx.accounts.config;
+            let (Some(weight_mint), Some(token_account)) = (
+                ctx.accounts.weight_mint.as_ref(),
+                ctx.accounts.voter_token_account.as_ref(),
+            ) else {
+                return err!(FastVoteError::MissingWeightAccounts);
+            };
+            require_keys_eq!(weight_mint.key(), config.weight_mint, FastVoteError::InvalidWeightMint);
+            let program = config.stake_lock_program;
+            require!(program != Pubkey::default(), FastVoteError::InvalidStakeLock);
+            require!(ctx.remaining_accounts.is_empty(), FastVoteError::InvalidDelegation);
+            let voter = ctx.accounts.voter.key();
+            // The balance, lock and delegation are the signer's
+            require_keys_eq!(agent, voter, FastVoteError::AgentMismatch);
+            let clock = Clock::get()?;
+            let balance = token_weight::read_locked_token_weight(
+                token_account,
+                weight_mint,
+                &voter,
+                config,
+                clock.unix_timestamp,
+            )?;
+            let weight =
+                vote_delegation::retained_share(&ctx.accounts.fast_action, &ctx.accounts.vote_delegation, balance)?;
+            require!(weight > 0, FastVoteError::NoVotingWeight);
+
+            let (Some(lock), Some(claim), Some(authority), Some(lock_program)) = (
+                ctx.accounts.stake_lock.as_mut(),
+                ctx.accounts.stake_lock_claim.as_mut(),
+                ctx.accounts.lock_authority.as_ref(),
+                ctx.accounts.stake_lock_program.as_ref(),
+            ) else {
+                return err!(FastVoteError::MissingWeightAccounts);
+            };
+            let first = stake_lock::take_share(
+                lock,
+                ctx.bumps.stake_lock.unwrap_or_default(),
+                claim,
+                ctx.bumps.stake_lock_claim.unwrap_or_default(),
+                voter,
+                program,
+                token_account.key(),
+            )?;
+            if first {
+                stake_lock::invoke_hook(
+                    true,
+                    lock_program,
+                    authority,
+                    ctx.bumps.lock_authority.unwrap_or_default(),
+                    &ctx.accounts.voter.to_account_info(),
+                    token_account,
+                    weight_mint,
+                )?;
+            }
+            locked = Some((token_account.key(), lock.active));
+
+            let weight = agent_registry::aged_weight(&ctx.accounts.agent_record, config, weight, clock.epoch)?;
+            let action = &ctx.accounts.fast_action;
+            let weight = action.early_weight(weight, clock.slot);
+            action.capped_weight(action.class_weight(class, weight))
+        }
+        VoteMode::Optimistic => return err!(FastVoteError::WrongVoteMode),
+    };
+    let allocated = weights
+        .iter()
+        .try_fold(0u64, |sum, w| sum.checked_add(*w))
+        .ok_or(FastVoteError::VoteOverflow)?;
+    require!(allocated == total, FastVoteError::InvalidOptionWeights);
+    // Runoffs keep the parent's indices; knocked-out options take no weight
+    let eliminated_mask = ctx.accounts.fast_action.eliminated_mask;
+    require!(
+        weights.iter().enumerate().all(|(i, w)| *w == 0 || eliminated_mask & (1 << i) == 0),
+        FastVoteError::InvalidOptionWeights
+    );
+
+    vote_credits::charge_ballot(
+        &mut ctx.accounts.vote_credits,
+        agent,
+        ctx.bumps.vote_credits,
+        &ctx.accounts.config,
+    )?;
+
+    let action = &mut ctx.accounts.fast_action;
+    open_ballot(
+        action,
+        &mut ctx.accounts.fast_vote,
+        agent,
+        ctx.bumps.fast_vote,
+        true,
+        voter_commitment,
+        total,
+    )?;
+    for (tally, weight) in action.option_weights.iter_mut().zip(&weights) {
+        *tally = tally.checked_add(*weight).ok_or(FastVoteError::VoteOverflow)?;
+    }
+    metrics::record(&ctx.accounts.metrics, &action.hive, Counter::Vote, Clock::get()?.slot)?;
+    let stake_locked = locked.map(|(token_account, active)| StakeLocked {
+        voter: agent,
+        action: action.key(),
+        hive: action.hive,
+        token_account,
+        active,
+    });
+
+    emit_event!(ctx, OptionVoteCast {
+        action: action.key(),
+        hive: action.hive,
+        voter_commitment,
+        vote_count: action.vote_count,
+        weights,
+    });
+    if let Some(event) = stake_locked {
+        emit_event!(ctx, event);
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(action_id: u64)]
+pub struct VoteOptions<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    /// The agent's ballot; open_ballot refuses a second with AlreadyVoted
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = FastVote::LEN,
+        seeds = [FAST_VOTE_SEED, fast_action.key().as_ref(), agent.key().as_ref()],
+        bump
+    )]
+    pub fast_vote: Account<'info, FastVote>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoteCredits::LEN,
+        seeds = [VOTE_CREDITS_SEED, agent.key().as_ref()],
+        bump
+    )]
+    pub vote_credits: Account<'info, VoteCredits>,
+    /// Token-weighted actions only: the voter's stake lock, as in
+    /// vote_fast_weighted
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = StakeLock::LEN,
+        seeds = [STAKE_LOCK_SEED, voter.key().as_ref()],
+        bump
+    )]
+    pub stake_lock: Option<Account<'info, StakeLock>>,
+    #[account(
+        init,
+        payer = voter,
+        space = StakeLockClaim::LEN,
+        seeds = [STAKE_LOCK_CLAIM_SEED, fast_action.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub stake_lock_claim: Option<Account<'info, StakeLockClaim>>,
+    /// CHECK: Signs lock_stake; holds nothing
+    #[account(seeds = [STAKE_LOCK_AUTHORITY_SEED], bump)]
+    pub lock_authority: Option<UncheckedAccount<'info>>,
+    /// CHECK: The config's stake lock program
+    #[account(address = config.stake_lock_program @ FastVoteError::InvalidStakeLock)]
+    pub stake_lock_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: The agent's AgentRecord PDA; read in agent_registry if it exists
+    #[account(seeds = [AGENT_SEED, agent.key().as_ref()], bump)]
+    pub agent_record: UncheckedAccount<'info>,
+    /// CHECK: The voter's VoteKey PDA; if it exists the voter signs for the
+    /// agent it names
+    #[account(seeds = [VOTE_KEY_SEED, voter.key().as_ref()], bump)]
+    pub vote_key: UncheckedAccount<'info>,
+    /// CHECK: The agent the ballot is cast for: the voter itself, or, for
+    /// headcount actions, the agent its vote key is bound to
+    #[account(
+        constraint = agent.key() == agent_registry::identity_of(&vote_key, voter.key)? @ FastVoteError::AgentMismatch
+    )]
+    pub agent: UncheckedAccount<'info>,
+    /// Signs and pays; the agent itself or one of its vote keys
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// Token-weighted actions only, as in vote_fast_weighted
+    /// CHECK: Must be the configured weight mint; layout parsed in token_weight
+    pub weight_mint: Option<AccountInfo<'info>>,
+    /// CHECK: Token or Token-2022 account; mint/owner/state validated in
+    /// token_weight, locked by the stake lock program
+    #[account(mut)]
+    pub voter_token_account: Option<AccountInfo<'info>>,
+    /// CHECK: The voter's VoteDelegation PDA; read in vote_delegation if it exists
+    #[account(seeds = [VOTE_DELEGATION_SEED, voter.key().as_ref()], bump)]
+    pub vote_delegation: UncheckedAccount<'info>,
+    /// CHECK: The ER's Metrics PDA for headcount ballots, the base layer's
+    /// for token-weighted ones, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
+    /// CHECK: The HiveFreeze PDA of the action's hive, whether or not it
+    /// exists; read in freeze::require_live
+    #[account(seeds = [HIVE_FREEZE_SEED, fast_action.hive.as_ref()], bump)]
+    pub hive_freeze: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
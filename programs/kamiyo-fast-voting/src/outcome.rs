@@ -0,0 +1,41 @@
+// "Did action X pass?" for other programs, through CPI. assert_action_passed
+// fails unless the action was finalized as Passed and otherwise returns
+// ActionPassed as return data, so callers depend on that struct rather than
+// on FastAction's layout. kamiyo-voting-interface holds the CPI client and
+// a copy of the struct; the two must serialize identically.
+
+use anchor_lang::prelude::*;
+
+use crate::dispute::is_settled;
+use crate::{market, ActionPassed, FastAction, FastVoteError, VoteResult, FAST_ACTION_SEED, FAST_ACTION_VERSION};
+
+pub fn process_assert_action_passed(ctx: Context<AssertActionPassed>) -> Result<ActionPassed> {
+    let action = &ctx.accounts.fast_action;
+    let VoteResult::Passed { tier } = action.result else {
+        return err!(FastVoteError::ActionNotPassed);
+    };
+    let slot = Clock::get()?.slot;
+
+    Ok(ActionPassed {
+        action: action.key(),
+        action_id: action.action_id,
+        action_hash: action.action_hash,
+        hive: action.hive,
+        tier,
+        finalized_slot: action.finalized_slot,
+        result_digest: action.result_digest,
+        settled: is_settled(action, slot) && market::is_executable(action, slot),
+    })
+}
+
+#[derive(Accounts)]
+pub struct AssertActionPassed<'info> {
+    /// Base layer state only: a delegated action is owned by the delegation
+    /// program and fails here
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+}
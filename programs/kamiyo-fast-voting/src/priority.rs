@@ -0,0 +1,142 @@
+// Priority lanes. Hundreds of actions may share one ER session, and a
+// validator committing them in creation order can leave a critical one
+// waiting behind a queue of routine ones. Each action picks a lane at
+// creation, and a hive's ActiveActions index (one more for actions outside
+// any hive) counts the actions running in each lane, so validators and
+// keepers can see what is pending and commit by lane. The config admin may
+// also cap how many LOW_PRIORITY actions run at once.
+//
+// The index lives on the base layer: actions are counted by create_action
+// and leave their lane through retire_action once final, or when the expiry
+// sweep closes them. Counting is optional until the cap is set; from then on
+// every new action must pass its index. Runoffs keep their parent's lane but
+// are not counted, since they are spawned from a final action rather than
+// queued by a creator.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    hive, ActiveActions, ActiveActionsUpdated, FastAction, FastVoteError, GlobalConfig, Hive, ACTIVE_ACTIONS_SEED,
+    FAST_ACTION_SEED, FAST_ACTION_VERSION, HIVE_SEED, LOW_PRIORITY, PRIORITY_LANES,
+};
+
+/// The ActiveActions index passed as `index`, if one was passed and it
+/// exists
+fn load(index: &Option<UncheckedAccount>) -> Result<Option<ActiveActions>> {
+    let Some(info) = index else {
+        return Ok(None);
+    };
+    if info.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    Ok(Some(ActiveActions::try_deserialize(&mut &info.try_borrow_data()?[..])?))
+}
+
+fn store(index: &Option<UncheckedAccount>, active_actions: &ActiveActions) -> Result<()> {
+    let info = index.as_ref().ok_or(FastVoteError::ActiveActionsRequired)?;
+    active_actions.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+}
+
+/// Checks a new action's lane and counts it in `index`, if it exists;
+/// returns the index's hive and the lane's new count
+pub fn count(
+    action: &mut FastAction,
+    index: &Option<UncheckedAccount>,
+    config: &GlobalConfig,
+) -> Result<Option<(Pubkey, u32)>> {
+    require!(usize::from(action.priority) < PRIORITY_LANES, FastVoteError::InvalidPriority);
+    let Some(mut active_actions) = load(index)? else {
+        require!(config.max_low_priority_actions == 0, FastVoteError::ActiveActionsRequired);
+        return Ok(None);
+    };
+    let lane = &mut active_actions.counts[usize::from(action.priority)];
+    if action.priority == LOW_PRIORITY && config.max_low_priority_actions > 0 {
+        require!(*lane < config.max_low_priority_actions, FastVoteError::LowPriorityLaneFull);
+    }
+    *lane = lane.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+    let active = *lane;
+    store(index, &active_actions)?;
+    action.lane_counted = true;
+    Ok(Some((active_actions.hive, active)))
+}
+
+/// Takes a counted action out of its lane; returns the lane's new count
+fn retire(action: &mut FastAction, active_actions: &mut ActiveActions) -> u32 {
+    let lane = &mut active_actions.counts[usize::from(action.priority)];
+    *lane = lane.saturating_sub(1);
+    action.lane_counted = false;
+    *lane
+}
+
+/// retire for an action leaving without retire_action; `index` is required
+/// while it is counted. Returns the index's hive and the lane's new count
+pub fn retire_closing(action: &mut FastAction, index: &Option<UncheckedAccount>) -> Result<Option<(Pubkey, u32)>> {
+    if !action.lane_counted {
+        return Ok(None);
+    }
+    let mut active_actions = load(index)?.ok_or(FastVoteError::ActiveActionsRequired)?;
+    let active = retire(action, &mut active_actions);
+    store(index, &active_actions)?;
+    Ok(Some((active_actions.hive, active)))
+}
+
+/// Permissionless; creates the index of `hive_account`'s actions, or of
+/// unscoped actions without one
+pub fn process_initialize_active_actions(ctx: Context<InitializeActiveActions>) -> Result<()> {
+    let index = &mut ctx.accounts.active_actions;
+    index.hive = ctx.accounts.hive_account.as_ref().map_or(Pubkey::default(), |h| h.hive);
+    index.counts = [0; PRIORITY_LANES];
+    index.bump = ctx.bumps.active_actions;
+    Ok(())
+}
+
+/// Permissionless, on the base layer, once the action is final
+pub fn process_retire_action(ctx: Context<RetireAction>) -> Result<()> {
+    let action = &mut ctx.accounts.fast_action;
+    require!(action.executed, FastVoteError::ActionNotFinalized);
+    require!(action.lane_counted, FastVoteError::ActionNotInLane);
+
+    let active = retire(action, &mut ctx.accounts.active_actions);
+
+    emit_event!(ctx, ActiveActionsUpdated {
+        hive: ctx.accounts.active_actions.hive,
+        action: ctx.accounts.fast_action.key(),
+        priority: ctx.accounts.fast_action.priority,
+        active,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct InitializeActiveActions<'info> {
+    #[account(seeds = [HIVE_SEED, hive_account.hive.as_ref()], bump = hive_account.bump)]
+    pub hive_account: Option<Account<'info, Hive>>,
+    #[account(
+        init,
+        payer = payer,
+        space = ActiveActions::LEN,
+        seeds = [ACTIVE_ACTIONS_SEED, hive::action_seed(&hive_account)],
+        bump
+    )]
+    pub active_actions: Account<'info, ActiveActions>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RetireAction<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(mut, seeds = [ACTIVE_ACTIONS_SEED, fast_action.hive_seed()], bump = active_actions.bump)]
+    pub active_actions: Account<'info, ActiveActions>,
+}
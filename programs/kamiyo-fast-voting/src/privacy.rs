@@ -0,0 +1,327 @@
+// Ballot privacy, picked per action at creation rather than per deployment.
+// Public actions count each ballot as cast. The other levels each change
+// what a ballot records and what a tally must wait for:
+//
+// - CommitReveal: vote_fast takes the ballot_commitment as its voter
+//   commitment and counts the ballot toward quorum and turnout only.
+//   After the deadline, reveal_ballot opens it and counts its choice;
+//   tally_and_commit waits out the reveal window, and ballots never revealed
+//   count for neither side.
+// - Encrypted: the ballot is counted as cast, inside the TEE validator the
+//   action is delegated to, but its FastVote records no choice and the
+//   standings board is left alone until the tally. Nothing outside the TEE
+//   can recount such an action, so its tally needs the hive's result
+//   attestors, and Evidence::Votes cannot be brought against it.
+// - AnonymousZk: ballots do not name their voter. Each carries a nullifier
+//   and a proof that it comes from a member of the hive's registry and that
+//   the nullifier is that member's for the action. The action's authority
+//   names the verifier program before the first ballot, from those the admin
+//   has allowed, and the members root is the hive's own member_root at that
+//   moment, so a creator can neither wave ballots through nor vote with a
+//   registry of their own. Ballots go through vote_anonymous, and any payer
+//   may send them, so the voter's key never appears.
+//
+// The levels other than Public are for headcount for/against actions: their
+// ballots carry a single unit of weight and one bit of choice, and audits
+// recount choices the records here do not always hold.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke;
+
+use crate::{
+    admit_ballot, ballot_commitment, commitment_matches, freeze, AnonymousBallots, AnonymousBallotsEnabled,
+    AnonymousVoteCast, BallotRevealed, BallotVerifier, BallotVerifierSet, FastAction, FastVote, FastVoteError,
+    GlobalConfig, Hive, Nullifier, PrivacyLevel, VoteMode, ANONYMOUS_BALLOTS_SEED, BALLOT_VERIFIER_SEED,
+    FAST_ACTION_SEED, FAST_ACTION_VERSION, FAST_VOTE_SEED, GLOBAL_CONFIG_SEED, HIVE_FREEZE_SEED, HIVE_SEED,
+    NULLIFIER_SEED, REVEAL_WINDOW_SLOTS,
+};
+
+/// Creation's check of `privacy` against the rest of the action's params
+pub(crate) fn validate_level(
+    privacy: PrivacyLevel,
+    vote_mode: VoteMode,
+    option_count: u8,
+    audited: bool,
+    restricted: bool,
+) -> Result<()> {
+    if privacy == PrivacyLevel::Public {
+        return Ok(());
+    }
+    require!(
+        vote_mode == VoteMode::Headcount
+            && option_count == 0
+            && !audited
+            && !(privacy == PrivacyLevel::AnonymousZk && restricted),
+        FastVoteError::InvalidPrivacyLevel
+    );
+    Ok(())
+}
+
+/// tally_and_commit's requirements beyond the tally itself
+pub(crate) fn require_finalizable(action: &FastAction, attested: bool, slot: u64) -> Result<()> {
+    match action.privacy {
+        PrivacyLevel::CommitReveal => require!(
+            slot > action.deadline_slot.saturating_add(REVEAL_WINDOW_SLOTS),
+            FastVoteError::RevealWindowOpen
+        ),
+        PrivacyLevel::Encrypted => require!(attested, FastVoteError::AttestationMissing),
+        PrivacyLevel::Public | PrivacyLevel::AnonymousZk => {}
+    }
+    Ok(())
+}
+
+/// Permissionless, in the reveal window: whoever holds the salt may open
+/// the ballot
+pub fn process_reveal_ballot(ctx: Context<RevealBallot>, vote_value: bool, salt: [u8; 32]) -> Result<()> {
+    let action = &mut ctx.accounts.fast_action;
+    let vote = &mut ctx.accounts.fast_vote;
+    let slot = Clock::get()?.slot;
+    require!(action.privacy == PrivacyLevel::CommitReveal, FastVoteError::PrivacyLevelMismatch);
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(slot > action.deadline_slot, FastVoteError::VotingNotEnded);
+    require!(
+        slot <= action.deadline_slot.saturating_add(REVEAL_WINDOW_SLOTS),
+        FastVoteError::RevealWindowClosed
+    );
+    // Committed ballots are recorded with no weight until revealed
+    require!(vote.weight == 0, FastVoteError::BallotAlreadyRevealed);
+    let commitment = ballot_commitment(&action.key(), &vote.voter, vote_value, &salt);
+    require!(
+        commitment_matches(&vote.voter_commitment, &commitment),
+        FastVoteError::BallotCommitmentMismatch
+    );
+
+    vote.vote_value = vote_value;
+    vote.weight = 1;
+    if vote_value {
+        action.votes_for = action.votes_for.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+    } else {
+        action.votes_against = action.votes_against.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+    }
+
+    emit_event!(ctx, BallotRevealed {
+        action: action.key(),
+        hive: action.hive,
+        voter: vote.voter,
+        vote_value,
+    });
+
+    Ok(())
+}
+
+/// Admin only; creates the verifier's record on first use
+pub fn process_set_ballot_verifier(ctx: Context<SetBallotVerifier>, allowed: bool) -> Result<()> {
+    let verifier = &ctx.accounts.verifier;
+    require!(
+        !allowed || (verifier.executable && verifier.key() != crate::ID),
+        FastVoteError::InvalidBallotVerifier
+    );
+
+    let record = &mut ctx.accounts.ballot_verifier;
+    record.verifier = verifier.key();
+    record.allowed = allowed;
+    record.bump = ctx.bumps.ballot_verifier;
+
+    emit_event!(ctx, BallotVerifierSet {
+        verifier: record.verifier,
+        allowed,
+        admin: ctx.accounts.admin.key(),
+    });
+
+    Ok(())
+}
+
+/// Action authority only, before the first ballot, on the base layer
+pub fn process_enable_anonymous_ballots(ctx: Context<EnableAnonymousBallots>) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(action.vote_count == 0, FastVoteError::ActionHasVotes);
+    require!(action.privacy == PrivacyLevel::AnonymousZk, FastVoteError::PrivacyLevelMismatch);
+    require!(ctx.accounts.ballot_verifier.allowed, FastVoteError::InvalidBallotVerifier);
+    let verifier = &ctx.accounts.verifier;
+    let members_root = ctx.accounts.hive_account.member_root;
+    require!(members_root != [0; 32], FastVoteError::MemberRootRequired);
+
+    let ballots = &mut ctx.accounts.anonymous_ballots;
+    ballots.action = action.key();
+    ballots.verifier = verifier.key();
+    ballots.members_root = members_root;
+    ballots.bump = ctx.bumps.anonymous_ballots;
+
+    emit_event!(ctx, AnonymousBallotsEnabled {
+        action: ballots.action,
+        hive: action.hive,
+        verifier: ballots.verifier,
+        members_root,
+    });
+
+    Ok(())
+}
+
+/// Ballot on an AnonymousZk action; `proof` goes to the verifier after the
+/// public inputs
+pub fn process_vote_anonymous(
+    ctx: Context<VoteAnonymous>,
+    nullifier: [u8; 32],
+    vote_value: bool,
+    proof: Vec<u8>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.fast_action.privacy == PrivacyLevel::AnonymousZk,
+        FastVoteError::PrivacyLevelMismatch
+    );
+    require!(ctx.accounts.ballot_verifier.allowed, FastVoteError::InvalidBallotVerifier);
+    freeze::require_live(&ctx.accounts.hive_freeze, &ctx.accounts.fast_action)?;
+    let action_key = ctx.accounts.fast_action.key();
+    let ballots = &ctx.accounts.anonymous_ballots;
+    let mut data = Vec::with_capacity(32 * 3 + 1 + proof.len());
+    data.extend_from_slice(&ballots.members_root);
+    data.extend_from_slice(action_key.as_ref());
+    data.extend_from_slice(&nullifier);
+    data.push(vote_value as u8);
+    data.extend_from_slice(&proof);
+    invoke(
+        &Instruction {
+            program_id: ballots.verifier,
+            accounts: Vec::new(),
+            data,
+        },
+        &[ctx.accounts.verifier.to_account_info()],
+    )?;
+
+    let action = &mut ctx.accounts.fast_action;
+    let vote_count = admit_ballot(action, Clock::get()?.slot)?;
+    if vote_value {
+        action.votes_for = action.votes_for.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+    } else {
+        action.votes_against = action.votes_against.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+    }
+
+    let record = &mut ctx.accounts.nullifier_record;
+    record.action = action_key;
+    record.nullifier = nullifier;
+    record.vote_value = vote_value;
+    record.bump = ctx.bumps.nullifier_record;
+
+    emit_event!(ctx, AnonymousVoteCast {
+        action: action_key,
+        hive: action.hive,
+        nullifier,
+        vote_value,
+        vote_count,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RevealBallot<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        mut,
+        seeds = [FAST_VOTE_SEED, fast_action.key().as_ref(), fast_vote.voter.as_ref()],
+        bump = fast_vote.bump
+    )]
+    pub fast_vote: Account<'info, FastVote>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct EnableAnonymousBallots<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        has_one = authority @ FastVoteError::Unauthorized,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = authority,
+        space = AnonymousBallots::LEN,
+        seeds = [ANONYMOUS_BALLOTS_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub anonymous_ballots: Account<'info, AnonymousBallots>,
+    /// The action's hive, whose member_root the ballots are proven against
+    #[account(seeds = [HIVE_SEED, fast_action.hive.as_ref()], bump = hive_account.bump)]
+    pub hive_account: Account<'info, Hive>,
+    /// CHECK: Verifier program; must have an allowed BallotVerifier
+    pub verifier: UncheckedAccount<'info>,
+    #[account(seeds = [BALLOT_VERIFIER_SEED, verifier.key().as_ref()], bump = ballot_verifier.bump)]
+    pub ballot_verifier: Account<'info, BallotVerifier>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetBallotVerifier<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = BallotVerifier::LEN,
+        seeds = [BALLOT_VERIFIER_SEED, verifier.key().as_ref()],
+        bump
+    )]
+    pub ballot_verifier: Account<'info, BallotVerifier>,
+    /// CHECK: Verifier program; must be executable to be allowed
+    pub verifier: UncheckedAccount<'info>,
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ FastVoteError::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(nullifier: [u8; 32])]
+pub struct VoteAnonymous<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        seeds = [ANONYMOUS_BALLOTS_SEED, fast_action.key().as_ref()],
+        bump = anonymous_ballots.bump
+    )]
+    pub anonymous_ballots: Account<'info, AnonymousBallots>,
+    /// CHECK: Must be the action's verifier
+    #[account(address = anonymous_ballots.verifier @ FastVoteError::InvalidBallotVerifier)]
+    pub verifier: UncheckedAccount<'info>,
+    #[account(seeds = [BALLOT_VERIFIER_SEED, verifier.key().as_ref()], bump = ballot_verifier.bump)]
+    pub ballot_verifier: Account<'info, BallotVerifier>,
+    #[account(
+        init,
+        payer = payer,
+        space = Nullifier::LEN,
+        seeds = [NULLIFIER_SEED, fast_action.key().as_ref(), &nullifier],
+        bump
+    )]
+    pub nullifier_record: Account<'info, Nullifier>,
+    /// CHECK: The HiveFreeze PDA of the action's hive, whether or not it
+    /// exists; read in freeze::require_live
+    #[account(seeds = [HIVE_FREEZE_SEED, fast_action.hive.as_ref()], bump)]
+    pub hive_freeze: UncheckedAccount<'info>,
+    /// Any account; not the voter's
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,232 @@
+// Ranked-choice (instant runoff) ballots for multi-option actions. Each voter
+// stores an ordered ranking; `option_weights` holds the live first-choice
+// counts. After the deadline, tally_round is called repeatedly: it either
+// eliminates the weakest option, or moves a batch of ballots off the option
+// being eliminated, so every transaction stays within ER compute limits.
+
+use anchor_lang::prelude::*;
+
+use crate::agent_registry;
+use crate::vote_credits;
+use crate::{
+    freeze, metrics, open_ballot, tally, BallotsRedistributed, Counter, FastAction, FastVote, FastVoteError,
+    GlobalConfig, OptionEliminated, RankedBallot, RankedVoteCast, VoteCredits, VoteMode, AGENT_SEED, FAST_ACTION_SEED,
+    FAST_ACTION_VERSION, FAST_VOTE_SEED, GLOBAL_CONFIG_SEED, HIVE_FREEZE_SEED, MAX_OPTIONS, NO_ELIMINATION,
+    RANKED_BALLOT_SEED, VOTE_CREDITS_SEED, VOTE_KEY_SEED,
+};
+
+pub fn process_vote_ranked(ctx: Context<VoteRanked>, ranking: Vec<u8>, voter_commitment: [u8; 32]) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(action.ranked, FastVoteError::NotARankedAction);
+    freeze::require_live(&ctx.accounts.hive_freeze, action)?;
+
+    let option_count = action.option_count;
+    require!(
+        !ranking.is_empty() && ranking.len() <= option_count as usize,
+        FastVoteError::InvalidRanking
+    );
+    for (i, option) in ranking.iter().enumerate() {
+        require!(
+            *option < option_count && !ranking[..i].contains(option),
+            FastVoteError::InvalidRanking
+        );
+    }
+
+    // The agent's, whichever of its keys signed
+    let voter = ctx.accounts.agent.key();
+    agent_registry::require_eligible(&ctx.accounts.agent_record, &ctx.accounts.config)?;
+    agent_registry::count_class_vote(&mut ctx.accounts.fast_action, &ctx.accounts.agent_record)?;
+    vote_credits::charge_ballot(
+        &mut ctx.accounts.vote_credits,
+        voter,
+        ctx.bumps.vote_credits,
+        &ctx.accounts.config,
+    )?;
+
+    let action = &mut ctx.accounts.fast_action;
+    open_ballot(
+        action,
+        &mut ctx.accounts.fast_vote,
+        voter,
+        ctx.bumps.fast_vote,
+        true,
+        voter_commitment,
+        1,
+    )?;
+    let first = ranking[0] as usize;
+    action.option_weights[first] = action.option_weights[first]
+        .checked_add(1)
+        .ok_or(FastVoteError::VoteOverflow)?;
+    metrics::record(&ctx.accounts.metrics, &action.hive, Counter::Vote, Clock::get()?.slot)?;
+
+    let ballot = &mut ctx.accounts.ranked_ballot;
+    ballot.action = action.key();
+    ballot.voter = voter;
+    ballot.ranking = [NO_ELIMINATION; MAX_OPTIONS];
+    ballot.ranking[..ranking.len()].copy_from_slice(&ranking);
+    ballot.len = ranking.len() as u8;
+    ballot.position = 0;
+    ballot.bump = ctx.bumps.ranked_ballot;
+
+    emit_event!(ctx, RankedVoteCast {
+        action: action.key(),
+        hive: action.hive,
+        voter_commitment,
+        vote_count: action.vote_count,
+        ranking,
+    });
+
+    Ok(())
+}
+
+/// Permissionless, after the deadline. With an elimination pending, moves the
+/// RankedBallot accounts passed as remaining accounts to their next surviving
+/// choice; otherwise eliminates the weakest option unless one already holds
+/// a majority. tally_and_commit finalizes once a winner stands.
+pub fn process_tally_round<'info>(ctx: Context<'_, '_, '_, 'info, TallyRound<'info>>) -> Result<()> {
+    let action = &mut ctx.accounts.fast_action;
+    require!(action.ranked, FastVoteError::NotARankedAction);
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(Clock::get()?.slot > action.deadline_slot, FastVoteError::VotingNotEnded);
+
+    if action.pending_elimination != NO_ELIMINATION {
+        let eliminated = action.pending_elimination;
+        let mut moved = 0u32;
+        for info in ctx.remaining_accounts {
+            require_keys_eq!(*info.owner, crate::ID, FastVoteError::InvalidRankedBallot);
+            let mut ballot = RankedBallot::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            require_keys_eq!(ballot.action, action.key(), FastVoteError::InvalidRankedBallot);
+            if ballot.current() != Some(eliminated) {
+                continue;
+            }
+
+            while ballot
+                .current()
+                .is_some_and(|option| action.eliminated_mask & (1 << option) != 0)
+            {
+                ballot.position += 1;
+            }
+            action.option_weights[eliminated as usize] -= 1;
+            match ballot.current() {
+                Some(next) => action.option_weights[next as usize] += 1,
+                None => action.exhausted_ballots += 1,
+            }
+            ballot.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+            moved += 1;
+        }
+
+        let remaining = action.option_weights[eliminated as usize];
+        if remaining == 0 {
+            action.pending_elimination = NO_ELIMINATION;
+        }
+        emit_event!(ctx, BallotsRedistributed {
+            action: action.key(),
+            hive: action.hive,
+            round: action.round,
+            eliminated,
+            moved,
+            remaining,
+        });
+        return Ok(());
+    }
+
+    let options = &action.option_weights[..action.option_count as usize];
+    require!(
+        tally::runoff_winner(options, action.eliminated_mask).is_none(),
+        FastVoteError::RunoffDecided
+    );
+    let eliminated = tally::weakest_option(options, action.eliminated_mask).ok_or(FastVoteError::RunoffDecided)?;
+    action.eliminated_mask |= 1 << eliminated;
+    action.round += 1;
+    if action.option_weights[eliminated as usize] > 0 {
+        action.pending_elimination = eliminated;
+    }
+
+    emit_event!(ctx, OptionEliminated {
+        action: action.key(),
+        hive: action.hive,
+        round: action.round,
+        option: eliminated,
+        ballots: action.option_weights[eliminated as usize],
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(action_id: u64)]
+pub struct VoteRanked<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration,
+        constraint = fast_action.vote_mode == VoteMode::Headcount @ FastVoteError::WrongVoteMode
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    /// The agent's ballot; open_ballot refuses a second with AlreadyVoted
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = FastVote::LEN,
+        seeds = [FAST_VOTE_SEED, fast_action.key().as_ref(), agent.key().as_ref()],
+        bump
+    )]
+    pub fast_vote: Account<'info, FastVote>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = RankedBallot::LEN,
+        seeds = [RANKED_BALLOT_SEED, fast_action.key().as_ref(), agent.key().as_ref()],
+        bump
+    )]
+    pub ranked_ballot: Account<'info, RankedBallot>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoteCredits::LEN,
+        seeds = [VOTE_CREDITS_SEED, agent.key().as_ref()],
+        bump
+    )]
+    pub vote_credits: Account<'info, VoteCredits>,
+    /// CHECK: The agent's AgentRecord PDA; read in agent_registry if it exists
+    #[account(seeds = [AGENT_SEED, agent.key().as_ref()], bump)]
+    pub agent_record: UncheckedAccount<'info>,
+    /// CHECK: The voter's VoteKey PDA; if it exists the voter signs for the
+    /// agent it names
+    #[account(seeds = [VOTE_KEY_SEED, voter.key().as_ref()], bump)]
+    pub vote_key: UncheckedAccount<'info>,
+    /// CHECK: The agent the ballot is cast for: the voter itself, or the
+    /// agent its vote key is bound to
+    #[account(
+        constraint = agent.key() == agent_registry::identity_of(&vote_key, voter.key)? @ FastVoteError::AgentMismatch
+    )]
+    pub agent: UncheckedAccount<'info>,
+    /// Signs and pays; the agent itself or one of its vote keys
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// CHECK: The ER's Metrics PDA, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
+    /// CHECK: The HiveFreeze PDA of the action's hive, whether or not it
+    /// exists; read in freeze::require_live
+    #[account(seeds = [HIVE_FREEZE_SEED, fast_action.hive.as_ref()], bump)]
+    pub hive_freeze: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct TallyRound<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+}
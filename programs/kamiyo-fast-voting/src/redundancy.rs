@@ -0,0 +1,319 @@
+// Redundant delegation. An action delegated to one validator stalls when
+// that validator goes down, until force_undelegate hands it back expired.
+// An action in redundancy mode also names a backup: its ActionMirror PDA is
+// delegated to the backup while the action itself goes to the primary.
+//
+// The primary finalizes as usual through tally_and_commit, which then also
+// needs the primary's ed25519 signature over the result's attestation
+// digest. If nothing has been finalized once the liveness timeout has
+// passed, the backup may instead commit through its mirror: commit_mirror
+// tallies the action as its last checkpoint left it on the base layer, takes
+// the backup's signature over that tally and brings the mirror back. Ballots
+// after the last checkpoint are lost to the backup, so keepers should
+// checkpoint such actions as they run.
+//
+// Both commits can land, the primary's after the backup's. Once the action
+// and the mirror are both back on the base layer, resolve_redundant_commit
+// keeps whichever was committed first, moving the backup's into the action if
+// it won, and leaves the other in the mirror. A losing commit that counted
+// more ballots than the winner is evidence against it: challenge_result with
+// Evidence::ConflictingCommit voids the result and slashes the validator
+// whose commit stood.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::ID as INSTRUCTIONS_SYSVAR_ID;
+use ephemeral_rollups_sdk::anchor::delegate;
+use ephemeral_rollups_sdk::consts::{MAGIC_CONTEXT_ID, MAGIC_PROGRAM_ID};
+use ephemeral_rollups_sdk::cpi::DelegateConfig;
+use ephemeral_rollups_sdk::ephem::commit_and_undelegate_accounts;
+
+use crate::dispute::is_settled;
+use crate::equivocation::verified_signatures;
+use crate::liveness::unlock_slot;
+use crate::validators::require_live_validator;
+use crate::{
+    attestation_digest, tally, tally_checked, ActionMirror, DelegationState, FastAction, FastVoteError,
+    MirrorCommitted, MirrorDelegated, PrivacyLevel, RedundancyEnabled, RedundantCommitResolved, ValidatorRecord,
+    VoteResult, ACTION_MIRROR_SEED, FAST_ACTION_SEED, FAST_ACTION_VERSION, VALIDATOR_SEED,
+};
+
+/// Fails unless the ed25519 instructions before this one have `validator`
+/// signing `digest`
+pub(crate) fn require_signed_commit(instructions: &AccountInfo, validator: &Pubkey, digest: &[u8; 32]) -> Result<()> {
+    let verified = verified_signatures(instructions)?;
+    require!(
+        verified.iter().any(|(signer, message)| signer == validator && message[..] == digest[..]),
+        FastVoteError::CommitSignatureMissing
+    );
+    Ok(())
+}
+
+/// Trades the action's tally for the mirror's
+fn swap_commit(action: &mut FastAction, mirror: &mut ActionMirror) {
+    std::mem::swap(&mut action.votes_for, &mut mirror.votes_for);
+    std::mem::swap(&mut action.votes_against, &mut mirror.votes_against);
+    std::mem::swap(&mut action.weight_for, &mut mirror.weight_for);
+    std::mem::swap(&mut action.weight_against, &mut mirror.weight_against);
+    std::mem::swap(&mut action.vote_count, &mut mirror.vote_count);
+    std::mem::swap(&mut action.result, &mut mirror.result);
+    std::mem::swap(&mut action.finalized_slot, &mut mirror.committed_slot);
+}
+
+fn action_digest(action: &Account<FastAction>) -> [u8; 32] {
+    attestation_digest(&action.key(), action.votes_for, action.votes_against, &action.result)
+}
+
+/// Action authority only, on the base layer, before the action is delegated
+/// or takes a ballot; `backup` must be a live allowlisted validator
+pub fn process_enable_redundancy(ctx: Context<EnableRedundancy>, backup: Pubkey) -> Result<()> {
+    let action = &mut ctx.accounts.fast_action;
+    require!(
+        !action.executed
+            && action.vote_count == 0
+            && action.delegation_state == DelegationState::Undelegated
+            && action.privacy == PrivacyLevel::Public
+            && action.option_count == 0
+            && !action.ranked
+            && !action.audited,
+        FastVoteError::RedundancyUnsupported
+    );
+    require_live_validator(Some(&ctx.accounts.backup_record), &backup, Clock::get()?.slot)?;
+    action.backup_validator = backup;
+
+    let mirror = &mut ctx.accounts.action_mirror;
+    mirror.action = action.key();
+    mirror.hive = action.hive;
+    mirror.backup = backup;
+    mirror.delegation_state = DelegationState::Undelegated;
+    mirror.result = VoteResult::Pending;
+    mirror.bump = ctx.bumps.action_mirror;
+
+    emit_event!(ctx, RedundancyEnabled {
+        action: mirror.action,
+        hive: mirror.hive,
+        backup,
+        enabled_by: ctx.accounts.authority.key(),
+    });
+
+    Ok(())
+}
+
+/// Permissionless; delegates the mirror to its backup, which must still be
+/// live
+pub fn process_delegate_mirror(ctx: Context<DelegateMirror>) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let mirror = &mut ctx.accounts.action_mirror;
+    require!(mirror.committed_by == Pubkey::default(), FastVoteError::MirrorAlreadyCommitted);
+    require_live_validator(Some(&ctx.accounts.backup_record), &mirror.backup, slot)?;
+
+    mirror.delegation_state = DelegationState::Delegated;
+    // Written out before the delegation program takes the account over, so
+    // Anchor's own write on exit leaves the bytes it restored unchanged
+    mirror.exit(&crate::ID)?;
+    let (action, hive, backup) = (mirror.action, mirror.hive, mirror.backup);
+
+    emit_event!(ctx, MirrorDelegated { action, hive, backup, slot });
+
+    ctx.accounts.delegate_action_mirror(
+        &ctx.accounts.payer,
+        &[ACTION_MIRROR_SEED, action.as_ref()],
+        DelegateConfig {
+            validator: Some(backup),
+            ..Default::default()
+        },
+    )?;
+
+    Ok(())
+}
+
+/// On the backup's rollup, once the liveness timeout has passed with the
+/// action unfinalized. `fast_action` is read as the base layer holds it, at
+/// its last checkpoint.
+pub fn process_commit_mirror(ctx: Context<CommitMirror>) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let action = FastAction::try_deserialize(&mut &ctx.accounts.fast_action.try_borrow_data()?[..])?;
+    require!(action.version == FAST_ACTION_VERSION, FastVoteError::ActionNeedsMigration);
+    require!(slot > unlock_slot(&action)?, FastVoteError::LivenessTimeoutNotReached);
+
+    let mirror = &mut ctx.accounts.action_mirror;
+    require!(mirror.committed_by == Pubkey::default(), FastVoteError::MirrorAlreadyCommitted);
+    let result = tally_checked(&action, None, slot)?;
+    let digest = attestation_digest(&mirror.action, action.votes_for, action.votes_against, &result);
+    require_signed_commit(&ctx.accounts.instructions, &mirror.backup, &digest)?;
+
+    mirror.committed_by = mirror.backup;
+    mirror.votes_for = action.votes_for;
+    mirror.votes_against = action.votes_against;
+    mirror.weight_for = action.weight_for;
+    mirror.weight_against = action.weight_against;
+    mirror.vote_count = action.vote_count;
+    mirror.result = result.clone();
+    mirror.committed_slot = slot;
+    mirror.digest = digest;
+    mirror.delegation_state = DelegationState::Undelegated;
+    mirror.exit(&crate::ID)?;
+
+    commit_and_undelegate_accounts(
+        &ctx.accounts.payer,
+        vec![&mirror.to_account_info()],
+        &ctx.accounts.magic_context,
+        &ctx.accounts.magic_program,
+    )?;
+
+    emit_event!(ctx, MirrorCommitted {
+        action: mirror.action,
+        hive: mirror.hive,
+        backup: mirror.backup,
+        result,
+        digest,
+        slot,
+    });
+
+    Ok(())
+}
+
+/// Permissionless, on the base layer, once the backup has committed and the
+/// action is back. A backup commit that came first replaces the primary's
+/// only while the latter is in its dispute window, and only once the action
+/// has been released from the primary's count.
+pub fn process_resolve_redundant_commit(ctx: Context<ResolveRedundantCommit>) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let action = &mut ctx.accounts.fast_action;
+    let mirror = &mut ctx.accounts.action_mirror;
+    require!(mirror.committed_by != Pubkey::default(), FastVoteError::MirrorNotCommitted);
+    require!(!mirror.resolved, FastVoteError::CommitAlreadyResolved);
+    mirror.resolved = true;
+
+    let primary = action.delegated_validator;
+    let committed = action.executed && action.result.is_tallied();
+    let digest = action_digest(action);
+    let (winner, loser, conflicting) = if committed && action.finalized_slot <= mirror.committed_slot {
+        (primary, mirror.backup, digest != mirror.digest)
+    } else {
+        // Only a force_undelegate may have ended the action ahead of the
+        // backup's commit
+        require!(
+            !action.executed || committed || action.result == VoteResult::Expired,
+            FastVoteError::ActionAlreadyExecuted
+        );
+        require!(!is_settled(action, slot), FastVoteError::DisputeWindowClosed);
+        require!(!action.validator_counted, FastVoteError::ValidatorStillCounted);
+        let backup = mirror.backup;
+        swap_commit(action, mirror);
+        action.executed = true;
+        action.result_digest = tally::result_digest(
+            action.action_id,
+            &action.action_hash,
+            action.votes_for,
+            action.votes_against,
+            &action.result,
+            action.finalized_slot,
+        );
+        action.delegated_validator = backup;
+        action.log_result(slot);
+
+        // The mirror keeps the primary's commit, if it made one
+        mirror.committed_by = if committed { primary } else { Pubkey::default() };
+        mirror.digest = digest;
+        (backup, mirror.committed_by, committed && digest != action_digest(action))
+    };
+
+    emit_event!(ctx, RedundantCommitResolved {
+        action: action.key(),
+        hive: action.hive,
+        winner,
+        loser,
+        conflicting,
+        slot,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct EnableRedundancy<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        has_one = authority @ FastVoteError::Unauthorized,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = authority,
+        space = ActionMirror::LEN,
+        seeds = [ACTION_MIRROR_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub action_mirror: Account<'info, ActionMirror>,
+    /// Registry entry of the backup validator
+    #[account(seeds = [VALIDATOR_SEED, backup_record.validator.as_ref()], bump = backup_record.bump)]
+    pub backup_record: Account<'info, ValidatorRecord>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[delegate]
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct DelegateMirror<'info> {
+    #[account(
+        mut,
+        seeds = [ACTION_MIRROR_SEED, action_mirror.action.as_ref()],
+        bump = action_mirror.bump,
+        del
+    )]
+    pub action_mirror: Account<'info, ActionMirror>,
+    #[account(seeds = [VALIDATOR_SEED, action_mirror.backup.as_ref()], bump = backup_record.bump)]
+    pub backup_record: Account<'info, ValidatorRecord>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CommitMirror<'info> {
+    #[account(
+        mut,
+        seeds = [ACTION_MIRROR_SEED, action_mirror.action.as_ref()],
+        bump = action_mirror.bump
+    )]
+    pub action_mirror: Account<'info, ActionMirror>,
+    /// CHECK: The mirrored action, owned by the delegation program while
+    /// its primary holds it; read in the handler
+    #[account(address = action_mirror.action)]
+    pub fast_action: UncheckedAccount<'info>,
+    /// CHECK: The instructions sysvar, for the backup's signature
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: MagicBlock context - validated via address constraint
+    #[account(mut, address = MAGIC_CONTEXT_ID @ FastVoteError::InvalidMagicContext)]
+    pub magic_context: AccountInfo<'info>,
+    /// CHECK: MagicBlock program - validated via address constraint
+    #[account(address = MAGIC_PROGRAM_ID @ FastVoteError::InvalidMagicBlockProgram)]
+    pub magic_program: AccountInfo<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ResolveRedundantCommit<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        mut,
+        seeds = [ACTION_MIRROR_SEED, fast_action.key().as_ref()],
+        bump = action_mirror.bump
+    )]
+    pub action_mirror: Account<'info, ActionMirror>,
+}
@@ -0,0 +1,36 @@
+// Why tally_and_commit refused to finalize. A keeper otherwise sees only the
+// error code; with the `rejection-events` feature the program also logs a
+// TallyRejected event with the numbers behind it, so monitoring can spot
+// actions that keep missing quorum. The failed transaction's logs are the
+// event's only record; nothing is written on chain.
+
+use anchor_lang::prelude::*;
+
+use crate::{audit, tally, FastAction, FastVoteError, TallyAudit, TallyRejected, TallyRejection, VoteResult};
+
+/// tally_and_commit's checks, then the result it would record
+pub fn tally_checked(action: &FastAction, tally_audit: Option<&Account<TallyAudit>>, slot: u64) -> Result<VoteResult> {
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(slot > action.deadline_slot, FastVoteError::VotingNotEnded);
+    audit::require_verified(action, tally_audit)?;
+    action.tallied_result(slot)
+}
+
+/// The event for `tally_checked`'s failure with `err`, if it is one of TallyRejection
+pub fn rejected(action: &Account<FastAction>, err: &Error, slot: u64) -> Option<TallyRejected> {
+    Some(TallyRejected {
+        action: action.key(),
+        hive: action.hive,
+        reason: TallyRejection::of(err)?,
+        vote_count: action.vote_count,
+        quorum: tally::required_quorum(
+            action.quorum,
+            action.quorum_floor,
+            action.quorum_grace_slots,
+            action.deadline_slot,
+            slot,
+        ),
+        slot,
+        deadline_slot: action.deadline_slot,
+    })
+}
@@ -0,0 +1,156 @@
+// Relayer pinning. A voter group's threshold key never signs a transaction,
+// so its ballot reaches the chain through whoever submits it; that
+// submitter chooses when the ballot lands, or whether it does. Before the
+// first ballot, an action's authority may pin up to MAX_RELAYERS relayers,
+// and vote_fast_group then takes group ballots from them alone.
+//
+// A pinned relayer could still sit on a ballot. A member of the group then
+// calls request_self_submit, and once the action's fallback_slots have
+// passed without the ballot landing, any member may submit it directly.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    ActionRelayers, ActionRelayersSet, FastAction, FastVoteError, SelfSubmit, SelfSubmitRequested, VoterGroup,
+    ACTION_RELAYERS_SEED, FAST_ACTION_SEED, FAST_ACTION_VERSION, MAX_RELAYERS, SELF_SUBMIT_SEED, VOTER_GROUP_SEED,
+};
+
+/// Action authority only, before the first ballot; replaces any earlier
+/// pinning
+pub fn process_set_action_relayers(
+    ctx: Context<SetActionRelayers>,
+    relayers: Vec<Pubkey>,
+    fallback_slots: u64,
+) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(action.vote_count == 0, FastVoteError::ActionHasVotes);
+    require!(
+        !relayers.is_empty() && relayers.len() <= MAX_RELAYERS && fallback_slots > 0,
+        FastVoteError::InvalidRelayers
+    );
+    for (i, relayer) in relayers.iter().enumerate() {
+        require!(
+            *relayer != Pubkey::default() && !relayers[..i].contains(relayer),
+            FastVoteError::InvalidRelayers
+        );
+    }
+
+    let pinned = &mut ctx.accounts.action_relayers;
+    pinned.action = action.key();
+    pinned.relayers = [Pubkey::default(); MAX_RELAYERS];
+    pinned.relayers[..relayers.len()].copy_from_slice(&relayers);
+    pinned.relayer_count = relayers.len() as u8;
+    pinned.fallback_slots = fallback_slots;
+    pinned.bump = ctx.bumps.action_relayers;
+
+    emit_event!(ctx, ActionRelayersSet {
+        action: pinned.action,
+        relayers,
+        fallback_slots,
+    });
+
+    Ok(())
+}
+
+/// A group member's notice that the group's ballot has not been relayed;
+/// starts the fallback delay
+pub fn process_request_self_submit(ctx: Context<RequestSelfSubmit>) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    let slot = Clock::get()?.slot;
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(slot <= action.deadline_slot, FastVoteError::VotingEnded);
+    require!(
+        ctx.accounts.voter_group.members.contains(ctx.accounts.member.key),
+        FastVoteError::Unauthorized
+    );
+
+    let request = &mut ctx.accounts.self_submit;
+    request.action = action.key();
+    request.voter = ctx.accounts.voter_group.group_key;
+    request.requested_slot = slot;
+    request.bump = ctx.bumps.self_submit;
+
+    emit_event!(ctx, SelfSubmitRequested {
+        action: request.action,
+        voter: request.voter,
+        due_slot: slot.saturating_add(ctx.accounts.action_relayers.fallback_slots),
+    });
+
+    Ok(())
+}
+
+/// Fails unless `submitter` may submit a relayed ballot: any submitter if
+/// `action_relayers` does not exist, else a pinned relayer, or one of
+/// `members` once the voter's SelfSubmit request is due
+pub fn require_submitter(
+    action_relayers: &AccountInfo,
+    self_submit: &AccountInfo,
+    submitter: &Pubkey,
+    members: &[Pubkey],
+    slot: u64,
+) -> Result<()> {
+    let Some(pinned) = ActionRelayers::load(action_relayers)? else {
+        return Ok(());
+    };
+    if pinned.relayers().contains(submitter) {
+        return Ok(());
+    }
+    require!(members.contains(submitter), FastVoteError::RelayerNotAllowed);
+    let request = SelfSubmit::load(self_submit)?.ok_or(FastVoteError::SelfSubmitNotDue)?;
+    require!(
+        slot >= request.requested_slot.saturating_add(pinned.fallback_slots),
+        FastVoteError::SelfSubmitNotDue
+    );
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetActionRelayers<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        has_one = authority @ FastVoteError::Unauthorized,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ActionRelayers::LEN,
+        seeds = [ACTION_RELAYERS_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub action_relayers: Account<'info, ActionRelayers>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RequestSelfSubmit<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    /// The action's pinning; there is nothing to fall back from without it
+    #[account(seeds = [ACTION_RELAYERS_SEED, fast_action.key().as_ref()], bump = action_relayers.bump)]
+    pub action_relayers: Account<'info, ActionRelayers>,
+    #[account(seeds = [VOTER_GROUP_SEED, voter_group.group_key.as_ref()], bump = voter_group.bump)]
+    pub voter_group: Account<'info, VoterGroup>,
+    #[account(
+        init,
+        payer = member,
+        space = SelfSubmit::LEN,
+        seeds = [SELF_SUBMIT_SEED, fast_action.key().as_ref(), voter_group.group_key.as_ref()],
+        bump
+    )]
+    pub self_submit: Account<'info, SelfSubmit>,
+    #[account(mut)]
+    pub member: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,134 @@
+// Portable result proofs. assert_action_passed serves programs on Solana; a
+// contract on another chain, or a service without Solana RPC, instead needs
+// the outcome in a form it can check on its own. export_result_proof
+// writes a settled action's result, its counters, the hash of its account
+// data and validator signatures over its attestation digest into a
+// ResultProof, and both stores the encoding in a buffer account and emits
+// it, so a relayer can take it from either.
+//
+// The signatures are ed25519 program instructions earlier in the same
+// transaction, as for attested tallies: the hive's result attestors, at
+// least its threshold of them, and optionally the validator that hosted the
+// action. Attestors sign the same digest for tally_and_commit, so their
+// signatures from the tally can be carried over. Only settled results are
+// exported, as a proof cannot be withdrawn once it leaves the chain.
+//
+// The buffer is temporary: whoever exported the proof closes it and takes
+// back its rent.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::sysvar::instructions::ID as INSTRUCTIONS_SYSVAR_ID;
+
+use crate::dispute::is_settled;
+use crate::equivocation::verified_signed_messages;
+use crate::{
+    FastAction, FastVoteError, ProofSignature, ResultAttestors, ResultProof, ResultProofBuffer, ResultProofExported,
+    FAST_ACTION_SEED, FAST_ACTION_VERSION, RESULT_ATTESTORS_SEED, RESULT_PROOF_SEED,
+};
+
+/// Permissionless, on the base layer, once the action has settled; `payer`
+/// covers the buffer until it closes it
+pub fn process_export_result_proof(ctx: Context<ExportResultProof>) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(is_settled(action, Clock::get()?.slot), FastVoteError::DisputeWindowOpen);
+
+    let info = &ctx.accounts.result_attestors;
+    let (attestors, threshold) = if info.data_is_empty() {
+        (Vec::new(), 0)
+    } else {
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let attestors = ResultAttestors::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        (attestors.validators, attestors.threshold)
+    };
+
+    let mut proof = ResultProof {
+        program_id: crate::ID,
+        action: action.key(),
+        action_id: action.action_id,
+        action_hash: action.action_hash,
+        hive: action.hive,
+        votes_for: action.votes_for,
+        votes_against: action.votes_against,
+        weight_for: action.weight_for,
+        weight_against: action.weight_against,
+        result: action.result.clone(),
+        finalized_slot: action.finalized_slot,
+        result_digest: action.result_digest,
+        account_hash: hash(&action.to_account_info().try_borrow_data()?).to_bytes(),
+        signatures: Vec::new(),
+    };
+    let digest = proof.attestation_digest();
+    for (validator, signature, message) in verified_signed_messages(&ctx.accounts.instructions)? {
+        let trusted = attestors.contains(&validator)
+            || (validator == action.delegated_validator && validator != Pubkey::default());
+        if trusted && message == digest && proof.signatures.iter().all(|s| s.validator != validator) {
+            proof.signatures.push(ProofSignature { validator, signature });
+        }
+    }
+    let attested = proof.signatures.iter().filter(|s| attestors.contains(&s.validator)).count();
+    require!(attested >= threshold as usize, FastVoteError::AttestationMissing);
+
+    let encoded = proof.encode();
+    let buffer = &mut ctx.accounts.result_proof;
+    buffer.action = proof.action;
+    buffer.payer = ctx.accounts.payer.key();
+    buffer.proof = encoded.clone();
+    buffer.bump = ctx.bumps.result_proof;
+
+    emit_event!(ctx, ResultProofExported {
+        action: proof.action,
+        hive: proof.hive,
+        action_id: proof.action_id,
+        proof: encoded,
+    });
+
+    Ok(())
+}
+
+/// Payer only; returns the buffer's rent
+pub fn process_close_result_proof(_ctx: Context<CloseResultProof>) -> Result<()> {
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ExportResultProof<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    /// CHECK: The action hive's ResultAttestors PDA, which may not exist;
+    /// read in the handler
+    #[account(seeds = [RESULT_ATTESTORS_SEED, fast_action.hive.as_ref()], bump)]
+    pub result_attestors: UncheckedAccount<'info>,
+    /// CHECK: The instructions sysvar, for the validators' signatures
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = ResultProofBuffer::LEN,
+        seeds = [RESULT_PROOF_SEED, fast_action.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub result_proof: Account<'info, ResultProofBuffer>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseResultProof<'info> {
+    #[account(
+        mut,
+        close = payer,
+        seeds = [RESULT_PROOF_SEED, result_proof.action.as_ref(), payer.key().as_ref()],
+        bump = result_proof.bump
+    )]
+    pub result_proof: Account<'info, ResultProofBuffer>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+}
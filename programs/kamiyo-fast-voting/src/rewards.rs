@@ -0,0 +1,203 @@
+// Validator rewards: creation fees accrue in a pool, paid out to validators in
+// proportion to the ER sessions they hosted and committed on time.
+//
+// Creators get part of their fee back when an action draws a high turnout.
+// tally_and_commit works the rebate out from the config's schedule and
+// records it on the action; it runs on the ephemeral rollup, where the pool
+// cannot be debited, so claim_fee_rebate pays it once the action is back on
+// the base layer.
+
+use anchor_lang::prelude::*;
+
+use crate::dispute::is_settled;
+use crate::{
+    CreationFeeUpdated, FastAction, FastVoteError, FeeRebatePaid, GlobalConfig, RewardPool, ValidatorRecord,
+    ValidatorRewardsClaimed, ValidatorSessionRecorded, FAST_ACTION_SEED, FAST_ACTION_VERSION, GLOBAL_CONFIG_SEED,
+    ON_TIME_GRACE_SLOTS, REWARD_POOL_SEED, VALIDATOR_SEED,
+};
+
+/// A session earns when the action was delegated to a pinned validator and
+/// tallied, not expired or cancelled, within the grace period.
+pub fn session_on_time(action: &FastAction) -> bool {
+    action.result.is_tallied()
+        && action.delegated_validator != Pubkey::default()
+        && action.finalized_slot <= action.deadline_slot.saturating_add(ON_TIME_GRACE_SLOTS)
+}
+
+pub fn process_set_creation_fee(ctx: Context<SetCreationFee>, creation_fee: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.reward_pool;
+    pool.creation_fee = creation_fee;
+    pool.bump = ctx.bumps.reward_pool;
+
+    emit_event!(ctx, CreationFeeUpdated {
+        creation_fee,
+        admin: ctx.accounts.admin.key(),
+    });
+
+    Ok(())
+}
+
+/// Permissionless. Credits the hosting validator once the action is back on
+/// the base layer.
+pub fn process_record_session(ctx: Context<RecordSession>) -> Result<()> {
+    let action = &mut ctx.accounts.fast_action;
+    require!(action.executed, FastVoteError::ActionNotFinalized);
+    require!(!action.session_recorded, FastVoteError::SessionAlreadyRecorded);
+    require!(session_on_time(action), FastVoteError::SessionNotRewardable);
+    require!(is_settled(action, Clock::get()?.slot), FastVoteError::DisputeWindowOpen);
+    action.session_recorded = true;
+
+    let record = &mut ctx.accounts.validator_record;
+    record.sessions_hosted = record.sessions_hosted.saturating_add(1);
+    record.unclaimed_sessions = record.unclaimed_sessions.saturating_add(1);
+
+    let pool = &mut ctx.accounts.reward_pool;
+    pool.pending_sessions = pool.pending_sessions.saturating_add(1);
+
+    emit_event!(ctx, ValidatorSessionRecorded {
+        validator: record.validator,
+        action: action.key(),
+        hive: action.hive,
+        action_id: action.action_id,
+        sessions_hosted: record.sessions_hosted,
+    });
+
+    Ok(())
+}
+
+/// Pays the validator's share of the pool's spendable balance:
+/// balance * unclaimed_sessions / pending_sessions.
+pub fn process_claim_validator_rewards(ctx: Context<ClaimValidatorRewards>) -> Result<()> {
+    let record = &mut ctx.accounts.validator_record;
+    let pool = &mut ctx.accounts.reward_pool;
+    require!(record.unclaimed_sessions > 0, FastVoteError::NoRewards);
+
+    let pool_info = pool.to_account_info();
+    let spendable = pool_info
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(RewardPool::LEN));
+    let amount = (spendable as u128)
+        .checked_mul(record.unclaimed_sessions as u128)
+        .ok_or(FastVoteError::VoteOverflow)?
+        / pool.pending_sessions.max(1) as u128;
+    let amount = amount as u64;
+
+    **pool_info.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.validator.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    let sessions = record.unclaimed_sessions;
+    pool.pending_sessions = pool.pending_sessions.saturating_sub(sessions);
+    pool.total_paid = pool.total_paid.saturating_add(amount);
+    record.unclaimed_sessions = 0;
+    record.rewards_claimed = record.rewards_claimed.saturating_add(amount);
+
+    emit_event!(ctx, ValidatorRewardsClaimed {
+        validator: record.validator,
+        sessions,
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Permissionless, on the base layer. Pays out of the pool's spendable
+/// balance, so a drained pool pays what it has and the rest lapses.
+pub fn process_claim_fee_rebate(ctx: Context<ClaimFeeRebate>) -> Result<()> {
+    let action = &mut ctx.accounts.fast_action;
+    require!(action.executed, FastVoteError::ActionNotFinalized);
+    require!(action.fee_rebate > 0, FastVoteError::NoFeeRebate);
+
+    let pool_info = ctx.accounts.reward_pool.to_account_info();
+    let spendable = pool_info
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(RewardPool::LEN));
+    let amount = action.fee_rebate.min(spendable);
+    **pool_info.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.creator.to_account_info().try_borrow_mut_lamports()? += amount;
+    action.fee_rebate = 0;
+
+    emit_event!(ctx, FeeRebatePaid {
+        action: action.key(),
+        hive: action.hive,
+        action_id: action.action_id,
+        creator: action.creator,
+        amount,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetCreationFee<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = RewardPool::LEN,
+        seeds = [REWARD_POOL_SEED],
+        bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ FastVoteError::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RecordSession<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        mut,
+        seeds = [VALIDATOR_SEED, fast_action.delegated_validator.as_ref()],
+        bump = validator_record.bump
+    )]
+    pub validator_record: Account<'info, ValidatorRecord>,
+    #[account(mut, seeds = [REWARD_POOL_SEED], bump = reward_pool.bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ClaimValidatorRewards<'info> {
+    #[account(
+        mut,
+        seeds = [VALIDATOR_SEED, validator.key().as_ref()],
+        bump = validator_record.bump
+    )]
+    pub validator_record: Account<'info, ValidatorRecord>,
+    #[account(mut, seeds = [REWARD_POOL_SEED], bump = reward_pool.bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(mut)]
+    pub validator: Signer<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ClaimFeeRebate<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        has_one = creator,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    /// CHECK: Rebate destination, bound by has_one
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+    #[account(mut, seeds = [REWARD_POOL_SEED], bump = reward_pool.bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+}
@@ -0,0 +1,113 @@
+// Role-based access control, one Roles PDA per hive
+
+use anchor_lang::prelude::*;
+
+use crate::{FastVoteError, Role, RoleGranted, RoleMember, RoleRevoked, Roles, MAX_ROLE_MEMBERS, ROLES_SEED};
+
+/// Check `key` holds `role` in the Roles account of `hive`.
+pub fn require_role(roles: Option<&Account<Roles>>, hive: &Pubkey, key: &Pubkey, role: Role) -> Result<()> {
+    let roles = roles.ok_or(FastVoteError::MissingRoles)?;
+    require_keys_eq!(roles.hive, *hive, FastVoteError::InvalidRoles);
+    require!(roles.has_role(key, role), FastVoteError::MissingRole);
+    Ok(())
+}
+
+pub fn process_initialize_roles(ctx: Context<InitializeRoles>) -> Result<()> {
+    let roles = &mut ctx.accounts.roles;
+    roles.hive = ctx.accounts.hive.key();
+    roles.bump = ctx.bumps.roles;
+    roles.members = vec![RoleMember {
+        member: ctx.accounts.hive.key(),
+        roles: Role::Admin.mask(),
+    }];
+
+    emit_event!(ctx, RoleGranted {
+        hive: roles.hive,
+        member: roles.hive,
+        role: Role::Admin,
+        granted_by: roles.hive,
+    });
+
+    Ok(())
+}
+
+pub fn process_grant_role(ctx: Context<ManageRoles>, member: Pubkey, role: Role) -> Result<()> {
+    let roles = &mut ctx.accounts.roles;
+    let admin = ctx.accounts.admin.key();
+    require!(roles.has_role(&admin, Role::Admin), FastVoteError::MissingRole);
+
+    match roles.members.iter_mut().find(|m| m.member == member) {
+        Some(entry) => entry.roles |= role.mask(),
+        None => {
+            require!(roles.members.len() < MAX_ROLE_MEMBERS, FastVoteError::RolesFull);
+            roles.members.push(RoleMember {
+                member,
+                roles: role.mask(),
+            });
+        }
+    }
+
+    emit_event!(ctx, RoleGranted {
+        hive: roles.hive,
+        member,
+        role,
+        granted_by: admin,
+    });
+
+    Ok(())
+}
+
+pub fn process_revoke_role(ctx: Context<ManageRoles>, member: Pubkey, role: Role) -> Result<()> {
+    let roles = &mut ctx.accounts.roles;
+    let admin = ctx.accounts.admin.key();
+    require!(roles.has_role(&admin, Role::Admin), FastVoteError::MissingRole);
+    require!(roles.has_role(&member, role), FastVoteError::MissingRole);
+
+    if role == Role::Admin {
+        require!(roles.admin_count() > 1, FastVoteError::LastAdmin);
+    }
+
+    if let Some(entry) = roles.members.iter_mut().find(|m| m.member == member) {
+        entry.roles &= !role.mask();
+    }
+    roles.members.retain(|m| m.has_roles());
+
+    emit_event!(ctx, RoleRevoked {
+        hive: roles.hive,
+        member,
+        role,
+        revoked_by: admin,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct InitializeRoles<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = Roles::LEN,
+        seeds = [ROLES_SEED, hive.key().as_ref()],
+        bump
+    )]
+    pub roles: Account<'info, Roles>,
+    /// Hive authority; becomes the first Admin
+    pub hive: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ManageRoles<'info> {
+    #[account(
+        mut,
+        seeds = [ROLES_SEED, roles.hive.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
+    pub admin: Signer<'info>,
+}
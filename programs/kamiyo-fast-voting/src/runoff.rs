@@ -0,0 +1,289 @@
+// Runoffs for multi-option actions. When no option reaches the threshold,
+// the tally fails and anyone may spawn a runoff action between the two
+// leading options. The runoff keeps the parent's option indices, with every
+// other option eliminated, and the two are linked both ways so clients can
+// follow the chain. Tallies run on the ER, where the action counter is not
+// writable, so spawning is its own base-layer step. spawn_and_delegate also
+// delegates the runoff in the same instruction, to the validator that served
+// the parent, so voting can resume without a separate delegate_action.
+
+use anchor_lang::prelude::*;
+#[cfg(not(feature = "no-er"))]
+use ephemeral_rollups_sdk::anchor::delegate;
+#[cfg(not(feature = "no-er"))]
+use ephemeral_rollups_sdk::cpi::DelegateConfig;
+
+use crate::dispute::is_settled;
+#[cfg(not(feature = "no-er"))]
+use crate::{
+    require_live_validator, validators, ActionDelegated, GlobalConfig, LogTag, ValidatorRecord, ValidatorSaturated,
+    GLOBAL_CONFIG_SEED, VALIDATOR_SEED,
+};
+use crate::{
+    tally, ActionCounter, ActionLog, DelegationState, FastAction, FastVoteError, RunoffSpawned, VoteMode,
+    VoteResult, ACTION_COUNTER_SEED, AGENT_CLASSES, FAST_ACTION_SEED, FAST_ACTION_VERSION, MAX_DEPENDENCIES,
+    MAX_OPTIONS, NO_ELIMINATION, VOTING_WINDOW_SLOTS,
+};
+
+/// Opens `runoff`, at `runoff_key`, between the leading options of the
+/// failed `parent` and links the two
+fn spawn(
+    parent: &mut FastAction,
+    parent_key: Pubkey,
+    counter: &mut ActionCounter,
+    runoff: &mut FastAction,
+    runoff_key: Pubkey,
+    bump: u8,
+) -> Result<RunoffSpawned> {
+    let clock = Clock::get()?;
+    require!(
+        parent.option_count > 0 && !parent.ranked && parent.result == VoteResult::Failed,
+        FastVoteError::RunoffNotRequired
+    );
+    require!(parent.runoff_action == Pubkey::default(), FastVoteError::RunoffAlreadySpawned);
+    require!(is_settled(parent, clock.slot), FastVoteError::DisputeWindowOpen);
+
+    let options = &parent.option_weights[..parent.option_count as usize];
+    let (first, second) = tally::top_two(options).ok_or(FastVoteError::RunoffNotRequired)?;
+
+    let action_id = counter.next_id;
+    counter.next_id = action_id.checked_add(1).ok_or(FastVoteError::ActionIdOverflow)?;
+
+    let deadline_slot = clock
+        .slot
+        .checked_add(VOTING_WINDOW_SLOTS)
+        .ok_or(FastVoteError::SlotOverflow)?;
+
+    runoff.version = FAST_ACTION_VERSION;
+    runoff.action_id = action_id;
+    runoff.action_hash = parent.action_hash;
+    runoff.description_hash = parent.description_hash;
+    runoff.creator = parent.creator;
+    runoff.hive = parent.hive;
+    runoff.threshold = parent.threshold;
+    runoff.threshold_bps = parent.threshold_bps;
+    runoff.approval_rounding = parent.approval_rounding;
+    runoff.tier_thresholds = parent.tier_thresholds;
+    runoff.dependency_count = 0;
+    runoff.dependencies = [Pubkey::default(); MAX_DEPENDENCIES];
+    runoff.votes_for = 0;
+    runoff.votes_against = 0;
+    runoff.vote_count = 0;
+    runoff.vote_mode = parent.vote_mode;
+    runoff.weight_for = 0;
+    runoff.weight_against = 0;
+    runoff.created_slot = clock.slot;
+    runoff.deadline_slot = deadline_slot;
+    runoff.executed = false;
+    runoff.result = VoteResult::Pending;
+    runoff.bump = bump;
+    runoff.last_checkpoint_slot = clock.slot;
+    runoff.last_checkpoint_votes = 0;
+    runoff.delegated_validator = Pubkey::default();
+    runoff.session_recorded = false;
+    runoff.electorate = parent.electorate;
+    runoff.bond = 0;
+    runoff.quorum = parent.quorum;
+    runoff.quorum_floor = parent.quorum_floor;
+    runoff.quorum_grace_slots = parent.quorum_grace_slots;
+    runoff.min_turnout_bps = parent.min_turnout_bps;
+    runoff.option_count = parent.option_count;
+    runoff.option_weights = [0; MAX_OPTIONS];
+    runoff.ranked = false;
+    runoff.eliminated_mask = !((1u8 << first) | (1u8 << second));
+    runoff.pending_elimination = NO_ELIMINATION;
+    runoff.round = 0;
+    runoff.exhausted_ballots = 0;
+    runoff.parent_action = parent_key;
+    runoff.runoff_action = Pubkey::default();
+    runoff.market_feed = Pubkey::default();
+    runoff.market_program = Pubkey::default();
+    runoff.market_price_offset = 0;
+    runoff.market_min_price = 0;
+    runoff.market_cleared = false;
+    runoff.authority = parent.authority;
+    runoff.draft = Pubkey::default();
+    runoff.kind = parent.kind;
+    runoff.max_weight_per_voter = parent.max_weight_per_voter;
+    runoff.class_max_votes = parent.class_max_votes;
+    runoff.class_weight_bps = parent.class_weight_bps;
+    runoff.class_votes = [0; AGENT_CLASSES];
+    runoff.delegation_epoch = 0;
+    runoff.delegation_state = DelegationState::Undelegated;
+    runoff.audited = false;
+    runoff.hive_seeded = parent.hive_seeded;
+    runoff.early_vote_curve = parent.early_vote_curve;
+    runoff.log = ActionLog::default();
+    runoff.excluded_count = parent.excluded_count;
+    runoff.excluded = parent.excluded;
+    runoff.extension_policy = parent.extension_policy;
+    runoff.extensions = 0;
+    runoff.extended_slots = 0;
+    runoff.identity_weighted = parent.identity_weighted;
+    runoff.member_collection = parent.member_collection;
+    runoff.stake_lock_program = parent.stake_lock_program;
+    runoff.priority = parent.priority;
+    runoff.lane_counted = false;
+    runoff.max_votes = parent.max_votes;
+    runoff.tags = parent.tags;
+    runoff.tags_indexed = false;
+    runoff.quorum_weight_bps = parent.quorum_weight_bps;
+    runoff.snapshot_weight = 0;
+    runoff.creation_fee = 0;
+    runoff.fee_rebate = 0;
+    runoff.privacy = parent.privacy;
+    runoff.validator_counted = false;
+    runoff.backup_validator = Pubkey::default();
+
+    parent.runoff_action = runoff_key;
+
+    Ok(RunoffSpawned {
+        parent: parent_key,
+        runoff: runoff_key,
+        hive: parent.hive,
+        action_id,
+        options: [first, second],
+        deadline_slot,
+    })
+}
+
+/// Permissionless once the failed parent has settled; `payer` covers rent.
+pub fn process_spawn_runoff(ctx: Context<SpawnRunoff>) -> Result<()> {
+    let parent_key = ctx.accounts.parent_action.key();
+    let runoff_key = ctx.accounts.runoff_action.key();
+    let spawned = spawn(
+        &mut ctx.accounts.parent_action,
+        parent_key,
+        &mut ctx.accounts.action_counter,
+        &mut ctx.accounts.runoff_action,
+        runoff_key,
+        ctx.bumps.runoff_action,
+    )?;
+    emit_event!(ctx, spawned);
+
+    Ok(())
+}
+
+/// spawn_runoff, then delegates the runoff to the validator that served the
+/// parent, which must still be live; unpinned if the parent was.
+#[cfg(not(feature = "no-er"))]
+pub fn process_spawn_and_delegate(ctx: Context<SpawnAndDelegate>) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let validator = Some(ctx.accounts.parent_action.delegated_validator).filter(|v| *v != Pubkey::default());
+    if let Some(validator) = &validator {
+        require_live_validator(ctx.accounts.validator_record.as_ref(), validator, slot)?;
+    }
+
+    let parent_key = ctx.accounts.parent_action.key();
+    let runoff_key = ctx.accounts.runoff_action.key();
+    let spawned = spawn(
+        &mut ctx.accounts.parent_action,
+        parent_key,
+        &mut ctx.accounts.action_counter,
+        &mut ctx.accounts.runoff_action,
+        runoff_key,
+        ctx.bumps.runoff_action,
+    )?;
+
+    let runoff = &mut ctx.accounts.runoff_action;
+    let record = validator.and(ctx.accounts.validator_record.as_deref_mut());
+    let saturated = validators::count_action(runoff, record, &ctx.accounts.config)?;
+    runoff.delegated_validator = validator.unwrap_or_default();
+    runoff.delegation_epoch = runoff.delegation_epoch.wrapping_add(1);
+    runoff.delegation_state = DelegationState::Delegated;
+    let epoch = runoff.delegation_epoch as u64;
+    runoff.log.push(slot, LogTag::Delegated, epoch);
+    // Written out before the delegation program takes the account over, so
+    // Anchor's own write on exit leaves the bytes it restored unchanged
+    runoff.exit(&crate::ID)?;
+    let hive_seed = runoff.hive_seed().to_vec();
+    let hive = runoff.hive;
+    let action_id = runoff.action_id;
+
+    emit_event!(ctx, spawned);
+    emit_event!(ctx, ActionDelegated {
+        action: runoff_key,
+        hive,
+        validator: validator.unwrap_or_default(),
+        slot,
+    });
+    if let Some(record) = ctx.accounts.validator_record.as_ref().filter(|_| saturated) {
+        emit_event!(ctx, ValidatorSaturated {
+            validator: record.validator,
+            action: runoff_key,
+            active_actions: record.active_actions,
+            slot,
+        });
+    }
+
+    ctx.accounts.delegate_runoff_action(
+        &ctx.accounts.payer,
+        &[FAST_ACTION_SEED, &hive_seed, &action_id.to_le_bytes()],
+        DelegateConfig {
+            validator,
+            ..Default::default()
+        },
+    )?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SpawnRunoff<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, parent_action.hive_seed(), &parent_action.action_id.to_le_bytes()],
+        bump = parent_action.bump,
+        constraint = parent_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration,
+        constraint = parent_action.vote_mode != VoteMode::Optimistic @ FastVoteError::RunoffNotRequired
+    )]
+    pub parent_action: Account<'info, FastAction>,
+    #[account(mut, seeds = [ACTION_COUNTER_SEED], bump = action_counter.bump)]
+    pub action_counter: Account<'info, ActionCounter>,
+    #[account(
+        init,
+        payer = payer,
+        space = FastAction::LEN,
+        seeds = [FAST_ACTION_SEED, parent_action.hive_seed(), &action_counter.next_id.to_le_bytes()],
+        bump
+    )]
+    pub runoff_action: Account<'info, FastAction>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(not(feature = "no-er"))]
+#[delegate]
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SpawnAndDelegate<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, parent_action.hive_seed(), &parent_action.action_id.to_le_bytes()],
+        bump = parent_action.bump,
+        constraint = parent_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration,
+        constraint = parent_action.vote_mode != VoteMode::Optimistic @ FastVoteError::RunoffNotRequired
+    )]
+    pub parent_action: Account<'info, FastAction>,
+    #[account(mut, seeds = [ACTION_COUNTER_SEED], bump = action_counter.bump)]
+    pub action_counter: Account<'info, ActionCounter>,
+    #[account(
+        init,
+        payer = payer,
+        space = FastAction::LEN,
+        seeds = [FAST_ACTION_SEED, parent_action.hive_seed(), &action_counter.next_id.to_le_bytes()],
+        bump,
+        del
+    )]
+    pub runoff_action: Account<'info, FastAction>,
+    /// Registry entry of the parent's validator; required if it had one
+    #[account(mut, seeds = [VALIDATOR_SEED, validator_record.validator.as_ref()], bump = validator_record.bump)]
+    pub validator_record: Option<Account<'info, ValidatorRecord>>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,129 @@
+// Sealed descriptions, for proposals that must stay private until they pass
+// (a trading strategy change, say). The creator encrypts the description to
+// a TEE validator's key before the first vote and records the ciphertext's
+// hash and that key; the ciphertext itself travels off-chain. Once the
+// action has passed and settled, the TEE posts the plaintext, which must
+// hash to the action's `description_hash`, and it is published in the
+// DescriptionRevealed event. Actions that fail never reveal.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+
+use crate::dispute::is_settled;
+use crate::{
+    DescriptionRevealed, DescriptionSealed, FastAction, FastVoteError, SealedDescription, ValidatorRecord,
+    ValidatorStatus, VoteResult, FAST_ACTION_SEED, FAST_ACTION_VERSION, SEALED_DESCRIPTION_SEED, VALIDATOR_SEED,
+};
+
+/// Action authority only, before the first vote
+pub fn process_seal_description(
+    ctx: Context<SealDescription>,
+    ciphertext_hash: [u8; 32],
+    encryption_key: [u8; 32],
+) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(action.vote_count == 0, FastVoteError::ActionHasVotes);
+    require!(
+        action.description_hash != [0u8; 32] && ciphertext_hash != [0u8; 32] && encryption_key != [0u8; 32],
+        FastVoteError::InvalidSealedDescription
+    );
+
+    let sealed = &mut ctx.accounts.sealed_description;
+    sealed.action = action.key();
+    sealed.ciphertext_hash = ciphertext_hash;
+    sealed.encryption_key = encryption_key;
+    sealed.revealed_slot = 0;
+    sealed.bump = ctx.bumps.sealed_description;
+
+    emit_event!(ctx, DescriptionSealed {
+        action: sealed.action,
+        hive: action.hive,
+        ciphertext_hash,
+        encryption_key,
+    });
+
+    Ok(())
+}
+
+/// An allowlisted validator only, and the one the action was pinned to if it
+/// was, once the action has passed and settled
+pub fn process_reveal_description(ctx: Context<RevealDescription>, description: String) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(
+        matches!(action.result, VoteResult::Passed { .. } | VoteResult::Chosen { .. }),
+        FastVoteError::ActionNotExecutable
+    );
+    let slot = Clock::get()?.slot;
+    require!(is_settled(action, slot), FastVoteError::DisputeWindowOpen);
+
+    let validator = ctx.accounts.validator.key();
+    require!(
+        ctx.accounts.validator_record.status == ValidatorStatus::Allowed,
+        FastVoteError::ValidatorNotAllowed
+    );
+    require!(
+        action.delegated_validator == Pubkey::default() || action.delegated_validator == validator,
+        FastVoteError::Unauthorized
+    );
+    require!(
+        hash(description.as_bytes()).to_bytes() == action.description_hash,
+        FastVoteError::DescriptionMismatch
+    );
+
+    let sealed = &mut ctx.accounts.sealed_description;
+    require!(sealed.revealed_slot == 0, FastVoteError::DescriptionAlreadyRevealed);
+    sealed.revealed_slot = slot;
+
+    emit_event!(ctx, DescriptionRevealed {
+        action: sealed.action,
+        hive: action.hive,
+        validator,
+        description,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SealDescription<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        has_one = authority @ FastVoteError::Unauthorized,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = authority,
+        space = SealedDescription::LEN,
+        seeds = [SEALED_DESCRIPTION_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub sealed_description: Account<'info, SealedDescription>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RevealDescription<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        mut,
+        seeds = [SEALED_DESCRIPTION_SEED, fast_action.key().as_ref()],
+        bump = sealed_description.bump
+    )]
+    pub sealed_description: Account<'info, SealedDescription>,
+    #[account(seeds = [VALIDATOR_SEED, validator.key().as_ref()], bump = validator_record.bump)]
+    pub validator_record: Account<'info, ValidatorRecord>,
+    pub validator: Signer<'info>,
+}
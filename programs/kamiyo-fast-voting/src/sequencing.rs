@@ -0,0 +1,216 @@
+// Sequencing commitments against censorship inside the rollup. Ballots reach
+// an action through the validator hosting it, which could drop an unwelcome
+// one and no fraud proof over the committed counters would show it. A
+// validator an action is pinned to therefore commits to the order it
+// received the action's ballots in: every so often it posts the latest batch
+// of them to the base layer, each batch extending one hash chain from the
+// previous batch's head, with received slots that never go back.
+//
+// The chain lists the ballots the validator accepted for execution, not
+// everything that reached it; a ballot it sequenced has to land. During the
+// dispute window anyone may show, from one posted batch, a ballot received
+// by the deadline whose FastVote does not exist: the result is voided and
+// the validator slashed as for any other fraud proof. Actions tallied from
+// compressed or ranked ballots keep no FastVote per ballot and cannot be
+// challenged this way, nor can unpinned actions, whose host is not known in
+// advance.
+
+use anchor_lang::prelude::*;
+
+use crate::dispute::{require_disputable, void_result};
+use crate::{
+    sequence_head, Evidence, FastAction, FastVoteError, GlobalConfig, ResultChallenged, SequenceBatch,
+    SequenceCommitted, SequencedBallot, Treasury, ValidatorRecord, ValidatorStatus, COMPRESSED_BALLOTS_SEED,
+    FAST_ACTION_SEED, FAST_ACTION_VERSION, FAST_VOTE_SEED, GLOBAL_CONFIG_SEED, MAX_SEQUENCE_BATCH,
+    SEQUENCE_BATCH_SEED, TREASURY_SEED, VALIDATOR_SEED,
+};
+
+/// Allowed validators only, on the base layer; batch `index` of `action`
+/// follows batch `index - 1`
+pub fn process_post_sequence_commitment(
+    ctx: Context<PostSequenceCommitment>,
+    action: Pubkey,
+    index: u32,
+    ballots: Vec<SequencedBallot>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.validator_record.status == ValidatorStatus::Allowed,
+        FastVoteError::ValidatorNotAllowed
+    );
+    require!(
+        !ballots.is_empty() && ballots.len() <= MAX_SEQUENCE_BATCH,
+        FastVoteError::InvalidSequenceBatch
+    );
+    let (prev_head, prev_through) = match (index, &ctx.accounts.previous) {
+        (0, _) => ([0; 32], 0),
+        (_, Some(previous)) => (previous.head, previous.through_slot),
+        (_, None) => return err!(FastVoteError::InvalidSequenceBatch),
+    };
+    let slot = Clock::get()?.slot;
+    let mut received = prev_through;
+    for ballot in &ballots {
+        require!(
+            ballot.received_slot >= received && ballot.received_slot <= slot,
+            FastVoteError::InvalidSequenceBatch
+        );
+        received = ballot.received_slot;
+    }
+
+    let batch = &mut ctx.accounts.sequence_batch;
+    batch.action = action;
+    batch.validator = ctx.accounts.validator.key();
+    batch.index = index;
+    batch.prev_head = prev_head;
+    batch.head = sequence_head(prev_head, &action, &ballots);
+    batch.count = ballots.len() as u8;
+    batch.through_slot = received;
+    batch.posted_slot = slot;
+    batch.bump = ctx.bumps.sequence_batch;
+
+    emit_event!(ctx, SequenceCommitted {
+        action,
+        validator: batch.validator,
+        index,
+        head: batch.head,
+        count: batch.count,
+        through_slot: batch.through_slot,
+    });
+
+    Ok(())
+}
+
+/// Permissionless during the dispute window. `ballots` are the whole of
+/// the batch, as posted; the one at `position` is the excluded ballot.
+pub fn process_challenge_exclusion(
+    ctx: Context<ChallengeExclusion>,
+    _index: u32,
+    ballots: Vec<SequencedBallot>,
+    position: u8,
+) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require_disputable(action, Clock::get()?.slot)?;
+    require!(
+        action.delegated_validator != Pubkey::default() && !action.ranked,
+        FastVoteError::InvalidEvidence
+    );
+    require!(ctx.accounts.compressed_ballots.data_is_empty(), FastVoteError::InvalidEvidence);
+
+    let batch = &ctx.accounts.sequence_batch;
+    require!(
+        ballots.len() == batch.count as usize && sequence_head(batch.prev_head, &action.key(), &ballots) == batch.head,
+        FastVoteError::InvalidEvidence
+    );
+    let ballot = ballots.get(position as usize).ok_or(FastVoteError::InvalidEvidence)?;
+    let (fast_vote, _) = Pubkey::find_program_address(
+        &[FAST_VOTE_SEED, action.key().as_ref(), ballot.voter.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(ctx.accounts.fast_vote.key(), fast_vote, FastVoteError::InvalidEvidence);
+    require!(
+        ballot.received_slot <= action.deadline_slot && ctx.accounts.fast_vote.data_is_empty(),
+        FastVoteError::ResultNotFraudulent
+    );
+
+    let accounts = &mut *ctx.accounts;
+    let (voided, slashed, bounty) = void_result(
+        &mut accounts.fast_action,
+        Some(&mut accounts.validator_record),
+        &mut accounts.treasury,
+        ctx.bumps.treasury,
+        &accounts.config,
+        &accounts.challenger,
+    )?;
+
+    let action = &ctx.accounts.fast_action;
+    emit_event!(ctx, ResultChallenged {
+        action: action.key(),
+        hive: action.hive,
+        action_id: action.action_id,
+        evidence: Evidence::Excluded,
+        voided_result: voided,
+        challenger: ctx.accounts.challenger.key(),
+        validator: action.delegated_validator,
+        slashed,
+        bounty,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(action: Pubkey, index: u32)]
+pub struct PostSequenceCommitment<'info> {
+    #[account(
+        init,
+        payer = validator,
+        space = SequenceBatch::LEN,
+        seeds = [SEQUENCE_BATCH_SEED, action.as_ref(), validator.key().as_ref(), &index.to_le_bytes()],
+        bump
+    )]
+    pub sequence_batch: Account<'info, SequenceBatch>,
+    /// The batch this one follows; required unless `index` is 0
+    #[account(
+        seeds = [
+            SEQUENCE_BATCH_SEED,
+            action.as_ref(),
+            validator.key().as_ref(),
+            &index.saturating_sub(1).to_le_bytes()
+        ],
+        bump = previous.bump
+    )]
+    pub previous: Option<Account<'info, SequenceBatch>>,
+    #[account(seeds = [VALIDATOR_SEED, validator.key().as_ref()], bump = validator_record.bump)]
+    pub validator_record: Account<'info, ValidatorRecord>,
+    #[account(mut)]
+    pub validator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(index: u32)]
+pub struct ChallengeExclusion<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        seeds = [
+            SEQUENCE_BATCH_SEED,
+            fast_action.key().as_ref(),
+            fast_action.delegated_validator.as_ref(),
+            &index.to_le_bytes()
+        ],
+        bump = sequence_batch.bump
+    )]
+    pub sequence_batch: Account<'info, SequenceBatch>,
+    /// CHECK: The excluded ballot's FastVote PDA, which must not exist;
+    /// checked in the handler
+    pub fast_vote: UncheckedAccount<'info>,
+    /// CHECK: The action's CompressedBallots PDA, which must not exist
+    #[account(seeds = [COMPRESSED_BALLOTS_SEED, fast_action.key().as_ref()], bump)]
+    pub compressed_ballots: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [VALIDATOR_SEED, fast_action.delegated_validator.as_ref()],
+        bump = validator_record.bump
+    )]
+    pub validator_record: Account<'info, ValidatorRecord>,
+    #[account(
+        init_if_needed,
+        payer = challenger,
+        space = Treasury::LEN,
+        seeds = [TREASURY_SEED],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
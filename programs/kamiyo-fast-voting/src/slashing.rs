@@ -0,0 +1,198 @@
+// Validator accountability: anyone may report a hosted session that was
+// force-undelegated or finalized far past its deadline. Governance may void
+// the report during the dispute window; otherwise part of the validator's
+// bond moves to the treasury.
+//
+// Every slash, here, in a fraud proof or for equivocation, pays
+// GlobalConfig::whistleblower_bps of the amount to whoever reported it, so
+// watching for misbehaviour pays for itself.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    FastAction, FastVoteError, GlobalConfig, MissedCommitReported, SlashReport, SlashStatus, SlashVoided, Treasury,
+    ValidatorRecord, ValidatorSlashed, VoteResult, FAST_ACTION_SEED, FAST_ACTION_VERSION, GLOBAL_CONFIG_SEED,
+    LATE_COMMIT_SLOTS, SLASH_BPS, SLASH_DISPUTE_WINDOW_SLOTS, SLASH_REPORT_SEED, TREASURY_SEED, VALIDATOR_SEED,
+};
+
+/// Moves `amount` out of `from`, paying `whistleblower_bps` of it to
+/// `reporter` and the rest to the treasury; returns the reporter's share
+pub(crate) fn pay_slash(
+    from: &AccountInfo,
+    reporter: &AccountInfo,
+    treasury: &mut Account<Treasury>,
+    amount: u64,
+    whistleblower_bps: u16,
+) -> Result<u64> {
+    let bounty = (amount as u128 * whistleblower_bps as u128 / 10_000) as u64;
+    **from.try_borrow_mut_lamports()? -= amount;
+    **reporter.try_borrow_mut_lamports()? += bounty;
+    **treasury.to_account_info().try_borrow_mut_lamports()? += amount - bounty;
+    treasury.total_slashed = treasury.total_slashed.saturating_add(amount - bounty);
+    Ok(bounty)
+}
+
+/// The hosting validator missed its commit if the action expired or was
+/// finalized more than LATE_COMMIT_SLOTS past its deadline.
+pub fn commit_missed(action: &FastAction) -> bool {
+    action.executed
+        && action.delegated_validator != Pubkey::default()
+        && (action.result == VoteResult::Expired
+            || action.finalized_slot > action.deadline_slot.saturating_add(LATE_COMMIT_SLOTS))
+}
+
+/// Permissionless. The reporter pays for the report account.
+pub fn process_report_missed_commit(ctx: Context<ReportMissedCommit>) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(commit_missed(action), FastVoteError::CommitNotMissed);
+
+    let slot = Clock::get()?.slot;
+    let report = &mut ctx.accounts.slash_report;
+    report.action = action.key();
+    report.validator = action.delegated_validator;
+    report.reporter = ctx.accounts.reporter.key();
+    report.reported_slot = slot;
+    report.status = SlashStatus::Pending;
+    report.amount = 0;
+    report.bump = ctx.bumps.slash_report;
+
+    emit_event!(ctx, MissedCommitReported {
+        action: report.action,
+        hive: action.hive,
+        action_id: action.action_id,
+        validator: report.validator,
+        reporter: report.reporter,
+        slot,
+    });
+
+    Ok(())
+}
+
+pub fn process_void_slash(ctx: Context<VoidSlash>) -> Result<()> {
+    let report = &mut ctx.accounts.slash_report;
+    require!(report.status == SlashStatus::Pending, FastVoteError::SlashNotPending);
+    let closes = report
+        .reported_slot
+        .checked_add(SLASH_DISPUTE_WINDOW_SLOTS)
+        .ok_or(FastVoteError::SlotOverflow)?;
+    require!(Clock::get()?.slot <= closes, FastVoteError::DisputeWindowClosed);
+
+    report.status = SlashStatus::Voided;
+
+    emit_event!(ctx, SlashVoided {
+        action: report.action,
+        validator: report.validator,
+        admin: ctx.accounts.admin.key(),
+    });
+
+    Ok(())
+}
+
+/// Permissionless once the dispute window has passed.
+pub fn process_execute_slash(ctx: Context<ExecuteSlash>) -> Result<()> {
+    let report = &mut ctx.accounts.slash_report;
+    require!(report.status == SlashStatus::Pending, FastVoteError::SlashNotPending);
+    let closes = report
+        .reported_slot
+        .checked_add(SLASH_DISPUTE_WINDOW_SLOTS)
+        .ok_or(FastVoteError::SlotOverflow)?;
+    require!(Clock::get()?.slot > closes, FastVoteError::DisputeWindowOpen);
+
+    let record = &mut ctx.accounts.validator_record;
+    let amount = (record.bond as u128 * SLASH_BPS as u128 / 10_000) as u64;
+    ctx.accounts.treasury.bump = ctx.bumps.treasury;
+    let bounty = pay_slash(
+        &record.to_account_info(),
+        &ctx.accounts.reporter,
+        &mut ctx.accounts.treasury,
+        amount,
+        ctx.accounts.config.whistleblower_bps,
+    )?;
+    record.bond -= amount;
+
+    report.status = SlashStatus::Executed;
+    report.amount = amount;
+
+    emit_event!(ctx, ValidatorSlashed {
+        action: report.action,
+        validator: report.validator,
+        amount,
+        bounty,
+        remaining_bond: record.bond,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ReportMissedCommit<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = reporter,
+        space = SlashReport::LEN,
+        seeds = [SLASH_REPORT_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub slash_report: Account<'info, SlashReport>,
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct VoidSlash<'info> {
+    #[account(
+        mut,
+        seeds = [SLASH_REPORT_SEED, slash_report.action.as_ref()],
+        bump = slash_report.bump
+    )]
+    pub slash_report: Account<'info, SlashReport>,
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ FastVoteError::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ExecuteSlash<'info> {
+    #[account(
+        mut,
+        seeds = [SLASH_REPORT_SEED, slash_report.action.as_ref()],
+        bump = slash_report.bump
+    )]
+    pub slash_report: Account<'info, SlashReport>,
+    #[account(
+        mut,
+        seeds = [VALIDATOR_SEED, slash_report.validator.as_ref()],
+        bump = validator_record.bump
+    )]
+    pub validator_record: Account<'info, ValidatorRecord>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = Treasury::LEN,
+        seeds = [TREASURY_SEED],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// CHECK: Receives the whistleblower share; the report's reporter
+    #[account(mut, address = slash_report.reporter)]
+    pub reporter: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
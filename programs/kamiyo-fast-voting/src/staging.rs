@@ -0,0 +1,80 @@
+// Staged execution. Callback accounts are pinned per callback, but the
+// transaction carrying execute_action is still public in the mempool, so a
+// watcher could race it with its own. An executor therefore first stages
+// the call: it commits to the exact remaining accounts it will pass, and
+// execute_action only runs, for that signer, in a later slot and with
+// those accounts. A copy of the transaction lacks the executor's signature,
+// and a rival executor has to stage its own call a slot behind.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::AccountMeta;
+
+use crate::{
+    accounts_hash, CallbackRegistry, ExecutionStage, ExecutionStaged, FastAction, FastVoteError,
+    CALLBACK_REGISTRY_SEED, EXECUTION_STAGE_SEED, FAST_ACTION_SEED, FAST_ACTION_VERSION,
+};
+
+/// Any executor, until the callbacks are dispatched. Staging again
+/// replaces the commitment and restarts the one-slot wait.
+pub fn process_stage_execution(ctx: Context<StageExecution>, commitment: [u8; 32]) -> Result<()> {
+    require!(!ctx.accounts.registry.dispatched, FastVoteError::CallbacksDispatched);
+    let slot = Clock::get()?.slot;
+    let stage = &mut ctx.accounts.stage;
+    stage.action = ctx.accounts.fast_action.key();
+    stage.executor = ctx.accounts.executor.key();
+    stage.commitment = commitment;
+    stage.staged_slot = slot;
+    stage.bump = ctx.bumps.stage;
+
+    emit_event!(ctx, ExecutionStaged {
+        action: stage.action,
+        hive: ctx.accounts.fast_action.hive,
+        executor: stage.executor,
+        commitment,
+        slot,
+    });
+
+    Ok(())
+}
+
+/// Called by execute_action before dispatch: `remaining` must be exactly
+/// what the stage committed to, from an earlier slot
+pub(crate) fn check_stage(stage: &ExecutionStage, remaining: &[AccountInfo], slot: u64) -> Result<()> {
+    let metas: Vec<AccountMeta> = remaining
+        .iter()
+        .map(|info| AccountMeta {
+            pubkey: info.key(),
+            is_signer: false,
+            is_writable: info.is_writable,
+        })
+        .collect();
+    require!(
+        stage.staged_slot < slot && accounts_hash(&metas) == stage.commitment,
+        FastVoteError::InvalidExecutionStage
+    );
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct StageExecution<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(seeds = [CALLBACK_REGISTRY_SEED, fast_action.key().as_ref()], bump = registry.bump)]
+    pub registry: Account<'info, CallbackRegistry>,
+    #[account(
+        init_if_needed,
+        payer = executor,
+        space = ExecutionStage::LEN,
+        seeds = [EXECUTION_STAGE_SEED, fast_action.key().as_ref(), executor.key().as_ref()],
+        bump
+    )]
+    pub stage: Account<'info, ExecutionStage>,
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
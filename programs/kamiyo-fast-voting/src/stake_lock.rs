@@ -0,0 +1,372 @@
+// Stake locks. A token-weighted ballot reads a live balance, so a voter
+// could vote, move the tokens to a second wallet and vote again with them.
+// Weight snapshots close that gap by fixing weights up front; a stake lock
+// does it at the ballot instead. Every ballot counting a live balance takes
+// one: vote_fast_weighted and token-weighted vote_options lock through the
+// config's stake lock program, and an action created with `lock_stake`
+// takes its ballots through vote_fast_locked, which locks through the
+// program the action pinned at creation. The lock program locks the voter's
+// token account, usually by freezing it as the weight mint's freeze
+// authority, and release_stake_lock asks it to release the account once
+// the action is final. The token program itself may serve as the lock
+// program, with the lock authority as the mint's freeze authority: the
+// lock is then a plain FreezeAccount, the release a ThawAccount.
+//
+// The lock is counted per voter across actions: only the first ballot while
+// none is held calls lock_stake, only the last release calls release_stake,
+// and until then every ballot must use the same token account and program.
+// A frozen account still counts as weight, since it cannot move. Each ballot
+// holds one StakeLockClaim, closed by its release, so a share is only ever
+// given back once; FastVote accounts are never closed, so finalization is
+// the only release. An action closed by the expiry sweep counts as final,
+// as it can take no more ballots.
+//
+// Scope. The lock program's accounts live on the base layer, so live
+// balances are voted undelegated; an action voted in the ER takes its
+// weights from a snapshot. Delegated weight is not claimable against a
+// live balance: a delegator's tokens are not locked, so a delegate voting
+// with them would reopen the gap. A snapshot folds it into the delegate's
+// leaf instead. The voter's own ballot still counts only the share it
+// retained.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+
+use crate::{
+    agent_registry, freeze, metrics, record_vote, token_weight, vote_credits, vote_delegation, Counter, FastAction,
+    FastVote, FastVoteError, GlobalConfig, StakeLock, StakeLockClaim, StakeLocked, StakeReleased, VoteCredits, VoteMode,
+    AGENT_SEED, FAST_ACTION_SEED, FAST_ACTION_VERSION, FAST_VOTE_SEED, GLOBAL_CONFIG_SEED, HIVE_FREEZE_SEED,
+    LOCK_STAKE_DISCRIMINATOR, RELEASE_STAKE_DISCRIMINATOR, STAKE_LOCK_AUTHORITY_SEED, STAKE_LOCK_CLAIM_SEED,
+    STAKE_LOCK_SEED, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, VOTE_CREDITS_SEED, VOTE_DELEGATION_SEED,
+};
+
+// spl-token FreezeAccount and ThawAccount, for a token program as the lock
+// program
+const FREEZE_ACCOUNT: u8 = 10;
+const THAW_ACCOUNT: u8 = 11;
+
+/// Lock program for a new action; default = unlocked
+pub fn resolve_program(
+    lock_stake: bool,
+    vote_mode: VoteMode,
+    option_count: u8,
+    identity_weighted: bool,
+    config: &GlobalConfig,
+) -> Result<Pubkey> {
+    if !lock_stake {
+        return Ok(Pubkey::default());
+    }
+    require!(
+        vote_mode == VoteMode::TokenWeighted
+            && option_count == 0
+            && !identity_weighted
+            && config.stake_lock_program != Pubkey::default(),
+        FastVoteError::InvalidStakeLock
+    );
+    Ok(config.stake_lock_program)
+}
+
+/// lock_stake (`lock`) or release_stake on `program`, signed by the lock
+/// authority; FreezeAccount or ThawAccount when `program` is a token program
+pub fn invoke_hook<'info>(
+    lock: bool,
+    program: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    authority_bump: u8,
+    voter: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    weight_mint: &AccountInfo<'info>,
+) -> Result<()> {
+    let program_id = program.key();
+    let instruction = if program_id == TOKEN_PROGRAM_ID || program_id == TOKEN_2022_PROGRAM_ID {
+        Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(token_account.key(), false),
+                AccountMeta::new_readonly(weight_mint.key(), false),
+                AccountMeta::new_readonly(authority.key(), true),
+            ],
+            data: vec![if lock { FREEZE_ACCOUNT } else { THAW_ACCOUNT }],
+        }
+    } else {
+        Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(authority.key(), true),
+                AccountMeta::new_readonly(voter.key(), false),
+                AccountMeta::new(token_account.key(), false),
+                AccountMeta::new_readonly(weight_mint.key(), false),
+            ],
+            data: if lock { LOCK_STAKE_DISCRIMINATOR } else { RELEASE_STAKE_DISCRIMINATOR }.to_vec(),
+        }
+    };
+    invoke_signed(
+        &instruction,
+        &[authority.clone(), voter.clone(), token_account.clone(), weight_mint.clone()],
+        &[&[STAKE_LOCK_AUTHORITY_SEED, &[authority_bump]]],
+    )?;
+    Ok(())
+}
+
+/// Takes one ballot's share of `voter`'s lock into `claim`; true when no
+/// other ballot held it, and the caller must lock the stake through
+/// `program`
+pub fn take_share(
+    lock: &mut StakeLock,
+    lock_bump: u8,
+    claim: &mut StakeLockClaim,
+    claim_bump: u8,
+    voter: Pubkey,
+    program: Pubkey,
+    token_account: Pubkey,
+) -> Result<bool> {
+    let first = lock.active == 0;
+    if first {
+        lock.voter = voter;
+        lock.program = program;
+        lock.token_account = token_account;
+        lock.bump = lock_bump;
+    } else {
+        require!(
+            lock.program == program && lock.token_account == token_account,
+            FastVoteError::StakeLockMismatch
+        );
+    }
+    lock.active = lock.active.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+    claim.voter = voter;
+    claim.bump = claim_bump;
+    Ok(first)
+}
+
+/// Token-weighted ballot on a stake-locked action; locks the voter's stake
+/// unless another of its ballots already holds the lock
+pub fn process_vote_fast_locked(
+    ctx: Context<VoteFastLocked>,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+    delegation_epoch: u32,
+) -> Result<()> {
+    ctx.accounts.fast_action.check_delegation_epoch(delegation_epoch)?;
+    freeze::require_live(&ctx.accounts.hive_freeze, &ctx.accounts.fast_action)?;
+    let action = &ctx.accounts.fast_action;
+    require!(
+        action.vote_mode == VoteMode::TokenWeighted && action.stake_lock_program != Pubkey::default(),
+        FastVoteError::WrongVoteMode
+    );
+    let voter = ctx.accounts.voter.key();
+    let balance = token_weight::read_locked_token_weight(
+        &ctx.accounts.voter_token_account,
+        &ctx.accounts.weight_mint,
+        &voter,
+        &ctx.accounts.config,
+        Clock::get()?.unix_timestamp,
+    )?;
+    let weight = vote_delegation::retained_share(action, &ctx.accounts.vote_delegation, balance)?;
+    require!(weight > 0, FastVoteError::NoVotingWeight);
+
+    let token_account = ctx.accounts.voter_token_account.key();
+    let first = take_share(
+        &mut ctx.accounts.stake_lock,
+        ctx.bumps.stake_lock,
+        &mut ctx.accounts.stake_lock_claim,
+        ctx.bumps.stake_lock_claim,
+        voter,
+        action.stake_lock_program,
+        token_account,
+    )?;
+    if first {
+        invoke_hook(
+            true,
+            &ctx.accounts.stake_lock_program,
+            &ctx.accounts.lock_authority,
+            ctx.bumps.lock_authority,
+            &ctx.accounts.voter.to_account_info(),
+            &ctx.accounts.voter_token_account,
+            &ctx.accounts.weight_mint,
+        )?;
+    }
+    let active = ctx.accounts.stake_lock.active;
+
+    agent_registry::require_eligible(&ctx.accounts.agent_record, &ctx.accounts.config)?;
+    let class = agent_registry::count_class_vote(&mut ctx.accounts.fast_action, &ctx.accounts.agent_record)?;
+    let clock = Clock::get()?;
+    let weight = agent_registry::aged_weight(&ctx.accounts.agent_record, &ctx.accounts.config, weight, clock.epoch)?;
+    let action = &ctx.accounts.fast_action;
+    let weight = action.early_weight(weight, clock.slot);
+    let weight = action.capped_weight(action.class_weight(class, weight));
+    vote_credits::charge_ballot(
+        &mut ctx.accounts.vote_credits,
+        voter,
+        ctx.bumps.vote_credits,
+        &ctx.accounts.config,
+    )?;
+
+    let event = record_vote(
+        &mut ctx.accounts.fast_action,
+        &mut ctx.accounts.fast_vote,
+        voter,
+        ctx.bumps.fast_vote,
+        vote_value,
+        voter_commitment,
+        weight,
+    )?;
+    metrics::record(&ctx.accounts.metrics, &ctx.accounts.fast_action.hive, Counter::Vote, Clock::get()?.slot)?;
+    emit_event!(ctx, event);
+    emit_event!(ctx, StakeLocked {
+        voter,
+        action: ctx.accounts.fast_action.key(),
+        hive: ctx.accounts.fast_action.hive,
+        token_account,
+        active,
+    });
+
+    Ok(())
+}
+
+/// Permissionless once the action is final or closed: gives back one
+/// ballot's share of its voter's lock, releasing the stake with the last
+/// share
+pub fn process_release_stake_lock(ctx: Context<ReleaseStakeLock>) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    if !action.data_is_empty() {
+        require_keys_eq!(*action.owner, crate::ID, FastVoteError::ActionNotFinalized);
+        let action = FastAction::try_deserialize(&mut &action.try_borrow_data()?[..])?;
+        require!(action.executed, FastVoteError::ActionNotFinalized);
+    }
+
+    let lock = &mut ctx.accounts.stake_lock;
+    lock.active = lock.active.saturating_sub(1);
+    let active = lock.active;
+    if active == 0 {
+        invoke_hook(
+            false,
+            &ctx.accounts.stake_lock_program,
+            &ctx.accounts.lock_authority,
+            ctx.bumps.lock_authority,
+            &ctx.accounts.voter,
+            &ctx.accounts.voter_token_account,
+            &ctx.accounts.weight_mint,
+        )?;
+    }
+
+    emit_event!(ctx, StakeReleased {
+        voter: ctx.accounts.voter.key(),
+        action: ctx.accounts.fast_action.key(),
+        active,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(action_id: u64)]
+pub struct VoteFastLocked<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = voter,
+        space = FastVote::LEN,
+        seeds = [FAST_VOTE_SEED, fast_action.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub fast_vote: Account<'info, FastVote>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoteCredits::LEN,
+        seeds = [VOTE_CREDITS_SEED, voter.key().as_ref()],
+        bump
+    )]
+    pub vote_credits: Account<'info, VoteCredits>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = StakeLock::LEN,
+        seeds = [STAKE_LOCK_SEED, voter.key().as_ref()],
+        bump
+    )]
+    pub stake_lock: Account<'info, StakeLock>,
+    #[account(
+        init,
+        payer = voter,
+        space = StakeLockClaim::LEN,
+        seeds = [STAKE_LOCK_CLAIM_SEED, fast_action.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub stake_lock_claim: Account<'info, StakeLockClaim>,
+    /// CHECK: Signs lock_stake; holds nothing
+    #[account(seeds = [STAKE_LOCK_AUTHORITY_SEED], bump)]
+    pub lock_authority: UncheckedAccount<'info>,
+    /// CHECK: The action's stake lock program
+    #[account(address = fast_action.stake_lock_program @ FastVoteError::InvalidStakeLock)]
+    pub stake_lock_program: UncheckedAccount<'info>,
+    /// CHECK: The voter's AgentRecord PDA; read in agent_registry if it exists
+    #[account(seeds = [AGENT_SEED, voter.key().as_ref()], bump)]
+    pub agent_record: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// CHECK: Must be the configured weight mint; layout parsed in token_weight
+    #[account(address = config.weight_mint @ FastVoteError::InvalidWeightMint)]
+    pub weight_mint: AccountInfo<'info>,
+    /// CHECK: Token or Token-2022 account; mint/owner/state validated in
+    /// token_weight, locked by the stake lock program
+    #[account(mut)]
+    pub voter_token_account: AccountInfo<'info>,
+    /// CHECK: The voter's VoteDelegation PDA; read in vote_delegation if it exists
+    #[account(seeds = [VOTE_DELEGATION_SEED, voter.key().as_ref()], bump)]
+    pub vote_delegation: UncheckedAccount<'info>,
+    /// CHECK: The base layer's Metrics PDA, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
+    /// CHECK: The HiveFreeze PDA of the action's hive, whether or not it
+    /// exists; read in freeze::require_live
+    #[account(seeds = [HIVE_FREEZE_SEED, fast_action.hive.as_ref()], bump)]
+    pub hive_freeze: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ReleaseStakeLock<'info> {
+    /// CHECK: The action the claim was made on, bound by the claim's seeds;
+    /// read in process_release_stake_lock unless the expiry sweep closed it
+    pub fast_action: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = voter,
+        seeds = [STAKE_LOCK_CLAIM_SEED, fast_action.key().as_ref(), voter.key().as_ref()],
+        bump = stake_lock_claim.bump
+    )]
+    pub stake_lock_claim: Account<'info, StakeLockClaim>,
+    #[account(mut, seeds = [STAKE_LOCK_SEED, voter.key().as_ref()], bump = stake_lock.bump)]
+    pub stake_lock: Account<'info, StakeLock>,
+    /// CHECK: The ballot's voter, bound by the claim's seeds; receives the
+    /// claim's rent
+    #[account(mut)]
+    pub voter: UncheckedAccount<'info>,
+    /// CHECK: Signs release_stake; holds nothing
+    #[account(seeds = [STAKE_LOCK_AUTHORITY_SEED], bump)]
+    pub lock_authority: UncheckedAccount<'info>,
+    /// CHECK: The program the lock was placed with
+    #[account(address = stake_lock.program @ FastVoteError::StakeLockMismatch)]
+    pub stake_lock_program: UncheckedAccount<'info>,
+    /// CHECK: The token account the lock was placed on
+    #[account(mut, address = stake_lock.token_account @ FastVoteError::StakeLockMismatch)]
+    pub voter_token_account: UncheckedAccount<'info>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// CHECK: Must be the configured weight mint
+    #[account(address = config.weight_mint @ FastVoteError::InvalidWeightMint)]
+    pub weight_mint: UncheckedAccount<'info>,
+}
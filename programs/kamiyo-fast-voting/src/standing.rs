@@ -0,0 +1,100 @@
+// Live standing of an action as return data, for clients in the ER and CPI
+// callers that would rather not decode FastAction and redo the tally
+// arithmetic themselves. Simulating get_standing is enough to read it.
+// preview_tally is the creator's view of the same thing: what closing the
+// vote now would record, or why it would be refused, so an agent can decide
+// whether to extend, cancel or lobby for votes before the deadline.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    tally, FastAction, FastVoteError, Standing, TallyPreview, TallyRejection, VoteMode, VoteResult, FAST_ACTION_SEED,
+    FAST_ACTION_VERSION,
+};
+
+pub fn process_get_standing(ctx: Context<GetStanding>) -> Result<Standing> {
+    let action = &ctx.accounts.fast_action;
+    let slot = Clock::get()?.slot;
+
+    let approval_pct = if action.option_count > 0 {
+        let options = &action.option_weights[..action.option_count as usize];
+        let total = options.iter().fold(0u64, |sum, w| sum.saturating_add(*w));
+        let leading = options.iter().copied().max().unwrap_or(0);
+        tally::approval_pct(leading, total - leading).unwrap_or(0)
+    } else {
+        let (tally_for, tally_against) = match action.vote_mode {
+            VoteMode::TokenWeighted => (action.weight_for, action.weight_against),
+            VoteMode::Headcount | VoteMode::Optimistic => (action.votes_for as u64, action.votes_against as u64),
+        };
+        tally::approval_pct(tally_for, tally_against).unwrap_or(0)
+    };
+    let required_votes = required_votes(action, slot);
+    let projected = if action.executed {
+        action.result.clone()
+    } else {
+        action.tallied_result(slot).unwrap_or(VoteResult::Pending)
+    };
+
+    Ok(Standing {
+        approval_pct: approval_pct as u8,
+        slots_remaining: action.deadline_slot.saturating_sub(slot),
+        vote_count: action.vote_count,
+        required_votes,
+        projected,
+    })
+}
+
+/// tally_and_commit's arithmetic without its deadline and audit checks.
+/// Fails only on an already finalized action.
+pub fn process_preview_tally(ctx: Context<PreviewTally>) -> Result<TallyPreview> {
+    let action = &ctx.accounts.fast_action;
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    let slot = Clock::get()?.slot;
+
+    let (result, rejection) = match action.tallied_result(slot) {
+        Ok(result) => (result, None),
+        Err(err) => (VoteResult::Pending, Some(TallyRejection::of(&err).ok_or(err)?)),
+    };
+    Ok(TallyPreview {
+        result,
+        rejection,
+        vote_count: action.vote_count,
+        required_votes: required_votes(action, slot),
+        slot,
+    })
+}
+
+fn required_votes(action: &FastAction, slot: u64) -> u32 {
+    match action.vote_mode {
+        VoteMode::Optimistic => 0,
+        VoteMode::Headcount | VoteMode::TokenWeighted => tally::required_quorum(
+            action.quorum,
+            action.quorum_floor,
+            action.quorum_grace_slots,
+            action.deadline_slot,
+            slot,
+        ),
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(action_id: u64)]
+pub struct GetStanding<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+}
+
+#[derive(Accounts)]
+#[instruction(action_id: u64)]
+pub struct PreviewTally<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+}
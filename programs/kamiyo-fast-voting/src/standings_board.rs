@@ -0,0 +1,128 @@
+// Read-optimized standings of a hive's actions for agents on the ER. A
+// coordinator following a hive's live votes would otherwise poll every open
+// action each slot; the hive's StandingsBoard holds the tallies of its
+// BOARD_SLOTS most recent actions in one account instead.
+//
+// The hive authority creates the board with a transaction sent to the
+// ephemeral rollup, where it stays, as with a hive's ER Metrics. vote_fast
+// and tally_and_commit take it as an optional account and rewrite only the
+// entry of the action they touch, in place; see StandingsBoard for which
+// slot that is. Like the Metrics counters, the board is a cache: a ballot or
+// tally without it, or sent before it exists, leaves it stale until the
+// action's next one, and the action account stays the source of truth.
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::{
+    BoardEntry, BoardStatus, FastAction, FastVoteError, Hive, StandingsBoard, StandingsBoardInitialized, BOARD_SLOTS,
+    HIVE_SEED, STANDINGS_BOARD_SEED,
+};
+
+// Byte offsets in a BoardEntry
+const ACTION_ID: usize = 32;
+const STATUS: usize = 40;
+const UPDATED_SLOT: usize = 77;
+
+/// The entry of `action` as tallied now
+pub fn entry_of(key: Pubkey, action: &FastAction, slot: u64) -> BoardEntry {
+    BoardEntry {
+        action: key,
+        action_id: action.action_id,
+        status: if action.executed {
+            BoardStatus::finalized(&action.result)
+        } else {
+            BoardStatus::Open
+        },
+        deadline_slot: action.deadline_slot,
+        votes_for: action.votes_for,
+        votes_against: action.votes_against,
+        vote_count: action.vote_count,
+        weight_for: action.weight_for,
+        weight_against: action.weight_against,
+        updated_slot: slot,
+    }
+}
+
+/// Writes `entry` to the StandingsBoard passed as `board`, if one was passed
+/// and it exists; it must be `hive`'s
+pub fn post(board: &Option<UncheckedAccount>, hive: &Pubkey, entry: &BoardEntry) -> Result<()> {
+    let Some(info) = board else {
+        return Ok(());
+    };
+    if info.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let mut data = info.try_borrow_mut_data()?;
+    require!(
+        data.len() == StandingsBoard::LEN && data[..8] == *StandingsBoard::DISCRIMINATOR,
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    require!(data[8..40] == *hive.as_ref(), FastVoteError::BoardHiveMismatch);
+
+    let entries = &mut data[StandingsBoard::ENTRIES..];
+    let Some(slot) = slot_for(entries, entry) else {
+        return Ok(());
+    };
+    let at = slot * BoardEntry::LEN;
+    entry.serialize(&mut &mut entries[at..at + BoardEntry::LEN])?;
+    Ok(())
+}
+
+/// The slot `entry` goes to among the packed `entries`, or None if every
+/// slot holds an open action at least as recent
+fn slot_for(entries: &[u8], entry: &BoardEntry) -> Option<usize> {
+    let u64_at = |e: &[u8], offset: usize| u64::from_le_bytes(e[offset..offset + 8].try_into().unwrap());
+    let slots = || entries.chunks_exact(BoardEntry::LEN).take(BOARD_SLOTS).enumerate();
+    let with_status = |status: BoardStatus| slots().filter(move |(_, e)| e[STATUS] == status as u8);
+
+    if let Some((i, _)) = slots().find(|(_, e)| e[..32] == *entry.action.as_ref()) {
+        return Some(i);
+    }
+    if let Some((i, _)) = with_status(BoardStatus::Empty).next() {
+        return Some(i);
+    }
+    let finalized = slots().filter(|(_, e)| e[STATUS] != BoardStatus::Open as u8);
+    if let Some((i, _)) = finalized.min_by_key(|(_, e)| u64_at(e, UPDATED_SLOT)) {
+        return Some(i);
+    }
+    let (i, oldest) = with_status(BoardStatus::Open).min_by_key(|(_, e)| u64_at(e, ACTION_ID))?;
+    (entry.status == BoardStatus::Open && entry.action_id > u64_at(oldest, ACTION_ID)).then_some(i)
+}
+
+/// Hive authority only; send to the ephemeral rollup.
+pub fn process_initialize_standings_board(ctx: Context<InitializeStandingsBoard>) -> Result<()> {
+    let board = &mut ctx.accounts.standings_board;
+    board.hive = ctx.accounts.hive_account.hive;
+    board.bump = ctx.bumps.standings_board;
+
+    emit_event!(ctx, StandingsBoardInitialized {
+        board: board.key(),
+        hive: board.hive,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct InitializeStandingsBoard<'info> {
+    #[account(
+        seeds = [HIVE_SEED, hive_account.hive.as_ref()],
+        bump = hive_account.bump,
+        has_one = authority @ FastVoteError::Unauthorized
+    )]
+    pub hive_account: Account<'info, Hive>,
+    #[account(
+        init,
+        payer = authority,
+        space = StandingsBoard::LEN,
+        seeds = [STANDINGS_BOARD_SEED, hive_account.hive.as_ref()],
+        bump
+    )]
+    pub standings_board: Account<'info, StandingsBoard>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
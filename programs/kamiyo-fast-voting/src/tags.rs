@@ -0,0 +1,170 @@
+// Action tags. An action may carry up to MAX_TAGS eight-byte labels, and a
+// hive (one more for actions outside any hive) keeps a paginated TagIndex
+// per tag listing its open actions, so an agent on the ER can enumerate,
+// say, every open action tagged `trading` from account reads alone, without
+// an off-chain indexer.
+//
+// Pages live on the base layer and are created by initialize_tag_index, in
+// order from page 0, so readers walk them until the next is missing.
+// create_action lists a tagged action in one page per tag, passed after the
+// creation accounts in tag order; any page of the tag with room will do.
+// Once the action is final, retire_tags takes it out again, or the expiry
+// sweep does when it closes the action. Runoffs keep their parent's tags
+// but are not listed, as with priority lanes.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    hive, ActionTagged, ActionUntagged, FastAction, FastVoteError, Hive, TagIndex, FAST_ACTION_SEED,
+    FAST_ACTION_VERSION, HIVE_SEED, MAX_TAGS, TAG_INDEX_SEED, TAG_PAGE_LEN,
+};
+
+/// `tags` packed into the action's slots; distinct and non-zero
+pub fn pack(tags: &[[u8; 8]]) -> Result<[[u8; 8]; MAX_TAGS]> {
+    require!(tags.len() <= MAX_TAGS, FastVoteError::InvalidTags);
+    let mut slots = [[0u8; 8]; MAX_TAGS];
+    for (i, tag) in tags.iter().enumerate() {
+        require!(*tag != [0u8; 8] && !tags[..i].contains(tag), FastVoteError::InvalidTags);
+        slots[i] = *tag;
+    }
+    Ok(slots)
+}
+
+/// The writable TagIndex page at `info`, checked to be one of `tag`'s for
+/// the action's hive
+fn load(action: &FastAction, tag: &[u8; 8], info: &AccountInfo) -> Result<TagIndex> {
+    require_keys_eq!(*info.owner, crate::ID, FastVoteError::InvalidTagIndex);
+    require!(info.is_writable, FastVoteError::InvalidTagIndex);
+    let page = TagIndex::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    let address = Pubkey::create_program_address(
+        &[TAG_INDEX_SEED, action.hive_seed(), tag, &page.page.to_le_bytes(), &[page.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| FastVoteError::InvalidTagIndex)?;
+    require!(page.tag == *tag && address == info.key(), FastVoteError::InvalidTagIndex);
+    Ok(page)
+}
+
+fn store(page: &TagIndex, info: &AccountInfo) -> Result<()> {
+    page.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+}
+
+/// Lists a new action in `pages`, one per tag in tag order
+pub fn index(action: &mut FastAction, key: Pubkey, pages: &[AccountInfo]) -> Result<Vec<ActionTagged>> {
+    let tags = action.tag_list().to_vec();
+    require!(pages.len() == tags.len(), FastVoteError::InvalidTagIndex);
+    let mut tagged = Vec::with_capacity(tags.len());
+    for (tag, info) in tags.iter().zip(pages) {
+        let mut page = load(action, tag, info)?;
+        require!((page.count as usize) < TAG_PAGE_LEN, FastVoteError::InvalidTagIndex);
+        page.actions[page.count as usize] = key;
+        page.count += 1;
+        store(&page, info)?;
+        tagged.push(ActionTagged {
+            hive: page.hive,
+            action: key,
+            tag: *tag,
+            page: page.page,
+        });
+    }
+    action.tags_indexed = !tags.is_empty();
+    Ok(tagged)
+}
+
+/// Takes a listed action out of `pages`, the one holding it for each tag in
+/// tag order
+pub fn retire(action: &mut FastAction, key: Pubkey, pages: &[AccountInfo]) -> Result<Vec<ActionUntagged>> {
+    require!(action.tags_indexed, FastVoteError::ActionNotTagged);
+    let tags = action.tag_list().to_vec();
+    require!(pages.len() == tags.len(), FastVoteError::InvalidTagIndex);
+    let mut untagged = Vec::with_capacity(tags.len());
+    for (tag, info) in tags.iter().zip(pages) {
+        let mut page = load(action, tag, info)?;
+        let position = page
+            .actions()
+            .iter()
+            .position(|listed| *listed == key)
+            .ok_or(FastVoteError::ActionNotTagged)?;
+        let last = page.count as usize - 1;
+        page.actions[position] = page.actions[last];
+        page.actions[last] = Pubkey::default();
+        page.count -= 1;
+        store(&page, info)?;
+        untagged.push(ActionUntagged {
+            hive: page.hive,
+            action: key,
+            tag: *tag,
+            page: page.page,
+        });
+    }
+    action.tags_indexed = false;
+    Ok(untagged)
+}
+
+/// Permissionless; creates page `page` of `tag`'s index for `hive_account`'s
+/// actions, or for unscoped actions without one. Pages after the first need
+/// the one before
+pub fn process_initialize_tag_index(ctx: Context<InitializeTagIndex>, tag: [u8; 8], page: u32) -> Result<()> {
+    require!(tag != [0u8; 8], FastVoteError::InvalidTags);
+    require!((page == 0) == ctx.accounts.previous.is_none(), FastVoteError::InvalidTagIndex);
+
+    let index = &mut ctx.accounts.tag_index;
+    index.hive = ctx.accounts.hive_account.as_ref().map_or(Pubkey::default(), |h| h.hive);
+    index.tag = tag;
+    index.page = page;
+    index.count = 0;
+    index.actions = [Pubkey::default(); TAG_PAGE_LEN];
+    index.bump = ctx.bumps.tag_index;
+    Ok(())
+}
+
+/// Permissionless, on the base layer, once the action is final. Remaining
+/// accounts hold the page listing it for each of its tags, in tag order
+pub fn process_retire_tags(ctx: Context<RetireTags>) -> Result<()> {
+    let key = ctx.accounts.fast_action.key();
+    let action = &mut ctx.accounts.fast_action;
+    require!(action.executed, FastVoteError::ActionNotFinalized);
+
+    for event in retire(action, key, ctx.remaining_accounts)? {
+        emit_event!(ctx, event);
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(tag: [u8; 8], page: u32)]
+pub struct InitializeTagIndex<'info> {
+    #[account(seeds = [HIVE_SEED, hive_account.hive.as_ref()], bump = hive_account.bump)]
+    pub hive_account: Option<Account<'info, Hive>>,
+    /// The tag's page before `page`; required unless `page` is 0
+    #[account(
+        seeds = [TAG_INDEX_SEED, hive::action_seed(&hive_account), &tag, &page.wrapping_sub(1).to_le_bytes()],
+        bump = previous.bump
+    )]
+    pub previous: Option<Account<'info, TagIndex>>,
+    #[account(
+        init,
+        payer = payer,
+        space = TagIndex::LEN,
+        seeds = [TAG_INDEX_SEED, hive::action_seed(&hive_account), &tag, &page.to_le_bytes()],
+        bump
+    )]
+    pub tag_index: Account<'info, TagIndex>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RetireTags<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+}
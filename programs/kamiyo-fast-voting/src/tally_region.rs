@@ -0,0 +1,768 @@
+// Zero-copy ballot path. vote_fast is what the rollup runs most, and a Borsh
+// round trip of the whole ~1.2 KB FastAction used to dominate its compute.
+// ActionView instead reads and writes just the fields a ballot touches, in
+// place in the account data; the tally counters, class counts and log are the
+// only bytes it writes.
+//
+// FastAction is Borsh, so its layout is fixed except for `result`: one byte,
+// or two for a result carrying a tier or option (an optimistic action opens
+// as Passed). The offsets below are for the one-byte form; those past
+// `result` move one byte further on for the two-byte form. They track
+// FastAction's field order, which only ever grows at the end; see
+// `migration`.
+//
+// vote_compact shares this path; it differs only in how its arguments are
+// encoded, see `compact_vote`.
+//
+// The ballot's FastVote is created lazily: only once every check has passed,
+// with a single create_account when it holds no lamports, rather than by
+// `init` before the handler runs.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{
+    self, allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+};
+use anchor_lang::Discriminator;
+
+use crate::agent_registry;
+use crate::{
+    freeze, membership, metrics, standings_board, tally, vote_credits, ActionKind, ActionLog, AgentClass,
+    ApprovalRounding, BallotChoice, BoardEntry, BoardStatus, Counter, FastAction, FastVote, FastVoteCast,
+    FastVoteError, LogTag, OptionVoteCast, PrivacyLevel, VoteFast, VoteMode, ACTION_LOG_LEN, AGENT_CLASSES,
+    FAST_ACTION_SEED, FAST_ACTION_VERSION, FAST_VOTE_SEED, MAX_DEPENDENCIES, MAX_EXCLUSIONS, MAX_EXTRA_TIERS,
+    MAX_OPTIONS, MAX_TAGS,
+};
+
+// Byte offsets in the account data, discriminator included. Each is the
+// one before it plus the widths of the fields in between, named where they
+// are skipped.
+const KEY: usize = size_of::<Pubkey>();
+const VERSION: usize = FastAction::DISCRIMINATOR.len();
+const ACTION_ID: usize = VERSION + size_of::<u8>();
+const ACTION_HASH: usize = ACTION_ID + size_of::<u64>();
+// description_hash, creator, threshold
+const VOTES_FOR: usize = ACTION_HASH + 32 + 32 + KEY + size_of::<u8>();
+const VOTES_AGAINST: usize = VOTES_FOR + size_of::<u32>();
+const VOTE_COUNT: usize = VOTES_AGAINST + size_of::<u32>();
+// created_slot
+const DEADLINE_SLOT: usize = VOTE_COUNT + size_of::<u32>() + size_of::<u64>();
+const EXECUTED: usize = DEADLINE_SLOT + size_of::<u64>();
+const RESULT: usize = EXECUTED + size_of::<bool>();
+// Past `result`; add `shift`
+const BUMP: usize = RESULT + 1;
+const VOTE_MODE: usize = BUMP + size_of::<u8>();
+const WEIGHT_FOR: usize = VOTE_MODE + 1;
+const WEIGHT_AGAINST: usize = WEIGHT_FOR + size_of::<u64>();
+const HIVE: usize = WEIGHT_AGAINST + size_of::<u64>();
+// tier_thresholds, dependency_count, dependencies, finalized_slot,
+// result_digest, last_checkpoint_slot, last_checkpoint_votes,
+// delegated_validator, session_recorded, electorate, bond
+const QUORUM: usize = HIVE
+    + KEY
+    + MAX_EXTRA_TIERS
+    + 1
+    + MAX_DEPENDENCIES * KEY
+    + size_of::<u64>()
+    + 32
+    + size_of::<u64>()
+    + size_of::<u32>()
+    + KEY
+    + size_of::<bool>()
+    + size_of::<u32>()
+    + size_of::<u64>();
+// quorum_floor, quorum_grace_slots, min_turnout_bps
+const OPTION_COUNT: usize = QUORUM + size_of::<u32>() + size_of::<u32>() + size_of::<u64>() + size_of::<u16>();
+const OPTION_WEIGHTS: usize = OPTION_COUNT + size_of::<u8>();
+const RANKED: usize = OPTION_WEIGHTS + MAX_OPTIONS * size_of::<u64>();
+const ELIMINATED_MASK: usize = RANKED + size_of::<bool>();
+// pending_elimination, round, exhausted_ballots, parent_action,
+// runoff_action, market_feed, market_program, market_price_offset,
+// market_min_price, market_cleared, authority, draft
+const KIND: usize = ELIMINATED_MASK
+    + size_of::<u8>()
+    + size_of::<u8>()
+    + size_of::<u8>()
+    + size_of::<u32>()
+    + 4 * KEY
+    + size_of::<u16>()
+    + size_of::<u64>()
+    + size_of::<bool>()
+    + 2 * KEY;
+// max_weight_per_voter
+const CLASS_MAX_VOTES: usize = KIND + 1 + size_of::<u64>();
+// class_weight_bps
+const CLASS_VOTES: usize = CLASS_MAX_VOTES + AGENT_CLASSES * size_of::<u32>() + AGENT_CLASSES * size_of::<u16>();
+const DELEGATION_EPOCH: usize = CLASS_VOTES + AGENT_CLASSES * size_of::<u32>();
+// delegation_state, audited
+const HIVE_SEEDED: usize = DELEGATION_EPOCH + size_of::<u32>() + 1 + size_of::<bool>();
+// early_vote_curve: bonus_bps u16, shape
+const LOG_ENTRIES: usize = HIVE_SEEDED + size_of::<bool>() + size_of::<u16>() + size_of::<u8>();
+const LOG_ENTRY_LEN: usize = size_of::<u64>() + 1 + size_of::<u64>();
+const LOG_APPENDED: usize = LOG_ENTRIES + ACTION_LOG_LEN * LOG_ENTRY_LEN;
+const EXCLUDED_COUNT: usize = LOG_ENTRIES + ActionLog::LEN;
+const EXCLUDED: usize = EXCLUDED_COUNT + size_of::<u8>();
+// extension_policy: max_extensions u8, support_bps u16; extensions,
+// extended_slots, identity_weighted
+const MEMBER_COLLECTION: usize = EXCLUDED
+    + MAX_EXCLUSIONS * KEY
+    + size_of::<u8>()
+    + size_of::<u16>()
+    + size_of::<u8>()
+    + size_of::<u64>()
+    + size_of::<bool>();
+// stake_lock_program, priority, lane_counted
+const MAX_VOTES: usize = MEMBER_COLLECTION + KEY + KEY + size_of::<u8>() + size_of::<bool>();
+// tags, tags_indexed, quorum_weight_bps, snapshot_weight, creation_fee,
+// fee_rebate
+const PRIVACY: usize = MAX_VOTES
+    + size_of::<u32>()
+    + MAX_TAGS * 8
+    + size_of::<bool>()
+    + size_of::<u16>()
+    + 3 * size_of::<u64>();
+// validator_counted
+const THRESHOLD_BPS: usize = PRIVACY + 1 + size_of::<bool>();
+const APPROVAL_ROUNDING: usize = THRESHOLD_BPS + size_of::<u16>();
+
+/// Result variants encoded in two bytes: Passed { tier }, Chosen { option },
+/// Contested { tier }
+const WIDE_RESULTS: [u8; 3] = [1, 6, 8];
+
+pub struct ActionView<'a> {
+    data: &'a mut [u8],
+    shift: usize,
+}
+
+impl<'a> ActionView<'a> {
+    /// The checks `Account<FastAction>` and VoteFast's constraints made:
+    /// owner, discriminator, version and the action's address for the
+    /// action id it holds.
+    pub fn load(key: &Pubkey, owner: &Pubkey, data: &'a mut [u8]) -> Result<Self> {
+        require_keys_eq!(*owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        require!(
+            data.len() == FastAction::LEN && data[..8] == *FastAction::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        require!(data[VERSION] == FAST_ACTION_VERSION, FastVoteError::ActionNeedsMigration);
+        let shift = WIDE_RESULTS.contains(&data[RESULT]) as usize;
+        let view = Self { data, shift };
+
+        let hive_seed: &[u8] = if view.data[HIVE_SEEDED + shift] != 0 {
+            &view.data[HIVE + shift..HIVE + shift + 32]
+        } else {
+            &[]
+        };
+        let address = Pubkey::create_program_address(
+            &[FAST_ACTION_SEED, hive_seed, &view.data[ACTION_ID..ACTION_ID + 8], &[view.data[BUMP + shift]]],
+            &crate::ID,
+        )
+        .map_err(|_| ErrorCode::ConstraintSeeds)?;
+        require_keys_eq!(address, *key, ErrorCode::ConstraintSeeds);
+        Ok(view)
+    }
+
+    pub fn action_id(&self) -> u64 {
+        self.u64_at(ACTION_ID)
+    }
+
+    fn u32_at(&self, offset: usize) -> u32 {
+        u32::from_le_bytes(self.data[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn u16_at(&self, offset: usize) -> u16 {
+        u16::from_le_bytes(self.data[offset..offset + 2].try_into().unwrap())
+    }
+
+    fn u64_at(&self, offset: usize) -> u64 {
+        u64::from_le_bytes(self.data[offset..offset + 8].try_into().unwrap())
+    }
+
+    fn set_u64(&mut self, offset: usize, value: u64) {
+        self.data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn set_u32(&mut self, offset: usize, value: u32) {
+        self.data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn bump_u32(&mut self, offset: usize) -> Result<u32> {
+        let value = self.u32_at(offset).checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+        self.set_u32(offset, value);
+        Ok(value)
+    }
+
+    pub fn vote_mode(&self) -> Result<VoteMode> {
+        Ok(VoteMode::try_from_slice(&self.data[VOTE_MODE + self.shift..][..1])?)
+    }
+
+    pub fn kind(&self) -> Result<ActionKind> {
+        Ok(ActionKind::try_from_slice(&self.data[KIND + self.shift..][..1])?)
+    }
+
+    pub fn action_hash(&self) -> [u8; 32] {
+        self.data[ACTION_HASH..ACTION_HASH + 32].try_into().unwrap()
+    }
+
+    pub fn option_count(&self) -> u8 {
+        self.data[OPTION_COUNT + self.shift]
+    }
+
+    pub fn privacy(&self) -> Result<PrivacyLevel> {
+        Ok(PrivacyLevel::try_from_slice(&self.data[PRIVACY + self.shift..][..1])?)
+    }
+
+    pub fn vote_count(&self) -> u32 {
+        self.u32_at(VOTE_COUNT)
+    }
+
+    pub fn check_delegation_epoch(&self, delegation_epoch: u32) -> Result<()> {
+        require!(
+            delegation_epoch == self.u32_at(DELEGATION_EPOCH + self.shift),
+            FastVoteError::StaleDelegationEpoch
+        );
+        Ok(())
+    }
+
+    /// FastAction::passing for a headcount tally
+    fn passing(&self) -> bool {
+        let (votes_for, votes_against) = (self.u32_at(VOTES_FOR), self.u32_at(VOTES_AGAINST));
+        let Ok(rounding) = ApprovalRounding::try_from_slice(&self.data[APPROVAL_ROUNDING + self.shift..][..1]) else {
+            return false;
+        };
+        tally::approval_bps(votes_for as u64, votes_against as u64, rounding)
+            .is_ok_and(|bps| bps >= self.u16_at(THRESHOLD_BPS + self.shift) as u64)
+    }
+
+    fn excludes(&self, voter: &Pubkey) -> bool {
+        let count = (self.data[EXCLUDED_COUNT + self.shift] as usize).min(MAX_EXCLUSIONS);
+        let start = EXCLUDED + self.shift;
+        self.data[start..start + count * 32].chunks_exact(32).any(|key| key == voter.as_ref())
+    }
+
+    /// FastAction::member_collection
+    pub fn hive(&self) -> Pubkey {
+        let start = HIVE + self.shift;
+        Pubkey::try_from(&self.data[start..start + 32]).unwrap()
+    }
+
+    pub fn member_collection(&self) -> Pubkey {
+        let start = MEMBER_COLLECTION + self.shift;
+        Pubkey::try_from(&self.data[start..start + 32]).unwrap()
+    }
+
+    /// standings_board::entry_of for the open action at `key`
+    pub fn board_entry(&self, key: Pubkey, slot: u64) -> BoardEntry {
+        BoardEntry {
+            action: key,
+            action_id: self.action_id(),
+            status: BoardStatus::Open,
+            deadline_slot: self.u64_at(DEADLINE_SLOT),
+            votes_for: self.u32_at(VOTES_FOR),
+            votes_against: self.u32_at(VOTES_AGAINST),
+            vote_count: self.vote_count(),
+            weight_for: self.u64_at(WEIGHT_FOR + self.shift),
+            weight_against: self.u64_at(WEIGHT_AGAINST + self.shift),
+            updated_slot: slot,
+        }
+    }
+
+    fn push_log(&mut self, slot: u64, tag: LogTag, payload: u64) {
+        let appended_at = LOG_APPENDED + self.shift;
+        let appended = self.u32_at(appended_at);
+        let at = LOG_ENTRIES + self.shift + appended as usize % ACTION_LOG_LEN * LOG_ENTRY_LEN;
+        self.data[at..at + 8].copy_from_slice(&slot.to_le_bytes());
+        self.data[at + 8] = tag as u8;
+        self.data[at + 9..at + 17].copy_from_slice(&payload.to_le_bytes());
+        self.set_u32(appended_at, appended.wrapping_add(1));
+    }
+
+    /// agent_registry::count_class_vote
+    pub fn count_class_vote(&mut self, class: AgentClass) -> Result<()> {
+        let index = class as usize;
+        debug_assert!(index < AGENT_CLASSES);
+        let max_votes = self.u32_at(CLASS_MAX_VOTES + self.shift + index * 4);
+        let votes_at = CLASS_VOTES + self.shift + index * 4;
+        require!(
+            max_votes == 0 || self.u32_at(votes_at) < max_votes,
+            FastVoteError::ClassVoteCapReached
+        );
+        self.bump_u32(votes_at)?;
+        Ok(())
+    }
+
+    /// open_ballot; returns the new vote count
+    fn open(&mut self, voter: &Pubkey, voter_commitment: &[u8; 32], slot: u64) -> Result<u32> {
+        require!(self.data[EXECUTED] == 0, FastVoteError::ActionAlreadyExecuted);
+        require!(slot <= self.u64_at(DEADLINE_SLOT), FastVoteError::VotingEnded);
+        require!(self.vote_count() < self.u32_at(MAX_VOTES + self.shift), FastVoteError::MaxVotesReached);
+        require!(*voter_commitment != [0u8; 32], FastVoteError::InvalidVoterCommitment);
+        require!(!self.excludes(voter), FastVoteError::VoterExcluded);
+
+        let votes = self.bump_u32(VOTE_COUNT)?;
+        if votes == self.u32_at(QUORUM + self.shift) {
+            self.push_log(slot, LogTag::QuorumReached, votes as u64);
+        }
+        Ok(votes)
+    }
+
+    /// record_vote for a weight-1 ballot on a for/against action; returns
+    /// the new vote count
+    pub fn cast(
+        &mut self,
+        voter: &Pubkey,
+        vote_value: bool,
+        voter_commitment: &[u8; 32],
+        slot: u64,
+    ) -> Result<u32> {
+        require!(self.option_count() == 0, FastVoteError::OptionBallotRequired);
+        let passing_before = self.passing();
+        let votes = self.open(voter, voter_commitment, slot)?;
+        self.bump_u32(if vote_value { VOTES_FOR } else { VOTES_AGAINST })?;
+
+        if self.vote_mode()? != VoteMode::Optimistic && self.passing() != passing_before {
+            let (votes_for, votes_against) = (self.u32_at(VOTES_FOR), self.u32_at(VOTES_AGAINST));
+            let approval = tally::approval_pct(votes_for as u64, votes_against as u64).unwrap_or_default();
+            self.push_log(slot, LogTag::ThresholdCrossed, approval);
+        }
+        Ok(votes)
+    }
+
+    /// A CommitReveal ballot, which counts toward quorum and turnout until
+    /// reveal_ballot counts its choice; returns the new vote count
+    pub fn commit(&mut self, voter: &Pubkey, voter_commitment: &[u8; 32], slot: u64) -> Result<u32> {
+        require!(self.option_count() == 0, FastVoteError::OptionBallotRequired);
+        self.open(voter, voter_commitment, slot)
+    }
+
+    /// vote_options for a headcount ballot, which puts its single unit of
+    /// weight on `option`; returns the new vote count
+    pub fn cast_option(
+        &mut self,
+        voter: &Pubkey,
+        option: u8,
+        voter_commitment: &[u8; 32],
+        slot: u64,
+    ) -> Result<u32> {
+        let option_count = self.option_count();
+        require!(option_count > 0, FastVoteError::NotAnOptionAction);
+        require!(self.data[RANKED + self.shift] == 0, FastVoteError::RankedBallotRequired);
+        require!(
+            option < option_count && self.data[ELIMINATED_MASK + self.shift] & (1 << option) == 0,
+            FastVoteError::InvalidOptionWeights
+        );
+        let votes = self.open(voter, voter_commitment, slot)?;
+        let at = OPTION_WEIGHTS + self.shift + option as usize * 8;
+        let weight = self.u64_at(at).checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+        self.set_u64(at, weight);
+        Ok(votes)
+    }
+}
+
+pub fn process_vote_fast(
+    ctx: Context<VoteFast>,
+    action_id: u64,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+    delegation_epoch: u32,
+) -> Result<()> {
+    cast_ballot(
+        ctx,
+        Some(action_id),
+        BallotChoice::ForAgainst(vote_value),
+        voter_commitment,
+        delegation_epoch,
+    )
+}
+
+/// vote_fast and vote_compact. `action_id`, when given, must be the
+/// action's; vote_compact leaves it out and relies on the address check in
+/// ActionView::load alone.
+pub(crate) fn cast_ballot(
+    ctx: Context<VoteFast>,
+    action_id: Option<u64>,
+    choice: BallotChoice,
+    voter_commitment: [u8; 32],
+    delegation_epoch: u32,
+) -> Result<()> {
+    let action_info = ctx.accounts.fast_action.to_account_info();
+    // The agent's, whichever of its keys signed
+    let voter = ctx.accounts.agent.key();
+    let slot = Clock::get()?.slot;
+
+    let (vote_count, option_count, hive, entry, privacy) = {
+        let mut data = action_info.try_borrow_mut_data()?;
+        let mut action = ActionView::load(action_info.key, action_info.owner, &mut data)?;
+        if let Some(action_id) = action_id {
+            require!(action.action_id() == action_id, ErrorCode::ConstraintSeeds);
+        }
+        action.check_delegation_epoch(delegation_epoch)?;
+        match (action.vote_mode()?, choice) {
+            (VoteMode::Headcount, _) => {}
+            (VoteMode::Optimistic, BallotChoice::ForAgainst(vote_value)) => {
+                require!(!vote_value, FastVoteError::OptimisticVetoOnly)
+            }
+            _ => return err!(FastVoteError::WrongVoteMode),
+        }
+        let privacy = action.privacy()?;
+        match (privacy, choice) {
+            (PrivacyLevel::Public, _) => {}
+            (PrivacyLevel::CommitReveal | PrivacyLevel::Encrypted, BallotChoice::ForAgainst(_)) => {}
+            _ => return err!(FastVoteError::PrivacyLevelMismatch),
+        }
+        require!(ctx.accounts.compressed_ballots.data_is_empty(), FastVoteError::CompressedBallotsOnly);
+        freeze::check_vote_fast(&ctx.accounts.hive_freeze, &action.hive(), action.kind()?, &action.action_hash())?;
+
+        agent_registry::require_eligible(&ctx.accounts.agent_record, &ctx.accounts.config)?;
+        let collection = action.member_collection();
+        if collection != Pubkey::default() {
+            membership::require_member(&collection, &voter, ctx.remaining_accounts)?;
+        }
+        action.count_class_vote(agent_registry::class_of(&ctx.accounts.agent_record)?)?;
+        vote_credits::charge_ballot(
+            &mut ctx.accounts.vote_credits,
+            voter,
+            ctx.bumps.vote_credits,
+            &ctx.accounts.config,
+        )?;
+        let vote_count = match choice {
+            // The voter commitment is the ballot_commitment
+            _ if privacy == PrivacyLevel::CommitReveal => action.commit(&voter, &voter_commitment, slot)?,
+            BallotChoice::ForAgainst(vote_value) => action.cast(&voter, vote_value, &voter_commitment, slot)?,
+            BallotChoice::Option(option) => action.cast_option(&voter, option, &voter_commitment, slot)?,
+        };
+        let entry = action.board_entry(action_info.key(), slot);
+        (vote_count, action.option_count(), action.hive(), entry, privacy)
+    };
+    #[cfg(feature = "audit")]
+    crate::invariants::check_account(&action_info)?;
+
+    create_vote_record(
+        &ctx,
+        FastVote {
+            fast_action: action_info.key(),
+            voter,
+            voter_commitment,
+            // vote_options records option ballots as `true`; private ballots
+            // record no choice, and unrevealed ones no weight
+            vote_value: privacy == PrivacyLevel::Public && !matches!(choice, BallotChoice::ForAgainst(false)),
+            voted_slot: slot,
+            bump: ctx.bumps.fast_vote,
+            weight: (privacy != PrivacyLevel::CommitReveal) as u64,
+            reason_hash: [0; 32],
+        },
+    )?;
+    metrics::record(&ctx.accounts.metrics, &hive, Counter::Vote, slot)?;
+    if privacy != PrivacyLevel::Encrypted {
+        standings_board::post(&ctx.accounts.standings_board, &hive, &entry)?;
+    }
+
+    match choice {
+        BallotChoice::ForAgainst(_) => emit_event!(ctx, FastVoteCast {
+            action: action_info.key(),
+            hive,
+            voter_commitment,
+            vote_count,
+            weight: 1,
+        }),
+        BallotChoice::Option(option) => {
+            let mut weights = vec![0; option_count as usize];
+            weights[option as usize] = 1;
+            emit_event!(ctx, OptionVoteCast {
+                action: action_info.key(),
+                hive,
+                voter_commitment,
+                vote_count,
+                weights,
+            })
+        }
+    }
+
+    Ok(())
+}
+
+/// Create and write the ballot's FastVote, at the agent's address; the
+/// signer pays. One create_account unless someone pre-funded the address,
+/// which then needs topping up, allocating and assigning instead.
+fn create_vote_record(ctx: &Context<VoteFast>, vote: FastVote) -> Result<()> {
+    let info = ctx.accounts.fast_vote.to_account_info();
+    let voter = ctx.accounts.voter.to_account_info();
+    let system = ctx.accounts.system_program.to_account_info();
+    require_keys_eq!(*info.owner, system_program::ID, FastVoteError::AlreadyVoted);
+
+    let seeds: &[&[u8]] = &[FAST_VOTE_SEED, vote.fast_action.as_ref(), vote.voter.as_ref(), &[vote.bump]];
+    let rent = Rent::get()?.minimum_balance(FastVote::LEN);
+    if info.lamports() == 0 {
+        create_account(
+            CpiContext::new_with_signer(
+                system,
+                CreateAccount {
+                    from: voter,
+                    to: info.clone(),
+                },
+                &[seeds],
+            ),
+            rent,
+            FastVote::LEN as u64,
+            &crate::ID,
+        )?;
+    } else {
+        let shortfall = rent.saturating_sub(info.lamports());
+        if shortfall > 0 {
+            transfer(
+                CpiContext::new(
+                    system.clone(),
+                    Transfer {
+                        from: voter,
+                        to: info.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+        allocate(
+            CpiContext::new_with_signer(
+                system.clone(),
+                Allocate {
+                    account_to_allocate: info.clone(),
+                },
+                &[seeds],
+            ),
+            FastVote::LEN as u64,
+        )?;
+        assign(
+            CpiContext::new_with_signer(
+                system,
+                Assign {
+                    account_to_assign: info.clone(),
+                },
+                &[seeds],
+            ),
+            &crate::ID,
+        )?;
+    }
+    vote.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VoteResult;
+
+    const SLOT: u64 = 500;
+
+    /// Results in both encodings: one byte, and two for those carrying a
+    /// tier or option
+    fn results() -> [VoteResult; 5] {
+        [
+            VoteResult::Pending,
+            VoteResult::Failed,
+            VoteResult::Passed { tier: 2 },
+            VoteResult::Chosen { option: 1 },
+            VoteResult::Contested { tier: 1 },
+        ]
+    }
+
+    /// An open headcount action one ballot short of its quorum and of its
+    /// threshold, with every field ActionView reads set apart from its
+    /// neighbours
+    fn action(result: VoteResult, hive_seeded: bool) -> (Pubkey, FastAction) {
+        let mut data = FastAction::DISCRIMINATOR.to_vec();
+        data.resize(FastAction::LEN, 0);
+        let mut action = FastAction::try_deserialize(&mut &data[..]).unwrap();
+        action.version = FAST_ACTION_VERSION;
+        action.action_id = 0x0102_0304_0506_0708;
+        action.action_hash = [7; 32];
+        action.description_hash = [0xff; 32];
+        action.votes_for = 5;
+        action.votes_against = 4;
+        action.vote_count = 9;
+        action.created_slot = u64::MAX;
+        action.deadline_slot = 1_000;
+        action.result = result;
+        action.vote_mode = VoteMode::Headcount;
+        action.weight_for = 40;
+        action.weight_against = 41;
+        action.hive = Pubkey::new_unique();
+        action.bond = u64::MAX;
+        action.quorum = 10;
+        action.quorum_floor = u32::MAX;
+        action.kind = ActionKind::Emergency;
+        action.max_weight_per_voter = u64::MAX;
+        action.class_max_votes = [0, 3, 2, 0];
+        action.class_weight_bps = [u16::MAX; AGENT_CLASSES];
+        action.class_votes = [1, 2, 2, 4];
+        action.delegation_epoch = 9;
+        action.audited = true;
+        action.hive_seeded = hive_seeded;
+        action.log.push(SLOT - 1, LogTag::Checkpointed, 8);
+        action.excluded_count = 2;
+        action.excluded[..2].copy_from_slice(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+        action.excluded[2] = Pubkey::new_unique();
+        action.identity_weighted = true;
+        action.member_collection = Pubkey::new_unique();
+        action.stake_lock_program = Pubkey::new_unique();
+        action.max_votes = 100;
+        action.fee_rebate = u64::MAX;
+        action.privacy = PrivacyLevel::Encrypted;
+        action.validator_counted = true;
+        action.threshold_bps = 6_000;
+        action.approval_rounding = ApprovalRounding::HalfEven;
+        action.backup_validator = Pubkey::new_unique();
+
+        let hive_seed: &[u8] = if hive_seeded { action.hive.as_ref() } else { &[] };
+        let (key, bump) = Pubkey::find_program_address(
+            &[FAST_ACTION_SEED, hive_seed, &action.action_id.to_le_bytes()],
+            &crate::ID,
+        );
+        action.bump = bump;
+        (key, action)
+    }
+
+    /// The account data holding `action`; one-byte results leave a byte of
+    /// padding at the end
+    fn account_data(action: &FastAction) -> Vec<u8> {
+        let mut data = Vec::new();
+        action.try_serialize(&mut data).unwrap();
+        assert!(data.len() <= FastAction::LEN);
+        data.resize(FastAction::LEN, 0);
+        data
+    }
+
+    #[test]
+    fn offsets_span_the_layout() {
+        // backup_validator is the last field; a two-byte result fills LEN
+        assert_eq!(APPROVAL_ROUNDING + 1 + KEY + 1, FastAction::LEN);
+        assert_eq!(LOG_APPENDED + size_of::<u32>(), EXCLUDED_COUNT);
+    }
+
+    #[test]
+    fn getters_read_the_fields() {
+        for result in results() {
+            for hive_seeded in [false, true] {
+                let (key, action) = action(result.clone(), hive_seeded);
+                let mut data = account_data(&action);
+                let view = ActionView::load(&key, &crate::ID, &mut data).unwrap();
+
+                let wide = matches!(
+                    action.result,
+                    VoteResult::Passed { .. } | VoteResult::Chosen { .. } | VoteResult::Contested { .. }
+                );
+                assert_eq!(view.shift, wide as usize);
+                assert_eq!(view.action_id(), action.action_id);
+                assert_eq!(view.action_hash(), action.action_hash);
+                assert_eq!(view.vote_mode().unwrap(), action.vote_mode);
+                assert_eq!(view.kind().unwrap(), action.kind);
+                assert_eq!(view.option_count(), action.option_count);
+                assert_eq!(view.privacy().unwrap(), action.privacy);
+                assert_eq!(view.vote_count(), action.vote_count);
+                assert_eq!(view.hive(), action.hive);
+                assert_eq!(view.member_collection(), action.member_collection);
+                assert_eq!(view.passing(), action.passing());
+                assert!(view.check_delegation_epoch(action.delegation_epoch).is_ok());
+                assert!(view.check_delegation_epoch(action.delegation_epoch + 1).is_err());
+                for voter in action.excluded {
+                    assert_eq!(view.excludes(&voter), action.excludes(&voter));
+                }
+
+                let entry = view.board_entry(key, SLOT);
+                assert_eq!(entry.action, key);
+                assert_eq!(entry.action_id, action.action_id);
+                assert_eq!(entry.deadline_slot, action.deadline_slot);
+                assert_eq!(entry.votes_for, action.votes_for);
+                assert_eq!(entry.votes_against, action.votes_against);
+                assert_eq!(entry.vote_count, action.vote_count);
+                assert_eq!(entry.weight_for, action.weight_for);
+                assert_eq!(entry.weight_against, action.weight_against);
+                assert_eq!(entry.updated_slot, SLOT);
+            }
+        }
+    }
+
+    #[test]
+    fn load_checks_the_account() {
+        let (key, action) = action(VoteResult::Passed { tier: 1 }, true);
+        let mut data = account_data(&action);
+        assert!(ActionView::load(&key, &Pubkey::new_unique(), &mut data).is_err());
+        assert!(ActionView::load(&Pubkey::new_unique(), &crate::ID, &mut data).is_err());
+        assert!(ActionView::load(&key, &crate::ID, &mut data[..FastAction::LEN - 1]).is_err());
+
+        let mut stale = action.clone();
+        stale.version -= 1;
+        assert!(ActionView::load(&key, &crate::ID, &mut account_data(&stale)).is_err());
+        let mut unseeded = action;
+        unseeded.hive_seeded = false;
+        assert!(ActionView::load(&key, &crate::ID, &mut account_data(&unseeded)).is_err());
+    }
+
+    #[test]
+    fn cast_writes_the_fields() {
+        for result in results() {
+            let (key, mut expected) = action(result, true);
+            let mut data = account_data(&expected);
+            let voter = Pubkey::new_unique();
+            let mut view = ActionView::load(&key, &crate::ID, &mut data).unwrap();
+
+            view.count_class_vote(AgentClass::Scout).unwrap();
+            assert!(view.count_class_vote(AgentClass::Worker).is_err());
+            assert_eq!(view.cast(&voter, true, &[1; 32], SLOT).unwrap(), 10);
+            assert!(view.cast(&expected.excluded[0], false, &[1; 32], SLOT).is_err());
+            assert!(view.cast(&expected.excluded[2], false, &[0; 32], SLOT).is_err());
+            assert!(view.cast(&expected.excluded[2], false, &[1; 32], expected.deadline_slot + 1).is_err());
+
+            expected.class_votes[AgentClass::Scout as usize] += 1;
+            expected.vote_count += 1;
+            expected.log.push(SLOT, LogTag::QuorumReached, 10);
+            let passing_before = expected.passing();
+            expected.votes_for += 1;
+            assert_ne!(expected.passing(), passing_before);
+            expected.log.push(SLOT, LogTag::ThresholdCrossed, expected.approval_pct().unwrap());
+            assert_eq!(data, account_data(&expected));
+
+            let decoded = FastAction::try_deserialize(&mut &data[..]).unwrap();
+            assert_eq!(decoded.result, expected.result);
+            assert_eq!(decoded.log, expected.log);
+        }
+    }
+
+    #[test]
+    fn commit_counts_only_the_ballot() {
+        for result in results() {
+            let (key, mut expected) = action(result, false);
+            let mut data = account_data(&expected);
+            let mut view = ActionView::load(&key, &crate::ID, &mut data).unwrap();
+
+            assert_eq!(view.commit(&Pubkey::new_unique(), &[1; 32], SLOT).unwrap(), 10);
+            expected.vote_count += 1;
+            expected.log.push(SLOT, LogTag::QuorumReached, 10);
+            assert_eq!(data, account_data(&expected));
+        }
+    }
+
+    #[test]
+    fn cast_option_writes_the_fields() {
+        for result in results() {
+            let (key, mut expected) = action(result, true);
+            expected.option_count = 3;
+            expected.option_weights = [u64::MAX, 4, 6, 0, 0, 0, 0, u64::MAX];
+            expected.eliminated_mask = 0b010;
+            let mut data = account_data(&expected);
+            let mut view = ActionView::load(&key, &crate::ID, &mut data).unwrap();
+
+            assert!(view.cast(&Pubkey::new_unique(), true, &[1; 32], SLOT).is_err());
+            assert!(view.cast_option(&Pubkey::new_unique(), 1, &[1; 32], SLOT).is_err());
+            assert!(view.cast_option(&Pubkey::new_unique(), 3, &[1; 32], SLOT).is_err());
+            assert_eq!(view.cast_option(&Pubkey::new_unique(), 2, &[1; 32], SLOT).unwrap(), 10);
+
+            expected.vote_count += 1;
+            expected.log.push(SLOT, LogTag::QuorumReached, 10);
+            expected.option_weights[2] += 1;
+            assert_eq!(data, account_data(&expected));
+
+            expected.ranked = true;
+            let mut data = account_data(&expected);
+            let mut view = ActionView::load(&key, &crate::ID, &mut data).unwrap();
+            assert!(view.cast_option(&Pubkey::new_unique(), 0, &[1; 32], SLOT).is_err());
+        }
+    }
+}
@@ -0,0 +1,144 @@
+// Task assignments. A TaskAssignment action is a multi-option action whose
+// options are candidate assignees; its action hash commits to the task, the
+// queue it goes to and the candidates. Once the winning option settles,
+// anyone may push the task into the queue, a kamiyo-taskqueue program
+// account, so the hive's work queue fills straight from the vote.
+//
+// The queue program receives the queue, the TASK_AUTHORITY_SEED PDA as
+// signer, the payer and the system program, then any remaining accounts,
+// with instruction data PUSH_TASK_DISCRIMINATOR followed by a borsh
+// TaskRecord; an Anchor program can implement this as
+// `push_task(task: TaskRecord)` and trust the authority's signature as
+// proof the record came from a settled vote.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+
+use crate::dispute::is_settled;
+use crate::{
+    ActionKind, FastAction, FastVoteError, TaskAssignment, TaskPushed, TaskReceipt, TaskRecord, VoteMode,
+    VoteResult, FAST_ACTION_SEED, FAST_ACTION_VERSION, PUSH_TASK_DISCRIMINATOR, TASK_AUTHORITY_SEED,
+    TASK_RECEIPT_SEED,
+};
+
+/// TaskAssignment constraints on top of the usual creation checks
+pub fn validate_task_assignment(option_count: u8, vote_mode: VoteMode) -> Result<()> {
+    require!(
+        option_count >= 2 && vote_mode != VoteMode::Optimistic,
+        FastVoteError::InvalidTaskAssignment
+    );
+    Ok(())
+}
+
+/// Permissionless, on the base layer, once the winning option has settled.
+/// Remaining accounts are passed on to the queue program.
+pub fn process_push_task<'info>(
+    ctx: Context<'_, '_, '_, 'info, PushTask<'info>>,
+    task: TaskAssignment,
+) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(task.action_hash()? == action.action_hash, FastVoteError::TaskAssignmentMismatch);
+    require!(
+        task.assignees.len() == action.option_count as usize
+            && task.queue_program != crate::ID
+            && ctx.accounts.queue_program.key() == task.queue_program
+            && ctx.accounts.queue.key() == task.queue,
+        FastVoteError::TaskAssignmentMismatch
+    );
+    let VoteResult::Chosen { option } = action.result else {
+        return err!(FastVoteError::ActionNotExecutable);
+    };
+    let slot = Clock::get()?.slot;
+    require!(is_settled(action, slot), FastVoteError::DisputeWindowOpen);
+
+    let record = TaskRecord {
+        action: action.key(),
+        task_hash: task.task_hash,
+        assignee: task.assignees[option as usize],
+        deadline_slot: task.deadline_slot,
+    };
+    let mut data = PUSH_TASK_DISCRIMINATOR.to_vec();
+    record.serialize(&mut data)?;
+
+    let authority = &ctx.accounts.task_authority;
+    let mut metas = vec![
+        AccountMeta::new(ctx.accounts.queue.key(), false),
+        AccountMeta::new_readonly(authority.key(), true),
+        AccountMeta::new(ctx.accounts.payer.key(), true),
+        AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
+    ];
+    let mut infos = vec![
+        ctx.accounts.queue.to_account_info(),
+        authority.to_account_info(),
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    ];
+    for info in ctx.remaining_accounts {
+        metas.push(AccountMeta {
+            pubkey: info.key(),
+            is_signer: false,
+            is_writable: info.is_writable,
+        });
+        infos.push(info.clone());
+    }
+    infos.push(ctx.accounts.queue_program.to_account_info());
+    invoke_signed(
+        &Instruction {
+            program_id: task.queue_program,
+            accounts: metas,
+            data,
+        },
+        &infos,
+        &[&[TASK_AUTHORITY_SEED, &[ctx.bumps.task_authority]]],
+    )?;
+
+    let receipt = &mut ctx.accounts.receipt;
+    receipt.action = record.action;
+    receipt.assignee = record.assignee;
+    receipt.pushed_slot = slot;
+    receipt.bump = ctx.bumps.receipt;
+
+    emit_event!(ctx, TaskPushed {
+        action: record.action,
+        hive: ctx.accounts.fast_action.hive,
+        queue: task.queue,
+        task_hash: record.task_hash,
+        assignee: record.assignee,
+        deadline_slot: record.deadline_slot,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct PushTask<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration,
+        constraint = fast_action.kind == ActionKind::TaskAssignment @ FastVoteError::InvalidTaskAssignment
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = payer,
+        space = TaskReceipt::LEN,
+        seeds = [TASK_RECEIPT_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, TaskReceipt>,
+    /// CHECK: The queue account; checked against the task
+    #[account(mut)]
+    pub queue: UncheckedAccount<'info>,
+    /// CHECK: Signs the push; holds nothing
+    #[account(seeds = [TASK_AUTHORITY_SEED], bump)]
+    pub task_authority: UncheckedAccount<'info>,
+    /// CHECK: The task queue program; checked against the task
+    #[account(executable)]
+    pub queue_program: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,138 @@
+// Action templates. A hive's admins fix the shape of a recurring decision
+// once (kind, vote mode, threshold, window, the schema its payloads follow and
+// the callbacks its result feeds) and creators then open actions from it with
+// create_from_template, supplying only what varies per action. Updating a
+// template leaves actions already created from it untouched.
+//
+// The schema hash is not interpreted on-chain; clients use it to check a
+// payload against the template before hashing it into `action_hash`.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    ActionCreatedFromTemplate, CallbackRegistered, CreateFastAction, FastVoteError, GlobalConfig, Role, Roles,
+    Template, TemplateArgs, TemplateParams, TemplateWritten, GLOBAL_CONFIG_SEED, ROLES_SEED, TEMPLATE_SEED,
+};
+
+pub fn process_create_template(ctx: Context<CreateTemplate>, template_id: u16, params: TemplateParams) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    require!(ctx.accounts.roles.has_role(&admin, Role::Admin), FastVoteError::MissingRole);
+
+    let template = &mut ctx.accounts.template;
+    template.hive = ctx.accounts.roles.hive;
+    template.template_id = template_id;
+    template.bump = ctx.bumps.template;
+    template.write(params, &ctx.accounts.config)?;
+
+    emit_event!(ctx, TemplateWritten {
+        template: template.key(),
+        hive: template.hive,
+        template_id,
+        kind: template.kind,
+        schema_hash: template.schema_hash,
+        written_by: admin,
+    });
+
+    Ok(())
+}
+
+pub fn process_update_template(ctx: Context<UpdateTemplate>, params: TemplateParams) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    require!(ctx.accounts.roles.has_role(&admin, Role::Admin), FastVoteError::MissingRole);
+
+    let template = &mut ctx.accounts.template;
+    template.write(params, &ctx.accounts.config)?;
+
+    emit_event!(ctx, TemplateWritten {
+        template: template.key(),
+        hive: template.hive,
+        template_id: template.template_id,
+        kind: template.kind,
+        schema_hash: template.schema_hash,
+        written_by: admin,
+    });
+
+    Ok(())
+}
+
+/// Hive-scoped only: `roles` must be the template's hive's, in which the
+/// creator holds Role::Creator. The template's default callbacks go into the
+/// action's registry, created alongside it.
+pub fn process_create_from_template(mut ctx: Context<CreateFastAction>, args: TemplateArgs) -> Result<()> {
+    let template = ctx.accounts.template.as_ref().ok_or(FastVoteError::MissingTemplate)?;
+    let hive = ctx.accounts.roles.as_ref().map_or(Pubkey::default(), |r| r.hive);
+    require_keys_eq!(template.hive, hive, FastVoteError::InvalidHive);
+    let window_slots = template.window(&ctx.accounts.config)?;
+    let template_key = template.key();
+    let schema_hash = template.schema_hash;
+    let callbacks = template.callbacks().to_vec();
+    let params = template.instantiate(args);
+
+    crate::create_action(&mut ctx, params, window_slots)?;
+
+    let action = ctx.accounts.fast_action.key();
+    if !callbacks.is_empty() {
+        let registry = ctx.accounts.callback_registry.as_mut().ok_or(FastVoteError::MissingTemplate)?;
+        registry.action = action;
+        registry.bump = ctx.bumps.callback_registry.unwrap_or_default();
+        registry.callbacks[..callbacks.len()].copy_from_slice(&callbacks);
+        registry.count = callbacks.len() as u8;
+        for (index, callback) in callbacks.iter().enumerate() {
+            emit_event!(ctx, CallbackRegistered {
+                action,
+                hive,
+                index: index as u8,
+                program_id: callback.program_id,
+                accounts_hash: callback.accounts_hash,
+            });
+        }
+    }
+
+    emit_event!(ctx, ActionCreatedFromTemplate {
+        action,
+        hive,
+        template: template_key,
+        schema_hash,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(template_id: u16)]
+pub struct CreateTemplate<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = Template::LEN,
+        seeds = [TEMPLATE_SEED, roles.hive.as_ref(), &template_id.to_le_bytes()],
+        bump
+    )]
+    pub template: Account<'info, Template>,
+    #[account(seeds = [ROLES_SEED, roles.hive.as_ref()], bump = roles.bump)]
+    pub roles: Account<'info, Roles>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// Must hold Role::Admin in the hive
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct UpdateTemplate<'info> {
+    #[account(
+        mut,
+        seeds = [TEMPLATE_SEED, template.hive.as_ref(), &template.template_id.to_le_bytes()],
+        bump = template.bump
+    )]
+    pub template: Account<'info, Template>,
+    #[account(seeds = [ROLES_SEED, template.hive.as_ref()], bump = roles.bump)]
+    pub roles: Account<'info, Roles>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// Must hold Role::Admin in the hive
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,262 @@
+// Vote weight from SPL Token / Token-2022 balances
+//
+// Token-2022 accounts are parsed by hand (base layout + TLV extensions) so the
+// program doesn't pull spl-token-2022 into the ER build. Only extensions that
+// change what a holder effectively controls are decoded. Transfer fees
+// withheld in an account (TransferFeeAmount) are not the holder's and are
+// never counted: the weight is the account's `amount` alone.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    FastVoteError, GlobalConfig, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, WEIGHT_EXT_INTEREST,
+    WEIGHT_EXT_TRANSFER_HOOK,
+};
+
+// spl-token base layouts
+const MINT_BASE_LEN: usize = 82;
+const ACCOUNT_BASE_LEN: usize = 165;
+const ACCOUNT_MINT_OFFSET: usize = 0;
+const ACCOUNT_OWNER_OFFSET: usize = 32;
+const ACCOUNT_AMOUNT_OFFSET: usize = 64;
+const ACCOUNT_STATE_OFFSET: usize = 108;
+const ACCOUNT_STATE_INITIALIZED: u8 = 1;
+const ACCOUNT_STATE_FROZEN: u8 = 2;
+
+// Token-2022: mints are padded to the account length so both share the
+// AccountType byte at 165 followed by the TLV region
+const ACCOUNT_TYPE_OFFSET: usize = ACCOUNT_BASE_LEN;
+const TLV_START: usize = ACCOUNT_BASE_LEN + 1;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+// ExtensionType discriminants
+const EXT_UNINITIALIZED: u16 = 0;
+const EXT_INTEREST_BEARING_CONFIG: u16 = 10;
+const EXT_TRANSFER_HOOK: u16 = 14;
+const EXT_TRANSFER_HOOK_ACCOUNT: u16 = 15;
+
+const SECONDS_PER_YEAR: f64 = 60.0 * 60.0 * 24.0 * 365.24;
+const ONE_IN_BASIS_POINTS: f64 = 10_000.0;
+
+/// Effective voting weight of `voter` held in `token_account` of `mint`.
+/// Only a ballot that holds a stake lock on the account may count it; a
+/// frozen account still counts, as its balance cannot move to another
+/// voter.
+pub fn read_locked_token_weight(
+    token_account: &AccountInfo,
+    mint: &AccountInfo,
+    voter: &Pubkey,
+    config: &GlobalConfig,
+    unix_timestamp: i64,
+) -> Result<u64> {
+    let token_program = *token_account.owner;
+    require!(
+        token_program == TOKEN_PROGRAM_ID || token_program == TOKEN_2022_PROGRAM_ID,
+        FastVoteError::InvalidTokenAccount
+    );
+    require!(*mint.owner == token_program, FastVoteError::InvalidWeightMint);
+
+    let account_data = token_account.try_borrow_data()?;
+    let mint_data = mint.try_borrow_data()?;
+    require!(account_data.len() >= ACCOUNT_BASE_LEN, FastVoteError::InvalidTokenAccount);
+    require!(mint_data.len() >= MINT_BASE_LEN, FastVoteError::InvalidWeightMint);
+
+    require!(
+        read_pubkey(&account_data, ACCOUNT_MINT_OFFSET) == mint.key(),
+        FastVoteError::InvalidTokenAccount
+    );
+    require!(
+        read_pubkey(&account_data, ACCOUNT_OWNER_OFFSET) == *voter,
+        FastVoteError::InvalidTokenAccount
+    );
+    let state = account_data[ACCOUNT_STATE_OFFSET];
+    require!(
+        state == ACCOUNT_STATE_INITIALIZED || state == ACCOUNT_STATE_FROZEN,
+        FastVoteError::InvalidTokenAccount
+    );
+
+    let mut weight = read_u64(&account_data, ACCOUNT_AMOUNT_OFFSET);
+
+    if token_program == TOKEN_2022_PROGRAM_ID {
+        let flags = config.weight_extensions;
+
+        if flags & WEIGHT_EXT_TRANSFER_HOOK != 0 {
+            if let Some(hook) = find_extension(&mint_data, ACCOUNT_TYPE_MINT, EXT_TRANSFER_HOOK)? {
+                // TransferHook { authority: 32, program_id: 32 }
+                require!(hook.len() >= 64, FastVoteError::InvalidTokenExtension);
+                require!(
+                    read_pubkey(hook, 32) == config.transfer_hook_program,
+                    FastVoteError::TransferHookMismatch
+                );
+            }
+            if let Some(hook_account) =
+                find_extension(&account_data, ACCOUNT_TYPE_ACCOUNT, EXT_TRANSFER_HOOK_ACCOUNT)?
+            {
+                // TransferHookAccount { transferring: bool }
+                require!(
+                    hook_account.first().copied().unwrap_or(0) == 0,
+                    FastVoteError::TokenAccountTransferring
+                );
+            }
+        }
+
+        if flags & WEIGHT_EXT_INTEREST != 0 {
+            if let Some(interest) =
+                find_extension(&mint_data, ACCOUNT_TYPE_MINT, EXT_INTEREST_BEARING_CONFIG)?
+            {
+                weight = apply_interest(weight, interest, unix_timestamp)?;
+            }
+        }
+    }
+
+    require!(weight > 0, FastVoteError::NoVotingWeight);
+    Ok(weight)
+}
+
+/// Locate an extension's value bytes in a Token-2022 account's TLV region.
+/// Returns `None` for base-size accounts that carry no extensions.
+fn find_extension(data: &[u8], account_type: u8, extension: u16) -> Result<Option<&[u8]>> {
+    if data.len() <= ACCOUNT_TYPE_OFFSET {
+        return Ok(None);
+    }
+    require!(data[ACCOUNT_TYPE_OFFSET] == account_type, FastVoteError::InvalidTokenExtension);
+
+    let mut offset = TLV_START;
+    while offset + 4 <= data.len() {
+        let ext_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let ext_len = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        if ext_type == EXT_UNINITIALIZED {
+            break;
+        }
+
+        let value_start = offset + 4;
+        let value_end = value_start
+            .checked_add(ext_len)
+            .filter(|end| *end <= data.len())
+            .ok_or(FastVoteError::InvalidTokenExtension)?;
+        if ext_type == extension {
+            return Ok(Some(&data[value_start..value_end]));
+        }
+        offset = value_end;
+    }
+
+    Ok(None)
+}
+
+/// Mirror of spl-token-2022's InterestBearingConfig UI amount scaling:
+/// amount * exp(pre_rate * pre_span) * exp(current_rate * post_span)
+fn apply_interest(amount: u64, config: &[u8], unix_timestamp: i64) -> Result<u64> {
+    // InterestBearingConfig {
+    //     rate_authority: 32, initialization_timestamp: i64, pre_update_average_rate: i16,
+    //     last_update_timestamp: i64, current_rate: i16 }
+    require!(config.len() >= 52, FastVoteError::InvalidTokenExtension);
+    let initialization_timestamp = read_i64(config, 32);
+    let pre_update_average_rate = read_i16(config, 40);
+    let last_update_timestamp = read_i64(config, 42);
+    let current_rate = read_i16(config, 50);
+
+    let pre_span = last_update_timestamp.saturating_sub(initialization_timestamp);
+    let post_span = unix_timestamp.saturating_sub(last_update_timestamp);
+    let scale = growth(pre_update_average_rate, pre_span) * growth(current_rate, post_span);
+
+    let scaled = (amount as f64) * scale;
+    require!(scaled.is_finite() && scaled >= 0.0, FastVoteError::VoteOverflow);
+    // `as` saturates at u64::MAX for out-of-range floats
+    Ok(scaled as u64)
+}
+
+fn growth(rate_bps: i16, span_seconds: i64) -> f64 {
+    let rate = rate_bps as f64 / ONE_IN_BASIS_POINTS;
+    (rate * span_seconds as f64 / SECONDS_PER_YEAR).exp()
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&data[offset..offset + 32]);
+    Pubkey::new_from_array(bytes)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn read_i64(data: &[u8], offset: usize) -> i64 {
+    read_u64(data, offset) as i64
+}
+
+fn read_i16(data: &[u8], offset: usize) -> i16 {
+    i16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Token-2022 account of `mint` owned by `owner` holding `amount`, with
+    /// `withheld` in its TransferFeeAmount extension
+    fn fee_account(mint: &Pubkey, owner: &Pubkey, amount: u64, withheld: u64) -> Vec<u8> {
+        let mut data = vec![0; ACCOUNT_BASE_LEN];
+        data[ACCOUNT_MINT_OFFSET..ACCOUNT_MINT_OFFSET + 32].copy_from_slice(mint.as_ref());
+        data[ACCOUNT_OWNER_OFFSET..ACCOUNT_OWNER_OFFSET + 32].copy_from_slice(owner.as_ref());
+        data[ACCOUNT_AMOUNT_OFFSET..ACCOUNT_AMOUNT_OFFSET + 8].copy_from_slice(&amount.to_le_bytes());
+        data[ACCOUNT_STATE_OFFSET] = ACCOUNT_STATE_INITIALIZED;
+        data.push(ACCOUNT_TYPE_ACCOUNT);
+        // TransferFeeAmount, ExtensionType 2
+        data.extend_from_slice(&2u16.to_le_bytes());
+        data.extend_from_slice(&8u16.to_le_bytes());
+        data.extend_from_slice(&withheld.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn withheld_fees_never_count() {
+        let (account_key, mint_key, voter) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut account_data = fee_account(&mint_key, &voter, 100, 50);
+        let mut mint_data = vec![0; MINT_BASE_LEN];
+        let (mut account_lamports, mut mint_lamports) = (0, 0);
+        let account = AccountInfo::new(
+            &account_key,
+            false,
+            false,
+            &mut account_lamports,
+            &mut account_data,
+            &TOKEN_2022_PROGRAM_ID,
+            false,
+            0,
+        );
+        let mint = AccountInfo::new(
+            &mint_key,
+            false,
+            false,
+            &mut mint_lamports,
+            &mut mint_data,
+            &TOKEN_2022_PROGRAM_ID,
+            false,
+            0,
+        );
+
+        let mut data = GlobalConfig::DISCRIMINATOR.to_vec();
+        data.resize(GlobalConfig::LEN, 0);
+        let mut config = GlobalConfig::try_deserialize(&mut &data[..]).unwrap();
+        // Every bit, the retired withheld-fees one included
+        config.weight_extensions = u8::MAX;
+        assert_eq!(read_locked_token_weight(&account, &mint, &voter, &config, 0).unwrap(), 100);
+
+        // Withheld fees alone are no weight
+        let mut account_data = fee_account(&mint_key, &voter, 0, 50);
+        let account = AccountInfo::new(
+            &account_key,
+            false,
+            false,
+            &mut account_lamports,
+            &mut account_data,
+            &TOKEN_2022_PROGRAM_ID,
+            false,
+            0,
+        );
+        assert!(read_locked_token_weight(&account, &mint, &voter, &config, 0).is_err());
+    }
+}
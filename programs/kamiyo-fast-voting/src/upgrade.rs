@@ -0,0 +1,123 @@
+// Governed program upgrades. Hand a program's upgrade authority to the
+// UPGRADE_AUTHORITY_SEED PDA and it can only be upgraded by a passed
+// ProgramUpgrade action, once settled and past UPGRADE_TIMELOCK_SLOTS. The
+// action hash commits to the program and buffer; the loader only accepts a
+// buffer whose authority is also the PDA, and that freezes its contents, so
+// voters should check the buffer is already handed over before voting.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::solana_program::program::invoke_signed;
+
+use crate::config_change::is_token_majority;
+use crate::{
+    market, ActionKind, FastAction, FastVoteError, GlobalConfig, ProgramUpgrade, ProgramUpgraded, UpgradeReceipt,
+    VoteMode, FAST_ACTION_SEED, FAST_ACTION_VERSION, PROGRAM_UPGRADE_SEED, UPGRADE_AUTHORITY_SEED,
+    UPGRADE_TIMELOCK_SLOTS,
+};
+
+/// ProgramUpgrade actions are held to the same vote as ConfigChange ones
+pub fn validate_upgrade(
+    threshold: u8,
+    vote_mode: VoteMode,
+    option_count: u8,
+    config: &GlobalConfig,
+) -> Result<()> {
+    require!(
+        is_token_majority(threshold, vote_mode, option_count, config),
+        FastVoteError::InvalidUpgradeAction
+    );
+    Ok(())
+}
+
+/// Permissionless, on the base layer. The buffer's lamports go to `payer`.
+pub fn process_execute_upgrade(ctx: Context<ExecuteUpgrade>, upgrade: ProgramUpgrade) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(upgrade.action_hash() == action.action_hash, FastVoteError::UpgradeMismatch);
+    let slot = Clock::get()?.slot;
+    require!(market::is_executable(action, slot), FastVoteError::ActionNotExecutable);
+    require!(
+        slot >= action.finalized_slot.saturating_add(UPGRADE_TIMELOCK_SLOTS),
+        FastVoteError::TimelockActive
+    );
+
+    let authority = ctx.accounts.upgrade_authority.key();
+    let seeds: &[&[u8]] = &[UPGRADE_AUTHORITY_SEED, &[ctx.bumps.upgrade_authority]];
+    invoke_signed(
+        &bpf_loader_upgradeable::upgrade(&upgrade.program, &upgrade.buffer, &authority, &ctx.accounts.payer.key()),
+        &[
+            ctx.accounts.program_data.to_account_info(),
+            ctx.accounts.target_program.to_account_info(),
+            ctx.accounts.buffer.to_account_info(),
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.rent.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            ctx.accounts.upgrade_authority.to_account_info(),
+        ],
+        &[seeds],
+    )?;
+
+    let receipt = &mut ctx.accounts.receipt;
+    receipt.action = action.key();
+    receipt.program = upgrade.program;
+    receipt.buffer = upgrade.buffer;
+    receipt.upgraded_slot = slot;
+    receipt.bump = ctx.bumps.receipt;
+
+    emit_event!(ctx, ProgramUpgraded {
+        action: receipt.action,
+        hive: action.hive,
+        action_id: action.action_id,
+        program: upgrade.program,
+        buffer: upgrade.buffer,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(upgrade: ProgramUpgrade)]
+pub struct ExecuteUpgrade<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration,
+        constraint = fast_action.kind == ActionKind::ProgramUpgrade @ FastVoteError::InvalidUpgradeAction
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = payer,
+        space = UpgradeReceipt::LEN,
+        seeds = [PROGRAM_UPGRADE_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, UpgradeReceipt>,
+    /// CHECK: Program being upgraded; the loader checks it against its data
+    /// (`program` is taken by event-cpi)
+    #[account(mut, address = upgrade.program @ FastVoteError::UpgradeMismatch)]
+    pub target_program: UncheckedAccount<'info>,
+    /// CHECK: The program's ProgramData account
+    #[account(
+        mut,
+        seeds = [upgrade.program.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID
+    )]
+    pub program_data: UncheckedAccount<'info>,
+    /// CHECK: Buffer holding the new program; the loader checks its authority
+    #[account(mut, address = upgrade.buffer @ FastVoteError::UpgradeMismatch)]
+    pub buffer: UncheckedAccount<'info>,
+    /// CHECK: Signing PDA; holds no data
+    #[account(seeds = [UPGRADE_AUTHORITY_SEED], bump)]
+    pub upgrade_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+    /// CHECK: BPF upgradeable loader
+    #[account(address = bpf_loader_upgradeable::ID)]
+    pub bpf_loader: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,247 @@
+// TEE validator registry: validators register and heartbeat, governance
+// (the config admin) allowlists or denylists them
+//
+// The registry also admits delegations. An ER session holds only so many
+// actions before its commits fall behind, so each record counts the actions
+// delegated to its validator and the config admin may cap that count. A
+// delegation pinned to a validator at the cap is refused with
+// ValidatorAtCapacity, and the one that reaches the cap emits
+// ValidatorSaturated, so creators can pick another validator before theirs
+// fails. An action leaves the count through release_validator once it is
+// back on the base layer, or when the expiry sweep closes it.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+
+use crate::{
+    FastAction, FastVoteError, GlobalConfig, ValidatorRecord, ValidatorRegistered, ValidatorReleased,
+    ValidatorStatus, ValidatorStatusChanged, FAST_ACTION_SEED, FAST_ACTION_VERSION, GLOBAL_CONFIG_SEED,
+    MIN_VALIDATOR_BOND, VALIDATOR_SEED,
+};
+
+/// Check `validator` is allowlisted and has heartbeated recently.
+pub fn require_live_validator(record: Option<&Account<ValidatorRecord>>, validator: &Pubkey, slot: u64) -> Result<()> {
+    let record = record.ok_or(FastVoteError::ValidatorNotRegistered)?;
+    require_keys_eq!(record.validator, *validator, FastVoteError::ValidatorNotRegistered);
+    require!(record.status == ValidatorStatus::Allowed, FastVoteError::ValidatorNotAllowed);
+    require!(record.is_live(slot), FastVoteError::ValidatorNotLive);
+    Ok(())
+}
+
+/// Counts `action`, about to be delegated, against `record` of the validator
+/// it is pinned to, if any; returns whether it took the validator's last
+/// place under the config's cap
+pub fn count_action(
+    action: &mut FastAction,
+    record: Option<&mut ValidatorRecord>,
+    config: &GlobalConfig,
+) -> Result<bool> {
+    let Some(record) = record else {
+        require!(!action.validator_counted, FastVoteError::ValidatorStillCounted);
+        return Ok(false);
+    };
+    if action.validator_counted {
+        // Delegated again to the same validator, which still counts it
+        require_keys_eq!(action.delegated_validator, record.validator, FastVoteError::ValidatorStillCounted);
+        return Ok(false);
+    }
+    let cap = config.max_actions_per_validator;
+    require!(cap == 0 || record.active_actions < cap, FastVoteError::ValidatorAtCapacity);
+    record.active_actions = record.active_actions.checked_add(1).ok_or(FastVoteError::VoteOverflow)?;
+    action.validator_counted = true;
+    Ok(cap > 0 && record.active_actions == cap)
+}
+
+/// Takes a counted action off its validator's count; returns the
+/// validator's new count
+fn release(action: &mut FastAction, record: &mut ValidatorRecord) -> u32 {
+    record.active_actions = record.active_actions.saturating_sub(1);
+    action.validator_counted = false;
+    record.active_actions
+}
+
+/// release for an action leaving without release_validator; `record` is
+/// required while it is counted. Returns the validator's new count
+pub fn release_closing(action: &mut FastAction, record: Option<&mut ValidatorRecord>) -> Result<Option<u32>> {
+    if !action.validator_counted {
+        return Ok(None);
+    }
+    let record = record.ok_or(FastVoteError::ValidatorRecordRequired)?;
+    Ok(Some(release(action, record)))
+}
+
+pub fn process_register_validator(ctx: Context<RegisterValidator>, bond: u64) -> Result<()> {
+    require!(bond >= MIN_VALIDATOR_BOND, FastVoteError::BondTooLow);
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: ctx.accounts.validator_record.to_account_info(),
+            },
+        ),
+        bond,
+    )?;
+
+    let slot = Clock::get()?.slot;
+    let record = &mut ctx.accounts.validator_record;
+    record.validator = ctx.accounts.validator.key();
+    record.status = ValidatorStatus::Pending;
+    record.registered_slot = slot;
+    record.last_heartbeat_slot = slot;
+    record.bump = ctx.bumps.validator_record;
+    record.bond = bond;
+
+    emit_event!(ctx, ValidatorRegistered {
+        validator: record.validator,
+        bond,
+        slot,
+    });
+
+    Ok(())
+}
+
+pub fn process_validator_heartbeat(ctx: Context<ValidatorHeartbeat>) -> Result<()> {
+    ctx.accounts.validator_record.last_heartbeat_slot = Clock::get()?.slot;
+    Ok(())
+}
+
+pub fn process_set_validator_status(ctx: Context<SetValidatorStatus>, status: ValidatorStatus) -> Result<()> {
+    let record = &mut ctx.accounts.validator_record;
+    record.status = status;
+
+    emit_event!(ctx, ValidatorStatusChanged {
+        validator: record.validator,
+        status,
+        admin: ctx.accounts.admin.key(),
+    });
+
+    Ok(())
+}
+
+/// Permissionless, on the base layer: an action there is no longer
+/// delegated, whether it came back through a commit or force_undelegate
+pub fn process_release_validator(ctx: Context<ReleaseValidator>) -> Result<()> {
+    let action = &mut ctx.accounts.fast_action;
+    require!(action.validator_counted, FastVoteError::ActionNotCounted);
+    let active_actions = release(action, &mut ctx.accounts.validator_record);
+
+    emit_event!(ctx, ValidatorReleased {
+        validator: ctx.accounts.validator_record.validator,
+        action: ctx.accounts.fast_action.key(),
+        active_actions,
+    });
+
+    Ok(())
+}
+
+/// Permissionless; grows a record registered before `active_actions`, which
+/// starts at 0. Actions already delegated to the validator are not counted.
+pub fn process_migrate_validator_record(ctx: Context<MigrateValidatorRecord>) -> Result<()> {
+    let info = ctx.accounts.validator_record.to_account_info();
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    require!(info.data_len() == ValidatorRecord::LEGACY_LEN, FastVoteError::AlreadyMigrated);
+    require!(
+        info.try_borrow_data()?.starts_with(ValidatorRecord::DISCRIMINATOR),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+
+    let rent = Rent::get()?.minimum_balance(ValidatorRecord::LEN);
+    let shortfall = rent.saturating_sub(info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    info.realloc(ValidatorRecord::LEN, true)?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RegisterValidator<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = ValidatorRecord::LEN,
+        seeds = [VALIDATOR_SEED, validator.key().as_ref()],
+        bump
+    )]
+    pub validator_record: Account<'info, ValidatorRecord>,
+    /// Validator identity
+    pub validator: Signer<'info>,
+    /// Pays rent and the bond
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ValidatorHeartbeat<'info> {
+    #[account(
+        mut,
+        seeds = [VALIDATOR_SEED, validator.key().as_ref()],
+        bump = validator_record.bump
+    )]
+    pub validator_record: Account<'info, ValidatorRecord>,
+    pub validator: Signer<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetValidatorStatus<'info> {
+    #[account(
+        mut,
+        seeds = [VALIDATOR_SEED, validator_record.validator.as_ref()],
+        bump = validator_record.bump
+    )]
+    pub validator_record: Account<'info, ValidatorRecord>,
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ FastVoteError::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ReleaseValidator<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        mut,
+        seeds = [VALIDATOR_SEED, fast_action.delegated_validator.as_ref()],
+        bump = validator_record.bump
+    )]
+    pub validator_record: Account<'info, ValidatorRecord>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateValidatorRecord<'info> {
+    /// CHECK: The legacy layout does not deserialize as ValidatorRecord;
+    /// owner, size and discriminator are checked in
+    /// process_migrate_validator_record
+    #[account(mut, seeds = [VALIDATOR_SEED, validator.key().as_ref()], bump)]
+    pub validator_record: UncheckedAccount<'info>,
+    /// CHECK: Only keys the record
+    pub validator: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
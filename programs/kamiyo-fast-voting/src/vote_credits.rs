@@ -0,0 +1,23 @@
+// Per-agent ballot budget. Every ballot spends credits from the voter's
+// VoteCredits account, which refills to GlobalConfig::vote_credits_per_epoch
+// when the epoch rolls over; an agent out of credits waits for the next
+// epoch. Ballots cost BALLOT_COST each, so costlier ballot kinds only need a
+// different price.
+
+use anchor_lang::prelude::*;
+
+use crate::{GlobalConfig, VoteCredits, BALLOT_COST};
+
+/// Charge one ballot to `voter`, whose VoteCredits may have just been created
+pub(crate) fn charge_ballot(
+    credits: &mut Account<VoteCredits>,
+    voter: Pubkey,
+    bump: u8,
+    config: &GlobalConfig,
+) -> Result<()> {
+    if credits.agent == Pubkey::default() {
+        credits.agent = voter;
+        credits.bump = bump;
+    }
+    credits.spend(config, Clock::get()?.epoch, BALLOT_COST)
+}
@@ -0,0 +1,93 @@
+// Partial delegation of token weight. A delegator splits its balance across
+// up to MAX_DELEGATES delegates in basis points and keeps the rest, e.g.
+// 50% to A, 30% to B, 20% retained. Delegation is not transitive: a delegate
+// only passes on its own balance.
+//
+// Each action uses the split in force when it was created. A delegation keeps
+// its previous split and may change only once per DELEGATION_COOLDOWN_SLOTS,
+// longer than any voting window, so that split is always still on record and
+// a delegator cannot move weight to a second delegate mid-vote: the shares
+// and the retained part never add up to more than the balance.
+//
+// Delegated shares are counted only through weight snapshots, which fold
+// them into the delegate's leaf. A ballot on a live balance locks that
+// balance, and a delegator's tokens are not its to lock, so such a ballot
+// counts only the share its voter retained.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    DelegationShare, DelegationSplit, FastAction, FastVoteError, VoteDelegation, VoteDelegationSet,
+    DELEGATION_COOLDOWN_SLOTS, MAX_DELEGATES, VOTE_DELEGATION_SEED,
+};
+
+/// The part of `balance` the split in `own_delegation` kept for its
+/// delegator on `action`
+pub fn retained_share(action: &FastAction, own_delegation: &AccountInfo, balance: u64) -> Result<u64> {
+    let retained_bps = match VoteDelegation::load(own_delegation)? {
+        Some(delegation) => delegation.split_at(action.created_slot).retained_bps(),
+        None => 10_000,
+    };
+    Ok(share_of(balance, retained_bps))
+}
+
+fn share_of(weight: u64, bps: u16) -> u64 {
+    (weight as u128 * bps as u128 / 10_000) as u64
+}
+
+/// Replace the signer's split; an empty list revokes every delegation.
+pub fn process_set_vote_delegation(ctx: Context<SetVoteDelegation>, shares: Vec<DelegationShare>) -> Result<()> {
+    let delegator = ctx.accounts.delegator.key();
+    require!(shares.len() <= MAX_DELEGATES, FastVoteError::InvalidDelegation);
+    let mut total = 0u16;
+    for (i, share) in shares.iter().enumerate() {
+        require!(
+            share.bps > 0
+                && share.delegate != delegator
+                && share.delegate != Pubkey::default()
+                && !shares[..i].iter().any(|s| s.delegate == share.delegate),
+            FastVoteError::InvalidDelegation
+        );
+        total = total.checked_add(share.bps).ok_or(FastVoteError::InvalidDelegation)?;
+    }
+    require!(total <= 10_000, FastVoteError::InvalidDelegation);
+
+    let slot = Clock::get()?.slot;
+    let delegation = &mut ctx.accounts.vote_delegation;
+    if delegation.delegator == Pubkey::default() {
+        delegation.delegator = delegator;
+        delegation.bump = ctx.bumps.vote_delegation;
+    } else {
+        require!(
+            slot >= delegation.updated_slot.saturating_add(DELEGATION_COOLDOWN_SLOTS),
+            FastVoteError::DelegationCooldown
+        );
+    }
+
+    let mut split = DelegationSplit::default();
+    split.shares[..shares.len()].copy_from_slice(&shares);
+    split.count = shares.len() as u8;
+    delegation.previous = delegation.current;
+    delegation.current = split;
+    delegation.updated_slot = slot;
+
+    emit_event!(ctx, VoteDelegationSet { delegator, shares });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetVoteDelegation<'info> {
+    #[account(
+        init_if_needed,
+        payer = delegator,
+        space = VoteDelegation::LEN,
+        seeds = [VOTE_DELEGATION_SEED, delegator.key().as_ref()],
+        bump
+    )]
+    pub vote_delegation: Account<'info, VoteDelegation>,
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
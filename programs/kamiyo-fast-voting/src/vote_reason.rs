@@ -0,0 +1,86 @@
+// Vote reason memos. A voter may commit the hash of its reasoning onto its
+// FastVote while voting is open, in a transaction of its own, so ballots
+// stay as small as they were. Once the action is finalized anyone holding
+// the text can reveal it: the program checks it against the hash and emits
+// it, leaving the audit trail in the logs rather than in account space.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    reason_hash, FastAction, FastVote, FastVoteError, VoteReasonRevealed, VoteReasonSet, FAST_ACTION_SEED,
+    FAST_ACTION_VERSION, FAST_VOTE_SEED, MAX_REASON_LEN,
+};
+
+/// The ballot's voter only, while the action is open; once per ballot
+pub fn process_set_vote_reason(ctx: Context<SetVoteReason>, hash: [u8; 32]) -> Result<()> {
+    require!(!ctx.accounts.fast_action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(hash != [0u8; 32], FastVoteError::InvalidReason);
+    let vote = &mut ctx.accounts.fast_vote;
+    require!(vote.reason_hash == [0u8; 32], FastVoteError::ReasonAlreadySet);
+    vote.reason_hash = hash;
+
+    emit_event!(ctx, VoteReasonSet {
+        action: vote.fast_action,
+        hive: ctx.accounts.fast_action.hive,
+        voter: vote.voter,
+        reason_hash: hash,
+    });
+
+    Ok(())
+}
+
+/// Permissionless, once the action's result is final
+pub fn process_reveal_reason(ctx: Context<RevealReason>, reason: String) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(action.executed && action.result.is_tallied(), FastVoteError::ActionNotFinalized);
+    let vote = &ctx.accounts.fast_vote;
+    require!(
+        reason.len() <= MAX_REASON_LEN
+            && vote.reason_hash != [0u8; 32]
+            && reason_hash(&vote.key(), &reason) == vote.reason_hash,
+        FastVoteError::InvalidReason
+    );
+
+    emit_event!(ctx, VoteReasonRevealed {
+        action: vote.fast_action,
+        hive: action.hive,
+        voter: vote.voter,
+        reason,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetVoteReason<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        mut,
+        seeds = [FAST_VOTE_SEED, fast_action.key().as_ref(), voter.key().as_ref()],
+        bump = fast_vote.bump
+    )]
+    pub fast_vote: Account<'info, FastVote>,
+    pub voter: Signer<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RevealReason<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        seeds = [FAST_VOTE_SEED, fast_action.key().as_ref(), fast_vote.voter.as_ref()],
+        bump = fast_vote.bump
+    )]
+    pub fast_vote: Account<'info, FastVote>,
+}
@@ -0,0 +1,216 @@
+// Vote receipts: compressed NFTs that give an agent portable proof it took
+// part in an action. Once the result has settled, anyone may mint the
+// receipt for a FastVote to its voter; the FastVote is the proof, and the
+// VoteReceipt marker stops a ballot minting twice. Receipts go into the
+// Bubblegum tree recorded in the ReceiptTree PDA. The admin creates that tree
+// and hands it to the RECEIPT_AUTHORITY_SEED PDA with Bubblegum's
+// set_tree_delegate. The leaf's URI encodes the action id, the ballot and
+// the result.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+
+use crate::dispute::is_settled;
+use crate::{
+    FastAction, FastVote, FastVoteError, GlobalConfig, ReceiptTree, ReceiptTreeSet, VoteReceipt, VoteReceiptMinted,
+    BUBBLEGUM_PROGRAM_ID, FAST_ACTION_SEED, FAST_ACTION_VERSION, FAST_VOTE_SEED, GLOBAL_CONFIG_SEED,
+    MAX_RECEIPT_URI_PREFIX_LEN, RECEIPT_AUTHORITY_SEED, RECEIPT_TREE_SEED, SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+    SPL_NOOP_PROGRAM_ID, VOTE_RECEIPT_SEED,
+};
+
+/// Anchor discriminator of Bubblegum's `mint_v1`
+const MINT_V1_DISCRIMINATOR: [u8; 8] = [145, 98, 192, 118, 184, 147, 118, 104];
+
+const RECEIPT_SYMBOL: &str = "KVOTE";
+
+/// Bubblegum's MetadataArgs, in its borsh layout. Enums are written as their
+/// variant index; the options Bubblegum reads as structs are always None here.
+#[derive(AnchorSerialize)]
+struct MetadataArgs {
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    primary_sale_happened: bool,
+    is_mutable: bool,
+    edition_nonce: Option<u8>,
+    /// Some(0): TokenStandard::NonFungible
+    token_standard: Option<u8>,
+    collection: Option<u8>,
+    uses: Option<u8>,
+    /// 0: TokenProgramVersion::Original
+    token_program_version: u8,
+    creators: Vec<(Pubkey, bool, u8)>,
+}
+
+/// `prefix` followed by the action id, the ballot and the encoded result
+pub fn receipt_uri(prefix: &str, action: &FastAction, vote: &FastVote) -> String {
+    let commitment: String = vote.voter_commitment.iter().map(|b| format!("{b:02x}")).collect();
+    let [variant, tier] = action.result.encode();
+    format!(
+        "{prefix}{}?vote={}&commitment={commitment}&result={variant}-{tier}",
+        action.action_id, vote.vote_value as u8
+    )
+}
+
+/// Config admin only
+pub fn process_set_receipt_tree(ctx: Context<SetReceiptTree>, merkle_tree: Pubkey, uri_prefix: String) -> Result<()> {
+    require!(
+        merkle_tree != Pubkey::default() && uri_prefix.len() <= MAX_RECEIPT_URI_PREFIX_LEN,
+        FastVoteError::InvalidReceiptTree
+    );
+    let tree = &mut ctx.accounts.receipt_tree;
+    tree.merkle_tree = merkle_tree;
+    tree.uri_prefix = uri_prefix;
+    tree.bump = ctx.bumps.receipt_tree;
+
+    emit_event!(ctx, ReceiptTreeSet {
+        merkle_tree,
+        uri_prefix: tree.uri_prefix.clone(),
+    });
+
+    Ok(())
+}
+
+/// Permissionless, on the base layer, once the action's result has settled
+pub fn process_mint_receipt(ctx: Context<MintReceipt>) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(action.executed && action.result.is_tallied(), FastVoteError::ActionNotFinalized);
+    let slot = Clock::get()?.slot;
+    require!(is_settled(action, slot), FastVoteError::DisputeWindowOpen);
+
+    let vote = &ctx.accounts.fast_vote;
+    let metadata = MetadataArgs {
+        name: format!("KAMIYO vote {}", action.action_id),
+        symbol: RECEIPT_SYMBOL.to_string(),
+        uri: receipt_uri(&ctx.accounts.receipt_tree.uri_prefix, action, vote),
+        seller_fee_basis_points: 0,
+        primary_sale_happened: false,
+        is_mutable: false,
+        edition_nonce: None,
+        token_standard: Some(0),
+        collection: None,
+        uses: None,
+        token_program_version: 0,
+        creators: Vec::new(),
+    };
+    let mut data = MINT_V1_DISCRIMINATOR.to_vec();
+    metadata.serialize(&mut data)?;
+
+    let accounts = &ctx.accounts;
+    invoke_signed(
+        &Instruction {
+            program_id: BUBBLEGUM_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(accounts.tree_config.key(), false),
+                AccountMeta::new_readonly(accounts.voter.key(), false),
+                AccountMeta::new_readonly(accounts.voter.key(), false),
+                AccountMeta::new(accounts.merkle_tree.key(), false),
+                AccountMeta::new(accounts.payer.key(), true),
+                AccountMeta::new_readonly(accounts.receipt_authority.key(), true),
+                AccountMeta::new_readonly(accounts.log_wrapper.key(), false),
+                AccountMeta::new_readonly(accounts.compression_program.key(), false),
+                AccountMeta::new_readonly(accounts.system_program.key(), false),
+            ],
+            data,
+        },
+        &[
+            accounts.tree_config.to_account_info(),
+            accounts.voter.to_account_info(),
+            accounts.merkle_tree.to_account_info(),
+            accounts.payer.to_account_info(),
+            accounts.receipt_authority.to_account_info(),
+            accounts.log_wrapper.to_account_info(),
+            accounts.compression_program.to_account_info(),
+            accounts.system_program.to_account_info(),
+            accounts.bubblegum_program.to_account_info(),
+        ],
+        &[&[RECEIPT_AUTHORITY_SEED, &[ctx.bumps.receipt_authority]]],
+    )?;
+
+    let receipt = &mut ctx.accounts.receipt;
+    receipt.fast_vote = ctx.accounts.fast_vote.key();
+    receipt.action = ctx.accounts.fast_action.key();
+    receipt.voter = ctx.accounts.fast_vote.voter;
+    receipt.minted_slot = slot;
+    receipt.bump = ctx.bumps.receipt;
+
+    emit_event!(ctx, VoteReceiptMinted {
+        action: receipt.action,
+        hive: ctx.accounts.fast_action.hive,
+        fast_vote: receipt.fast_vote,
+        voter: receipt.voter,
+        merkle_tree: ctx.accounts.merkle_tree.key(),
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetReceiptTree<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = ReceiptTree::LEN,
+        seeds = [RECEIPT_TREE_SEED],
+        bump
+    )]
+    pub receipt_tree: Account<'info, ReceiptTree>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump, has_one = admin @ FastVoteError::Unauthorized)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct MintReceipt<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        seeds = [FAST_VOTE_SEED, fast_action.key().as_ref(), fast_vote.voter.as_ref()],
+        bump = fast_vote.bump
+    )]
+    pub fast_vote: Account<'info, FastVote>,
+    #[account(
+        init,
+        payer = payer,
+        space = VoteReceipt::LEN,
+        seeds = [VOTE_RECEIPT_SEED, fast_vote.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, VoteReceipt>,
+    #[account(seeds = [RECEIPT_TREE_SEED], bump = receipt_tree.bump)]
+    pub receipt_tree: Account<'info, ReceiptTree>,
+    /// CHECK: Signs the mint as the tree's delegate; holds nothing
+    #[account(seeds = [RECEIPT_AUTHORITY_SEED], bump)]
+    pub receipt_authority: UncheckedAccount<'info>,
+    /// CHECK: Receives the receipt; the ballot's voter
+    #[account(address = fast_vote.voter)]
+    pub voter: UncheckedAccount<'info>,
+    /// CHECK: The tree's Bubblegum TreeConfig; checked by Bubblegum
+    #[account(mut)]
+    pub tree_config: UncheckedAccount<'info>,
+    /// CHECK: The receipt tree
+    #[account(mut, address = receipt_tree.merkle_tree @ FastVoteError::InvalidReceiptTree)]
+    pub merkle_tree: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: SPL noop
+    #[account(address = SPL_NOOP_PROGRAM_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+    /// CHECK: SPL account compression
+    #[account(address = SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK: Bubblegum
+    #[account(address = BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,260 @@
+// Participation rewards. An action's authority may attach an SPL reward pool
+// before the first vote, usually in the creation transaction; the tokens sit
+// in a vault the pool PDA owns. Once the action is finalized, each voter
+// claims a pro-rata share with its FastVote: one share per headcount ballot,
+// or its weight's share of the total on token-weighted actions. After the
+// claim window closes, anyone may sweep what is left to the treasury.
+
+use anchor_lang::prelude::*;
+
+use crate::budget::transfer_checked;
+use crate::{
+    FastAction, FastVote, FastVoteError, Treasury, VoteResult, VoteRewardClaim, VoteRewardClaimed, VoteRewardPool,
+    VoteRewardsOpened, VoteRewardsSwept, FAST_ACTION_SEED, FAST_ACTION_VERSION, FAST_VOTE_SEED, TREASURY_SEED,
+    VOTE_REWARD_CLAIM_SEED, VOTE_REWARD_POOL_SEED,
+};
+
+/// Ballots count once a tally or veto has closed the action; cancelled and
+/// expired actions pay nothing
+fn is_claimable(action: &FastAction) -> bool {
+    action.executed && !matches!(action.result, VoteResult::Pending | VoteResult::Cancelled | VoteResult::Expired)
+}
+
+/// `token_account` is a token account of `mint` owned by `owner`
+fn require_token_account(token_account: &AccountInfo, mint: &Pubkey, owner: &Pubkey) -> Result<()> {
+    let data = token_account.try_borrow_data()?;
+    require!(
+        data.get(..32) == Some(mint.as_ref()) && data.get(32..64) == Some(owner.as_ref()),
+        FastVoteError::InvalidTokenAccount
+    );
+    Ok(())
+}
+
+/// Action authority only, before the first vote
+pub fn process_open_vote_rewards(ctx: Context<OpenVoteRewards>, amount: u64, claim_slots: u64) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(action.vote_count == 0, FastVoteError::ActionHasVotes);
+    require!(amount > 0 && claim_slots > 0, FastVoteError::InvalidVoteRewards);
+
+    let pool_key = ctx.accounts.pool.key();
+    let mint = ctx.accounts.mint.key();
+    require_token_account(&ctx.accounts.vault, &mint, &pool_key)?;
+    require_keys_eq!(*ctx.accounts.vault.owner, ctx.accounts.token_program.key(), FastVoteError::InvalidTokenAccount);
+    transfer_checked(
+        &ctx.accounts.token_program,
+        &ctx.accounts.funder_token_account,
+        &ctx.accounts.mint,
+        &ctx.accounts.vault,
+        &ctx.accounts.authority,
+        amount,
+        &[],
+    )?;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.action = action.key();
+    pool.mint = mint;
+    pool.vault = ctx.accounts.vault.key();
+    pool.amount = amount;
+    pool.claimed = 0;
+    pool.claim_slots = claim_slots;
+    pool.swept = false;
+    pool.bump = ctx.bumps.pool;
+
+    emit_event!(ctx, VoteRewardsOpened {
+        action: pool.action,
+        hive: action.hive,
+        mint,
+        amount,
+        claim_slots,
+    });
+
+    Ok(())
+}
+
+/// Permissionless: pays the ballot's share to a token account its voter owns
+pub fn process_claim_vote_reward(ctx: Context<ClaimVoteReward>) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(is_claimable(action), FastVoteError::ActionNotFinalized);
+    let pool = &ctx.accounts.pool;
+    require!(
+        Clock::get()?.slot <= pool.claim_deadline(action),
+        FastVoteError::RewardClaimsClosed
+    );
+
+    let vote = &ctx.accounts.fast_vote;
+    let amount = pool.share(action, vote);
+    require!(amount > 0, FastVoteError::NoRewardDue);
+    require_token_account(&ctx.accounts.voter_token_account, &pool.mint, &vote.voter)?;
+
+    let action_key = action.key();
+    let seeds: &[&[u8]] = &[VOTE_REWARD_POOL_SEED, action_key.as_ref(), &[pool.bump]];
+    transfer_checked(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault,
+        &ctx.accounts.mint,
+        &ctx.accounts.voter_token_account,
+        &ctx.accounts.pool.to_account_info(),
+        amount,
+        &[seeds],
+    )?;
+
+    let claim = &mut ctx.accounts.claim;
+    claim.fast_vote = vote.key();
+    claim.amount = amount;
+    claim.bump = ctx.bumps.claim;
+    let pool = &mut ctx.accounts.pool;
+    pool.claimed = pool.claimed.checked_add(amount).ok_or(FastVoteError::VoteOverflow)?;
+
+    emit_event!(ctx, VoteRewardClaimed {
+        action: action_key,
+        hive: action.hive,
+        voter: ctx.accounts.fast_vote.voter,
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Permissionless once the claim window has closed
+pub fn process_sweep_vote_rewards(ctx: Context<SweepVoteRewards>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    require!(!pool.swept, FastVoteError::RewardsNotSweepable);
+    require!(
+        Clock::get()?.slot > pool.claim_deadline(&ctx.accounts.fast_action),
+        FastVoteError::RewardsNotSweepable
+    );
+    require_token_account(&ctx.accounts.treasury_token_account, &pool.mint, &ctx.accounts.treasury.key())?;
+
+    let amount = pool.amount - pool.claimed;
+    if amount > 0 {
+        let seeds: &[&[u8]] = &[VOTE_REWARD_POOL_SEED, pool.action.as_ref(), &[pool.bump]];
+        transfer_checked(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.treasury_token_account,
+            &ctx.accounts.pool.to_account_info(),
+            amount,
+            &[seeds],
+        )?;
+    }
+    let pool = &mut ctx.accounts.pool;
+    pool.swept = true;
+
+    emit_event!(ctx, VoteRewardsSwept {
+        action: pool.action,
+        hive: ctx.accounts.fast_action.hive,
+        amount,
+    });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct OpenVoteRewards<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        has_one = authority @ FastVoteError::Unauthorized,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = authority,
+        space = VoteRewardPool::LEN,
+        seeds = [VOTE_REWARD_POOL_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, VoteRewardPool>,
+    /// CHECK: The reward mint; checked by the token program
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: Token account of `mint` owned by `pool`; checked in
+    /// process_open_vote_rewards
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: The authority's token account; checked by the token program
+    #[account(mut)]
+    pub funder_token_account: UncheckedAccount<'info>,
+    /// CHECK: SPL Token or Token-2022; checked in transfer_checked
+    pub token_program: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ClaimVoteReward<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        seeds = [FAST_VOTE_SEED, fast_action.key().as_ref(), fast_vote.voter.as_ref()],
+        bump = fast_vote.bump
+    )]
+    pub fast_vote: Account<'info, FastVote>,
+    #[account(
+        mut,
+        seeds = [VOTE_REWARD_POOL_SEED, fast_action.key().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, VoteRewardPool>,
+    #[account(
+        init,
+        payer = payer,
+        space = VoteRewardClaim::LEN,
+        seeds = [VOTE_REWARD_CLAIM_SEED, fast_vote.key().as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, VoteRewardClaim>,
+    /// CHECK: The pool's mint
+    #[account(address = pool.mint @ FastVoteError::InvalidTokenAccount)]
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: The pool's vault
+    #[account(mut, address = pool.vault @ FastVoteError::InvalidTokenAccount)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: Owned by the voter; checked in process_claim_vote_reward
+    #[account(mut)]
+    pub voter_token_account: UncheckedAccount<'info>,
+    /// CHECK: SPL Token or Token-2022; checked in transfer_checked
+    pub token_program: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SweepVoteRewards<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        mut,
+        seeds = [VOTE_REWARD_POOL_SEED, fast_action.key().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, VoteRewardPool>,
+    /// CHECK: The pool's mint
+    #[account(address = pool.mint @ FastVoteError::InvalidTokenAccount)]
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: The pool's vault
+    #[account(mut, address = pool.vault @ FastVoteError::InvalidTokenAccount)]
+    pub vault: UncheckedAccount<'info>,
+    #[account(seeds = [TREASURY_SEED], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    /// CHECK: Owned by the treasury; checked in process_sweep_vote_rewards
+    #[account(mut)]
+    pub treasury_token_account: UncheckedAccount<'info>,
+    /// CHECK: SPL Token or Token-2022; checked in transfer_checked
+    pub token_program: UncheckedAccount<'info>,
+}
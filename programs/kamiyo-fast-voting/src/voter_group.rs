@@ -0,0 +1,250 @@
+// Threshold-key voter groups. Some voters are committees whose members each
+// hold a share of a FROST (or other threshold) ed25519 key. A FROST
+// aggregate signature is an ordinary ed25519 signature under the group key,
+// so the ed25519 program verifies it like any other and the program never
+// sees the shares. The config admin registers the group key with its members
+// in a VoterGroup PDA; the group then votes once per action as a single
+// voter, keyed by the group key, with the VoterGroup's weight on
+// token-weighted actions.
+//
+// The ballot is the signed Ballot message also used as equivocation
+// evidence, verified by an ed25519 instruction earlier in the transaction.
+// Anyone may submit it and pay for the FastVote, usually a member or a
+// relayer; the group key never signs a transaction. An action may pin the
+// relayers it takes group ballots from; see relayers. Members keep their own
+// identities and may still vote under them.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::ID as INSTRUCTIONS_SYSVAR_ID;
+
+use crate::equivocation::verified_signatures;
+use crate::{
+    agent_registry, freeze, metrics, record_vote, relayers, vote_credits, Ballot, Counter, FastAction, FastVote,
+    FastVoteError, GlobalConfig, VoteCredits, VoteMode, VoterGroup, VoterGroupRemoved, VoterGroupSet,
+    ACTION_RELAYERS_SEED, AGENT_SEED, FAST_ACTION_SEED, FAST_ACTION_VERSION, FAST_VOTE_SEED, GLOBAL_CONFIG_SEED,
+    HIVE_FREEZE_SEED, MAX_GROUP_MEMBERS, SELF_SUBMIT_SEED, VOTER_GROUP_SEED, VOTE_CREDITS_SEED,
+};
+
+/// Config admin only; registers the group or replaces its members,
+/// threshold and weight
+pub fn process_set_voter_group(
+    ctx: Context<SetVoterGroup>,
+    members: Vec<Pubkey>,
+    threshold: u8,
+    weight: u64,
+) -> Result<()> {
+    require!(
+        !members.is_empty()
+            && members.len() <= MAX_GROUP_MEMBERS
+            && threshold >= 1
+            && threshold as usize <= members.len()
+            && weight > 0,
+        FastVoteError::InvalidVoterGroup
+    );
+    for (i, member) in members.iter().enumerate() {
+        require!(!members[..i].contains(member), FastVoteError::InvalidVoterGroup);
+    }
+
+    let group = &mut ctx.accounts.voter_group;
+    if group.registered_slot == 0 {
+        group.registered_slot = Clock::get()?.slot;
+    }
+    group.group_key = ctx.accounts.group_key.key();
+    group.members = members.clone();
+    group.threshold = threshold;
+    group.weight = weight;
+    group.bump = ctx.bumps.voter_group;
+
+    emit_event!(ctx, VoterGroupSet {
+        group_key: group.group_key,
+        members,
+        threshold,
+        weight,
+    });
+
+    Ok(())
+}
+
+/// Config admin only. Closes the group; its rent goes to the admin.
+pub fn process_remove_voter_group(ctx: Context<RemoveVoterGroup>) -> Result<()> {
+    emit_event!(ctx, VoterGroupRemoved {
+        group_key: ctx.accounts.voter_group.group_key,
+    });
+    Ok(())
+}
+
+/// Ballot signed by the group key, on a headcount or token-weighted action
+pub fn process_vote_fast_group(
+    ctx: Context<VoteFastGroup>,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+    delegation_epoch: u32,
+) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    action.check_delegation_epoch(delegation_epoch)?;
+    freeze::require_live(&ctx.accounts.hive_freeze, action)?;
+    let weighted = match action.vote_mode {
+        VoteMode::Headcount => false,
+        VoteMode::TokenWeighted => {
+            require!(
+                !action.identity_weighted
+                    && action.stake_lock_program == Pubkey::default()
+                    && action.quorum_weight_bps == 0,
+                FastVoteError::WrongVoteMode
+            );
+            true
+        }
+        VoteMode::Optimistic => return err!(FastVoteError::WrongVoteMode),
+    };
+
+    let group_key = ctx.accounts.voter_group.group_key;
+    let message = Ballot {
+        action: action.key(),
+        choice: vote_value as u8,
+    }
+    .message()?;
+    require!(
+        verified_signatures(&ctx.accounts.instructions)?
+            .iter()
+            .any(|(signer, signed)| *signer == group_key && *signed == message),
+        FastVoteError::MissingBallotSignature
+    );
+
+    relayers::require_submitter(
+        &ctx.accounts.action_relayers,
+        &ctx.accounts.self_submit,
+        ctx.accounts.submitter.key,
+        &ctx.accounts.voter_group.members,
+        Clock::get()?.slot,
+    )?;
+
+    agent_registry::require_eligible(&ctx.accounts.agent_record, &ctx.accounts.config)?;
+    let class = agent_registry::count_class_vote(&mut ctx.accounts.fast_action, &ctx.accounts.agent_record)?;
+    let weight = if weighted {
+        let clock = Clock::get()?;
+        let weight = ctx.accounts.voter_group.weight;
+        let weight =
+            agent_registry::aged_weight(&ctx.accounts.agent_record, &ctx.accounts.config, weight, clock.epoch)?;
+        let action = &ctx.accounts.fast_action;
+        let weight = action.early_weight(weight, clock.slot);
+        action.capped_weight(action.class_weight(class, weight))
+    } else {
+        1
+    };
+    vote_credits::charge_ballot(
+        &mut ctx.accounts.vote_credits,
+        group_key,
+        ctx.bumps.vote_credits,
+        &ctx.accounts.config,
+    )?;
+
+    let event = record_vote(
+        &mut ctx.accounts.fast_action,
+        &mut ctx.accounts.fast_vote,
+        group_key,
+        ctx.bumps.fast_vote,
+        vote_value,
+        voter_commitment,
+        weight,
+    )?;
+    metrics::record(&ctx.accounts.metrics, &ctx.accounts.fast_action.hive, Counter::Vote, Clock::get()?.slot)?;
+    emit_event!(ctx, event);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetVoterGroup<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = VoterGroup::LEN,
+        seeds = [VOTER_GROUP_SEED, group_key.key().as_ref()],
+        bump
+    )]
+    pub voter_group: Account<'info, VoterGroup>,
+    /// CHECK: The group's aggregate public key; never signs
+    pub group_key: UncheckedAccount<'info>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump, has_one = admin @ FastVoteError::Unauthorized)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RemoveVoterGroup<'info> {
+    #[account(
+        mut,
+        close = admin,
+        seeds = [VOTER_GROUP_SEED, voter_group.group_key.as_ref()],
+        bump = voter_group.bump
+    )]
+    pub voter_group: Account<'info, VoterGroup>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump, has_one = admin @ FastVoteError::Unauthorized)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(action_id: u64)]
+pub struct VoteFastGroup<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = submitter,
+        space = FastVote::LEN,
+        seeds = [FAST_VOTE_SEED, fast_action.key().as_ref(), voter_group.group_key.as_ref()],
+        bump
+    )]
+    pub fast_vote: Account<'info, FastVote>,
+    #[account(
+        init_if_needed,
+        payer = submitter,
+        space = VoteCredits::LEN,
+        seeds = [VOTE_CREDITS_SEED, voter_group.group_key.as_ref()],
+        bump
+    )]
+    pub vote_credits: Account<'info, VoteCredits>,
+    #[account(seeds = [VOTER_GROUP_SEED, voter_group.group_key.as_ref()], bump = voter_group.bump)]
+    pub voter_group: Account<'info, VoterGroup>,
+    /// CHECK: The group key's AgentRecord PDA; read in agent_registry if it
+    /// exists
+    #[account(seeds = [AGENT_SEED, voter_group.group_key.as_ref()], bump)]
+    pub agent_record: UncheckedAccount<'info>,
+    /// CHECK: The instructions sysvar
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions: UncheckedAccount<'info>,
+    /// CHECK: The action's ActionRelayers PDA; read in
+    /// relayers::require_submitter if it exists
+    #[account(seeds = [ACTION_RELAYERS_SEED, fast_action.key().as_ref()], bump)]
+    pub action_relayers: UncheckedAccount<'info>,
+    /// CHECK: The group's SelfSubmit PDA on the action; read in
+    /// relayers::require_submitter if it exists
+    #[account(seeds = [SELF_SUBMIT_SEED, fast_action.key().as_ref(), voter_group.group_key.as_ref()], bump)]
+    pub self_submit: UncheckedAccount<'info>,
+    /// Pays for the ballot; a pinned relayer if the action has any
+    #[account(mut)]
+    pub submitter: Signer<'info>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// CHECK: The ER's Metrics PDA, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
+    /// CHECK: The HiveFreeze PDA of the action's hive, whether or not it
+    /// exists; read in freeze::require_live
+    #[account(seeds = [HIVE_FREEZE_SEED, fast_action.hive.as_ref()], bump)]
+    pub hive_freeze: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,91 @@
+// Per-voter weight caps for token-weighted actions, so a single large holder
+// cannot decide an action alone. A cap is set per action or, failing that,
+// per ActionKind in the WeightCaps PDA, and is resolved to an absolute
+// weight at creation: supply-relative caps read the weight mint's supply
+// then, so later minting does not move them. Ballots above the cap count
+// at the cap.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    ActionKind, FastVoteError, GlobalConfig, VoteMode, WeightCap, WeightCapSet, WeightCaps, GLOBAL_CONFIG_SEED,
+    WEIGHT_CAPS_SEED,
+};
+
+// spl-token Mint.supply offset
+const MINT_SUPPLY_OFFSET: usize = 36;
+
+/// Absolute cap for a new action; 0 = uncapped. An explicit cap needs a
+/// token-weighted action; a per-kind default is ignored on other actions.
+pub fn resolve(
+    explicit: Option<WeightCap>,
+    default: Option<WeightCap>,
+    vote_mode: VoteMode,
+    weight_mint: Option<&AccountInfo>,
+) -> Result<u64> {
+    if explicit.is_some() {
+        require!(vote_mode == VoteMode::TokenWeighted, FastVoteError::InvalidWeightCap);
+    } else if vote_mode != VoteMode::TokenWeighted {
+        return Ok(0);
+    }
+    match explicit.or(default) {
+        None => Ok(0),
+        Some(cap) => {
+            validate(cap)?;
+            match cap {
+                WeightCap::Absolute(max) => Ok(max),
+                WeightCap::SupplyBps(bps) => {
+                    let mint = weight_mint.ok_or(FastVoteError::MissingWeightAccounts)?;
+                    let data = mint.try_borrow_data()?;
+                    let supply = data
+                        .get(MINT_SUPPLY_OFFSET..MINT_SUPPLY_OFFSET + 8)
+                        .ok_or(FastVoteError::InvalidWeightMint)?;
+                    let supply = u64::from_le_bytes(supply.try_into().unwrap());
+                    // Never round a cap down to "uncapped"
+                    Ok(((supply as u128 * bps as u128 / 10_000) as u64).max(1))
+                }
+            }
+        }
+    }
+}
+
+fn validate(cap: WeightCap) -> Result<()> {
+    let valid = match cap {
+        WeightCap::Absolute(max) => max > 0,
+        WeightCap::SupplyBps(bps) => bps > 0 && bps <= 10_000,
+    };
+    require!(valid, FastVoteError::InvalidWeightCap);
+    Ok(())
+}
+
+/// Config admin only. `None` clears the kind's default.
+pub fn process_set_weight_cap(ctx: Context<SetWeightCap>, kind: ActionKind, cap: Option<WeightCap>) -> Result<()> {
+    if let Some(cap) = cap {
+        validate(cap)?;
+    }
+    let caps = &mut ctx.accounts.weight_caps;
+    caps.caps[kind as usize] = cap;
+    caps.bump = ctx.bumps.weight_caps;
+
+    emit_event!(ctx, WeightCapSet { kind, cap });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetWeightCap<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = WeightCaps::LEN,
+        seeds = [WEIGHT_CAPS_SEED],
+        bump
+    )]
+    pub weight_caps: Account<'info, WeightCaps>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump, has_one = admin @ FastVoteError::Unauthorized)]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,251 @@
+// Weight snapshots. Reading live token accounts inside the rollup lets
+// weight move between wallets mid-vote and ties every ballot to a token
+// account the ER has to clone. Instead, before the first vote, the config's
+// snapshotter or an allowlisted TEE validator may post a Merkle root of
+// (voter, weight) pairs taken at the action's creation slot; delegated
+// weight is folded into the delegate's leaf. From then on the action only
+// takes vote_fast_snapshot ballots, each proving its weight against the
+// root, and vote_fast_weighted turns it away. An action weighed by a
+// weight strategy takes no snapshot.
+//
+// Leaves are sha256("kamiyo_weight" || voter || weight as u64 LE); each node
+// hashes its two children in ascending order, so proofs carry no directions.
+//
+// The poster also attests the leaves' total weight, which the action keeps
+// as `snapshot_weight`. An action created with a weight quorum counts its
+// quorum against that total instead of in heads, so it only takes snapshot
+// ballots and cannot tally until a snapshot is posted.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::{
+    agent_registry, freeze, metrics, record_vote, vote_credits, Counter, FastAction, FastVote, FastVoteError,
+    GlobalConfig, ValidatorRecord, ValidatorStatus, VoteCredits, VoteMode, WeightSnapshot, WeightSnapshotPosted,
+    AGENT_SEED, FAST_ACTION_SEED, FAST_ACTION_VERSION, FAST_VOTE_SEED, GLOBAL_CONFIG_SEED, HIVE_FREEZE_SEED,
+    MAX_WEIGHT_PROOF_LEN, VALIDATOR_SEED, VOTE_CREDITS_SEED, WEIGHT_SNAPSHOT_SEED, WEIGHT_STRATEGY_SEED,
+};
+
+const WEIGHT_LEAF_DOMAIN: &[u8] = b"kamiyo_weight";
+
+pub fn weight_leaf(voter: &Pubkey, weight: u64) -> [u8; 32] {
+    hashv(&[WEIGHT_LEAF_DOMAIN, voter.as_ref(), &weight.to_le_bytes()]).to_bytes()
+}
+
+/// Root `proof` leads to from `leaf`
+pub fn proof_root(leaf: [u8; 32], proof: &[[u8; 32]]) -> [u8; 32] {
+    proof.iter().fold(leaf, |node, sibling| {
+        let (left, right) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+        hashv(&[&left, &right]).to_bytes()
+    })
+}
+
+/// A weight quorum suits only actions weighed by a snapshot: token-weighted
+/// for/against ones, without an adaptive quorum, identity weights or stake
+/// locks
+pub fn validate_weight_quorum(
+    quorum_weight_bps: u16,
+    vote_mode: VoteMode,
+    option_count: u8,
+    adaptive_quorum: bool,
+    identity_weighted: bool,
+    lock_stake: bool,
+) -> Result<()> {
+    if quorum_weight_bps > 0 {
+        require!(
+            quorum_weight_bps <= 10_000
+                && vote_mode == VoteMode::TokenWeighted
+                && option_count == 0
+                && !adaptive_quorum
+                && !identity_weighted
+                && !lock_stake,
+            FastVoteError::InvalidWeightQuorum
+        );
+    }
+    Ok(())
+}
+
+/// The config's snapshotter or an allowlisted validator, before the first
+/// vote, on the base layer
+pub fn process_post_weight_snapshot(
+    ctx: Context<PostWeightSnapshot>,
+    root: [u8; 32],
+    total_weight: u64,
+) -> Result<()> {
+    let poster = ctx.accounts.poster.key();
+    let snapshotter = ctx.accounts.config.snapshotter;
+    let allowlisted = ctx
+        .accounts
+        .validator_record
+        .as_ref()
+        .is_some_and(|record| record.status == ValidatorStatus::Allowed);
+    require!(
+        (snapshotter != Pubkey::default() && poster == snapshotter) || allowlisted,
+        FastVoteError::Unauthorized
+    );
+
+    let action = &ctx.accounts.fast_action;
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(action.vote_count == 0, FastVoteError::ActionHasVotes);
+    require!(
+        action.vote_mode == VoteMode::TokenWeighted
+            && action.option_count == 0
+            && !action.identity_weighted
+            && action.stake_lock_program == Pubkey::default()
+            && ctx.accounts.weight_strategy.data_is_empty()
+            && root != [0u8; 32]
+            && total_weight > 0,
+        FastVoteError::InvalidWeightSnapshot
+    );
+
+    let action = &mut ctx.accounts.fast_action;
+    action.snapshot_weight = total_weight;
+    let snapshot = &mut ctx.accounts.weight_snapshot;
+    snapshot.action = action.key();
+    snapshot.root = root;
+    snapshot.snapshot_slot = action.created_slot;
+    snapshot.posted_by = poster;
+    snapshot.bump = ctx.bumps.weight_snapshot;
+    snapshot.total_weight = total_weight;
+
+    emit_event!(ctx, WeightSnapshotPosted {
+        action: snapshot.action,
+        hive: action.hive,
+        root,
+        snapshot_slot: snapshot.snapshot_slot,
+        total_weight,
+        posted_by: poster,
+    });
+
+    Ok(())
+}
+
+/// A token-weighted ballot whose weight is proven against the action's
+/// snapshot rather than read from a token account
+pub fn process_vote_fast_snapshot(
+    ctx: Context<VoteFastSnapshot>,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+    delegation_epoch: u32,
+    weight: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    ctx.accounts.fast_action.check_delegation_epoch(delegation_epoch)?;
+    freeze::require_live(&ctx.accounts.hive_freeze, &ctx.accounts.fast_action)?;
+    let voter = ctx.accounts.voter.key();
+    require!(
+        proof.len() <= MAX_WEIGHT_PROOF_LEN
+            && proof_root(weight_leaf(&voter, weight), &proof) == ctx.accounts.weight_snapshot.root,
+        FastVoteError::InvalidWeightProof
+    );
+    require!(weight > 0, FastVoteError::NoVotingWeight);
+
+    agent_registry::require_eligible(&ctx.accounts.agent_record, &ctx.accounts.config)?;
+    let class = agent_registry::count_class_vote(&mut ctx.accounts.fast_action, &ctx.accounts.agent_record)?;
+    let clock = Clock::get()?;
+    let weight = agent_registry::aged_weight(&ctx.accounts.agent_record, &ctx.accounts.config, weight, clock.epoch)?;
+    let action = &ctx.accounts.fast_action;
+    let weight = action.early_weight(weight, clock.slot);
+    let weight = action.capped_weight(action.class_weight(class, weight));
+    vote_credits::charge_ballot(
+        &mut ctx.accounts.vote_credits,
+        voter,
+        ctx.bumps.vote_credits,
+        &ctx.accounts.config,
+    )?;
+
+    let event = record_vote(
+        &mut ctx.accounts.fast_action,
+        &mut ctx.accounts.fast_vote,
+        voter,
+        ctx.bumps.fast_vote,
+        vote_value,
+        voter_commitment,
+        weight,
+    )?;
+    metrics::record(&ctx.accounts.metrics, &ctx.accounts.fast_action.hive, Counter::Vote, Clock::get()?.slot)?;
+    emit_event!(ctx, event);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct PostWeightSnapshot<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = poster,
+        space = WeightSnapshot::LEN,
+        seeds = [WEIGHT_SNAPSHOT_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub weight_snapshot: Account<'info, WeightSnapshot>,
+    /// CHECK: The action's WeightStrategy PDA; must not exist
+    #[account(seeds = [WEIGHT_STRATEGY_SEED, fast_action.key().as_ref()], bump)]
+    pub weight_strategy: UncheckedAccount<'info>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// Required unless the poster is the config's snapshotter
+    #[account(seeds = [VALIDATOR_SEED, poster.key().as_ref()], bump = validator_record.bump)]
+    pub validator_record: Option<Account<'info, ValidatorRecord>>,
+    #[account(mut)]
+    pub poster: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(action_id: u64)]
+pub struct VoteFastSnapshot<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = voter,
+        space = FastVote::LEN,
+        seeds = [FAST_VOTE_SEED, fast_action.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub fast_vote: Account<'info, FastVote>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoteCredits::LEN,
+        seeds = [VOTE_CREDITS_SEED, voter.key().as_ref()],
+        bump
+    )]
+    pub vote_credits: Account<'info, VoteCredits>,
+    #[account(
+        seeds = [WEIGHT_SNAPSHOT_SEED, fast_action.key().as_ref()],
+        bump = weight_snapshot.bump
+    )]
+    pub weight_snapshot: Account<'info, WeightSnapshot>,
+    /// CHECK: The voter's AgentRecord PDA; read in agent_registry if it exists
+    #[account(seeds = [AGENT_SEED, voter.key().as_ref()], bump)]
+    pub agent_record: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// CHECK: The ER's Metrics PDA, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
+    /// CHECK: The HiveFreeze PDA of the action's hive, whether or not it
+    /// exists; read in freeze::require_live
+    #[account(seeds = [HIVE_FREEZE_SEED, fast_action.hive.as_ref()], bump)]
+    pub hive_freeze: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,232 @@
+// Weight strategies. Each built-in weighting (token balances, snapshots,
+// identity assets, stake locks) took a program upgrade to add. A strategy
+// moves the weighing out: before the first ballot, the action's authority
+// names a strategy program and the params to pass it, and from then on the
+// action only takes vote_fast_strategy ballots, which ask that program's
+// compute_weight for the voter's weight and read it from return data.
+// vote_fast_weighted and post_weight_snapshot turn such an action away.
+//
+// The strategy sees the voter and the action and whatever accounts the
+// ballot passes after its own, all read-only and unsigned, so it can weigh
+// but cannot move anything of the voter's. The weight it returns is then
+// treated like any other: early-vote bonus, class scaling and the per-voter
+// cap still apply. A delegated action calls the strategy on the rollup, so
+// the program and the accounts it reads must be available there.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{get_return_data, invoke};
+
+use crate::{
+    agent_registry, freeze, metrics, record_vote, vote_credits, ComputeWeightArgs, Counter, FastAction, FastVote,
+    FastVoteError, GlobalConfig, VoteCredits, VoteMode, WeightStrategy, WeightStrategySet, AGENT_SEED,
+    COMPUTE_WEIGHT_DISCRIMINATOR, FAST_ACTION_SEED, FAST_ACTION_VERSION, FAST_VOTE_SEED, GLOBAL_CONFIG_SEED,
+    HIVE_FREEZE_SEED, MAX_STRATEGY_PARAMS_LEN, VOTE_CREDITS_SEED, WEIGHT_SNAPSHOT_SEED, WEIGHT_STRATEGY_SEED,
+};
+
+/// `voter`'s weight from the strategy's compute_weight; `accounts` are
+/// forwarded after the voter and the action
+fn compute_weight<'info>(
+    strategy: &WeightStrategy,
+    program: &AccountInfo<'info>,
+    voter: &AccountInfo<'info>,
+    action: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+) -> Result<u64> {
+    let mut data = COMPUTE_WEIGHT_DISCRIMINATOR.to_vec();
+    ComputeWeightArgs {
+        voter: voter.key(),
+        action: action.key(),
+        params: strategy.params.clone(),
+    }
+    .serialize(&mut data)?;
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(voter.key(), false),
+        AccountMeta::new_readonly(action.key(), false),
+    ];
+    metas.extend(accounts.iter().map(|info| AccountMeta::new_readonly(info.key(), false)));
+    let mut infos = vec![voter.clone(), action.clone()];
+    infos.extend(accounts.iter().cloned());
+    infos.push(program.clone());
+    invoke(
+        &Instruction {
+            program_id: strategy.program,
+            accounts: metas,
+            data,
+        },
+        &infos,
+    )?;
+
+    let (returned_by, weight) = get_return_data().ok_or(FastVoteError::StrategyReturnMissing)?;
+    require_keys_eq!(returned_by, strategy.program, FastVoteError::StrategyReturnMissing);
+    let weight: [u8; 8] = weight.as_slice().try_into().map_err(|_| FastVoteError::StrategyReturnMissing)?;
+    Ok(u64::from_le_bytes(weight))
+}
+
+/// Action authority only, before the first vote, on the base layer
+pub fn process_set_weight_strategy(ctx: Context<SetWeightStrategy>, params: Vec<u8>) -> Result<()> {
+    let action = &ctx.accounts.fast_action;
+    require!(!action.executed, FastVoteError::ActionAlreadyExecuted);
+    require!(action.vote_count == 0, FastVoteError::ActionHasVotes);
+    let program = &ctx.accounts.strategy_program;
+    require!(
+        action.vote_mode == VoteMode::TokenWeighted
+            && action.option_count == 0
+            && !action.identity_weighted
+            && action.stake_lock_program == Pubkey::default()
+            && action.quorum_weight_bps == 0
+            && ctx.accounts.weight_snapshot.data_is_empty()
+            && program.executable
+            && program.key() != crate::ID
+            && params.len() <= MAX_STRATEGY_PARAMS_LEN,
+        FastVoteError::InvalidWeightStrategy
+    );
+
+    let strategy = &mut ctx.accounts.weight_strategy;
+    strategy.action = action.key();
+    strategy.program = program.key();
+    strategy.params = params;
+    strategy.set_by = ctx.accounts.authority.key();
+    strategy.bump = ctx.bumps.weight_strategy;
+
+    emit_event!(ctx, WeightStrategySet {
+        action: ctx.accounts.weight_strategy.action,
+        hive: action.hive,
+        program: ctx.accounts.weight_strategy.program,
+        params: ctx.accounts.weight_strategy.params.clone(),
+        set_by: ctx.accounts.weight_strategy.set_by,
+    });
+
+    Ok(())
+}
+
+/// Ballot on an action with a weight strategy; remaining accounts go to the
+/// strategy program
+pub fn process_vote_fast_strategy<'info>(
+    ctx: Context<'_, '_, 'info, 'info, VoteFastStrategy<'info>>,
+    vote_value: bool,
+    voter_commitment: [u8; 32],
+    delegation_epoch: u32,
+) -> Result<()> {
+    ctx.accounts.fast_action.check_delegation_epoch(delegation_epoch)?;
+    freeze::require_live(&ctx.accounts.hive_freeze, &ctx.accounts.fast_action)?;
+    let voter = ctx.accounts.voter.key();
+    let weight = compute_weight(
+        &ctx.accounts.weight_strategy,
+        &ctx.accounts.strategy_program,
+        &ctx.accounts.voter.to_account_info(),
+        &ctx.accounts.fast_action.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    require!(weight > 0, FastVoteError::NoVotingWeight);
+
+    agent_registry::require_eligible(&ctx.accounts.agent_record, &ctx.accounts.config)?;
+    let class = agent_registry::count_class_vote(&mut ctx.accounts.fast_action, &ctx.accounts.agent_record)?;
+    let clock = Clock::get()?;
+    let weight = agent_registry::aged_weight(&ctx.accounts.agent_record, &ctx.accounts.config, weight, clock.epoch)?;
+    let action = &ctx.accounts.fast_action;
+    let weight = action.early_weight(weight, clock.slot);
+    let weight = action.capped_weight(action.class_weight(class, weight));
+    vote_credits::charge_ballot(
+        &mut ctx.accounts.vote_credits,
+        voter,
+        ctx.bumps.vote_credits,
+        &ctx.accounts.config,
+    )?;
+
+    let event = record_vote(
+        &mut ctx.accounts.fast_action,
+        &mut ctx.accounts.fast_vote,
+        voter,
+        ctx.bumps.fast_vote,
+        vote_value,
+        voter_commitment,
+        weight,
+    )?;
+    metrics::record(&ctx.accounts.metrics, &ctx.accounts.fast_action.hive, Counter::Vote, Clock::get()?.slot)?;
+    emit_event!(ctx, event);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetWeightStrategy<'info> {
+    #[account(
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &fast_action.action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        has_one = authority @ FastVoteError::Unauthorized,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = authority,
+        space = WeightStrategy::LEN,
+        seeds = [WEIGHT_STRATEGY_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub weight_strategy: Account<'info, WeightStrategy>,
+    /// CHECK: The action's WeightSnapshot PDA; must not exist
+    #[account(seeds = [WEIGHT_SNAPSHOT_SEED, fast_action.key().as_ref()], bump)]
+    pub weight_snapshot: UncheckedAccount<'info>,
+    /// CHECK: Strategy program; must be executable
+    pub strategy_program: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(action_id: u64)]
+pub struct VoteFastStrategy<'info> {
+    #[account(
+        mut,
+        seeds = [FAST_ACTION_SEED, fast_action.hive_seed(), &action_id.to_le_bytes()],
+        bump = fast_action.bump,
+        constraint = fast_action.version == FAST_ACTION_VERSION @ FastVoteError::ActionNeedsMigration
+    )]
+    pub fast_action: Account<'info, FastAction>,
+    #[account(
+        init,
+        payer = voter,
+        space = FastVote::LEN,
+        seeds = [FAST_VOTE_SEED, fast_action.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub fast_vote: Account<'info, FastVote>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoteCredits::LEN,
+        seeds = [VOTE_CREDITS_SEED, voter.key().as_ref()],
+        bump
+    )]
+    pub vote_credits: Account<'info, VoteCredits>,
+    #[account(
+        seeds = [WEIGHT_STRATEGY_SEED, fast_action.key().as_ref()],
+        bump = weight_strategy.bump
+    )]
+    pub weight_strategy: Account<'info, WeightStrategy>,
+    /// CHECK: Must be the strategy's program
+    #[account(address = weight_strategy.program @ FastVoteError::InvalidWeightStrategy)]
+    pub strategy_program: UncheckedAccount<'info>,
+    /// CHECK: The voter's AgentRecord PDA; read in agent_registry if it exists
+    #[account(seeds = [AGENT_SEED, voter.key().as_ref()], bump)]
+    pub agent_record: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    #[account(seeds = [GLOBAL_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    /// CHECK: The ER's Metrics PDA, or the hive's; counted in
+    /// metrics::record if it exists
+    #[account(mut)]
+    pub metrics: Option<UncheckedAccount<'info>>,
+    /// CHECK: The HiveFreeze PDA of the action's hive, whether or not it
+    /// exists; read in freeze::require_live
+    #[account(seeds = [HIVE_FREEZE_SEED, fast_action.hive.as_ref()], bump)]
+    pub hive_freeze: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,713 @@
+// KAMIYO Slow Voting - the slow track for high-stakes decisions
+//
+// A fast action is decided in about 30 seconds on an ephemeral rollup,
+// which suits agents coordinating on routine work but not a treasury-scale
+// payment. Slow actions run for days on the base layer, are never
+// delegated, and once passed stay locked for a timelock before anything may
+// act on them, so holders have time to notice and react.
+//
+// The slow track shares the fast voting program's pieces rather than
+// copying them:
+//
+// - Registry: only agents with an AgentRecord in the fast voting program
+//   may vote.
+// - Weighing: a slow action may name a weight strategy program, called
+//   through the same compute_weight interface as fast votes; without one
+//   each agent counts once.
+// - Execution: promote_fast_action escalates a Budget action whose amount
+//   reaches the config's promotion threshold. The fast voting program's
+//   open_stream then also needs the promotion to have passed and cleared
+//   its timelock, and refuses such a Budget that was never promoted.
+// - Ratification: promote_contested_action escalates any action whose pass
+//   fell within its category's contest margin. The fast voting program's
+//   ratify_contested turns it Passed once the slow vote passes and clears
+//   its timelock, or Failed if the slow vote does not pass.
+//
+// Slow actions created directly here execute nothing themselves; programs
+// gate on them with SlowAction::is_executable, as they gate on fast actions.
+
+// anchor 0.31 IDL handlers still call AccountInfo::realloc
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{get_return_data, invoke};
+use kamiyo_hive_interface::tally;
+use kamiyo_hive_interface::{
+    ActionKind, AgentRecord, ApprovalRounding, Budget, ComputeWeightArgs, FastAction, VoteResult, WeightStrategy,
+    AGENT_SEED, COMPUTE_WEIGHT_DISCRIMINATOR, FAST_ACTION_VERSION, MAX_EXTRA_TIERS, MAX_STRATEGY_PARAMS_LEN,
+    STREAM_SEED, WEIGHT_STRATEGY_SEED,
+};
+
+use crate::program::KamiyoSlowVoting;
+
+declare_id!("8yqR8QtTefWGDbYJ3S1hQn2U3GHJPteUNPd93CMshxCK");
+
+pub const SLOW_CONFIG_SEED: &[u8] = b"slow_config";
+pub const SLOW_ACTION_SEED: &[u8] = b"slow_action";
+pub const SLOW_BALLOT_SEED: &[u8] = b"slow_ballot";
+
+/// About a day at 400ms/slot
+pub const SLOTS_PER_DAY: u64 = 216_000;
+/// Shortest voting window the config may set
+pub const MIN_WINDOW_SLOTS: u64 = 2 * SLOTS_PER_DAY;
+/// Shortest timelock the config may set
+pub const MIN_TIMELOCK_SLOTS: u64 = SLOTS_PER_DAY;
+
+#[program]
+pub mod kamiyo_slow_voting {
+    use super::*;
+
+    /// Program upgrade authority only, once
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        settings: SlowSettings,
+        weight_params: Vec<u8>,
+    ) -> Result<()> {
+        validate_strategy(&settings.weight_program, &weight_params)?;
+        settings.validate()?;
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.settings = settings;
+        config.weight_params = weight_params;
+        config.bump = ctx.bumps.config;
+
+        emit!(SlowConfigUpdated {
+            admin: config.admin,
+            settings,
+        });
+
+        Ok(())
+    }
+
+    /// Config admin only; applies to slow actions created afterwards
+    pub fn update_config(ctx: Context<UpdateConfig>, settings: SlowSettings, weight_params: Vec<u8>) -> Result<()> {
+        validate_strategy(&settings.weight_program, &weight_params)?;
+        settings.validate()?;
+        let config = &mut ctx.accounts.config;
+        config.settings = settings;
+        config.weight_params = weight_params;
+
+        emit!(SlowConfigUpdated {
+            admin: config.admin,
+            settings,
+        });
+
+        Ok(())
+    }
+
+    /// Open a slow vote on `action_hash`. Window and timelock may be longer
+    /// than the config's but not shorter; `weight_program` is a weight
+    /// strategy called with `weight_params`, default to count agents
+    pub fn create_slow_action(
+        ctx: Context<CreateSlowAction>,
+        action_hash: [u8; 32],
+        threshold: u8,
+        window_slots: u64,
+        timelock_slots: u64,
+        weight_program: Pubkey,
+        weight_params: Vec<u8>,
+    ) -> Result<()> {
+        let settings = ctx.accounts.config.settings;
+        require!((1..=100).contains(&threshold), SlowVoteError::InvalidThreshold);
+        require!(window_slots >= settings.window_slots, SlowVoteError::InvalidWindow);
+        require!(timelock_slots >= settings.timelock_slots, SlowVoteError::InvalidTimelock);
+        validate_strategy(&weight_program, &weight_params)?;
+
+        let slot = Clock::get()?.slot;
+        let action = &mut ctx.accounts.slow_action;
+        action.creator = ctx.accounts.creator.key();
+        action.fast_action = Pubkey::default();
+        action.action_hash = action_hash;
+        action.threshold = threshold;
+        action.quorum = settings.quorum;
+        action.weight_program = weight_program;
+        action.weight_params = weight_params;
+        action.open(slot, window_slots, timelock_slots, ctx.bumps.slow_action);
+
+        emit!(action.created(action.key()));
+
+        Ok(())
+    }
+
+    /// Permissionless, on the base layer: escalate a Budget action paying at
+    /// least the promotion threshold into a slow vote, until its stream is
+    /// opened. The slow vote keeps the fast action's threshold and weight
+    /// strategy, or takes the config's strategy if it had none
+    pub fn promote_fast_action(ctx: Context<PromoteFastAction>, budget: Budget) -> Result<()> {
+        let settings = ctx.accounts.config.settings;
+        require!(settings.promotion_threshold > 0, SlowVoteError::PromotionDisabled);
+
+        let info = &ctx.accounts.fast_action;
+        let fast = load_fast_action(info)?;
+        require!(
+            fast.kind == ActionKind::Budget
+                && matches!(
+                    fast.result,
+                    VoteResult::Pending | VoteResult::Passed { .. } | VoteResult::Contested { .. }
+                ),
+            SlowVoteError::InvalidAction
+        );
+        require!(budget.action_hash()? == fast.action_hash, SlowVoteError::BudgetMismatch);
+        require!(
+            budget.mint == settings.promotion_mint && budget.amount >= settings.promotion_threshold,
+            SlowVoteError::BelowPromotionThreshold
+        );
+        require!(ctx.accounts.stream.data_is_empty(), SlowVoteError::StreamAlreadyOpen);
+
+        let strategy = strategy_of(&ctx.accounts.weight_strategy, &ctx.accounts.config)?;
+        let action = &mut ctx.accounts.slow_action;
+        action.promote(ctx.accounts.payer.key(), info.key(), &fast, &settings, strategy, ctx.bumps.slow_action)?;
+
+        emit!(action.created(action.key()));
+        emit!(FastActionPromoted {
+            slow_action: action.key(),
+            fast_action: info.key(),
+            hive: fast.hive,
+            amount: budget.amount,
+            mint: budget.mint,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless, on the base layer: escalate an action whose pass was
+    /// contested for ratification. The slow vote keeps the fast action's
+    /// threshold and weight strategy, or takes the config's
+    pub fn promote_contested_action(ctx: Context<PromoteContestedAction>) -> Result<()> {
+        let info = &ctx.accounts.fast_action;
+        let fast = load_fast_action(info)?;
+        require!(
+            matches!(fast.result, VoteResult::Contested { .. }),
+            SlowVoteError::NotContested
+        );
+
+        let settings = ctx.accounts.config.settings;
+        let strategy = strategy_of(&ctx.accounts.weight_strategy, &ctx.accounts.config)?;
+        let action = &mut ctx.accounts.slow_action;
+        action.promote(ctx.accounts.payer.key(), info.key(), &fast, &settings, strategy, ctx.bumps.slow_action)?;
+
+        emit!(action.created(action.key()));
+        emit!(ContestedActionPromoted {
+            slow_action: action.key(),
+            fast_action: info.key(),
+            hive: fast.hive,
+        });
+
+        Ok(())
+    }
+
+    /// One ballot per registered agent. If the action has a weight strategy,
+    /// remaining accounts are its program and then the accounts it reads
+    pub fn cast_slow_vote<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CastSlowVote<'info>>,
+        vote_value: bool,
+    ) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        require!(slot <= ctx.accounts.slow_action.deadline_slot, SlowVoteError::VotingClosed);
+        require!(
+            AgentRecord::load(&ctx.accounts.agent_record)?.is_some(),
+            SlowVoteError::AgentNotRegistered
+        );
+
+        let program = ctx.accounts.slow_action.weight_program;
+        let weight = if program == Pubkey::default() {
+            1
+        } else {
+            let strategy = ctx
+                .remaining_accounts
+                .first()
+                .filter(|info| info.key() == program)
+                .ok_or(SlowVoteError::InvalidWeightStrategy)?;
+            compute_weight(
+                strategy,
+                &ctx.accounts.slow_action.weight_params,
+                &ctx.accounts.voter.to_account_info(),
+                &ctx.accounts.slow_action.to_account_info(),
+                &ctx.remaining_accounts[1..],
+            )?
+        };
+        require!(weight > 0, SlowVoteError::NoVotingWeight);
+
+        let action = &mut ctx.accounts.slow_action;
+        if vote_value {
+            action.votes_for += 1;
+            action.weight_for = action.weight_for.checked_add(weight).ok_or(SlowVoteError::VoteOverflow)?;
+        } else {
+            action.votes_against += 1;
+            action.weight_against = action.weight_against.checked_add(weight).ok_or(SlowVoteError::VoteOverflow)?;
+        }
+
+        let ballot = &mut ctx.accounts.slow_ballot;
+        ballot.slow_action = action.key();
+        ballot.voter = ctx.accounts.voter.key();
+        ballot.vote_value = vote_value;
+        ballot.weight = weight;
+        ballot.voted_slot = slot;
+        ballot.bump = ctx.bumps.slow_ballot;
+
+        emit!(SlowVoteCast {
+            slow_action: action.key(),
+            voter: ballot.voter,
+            vote_value,
+            weight,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless, after the deadline. An action short of quorum fails;
+    /// a passed one starts its timelock
+    pub fn tally_slow_action(ctx: Context<TallySlowAction>) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let action = &mut ctx.accounts.slow_action;
+        require!(slot > action.deadline_slot, SlowVoteError::VotingOpen);
+        require!(action.result == VoteResult::Pending, SlowVoteError::AlreadyTallied);
+
+        let votes = action.votes_for + action.votes_against;
+        action.result = if votes == 0 || votes < action.quorum {
+            VoteResult::Failed
+        } else {
+            // Slow thresholds are whole percents, compared floored
+            let bps = tally::approval_bps(action.weight_for, action.weight_against, ApprovalRounding::Floor)?;
+            tally::grade(bps, action.threshold as u16 * 100, &[0; MAX_EXTRA_TIERS])
+        };
+        action.finalized_slot = slot;
+
+        emit!(SlowActionTallied {
+            slow_action: action.key(),
+            fast_action: action.fast_action,
+            result: action.result.clone(),
+            votes_for: action.votes_for,
+            votes_against: action.votes_against,
+            weight_for: action.weight_for,
+            weight_against: action.weight_against,
+            executable_slot: action.executable_slot(),
+        });
+
+        Ok(())
+    }
+}
+
+/// A current-version fast action that is not delegated
+fn load_fast_action(info: &AccountInfo) -> Result<FastAction> {
+    // Delegated actions are owned by the delegation program
+    require_keys_eq!(*info.owner, kamiyo_hive_interface::ID, SlowVoteError::ActionDelegated);
+    let fast = FastAction::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    require!(fast.version == FAST_ACTION_VERSION, SlowVoteError::InvalidAction);
+    Ok(fast)
+}
+
+/// The fast action's weight strategy stored at `info`, or the config's if it
+/// had none
+fn strategy_of(info: &AccountInfo, config: &SlowConfig) -> Result<(Pubkey, Vec<u8>)> {
+    if info.data_is_empty() {
+        return Ok((config.settings.weight_program, config.weight_params.clone()));
+    }
+    let strategy = WeightStrategy::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    Ok((strategy.program, strategy.params))
+}
+
+/// Params fit, and are only set along with a program
+fn validate_strategy(program: &Pubkey, params: &[u8]) -> Result<()> {
+    require!(
+        params.len() <= MAX_STRATEGY_PARAMS_LEN && (*program != Pubkey::default() || params.is_empty()),
+        SlowVoteError::InvalidWeightStrategy
+    );
+    Ok(())
+}
+
+/// `voter`'s weight from the strategy `program`'s compute_weight; `accounts`
+/// are forwarded after the voter and the action
+fn compute_weight<'info>(
+    program: &AccountInfo<'info>,
+    params: &[u8],
+    voter: &AccountInfo<'info>,
+    action: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+) -> Result<u64> {
+    let mut data = COMPUTE_WEIGHT_DISCRIMINATOR.to_vec();
+    ComputeWeightArgs {
+        voter: voter.key(),
+        action: action.key(),
+        params: params.to_vec(),
+    }
+    .serialize(&mut data)?;
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(voter.key(), false),
+        AccountMeta::new_readonly(action.key(), false),
+    ];
+    metas.extend(accounts.iter().map(|info| AccountMeta::new_readonly(info.key(), false)));
+    let mut infos = vec![voter.clone(), action.clone()];
+    infos.extend(accounts.iter().cloned());
+    infos.push(program.clone());
+    invoke(
+        &Instruction {
+            program_id: program.key(),
+            accounts: metas,
+            data,
+        },
+        &infos,
+    )?;
+
+    let (returned_by, weight) = get_return_data().ok_or(SlowVoteError::StrategyReturnMissing)?;
+    require_keys_eq!(returned_by, program.key(), SlowVoteError::StrategyReturnMissing);
+    let weight: [u8; 8] = weight.as_slice().try_into().map_err(|_| SlowVoteError::StrategyReturnMissing)?;
+    Ok(u64::from_le_bytes(weight))
+}
+
+/// What the config admin sets
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SlowSettings {
+    /// Default and shortest voting window, at least MIN_WINDOW_SLOTS
+    pub window_slots: u64,        // 8
+    /// Default and shortest timelock, at least MIN_TIMELOCK_SLOTS
+    pub timelock_slots: u64,      // 8
+    /// Ballots a slow action needs to pass
+    pub quorum: u32,              // 4
+    /// Budget amount, in `promotion_mint`, from which a Budget action must
+    /// be promoted before its stream opens (0 = never)
+    pub promotion_threshold: u64, // 8
+    /// Default pays lamports
+    pub promotion_mint: Pubkey,   // 32
+    /// Weight strategy of promoted actions that had none (default = one per
+    /// agent); its params are kept in SlowConfig
+    pub weight_program: Pubkey,   // 32
+}
+
+impl SlowSettings {
+    fn validate(&self) -> Result<()> {
+        require!(self.window_slots >= MIN_WINDOW_SLOTS, SlowVoteError::InvalidWindow);
+        require!(self.timelock_slots >= MIN_TIMELOCK_SLOTS, SlowVoteError::InvalidTimelock);
+        require!(self.quorum > 0, SlowVoteError::InvalidQuorum);
+        Ok(())
+    }
+}
+
+#[account]
+pub struct SlowConfig {
+    pub admin: Pubkey,           // 32
+    pub settings: SlowSettings,  // 92
+    /// Params for `settings.weight_program`
+    pub weight_params: Vec<u8>,  // 4 + MAX_STRATEGY_PARAMS_LEN
+    pub bump: u8,                // 1
+}
+
+impl SlowConfig {
+    pub const LEN: usize = 201; // 8 disc + 193 fields
+}
+
+#[account]
+pub struct SlowAction {
+    pub creator: Pubkey,              // 32
+    /// The Budget action this promotes; default for actions created here
+    pub fast_action: Pubkey,          // 32
+    pub action_hash: [u8; 32],        // 32
+    pub threshold: u8,                // 1
+    pub quorum: u32,                  // 4
+    pub created_slot: u64,            // 8
+    pub deadline_slot: u64,           // 8
+    pub timelock_slots: u64,          // 8
+    /// Default counts each agent once
+    pub weight_program: Pubkey,       // 32
+    pub weight_params: Vec<u8>,       // 4 + MAX_STRATEGY_PARAMS_LEN
+    pub votes_for: u32,               // 4
+    pub votes_against: u32,           // 4
+    pub weight_for: u64,              // 8
+    pub weight_against: u64,          // 8
+    pub result: VoteResult,           // 2
+    pub finalized_slot: u64,          // 8
+    pub bump: u8,                     // 1
+}
+
+impl SlowAction {
+    pub const LEN: usize = 268; // 8 disc + 260 fields
+
+    fn open(&mut self, slot: u64, window_slots: u64, timelock_slots: u64, bump: u8) {
+        self.created_slot = slot;
+        self.deadline_slot = slot.saturating_add(window_slots);
+        self.timelock_slots = timelock_slots;
+        self.votes_for = 0;
+        self.votes_against = 0;
+        self.weight_for = 0;
+        self.weight_against = 0;
+        self.result = VoteResult::Pending;
+        self.finalized_slot = 0;
+        self.bump = bump;
+    }
+
+    /// Open as the promotion of `fast`, at `key`, with the config's window
+    /// and timelock
+    fn promote(
+        &mut self,
+        payer: Pubkey,
+        key: Pubkey,
+        fast: &FastAction,
+        settings: &SlowSettings,
+        (weight_program, weight_params): (Pubkey, Vec<u8>),
+        bump: u8,
+    ) -> Result<()> {
+        self.creator = payer;
+        self.fast_action = key;
+        self.action_hash = fast.action_hash;
+        self.threshold = fast.threshold;
+        self.quorum = settings.quorum;
+        self.weight_program = weight_program;
+        self.weight_params = weight_params;
+        self.open(Clock::get()?.slot, settings.window_slots, settings.timelock_slots, bump);
+        Ok(())
+    }
+
+    fn created(&self, key: Pubkey) -> SlowActionCreated {
+        SlowActionCreated {
+            slow_action: key,
+            fast_action: self.fast_action,
+            creator: self.creator,
+            action_hash: self.action_hash,
+            threshold: self.threshold,
+            deadline_slot: self.deadline_slot,
+            timelock_slots: self.timelock_slots,
+        }
+    }
+
+    /// First slot anything may act on a pass
+    pub fn executable_slot(&self) -> u64 {
+        self.finalized_slot.saturating_add(self.timelock_slots)
+    }
+
+    /// Passed, and its timelock is over
+    pub fn is_executable(&self, slot: u64) -> bool {
+        matches!(self.result, VoteResult::Passed { .. }) && slot >= self.executable_slot()
+    }
+}
+
+#[account]
+pub struct SlowBallot {
+    pub slow_action: Pubkey, // 32
+    pub voter: Pubkey,       // 32
+    pub vote_value: bool,    // 1
+    pub weight: u64,         // 8
+    pub voted_slot: u64,     // 8
+    pub bump: u8,            // 1
+}
+
+impl SlowBallot {
+    pub const LEN: usize = 90; // 8 disc + 82 fields
+}
+
+#[event]
+pub struct SlowConfigUpdated {
+    pub admin: Pubkey,
+    pub settings: SlowSettings,
+}
+
+#[event]
+pub struct SlowActionCreated {
+    pub slow_action: Pubkey,
+    pub fast_action: Pubkey,
+    pub creator: Pubkey,
+    pub action_hash: [u8; 32],
+    pub threshold: u8,
+    pub deadline_slot: u64,
+    pub timelock_slots: u64,
+}
+
+#[event]
+pub struct FastActionPromoted {
+    pub slow_action: Pubkey,
+    pub fast_action: Pubkey,
+    pub hive: Pubkey,
+    pub amount: u64,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct ContestedActionPromoted {
+    pub slow_action: Pubkey,
+    pub fast_action: Pubkey,
+    pub hive: Pubkey,
+}
+
+#[event]
+pub struct SlowVoteCast {
+    pub slow_action: Pubkey,
+    pub voter: Pubkey,
+    pub vote_value: bool,
+    pub weight: u64,
+}
+
+#[event]
+pub struct SlowActionTallied {
+    pub slow_action: Pubkey,
+    pub fast_action: Pubkey,
+    pub result: VoteResult,
+    pub votes_for: u32,
+    pub votes_against: u32,
+    pub weight_for: u64,
+    pub weight_against: u64,
+    pub executable_slot: u64,
+}
+
+#[error_code]
+pub enum SlowVoteError {
+    #[msg("Signer is not the upgrade authority or the config admin")]
+    Unauthorized,
+    #[msg("Voting window is shorter than allowed")]
+    InvalidWindow,
+    #[msg("Timelock is shorter than allowed")]
+    InvalidTimelock,
+    #[msg("Quorum must be at least one ballot")]
+    InvalidQuorum,
+    #[msg("Threshold must be 1 to 100")]
+    InvalidThreshold,
+    #[msg("Weight params exceed MAX_STRATEGY_PARAMS_LEN, are set without a program, or the strategy program is missing")]
+    InvalidWeightStrategy,
+    #[msg("Promotion is disabled in the config")]
+    PromotionDisabled,
+    #[msg("Fast action is delegated to an ephemeral rollup")]
+    ActionDelegated,
+    #[msg("Fast action needs migration, or is not a Budget that is undecided, passed or contested")]
+    InvalidAction,
+    #[msg("Budget does not match the action hash")]
+    BudgetMismatch,
+    #[msg("Budget is below the promotion threshold or in another mint")]
+    BelowPromotionThreshold,
+    #[msg("Budget's stream is already open")]
+    StreamAlreadyOpen,
+    #[msg("Voter is not a registered agent")]
+    AgentNotRegistered,
+    #[msg("Voting window has closed")]
+    VotingClosed,
+    #[msg("Voting window is still open")]
+    VotingOpen,
+    #[msg("Slow action was already tallied")]
+    AlreadyTallied,
+    #[msg("Voter has no voting weight")]
+    NoVotingWeight,
+    #[msg("Weight strategy did not return a u64 weight")]
+    StrategyReturnMissing,
+    #[msg("Vote weight overflow")]
+    VoteOverflow,
+    #[msg("Fast action's result is not contested")]
+    NotContested,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(init, payer = admin, space = SlowConfig::LEN, seeds = [SLOW_CONFIG_SEED], bump)]
+    pub config: Account<'info, SlowConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(constraint = slow_voting_program.programdata_address()? == Some(program_data.key()) @ SlowVoteError::Unauthorized)]
+    pub slow_voting_program: Program<'info, KamiyoSlowVoting>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ SlowVoteError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(mut, seeds = [SLOW_CONFIG_SEED], bump = config.bump, has_one = admin @ SlowVoteError::Unauthorized)]
+    pub config: Account<'info, SlowConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(action_hash: [u8; 32])]
+pub struct CreateSlowAction<'info> {
+    #[account(seeds = [SLOW_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, SlowConfig>,
+    #[account(
+        init,
+        payer = creator,
+        space = SlowAction::LEN,
+        seeds = [SLOW_ACTION_SEED, creator.key().as_ref(), &action_hash],
+        bump
+    )]
+    pub slow_action: Account<'info, SlowAction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PromoteFastAction<'info> {
+    #[account(seeds = [SLOW_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, SlowConfig>,
+    /// CHECK: An undelegated Budget action; read in promote_fast_action
+    pub fast_action: UncheckedAccount<'info>,
+    /// CHECK: The fast action's weight strategy, if it has one
+    #[account(
+        seeds = [WEIGHT_STRATEGY_SEED, fast_action.key().as_ref()],
+        bump,
+        seeds::program = kamiyo_hive_interface::ID
+    )]
+    pub weight_strategy: UncheckedAccount<'info>,
+    /// CHECK: The Budget's stream; must not exist yet
+    #[account(seeds = [STREAM_SEED, fast_action.key().as_ref()], bump, seeds::program = kamiyo_hive_interface::ID)]
+    pub stream: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = SlowAction::LEN,
+        seeds = [SLOW_ACTION_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub slow_action: Account<'info, SlowAction>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PromoteContestedAction<'info> {
+    #[account(seeds = [SLOW_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, SlowConfig>,
+    /// CHECK: An undelegated, contested action; read in promote_contested_action
+    pub fast_action: UncheckedAccount<'info>,
+    /// CHECK: The fast action's weight strategy, if it has one
+    #[account(
+        seeds = [WEIGHT_STRATEGY_SEED, fast_action.key().as_ref()],
+        bump,
+        seeds::program = kamiyo_hive_interface::ID
+    )]
+    pub weight_strategy: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = SlowAction::LEN,
+        seeds = [SLOW_ACTION_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub slow_action: Account<'info, SlowAction>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastSlowVote<'info> {
+    #[account(mut)]
+    pub slow_action: Account<'info, SlowAction>,
+    #[account(
+        init,
+        payer = voter,
+        space = SlowBallot::LEN,
+        seeds = [SLOW_BALLOT_SEED, slow_action.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub slow_ballot: Account<'info, SlowBallot>,
+    /// CHECK: The voter's AgentRecord in the fast voting registry
+    #[account(seeds = [AGENT_SEED, voter.key().as_ref()], bump, seeds::program = kamiyo_hive_interface::ID)]
+    pub agent_record: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TallySlowAction<'info> {
+    #[account(mut)]
+    pub slow_action: Account<'info, SlowAction>,
+}
@@ -0,0 +1,365 @@
+// KAMIYO Weight Strategies - first-party weighing for fast votes
+//
+// A fast voting action with a weight strategy asks the strategy's program
+// for each ballot's weight through compute_weight, passing the params stored
+// with the strategy. This program reads them as StrategyParams:
+//
+// - Flat: every voter weighs the same, whatever it holds.
+// - Stake: the voter's balance of a mint, from the token account passed
+//   after the action; frozen accounts count, as the holder still owns them.
+// - Reputation: a score kept here per agent by a reputation authority, from
+//   the agent's Reputation account passed after the action.
+// - Receipt: the voter's balance of an LP or vault receipt mint, counted in
+//   the underlying it redeems for. The rate comes from a vault account that
+//   names the receipt mint and holds the underlying behind it, each at an
+//   offset the params give, along with the slot it last updated them.
+//   snapshot_receipt_rate fixes the rate for an action once, against the
+//   mint's supply, and refuses a vault whose figures are further from the
+//   action's creation slot, which its weights are taken at, than the params
+//   allow. Ballots pass the token account and then the action's
+//   ReceiptRate after the action. Receipt weighs fast actions only.
+//
+// Everything is read-only: the voting program passes every account unsigned
+// and unwritable, so compute_weight only weighs. Reputation scores are
+// written by their authority through set_reputation, on the layer the
+// actions it weighs are voted on. Receipt rates are recorded on the base
+// layer before the action is delegated.
+
+// anchor 0.31 IDL handlers still call AccountInfo::realloc
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+use kamiyo_hive_interface::{
+    FastAction, WeightStrategy, FAST_ACTION_VERSION, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, WEIGHT_STRATEGY_SEED,
+};
+
+declare_id!("5bgg6Xsx93kpW5Zg3SrT5WPAVFjAqb4G4rMZKXeWtCvU");
+
+pub const REPUTATION_SEED: &[u8] = b"reputation";
+pub const RECEIPT_RATE_SEED: &[u8] = b"receipt_rate";
+
+// spl-token account base layout, shared by Token-2022
+const ACCOUNT_BASE_LEN: usize = 165;
+const ACCOUNT_MINT_OFFSET: usize = 0;
+const ACCOUNT_OWNER_OFFSET: usize = 32;
+const ACCOUNT_AMOUNT_OFFSET: usize = 64;
+const ACCOUNT_STATE_OFFSET: usize = 108;
+const ACCOUNT_STATE_UNINITIALIZED: u8 = 0;
+const MINT_BASE_LEN: usize = 82;
+const MINT_SUPPLY_OFFSET: usize = 36;
+
+#[program]
+pub mod kamiyo_weight_strategies {
+    use super::*;
+
+    /// `voter`'s weight under `params`, a StrategyParams, returned as a u64.
+    /// Stake and Reputation take their account as the first remaining
+    /// account; Receipt takes the token account, then the action's
+    /// ReceiptRate
+    pub fn compute_weight(
+        ctx: Context<ComputeWeight>,
+        voter: Pubkey,
+        action: Pubkey,
+        params: Vec<u8>,
+    ) -> Result<u64> {
+        require_keys_eq!(ctx.accounts.voter.key(), voter, StrategyError::InvalidAccount);
+        let params = StrategyParams::try_from_slice(&params).map_err(|_| StrategyError::InvalidParams)?;
+
+        match params {
+            StrategyParams::Flat { weight } => Ok(weight),
+            StrategyParams::Stake { mint } => {
+                let account = ctx.remaining_accounts.first().ok_or(StrategyError::InvalidAccount)?;
+                stake_weight(account, &mint, &voter)
+            }
+            StrategyParams::Reputation { authority } => {
+                let account = ctx.remaining_accounts.first().ok_or(StrategyError::InvalidAccount)?;
+                let (expected, _) = Pubkey::find_program_address(
+                    &[REPUTATION_SEED, authority.as_ref(), voter.as_ref()],
+                    &crate::ID,
+                );
+                require_keys_eq!(account.key(), expected, StrategyError::InvalidAccount);
+                if account.data_is_empty() {
+                    return Ok(0);
+                }
+                require_keys_eq!(*account.owner, crate::ID, StrategyError::InvalidAccount);
+                Ok(Reputation::try_deserialize(&mut &account.try_borrow_data()?[..])?.score)
+            }
+            StrategyParams::Receipt { vault, .. } => {
+                let (Some(account), Some(rate)) = (ctx.remaining_accounts.first(), ctx.remaining_accounts.get(1))
+                else {
+                    return err!(StrategyError::InvalidAccount);
+                };
+                receipt_weight(account, rate, &action, &vault, &voter)
+            }
+        }
+    }
+
+    /// Permissionless, on the base layer before the action is delegated:
+    /// fix the receipt rate an action with a Receipt strategy weighs by
+    pub fn snapshot_receipt_rate(ctx: Context<SnapshotReceiptRate>) -> Result<()> {
+        let info = &ctx.accounts.fast_action;
+        // Delegated actions are owned by the delegation program
+        require_keys_eq!(*info.owner, kamiyo_hive_interface::ID, StrategyError::InvalidAccount);
+        let action = FastAction::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(action.version == FAST_ACTION_VERSION, StrategyError::InvalidAccount);
+        let strategy = &ctx.accounts.weight_strategy;
+        require_keys_eq!(strategy.program, crate::ID, StrategyError::InvalidParams);
+        let Ok(StrategyParams::Receipt {
+            vault,
+            mint_offset,
+            underlying_offset,
+            updated_slot_offset,
+            max_staleness_slots,
+        }) = StrategyParams::try_from_slice(&strategy.params)
+        else {
+            return err!(StrategyError::InvalidParams);
+        };
+
+        require_keys_eq!(ctx.accounts.vault.key(), vault, StrategyError::InvalidAccount);
+        let data = ctx.accounts.vault.try_borrow_data()?;
+        let read = |offset: u16, len: usize| {
+            data.get(offset as usize..offset as usize + len).ok_or(StrategyError::InvalidAccount)
+        };
+        let receipt_mint = Pubkey::new_from_array(read(mint_offset, 32)?.try_into().unwrap());
+        let underlying = u64::from_le_bytes(read(underlying_offset, 8)?.try_into().unwrap());
+        let vault_slot = u64::from_le_bytes(read(updated_slot_offset, 8)?.try_into().unwrap());
+        require!(
+            vault_slot <= Clock::get()?.slot && vault_slot.abs_diff(action.created_slot) <= max_staleness_slots,
+            StrategyError::VaultStale
+        );
+        let receipt_supply = mint_supply(&ctx.accounts.receipt_mint, &receipt_mint)?;
+
+        let rate = &mut ctx.accounts.receipt_rate;
+        rate.action = info.key();
+        rate.vault = vault;
+        rate.receipt_mint = receipt_mint;
+        rate.underlying = underlying;
+        rate.receipt_supply = receipt_supply;
+        rate.vault_slot = vault_slot;
+        rate.snapshot_slot = action.created_slot;
+        rate.bump = ctx.bumps.receipt_rate;
+
+        emit!(ReceiptRateRecorded {
+            action: rate.action,
+            vault,
+            receipt_mint,
+            underlying,
+            receipt_supply,
+            vault_slot,
+            snapshot_slot: rate.snapshot_slot,
+        });
+
+        Ok(())
+    }
+
+    /// Set `agent`'s score under the signing authority; creates the
+    /// Reputation account on first use
+    pub fn set_reputation(ctx: Context<SetReputation>, agent: Pubkey, score: u64) -> Result<()> {
+        let reputation = &mut ctx.accounts.reputation;
+        reputation.authority = ctx.accounts.authority.key();
+        reputation.agent = agent;
+        reputation.score = score;
+        reputation.updated_slot = Clock::get()?.slot;
+        reputation.bump = ctx.bumps.reputation;
+
+        emit!(ReputationSet {
+            authority: reputation.authority,
+            agent,
+            score,
+        });
+
+        Ok(())
+    }
+}
+
+/// Balance of `mint` in `info`, a token account `voter` holds
+fn stake_weight(info: &AccountInfo, mint: &Pubkey, voter: &Pubkey) -> Result<u64> {
+    require!(
+        *info.owner == TOKEN_PROGRAM_ID || *info.owner == TOKEN_2022_PROGRAM_ID,
+        StrategyError::InvalidAccount
+    );
+    let data = info.try_borrow_data()?;
+    require!(data.len() >= ACCOUNT_BASE_LEN, StrategyError::InvalidAccount);
+    let key_at = |offset: usize| Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap());
+    require!(
+        key_at(ACCOUNT_MINT_OFFSET) == *mint
+            && key_at(ACCOUNT_OWNER_OFFSET) == *voter
+            && data[ACCOUNT_STATE_OFFSET] != ACCOUNT_STATE_UNINITIALIZED,
+        StrategyError::InvalidAccount
+    );
+    Ok(u64::from_le_bytes(data[ACCOUNT_AMOUNT_OFFSET..ACCOUNT_AMOUNT_OFFSET + 8].try_into().unwrap()))
+}
+
+/// Supply of `mint`, held at `info`; must be a nonzero one
+fn mint_supply(info: &AccountInfo, mint: &Pubkey) -> Result<u64> {
+    require_keys_eq!(info.key(), *mint, StrategyError::InvalidAccount);
+    require!(
+        *info.owner == TOKEN_PROGRAM_ID || *info.owner == TOKEN_2022_PROGRAM_ID,
+        StrategyError::InvalidAccount
+    );
+    let data = info.try_borrow_data()?;
+    require!(data.len() >= MINT_BASE_LEN, StrategyError::InvalidAccount);
+    let supply = u64::from_le_bytes(data[MINT_SUPPLY_OFFSET..MINT_SUPPLY_OFFSET + 8].try_into().unwrap());
+    require!(supply > 0, StrategyError::InvalidAccount);
+    Ok(supply)
+}
+
+/// Underlying behind the receipts `voter` holds in `info`, at the rate
+/// recorded for `action` in `rate_info`
+fn receipt_weight(
+    info: &AccountInfo,
+    rate_info: &AccountInfo,
+    action: &Pubkey,
+    vault: &Pubkey,
+    voter: &Pubkey,
+) -> Result<u64> {
+    let (expected, _) = Pubkey::find_program_address(&[RECEIPT_RATE_SEED, action.as_ref()], &crate::ID);
+    require_keys_eq!(rate_info.key(), expected, StrategyError::InvalidAccount);
+    require!(!rate_info.data_is_empty(), StrategyError::RateNotRecorded);
+    require_keys_eq!(*rate_info.owner, crate::ID, StrategyError::InvalidAccount);
+    let rate = ReceiptRate::try_deserialize(&mut &rate_info.try_borrow_data()?[..])?;
+    require_keys_eq!(rate.vault, *vault, StrategyError::InvalidAccount);
+    Ok(rate.underlying_of(stake_weight(info, &rate.receipt_mint, voter)?))
+}
+
+/// A weight strategy's params, as stored in the voting program's
+/// WeightStrategy
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StrategyParams {
+    Flat { weight: u64 },
+    Stake { mint: Pubkey },
+    Reputation { authority: Pubkey },
+    /// Offsets are of little-endian figures in `vault`'s data: the receipt
+    /// mint, the underlying it holds as a u64 and the slot it last updated
+    /// them as a u64
+    Receipt {
+        vault: Pubkey,
+        mint_offset: u16,
+        underlying_offset: u16,
+        updated_slot_offset: u16,
+        /// Most slots the vault's update may lie from the snapshot slot
+        max_staleness_slots: u64,
+    },
+}
+
+#[account]
+pub struct Reputation {
+    pub authority: Pubkey, // 32
+    pub agent: Pubkey,     // 32
+    pub score: u64,        // 8
+    pub updated_slot: u64, // 8
+    pub bump: u8,          // 1
+}
+
+impl Reputation {
+    pub const LEN: usize = 89; // 8 disc + 81 fields
+}
+
+/// A vault's receipt rate as snapshot_receipt_rate recorded it for an
+/// action
+#[account]
+pub struct ReceiptRate {
+    pub action: Pubkey,       // 32
+    pub vault: Pubkey,        // 32
+    pub receipt_mint: Pubkey, // 32
+    pub underlying: u64,      // 8
+    /// The receipt mint's supply when the rate was recorded
+    pub receipt_supply: u64,  // 8
+    /// Slot the vault last updated its figures at
+    pub vault_slot: u64,      // 8
+    /// The action's creation slot
+    pub snapshot_slot: u64,   // 8
+    pub bump: u8,             // 1
+}
+
+impl ReceiptRate {
+    pub const LEN: usize = 137; // 8 disc + 129 fields
+
+    /// Underlying `receipts` redeem for, saturating at u64::MAX
+    pub fn underlying_of(&self, receipts: u64) -> u64 {
+        let underlying = receipts as u128 * self.underlying as u128 / self.receipt_supply as u128;
+        u64::try_from(underlying).unwrap_or(u64::MAX)
+    }
+}
+
+#[event]
+pub struct ReputationSet {
+    pub authority: Pubkey,
+    pub agent: Pubkey,
+    pub score: u64,
+}
+
+#[event]
+pub struct ReceiptRateRecorded {
+    pub action: Pubkey,
+    pub vault: Pubkey,
+    pub receipt_mint: Pubkey,
+    pub underlying: u64,
+    pub receipt_supply: u64,
+    pub vault_slot: u64,
+    pub snapshot_slot: u64,
+}
+
+#[error_code]
+pub enum StrategyError {
+    #[msg("Params are not StrategyParams")]
+    InvalidParams,
+    #[msg("Account does not match the voter or the strategy's params")]
+    InvalidAccount,
+    #[msg("Vault figures were updated too far from the snapshot slot")]
+    VaultStale,
+    #[msg("No receipt rate recorded for the action")]
+    RateNotRecorded,
+}
+
+#[derive(Accounts)]
+pub struct ComputeWeight<'info> {
+    /// CHECK: The voter weighed
+    pub voter: UncheckedAccount<'info>,
+    /// CHECK: The action voted on; not read by these strategies
+    pub fast_action: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SnapshotReceiptRate<'info> {
+    /// CHECK: A fast action; its owner and layout are checked in
+    /// snapshot_receipt_rate
+    pub fast_action: UncheckedAccount<'info>,
+    #[account(
+        seeds = [WEIGHT_STRATEGY_SEED, fast_action.key().as_ref()],
+        bump = weight_strategy.bump,
+        seeds::program = kamiyo_hive_interface::ID
+    )]
+    pub weight_strategy: Account<'info, WeightStrategy>,
+    /// CHECK: The strategy's vault; read at the params' offsets
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: The receipt mint the vault names
+    pub receipt_mint: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = ReceiptRate::LEN,
+        seeds = [RECEIPT_RATE_SEED, fast_action.key().as_ref()],
+        bump
+    )]
+    pub receipt_rate: Account<'info, ReceiptRate>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(agent: Pubkey)]
+pub struct SetReputation<'info> {
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Reputation::LEN,
+        seeds = [REPUTATION_SEED, authority.key().as_ref(), agent.as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, Reputation>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
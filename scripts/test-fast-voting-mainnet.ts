@@ -79,7 +79,9 @@ async function main() {
 
   // Test 2: Create a fast action
   console.log("--- Test 2: Create Fast Action ---");
-  const actionId = new anchor.BN(Date.now());
+  const [counterPDA] = PublicKey.findProgramAddressSync([Buffer.from("action_counter")], PROGRAM_ID);
+  const counter = await (program.account as any).actionCounter.fetchNullable(counterPDA);
+  const actionId: anchor.BN = counter ? counter.nextId : new anchor.BN(0);
   const [fastActionPDA] = deriveFastActionPDA(actionId);
 
   console.log("Action ID:", actionId.toString());
@@ -92,7 +94,24 @@ async function main() {
 
   try {
     const tx = await program.methods
-      .createFastAction(actionId, Array.from(actionHash), 50, Array.from(descHash))
+      .createFastAction({
+        actionHash: Array.from(actionHash),
+        threshold: 50,
+        descriptionHash: Array.from(descHash),
+        voteMode: { headcount: {} },
+        tierThresholds: [0, 0, 0],
+        dependencies: [],
+        adaptiveQuorum: null,
+        minTurnoutBps: 0,
+        optionCount: 0,
+        ranked: false,
+        marketGate: null,
+        kind: { standard: {} },
+        weightCap: null,
+        classLimits: [],
+        audited: false,
+        earlyVoteBonus: false,
+      })
       .accounts({
         fastAction: fastActionPDA,
         creator: walletKeypair.publicKey,
@@ -142,7 +161,7 @@ async function main() {
 
   try {
     const tx = await program.methods
-      .voteFast(actionId, true, Array.from(voterCommitment))
+      .voteFast(actionId, true, Array.from(voterCommitment), 0)
       .accounts({
         fastAction: fastActionPDA,
         fastVote: votePDA,
@@ -202,7 +221,7 @@ async function main() {
       .cancelAction(actionId)
       .accounts({
         fastAction: fastActionPDA,
-        creator: walletKeypair.publicKey,
+        authority: walletKeypair.publicKey,
       })
       .signers([walletKeypair])
       .rpc();
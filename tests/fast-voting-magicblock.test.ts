@@ -5,6 +5,7 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program, web3 } from "@coral-xyz/anchor";
 import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction, sendAndConfirmTransaction } from "@solana/web3.js";
 import { expect } from "chai";
+import { createHash } from "crypto";
 import { KamiyoFastVoting } from "../target/types/kamiyo_fast_voting";
 
 describe("kamiyo-fast-voting (MagicBlock Integration)", function() {
@@ -60,6 +61,30 @@ describe("kamiyo-fast-voting (MagicBlock Integration)", function() {
     );
   }
 
+  async function nextActionId(): Promise<anchor.BN> {
+    const [counterPDA] = PublicKey.findProgramAddressSync([Buffer.from("action_counter")], program.programId);
+    const counter = await program.account.actionCounter.fetchNullable(counterPDA);
+    return counter ? counter.nextId : new anchor.BN(0);
+  }
+
+  function actionParams(actionHash: number[], threshold: number, overrides: Record<string, any> = {}) {
+    return {
+      actionHash,
+      threshold,
+      descriptionHash: Array.from(Buffer.alloc(32)),
+      voteMode: { headcount: {} },
+      tierThresholds: [0, 0, 0],
+      dependencies: [],
+      adaptiveQuorum: null,
+      minTurnoutBps: 0,
+      optionCount: 0,
+      ranked: false,
+      marketGate: null,
+      kind: { standard: {} },
+      ...overrides,
+    };
+  }
+
   function isLocalhost(): boolean {
     const endpoint = providerEphemeralRollup.connection.rpcEndpoint || "";
     return endpoint.includes("localhost") || endpoint.includes("127.0.0.1");
@@ -72,7 +97,7 @@ describe("kamiyo-fast-voting (MagicBlock Integration)", function() {
     creator = Keypair.generate();
     voter1 = Keypair.generate();
     voter2 = Keypair.generate();
-    actionId = new anchor.BN(Date.now());
+    actionId = await nextActionId();
     [fastActionPDA] = deriveFastActionPDA(actionId);
 
     const payer = (provider.wallet as any).payer as Keypair;
@@ -118,7 +143,7 @@ describe("kamiyo-fast-voting (MagicBlock Integration)", function() {
       descHash.write("test-desc-hash-", 0);
 
       await program.methods
-        .createFastAction(actionId, Array.from(actionHash), 50, Array.from(descHash))
+        .createFastAction(actionParams(Array.from(actionHash), 50, { descriptionHash: Array.from(descHash) }))
         .accounts({
           fastAction: fastActionPDA,
           creator: creator.publicKey,
@@ -138,8 +163,10 @@ describe("kamiyo-fast-voting (MagicBlock Integration)", function() {
       // Delegation ALWAYS happens on the base layer (devnet) - we're telling the delegation
       // program to clone our account to the ephemeral rollup
       const delegateProgram = program; // Always use base layer program for delegation
-      const validatorAccount = isLocalhost() ? LOCAL_VALIDATOR_IDENTITY : null;
-      console.log("Validator account:", validatorAccount?.toString() || "null (devnet mode)");
+      // Pinning a validator requires it to be allowlisted and live in the
+      // registry; set KAMIYO_PIN_VALIDATOR once the local validator is registered
+      const validatorAccount = isLocalhost() && process.env.KAMIYO_PIN_VALIDATOR ? LOCAL_VALIDATOR_IDENTITY : null;
+      console.log("Validator account:", validatorAccount?.toString() || "null (unpinned)");
 
       try {
         // Build the transaction to inspect it
@@ -149,6 +176,9 @@ describe("kamiyo-fast-voting (MagicBlock Integration)", function() {
             pda: fastActionPDA,
             payer: creator.publicKey,
             validator: validatorAccount,
+            validatorRecord: validatorAccount
+              ? PublicKey.findProgramAddressSync([Buffer.from("validator"), validatorAccount.toBuffer()], program.programId)[0]
+              : null,
           } as any)
           .signers([creator])
           .rpc({ skipPreflight: true });
@@ -178,6 +208,8 @@ describe("kamiyo-fast-voting (MagicBlock Integration)", function() {
       // Verify on ER
       const accountInfo = await providerEphemeralRollup.connection.getAccountInfo(fastActionPDA);
       console.log("Account on ER exists:", accountInfo !== null);
+      const delegated = await (ephemeralProgram.account as any).fastAction.fetch(fastActionPDA);
+      expect(delegated.delegationState).to.deep.equal({ delegated: {} });
       console.log("Action delegated to ER");
     });
 
@@ -185,12 +217,14 @@ describe("kamiyo-fast-voting (MagicBlock Integration)", function() {
       // Vote 1 - YES (using ephemeral program instance)
       const [vote1PDA] = deriveFastVotePDA(fastActionPDA, voter1.publicKey);
 
+      // Delegating bumped the action's delegation epoch to 1
       const vote1Tx = await ephemeralProgram.methods
-        .voteFast(actionId, true, Array.from(Buffer.alloc(32, 11)))
+        .voteFast(actionId, true, Array.from(Buffer.alloc(32, 11)), 1)
         .accounts({
           fastAction: fastActionPDA,
           fastVote: vote1PDA,
           voter: voter1.publicKey,
+          agent: voter1.publicKey,
           systemProgram: SystemProgram.programId,
         } as any)
         .signers([voter1])
@@ -203,11 +237,12 @@ describe("kamiyo-fast-voting (MagicBlock Integration)", function() {
       const [vote2PDA] = deriveFastVotePDA(fastActionPDA, voter2.publicKey);
 
       const vote2Tx = await ephemeralProgram.methods
-        .voteFast(actionId, true, Array.from(Buffer.alloc(32, 22)))
+        .voteFast(actionId, true, Array.from(Buffer.alloc(32, 22)), 1)
         .accounts({
           fastAction: fastActionPDA,
           fastVote: vote2PDA,
           voter: voter2.publicKey,
+          agent: voter2.publicKey,
           systemProgram: SystemProgram.programId,
         } as any)
         .signers([voter2])
@@ -222,6 +257,39 @@ describe("kamiyo-fast-voting (MagicBlock Integration)", function() {
       expect(action.votesFor).to.equal(2);
     });
 
+    it("3b. Checkpoints the tally to the base layer", async function() {
+      const MAGIC_PROGRAM_ID = new PublicKey("Magic11111111111111111111111111111111111111");
+      const MAGIC_CONTEXT_ID = new PublicKey("MagicContext1111111111111111111111111111111");
+      const before = await (ephemeralProgram.account as any).fastAction.fetch(fastActionPDA);
+
+      try {
+        const tx = await ephemeralProgram.methods
+          .checkpoint()
+          .accounts({
+            fastAction: fastActionPDA,
+            payer: creator.publicKey,
+            magicContext: MAGIC_CONTEXT_ID,
+            magicProgram: MAGIC_PROGRAM_ID,
+          } as any)
+          .signers([creator])
+          .rpc({ skipPreflight: true });
+
+        console.log("Checkpoint tx:", tx);
+      } catch (e: any) {
+        // Interval depends on how fast the previous steps ran
+        if (e.message?.includes("CheckpointTooSoon")) {
+          console.log("Checkpoint interval not reached (expected on fast runs)");
+          this.skip();
+        } else {
+          throw e;
+        }
+      }
+
+      const action = await (ephemeralProgram.account as any).fastAction.fetch(fastActionPDA);
+      expect(action.lastCheckpointVotes).to.equal(2);
+      expect(action.lastCheckpointSlot.toNumber()).to.be.greaterThan(before.lastCheckpointSlot.toNumber());
+    });
+
     it("4. Waits for voting deadline", async function() {
       // In a real test we'd wait for slots to pass
       // For demo, we'll skip this or use a short window
@@ -265,22 +333,34 @@ describe("kamiyo-fast-voting (MagicBlock Integration)", function() {
       try {
         const action = await program.account.fastAction.fetch(fastActionPDA);
         expect(action.executed).to.equal(true);
+        expect(action.delegationState).to.deep.equal({ undelegated: {} });
         console.log("Results verified on base layer");
       } catch {
         // Account may still be on ER
         console.log("Account still on ER, commit pending");
       }
     });
+
+    it("5b. Records the result digest at tally", async function() {
+      const action = await program.account.fastAction.fetch(fastActionPDA);
+      if (!action.executed) {
+        console.log("Tally not committed yet");
+        this.skip();
+      }
+
+      expect(action.finalizedSlot.toNumber()).to.be.greaterThan(0);
+      expect(Buffer.from(action.resultDigest).equals(resultDigest(action))).to.be.true;
+    });
   });
 
   describe("Error Cases", function() {
     it("Rejects tally with invalid magic_program", async function() {
-      const testId = new anchor.BN(Date.now() + 1000);
+      const testId = await nextActionId();
       const [testPDA] = deriveFastActionPDA(testId);
 
       // Create action
       await program.methods
-        .createFastAction(testId, Array.from(Buffer.alloc(32, 99)), 50, Array.from(Buffer.alloc(32)))
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 99)), 50))
         .accounts({
           fastAction: testPDA,
           creator: creator.publicKey,
@@ -313,11 +393,11 @@ describe("kamiyo-fast-voting (MagicBlock Integration)", function() {
     });
 
     it("Rejects tally with invalid magic_context", async function() {
-      const testId = new anchor.BN(Date.now() + 2000);
+      const testId = await nextActionId();
       const [testPDA] = deriveFastActionPDA(testId);
 
       await program.methods
-        .createFastAction(testId, Array.from(Buffer.alloc(32, 98)), 50, Array.from(Buffer.alloc(32)))
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 98)), 50))
         .accounts({
           fastAction: testPDA,
           creator: creator.publicKey,
@@ -346,9 +426,88 @@ describe("kamiyo-fast-voting (MagicBlock Integration)", function() {
         console.log("Rejected invalid magic_context");
       }
     });
+
+    it("Rejects tally while a dependency is still delegated", async function() {
+      const MAGIC_PROGRAM_ID = new PublicKey("Magic11111111111111111111111111111111111111");
+      const MAGIC_CONTEXT_ID = new PublicKey("MagicContext1111111111111111111111111111111");
+
+      const dependencyId = await nextActionId();
+      const [dependencyPDA] = deriveFastActionPDA(dependencyId);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 97)), 50))
+        .accounts({
+          fastAction: dependencyPDA,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([creator])
+        .rpc();
+      await program.methods
+        .delegateAction(dependencyId)
+        .accounts({ pda: dependencyPDA, payer: creator.publicKey, validator: null, validatorRecord: null } as any)
+        .signers([creator])
+        .rpc({ skipPreflight: true });
+
+      const testId = await nextActionId();
+      const [testPDA] = deriveFastActionPDA(testId);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 96)), 50, { dependencies: [dependencyPDA] }))
+        .accounts({
+          fastAction: testPDA,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([creator])
+        .rpc();
+
+      // On the base layer the dependency is the delegation program's until
+      // it is tallied and undelegated
+      try {
+        await program.methods
+          .tallyAndCommit()
+          .accounts({
+            fastAction: testPDA,
+            payer: creator.publicKey,
+            magicContext: MAGIC_CONTEXT_ID,
+            magicProgram: MAGIC_PROGRAM_ID,
+          } as any)
+          .remainingAccounts([{ pubkey: dependencyPDA, isSigner: false, isWritable: false }])
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have rejected a delegated dependency");
+      } catch (e: any) {
+        expect(e.message).to.include("DependencyDelegated");
+      }
+    });
   });
 });
 
 function sleep(ms: number): Promise<void> {
   return new Promise(resolve => setTimeout(resolve, ms));
 }
+
+// VoteResult::encode order; the digest takes [variant, tier or option]
+const RESULT_VARIANTS = [
+  "pending", "passed", "failed", "cancelled", "expired", "voided", "chosen", "vetoed", "contested",
+];
+
+// tally::result_digest, recomputed from the action's fields
+function resultDigest(action: any): Buffer {
+  const [variant] = Object.keys(action.result);
+  const fields = action.result[variant] ?? {};
+  const u32 = (n: number) => {
+    const out = Buffer.alloc(4);
+    out.writeUInt32LE(n);
+    return out;
+  };
+  return createHash("sha256")
+    .update(Buffer.concat([
+      action.actionId.toArrayLike(Buffer, "le", 8),
+      Buffer.from(action.actionHash),
+      u32(action.votesFor),
+      u32(action.votesAgainst),
+      Buffer.from([RESULT_VARIANTS.indexOf(variant), fields.tier ?? fields.option ?? 0]),
+      action.finalizedSlot.toArrayLike(Buffer, "le", 8),
+    ]))
+    .digest();
+}
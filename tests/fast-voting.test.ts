@@ -1,7 +1,16 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
-import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+  Ed25519Program,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+} from "@solana/web3.js";
+import { createAccount, createMint, getAccount, mintTo, transfer, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { expect } from "chai";
+import { createHash } from "crypto";
 import { KamiyoFastVoting } from "../target/types/kamiyo_fast_voting";
 
 describe("kamiyo-fast-voting", () => {
@@ -25,6 +34,63 @@ describe("kamiyo-fast-voting", () => {
     );
   }
 
+  function deriveRolesPDA(hive: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync([Buffer.from("roles"), hive.toBuffer()], program.programId);
+  }
+
+  function deriveHivePDA(hive: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync([Buffer.from("hive"), hive.toBuffer()], program.programId);
+  }
+
+  function deriveHiveActionPDA(hive: PublicKey, actionId: anchor.BN): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [FAST_ACTION_SEED, hive.toBuffer(), actionId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+  }
+
+  // Hive actions are created through the hive's Hive account
+  function createHiveAccount(hive: Keypair) {
+    return program.methods
+      .createHive(Array.from(hive.publicKey.toBuffer()), hive.publicKey)
+      .accounts({
+        hiveAccount: deriveHivePDA(hive.publicKey)[0],
+        hive: hive.publicKey,
+        payer: creator.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([hive, creator])
+      .rpc();
+  }
+
+  function deriveHiveConfigPDA(hive: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync([Buffer.from("hive_config"), hive.toBuffer()], program.programId);
+  }
+
+  function deriveCategoryConfigPDA(hive: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync([Buffer.from("category_config"), hive.toBuffer()], program.programId);
+  }
+
+  function deriveCreatorAllowlistPDA(hive: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync([Buffer.from("creator_allowlist"), hive.toBuffer()], program.programId);
+  }
+
+  function deriveHiveFreezePDA(hive: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync([Buffer.from("hive_freeze"), hive.toBuffer()], program.programId);
+  }
+
+  function deriveTemplatePDA(hive: PublicKey, templateId: number): [PublicKey, number] {
+    const id = Buffer.alloc(2);
+    id.writeUInt16LE(templateId);
+    return PublicKey.findProgramAddressSync([Buffer.from("template"), hive.toBuffer(), id], program.programId);
+  }
+
+  const VALIDATOR_BOND = new anchor.BN(LAMPORTS_PER_SOL);
+
+  function deriveValidatorPDA(validator: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync([Buffer.from("validator"), validator.toBuffer()], program.programId);
+  }
+
   function deriveFastVotePDA(fastAction: PublicKey, voter: PublicKey): [PublicKey, number] {
     return PublicKey.findProgramAddressSync(
       [FAST_VOTE_SEED, fastAction.toBuffer(), voter.toBuffer()],
@@ -32,11 +98,78 @@ describe("kamiyo-fast-voting", () => {
     );
   }
 
+  function deriveRankedBallotPDA(fastAction: PublicKey, voter: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("ranked_ballot"), fastAction.toBuffer(), voter.toBuffer()],
+      program.programId
+    );
+  }
+
+  async function nextActionId(): Promise<anchor.BN> {
+    const [counterPDA] = PublicKey.findProgramAddressSync([Buffer.from("action_counter")], program.programId);
+    const counter = await program.account.actionCounter.fetchNullable(counterPDA);
+    return counter ? counter.nextId : new anchor.BN(0);
+  }
+
+  function actionParams(actionHash: number[], threshold: number, overrides: Record<string, any> = {}) {
+    return {
+      actionHash,
+      threshold,
+      descriptionHash: Array.from(Buffer.alloc(32)),
+      voteMode: { headcount: {} },
+      tierThresholds: [0, 0, 0],
+      dependencies: [],
+      adaptiveQuorum: null,
+      minTurnoutBps: 0,
+      optionCount: 0,
+      ranked: false,
+      marketGate: null,
+      kind: { standard: {} },
+      weightCap: null,
+      classLimits: [],
+      audited: false,
+      earlyVoteBonus: false,
+      exclusions: [],
+      deadlineExtension: null,
+      identityWeighted: false,
+      memberCollection: null,
+      lockStake: false,
+      priority: 1,
+      tags: [],
+      quorumWeightBps: 0,
+      privacy: { public: {} },
+      thresholdBps: 0,
+      ...overrides,
+    };
+  }
+
+  async function ensureConfig() {
+    const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+    if (await provider.connection.getAccountInfo(configPDA)) return;
+
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+    await program.methods
+      .initializeConfig(PublicKey.default, 0, PublicKey.default)
+      .accounts({
+        config: configPDA,
+        admin: provider.wallet.publicKey,
+        fastVotingProgram: program.programId,
+        programData,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
   before(async () => {
+    await ensureConfig();
+
     creator = Keypair.generate();
     voter1 = Keypair.generate();
     voter2 = Keypair.generate();
-    actionId = new anchor.BN(Date.now());
+    actionId = await nextActionId();
 
     // Fund accounts
     const sigs = await Promise.all([
@@ -54,7 +187,7 @@ describe("kamiyo-fast-voting", () => {
       const descHash = Buffer.alloc(32, 2);
 
       await program.methods
-        .createFastAction(actionId, Array.from(actionHash), 51, Array.from(descHash))
+        .createFastAction(actionParams(Array.from(actionHash), 51, { descriptionHash: Array.from(descHash) }))
         .accounts({
           fastAction: fastActionPDA,
           creator: creator.publicKey,
@@ -64,6 +197,7 @@ describe("kamiyo-fast-voting", () => {
         .rpc();
 
       const action = await program.account.fastAction.fetch(fastActionPDA);
+      expect(action.version).to.equal(28);
       expect(action.actionId.toNumber()).to.equal(actionId.toNumber());
       expect(action.threshold).to.equal(51);
       expect(action.votesFor).to.equal(0);
@@ -73,12 +207,12 @@ describe("kamiyo-fast-voting", () => {
     });
 
     it("rejects threshold 0", async () => {
-      const badId = new anchor.BN(Date.now() + 1);
+      const badId = await nextActionId();
       const [pda] = deriveFastActionPDA(badId);
 
       try {
         await program.methods
-          .createFastAction(badId, Array.from(Buffer.alloc(32, 1)), 0, Array.from(Buffer.alloc(32)))
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 1)), 0))
           .accounts({
             fastAction: pda,
             creator: creator.publicKey,
@@ -93,12 +227,12 @@ describe("kamiyo-fast-voting", () => {
     });
 
     it("rejects threshold > 100", async () => {
-      const badId = new anchor.BN(Date.now() + 2);
+      const badId = await nextActionId();
       const [pda] = deriveFastActionPDA(badId);
 
       try {
         await program.methods
-          .createFastAction(badId, Array.from(Buffer.alloc(32, 1)), 101, Array.from(Buffer.alloc(32)))
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 1)), 101))
           .accounts({
             fastAction: pda,
             creator: creator.publicKey,
@@ -113,12 +247,12 @@ describe("kamiyo-fast-voting", () => {
     });
 
     it("rejects zero action hash", async () => {
-      const badId = new anchor.BN(Date.now() + 3);
+      const badId = await nextActionId();
       const [pda] = deriveFastActionPDA(badId);
 
       try {
         await program.methods
-          .createFastAction(badId, Array.from(Buffer.alloc(32, 0)), 50, Array.from(Buffer.alloc(32)))
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 0)), 50))
           .accounts({
             fastAction: pda,
             creator: creator.publicKey,
@@ -131,6 +265,136 @@ describe("kamiyo-fast-voting", () => {
         expect(err.message).to.include("InvalidActionHash");
       }
     });
+
+    it("rejects a second live action for the same hash", async () => {
+      const dupId = await nextActionId();
+      const [pda] = deriveFastActionPDA(dupId);
+
+      try {
+        // Same payload as the action created above, which is still open
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 1)), 60))
+          .accounts({
+            fastAction: pda,
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("DuplicateActionHash");
+      }
+    });
+
+    it("takes the action id from the counter and advances it", async () => {
+      const [counterPDA] = PublicKey.findProgramAddressSync([Buffer.from("action_counter")], program.programId);
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 203)), 50))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.actionId.toNumber()).to.equal(id.toNumber());
+      const counter = await program.account.actionCounter.fetch(counterPDA);
+      expect(counter.nextId.toNumber()).to.equal(id.toNumber() + 1);
+    });
+
+    it("rejects an action address that skips the counter", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id.addn(1));
+
+      try {
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 204)), 50))
+          .accounts({
+            fastAction: pda,
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ConstraintSeeds");
+      }
+      expect((await nextActionId()).toNumber()).to.equal(id.toNumber());
+      expect(await provider.connection.getAccountInfo(pda)).to.be.null;
+    });
+
+    it("rate limits a creator past the epoch's budget", async () => {
+      const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+      const spammer = Keypair.generate();
+      const [statePDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("creator_state"), spammer.publicKey.toBuffer()],
+        program.programId
+      );
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(spammer.publicKey, 2 * LAMPORTS_PER_SOL)
+      );
+
+      const create = (fill: number) =>
+        nextActionId().then((id) =>
+          program.methods
+            .createFastAction(actionParams(Array.from(Buffer.alloc(32, fill)), 50))
+            .accounts({
+              fastAction: deriveFastActionPDA(id)[0],
+              creator: spammer.publicKey,
+              systemProgram: SystemProgram.programId,
+            })
+            .signers([spammer])
+            .rpc()
+        );
+      const setLimit = (maxActionsPerEpoch: number) =>
+        program.methods
+          .updateConfig({
+            admin: null,
+            weightMint: null,
+            weightExtensions: null,
+            transferHookProgram: null,
+            maxActionsPerEpoch,
+            guardian: null,
+            voteCreditsPerEpoch: null,
+            minAgentBond: null,
+            whistleblowerBps: null,
+            snapshotter: null,
+            maxWindowSlots: null,
+            stakeLockProgram: null,
+            maxLowPriorityActions: null,
+            maxVotesPerAction: null,
+            identityRamp: null,
+            feeRebate: null,
+            maxActionsPerValidator: null,
+          })
+          .accounts({ config: configPDA, admin: provider.wallet.publicKey })
+          .rpc();
+
+      const { maxActionsPerEpoch } = await program.account.globalConfig.fetch(configPDA);
+      await setLimit(1);
+      try {
+        await create(201);
+        try {
+          await create(202);
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include("CreationRateLimited");
+        }
+      } finally {
+        await setLimit(maxActionsPerEpoch);
+      }
+
+      const state = await program.account.creatorState.fetch(statePDA);
+      expect(state.creator.toString()).to.equal(spammer.publicKey.toString());
+      expect(state.actionsInEpoch).to.equal(1);
+    });
   });
 
   describe("vote_fast", () => {
@@ -138,16 +402,11 @@ describe("kamiyo-fast-voting", () => {
     let fastActionPDA: PublicKey;
 
     before(async () => {
-      voteActionId = new anchor.BN(Date.now() + 100);
+      voteActionId = await nextActionId();
       [fastActionPDA] = deriveFastActionPDA(voteActionId);
 
       await program.methods
-        .createFastAction(
-          voteActionId,
-          Array.from(Buffer.alloc(32, 5)),
-          50,
-          Array.from(Buffer.alloc(32))
-        )
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 5)), 50))
         .accounts({
           fastAction: fastActionPDA,
           creator: creator.publicKey,
@@ -162,11 +421,12 @@ describe("kamiyo-fast-voting", () => {
       const commitment = Buffer.alloc(32, 11);
 
       await program.methods
-        .voteFast(voteActionId, true, Array.from(commitment))
+        .voteFast(voteActionId, true, Array.from(commitment), 0)
         .accounts({
           fastAction: fastActionPDA,
           fastVote: votePDA,
           voter: voter1.publicKey,
+          agent: voter1.publicKey,
           systemProgram: SystemProgram.programId,
         })
         .signers([voter1])
@@ -183,11 +443,12 @@ describe("kamiyo-fast-voting", () => {
       const commitment = Buffer.alloc(32, 22);
 
       await program.methods
-        .voteFast(voteActionId, false, Array.from(commitment))
+        .voteFast(voteActionId, false, Array.from(commitment), 0)
         .accounts({
           fastAction: fastActionPDA,
           fastVote: votePDA,
           voter: voter2.publicKey,
+          agent: voter2.publicKey,
           systemProgram: SystemProgram.programId,
         })
         .signers([voter2])
@@ -199,24 +460,64 @@ describe("kamiyo-fast-voting", () => {
       expect(action.voteCount).to.equal(2);
     });
 
+    it("reports the live standing", async () => {
+      const standing = await program.methods
+        .getStanding(voteActionId)
+        .accounts({ fastAction: fastActionPDA })
+        .view();
+
+      expect(standing.approvalPct).to.equal(50);
+      expect(standing.voteCount).to.equal(2);
+      expect(standing.requiredVotes).to.equal(2);
+      expect(standing.slotsRemaining.toNumber()).to.be.greaterThan(0);
+      expect(standing.projected).to.deep.equal({ passed: { tier: 0 } });
+    });
+
+    it("previews the tally before the deadline", async () => {
+      const preview = await program.methods
+        .previewTally(voteActionId)
+        .accounts({ fastAction: fastActionPDA })
+        .view();
+
+      expect(preview.result).to.deep.equal({ passed: { tier: 0 } });
+      expect(preview.rejection).to.be.null;
+      expect(preview.voteCount).to.equal(2);
+      expect(preview.requiredVotes).to.equal(2);
+
+      const action = await program.account.fastAction.fetch(fastActionPDA);
+      expect(action.executed).to.be.false;
+    });
+
+    it("does not assert a pending action passed", async () => {
+      try {
+        await program.methods
+          .assertActionPassed()
+          .accounts({ fastAction: fastActionPDA })
+          .view();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ActionNotPassed");
+      }
+    });
+
     it("rejects double voting", async () => {
       const [votePDA] = deriveFastVotePDA(fastActionPDA, voter1.publicKey);
 
       try {
         await program.methods
-          .voteFast(voteActionId, true, Array.from(Buffer.alloc(32, 99)))
+          .voteFast(voteActionId, true, Array.from(Buffer.alloc(32, 99)), 0)
           .accounts({
             fastAction: fastActionPDA,
             fastVote: votePDA,
             voter: voter1.publicKey,
+            agent: voter1.publicKey,
             systemProgram: SystemProgram.programId,
           })
           .signers([voter1])
           .rpc();
         expect.fail("Should have thrown");
       } catch (err: any) {
-        // Account already exists constraint
-        expect(err.message).to.include("already in use");
+        expect(err.message).to.include("AlreadyVoted");
       }
     });
 
@@ -228,11 +529,12 @@ describe("kamiyo-fast-voting", () => {
 
       try {
         await program.methods
-          .voteFast(voteActionId, true, Array.from(Buffer.alloc(32, 0)))
+          .voteFast(voteActionId, true, Array.from(Buffer.alloc(32, 0)), 0)
           .accounts({
             fastAction: fastActionPDA,
             fastVote: votePDA,
             voter: newVoter.publicKey,
+            agent: newVoter.publicKey,
             systemProgram: SystemProgram.programId,
           })
           .signers([newVoter])
@@ -242,15 +544,71 @@ describe("kamiyo-fast-voting", () => {
         expect(err.message).to.include("InvalidVoterCommitment");
       }
     });
+
+    it("rejects ballots signed for another delegation epoch", async () => {
+      const newVoter = Keypair.generate();
+      await provider.connection.requestAirdrop(newVoter.publicKey, LAMPORTS_PER_SOL);
+
+      try {
+        await program.methods
+          .voteFast(voteActionId, true, Array.from(Buffer.alloc(32, 98)), 1)
+          .accounts({
+            fastAction: fastActionPDA,
+            fastVote: deriveFastVotePDA(fastActionPDA, newVoter.publicKey)[0],
+            voter: newVoter.publicKey,
+            agent: newVoter.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([newVoter])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("StaleDelegationEpoch");
+      }
+    });
+
+    it("rejects headcount vote on token-weighted action", async () => {
+      const weightedId = await nextActionId();
+      const [pda] = deriveFastActionPDA(weightedId);
+
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 6)), 50, { voteMode: { tokenWeighted: {} } }))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      const [votePDA] = deriveFastVotePDA(pda, voter1.publicKey);
+
+      try {
+        await program.methods
+          .voteFast(weightedId, true, Array.from(Buffer.alloc(32, 11)), 0)
+          .accounts({
+            fastAction: pda,
+            fastVote: votePDA,
+            voter: voter1.publicKey,
+            agent: voter1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("WrongVoteMode");
+      }
+    });
   });
 
   describe("cancel_action", () => {
     it("creator can cancel their action", async () => {
-      const cancelId = new anchor.BN(Date.now() + 200);
+      const cancelId = await nextActionId();
       const [pda] = deriveFastActionPDA(cancelId);
 
       await program.methods
-        .createFastAction(cancelId, Array.from(Buffer.alloc(32, 7)), 50, Array.from(Buffer.alloc(32)))
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 7)), 50))
         .accounts({
           fastAction: pda,
           creator: creator.publicKey,
@@ -263,7 +621,7 @@ describe("kamiyo-fast-voting", () => {
         .cancelAction(cancelId)
         .accounts({
           fastAction: pda,
-          creator: creator.publicKey,
+          authority: creator.publicKey,
         })
         .signers([creator])
         .rpc();
@@ -274,11 +632,11 @@ describe("kamiyo-fast-voting", () => {
     });
 
     it("non-creator cannot cancel", async () => {
-      const otherId = new anchor.BN(Date.now() + 300);
+      const otherId = await nextActionId();
       const [pda] = deriveFastActionPDA(otherId);
 
       await program.methods
-        .createFastAction(otherId, Array.from(Buffer.alloc(32, 8)), 50, Array.from(Buffer.alloc(32)))
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 8)), 50))
         .accounts({
           fastAction: pda,
           creator: creator.publicKey,
@@ -292,7 +650,7 @@ describe("kamiyo-fast-voting", () => {
           .cancelAction(otherId)
           .accounts({
             fastAction: pda,
-            creator: voter1.publicKey,
+            authority: voter1.publicKey,
           })
           .signers([voter1])
           .rpc();
@@ -303,11 +661,11 @@ describe("kamiyo-fast-voting", () => {
     });
 
     it("cannot cancel already executed action", async () => {
-      const execId = new anchor.BN(Date.now() + 400);
+      const execId = await nextActionId();
       const [pda] = deriveFastActionPDA(execId);
 
       await program.methods
-        .createFastAction(execId, Array.from(Buffer.alloc(32, 9)), 50, Array.from(Buffer.alloc(32)))
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 9)), 50))
         .accounts({
           fastAction: pda,
           creator: creator.publicKey,
@@ -321,7 +679,7 @@ describe("kamiyo-fast-voting", () => {
         .cancelAction(execId)
         .accounts({
           fastAction: pda,
-          creator: creator.publicKey,
+          authority: creator.publicKey,
         })
         .signers([creator])
         .rpc();
@@ -332,7 +690,7 @@ describe("kamiyo-fast-voting", () => {
           .cancelAction(execId)
           .accounts({
             fastAction: pda,
-            creator: creator.publicKey,
+            authority: creator.publicKey,
           })
           .signers([creator])
           .rpc();
@@ -341,15 +699,13 @@ describe("kamiyo-fast-voting", () => {
         expect(err.message).to.include("ActionAlreadyExecuted");
       }
     });
-  });
 
-  describe("tally_and_commit validation", () => {
-    it("rejects tally before deadline", async () => {
-      const tallyId = new anchor.BN(Date.now() + 700);
-      const [pda] = deriveFastActionPDA(tallyId);
+    it("only the authority can transfer it", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
 
       await program.methods
-        .createFastAction(tallyId, Array.from(Buffer.alloc(32, 12)), 50, Array.from(Buffer.alloc(32)))
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 43)), 50))
         .accounts({
           fastAction: pda,
           creator: creator.publicKey,
@@ -358,93 +714,85 @@ describe("kamiyo-fast-voting", () => {
         .signers([creator])
         .rpc();
 
-      // Add votes to meet quorum
-      const [vote1Pda] = deriveFastVotePDA(pda, voter1.publicKey);
-      const [vote2Pda] = deriveFastVotePDA(pda, voter2.publicKey);
+      try {
+        await program.methods
+          .transferActionAuthority(voter1.publicKey)
+          .accounts({
+            fastAction: pda,
+            authority: voter1.publicKey,
+          })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+    });
+
+    it("new authority cancels in place of the creator", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
 
       await program.methods
-        .voteFast(tallyId, true, Array.from(Buffer.alloc(32, 1)))
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 44)), 50))
         .accounts({
           fastAction: pda,
-          fastVote: vote1Pda,
-          voter: voter1.publicKey,
+          creator: creator.publicKey,
           systemProgram: SystemProgram.programId,
         })
-        .signers([voter1])
+        .signers([creator])
         .rpc();
 
       await program.methods
-        .voteFast(tallyId, true, Array.from(Buffer.alloc(32, 2)))
+        .transferActionAuthority(voter1.publicKey)
         .accounts({
           fastAction: pda,
-          fastVote: vote2Pda,
-          voter: voter2.publicKey,
-          systemProgram: SystemProgram.programId,
+          authority: creator.publicKey,
         })
-        .signers([voter2])
+        .signers([creator])
         .rpc();
 
-      // MagicBlock program and context (will fail validation but we test the timing check first)
-      const fakeMagicProgram = new PublicKey("Magic11111111111111111111111111111111111111");
-      const fakeMagicContext = new PublicKey("MagicContext1111111111111111111111111111111");
+      let action = await program.account.fastAction.fetch(pda);
+      expect(action.authority.toBase58()).to.equal(voter1.publicKey.toBase58());
+      expect(action.creator.toBase58()).to.equal(creator.publicKey.toBase58());
 
       try {
         await program.methods
-          .tallyAndCommit()
+          .cancelAction(id)
           .accounts({
             fastAction: pda,
-            payer: creator.publicKey,
-            magicContext: fakeMagicContext,
-            magicProgram: fakeMagicProgram,
+            authority: creator.publicKey,
           })
           .signers([creator])
           .rpc();
         expect.fail("Should have thrown");
       } catch (err: any) {
-        // Either VotingNotEnded (if deadline not passed) or account validation error
-        expect(err.message).to.satisfy((msg: string) =>
-          msg.includes("VotingNotEnded") || msg.includes("ConstraintAddress")
-        );
+        expect(err.message).to.include("Unauthorized");
       }
-    });
-
-    it("rejects quorum not met", async () => {
-      const quorumId = new anchor.BN(Date.now() + 800);
-      const [pda] = deriveFastActionPDA(quorumId);
-
-      await program.methods
-        .createFastAction(quorumId, Array.from(Buffer.alloc(32, 13)), 50, Array.from(Buffer.alloc(32)))
-        .accounts({
-          fastAction: pda,
-          creator: creator.publicKey,
-          systemProgram: SystemProgram.programId,
-        })
-        .signers([creator])
-        .rpc();
 
-      // Only 1 vote (quorum is 2)
-      const [votePda] = deriveFastVotePDA(pda, voter1.publicKey);
       await program.methods
-        .voteFast(quorumId, true, Array.from(Buffer.alloc(32, 1)))
+        .cancelAction(id)
         .accounts({
           fastAction: pda,
-          fastVote: votePda,
-          voter: voter1.publicKey,
-          systemProgram: SystemProgram.programId,
+          authority: voter1.publicKey,
         })
         .signers([voter1])
         .rpc();
 
-      const action = await program.account.fastAction.fetch(pda);
-      expect(action.voteCount).to.equal(1);
+      action = await program.account.fastAction.fetch(pda);
+      expect(JSON.stringify(action.result)).to.include("cancelled");
     });
 
-    it("rejects invalid magic program", async () => {
-      const invalidId = new anchor.BN(Date.now() + 900);
-      const [pda] = deriveFastActionPDA(invalidId);
+    it("rejects cancellation once quorum is reached", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
 
       await program.methods
-        .createFastAction(invalidId, Array.from(Buffer.alloc(32, 14)), 50, Array.from(Buffer.alloc(32)))
+        .createFastAction(
+          actionParams(Array.from(Buffer.alloc(32, 45)), 50, {
+            adaptiveQuorum: { quorum: 1, floor: 1, graceSlots: new anchor.BN(0) },
+          })
+        )
         .accounts({
           fastAction: pda,
           creator: creator.publicKey,
@@ -453,97 +801,6160 @@ describe("kamiyo-fast-voting", () => {
         .signers([creator])
         .rpc();
 
-      // Add quorum votes
-      const [vote1Pda] = deriveFastVotePDA(pda, voter1.publicKey);
-      const [vote2Pda] = deriveFastVotePDA(pda, voter2.publicKey);
-
       await program.methods
-        .voteFast(invalidId, true, Array.from(Buffer.alloc(32, 1)))
+        .voteFast(id, false, Array.from(Buffer.alloc(32, 45)), 0)
         .accounts({
           fastAction: pda,
-          fastVote: vote1Pda,
+          fastVote: deriveFastVotePDA(pda, voter1.publicKey)[0],
           voter: voter1.publicKey,
+          agent: voter1.publicKey,
           systemProgram: SystemProgram.programId,
         })
         .signers([voter1])
         .rpc();
 
-      await program.methods
-        .voteFast(invalidId, true, Array.from(Buffer.alloc(32, 2)))
-        .accounts({
-          fastAction: pda,
-          fastVote: vote2Pda,
-          voter: voter2.publicKey,
-          systemProgram: SystemProgram.programId,
-        })
-        .signers([voter2])
-        .rpc();
-
-      // Use wrong magic program
-      const wrongMagicProgram = SystemProgram.programId;
-      const fakeMagicContext = new PublicKey("MagicContext1111111111111111111111111111111");
-
       try {
         await program.methods
-          .tallyAndCommit()
+          .cancelAction(id)
           .accounts({
             fastAction: pda,
-            payer: creator.publicKey,
-            magicContext: fakeMagicContext,
-            magicProgram: wrongMagicProgram,
+            authority: creator.publicKey,
           })
           .signers([creator])
           .rpc();
         expect.fail("Should have thrown");
       } catch (err: any) {
-        // Should fail with address constraint error
-        expect(err.message).to.include("ConstraintAddress");
+        expect(err.message).to.include("CancellationClosed");
       }
     });
   });
 
-  describe("delegate_action validation", () => {
-    it("rejects invalid PDA", async () => {
-      const delegateId = new anchor.BN(Date.now() + 1000);
-      const [correctPda] = deriveFastActionPDA(delegateId);
+  describe("amend_action", () => {
+    let amendId: anchor.BN;
+    let pda: PublicKey;
+
+    before(async () => {
+      amendId = await nextActionId();
+      [pda] = deriveFastActionPDA(amendId);
 
-      // Create the action first
       await program.methods
-        .createFastAction(delegateId, Array.from(Buffer.alloc(32, 15)), 50, Array.from(Buffer.alloc(32)))
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 47)), 50))
         .accounts({
-          fastAction: correctPda,
+          fastAction: pda,
           creator: creator.publicKey,
           systemProgram: SystemProgram.programId,
         })
         .signers([creator])
         .rpc();
+    });
+
+    it("rejects amendments from anyone but the authority", async () => {
+      try {
+        await program.methods
+          .amendAction({ threshold: 10, windowSlots: null, descriptionHash: null })
+          .accounts({ fastAction: pda, authority: voter1.publicKey })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+    });
+
+    it("authority amends threshold, window and description before any vote", async () => {
+      const before = await program.account.fastAction.fetch(pda);
+      const descriptionHash = Array.from(Buffer.alloc(32, 48));
+
+      await program.methods
+        .amendAction({ threshold: 66, windowSlots: new anchor.BN(150), descriptionHash })
+        .accounts({ fastAction: pda, authority: creator.publicKey })
+        .signers([creator])
+        .rpc();
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.threshold).to.equal(66);
+      expect(action.deadlineSlot.toNumber()).to.equal(before.createdSlot.toNumber() + 150);
+      expect(action.descriptionHash).to.deep.equal(descriptionHash);
+    });
+
+    it("caps windows at the config's max window", async () => {
+      const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+      const setMaxWindow = (slots: number) =>
+        program.methods
+          .updateConfig({
+            admin: null,
+            weightMint: null,
+            weightExtensions: null,
+            transferHookProgram: null,
+            maxActionsPerEpoch: null,
+            guardian: null,
+            voteCreditsPerEpoch: null,
+            minAgentBond: null,
+            whistleblowerBps: null,
+            snapshotter: null,
+            maxWindowSlots: new anchor.BN(slots),
+            stakeLockProgram: null,
+            maxLowPriorityActions: null,
+            maxVotesPerAction: null,
+            identityRamp: null,
+            feeRebate: null,
+            maxActionsPerValidator: null,
+          })
+          .accounts({ config: configPDA, admin: provider.wallet.publicKey })
+          .rpc();
+
+      try {
+        await setMaxWindow(50);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidMaxWindow");
+      }
+
+      await setMaxWindow(200);
+      try {
+        await program.methods
+          .amendAction({ threshold: null, windowSlots: new anchor.BN(201), descriptionHash: null })
+          .accounts({ fastAction: pda, authority: creator.publicKey })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidVotingWindow");
+      } finally {
+        await setMaxWindow(0);
+      }
+    });
+
+    it("rejects amendments once a vote is cast", async () => {
+      await program.methods
+        .voteFast(amendId, true, Array.from(Buffer.alloc(32, 47)), 0)
+        .accounts({
+          fastAction: pda,
+          fastVote: deriveFastVotePDA(pda, voter1.publicKey)[0],
+          voter: voter1.publicKey,
+          agent: voter1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter1])
+        .rpc();
+
+      try {
+        await program.methods
+          .amendAction({ threshold: 51, windowSlots: null, descriptionHash: null })
+          .accounts({ fastAction: pda, authority: creator.publicKey })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ActionHasVotes");
+      }
+    });
+  });
+
+  describe("co-sponsored drafts", () => {
+    const hash = Array.from(Buffer.alloc(32, 49));
+    const params = actionParams(hash, 60);
+    let draftPDA: PublicKey;
+
+    async function promote(draftParams: ReturnType<typeof actionParams>) {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(draftParams)
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          draft: draftPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+      return pda;
+    }
+
+    before(async () => {
+      [draftPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("proposal_draft"), creator.publicKey.toBuffer(), Buffer.from(hash)],
+        program.programId
+      );
+      await program.methods
+        .createDraft(params, [voter1.publicKey, voter2.publicKey], 2)
+        .accounts({ draft: draftPDA, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+    });
+
+    it("rejects signatures from non-sponsors", async () => {
+      try {
+        await program.methods
+          .sponsorDraft()
+          .accounts({ draft: draftPDA, sponsor: creator.publicKey })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("NotASponsor");
+      }
+    });
+
+    it("cannot be promoted before enough sponsors sign", async () => {
+      await program.methods
+        .sponsorDraft()
+        .accounts({ draft: draftPDA, sponsor: voter1.publicKey })
+        .signers([voter1])
+        .rpc();
+
+      try {
+        await promote(params);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("DraftNotSponsored");
+      }
+    });
+
+    it("promotes only the sponsored params", async () => {
+      await program.methods
+        .sponsorDraft()
+        .accounts({ draft: draftPDA, sponsor: voter2.publicKey })
+        .signers([voter2])
+        .rpc();
+
+      try {
+        await promote(actionParams(hash, 51));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("DraftParamsMismatch");
+      }
+
+      const pda = await promote(params);
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.draft.toBase58()).to.equal(draftPDA.toBase58());
+      const draft = await program.account.proposalDraft.fetch(draftPDA);
+      expect(draft.action.toBase58()).to.equal(pda.toBase58());
+    });
+  });
+
+  describe("action metadata", () => {
+    let pda: PublicKey;
+    let metadataPDA: PublicKey;
+
+    function setMetadata(description: string, uri: string, authority: Keypair = creator) {
+      return program.methods
+        .setMetadata(description, uri)
+        .accounts({
+          fastAction: pda,
+          metadata: metadataPDA,
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+    }
+
+    before(async () => {
+      const id = await nextActionId();
+      [pda] = deriveFastActionPDA(id);
+      [metadataPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("action_metadata"), pda.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 53)), 50))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+    });
+
+    it("stores the description and URI, sized to fit", async () => {
+      await setMetadata("Rotate the treasury signer", "https://example.com/proposals/53");
+
+      const metadata = await program.account.actionMetadata.fetch(metadataPDA);
+      expect(metadata.description).to.equal("Rotate the treasury signer");
+      expect(metadata.uri).to.equal("https://example.com/proposals/53");
+
+      await setMetadata("Rotate signer", "");
+      const info = await provider.connection.getAccountInfo(metadataPDA);
+      expect(info!.data.length).to.equal(8 + 32 + 4 + 13 + 4 + 0 + 1);
+    });
+
+    it("rejects descriptions over 256 bytes", async () => {
+      try {
+        await setMetadata("x".repeat(257), "");
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("DescriptionTooLong");
+      }
+    });
+
+    it("rejects updates from anyone but the authority", async () => {
+      try {
+        await setMetadata("hijacked", "", voter1);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  describe("sealed descriptions", () => {
+    const DESCRIPTION = "Rotate 40% of the treasury into the basis trade";
+    let pda: PublicKey;
+    let sealedPDA: PublicKey;
+
+    before(async () => {
+      const id = await nextActionId();
+      [pda] = deriveFastActionPDA(id);
+      [sealedPDA] = PublicKey.findProgramAddressSync([Buffer.from("sealed_description"), pda.toBuffer()], program.programId);
+      const descriptionHash = Array.from(createHash("sha256").update(DESCRIPTION).digest());
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 75)), 50, { descriptionHash }))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+    });
+
+    it("records the ciphertext hash and encryption key", async () => {
+      await program.methods
+        .sealDescription(Array.from(Buffer.alloc(32, 73)), Array.from(Buffer.alloc(32, 74)))
+        .accounts({
+          fastAction: pda,
+          sealedDescription: sealedPDA,
+          authority: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      const sealed = await program.account.sealedDescription.fetch(sealedPDA);
+      expect(sealed.action.toBase58()).to.equal(pda.toBase58());
+      expect(Buffer.from(sealed.encryptionKey)).to.deep.equal(Buffer.alloc(32, 74));
+      expect(sealed.revealedSlot.toNumber()).to.equal(0);
+    });
+
+    it("reveals nothing before the action passes", async () => {
+      const validator = Keypair.generate();
+      const [validatorRecord] = deriveValidatorPDA(validator.publicKey);
+      await program.methods
+        .registerValidator(VALIDATOR_BOND)
+        .accounts({
+          validatorRecord,
+          validator: validator.publicKey,
+          payer: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([validator])
+        .rpc();
+
+      try {
+        await program.methods
+          .revealDescription(DESCRIPTION)
+          .accounts({ fastAction: pda, sealedDescription: sealedPDA, validatorRecord, validator: validator.publicKey })
+          .signers([validator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ActionNotExecutable");
+      }
+    });
+  });
+
+  describe("result callbacks", () => {
+    let pda: PublicKey;
+    let registryPDA: PublicKey;
+
+    function registerCallback(programId: PublicKey, authority: Keypair = creator) {
+      return program.methods
+        .registerCallback(programId, Array.from(Buffer.alloc(32, 1)), 1)
+        .accounts({
+          fastAction: pda,
+          registry: registryPDA,
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+    }
+
+    before(async () => {
+      const id = await nextActionId();
+      [pda] = deriveFastActionPDA(id);
+      [registryPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("callbacks"), pda.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 54)), 50))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+    });
+
+    it("rejects registration by anyone but the authority", async () => {
+      try {
+        await registerCallback(Keypair.generate().publicKey, voter1);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+    });
+
+    it("registers up to four callbacks", async () => {
+      for (let i = 0; i < 4; i++) {
+        await registerCallback(Keypair.generate().publicKey);
+      }
+      const registry = await program.account.callbackRegistry.fetch(registryPDA);
+      expect(registry.count).to.equal(4);
+      expect(registry.dispatched).to.equal(false);
+
+      try {
+        await registerCallback(Keypair.generate().publicKey);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("TooManyCallbacks");
+      }
+    });
+
+    function stagePDA(executor: PublicKey) {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("execution_stage"), pda.toBuffer(), executor.toBuffer()],
+        program.programId
+      )[0];
+    }
+
+    function stageExecution(executor: Keypair, commitment: number[]) {
+      return program.methods
+        .stageExecution(commitment)
+        .accounts({
+          fastAction: pda,
+          registry: registryPDA,
+          stage: stagePDA(executor.publicKey),
+          executor: executor.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([executor])
+        .rpc();
+    }
+
+    it("stages an executor's commitment", async () => {
+      const commitment = Array.from(Buffer.alloc(32, 9));
+      await stageExecution(creator, commitment);
+      const stage = await program.account.executionStage.fetch(stagePDA(creator.publicKey));
+      expect(stage.action.toBase58()).to.equal(pda.toBase58());
+      expect(stage.executor.toBase58()).to.equal(creator.publicKey.toBase58());
+      expect(stage.commitment).to.deep.equal(commitment);
+    });
+
+    it("rejects execution before the result settles", async () => {
+      try {
+        await program.methods
+          .executeAction()
+          .accounts({
+            fastAction: pda,
+            registry: registryPDA,
+            stage: stagePDA(creator.publicKey),
+            executor: creator.publicKey,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("DisputeWindowOpen");
+      }
+    });
+
+    it("rejects execution without a stage of the executor's own", async () => {
+      try {
+        await program.methods
+          .executeAction()
+          .accounts({
+            fastAction: pda,
+            registry: registryPDA,
+            stage: stagePDA(creator.publicKey),
+            executor: voter1.publicKey,
+          })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ConstraintSeeds");
+      }
+    });
+
+    it("rejects a keeper schedule before the action is finalized", async () => {
+      const [schedulePDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("execution_schedule"), pda.toBuffer()],
+        program.programId
+      );
+      try {
+        await program.methods
+          .scheduleExecution(new anchor.BN(10_000), [])
+          .accounts({
+            fastAction: pda,
+            registry: registryPDA,
+            schedule: schedulePDA,
+            funder: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ActionNotFinalized");
+      }
+    });
+  });
+
+  describe("config change actions", () => {
+    const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+
+    it("rejects config changes decided by headcount", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      try {
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 55)), 60, { kind: { configChange: {} } }))
+          .accounts({
+            fastAction: pda,
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidConfigChangeAction");
+      }
+    });
+
+    it("refuses to apply changes from standard actions", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 56)), 60))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      try {
+        await program.methods
+          .applyConfigChange({ guardian: { 0: voter1.publicKey } })
+          .accounts({
+            fastAction: pda,
+            config: configPDA,
+            payer: provider.wallet.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidConfigChangeAction");
+      }
+    });
+  });
+
+  describe("program upgrade actions", () => {
+    it("rejects upgrades decided by headcount", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      try {
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 57)), 67, { kind: { programUpgrade: {} } }))
+          .accounts({
+            fastAction: pda,
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidUpgradeAction");
+      }
+    });
+  });
+
+  describe("budget actions", () => {
+    it("rejects budgets decided by headcount", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      try {
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 58)), 60, { kind: { budget: {} } }))
+          .accounts({
+            fastAction: pda,
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidBudgetAction");
+      }
+    });
+
+    it("refuses to open streams from standard actions", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 59)), 60))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      const budget = {
+        recipient: voter1.publicKey,
+        mint: PublicKey.default,
+        amount: new anchor.BN(1_000_000),
+        startSlot: new anchor.BN(0),
+        endSlot: new anchor.BN(1_000),
+      };
+      const slowVoting = anchor.workspace.KamiyoSlowVoting.programId as PublicKey;
+      const [slowConfig] = PublicKey.findProgramAddressSync([Buffer.from("slow_config")], slowVoting);
+      const [slowAction] = PublicKey.findProgramAddressSync([Buffer.from("slow_action"), pda.toBuffer()], slowVoting);
+      try {
+        await program.methods
+          .openStream(budget)
+          .accounts({
+            fastAction: pda,
+            slowConfig,
+            slowAction,
+            payer: provider.wallet.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidBudgetAction");
+      }
+    });
+  });
+
+  describe("weight caps", () => {
+    it("rejects a cap on a headcount action", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      try {
+        await program.methods
+          .createFastAction(
+            actionParams(Array.from(Buffer.alloc(32, 60)), 60, { weightCap: { absolute: { 0: new anchor.BN(100) } } })
+          )
+          .accounts({
+            fastAction: pda,
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidWeightCap");
+      }
+    });
+
+    it("only lets the config admin set defaults", async () => {
+      try {
+        await program.methods
+          .setWeightCap({ budget: {} }, { supplyBps: { 0: 500 } })
+          .accounts({ admin: voter1.publicKey, systemProgram: SystemProgram.programId })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  describe("identity weighting", () => {
+    const collection = Keypair.generate().publicKey;
+    const tiers = [
+      { value: "gold", weight: new anchor.BN(3) },
+      { value: "silver", weight: new anchor.BN(2) },
+    ];
+
+    it("needs a token-weighted for/against action", async () => {
+      const id = await nextActionId();
+      try {
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 3)), 60, { identityWeighted: true }))
+          .accounts({ fastAction: deriveFastActionPDA(id)[0], creator: creator.publicKey, systemProgram: SystemProgram.programId })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidIdentityWeighting");
+      }
+    });
+
+    it("rejects duplicate tiers", async () => {
+      try {
+        await program.methods
+          .setIdentityWeights(collection, "tier", [...tiers, { value: "gold", weight: new anchor.BN(1) }])
+          .accounts({ admin: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidIdentityWeights");
+      }
+    });
+
+    it("only lets the config admin set the tiers", async () => {
+      try {
+        await program.methods
+          .setIdentityWeights(collection, "tier", tiers)
+          .accounts({ admin: voter1.publicKey, systemProgram: SystemProgram.programId })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+
+      await program.methods
+        .setIdentityWeights(collection, "tier", tiers)
+        .accounts({ admin: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+        .rpc();
+      const [weightsPDA] = PublicKey.findProgramAddressSync([Buffer.from("identity_weights")], program.programId);
+      const weights = await program.account.identityWeights.fetch(weightsPDA);
+      expect(weights.collection.toBase58()).to.equal(collection.toBase58());
+      expect(weights.tiers.map((tier: any) => tier.value)).to.deep.equal(["gold", "silver"]);
+    });
+  });
+
+  describe("early-vote multiplier", () => {
+    it("rejects the bonus on a headcount action", async () => {
+      const id = await nextActionId();
+      try {
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 76)), 60, { earlyVoteBonus: true }))
+          .accounts({ fastAction: deriveFastActionPDA(id)[0], creator: creator.publicKey, systemProgram: SystemProgram.programId })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidEarlyVoteCurve");
+      }
+    });
+
+    it("records the kind's curve, 1.2x linear by default", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(
+          actionParams(Array.from(Buffer.alloc(32, 77)), 60, { voteMode: { tokenWeighted: {} }, earlyVoteBonus: true })
+        )
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.earlyVoteCurve.bonusBps).to.equal(2_000);
+      expect(action.earlyVoteCurve.shape).to.have.property("linear");
+    });
+
+    it("rejects curves above 1.2x", async () => {
+      try {
+        await program.methods
+          .setEarlyVoteCurve({ standard: {} }, { bonusBps: 2_001, shape: { quadratic: {} } })
+          .accounts({ admin: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidEarlyVoteCurve");
+      }
+    });
+  });
+
+  describe("weight snapshots", () => {
+    const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+    const WEIGHT = 7_000;
+    let actionId: anchor.BN;
+    let actionPDA: PublicKey;
+
+    function setSnapshotter(snapshotter: PublicKey) {
+      return program.methods
+        .updateConfig({
+          admin: null,
+          weightMint: null,
+          weightExtensions: null,
+          transferHookProgram: null,
+          maxActionsPerEpoch: null,
+          guardian: null,
+          voteCreditsPerEpoch: null,
+          minAgentBond: null,
+          whistleblowerBps: null,
+          snapshotter,
+          maxWindowSlots: null,
+          stakeLockProgram: null,
+          maxLowPriorityActions: null,
+          maxVotesPerAction: null,
+          identityRamp: null,
+          feeRebate: null,
+          maxActionsPerValidator: null,
+        })
+        .accounts({ config: configPDA, admin: provider.wallet.publicKey })
+        .rpc();
+    }
+
+    function leaf(voter: PublicKey, weight: number) {
+      const le = Buffer.alloc(8);
+      le.writeBigUInt64LE(BigInt(weight));
+      return createHash("sha256").update(Buffer.concat([Buffer.from("kamiyo_weight"), voter.toBuffer(), le])).digest();
+    }
+
+    function post(root: Buffer, action = actionPDA, totalWeight = WEIGHT) {
+      return program.methods
+        .postWeightSnapshot(Array.from(root), new anchor.BN(totalWeight))
+        .accounts({
+          fastAction: action,
+          validatorRecord: null,
+          poster: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    function vote(weight: number) {
+      return program.methods
+        .voteFastSnapshot(actionId, true, Array.from(Buffer.alloc(32, 82)), 0, new anchor.BN(weight), [])
+        .accounts({ fastAction: actionPDA, voter: voter1.publicKey, systemProgram: SystemProgram.programId })
+        .signers([voter1])
+        .rpc();
+    }
+
+    before(async () => {
+      actionId = await nextActionId();
+      [actionPDA] = deriveFastActionPDA(actionId);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 81)), 60, { voteMode: { tokenWeighted: {} } }))
+        .accounts({ fastAction: actionPDA, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+    });
+
+    after(async () => {
+      await setSnapshotter(PublicKey.default);
+    });
+
+    it("only takes roots from the snapshotter or an allowlisted validator", async () => {
+      try {
+        await post(leaf(voter1.publicKey, WEIGHT));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+    });
+
+    it("needs a token-weighted action", async () => {
+      await setSnapshotter(provider.wallet.publicKey);
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 83)), 60))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      try {
+        await post(leaf(voter1.publicKey, WEIGHT), pda);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidWeightSnapshot");
+      }
+    });
+
+    it("counts a ballot only at its proven weight", async () => {
+      await post(leaf(voter1.publicKey, WEIGHT));
+      const snapshot = await program.account.weightSnapshot.fetch(
+        PublicKey.findProgramAddressSync([Buffer.from("weight_snapshot"), actionPDA.toBuffer()], program.programId)[0]
+      );
+      const action = await program.account.fastAction.fetch(actionPDA);
+      expect(snapshot.snapshotSlot.toNumber()).to.equal(action.createdSlot.toNumber());
+      expect(snapshot.totalWeight.toNumber()).to.equal(WEIGHT);
+      expect(action.snapshotWeight.toNumber()).to.equal(WEIGHT);
+
+      try {
+        await vote(WEIGHT * 2);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidWeightProof");
+      }
+      await vote(WEIGHT);
+      const voted = await program.account.fastAction.fetch(actionPDA);
+      expect(voted.weightFor.toNumber()).to.equal(WEIGHT);
+    });
+
+    it("takes a weight quorum only on token-weighted for/against actions", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      for (const overrides of [
+        { quorumWeightBps: 5_000 },
+        { quorumWeightBps: 10_001, voteMode: { tokenWeighted: {} } },
+        { quorumWeightBps: 5_000, voteMode: { tokenWeighted: {} }, lockStake: true },
+      ]) {
+        try {
+          await program.methods
+            .createFastAction(actionParams(Array.from(Buffer.alloc(32, 84)), 60, overrides))
+            .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+            .signers([creator])
+            .rpc();
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include("InvalidWeightQuorum");
+        }
+      }
+    });
+
+    it("counts a weight quorum against the snapshot's total", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(
+          actionParams(Array.from(Buffer.alloc(32, 85)), 60, { voteMode: { tokenWeighted: {} }, quorumWeightBps: 5_000 })
+        )
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      await post(leaf(voter1.publicKey, WEIGHT), pda, WEIGHT * 4);
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.quorumWeightBps).to.equal(5_000);
+      expect(action.snapshotWeight.toNumber()).to.equal(WEIGHT * 4);
+    });
+  });
+
+  describe("compressed ballots", () => {
+    const addressTree = Keypair.generate().publicKey;
+
+    function enable(action: PublicKey, authority: Keypair) {
+      return program.methods
+        .enableCompressedBallots(addressTree)
+        .accounts({ fastAction: action, authority: authority.publicKey, systemProgram: SystemProgram.programId })
+        .signers([authority])
+        .rpc();
+    }
+
+    async function create(fill: number, voteMode?: any) {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, fill)), 60, voteMode ? { voteMode } : {}))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      return { id, pda };
+    }
+
+    it("only the action authority may enable them", async () => {
+      const { pda } = await create(84);
+      try {
+        await enable(pda, voter1);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+    });
+
+    it("needs a headcount or optimistic action", async () => {
+      const { pda } = await create(85, { tokenWeighted: {} });
+      try {
+        await enable(pda, creator);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidCompressedBallots");
+      }
+    });
+
+    it("turns PDA ballots away once enabled", async () => {
+      const { id, pda } = await create(86);
+      await enable(pda, creator);
+      const [marker] = PublicKey.findProgramAddressSync(
+        [Buffer.from("compressed_ballots"), pda.toBuffer()],
+        program.programId
+      );
+      const ballots = await program.account.compressedBallots.fetch(marker);
+      expect(ballots.action.toBase58()).to.equal(pda.toBase58());
+      expect(ballots.addressTree.toBase58()).to.equal(addressTree.toBase58());
+      expect((await program.account.fastAction.fetch(pda)).audited).to.equal(true);
+
+      try {
+        await program.methods
+          .voteFast(id, true, Array.from(Buffer.alloc(32, 86)), 0)
+          .accounts({
+            fastAction: pda,
+            voter: voter1.publicKey,
+            agent: voter1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("CompressedBallotsOnly");
+      }
+    });
+
+    it("takes ballots only through the pinned address tree", async () => {
+      const { id, pda } = await create(138);
+      await enable(pda, creator);
+      const proof = { proof: { a: Array(32).fill(0), b: Array(64).fill(0), c: Array(32).fill(0) }, rootIndex: 0 };
+      try {
+        await program.methods
+          .voteFastCompressed(id, true, Array.from(Buffer.alloc(32, 138)), 0, proof)
+          .accounts({
+            fastAction: pda,
+            voter: voter1.publicKey,
+            agent: voter1.publicKey,
+            registeredProgramPda: Keypair.generate().publicKey,
+            noopProgram: new PublicKey("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV"),
+            accountCompressionAuthority: Keypair.generate().publicKey,
+            accountCompressionProgram: new PublicKey("compr6CUsB5m2jS4Y3831ztGSTnDpnKJTKS95d64XVq"),
+            selfProgram: program.programId,
+            addressTree: Keypair.generate().publicKey,
+            addressQueue: Keypair.generate().publicKey,
+            stateTree: Keypair.generate().publicKey,
+            lightSystemProgram: new PublicKey("SySTEM1eSU2p4BGQfQpimFEWWSC1XDFeun3Nqzz3rT7"),
+            metrics: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidAddressTree");
+      }
+    });
+  });
+
+  describe("vote credits", () => {
+    const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+    const agent = Keypair.generate();
+
+    function setVoteCredits(credits: number) {
+      return program.methods
+        .updateConfig({
+          admin: null,
+          weightMint: null,
+          weightExtensions: null,
+          transferHookProgram: null,
+          maxActionsPerEpoch: null,
+          guardian: null,
+          voteCreditsPerEpoch: credits,
+          minAgentBond: null,
+          whistleblowerBps: null,
+          snapshotter: null,
+          maxWindowSlots: null,
+          stakeLockProgram: null,
+          maxLowPriorityActions: null,
+          maxVotesPerAction: null,
+          identityRamp: null,
+          feeRebate: null,
+          maxActionsPerValidator: null,
+        })
+        .accounts({ config: configPDA, admin: provider.wallet.publicKey })
+        .rpc();
+    }
+
+    async function createAction(hash: number): Promise<[anchor.BN, PublicKey]> {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, hash)), 50))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      return [id, pda];
+    }
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(agent.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await setVoteCredits(1);
+    });
+
+    after(async () => {
+      await setVoteCredits(0);
+    });
+
+    it("stops an agent once its credits run out", async () => {
+      const [firstId, first] = await createAction(61);
+      const [secondId, second] = await createAction(62);
+
+      await program.methods
+        .voteFast(firstId, true, Array.from(Buffer.alloc(32, 61)), 0)
+        .accounts({
+          fastAction: first,
+          voter: agent.publicKey,
+          agent: agent.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([agent])
+        .rpc();
+
+      const [creditsPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vote_credits"), agent.publicKey.toBuffer()],
+        program.programId
+      );
+      const credits = await program.account.voteCredits.fetch(creditsPDA);
+      expect(credits.spent).to.equal(1);
+
+      try {
+        await program.methods
+          .voteFast(secondId, true, Array.from(Buffer.alloc(32, 62)), 0)
+          .accounts({
+            fastAction: second,
+            voter: agent.publicKey,
+            agent: agent.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([agent])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("VoteCreditsExhausted");
+      }
+    });
+  });
+
+  describe("agent bonds", () => {
+    const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+    const agent = Keypair.generate();
+    const [recordPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("agent"), agent.publicKey.toBuffer()],
+      program.programId
+    );
+    let actionId: anchor.BN;
+    let actionPDA: PublicKey;
+
+    function setMinBond(bond: number) {
+      return program.methods
+        .updateConfig({
+          admin: null,
+          weightMint: null,
+          weightExtensions: null,
+          transferHookProgram: null,
+          maxActionsPerEpoch: null,
+          guardian: null,
+          voteCreditsPerEpoch: null,
+          minAgentBond: new anchor.BN(bond),
+          whistleblowerBps: null,
+          snapshotter: null,
+          maxWindowSlots: null,
+          stakeLockProgram: null,
+          maxLowPriorityActions: null,
+          maxVotesPerAction: null,
+          identityRamp: null,
+          feeRebate: null,
+          maxActionsPerValidator: null,
+        })
+        .accounts({ config: configPDA, admin: provider.wallet.publicKey })
+        .rpc();
+    }
+
+    function vote() {
+      return program.methods
+        .voteFast(actionId, true, Array.from(Buffer.alloc(32, 63)), 0)
+        .accounts({
+          fastAction: actionPDA,
+          voter: agent.publicKey,
+          agent: agent.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([agent])
+        .rpc();
+    }
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(agent.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      actionId = await nextActionId();
+      [actionPDA] = deriveFastActionPDA(actionId);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 63)), 50))
+        .accounts({ fastAction: actionPDA, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      await setMinBond(50_000_000);
+    });
+
+    after(async () => {
+      await setMinBond(0);
+    });
+
+    it("turns away agents without a bond", async () => {
+      try {
+        await vote();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("AgentNotBonded");
+      }
+    });
+
+    it("lets a bonded agent vote", async () => {
+      await program.methods
+        .registerAgent(new anchor.BN(50_000_000))
+        .accounts({ agent: agent.publicKey, systemProgram: SystemProgram.programId })
+        .signers([agent])
+        .rpc();
+      await vote();
+
+      const record = await program.account.agentRecord.fetch(recordPDA);
+      expect(record.bond.toNumber()).to.equal(50_000_000);
+      const { epoch } = await provider.connection.getEpochInfo();
+      expect(record.registeredEpoch.toNumber()).to.equal(epoch);
+    });
+
+    it("rejects an identity ramp floor above full weight", async () => {
+      const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+      try {
+        await program.methods
+          .updateConfig({
+            admin: null,
+            weightMint: null,
+            weightExtensions: null,
+            transferHookProgram: null,
+            maxActionsPerEpoch: null,
+            guardian: null,
+            voteCreditsPerEpoch: null,
+            minAgentBond: null,
+            whistleblowerBps: null,
+            snapshotter: null,
+            maxWindowSlots: null,
+            stakeLockProgram: null,
+            maxLowPriorityActions: null,
+            maxVotesPerAction: null,
+            identityRamp: { epochs: 4, floorBps: 10_001 },
+            feeRebate: null,
+            maxActionsPerValidator: null,
+          })
+          .accounts({ config: configPDA, admin: provider.wallet.publicKey })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidIdentityRamp");
+      }
+    });
+
+    it("refunds the bond on eviction", async () => {
+      const before = await provider.connection.getBalance(agent.publicKey);
+      await program.methods
+        .evictAgent()
+        .accounts({ agent: agent.publicKey, admin: provider.wallet.publicKey })
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(recordPDA)).to.be.null;
+      expect(await provider.connection.getBalance(agent.publicKey)).to.be.greaterThan(before + 50_000_000);
+    });
+  });
+
+  describe("vote keys", () => {
+    const agent = Keypair.generate();
+    const key = Keypair.generate();
+    const stranger = Keypair.generate();
+    const [keyPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vote_key"), key.publicKey.toBuffer()],
+      program.programId
+    );
+
+    async function create(hash: number, opts: any = {}): Promise<[anchor.BN, PublicKey]> {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, hash)), 50, opts))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      return [id, pda];
+    }
+
+    function vote(id: anchor.BN, pda: PublicKey, signer: Keypair, forAgent = agent.publicKey) {
+      return program.methods
+        .voteFast(id, true, Array.from(Buffer.alloc(32, 140)), 0)
+        .accounts({
+          fastAction: pda,
+          voter: signer.publicKey,
+          agent: forAgent,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([signer])
+        .rpc();
+    }
+
+    async function expectError(promise: Promise<unknown>, error: string) {
+      try {
+        await promise;
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include(error);
+      }
+    }
+
+    before(async () => {
+      for (const kp of [agent, key, stranger]) {
+        const sig = await provider.connection.requestAirdrop(kp.publicKey, LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(sig);
+      }
+      await program.methods
+        .registerAgent(new anchor.BN(1_000_000))
+        .accounts({ agent: agent.publicKey, systemProgram: SystemProgram.programId })
+        .signers([agent])
+        .rpc();
+      await program.methods
+        .setVoteKey()
+        .accounts({ agent: agent.publicKey, key: key.publicKey })
+        .signers([agent, key])
+        .rpc();
+    });
+
+    it("counts the main key's ballot for the agent", async () => {
+      const [id, pda] = await create(140);
+      await vote(id, pda, agent);
+
+      const ballot = await program.account.fastVote.fetch(deriveFastVotePDA(pda, agent.publicKey)[0]);
+      expect(ballot.voter.toBase58()).to.equal(agent.publicKey.toBase58());
+    });
+
+    it("counts a vote key's ballot for its agent", async () => {
+      const [id, pda] = await create(141);
+      await vote(id, pda, key);
+
+      const ballot = await program.account.fastVote.fetch(deriveFastVotePDA(pda, agent.publicKey)[0]);
+      expect(ballot.voter.toBase58()).to.equal(agent.publicKey.toBase58());
+      expect(await provider.connection.getAccountInfo(deriveFastVotePDA(pda, key.publicKey)[0])).to.be.null;
+    });
+
+    it("takes one ballot per agent whichever key signs first", async () => {
+      const [mainFirst, mainFirstPda] = await create(142);
+      await vote(mainFirst, mainFirstPda, agent);
+      await expectError(vote(mainFirst, mainFirstPda, key), "AlreadyVoted");
+
+      const [keyFirst, keyFirstPda] = await create(143);
+      await vote(keyFirst, keyFirstPda, key);
+      await expectError(vote(keyFirst, keyFirstPda, agent), "AlreadyVoted");
+      expect((await program.account.fastAction.fetch(keyFirstPda)).voteCount).to.equal(1);
+    });
+
+    it("refuses a vote key voting as itself or another agent", async () => {
+      const [id, pda] = await create(144);
+      await expectError(vote(id, pda, key, key.publicKey), "AgentMismatch");
+      await expectError(vote(id, pda, stranger, agent.publicKey), "AgentMismatch");
+    });
+
+    it("keeps a bound key from registering as an agent", async () => {
+      await expectError(
+        program.methods
+          .registerAgent(new anchor.BN(1_000_000))
+          .accounts({ agent: key.publicKey, systemProgram: SystemProgram.programId })
+          .signers([key])
+          .rpc(),
+        "VoteKeyInUse"
+      );
+      await expectError(
+        program.methods
+          .setVoteKey()
+          .accounts({ agent: key.publicKey, key: stranger.publicKey })
+          .signers([key, stranger])
+          .rpc(),
+        "AccountNotInitialized"
+      );
+
+      await program.methods
+        .registerAgent(new anchor.BN(1_000_000))
+        .accounts({ agent: stranger.publicKey, systemProgram: SystemProgram.programId })
+        .signers([stranger])
+        .rpc();
+      await expectError(
+        program.methods
+          .setVoteKey()
+          .accounts({ agent: agent.publicKey, key: stranger.publicKey })
+          .signers([agent, stranger])
+          .rpc(),
+        "VoteKeyInUse"
+      );
+    });
+
+    it("takes one ranked ballot per agent whichever key signs", async () => {
+      const [id, pda] = await create(146, { optionCount: 3, ranked: true });
+      const voteRanked = (signer: Keypair) =>
+        program.methods
+          .voteRanked(id, Buffer.from([1, 0]), Array.from(Buffer.alloc(32, 146)), 0)
+          .accounts({
+            fastAction: pda,
+            fastVote: deriveFastVotePDA(pda, agent.publicKey)[0],
+            rankedBallot: deriveRankedBallotPDA(pda, agent.publicKey)[0],
+            voter: signer.publicKey,
+            agent: agent.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([signer])
+          .rpc();
+
+      await voteRanked(key);
+      const ballot = await program.account.rankedBallot.fetch(deriveRankedBallotPDA(pda, agent.publicKey)[0]);
+      expect(ballot.voter.toBase58()).to.equal(agent.publicKey.toBase58());
+      await expectError(voteRanked(agent), "AlreadyVoted");
+      expect((await program.account.fastAction.fetch(pda)).voteCount).to.equal(1);
+    });
+
+    it("takes one headcount option ballot per agent whichever key signs", async () => {
+      const [id, pda] = await create(147, { optionCount: 3 });
+      const voteOptions = (signer: Keypair) =>
+        program.methods
+          .voteOptions(id, [new anchor.BN(0), new anchor.BN(1), new anchor.BN(0)], Array.from(Buffer.alloc(32, 147)), 0)
+          .accounts({
+            fastAction: pda,
+            fastVote: deriveFastVotePDA(pda, agent.publicKey)[0],
+            voter: signer.publicKey,
+            agent: agent.publicKey,
+            config: null,
+            stakeLock: null,
+            stakeLockClaim: null,
+            lockAuthority: null,
+            stakeLockProgram: null,
+            weightMint: null,
+            voterTokenAccount: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([signer])
+          .rpc();
+
+      await voteOptions(agent);
+      await expectError(voteOptions(key), "AlreadyVoted");
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.voteCount).to.equal(1);
+      expect(action.optionWeights.slice(0, 3).map((w: anchor.BN) => w.toNumber())).to.deep.equal([0, 1, 0]);
+    });
+
+    it("derives a vote key's compressed ballot from its agent", async () => {
+      const [id, pda] = await create(148);
+      await program.methods
+        .enableCompressedBallots(Keypair.generate().publicKey)
+        .accounts({ fastAction: pda, authority: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      const proof = { proof: { a: Array(32).fill(0), b: Array(64).fill(0), c: Array(32).fill(0) }, rootIndex: 0 };
+      // The address, and so Light's non-inclusion check, follows the agent;
+      // with no prover here the ballot stops at the unpinned tree
+      const voteCompressed = (forAgent: PublicKey) =>
+        program.methods
+          .voteFastCompressed(id, true, Array.from(Buffer.alloc(32, 148)), 0, proof)
+          .accounts({
+            fastAction: pda,
+            voter: key.publicKey,
+            agent: forAgent,
+            registeredProgramPda: Keypair.generate().publicKey,
+            noopProgram: new PublicKey("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV"),
+            accountCompressionAuthority: Keypair.generate().publicKey,
+            accountCompressionProgram: new PublicKey("compr6CUsB5m2jS4Y3831ztGSTnDpnKJTKS95d64XVq"),
+            selfProgram: program.programId,
+            addressTree: Keypair.generate().publicKey,
+            addressQueue: Keypair.generate().publicKey,
+            stateTree: Keypair.generate().publicKey,
+            lightSystemProgram: new PublicKey("SySTEM1eSU2p4BGQfQpimFEWWSC1XDFeun3Nqzz3rT7"),
+            metrics: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([key])
+          .rpc();
+
+      await expectError(voteCompressed(key.publicKey), "AgentMismatch");
+      await expectError(voteCompressed(agent.publicKey), "InvalidAddressTree");
+    });
+
+    it("lets a cleared key vote as itself again", async () => {
+      await program.methods
+        .clearVoteKey()
+        .accounts({ agent: agent.publicKey, voteKey: keyPDA })
+        .signers([agent])
+        .rpc();
+      expect(await provider.connection.getAccountInfo(keyPDA)).to.be.null;
+
+      const [id, pda] = await create(145);
+      await vote(id, pda, agent);
+      await vote(id, pda, key, key.publicKey);
+      expect((await program.account.fastAction.fetch(pda)).voteCount).to.equal(2);
+    });
+  });
+
+  describe("equivocation", () => {
+    const agent = Keypair.generate();
+    const [recordPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("agent"), agent.publicKey.toBuffer()],
+      program.programId
+    );
+    const [treasuryPDA] = PublicKey.findProgramAddressSync([Buffer.from("treasury")], program.programId);
+
+    function ballot(action: PublicKey, choice: number) {
+      return { action, choice };
+    }
+
+    function signed(b: { action: PublicKey; choice: number }, signer = agent) {
+      const message = Buffer.concat([Buffer.from("kamiyo_ballot"), b.action.toBuffer(), Buffer.from([b.choice])]);
+      return Ed25519Program.createInstructionWithPrivateKey({ privateKey: signer.secretKey, message });
+    }
+
+    function report(first: any, second: any, signatures: any[]) {
+      return program.methods
+        .reportEquivocation(first, second)
+        .accounts({
+          agent: agent.publicKey,
+          reporter: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .preInstructions(signatures)
+        .rpc();
+    }
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(agent.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      await program.methods
+        .registerAgent(new anchor.BN(10_000_000))
+        .accounts({ agent: agent.publicKey, systemProgram: SystemProgram.programId })
+        .signers([agent])
+        .rpc();
+    });
+
+    it("rejects ballots that do not conflict", async () => {
+      const action = Keypair.generate().publicKey;
+      const first = ballot(action, 1);
+      try {
+        await report(first, first, [signed(first)]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("BallotsDoNotConflict");
+      }
+    });
+
+    it("needs both ballots signed by the agent", async () => {
+      const action = Keypair.generate().publicKey;
+      const [first, second] = [ballot(action, 1), ballot(action, 0)];
+      try {
+        await report(first, second, [signed(first), signed(second, voter1)]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("MissingBallotSignature");
+      }
+    });
+
+    it("caps the whistleblower share at half", async () => {
+      const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+      try {
+        await program.methods
+          .updateConfig({
+            admin: null,
+            weightMint: null,
+            weightExtensions: null,
+            transferHookProgram: null,
+            maxActionsPerEpoch: null,
+            guardian: null,
+            voteCreditsPerEpoch: null,
+            minAgentBond: null,
+            whistleblowerBps: 5_001,
+            snapshotter: null,
+            maxWindowSlots: null,
+            stakeLockProgram: null,
+            maxLowPriorityActions: null,
+            maxVotesPerAction: null,
+            identityRamp: null,
+            feeRebate: null,
+            maxActionsPerValidator: null,
+          })
+          .accounts({ config: configPDA, admin: provider.wallet.publicKey })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidWhistleblowerBounty");
+      }
+      const config = await program.account.globalConfig.fetch(configPDA);
+      expect(config.whistleblowerBps).to.equal(1_000);
+    });
+
+    it("slashes the agent's bond, paying the reporter a bounty", async () => {
+      const action = Keypair.generate().publicKey;
+      const [first, second] = [ballot(action, 1), ballot(action, 0)];
+      const before = await provider.connection.getBalance(treasuryPDA);
+      await report(first, second, [signed(first), signed(second)]);
+
+      const record = await program.account.agentRecord.fetch(recordPDA);
+      expect(record.bond.toNumber()).to.equal(0);
+      // 10% to the reporter, the rest to the treasury
+      expect(await provider.connection.getBalance(treasuryPDA)).to.be.at.least(before + 9_000_000);
+
+      try {
+        await report(first, second, [signed(first), signed(second)]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("already in use");
+      }
+    });
+  });
+
+  describe("agent classes", () => {
+    const scouts = [Keypair.generate(), Keypair.generate()];
+    let actionId: anchor.BN;
+    let actionPDA: PublicKey;
+
+    function vote(scout: Keypair) {
+      return program.methods
+        .voteFast(actionId, true, Array.from(Buffer.alloc(32, 64)), 0)
+        .accounts({
+          fastAction: actionPDA,
+          voter: scout.publicKey,
+          agent: scout.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([scout])
+        .rpc();
+    }
+
+    before(async () => {
+      for (const scout of scouts) {
+        const sig = await provider.connection.requestAirdrop(scout.publicKey, LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(sig);
+        await program.methods
+          .setAgentClass({ scout: {} })
+          .accounts({
+            agent: scout.publicKey,
+            admin: provider.wallet.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+      }
+      actionId = await nextActionId();
+      [actionPDA] = deriveFastActionPDA(actionId);
+      await program.methods
+        .createFastAction(
+          actionParams(Array.from(Buffer.alloc(32, 64)), 50, {
+            classLimits: [{ class: { scout: {} }, maxVotes: 1, weightBps: 0 }],
+          })
+        )
+        .accounts({ fastAction: actionPDA, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+    });
+
+    it("rejects weight scales on a headcount action", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      try {
+        await program.methods
+          .createFastAction(
+            actionParams(Array.from(Buffer.alloc(32, 65)), 50, {
+              classLimits: [{ class: { coordinator: {} }, maxVotes: 0, weightBps: 20_000 }],
+            })
+          )
+          .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidClassLimit");
+      }
+    });
+
+    it("caps the ballots a class may cast", async () => {
+      await vote(scouts[0]);
+      try {
+        await vote(scouts[1]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ClassVoteCapReached");
+      }
+
+      const action = await program.account.fastAction.fetch(actionPDA);
+      expect(action.classVotes).to.deep.equal([0, 1, 0, 0]);
+    });
+  });
+
+  describe("vote delegation", () => {
+    const delegator = Keypair.generate();
+    const [delegationPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vote_delegation"), delegator.publicKey.toBuffer()],
+      program.programId
+    );
+
+    function setDelegation(shares: { delegate: PublicKey; bps: number }[]) {
+      return program.methods
+        .setVoteDelegation(shares)
+        .accounts({ delegator: delegator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([delegator])
+        .rpc();
+    }
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(delegator.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+    });
+
+    it("rejects splits over 100%", async () => {
+      try {
+        await setDelegation([
+          { delegate: voter1.publicKey, bps: 6_000 },
+          { delegate: voter2.publicKey, bps: 5_000 },
+        ]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidDelegation");
+      }
+    });
+
+    it("splits weight and keeps the rest", async () => {
+      await setDelegation([
+        { delegate: voter1.publicKey, bps: 5_000 },
+        { delegate: voter2.publicKey, bps: 3_000 },
+      ]);
+
+      const delegation = await program.account.voteDelegation.fetch(delegationPDA);
+      expect(delegation.current.count).to.equal(2);
+      expect(delegation.current.shares[0].delegate.equals(voter1.publicKey)).to.be.true;
+      expect(delegation.current.shares[1].bps).to.equal(3_000);
+      expect(delegation.previous.count).to.equal(0);
+    });
+
+    it("refuses a second change within the cooldown", async () => {
+      try {
+        await setDelegation([]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("DelegationCooldown");
+      }
+    });
+  });
+
+  describe("audited tallies", () => {
+    it("rejects audited option actions", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      try {
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 66)), 50, { optionCount: 3, audited: true }))
+          .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidAuditedTally");
+      }
+    });
+
+    it("only recounts once voting has ended", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 66)), 50, { audited: true }))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      await program.methods
+        .voteFast(id, true, Array.from(Buffer.alloc(32, 66)), 0)
+        .accounts({
+          fastAction: pda,
+          voter: voter1.publicKey,
+          agent: voter1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter1])
+        .rpc();
+
+      try {
+        await program.methods
+          .verifyTallyPage()
+          .accounts({ fastAction: pda, payer: creator.publicKey, systemProgram: SystemProgram.programId })
+          .remainingAccounts([
+            { pubkey: deriveFastVotePDA(pda, voter1.publicKey)[0], isWritable: false, isSigner: false },
+          ])
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("VotingNotEnded");
+      }
+    });
+  });
+
+  describe("tally_and_commit validation", () => {
+    it("rejects tally before deadline", async () => {
+      const tallyId = await nextActionId();
+      const [pda] = deriveFastActionPDA(tallyId);
+
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 12)), 50))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      // Add votes to meet quorum
+      const [vote1Pda] = deriveFastVotePDA(pda, voter1.publicKey);
+      const [vote2Pda] = deriveFastVotePDA(pda, voter2.publicKey);
+
+      await program.methods
+        .voteFast(tallyId, true, Array.from(Buffer.alloc(32, 1)), 0)
+        .accounts({
+          fastAction: pda,
+          fastVote: vote1Pda,
+          voter: voter1.publicKey,
+          agent: voter1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter1])
+        .rpc();
+
+      await program.methods
+        .voteFast(tallyId, true, Array.from(Buffer.alloc(32, 2)), 0)
+        .accounts({
+          fastAction: pda,
+          fastVote: vote2Pda,
+          voter: voter2.publicKey,
+          agent: voter2.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter2])
+        .rpc();
+
+      // MagicBlock program and context (will fail validation but we test the timing check first)
+      const fakeMagicProgram = new PublicKey("Magic11111111111111111111111111111111111111");
+      const fakeMagicContext = new PublicKey("MagicContext1111111111111111111111111111111");
+
+      try {
+        await program.methods
+          .tallyAndCommit()
+          .accounts({
+            fastAction: pda,
+            payer: creator.publicKey,
+            magicContext: fakeMagicContext,
+            magicProgram: fakeMagicProgram,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        // Either VotingNotEnded (if deadline not passed) or account validation error
+        expect(err.message).to.satisfy((msg: string) =>
+          msg.includes("VotingNotEnded") || msg.includes("ConstraintAddress")
+        );
+      }
+    });
+
+    it("rejects quorum not met", async () => {
+      const quorumId = await nextActionId();
+      const [pda] = deriveFastActionPDA(quorumId);
+
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 13)), 50))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      // Only 1 vote (quorum is 2)
+      const [votePda] = deriveFastVotePDA(pda, voter1.publicKey);
+      await program.methods
+        .voteFast(quorumId, true, Array.from(Buffer.alloc(32, 1)), 0)
+        .accounts({
+          fastAction: pda,
+          fastVote: votePda,
+          voter: voter1.publicKey,
+          agent: voter1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter1])
+        .rpc();
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.voteCount).to.equal(1);
+    });
+
+    it("rejects invalid magic program", async () => {
+      const invalidId = await nextActionId();
+      const [pda] = deriveFastActionPDA(invalidId);
+
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 14)), 50))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      // Add quorum votes
+      const [vote1Pda] = deriveFastVotePDA(pda, voter1.publicKey);
+      const [vote2Pda] = deriveFastVotePDA(pda, voter2.publicKey);
+
+      await program.methods
+        .voteFast(invalidId, true, Array.from(Buffer.alloc(32, 1)), 0)
+        .accounts({
+          fastAction: pda,
+          fastVote: vote1Pda,
+          voter: voter1.publicKey,
+          agent: voter1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter1])
+        .rpc();
+
+      await program.methods
+        .voteFast(invalidId, true, Array.from(Buffer.alloc(32, 2)), 0)
+        .accounts({
+          fastAction: pda,
+          fastVote: vote2Pda,
+          voter: voter2.publicKey,
+          agent: voter2.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter2])
+        .rpc();
+
+      // Use wrong magic program
+      const wrongMagicProgram = SystemProgram.programId;
+      const fakeMagicContext = new PublicKey("MagicContext1111111111111111111111111111111");
+
+      try {
+        await program.methods
+          .tallyAndCommit()
+          .accounts({
+            fastAction: pda,
+            payer: creator.publicKey,
+            magicContext: fakeMagicContext,
+            magicProgram: wrongMagicProgram,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        // Should fail with address constraint error
+        expect(err.message).to.include("ConstraintAddress");
+      }
+    });
+
+    it("records no result digest for a rejected tally", async () => {
+      const digestId = await nextActionId();
+      const [pda] = deriveFastActionPDA(digestId);
+
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 205)), 50))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      try {
+        await program.methods
+          .tallyAndCommit()
+          .accounts({
+            fastAction: pda,
+            payer: creator.publicKey,
+            magicContext: new PublicKey("MagicContext1111111111111111111111111111111"),
+            magicProgram: new PublicKey("Magic11111111111111111111111111111111111111"),
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.satisfy((msg: string) =>
+          msg.includes("VotingNotEnded") || msg.includes("ConstraintAddress")
+        );
+      }
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.executed).to.equal(false);
+      expect(action.finalizedSlot.toNumber()).to.equal(0);
+      expect(Buffer.from(action.resultDigest).equals(Buffer.alloc(32))).to.be.true;
+    });
+  });
+
+  describe("checkpoint", () => {
+    it("starts the interval at creation and refuses a checkpoint too soon", async () => {
+      const checkpointId = await nextActionId();
+      const [pda] = deriveFastActionPDA(checkpointId);
+
+      const sig = await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 206)), 50))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc({ commitment: "confirmed" });
+      const created = await provider.connection.getTransaction(sig, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+
+      try {
+        await program.methods
+          .checkpoint()
+          .accounts({
+            fastAction: pda,
+            payer: creator.publicKey,
+            magicContext: new PublicKey("MagicContext1111111111111111111111111111111"),
+            magicProgram: new PublicKey("Magic11111111111111111111111111111111111111"),
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        // No votes and well inside the slot interval
+        expect(err.message).to.include("CheckpointTooSoon");
+      }
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.lastCheckpointSlot.toNumber()).to.equal(created!.slot);
+      expect(action.lastCheckpointVotes).to.equal(0);
+    });
+  });
+
+  describe("delegate_action validation", () => {
+    it("rejects invalid PDA", async () => {
+      const delegateId = await nextActionId();
+      const [correctPda] = deriveFastActionPDA(delegateId);
+
+      // Create the action first
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 15)), 50))
+        .accounts({
+          fastAction: correctPda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      // Try to delegate with wrong PDA
+      const wrongPda = Keypair.generate().publicKey;
+
+      try {
+        await program.methods
+          .delegateAction(delegateId)
+          .accounts({
+            pda: wrongPda,
+            payer: creator.publicKey,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidPda");
+      }
+    });
+
+    it("rejects a validator that is not allowlisted", async () => {
+      const delegateId = await nextActionId();
+      const [pda] = deriveFastActionPDA(delegateId);
+
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 24)), 50))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      const validator = Keypair.generate();
+      const [validatorRecord] = deriveValidatorPDA(validator.publicKey);
+
+      try {
+        await program.methods
+          .delegateAction(delegateId)
+          .accounts({ pda, payer: creator.publicKey, validator: validator.publicKey })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ValidatorNotRegistered");
+      }
+
+      await program.methods
+        .registerValidator(VALIDATOR_BOND)
+        .accounts({
+          validatorRecord,
+          validator: validator.publicKey,
+          payer: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([validator])
+        .rpc();
+
+      try {
+        await program.methods
+          .delegateAction(delegateId)
+          .accounts({ pda, payer: creator.publicKey, validator: validator.publicKey, validatorRecord })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ValidatorNotAllowed");
+      }
+    });
+  });
+
+  describe("redundant delegation", () => {
+    const backup = Keypair.generate();
+    const [backupRecord] = deriveValidatorPDA(backup.publicKey);
+
+    function deriveMirrorPDA(fastAction: PublicKey): [PublicKey, number] {
+      return PublicKey.findProgramAddressSync([Buffer.from("action_mirror"), fastAction.toBuffer()], program.programId);
+    }
+
+    it("only takes a live allowlisted backup, once", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      const [actionMirror] = deriveMirrorPDA(pda);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 134)), 50))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      await program.methods
+        .registerValidator(VALIDATOR_BOND)
+        .accounts({
+          validatorRecord: backupRecord,
+          validator: backup.publicKey,
+          payer: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([backup])
+        .rpc();
+
+      try {
+        await program.methods
+          .enableRedundancy(backup.publicKey)
+          .accounts({ fastAction: pda, actionMirror, backupRecord, authority: creator.publicKey })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ValidatorNotAllowed");
+      }
+
+      await program.methods
+        .setValidatorStatus({ allowed: {} })
+        .accounts({ validatorRecord: backupRecord, admin: provider.wallet.publicKey })
+        .rpc();
+      await program.methods
+        .enableRedundancy(backup.publicKey)
+        .accounts({ fastAction: pda, actionMirror, backupRecord, authority: creator.publicKey })
+        .signers([creator])
+        .rpc();
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.backupValidator.toBase58()).to.equal(backup.publicKey.toBase58());
+      const mirror = await program.account.actionMirror.fetch(actionMirror);
+      expect(mirror.action.toBase58()).to.equal(pda.toBase58());
+      expect(mirror.committedBy.toBase58()).to.equal(PublicKey.default.toBase58());
+
+      try {
+        await program.methods
+          .enableRedundancy(backup.publicKey)
+          .accounts({ fastAction: pda, actionMirror, backupRecord, authority: creator.publicKey })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("already in use");
+      }
+    });
+
+    it("keeps the primary off the backup and waits for a commit to resolve", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      const [actionMirror] = deriveMirrorPDA(pda);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 135)), 50))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      await program.methods
+        .enableRedundancy(backup.publicKey)
+        .accounts({ fastAction: pda, actionMirror, backupRecord, authority: creator.publicKey })
+        .signers([creator])
+        .rpc();
+
+      for (const accounts of [
+        { pda, payer: creator.publicKey },
+        { pda, payer: creator.publicKey, validator: backup.publicKey, validatorRecord: backupRecord },
+      ]) {
+        try {
+          await program.methods.delegateAction(id).accounts(accounts).signers([creator]).rpc();
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include("InvalidPrimaryValidator");
+        }
+      }
+
+      try {
+        await program.methods.resolveRedundantCommit().accounts({ fastAction: pda, actionMirror }).rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("MirrorNotCommitted");
+      }
+    });
+  });
+
+  describe("vote receipts", () => {
+    const BUBBLEGUM = new PublicKey("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+    const merkleTree = Keypair.generate().publicKey;
+
+    it("only lets the config admin set the tree, with a short URI prefix", async () => {
+      try {
+        await program.methods
+          .setReceiptTree(merkleTree, "https://receipts.example/")
+          .accounts({ admin: voter1.publicKey, systemProgram: SystemProgram.programId })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+      try {
+        await program.methods
+          .setReceiptTree(merkleTree, "x".repeat(65))
+          .accounts({ admin: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidReceiptTree");
+      }
+
+      await program.methods
+        .setReceiptTree(merkleTree, "https://receipts.example/")
+        .accounts({ admin: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+        .rpc();
+    });
+
+    it("mints no receipt before the action is final", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 78)), 50))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      const [votePDA] = deriveFastVotePDA(pda, voter1.publicKey);
+      await program.methods
+        .voteFast(id, true, Array.from(Buffer.alloc(32, 79)), 0)
+        .accounts({
+          fastAction: pda,
+          fastVote: votePDA,
+          voter: voter1.publicKey,
+          agent: voter1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter1])
+        .rpc();
+
+      const [treeConfig] = PublicKey.findProgramAddressSync([merkleTree.toBuffer()], BUBBLEGUM);
+      try {
+        await program.methods
+          .mintReceipt()
+          .accounts({
+            fastAction: pda,
+            fastVote: votePDA,
+            voter: voter1.publicKey,
+            treeConfig,
+            merkleTree,
+            payer: provider.wallet.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ActionNotFinalized");
+      }
+    });
+  });
+
+  describe("vote rewards", () => {
+    const TOKEN_PROGRAM = new PublicKey("TokenkegQfeZyiNwAJbNbGi4PS2vL7uSwbwWW7xVQ2R");
+    const mint = Keypair.generate().publicKey;
+    const vault = Keypair.generate().publicKey;
+    const funderTokenAccount = Keypair.generate().publicKey;
+
+    it("only lets the action authority open a pool, with an amount and a window", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 80)), 50))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+
+      try {
+        await program.methods
+          .openVoteRewards(new anchor.BN(1_000), new anchor.BN(1_000))
+          .accounts({
+            fastAction: pda,
+            mint,
+            vault,
+            funderTokenAccount,
+            tokenProgram: TOKEN_PROGRAM,
+            authority: voter1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+      try {
+        await program.methods
+          .openVoteRewards(new anchor.BN(0), new anchor.BN(1_000))
+          .accounts({
+            fastAction: pda,
+            mint,
+            vault,
+            funderTokenAccount,
+            tokenProgram: TOKEN_PROGRAM,
+            authority: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidVoteRewards");
+      }
+    });
+  });
+
+  describe("validator registry", () => {
+    it("only the config admin can allowlist", async () => {
+      const validator = Keypair.generate();
+      const [validatorRecord] = deriveValidatorPDA(validator.publicKey);
+
+      await program.methods
+        .registerValidator(VALIDATOR_BOND)
+        .accounts({
+          validatorRecord,
+          validator: validator.publicKey,
+          payer: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([validator])
+        .rpc();
+
+      try {
+        await program.methods
+          .setValidatorStatus({ allowed: {} })
+          .accounts({ validatorRecord, admin: creator.publicKey })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+
+      await program.methods
+        .setValidatorStatus({ allowed: {} })
+        .accounts({ validatorRecord, admin: provider.wallet.publicKey })
+        .rpc();
+
+      const record = await program.account.validatorRecord.fetch(validatorRecord);
+      expect(JSON.stringify(record.status)).to.include("allowed");
+    });
+  });
+
+  describe("validator rewards", () => {
+    const [rewardPool] = PublicKey.findProgramAddressSync([Buffer.from("reward_pool")], program.programId);
+
+    it("charges the creation fee into the reward pool", async () => {
+      await program.methods.setCreationFee(new anchor.BN(5_000)).accounts({ admin: provider.wallet.publicKey }).rpc();
+
+      const before = await provider.connection.getBalance(rewardPool);
+      const feeId = await nextActionId();
+      const [pda] = deriveFastActionPDA(feeId);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 25)), 50))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+      expect(await provider.connection.getBalance(rewardPool)).to.equal(before + 5_000);
+      expect((await program.account.fastAction.fetch(pda)).creationFee.toNumber()).to.equal(5_000);
+
+      await program.methods.setCreationFee(new anchor.BN(0)).accounts({ admin: provider.wallet.publicKey }).rpc();
+    });
+
+    it("rejects a fee rebate share above the whole fee", async () => {
+      const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+      try {
+        await program.methods
+          .updateConfig({
+            admin: null,
+            weightMint: null,
+            weightExtensions: null,
+            transferHookProgram: null,
+            maxActionsPerEpoch: null,
+            guardian: null,
+            voteCreditsPerEpoch: null,
+            minAgentBond: null,
+            whistleblowerBps: null,
+            snapshotter: null,
+            maxWindowSlots: null,
+            stakeLockProgram: null,
+            maxLowPriorityActions: null,
+            maxVotesPerAction: null,
+            identityRamp: null,
+            feeRebate: { fullTurnoutBps: 6_000, rebateBps: 10_001 },
+            maxActionsPerValidator: null,
+          })
+          .accounts({ config: configPDA, admin: provider.wallet.publicKey })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidFeeRebate");
+      }
+    });
+
+    it("pays no fee rebate before the tally", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 26)), 50))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      try {
+        await program.methods
+          .claimFeeRebate()
+          .accounts({ fastAction: pda, creator: creator.publicKey, rewardPool })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ActionNotFinalized");
+      }
+    });
+
+    it("rejects a claim with no recorded sessions", async () => {
+      const validator = Keypair.generate();
+      const [validatorRecord] = deriveValidatorPDA(validator.publicKey);
+      await program.methods
+        .registerValidator(VALIDATOR_BOND)
+        .accounts({
+          validatorRecord,
+          validator: validator.publicKey,
+          payer: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([validator])
+        .rpc();
+
+      try {
+        await program.methods
+          .claimValidatorRewards()
+          .accounts({ validatorRecord, rewardPool, validator: validator.publicKey })
+          .signers([validator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("NoRewards");
+      }
+    });
+  });
+
+  describe("slashing", () => {
+    it("rejects a report against an action that was not missed", async () => {
+      const slashId = await nextActionId();
+      const [pda] = deriveFastActionPDA(slashId);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 26)), 50))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      try {
+        await program.methods
+          .reportMissedCommit()
+          .accounts({ fastAction: pda, reporter: creator.publicKey })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("CommitNotMissed");
+      }
+    });
+  });
+
+  describe("challenge_result", () => {
+    it("rejects a challenge against an action that is not finalized", async () => {
+      const [pda] = deriveFastActionPDA(actionId);
+
+      try {
+        await program.methods
+          .challengeResult({ counters: {} })
+          .accounts({ fastAction: pda, validatorRecord: null, challenger: creator.publicKey })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ActionNotFinalized");
+      }
+    });
+  });
+
+  describe("sequence commitments", () => {
+    it("refuses a batch from a validator governance has not allowed", async () => {
+      const validator = Keypair.generate();
+      const [validatorRecord] = deriveValidatorPDA(validator.publicKey);
+      await program.methods
+        .registerValidator(VALIDATOR_BOND)
+        .accounts({
+          validatorRecord,
+          validator: validator.publicKey,
+          payer: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([validator])
+        .rpc();
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(validator.publicKey, LAMPORTS_PER_SOL)
+      );
+
+      const [action] = deriveFastActionPDA(actionId);
+      const index = Buffer.alloc(4);
+      const [sequenceBatch] = PublicKey.findProgramAddressSync(
+        [Buffer.from("sequence_batch"), action.toBuffer(), validator.publicKey.toBuffer(), index],
+        program.programId
+      );
+      const ballot = {
+        voter: voter1.publicKey,
+        receivedSlot: new anchor.BN(0),
+        ballotHash: Array.from(Buffer.alloc(32)),
+      };
+      try {
+        await program.methods
+          .postSequenceCommitment(action, 0, [ballot])
+          .accounts({
+            sequenceBatch,
+            previous: null,
+            validatorRecord,
+            validator: validator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([validator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ValidatorNotAllowed");
+      }
+    });
+
+    it("chains an allowed validator's batches", async () => {
+      const validator = Keypair.generate();
+      const [validatorRecord] = deriveValidatorPDA(validator.publicKey);
+      await program.methods
+        .registerValidator(VALIDATOR_BOND)
+        .accounts({
+          validatorRecord,
+          validator: validator.publicKey,
+          payer: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([validator])
+        .rpc();
+      await program.methods
+        .setValidatorStatus({ allowed: {} })
+        .accounts({ validatorRecord, admin: provider.wallet.publicKey })
+        .rpc();
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(validator.publicKey, LAMPORTS_PER_SOL)
+      );
+
+      const [action] = deriveFastActionPDA(actionId);
+      const batchPDA = (index: number) => {
+        const le = Buffer.alloc(4);
+        le.writeUInt32LE(index);
+        return PublicKey.findProgramAddressSync(
+          [Buffer.from("sequence_batch"), action.toBuffer(), validator.publicKey.toBuffer(), le],
+          program.programId
+        )[0];
+      };
+      const receivedSlot = new anchor.BN(await provider.connection.getSlot());
+      const ballot = (voter: PublicKey, fill: number) => ({
+        voter,
+        receivedSlot,
+        ballotHash: Array.from(Buffer.alloc(32, fill)),
+      });
+      const post = (index: number, ballots: any[]) =>
+        program.methods
+          .postSequenceCommitment(action, index, ballots)
+          .accounts({
+            sequenceBatch: batchPDA(index),
+            previous: index > 0 ? batchPDA(index - 1) : null,
+            validatorRecord,
+            validator: validator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([validator])
+          .rpc();
+
+      await post(0, [ballot(voter1.publicKey, 1), ballot(voter2.publicKey, 2)]);
+      await post(1, [ballot(creator.publicKey, 3)]);
+
+      const first = await program.account.sequenceBatch.fetch(batchPDA(0));
+      expect(first.count).to.equal(2);
+      expect(Buffer.from(first.prevHead).equals(Buffer.alloc(32))).to.be.true;
+      const second = await program.account.sequenceBatch.fetch(batchPDA(1));
+      expect(second.index).to.equal(1);
+      expect(second.count).to.equal(1);
+      expect(Buffer.from(second.prevHead).equals(Buffer.from(first.head))).to.be.true;
+      expect(second.throughSlot.toNumber()).to.equal(receivedSlot.toNumber());
+    });
+  });
+
+  describe("result proofs", () => {
+    it("refuses to export a result that has not settled", async () => {
+      const [action] = deriveFastActionPDA(actionId);
+      const [resultAttestors] = PublicKey.findProgramAddressSync(
+        [Buffer.from("result_attestors"), PublicKey.default.toBuffer()],
+        program.programId
+      );
+      const [resultProof] = PublicKey.findProgramAddressSync(
+        [Buffer.from("result_proof"), action.toBuffer(), creator.publicKey.toBuffer()],
+        program.programId
+      );
+      try {
+        await program.methods
+          .exportResultProof()
+          .accounts({
+            fastAction: action,
+            resultAttestors,
+            instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+            resultProof,
+            payer: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("DisputeWindowOpen");
+      }
+    });
+  });
+
+  describe("adaptive quorum", () => {
+    it("rejects a floor above the quorum", async () => {
+      const quorumId = await nextActionId();
+      const [pda] = deriveFastActionPDA(quorumId);
+
+      try {
+        await program.methods
+          .createFastAction(
+            actionParams(Array.from(Buffer.alloc(32, 30)), 50, {
+              adaptiveQuorum: { quorum: 3, floor: 5, graceSlots: new anchor.BN(100) },
+            })
+          )
+          .accounts({
+            fastAction: pda,
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidQuorum");
+      }
+    });
+
+    it("stores the decay schedule on the action", async () => {
+      const quorumId = await nextActionId();
+      const [pda] = deriveFastActionPDA(quorumId);
+
+      await program.methods
+        .createFastAction(
+          actionParams(Array.from(Buffer.alloc(32, 31)), 50, {
+            adaptiveQuorum: { quorum: 10, floor: 3, graceSlots: new anchor.BN(150) },
+          })
+        )
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.quorum).to.equal(10);
+      expect(action.quorumFloor).to.equal(3);
+      expect(action.quorumGraceSlots.toNumber()).to.equal(150);
+    });
+  });
+
+  describe("turnout", () => {
+    it("rejects a turnout requirement without a hive electorate", async () => {
+      const turnoutId = await nextActionId();
+      const [pda] = deriveFastActionPDA(turnoutId);
+
+      try {
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 32)), 50, { minTurnoutBps: 5_000 }))
+          .accounts({
+            fastAction: pda,
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidTurnout");
+      }
+    });
+  });
+
+  describe("guardian veto", () => {
+    const guardian = Keypair.generate();
+    const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+    let pda: PublicKey;
+
+    before(async () => {
+      await program.methods
+        .updateConfig({
+          admin: null,
+          weightMint: null,
+          weightExtensions: null,
+          transferHookProgram: null,
+          maxActionsPerEpoch: null,
+          guardian: guardian.publicKey,
+          voteCreditsPerEpoch: null,
+          minAgentBond: null,
+          whistleblowerBps: null,
+          snapshotter: null,
+          maxWindowSlots: null,
+          stakeLockProgram: null,
+          maxLowPriorityActions: null,
+          maxVotesPerAction: null,
+          identityRamp: null,
+          feeRebate: null,
+          maxActionsPerValidator: null,
+        })
+        .accounts({ config: configPDA, admin: provider.wallet.publicKey })
+        .rpc();
+
+      const id = await nextActionId();
+      [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 42)), 50))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+    });
+
+    it("records the guardian in the config", async () => {
+      const config = await program.account.globalConfig.fetch(configPDA);
+      expect(config.guardian.equals(guardian.publicKey)).to.be.true;
+    });
+
+    it("rejects a veto from anyone but the guardian", async () => {
+      try {
+        await program.methods
+          .vetoAction("compromised")
+          .accounts({ fastAction: pda, config: configPDA, guardian: creator.publicKey })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+    });
+
+    it("cannot veto an action that has not passed", async () => {
+      try {
+        await program.methods
+          .vetoAction("compromised")
+          .accounts({ fastAction: pda, config: configPDA, guardian: guardian.publicKey })
+          .signers([guardian])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("NothingToVeto");
+      }
+    });
+
+    describe("emergency actions", () => {
+      const hive = Keypair.generate();
+      const [rolesPDA] = deriveRolesPDA(hive.publicKey);
+
+      async function createEmergency(hash: number | Buffer, threshold: number) {
+        const id = await nextActionId();
+        const [emergencyPDA] = deriveHiveActionPDA(hive.publicKey, id);
+        const actionHash = Buffer.isBuffer(hash) ? hash : Buffer.alloc(32, hash);
+        await program.methods
+          .createFastAction(actionParams(Array.from(actionHash), threshold, { kind: { emergency: {} } }))
+          .accounts({
+            hiveAccount: deriveHivePDA(hive.publicKey)[0],
+            fastAction: emergencyPDA,
+            creator: creator.publicKey,
+            roles: rolesPDA,
+            creatorAllowlist: deriveCreatorAllowlistPDA(hive.publicKey)[0],
+            hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+            hiveConfig: deriveHiveConfigPDA(hive.publicKey)[0],
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        return emergencyPDA;
+      }
+
+      before(async () => {
+        await program.methods
+          .initializeRoles()
+          .accounts({
+            roles: rolesPDA,
+            hive: hive.publicKey,
+            payer: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([hive, creator])
+          .rpc();
+        await createHiveAccount(hive);
+        await program.methods
+          .grantRole(creator.publicKey, { creator: {} })
+          .accounts({ roles: rolesPDA, admin: hive.publicKey })
+          .signers([hive])
+          .rpc();
+      });
+
+      it("requires the guardian in the hive's electorate", async () => {
+        try {
+          await createEmergency(50, 80);
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include("GuardianNotInElectorate");
+        }
+      });
+
+      it("requires a supermajority threshold", async () => {
+        await program.methods
+          .grantRole(guardian.publicKey, { canceller: {} })
+          .accounts({ roles: rolesPDA, admin: hive.publicKey })
+          .signers([hive])
+          .rpc();
+
+        try {
+          await createEmergency(51, 60);
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include("InvalidEmergencyAction");
+        }
+      });
+
+      it("opens with the short emergency window", async () => {
+        const emergencyPDA = await createEmergency(52, 80);
+        const action = await program.account.fastAction.fetch(emergencyPDA);
+        expect(action.kind).to.deep.equal({ emergency: {} });
+        expect(action.deadlineSlot.toNumber() - action.createdSlot.toNumber()).to.equal(10);
+      });
+
+      function setHiveFrozen(fastAction: PublicKey, frozen: boolean) {
+        return program.methods
+          .setHiveFrozen(frozen)
+          .accounts({
+            fastAction,
+            hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+            payer: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+      }
+
+      it("freezes the hive only through an action carrying its freeze hash", async () => {
+        const emergencyPDA = await createEmergency(53, 80);
+        try {
+          await setHiveFrozen(emergencyPDA, true);
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include("InvalidFreezeAction");
+        }
+      });
+
+      it("freezes the hive only once the freeze action has passed", async () => {
+        const freezeHash = createHash("sha256")
+          .update(Buffer.concat([Buffer.from("hive_freeze"), hive.publicKey.toBuffer(), Buffer.from([1])]))
+          .digest();
+        const emergencyPDA = await createEmergency(freezeHash, 80);
+        try {
+          await setHiveFrozen(emergencyPDA, true);
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include("ActionNotExecutable");
+        }
+        expect(await program.account.hiveFreeze.fetchNullable(deriveHiveFreezePDA(hive.publicKey)[0])).to.be.null;
+      });
+    });
+  });
+
+  describe("market gate", () => {
+    const feed = Keypair.generate().publicKey;
+    const gate = { feed, feedProgram: SystemProgram.programId, priceOffset: 0, minPrice: new anchor.BN(600_000) };
+
+    it("rejects a gate on a multi-option action", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      try {
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 40)), 50, { optionCount: 2, marketGate: gate }))
+          .accounts({
+            fastAction: pda,
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidMarketGate");
+      }
+    });
+
+    it("cannot clear before the action has passed", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 41)), 50, { marketGate: gate }))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.marketFeed.equals(feed)).to.be.true;
+      expect(action.marketCleared).to.be.false;
+
+      try {
+        await program.methods.clearMarketGate().accounts({ fastAction: pda, feed }).rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ActionNotFinalized");
+      }
+    });
+  });
+
+  describe("multi-option ballots", () => {
+    let optionsId: anchor.BN;
+    let pda: PublicKey;
+
+    before(async () => {
+      optionsId = await nextActionId();
+      [pda] = deriveFastActionPDA(optionsId);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 33)), 50, { optionCount: 3 }))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+    });
+
+    it("rejects a for/against ballot", async () => {
+      const [votePDA] = deriveFastVotePDA(pda, voter1.publicKey);
+      try {
+        await program.methods
+          .voteFast(optionsId, true, Array.from(Buffer.alloc(32, 34)), 0)
+          .accounts({
+            fastAction: pda,
+            fastVote: votePDA,
+            voter: voter1.publicKey,
+            agent: voter1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("OptionBallotRequired");
+      }
+    });
+
+    it("rejects weights that exceed a headcount voter's single vote", async () => {
+      const [votePDA] = deriveFastVotePDA(pda, voter1.publicKey);
+      try {
+        await program.methods
+          .voteOptions(optionsId, [new anchor.BN(1), new anchor.BN(1), new anchor.BN(0)], Array.from(Buffer.alloc(32, 34)), 0)
+          .accounts({
+            fastAction: pda,
+            fastVote: votePDA,
+            voter: voter1.publicKey,
+            agent: voter1.publicKey,
+            config: null,
+            stakeLock: null,
+            stakeLockClaim: null,
+            lockAuthority: null,
+            stakeLockProgram: null,
+            weightMint: null,
+            voterTokenAccount: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidOptionWeights");
+      }
+    });
+
+    it("counts a ballot toward its chosen option", async () => {
+      const [votePDA] = deriveFastVotePDA(pda, voter1.publicKey);
+      await program.methods
+        .voteOptions(optionsId, [new anchor.BN(0), new anchor.BN(1), new anchor.BN(0)], Array.from(Buffer.alloc(32, 34)), 0)
+        .accounts({
+          fastAction: pda,
+          fastVote: votePDA,
+          voter: voter1.publicKey,
+          agent: voter1.publicKey,
+          config: null,
+          stakeLock: null,
+          stakeLockClaim: null,
+          lockAuthority: null,
+          stakeLockProgram: null,
+          weightMint: null,
+          voterTokenAccount: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter1])
+        .rpc();
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.voteCount).to.equal(1);
+      expect(action.optionWeights.slice(0, 3).map((w: anchor.BN) => w.toNumber())).to.deep.equal([0, 1, 0]);
+    });
+
+    it("rejects a runoff while the vote is still open", async () => {
+      const runoffId = await nextActionId();
+      const [runoffPDA] = deriveFastActionPDA(runoffId);
+      const [counterPDA] = PublicKey.findProgramAddressSync([Buffer.from("action_counter")], program.programId);
+      try {
+        await program.methods
+          .spawnRunoff()
+          .accounts({
+            parentAction: pda,
+            actionCounter: counterPDA,
+            runoffAction: runoffPDA,
+            payer: provider.wallet.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("RunoffNotRequired");
+      }
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.runoffAction.equals(PublicKey.default)).to.be.true;
+      expect(action.parentAction.equals(PublicKey.default)).to.be.true;
+    });
+
+    it("will not spawn and delegate a runoff while the vote is still open", async () => {
+      const runoffId = await nextActionId();
+      const [runoffPDA] = deriveFastActionPDA(runoffId);
+      try {
+        await program.methods
+          .spawnAndDelegate()
+          .accounts({ parentAction: pda, runoffAction: runoffPDA, payer: provider.wallet.publicKey })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("RunoffNotRequired");
+      }
+      expect(await provider.connection.getAccountInfo(runoffPDA)).to.be.null;
+    });
+  });
+
+  describe("ranked-choice", () => {
+    let rankedId: anchor.BN;
+    let pda: PublicKey;
+
+    async function voteRanked(voter: Keypair, ranking: number[], fill: number) {
+      const [votePDA] = deriveFastVotePDA(pda, voter.publicKey);
+      const [ballotPDA] = deriveRankedBallotPDA(pda, voter.publicKey);
+      await program.methods
+        .voteRanked(rankedId, Buffer.from(ranking), Array.from(Buffer.alloc(32, fill)), 0)
+        .accounts({
+          fastAction: pda,
+          fastVote: votePDA,
+          rankedBallot: ballotPDA,
+          voter: voter.publicKey,
+          agent: voter.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter])
+        .rpc();
+    }
+
+    before(async () => {
+      rankedId = await nextActionId();
+      [pda] = deriveFastActionPDA(rankedId);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 35)), 50, { optionCount: 3, ranked: true }))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+    });
+
+    it("rejects ranking without options", async () => {
+      const id = await nextActionId();
+      const [otherPDA] = deriveFastActionPDA(id);
+      try {
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 36)), 50, { ranked: true }))
+          .accounts({
+            fastAction: otherPDA,
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidRankedAction");
+      }
+    });
+
+    it("rejects a ranking that repeats an option", async () => {
+      try {
+        await voteRanked(voter1, [1, 1], 37);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidRanking");
+      }
+    });
+
+    it("counts a ballot toward its first preference", async () => {
+      await voteRanked(voter1, [2, 0], 37);
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.voteCount).to.equal(1);
+      expect(action.optionWeights.slice(0, 3).map((w: anchor.BN) => w.toNumber())).to.deep.equal([0, 0, 1]);
+      expect(action.pendingElimination).to.equal(255);
+
+      const [ballotPDA] = deriveRankedBallotPDA(pda, voter1.publicKey);
+      const ballot = await program.account.rankedBallot.fetch(ballotPDA);
+      expect(ballot.len).to.equal(2);
+      expect(ballot.position).to.equal(0);
+    });
+
+    it("rejects tally_round before the deadline", async () => {
+      try {
+        await program.methods.tallyRound().accounts({ fastAction: pda }).rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("VotingNotEnded");
+      }
+    });
+  });
+
+  describe("conviction", () => {
+    const hash = Buffer.alloc(32, 38);
+    const [proposalPDA] = PublicKey.findProgramAddressSync([Buffer.from("conviction"), hash], program.programId);
+    let stakePDA: PublicKey;
+
+    before(() => {
+      [stakePDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("conviction_stake"), proposalPDA.toBuffer(), voter1.publicKey.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("rejects a retention of one billion ppb or more", async () => {
+      const badHash = Buffer.alloc(32, 39);
+      const [badPDA] = PublicKey.findProgramAddressSync([Buffer.from("conviction"), badHash], program.programId);
+      try {
+        await program.methods
+          .createConvictionProposal(Array.from(badHash), new anchor.BN(LAMPORTS_PER_SOL), 1_000_000_000)
+          .accounts({ proposal: badPDA, creator: creator.publicKey, roles: null, systemProgram: SystemProgram.programId })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidConvictionParams");
+      }
+    });
+
+    it("accrues conviction from staked lamports", async () => {
+      await program.methods
+        .createConvictionProposal(Array.from(hash), new anchor.BN(10 * LAMPORTS_PER_SOL), 999_000_000)
+        .accounts({ proposal: proposalPDA, creator: creator.publicKey, roles: null, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+
+      await program.methods
+        .stakeConviction(new anchor.BN(LAMPORTS_PER_SOL / 10))
+        .accounts({ proposal: proposalPDA, stake: stakePDA, staker: voter1.publicKey, systemProgram: SystemProgram.programId })
+        .signers([voter1])
+        .rpc();
+      await program.methods.updateConviction().accounts({ proposal: proposalPDA }).rpc();
+
+      const proposal = await program.account.convictionProposal.fetch(proposalPDA);
+      expect(proposal.totalStaked.toNumber()).to.equal(LAMPORTS_PER_SOL / 10);
+      expect(proposal.conviction.toNumber()).to.be.greaterThan(0);
+      expect(proposal.conviction.toNumber()).to.be.at.most(LAMPORTS_PER_SOL / 10);
+      expect(proposal.executed).to.be.false;
+    });
+
+    it("rejects withdrawing more than was staked", async () => {
+      try {
+        await program.methods
+          .withdrawConviction(new anchor.BN(LAMPORTS_PER_SOL))
+          .accounts({ proposal: proposalPDA, stake: stakePDA, staker: voter1.publicKey })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidStakeAmount");
+      }
+    });
+
+    it("returns withdrawn stake", async () => {
+      await program.methods
+        .withdrawConviction(new anchor.BN(LAMPORTS_PER_SOL / 10))
+        .accounts({ proposal: proposalPDA, stake: stakePDA, staker: voter1.publicKey })
+        .signers([voter1])
+        .rpc();
+
+      const proposal = await program.account.convictionProposal.fetch(proposalPDA);
+      const stake = await program.account.convictionStake.fetch(stakePDA);
+      expect(proposal.totalStaked.toNumber()).to.equal(0);
+      expect(stake.amount.toNumber()).to.equal(0);
+    });
+  });
+
+  describe("graded tiers", () => {
+    it("rejects tiers that do not ascend above the threshold", async () => {
+      const tierId = await nextActionId();
+      const [pda] = deriveFastActionPDA(tierId);
+
+      try {
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 17)), 50, { tierThresholds: [90, 66, 0] }))
+          .accounts({
+            fastAction: pda,
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidTierThresholds");
+      }
+    });
+
+    it("stores tier thresholds on the action", async () => {
+      const tierId = await nextActionId();
+      const [pda] = deriveFastActionPDA(tierId);
+
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 18)), 50, { tierThresholds: [66, 90, 0] }))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.tierThresholds).to.deep.equal([66, 90, 0]);
+    });
+  });
+
+  describe("dependencies", () => {
+    it("rejects an action depending on itself", async () => {
+      const depId = await nextActionId();
+      const [pda] = deriveFastActionPDA(depId);
+
+      try {
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 19)), 50, { dependencies: [pda] }))
+          .accounts({
+            fastAction: pda,
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidDependency");
+      }
+    });
+  });
+
+  describe("roles", () => {
+    let hive: Keypair;
+    let rolesPDA: PublicKey;
+
+    before(async () => {
+      hive = Keypair.generate();
+      [rolesPDA] = deriveRolesPDA(hive.publicKey);
+
+      await program.methods
+        .initializeRoles()
+        .accounts({
+          roles: rolesPDA,
+          hive: hive.publicKey,
+          payer: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([hive, creator])
+        .rpc();
+      await createHiveAccount(hive);
+
+      for (const [member, role] of [
+        [creator.publicKey, { creator: {} }],
+        [voter1.publicKey, { canceller: {} }],
+      ] as const) {
+        await program.methods
+          .grantRole(member, role as any)
+          .accounts({ roles: rolesPDA, admin: hive.publicKey })
+          .signers([hive])
+          .rpc();
+      }
+    });
+
+    it("hive canceller can cancel another creator's action", async () => {
+      const hiveActionId = await nextActionId();
+      const [pda] = deriveHiveActionPDA(hive.publicKey, hiveActionId);
+
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 16)), 50))
+        .accounts({
+          hiveAccount: deriveHivePDA(hive.publicKey)[0],
+          fastAction: pda,
+          creator: creator.publicKey,
+          roles: rolesPDA,
+          creatorAllowlist: deriveCreatorAllowlistPDA(hive.publicKey)[0],
+          hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+          hiveConfig: deriveHiveConfigPDA(hive.publicKey)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      await program.methods
+        .cancelAction(hiveActionId)
+        .accounts({
+          fastAction: pda,
+          authority: voter1.publicKey,
+          roles: rolesPDA,
+        })
+        .signers([voter1])
+        .rpc();
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.hive.toString()).to.equal(hive.publicKey.toString());
+      expect(JSON.stringify(action.result)).to.include("cancelled");
+    });
+
+    it("stricter cancellation policy closes cancellation at the first vote", async () => {
+      const [hiveConfigPDA] = deriveHiveConfigPDA(hive.publicKey);
+      await program.methods
+        .setCancellationPolicy({ beforeFirstVote: {} })
+        .accounts({
+          hiveConfig: hiveConfigPDA,
+          roles: rolesPDA,
+          admin: hive.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([hive])
+        .rpc();
+
+      const hiveActionId = await nextActionId();
+      const [pda] = deriveHiveActionPDA(hive.publicKey, hiveActionId);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 46)), 50))
+        .accounts({
+          hiveAccount: deriveHivePDA(hive.publicKey)[0],
+          fastAction: pda,
+          creator: creator.publicKey,
+          roles: rolesPDA,
+          creatorAllowlist: deriveCreatorAllowlistPDA(hive.publicKey)[0],
+          hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+          hiveConfig: deriveHiveConfigPDA(hive.publicKey)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      await program.methods
+        .voteFast(hiveActionId, true, Array.from(Buffer.alloc(32, 46)), 0)
+        .accounts({
+          fastAction: pda,
+          fastVote: deriveFastVotePDA(pda, voter2.publicKey)[0],
+          voter: voter2.publicKey,
+          agent: voter2.publicKey,
+          hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter2])
+        .rpc();
+
+      try {
+        await program.methods
+          .cancelAction(hiveActionId)
+          .accounts({
+            fastAction: pda,
+            authority: voter1.publicKey,
+            roles: rolesPDA,
+            hiveConfig: hiveConfigPDA,
+          })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("CancellationClosed");
+      }
+
+      await program.methods
+        .setCancellationPolicy({ standard: {} })
+        .accounts({
+          hiveConfig: hiveConfigPDA,
+          roles: rolesPDA,
+          admin: hive.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([hive])
+        .rpc();
+    });
+
+    it("copies the hive's approval rounding and a bps threshold into its actions", async () => {
+      const [hiveConfigPDA] = deriveHiveConfigPDA(hive.publicKey);
+      const setRounding = (admin: Keypair, rounding: any) =>
+        program.methods
+          .setApprovalRounding(rounding)
+          .accounts({
+            hiveConfig: hiveConfigPDA,
+            roles: rolesPDA,
+            admin: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+
+      try {
+        await setRounding(voter1, { ceiling: {} });
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("MissingRole");
+      }
+      await setRounding(hive, { ceiling: {} });
+      const config = await program.account.hiveConfig.fetch(hiveConfigPDA);
+      expect(JSON.stringify(config.approvalRounding)).to.include("ceiling");
+
+      const create = async (seed: number, threshold: number, thresholdBps: number, withConfig = true) => {
+        const hiveActionId = await nextActionId();
+        const [pda] = deriveHiveActionPDA(hive.publicKey, hiveActionId);
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, seed)), threshold, { thresholdBps }))
+          .accounts({
+            hiveAccount: deriveHivePDA(hive.publicKey)[0],
+            fastAction: pda,
+            creator: creator.publicKey,
+            roles: rolesPDA,
+            creatorAllowlist: deriveCreatorAllowlistPDA(hive.publicKey)[0],
+            hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+            hiveConfig: withConfig ? hiveConfigPDA : null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        return pda;
+      };
+
+      // 2 of 3 is 66.67%: it meets 6667 only once rounded up
+      const pda = await create(130, 67, 6667);
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.threshold).to.equal(67);
+      expect(action.thresholdBps).to.equal(6667);
+      expect(JSON.stringify(action.approvalRounding)).to.include("ceiling");
+
+      for (const [seed, threshold, thresholdBps, withConfig, error] of [
+        [131, 66, 6601, true, "InvalidThreshold"],
+        [132, 67, 6600, true, "InvalidThreshold"],
+        [133, 67, 6667, false, "HiveConfigRequired"],
+      ] as const) {
+        try {
+          await create(seed, threshold, thresholdBps, withConfig);
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include(error);
+        }
+      }
+
+      await setRounding(hive, { floor: {} });
+    });
+
+    it("sets contest margins per action kind, hive Admin only", async () => {
+      const [categoryConfigPDA] = deriveCategoryConfigPDA(hive.publicKey);
+      const setMargin = (admin: Keypair, margin: number) =>
+        program.methods
+          .setContestMargin({ budget: {} }, margin)
+          .accounts({
+            categoryConfig: categoryConfigPDA,
+            roles: rolesPDA,
+            admin: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+
+      await setMargin(hive, 5);
+      const config = await program.account.categoryConfig.fetch(categoryConfigPDA);
+      expect(config.hive.toString()).to.equal(hive.publicKey.toString());
+      // Budget is the fifth ActionKind
+      expect(config.contestMargins[4]).to.equal(5);
+      expect(config.contestMargins[0]).to.equal(0);
+
+      for (const [admin, margin, error] of [
+        [voter1, 5, "MissingRole"],
+        [hive, 101, "InvalidContestMargin"],
+      ] as const) {
+        try {
+          await setMargin(admin, margin);
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include(error);
+        }
+      }
+    });
+
+    it("refuses to ratify an action that is not contested", async () => {
+      const hiveActionId = await nextActionId();
+      const [pda] = deriveHiveActionPDA(hive.publicKey, hiveActionId);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 47)), 50))
+        .accounts({
+          hiveAccount: deriveHivePDA(hive.publicKey)[0],
+          fastAction: pda,
+          creator: creator.publicKey,
+          roles: rolesPDA,
+          creatorAllowlist: deriveCreatorAllowlistPDA(hive.publicKey)[0],
+          hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+          hiveConfig: deriveHiveConfigPDA(hive.publicKey)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      const slowProgram = anchor.workspace.KamiyoSlowVoting.programId as PublicKey;
+      try {
+        await program.methods
+          .ratifyContested()
+          .accounts({
+            fastAction: pda,
+            slowAction: PublicKey.findProgramAddressSync([Buffer.from("slow_action"), pda.toBuffer()], slowProgram)[0],
+          })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("NotContested");
+      }
+    });
+
+    it("seeds actions created through the Hive account with the hive key", async () => {
+      const [hivePDA] = deriveHivePDA(hive.publicKey);
+      await program.methods
+        .updateHive({ authority: null, memberRoot: null, overrides: { maxActionsPerEpoch: 0, defaultQuorum: 1 } })
+        .accounts({ hiveAccount: hivePDA, authority: hive.publicKey })
+        .signers([hive])
+        .rpc();
+
+      const hiveActionId = await nextActionId();
+      try {
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 67)), 50))
+          .accounts({
+            hiveAccount: hivePDA,
+            fastAction: deriveFastActionPDA(hiveActionId)[0],
+            creator: creator.publicKey,
+            roles: rolesPDA,
+            creatorAllowlist: deriveCreatorAllowlistPDA(hive.publicKey)[0],
+            hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+            hiveConfig: deriveHiveConfigPDA(hive.publicKey)[0],
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ConstraintSeeds");
+      }
+
+      const [pda] = deriveHiveActionPDA(hive.publicKey, hiveActionId);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 67)), 50))
+        .accounts({
+          hiveAccount: hivePDA,
+          fastAction: pda,
+          creator: creator.publicKey,
+          roles: rolesPDA,
+          creatorAllowlist: deriveCreatorAllowlistPDA(hive.publicKey)[0],
+          hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+          hiveConfig: deriveHiveConfigPDA(hive.publicKey)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+      await program.methods
+        .voteFast(hiveActionId, true, Array.from(Buffer.alloc(32, 67)), 0)
+        .accounts({
+          fastAction: pda,
+          fastVote: deriveFastVotePDA(pda, voter2.publicKey)[0],
+          voter: voter2.publicKey,
+          agent: voter2.publicKey,
+          hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter2])
+        .rpc();
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.hiveSeeded).to.be.true;
+      expect(action.quorum).to.equal(1);
+      expect(action.voteCount).to.equal(1);
+
+      await program.methods
+        .updateHive({ authority: null, memberRoot: null, overrides: { maxActionsPerEpoch: 0, defaultQuorum: 0 } })
+        .accounts({ hiveAccount: hivePDA, authority: hive.publicKey })
+        .signers([hive])
+        .rpc();
+    });
+
+    it("rejects a hive action created without its Hive account", async () => {
+      const [pda] = deriveFastActionPDA(await nextActionId());
+      try {
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 69)), 50))
+          .accounts({
+            hiveAccount: null,
+            fastAction: pda,
+            creator: creator.publicKey,
+            roles: rolesPDA,
+            creatorAllowlist: deriveCreatorAllowlistPDA(hive.publicKey)[0],
+            hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+            hiveConfig: deriveHiveConfigPDA(hive.publicKey)[0],
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("HiveAccountRequired");
+      }
+      expect(await provider.connection.getAccountInfo(pda)).to.be.null;
+    });
+
+    it("restricted hives take actions only from allowlisted creators", async () => {
+      const [hivePDA] = deriveHivePDA(hive.publicKey);
+      const [allowlistPDA] = deriveCreatorAllowlistPDA(hive.publicKey);
+      const create = (fill: number, creatorAllowlist: PublicKey | null) =>
+        nextActionId().then((id) =>
+          program.methods
+            .createFastAction(actionParams(Array.from(Buffer.alloc(32, fill)), 50))
+            .accounts({
+              hiveAccount: hivePDA,
+              fastAction: deriveHiveActionPDA(hive.publicKey, id)[0],
+              creator: creator.publicKey,
+              roles: rolesPDA,
+              creatorAllowlist,
+              hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+              hiveConfig: deriveHiveConfigPDA(hive.publicKey)[0],
+              systemProgram: SystemProgram.programId,
+            })
+            .signers([creator])
+            .rpc()
+        );
+      const setRestricted = (restricted: boolean, authority: Keypair) =>
+        program.methods
+          .setCreatorRestriction(restricted)
+          .accounts({ hiveAccount: hivePDA, creatorAllowlist: allowlistPDA, authority: authority.publicKey })
+          .signers([authority])
+          .rpc();
+
+      for (const [attempt, error] of [
+        [() => create(118, null), "CreatorAllowlistRequired"],
+        [() => setRestricted(true, creator), "Unauthorized"],
+      ] as const) {
+        try {
+          await attempt();
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include(error);
+        }
+      }
+
+      await setRestricted(true, hive);
+      try {
+        try {
+          await create(118, allowlistPDA);
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include("CreatorNotAllowed");
+        }
+
+        await program.methods
+          .allowCreator(creator.publicKey)
+          .accounts({ hiveAccount: hivePDA, creatorAllowlist: allowlistPDA, authority: hive.publicKey })
+          .signers([hive])
+          .rpc();
+        await create(118, allowlistPDA);
+        const allowlist = await program.account.creatorAllowlist.fetch(allowlistPDA);
+        expect(allowlist.restricted).to.be.true;
+        expect(allowlist.count).to.equal(1);
+        expect(allowlist.creators[0].equals(creator.publicKey)).to.be.true;
+      } finally {
+        await setRestricted(false, hive);
+      }
+    });
+
+    it("hive metrics count only the hive's actions", async () => {
+      const [hivePDA] = deriveHivePDA(hive.publicKey);
+      const [metricsPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("metrics"), Buffer.from([0]), hive.publicKey.toBuffer()],
+        program.programId
+      );
+      const initialize = (authority: Keypair) =>
+        program.methods
+          .initializeHiveMetrics({ base: {} })
+          .accounts({ hiveAccount: hivePDA, metrics: metricsPDA, authority: authority.publicKey })
+          .signers([authority])
+          .rpc();
+      const create = (fill: number, scoped: boolean) =>
+        nextActionId().then((id) =>
+          program.methods
+            .createFastAction(actionParams(Array.from(Buffer.alloc(32, fill)), 50))
+            .accounts({
+              hiveAccount: scoped ? hivePDA : null,
+              fastAction: scoped ? deriveHiveActionPDA(hive.publicKey, id)[0] : deriveFastActionPDA(id)[0],
+              creator: creator.publicKey,
+              roles: scoped ? rolesPDA : null,
+              creatorAllowlist: scoped ? deriveCreatorAllowlistPDA(hive.publicKey)[0] : null,
+              hiveFreeze: scoped ? deriveHiveFreezePDA(hive.publicKey)[0] : null,
+              hiveConfig: scoped ? deriveHiveConfigPDA(hive.publicKey)[0] : null,
+              metrics: metricsPDA,
+              systemProgram: SystemProgram.programId,
+            })
+            .signers([creator])
+            .rpc()
+        );
+
+      try {
+        await initialize(creator);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+      await initialize(hive);
+      await create(119, true);
+      try {
+        await create(120, false);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("MetricsHiveMismatch");
+      }
+
+      const metrics = await program.account.metrics.fetch(metricsPDA);
+      expect(metrics.hive.equals(hive.publicKey)).to.be.true;
+      expect(metrics.actionsCreated.toNumber()).to.equal(1);
+    });
+
+    it("standings board carries a hive action's live tally", async () => {
+      const [hivePDA] = deriveHivePDA(hive.publicKey);
+      const [boardPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("standings_board"), hive.publicKey.toBuffer()],
+        program.programId
+      );
+      const initialize = (authority: Keypair) =>
+        program.methods
+          .initializeStandingsBoard()
+          .accounts({ hiveAccount: hivePDA, standingsBoard: boardPDA, authority: authority.publicKey })
+          .signers([authority])
+          .rpc();
+
+      try {
+        await initialize(creator);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+      await initialize(hive);
+
+      const hiveActionId = await nextActionId();
+      const [pda] = deriveHiveActionPDA(hive.publicKey, hiveActionId);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 121)), 50))
+        .accounts({
+          hiveAccount: deriveHivePDA(hive.publicKey)[0],
+          fastAction: pda,
+          creator: creator.publicKey,
+          roles: rolesPDA,
+          creatorAllowlist: deriveCreatorAllowlistPDA(hive.publicKey)[0],
+          hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+          hiveConfig: deriveHiveConfigPDA(hive.publicKey)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+      await program.methods
+        .voteFast(hiveActionId, true, Array.from(Buffer.alloc(32, 121)), 0)
+        .accounts({
+          fastAction: pda,
+          fastVote: deriveFastVotePDA(pda, voter2.publicKey)[0],
+          voter: voter2.publicKey,
+          agent: voter2.publicKey,
+          hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+          standingsBoard: boardPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter2])
+        .rpc();
+
+      const board = await program.account.standingsBoard.fetch(boardPDA);
+      expect(board.hive.equals(hive.publicKey)).to.be.true;
+      const entry = board.entries.find((e: any) => e.action.equals(pda))!;
+      expect(entry.status).to.deep.equal({ open: {} });
+      expect(entry.actionId.toNumber()).to.equal(hiveActionId.toNumber());
+      expect(entry.votesFor).to.equal(1);
+      expect(entry.voteCount).to.equal(1);
+    });
+
+    it("non-admin cannot grant roles", async () => {
+      try {
+        await program.methods
+          .grantRole(voter2.publicKey, { admin: {} })
+          .accounts({ roles: rolesPDA, admin: voter2.publicKey })
+          .signers([voter2])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("MissingRole");
+      }
+    });
+
+    it("optimistic action passes provisionally and only accepts vetoes", async () => {
+      const optimisticId = await nextActionId();
+      const [pda] = deriveHiveActionPDA(hive.publicKey, optimisticId);
+
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 27)), 50, { voteMode: { optimistic: {} } }))
+        .accounts({
+          hiveAccount: deriveHivePDA(hive.publicKey)[0],
+          fastAction: pda,
+          creator: creator.publicKey,
+          roles: rolesPDA,
+          creatorAllowlist: deriveCreatorAllowlistPDA(hive.publicKey)[0],
+          hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+          hiveConfig: deriveHiveConfigPDA(hive.publicKey)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(JSON.stringify(action.result)).to.include("passed");
+      expect(action.electorate).to.equal(3);
+      expect(action.bond.toNumber()).to.be.greaterThan(0);
+
+      const [votePDA] = deriveFastVotePDA(pda, voter2.publicKey);
+      try {
+        await program.methods
+          .voteFast(optimisticId, true, Array.from(Buffer.alloc(32, 28)), 0)
+          .accounts({
+            fastAction: pda,
+            fastVote: votePDA,
+            voter: voter2.publicKey,
+            agent: voter2.publicKey,
+            hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([voter2])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("OptimisticVetoOnly");
+      }
+    });
+  });
+
+  describe("federated actions", () => {
+    const hives: Keypair[] = [];
+    const children: PublicKey[] = [];
+
+    before(async () => {
+      for (let i = 0; i < 2; i++) {
+        const hive = Keypair.generate();
+        const [rolesPDA] = deriveRolesPDA(hive.publicKey);
+        await program.methods
+          .initializeRoles()
+          .accounts({
+            roles: rolesPDA,
+            hive: hive.publicKey,
+            payer: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([hive, creator])
+          .rpc();
+        await createHiveAccount(hive);
+        await program.methods
+          .grantRole(creator.publicKey, { creator: {} })
+          .accounts({ roles: rolesPDA, admin: hive.publicKey })
+          .signers([hive])
+          .rpc();
+
+        const id = await nextActionId();
+        const [pda] = deriveHiveActionPDA(hive.publicKey, id);
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 68 + i)), 50))
+          .accounts({
+            hiveAccount: deriveHivePDA(hive.publicKey)[0],
+            fastAction: pda,
+            creator: creator.publicKey,
+            roles: rolesPDA,
+            creatorAllowlist: deriveCreatorAllowlistPDA(hive.publicKey)[0],
+            hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+            hiveConfig: deriveHiveConfigPDA(hive.publicKey)[0],
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        hives.push(hive);
+        children.push(pda);
+      }
+    });
+
+    function federatedPDA(hash: number[]): PublicKey {
+      return PublicKey.findProgramAddressSync([Buffer.from("federated_action"), Buffer.from(hash)], program.programId)[0];
+    }
+
+    it("rejects two children from the same hive", async () => {
+      const hash = Array.from(Buffer.alloc(32, 70));
+      try {
+        await program.methods
+          .createFederatedAction(hash, 60, [
+            { action: children[0], hive: hives[0].publicKey, weight: 1 },
+            { action: children[0], hive: hives[0].publicKey, weight: 1 },
+          ])
+          .accounts({ federatedAction: federatedPDA(hash), creator: creator.publicKey, systemProgram: SystemProgram.programId })
+          .remainingAccounts(
+            [children[0], children[0]].map((pubkey) => ({ pubkey, isWritable: false, isSigner: false }))
+          )
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidFederation");
+      }
+    });
+
+    it("settles only once every child is final", async () => {
+      const hash = Array.from(Buffer.alloc(32, 71));
+      const remaining = children.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false }));
+      await program.methods
+        .createFederatedAction(hash, 60, [
+          { action: children[0], hive: hives[0].publicKey, weight: 3 },
+          { action: children[1], hive: hives[1].publicKey, weight: 2 },
+        ])
+        .accounts({ federatedAction: federatedPDA(hash), creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .remainingAccounts(remaining)
+        .signers([creator])
+        .rpc();
+
+      try {
+        await program.methods
+          .settleFederatedAction()
+          .accounts({ federatedAction: federatedPDA(hash) })
+          .remainingAccounts(remaining)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("FederationNotSettled");
+      }
+
+      const federated = await program.account.federatedAction.fetch(federatedPDA(hash));
+      expect(federated.childCount).to.equal(2);
+      expect(JSON.stringify(federated.result)).to.include("pending");
+    });
+  });
+
+  describe("elections", () => {
+    const TERM_SLOTS = new anchor.BN(1_000);
+    let hive: Keypair;
+    let rolesPDA: PublicKey;
+
+    function electionHash(candidates: PublicKey[]): number[] {
+      const len = Buffer.alloc(4);
+      len.writeUInt32LE(candidates.length);
+      const data = Buffer.concat([len, ...candidates.map((c) => c.toBuffer()), TERM_SLOTS.toArrayLike(Buffer, "le", 8)]);
+      return Array.from(createHash("sha256").update(Buffer.from("election")).update(data).digest());
+    }
+
+    before(async () => {
+      hive = Keypair.generate();
+      [rolesPDA] = deriveRolesPDA(hive.publicKey);
+      await program.methods
+        .initializeRoles()
+        .accounts({ roles: rolesPDA, hive: hive.publicKey, payer: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([hive, creator])
+        .rpc();
+      await createHiveAccount(hive);
+      await program.methods
+        .grantRole(creator.publicKey, { creator: {} })
+        .accounts({ roles: rolesPDA, admin: hive.publicKey })
+        .signers([hive])
+        .rpc();
+    });
+
+    it("rejects elections outside a hive", async () => {
+      const id = await nextActionId();
+      try {
+        await program.methods
+          .createFastAction(
+            actionParams(electionHash([voter1.publicKey, voter2.publicKey]), 50, { optionCount: 2, kind: { election: {} } })
+          )
+          .accounts({ fastAction: deriveFastActionPDA(id)[0], creator: creator.publicKey, systemProgram: SystemProgram.programId })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidElectionAction");
+      }
+    });
+
+    it("installs no leader before the election is decided", async () => {
+      const candidates = [voter1.publicKey, voter2.publicKey];
+      const id = await nextActionId();
+      const [pda] = deriveHiveActionPDA(hive.publicKey, id);
+      await program.methods
+        .createFastAction(actionParams(electionHash(candidates), 50, { optionCount: 2, kind: { election: {} } }))
+        .accounts({
+          hiveAccount: deriveHivePDA(hive.publicKey)[0],
+          fastAction: pda,
+          creator: creator.publicKey,
+          roles: rolesPDA,
+          creatorAllowlist: deriveCreatorAllowlistPDA(hive.publicKey)[0],
+          hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+          hiveConfig: deriveHiveConfigPDA(hive.publicKey)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+      const [recordPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("agent"), voter1.publicKey.toBuffer()],
+        program.programId
+      );
+      if (!(await provider.connection.getAccountInfo(recordPDA))) {
+        await program.methods
+          .registerAgent(new anchor.BN(1_000_000))
+          .accounts({ agent: voter1.publicKey, systemProgram: SystemProgram.programId })
+          .signers([voter1])
+          .rpc();
+      }
+
+      try {
+        await program.methods
+          .installLeader({ candidates, termSlots: TERM_SLOTS })
+          .accounts({ fastAction: pda, agentRecord: recordPDA, payer: creator.publicKey, systemProgram: SystemProgram.programId })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ActionNotExecutable");
+      }
+    });
+  });
+
+  describe("task assignments", () => {
+    const queue = Keypair.generate().publicKey;
+    const DEADLINE = new anchor.BN(1_000_000);
+
+    function taskArgs(assignees: PublicKey[]) {
+      return {
+        queueProgram: SystemProgram.programId,
+        queue,
+        taskHash: Array(32).fill(72),
+        assignees,
+        deadlineSlot: DEADLINE,
+      };
+    }
+
+    function taskAssignmentHash(assignees: PublicKey[]): number[] {
+      const len = Buffer.alloc(4);
+      len.writeUInt32LE(assignees.length);
+      const data = Buffer.concat([
+        SystemProgram.programId.toBuffer(),
+        queue.toBuffer(),
+        Buffer.alloc(32, 72),
+        len,
+        ...assignees.map((a) => a.toBuffer()),
+        DEADLINE.toArrayLike(Buffer, "le", 8),
+      ]);
+      return Array.from(createHash("sha256").update(Buffer.from("task_assignment")).update(data).digest());
+    }
+
+    it("rejects for/against task assignments", async () => {
+      const id = await nextActionId();
+      try {
+        await program.methods
+          .createFastAction(
+            actionParams(taskAssignmentHash([voter1.publicKey]), 50, { optionCount: 0, kind: { taskAssignment: {} } })
+          )
+          .accounts({ fastAction: deriveFastActionPDA(id)[0], creator: creator.publicKey, systemProgram: SystemProgram.programId })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidTaskAssignment");
+      }
+    });
+
+    it("pushes no task before the assignment is decided", async () => {
+      const assignees = [voter1.publicKey, voter2.publicKey];
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(taskAssignmentHash(assignees), 50, { optionCount: 2, kind: { taskAssignment: {} } }))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+
+      try {
+        await program.methods
+          .pushTask(taskArgs(assignees))
+          .accounts({
+            fastAction: pda,
+            queue,
+            queueProgram: SystemProgram.programId,
+            payer: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ActionNotExecutable");
+      }
+    });
+  });
+
+  describe("optimistic mode", () => {
+    it("rejects an optimistic action outside a hive", async () => {
+      const optimisticId = await nextActionId();
+      const [pda] = deriveFastActionPDA(optimisticId);
+
+      try {
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 29)), 50, { voteMode: { optimistic: {} } }))
+          .accounts({
+            fastAction: pda,
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("OptimisticRequiresHive");
+      }
+    });
+  });
+
+  describe("force_undelegate", () => {
+    it("rejects before the liveness timeout", async () => {
+      const staleId = await nextActionId();
+      const [pda] = deriveFastActionPDA(staleId);
+
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 23)), 50))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      try {
+        await program.methods
+          .forceUndelegate()
+          .accounts({
+            fastAction: pda,
+            payer: creator.publicKey,
+            magicContext: new PublicKey("MagicContext1111111111111111111111111111111"),
+            magicProgram: new PublicKey("Magic11111111111111111111111111111111111111"),
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("LivenessTimeoutNotReached");
+      }
+    });
+
+    it("reap_stale_delegations skips actions that are not stale", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 95)), 50))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+
+      await program.methods
+        .reapStaleDelegations()
+        .accounts({
+          payer: creator.publicKey,
+          magicContext: new PublicKey("MagicContext1111111111111111111111111111111"),
+          magicProgram: new PublicKey("Magic11111111111111111111111111111111111111"),
+        })
+        .remainingAccounts([{ pubkey: pda, isWritable: true, isSigner: false }])
+        .signers([creator])
+        .rpc();
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.executed).to.equal(false);
+      expect(JSON.stringify(action.result)).to.include("pending");
+    });
+  });
+
+  describe("expire_action", () => {
+    async function create(fill: number) {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, fill)), 50))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      return { id, pda };
+    }
+
+    function expire(pda: PublicKey) {
+      return program.methods.expireAction().accounts({ fastAction: pda, creator: creator.publicKey }).rpc();
+    }
+
+    it("rejects before the expiry grace", async () => {
+      const { pda } = await create(88);
+      try {
+        await expire(pda);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ExpiryGraceNotReached");
+      }
+    });
+
+    it("never closes a finalized action", async () => {
+      const { id, pda } = await create(89);
+      await program.methods
+        .cancelAction(id)
+        .accounts({ fastAction: pda, authority: creator.publicKey })
+        .signers([creator])
+        .rpc();
+      try {
+        await expire(pda);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ActionAlreadyExecuted");
+      }
+    });
+  });
+
+  describe("action templates", () => {
+    let hive: Keypair;
+    let rolesPDA: PublicKey;
+    let templatePDA: PublicKey;
+    const callbackProgram = Keypair.generate().publicKey;
+
+    function templateParams(overrides: Record<string, unknown> = {}) {
+      return {
+        kind: { standard: {} },
+        voteMode: { headcount: {} },
+        threshold: 60,
+        windowSlots: new anchor.BN(150),
+        schemaHash: Array.from(Buffer.alloc(32, 7)),
+        callbacks: [{ programId: callbackProgram, accountsHash: Array.from(Buffer.alloc(32, 0)), accountCount: 0 }],
+        ...overrides,
+      };
+    }
+
+    function fromTemplate(fill: number, id: anchor.BN, accounts: Record<string, PublicKey> = {}) {
+      const [pda] = deriveHiveActionPDA(hive.publicKey, id);
+      return {
+        pda,
+        call: program.methods
+          .createFromTemplate({
+            actionHash: Array.from(Buffer.alloc(32, fill)),
+            descriptionHash: Array.from(Buffer.alloc(32, 0)),
+            dependencies: [],
+            exclusions: [],
+          })
+          .accounts({
+            hiveAccount: deriveHivePDA(hive.publicKey)[0],
+            fastAction: pda,
+            creator: creator.publicKey,
+            roles: rolesPDA,
+            creatorAllowlist: deriveCreatorAllowlistPDA(hive.publicKey)[0],
+            hiveFreeze: deriveHiveFreezePDA(hive.publicKey)[0],
+            hiveConfig: deriveHiveConfigPDA(hive.publicKey)[0],
+            template: templatePDA,
+            systemProgram: SystemProgram.programId,
+            ...accounts,
+          })
+          .signers([creator]),
+      };
+    }
+
+    before(async () => {
+      hive = Keypair.generate();
+      [rolesPDA] = deriveRolesPDA(hive.publicKey);
+      [templatePDA] = deriveTemplatePDA(hive.publicKey, 1);
+
+      await program.methods
+        .initializeRoles()
+        .accounts({ roles: rolesPDA, hive: hive.publicKey, payer: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([hive, creator])
+        .rpc();
+      await createHiveAccount(hive);
+      await program.methods
+        .grantRole(creator.publicKey, { creator: {} })
+        .accounts({ roles: rolesPDA, admin: hive.publicKey })
+        .signers([hive])
+        .rpc();
+    });
+
+    it("rejects a template from a non-admin", async () => {
+      try {
+        await program.methods
+          .createTemplate(2, templateParams())
+          .accounts({
+            template: deriveTemplatePDA(hive.publicKey, 2)[0],
+            roles: rolesPDA,
+            admin: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("MissingRole");
+      }
+    });
+
+    it("rejects an emergency template with its own window", async () => {
+      try {
+        await program.methods
+          .createTemplate(2, templateParams({ kind: { emergency: {} } }))
+          .accounts({
+            template: deriveTemplatePDA(hive.publicKey, 2)[0],
+            roles: rolesPDA,
+            admin: hive.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([hive])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidTemplateWindow");
+      }
+    });
+
+    it("creates an action with the template's shape and callbacks", async () => {
+      await program.methods
+        .createTemplate(1, templateParams())
+        .accounts({ template: templatePDA, roles: rolesPDA, admin: hive.publicKey, systemProgram: SystemProgram.programId })
+        .signers([hive])
+        .rpc();
+
+      const id = await nextActionId();
+      const [registryPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("callbacks"), deriveHiveActionPDA(hive.publicKey, id)[0].toBuffer()],
+        program.programId
+      );
+      const { pda, call } = fromTemplate(90, id, { callbackRegistry: registryPDA });
+      await call.rpc();
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.threshold).to.equal(60);
+      expect(action.hive.toString()).to.equal(hive.publicKey.toString());
+      expect(action.deadlineSlot.sub(action.createdSlot).toNumber()).to.equal(150);
+      const registry = await program.account.callbackRegistry.fetch(registryPDA);
+      expect(registry.count).to.equal(1);
+      expect(registry.callbacks[0].programId.toString()).to.equal(callbackProgram.toString());
+    });
+
+    it("needs the registry when the template has callbacks", async () => {
+      const { call } = fromTemplate(91, await nextActionId());
+      try {
+        await call.rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("MissingTemplate");
+      }
+    });
+
+    it("applies updates to later actions only", async () => {
+      await program.methods
+        .updateTemplate(templateParams({ threshold: 75, callbacks: [] }))
+        .accounts({ template: templatePDA, roles: rolesPDA, admin: hive.publicKey })
+        .signers([hive])
+        .rpc();
+
+      const { pda, call } = fromTemplate(92, await nextActionId());
+      await call.rpc();
+      expect((await program.account.fastAction.fetch(pda)).threshold).to.equal(75);
+    });
+  });
+
+  describe("exclusions", () => {
+    it("rejects ballots from excluded voters only", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 93)), 50, { exclusions: [voter1.publicKey] }))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+
+      function vote(voter: Keypair) {
+        return program.methods
+          .voteFast(id, true, Array.from(Buffer.alloc(32, 93)), 0)
+          .accounts({
+            fastAction: pda,
+            fastVote: deriveFastVotePDA(pda, voter.publicKey)[0],
+            voter: voter.publicKey,
+            agent: voter.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([voter])
+          .rpc();
+      }
+
+      try {
+        await vote(voter1);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("VoterExcluded");
+      }
+      await vote(voter2);
+      expect((await program.account.fastAction.fetch(pda)).voteCount).to.equal(1);
+    });
+
+    it("rejects duplicate exclusions", async () => {
+      const id = await nextActionId();
+      try {
+        await program.methods
+          .createFastAction(
+            actionParams(Array.from(Buffer.alloc(32, 94)), 50, { exclusions: [voter1.publicKey, voter1.publicKey] })
+          )
+          .accounts({
+            fastAction: deriveFastActionPDA(id)[0],
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidExclusions");
+      }
+    });
+  });
+
+  describe("collection gating", () => {
+    const collection = Keypair.generate().publicKey;
+
+    it("needs a headcount or optimistic for/against action", async () => {
+      const id = await nextActionId();
+      try {
+        await program.methods
+          .createFastAction(
+            actionParams(Array.from(Buffer.alloc(32, 20)), 50, { voteMode: { tokenWeighted: {} }, memberCollection: collection })
+          )
+          .accounts({ fastAction: deriveFastActionPDA(id)[0], creator: creator.publicKey, systemProgram: SystemProgram.programId })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidMemberCollection");
+      }
+    });
+
+    it("refuses ballots without an NFT of the collection", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 4)), 50, { memberCollection: collection }))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      expect((await program.account.fastAction.fetch(pda)).memberCollection.toBase58()).to.equal(collection.toBase58());
+
+      for (const shown of [[], [voter1.publicKey], [voter1.publicKey, collection]]) {
+        try {
+          await program.methods
+            .voteFast(id, true, Array.from(Buffer.alloc(32, 4)), 0)
+            .accounts({
+              fastAction: pda,
+              fastVote: deriveFastVotePDA(pda, voter1.publicKey)[0],
+              voter: voter1.publicKey,
+              agent: voter1.publicKey,
+              systemProgram: SystemProgram.programId,
+            })
+            .remainingAccounts(shown.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false })))
+            .signers([voter1])
+            .rpc();
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include("NotCollectionMember");
+        }
+      }
+    });
+  });
+
+  describe("priority lanes", () => {
+    const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+    const [indexPDA] = PublicKey.findProgramAddressSync([Buffer.from("active_actions"), Buffer.alloc(0)], program.programId);
+
+    const create = (fill: number, priority: number, activeActions: PublicKey | null) =>
+      nextActionId().then((id) =>
+        program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, fill)), 50, { priority }))
+          .accounts({
+            fastAction: deriveFastActionPDA(id)[0],
+            creator: creator.publicKey,
+            activeActions,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc()
+      );
+
+    const setCap = (max: number) =>
+      program.methods
+        .updateConfig({
+          admin: null,
+          weightMint: null,
+          weightExtensions: null,
+          transferHookProgram: null,
+          maxActionsPerEpoch: null,
+          guardian: null,
+          voteCreditsPerEpoch: null,
+          minAgentBond: null,
+          whistleblowerBps: null,
+          snapshotter: null,
+          maxWindowSlots: null,
+          stakeLockProgram: null,
+          maxLowPriorityActions: max,
+          maxVotesPerAction: null,
+          identityRamp: null,
+          feeRebate: null,
+          maxActionsPerValidator: null,
+        })
+        .accounts({ config: configPDA, admin: provider.wallet.publicKey })
+        .rpc();
+
+    async function expectError(promise: Promise<unknown>, error: string) {
+      try {
+        await promise;
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include(error);
+      }
+    }
+
+    it("rejects a priority past the last lane", async () => {
+      await expectError(create(69, 4, null), "InvalidPriority");
+    });
+
+    it("counts actions in their lane and caps the lowest", async () => {
+      await program.methods
+        .initializeActiveActions()
+        .accounts({ hiveAccount: null, activeActions: indexPDA, payer: provider.wallet.publicKey })
+        .rpc();
+      await create(69, 0, indexPDA);
+      const index = await program.account.activeActions.fetch(indexPDA);
+      expect(index.counts[0]).to.equal(1);
+      expect(index.counts[1]).to.equal(0);
+
+      await setCap(1);
+      try {
+        await expectError(create(84, 2, null), "ActiveActionsRequired");
+        await expectError(create(84, 0, indexPDA), "LowPriorityLaneFull");
+      } finally {
+        await setCap(0);
+      }
+    });
+  });
+
+  describe("tags", () => {
+    const tag = Array.from(Buffer.from("trading\0"));
+    const pagePDA = (page: number) => {
+      const index = Buffer.alloc(4);
+      index.writeUInt32LE(page);
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("tag_index"), Buffer.alloc(0), Buffer.from(tag), index],
+        program.programId
+      )[0];
+    };
+
+    const create = (fill: number, tags: number[][], pages: PublicKey[]) =>
+      nextActionId().then((id) =>
+        program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, fill)), 50, { tags }))
+          .accounts({
+            fastAction: deriveFastActionPDA(id)[0],
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .remainingAccounts(pages.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+          .signers([creator])
+          .rpc()
+          .then(() => deriveFastActionPDA(id)[0])
+      );
+
+    async function expectError(promise: Promise<unknown>, error: string) {
+      try {
+        await promise;
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include(error);
+      }
+    }
+
+    it("rejects zero and repeated tags", async () => {
+      await expectError(create(120, [Array(8).fill(0)], []), "InvalidTags");
+      await expectError(create(120, [tag, tag], []), "InvalidTags");
+    });
+
+    it("lists a tagged action in the tag's page", async () => {
+      await program.methods
+        .initializeTagIndex(tag, 0)
+        .accounts({
+          hiveAccount: null,
+          previous: null,
+          tagIndex: pagePDA(0),
+          payer: provider.wallet.publicKey,
+        })
+        .rpc();
+      await expectError(create(121, [tag], []), "InvalidTagIndex");
+
+      const action = await create(121, [tag], [pagePDA(0)]);
+      const page = await program.account.tagIndex.fetch(pagePDA(0));
+      expect(page.count).to.equal(1);
+      expect(page.actions[0].equals(action)).to.be.true;
+      const fetched = await program.account.fastAction.fetch(action);
+      expect(fetched.tagsIndexed).to.be.true;
+    });
+
+    it("needs the previous page to open the next", async () => {
+      await expectError(
+        program.methods
+          .initializeTagIndex(tag, 2)
+          .accounts({
+            hiveAccount: null,
+            previous: pagePDA(1),
+            tagIndex: pagePDA(2),
+            payer: provider.wallet.publicKey,
+          })
+          .rpc(),
+        "AccountNotInitialized"
+      );
+    });
+
+    it("keeps an open action listed", async () => {
+      const action = await create(122, [tag], [pagePDA(0)]);
+      await expectError(
+        program.methods
+          .retireTags()
+          .accounts({ fastAction: action })
+          .remainingAccounts([{ pubkey: pagePDA(0), isSigner: false, isWritable: true }])
+          .rpc(),
+        "ActionNotFinalized"
+      );
+    });
+  });
+
+  describe("create_actions_batch", () => {
+    it("opens each action under consecutive ids", async () => {
+      const first = await nextActionId();
+      const batch = [100, 101].map((fill) => actionParams(Array.from(Buffer.alloc(32, fill)), 50));
+      const accounts = [];
+      for (const [i, params] of batch.entries()) {
+        const ix = await program.methods
+          .createFastAction(params)
+          .accounts({
+            fastAction: deriveFastActionPDA(first.addn(i))[0],
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .instruction();
+        accounts.push(...ix.keys);
+      }
+      await program.methods
+        .createActionsBatch(batch)
+        .accounts({ creator: creator.publicKey })
+        .remainingAccounts(accounts)
+        .signers([creator])
+        .rpc();
+
+      for (const [i, fill] of [100, 101].entries()) {
+        const action = await program.account.fastAction.fetch(deriveFastActionPDA(first.addn(i))[0]);
+        expect(action.actionId.toString()).to.equal(first.addn(i).toString());
+        expect(action.actionHash).to.deep.equal(Array.from(Buffer.alloc(32, fill)));
+      }
+      expect((await nextActionId()).toString()).to.equal(first.addn(2).toString());
+    });
+
+    it("refuses accounts left over after the last entry", async () => {
+      const id = await nextActionId();
+      const params = actionParams(Array.from(Buffer.alloc(32, 102)), 50);
+      const ix = await program.methods
+        .createFastAction(params)
+        .accounts({ fastAction: deriveFastActionPDA(id)[0], creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .instruction();
+      try {
+        await program.methods
+          .createActionsBatch([params])
+          .accounts({ creator: creator.publicKey })
+          .remainingAccounts([...ix.keys, ix.keys[0]])
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidBatch");
+      }
+    });
+  });
+
+  describe("stake locks", () => {
+    for (const [name, fill, overrides] of [
+      ["needs a token-weighted action", 52, {}],
+      ["needs a stake lock program in the config", 68, { voteMode: { tokenWeighted: {} } }],
+    ] as const) {
+      it(name, async () => {
+        const id = await nextActionId();
+        try {
+          await program.methods
+            .createFastAction(actionParams(Array.from(Buffer.alloc(32, fill)), 50, { ...overrides, lockStake: true }))
+            .accounts({ fastAction: deriveFastActionPDA(id)[0], creator: creator.publicKey, systemProgram: SystemProgram.programId })
+            .signers([creator])
+            .rpc();
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include("InvalidStakeLock");
+        }
+      });
+    }
+
+    describe("live balances", () => {
+      const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+      const [lockAuthority] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake_lock_authority")],
+        program.programId
+      );
+      const BALANCE = 1_000;
+      let mint: PublicKey;
+      let account1: PublicKey;
+      let account2: PublicKey;
+      let actionId: anchor.BN;
+      let actionPDA: PublicKey;
+
+      function setWeightConfig(weightMint: PublicKey, stakeLockProgram: PublicKey) {
+        return program.methods
+          .updateConfig({
+            admin: null,
+            weightMint,
+            weightExtensions: null,
+            transferHookProgram: null,
+            maxActionsPerEpoch: null,
+            guardian: null,
+            voteCreditsPerEpoch: null,
+            minAgentBond: null,
+            whistleblowerBps: null,
+            snapshotter: null,
+            maxWindowSlots: null,
+            stakeLockProgram,
+            maxLowPriorityActions: null,
+            maxVotesPerAction: null,
+            identityRamp: null,
+            feeRebate: null,
+            maxActionsPerValidator: null,
+          })
+          .accounts({ config: configPDA, admin: provider.wallet.publicKey })
+          .rpc();
+      }
+
+      function vote(voter: Keypair, tokenAccount: PublicKey, stakeLockProgram = TOKEN_PROGRAM_ID) {
+        return program.methods
+          .voteFastWeighted(actionId, true, Array.from(Buffer.alloc(32, 152)), 0)
+          .accounts({
+            fastAction: actionPDA,
+            voter: voter.publicKey,
+            weightMint: mint,
+            voterTokenAccount: tokenAccount,
+            stakeLockProgram,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([voter])
+          .rpc();
+      }
+
+      before(async () => {
+        // The lock authority freezes accounts itself, with the token
+        // program as the lock program
+        mint = await createMint(provider.connection, creator, creator.publicKey, lockAuthority, 0);
+        account1 = await createAccount(provider.connection, creator, mint, voter1.publicKey);
+        account2 = await createAccount(provider.connection, creator, mint, voter2.publicKey);
+        await mintTo(provider.connection, creator, mint, account1, creator, BALANCE);
+
+        actionId = await nextActionId();
+        [actionPDA] = deriveFastActionPDA(actionId);
+        await program.methods
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, 151)), 50, { voteMode: { tokenWeighted: {} } }))
+          .accounts({ fastAction: actionPDA, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+          .signers([creator])
+          .rpc();
+      });
+
+      after(async () => {
+        await setWeightConfig(PublicKey.default, PublicKey.default);
+      });
+
+      it("are refused without a stake lock program", async () => {
+        await setWeightConfig(mint, PublicKey.default);
+        try {
+          await vote(voter1, account1, SystemProgram.programId);
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include("InvalidStakeLock");
+        }
+      });
+
+      it("stay locked once counted, so a transfer cannot vote them again", async () => {
+        await setWeightConfig(mint, TOKEN_PROGRAM_ID);
+        await vote(voter1, account1);
+        const action = await program.account.fastAction.fetch(actionPDA);
+        expect(action.weightFor.toNumber()).to.equal(BALANCE);
+        expect((await getAccount(provider.connection, account1)).isFrozen).to.be.true;
+        const [lockPDA] = PublicKey.findProgramAddressSync(
+          [Buffer.from("stake_lock"), voter1.publicKey.toBuffer()],
+          program.programId
+        );
+        const lock = await program.account.stakeLock.fetch(lockPDA);
+        expect(lock.active).to.equal(1);
+        expect(lock.tokenAccount.toBase58()).to.equal(account1.toBase58());
+
+        try {
+          await transfer(provider.connection, voter1, account1, account2, voter1, BALANCE);
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          // spl-token AccountFrozen
+          expect(err.message).to.include("0x11");
+        }
+        try {
+          await vote(voter2, account2);
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include("NoVotingWeight");
+        }
+        expect((await program.account.fastAction.fetch(actionPDA)).weightFor.toNumber()).to.equal(BALANCE);
+      });
+    });
+  });
+
+  describe("extend_deadline", () => {
+    async function create(fill: number, overrides: Record<string, unknown> = {}): Promise<[anchor.BN, PublicKey]> {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, fill)), 50, overrides))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      return [id, pda];
+    }
+
+    function extend(pda: PublicKey, slots: number, cosigners: Keypair[]) {
+      const supporters = cosigners
+        .map((voter) => ({ voter, vote: deriveFastVotePDA(pda, voter.publicKey)[0] }))
+        .sort((a, b) => Buffer.compare(a.vote.toBuffer(), b.vote.toBuffer()));
+      return program.methods
+        .extendDeadline(new anchor.BN(slots))
+        .accounts({ fastAction: pda, authority: creator.publicKey })
+        .remainingAccounts(
+          supporters.flatMap(({ voter, vote }) => [
+            { pubkey: vote, isSigner: false, isWritable: false },
+            { pubkey: voter.publicKey, isSigner: true, isWritable: false },
+          ])
+        )
+        .signers([creator, ...cosigners])
+        .rpc();
+    }
+
+    it("extends once enough voters co-sign, up to the policy's limit", async () => {
+      const [id, pda] = await create(50, { deadlineExtension: { maxExtensions: 1, supportBps: 6000 } });
+      for (const voter of [voter1, voter2]) {
+        await program.methods
+          .voteFast(id, true, Array.from(Buffer.alloc(32, 50)), 0)
+          .accounts({
+            fastAction: pda,
+            fastVote: deriveFastVotePDA(pda, voter.publicKey)[0],
+            voter: voter.publicKey,
+            agent: voter.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([voter])
+          .rpc();
+      }
+      const before = await program.account.fastAction.fetch(pda);
+
+      try {
+        await extend(pda, 50, [voter1]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ExtensionNotSupported");
+      }
+
+      await extend(pda, 50, [voter1, voter2]);
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.deadlineSlot.toNumber()).to.equal(before.deadlineSlot.toNumber() + 50);
+      expect(action.extensions).to.equal(1);
+      expect(action.extendedSlots.toNumber()).to.equal(50);
+
+      try {
+        await extend(pda, 10, [voter1, voter2]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ExtensionsExhausted");
+      }
+    });
+
+    it("keeps a deadline without a policy fixed", async () => {
+      const [, pda] = await create(51);
+      try {
+        await extend(pda, 10, []);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ExtensionsExhausted");
+      }
+    });
+  });
+
+  describe("vote_compact", () => {
+    async function create(fill: number, optionCount = 0): Promise<PublicKey> {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, fill)), 50, { optionCount }))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      return pda;
+    }
+
+    function voteCompact(pda: PublicKey, voter: Keypair, ballot: number, commitment: number[] | null) {
+      return program.methods
+        .voteCompact(ballot, 0, commitment)
+        .accounts({
+          fastAction: pda,
+          fastVote: deriveFastVotePDA(pda, voter.publicKey)[0],
+          voter: voter.publicKey,
+          agent: voter.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter])
+        .rpc();
+    }
+
+    it("has the only discriminator starting with its byte", () => {
+      const compact = program.idl.instructions.find((ix) => ix.name === "voteCompact")!;
+      expect(compact.discriminator).to.deep.equal([1]);
+      const clashing = program.idl.instructions.filter((ix) => ix !== compact && ix.discriminator[0] === 1);
+      expect(clashing).to.be.empty;
+    });
+
+    it("records a for/against ballot with a truncated or absent commitment", async () => {
+      const pda = await create(96);
+      await voteCompact(pda, voter1, 0b1, Array.from(Buffer.alloc(16, 96)));
+      await voteCompact(pda, voter2, 0b0, null);
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.votesFor).to.equal(1);
+      expect(action.votesAgainst).to.equal(1);
+      const first = await program.account.fastVote.fetch(deriveFastVotePDA(pda, voter1.publicKey)[0]);
+      expect(first.voterCommitment).to.deep.equal([...Array(16).fill(96), ...Array(16).fill(0)]);
+      const second = await program.account.fastVote.fetch(deriveFastVotePDA(pda, voter2.publicKey)[0]);
+      expect(second.voteValue).to.equal(false);
+      expect(second.voterCommitment).to.deep.equal(Array.from(voter2.publicKey.toBytes()));
+    });
+
+    it("puts an option ballot's weight on its option", async () => {
+      const pda = await create(97, 3);
+      await voteCompact(pda, voter1, (2 + 1) << 1, null);
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.voteCount).to.equal(1);
+      expect(action.optionWeights.slice(0, 3).map((w: anchor.BN) => w.toNumber())).to.deep.equal([0, 0, 1]);
+    });
+
+    it("rejects malformed ballots", async () => {
+      const pda = await create(98, 2);
+      for (const ballot of [1 << 5, ((0 + 1) << 1) | 1, (2 + 1) << 1]) {
+        try {
+          await voteCompact(pda, voter1, ballot, null);
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.match(/InvalidCompactBallot|InvalidOptionWeights/);
+        }
+      }
+    });
+  });
+
+  describe("migrate_action", () => {
+    it("rejects an action already at the current version", async () => {
+      const migrateId = await nextActionId();
+      const [pda] = deriveFastActionPDA(migrateId);
+
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 21)), 50))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      try {
+        await program.methods
+          .migrateAction(migrateId)
+          .accounts({
+            fastAction: pda,
+            payer: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("AlreadyMigrated");
+      }
+    });
+  });
+
+  describe("action log", () => {
+    it("records threshold crossings and the result", async () => {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 87)), 50))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      expect((await program.account.fastAction.fetch(pda)).log.appended).to.equal(0);
+
+      await program.methods
+        .voteFast(id, true, Array.from(Buffer.alloc(32, 87)), 0)
+        .accounts({
+          fastAction: pda,
+          voter: voter1.publicKey,
+          agent: voter1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter1])
+        .rpc();
+      await program.methods
+        .cancelAction(id)
+        .accounts({ fastAction: pda, authority: creator.publicKey })
+        .signers([creator])
+        .rpc();
+
+      const { log } = await program.account.fastAction.fetch(pda);
+      expect(log.appended).to.equal(2);
+      expect(log.entries[0].tag).to.have.property("thresholdCrossed");
+      expect(log.entries[0].payload.toNumber()).to.equal(100);
+      expect(log.entries[1].tag).to.have.property("resultRecorded");
+      expect(log.entries[1].slot.toNumber()).to.be.at.least(log.entries[0].slot.toNumber());
+      expect(log.entries[2].tag).to.have.property("empty");
+    });
+  });
+
+  describe("metrics", () => {
+    const [metricsPDA] = PublicKey.findProgramAddressSync([Buffer.from("metrics"), Buffer.from([0])], program.programId);
+
+    it("only lets the config admin create a layer's metrics", async () => {
+      try {
+        await program.methods
+          .initializeMetrics({ base: {} })
+          .accounts({ metrics: metricsPDA, admin: voter1.publicKey, systemProgram: SystemProgram.programId })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+    });
+
+    it("counts creations and cancellations passed the base layer's metrics", async () => {
+      await program.methods
+        .initializeMetrics({ base: {} })
+        .accounts({ metrics: metricsPDA, admin: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+        .rpc();
+
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 37)), 50))
+        .accounts({
+          fastAction: pda,
+          creator: creator.publicKey,
+          metrics: metricsPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+      await program.methods
+        .cancelAction(id)
+        .accounts({ fastAction: pda, authority: creator.publicKey, metrics: metricsPDA })
+        .signers([creator])
+        .rpc();
+
+      const metrics = await program.account.metrics.fetch(metricsPDA);
+      expect(metrics.actionsCreated.toNumber()).to.equal(1);
+      expect(metrics.actionsCancelled.toNumber()).to.equal(1);
+      expect(metrics.votes.toNumber()).to.equal(0);
+      expect(metrics.lastActivitySlot.toNumber()).to.be.greaterThan(0);
+    });
+  });
+
+  describe("epoch stats", () => {
+    it("creates a hive's stats for the current epoch", async () => {
+      const hive = Keypair.generate().publicKey;
+      const { epoch } = await provider.connection.getEpochInfo();
+      const epochBytes = new anchor.BN(epoch).toArrayLike(Buffer, "le", 8);
+      const [stats] = PublicKey.findProgramAddressSync(
+        [Buffer.from("epoch_stats"), hive.toBuffer(), epochBytes],
+        program.programId
+      );
+      await program.methods
+        .initializeEpochStats(hive, new anchor.BN(epoch))
+        .accounts({ epochStats: stats, payer: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+
+      const account = await program.account.epochStats.fetch(stats);
+      expect(account.hive.toBase58()).to.equal(hive.toBase58());
+      expect(account.epoch.toNumber()).to.equal(epoch);
+      expect(account.finalized).to.equal(0);
+      expect(account.kinds).to.deep.equal([0, 0, 0, 0, 0, 0, 0]);
+      expect((await provider.connection.getAccountInfo(stats))!.data.length).to.equal(121);
+    });
+  });
+
+  describe("insurance pools", () => {
+    const hive = Keypair.generate();
+    const [hivePDA] = deriveHivePDA(hive.publicKey);
+    const [poolPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("insurance_pool"), hive.publicKey.toBuffer()],
+      program.programId
+    );
+
+    function initialize(authority: Keypair, feeBps: number) {
+      return program.methods
+        .initializeInsurancePool(feeBps)
+        .accounts({
+          hiveAccount: hivePDA,
+          insurancePool: poolPDA,
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+    }
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(hive.publicKey, LAMPORTS_PER_SOL)
+      );
+      await program.methods
+        .createHive(Array.from(Buffer.alloc(32, 103)), hive.publicKey)
+        .accounts({
+          hiveAccount: hivePDA,
+          hive: hive.publicKey,
+          payer: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([hive, creator])
+        .rpc();
+    });
+
+    it("only the hive authority may create the pool", async () => {
+      try {
+        await initialize(voter1, 2_500);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+    });
+
+    it("caps the fee cut at 10000 bps", async () => {
+      try {
+        await initialize(hive, 10_001);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidInsuranceFee");
+      }
+    });
+
+    it("takes deposits from anyone", async () => {
+      await initialize(hive, 2_500);
+      const before = await provider.connection.getBalance(poolPDA);
+      await program.methods
+        .fundInsurance(new anchor.BN(10_000))
+        .accounts({ insurancePool: poolPDA, funder: voter2.publicKey, systemProgram: SystemProgram.programId })
+        .signers([voter2])
+        .rpc();
+
+      expect(await provider.connection.getBalance(poolPDA)).to.equal(before + 10_000);
+      const pool = await program.account.insurancePool.fetch(poolPDA);
+      expect(pool.hive.toBase58()).to.equal(hive.publicKey.toBase58());
+      expect(pool.feeBps).to.equal(2_500);
+      expect(pool.totalReceived.toNumber()).to.equal(10_000);
+    });
+  });
+
+  describe("member activity", () => {
+    const hive = Keypair.generate();
+    const [rolesPDA] = deriveRolesPDA(hive.publicKey);
+
+    function activityPDA(member: PublicKey) {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("member_activity"), hive.publicKey.toBuffer(), member.toBuffer()],
+        program.programId
+      )[0];
+    }
+
+    before(async () => {
+      await program.methods
+        .initializeRoles()
+        .accounts({ roles: rolesPDA, hive: hive.publicKey, payer: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([hive, creator])
+        .rpc();
+      await program.methods
+        .grantRole(voter1.publicKey, { creator: {} })
+        .accounts({ roles: rolesPDA, admin: hive.publicKey })
+        .signers([hive])
+        .rpc();
+    });
+
+    it("only tracks members", async () => {
+      try {
+        await program.methods
+          .trackMemberActivity(voter2.publicKey)
+          .accounts({
+            roles: rolesPDA,
+            memberActivity: activityPDA(voter2.publicKey),
+            payer: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("NotHiveMember");
+      }
+    });
+
+    it("records a heartbeat", async () => {
+      await program.methods
+        .memberHeartbeat()
+        .accounts({
+          roles: rolesPDA,
+          memberActivity: activityPDA(voter1.publicKey),
+          member: voter1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter1])
+        .rpc();
+
+      const { epoch } = await provider.connection.getEpochInfo();
+      const activity = await program.account.memberActivity.fetch(activityPDA(voter1.publicKey));
+      expect(activity.member.toBase58()).to.equal(voter1.publicKey.toBase58());
+      expect(activity.lastActiveEpoch.toNumber()).to.equal(epoch);
+      expect((await provider.connection.getAccountInfo(activityPDA(voter1.publicKey)))!.data.length).to.equal(81);
+    });
+
+    it("keeps a recently active member in the electorate", async () => {
+      try {
+        await program.methods
+          .markInactive()
+          .accounts({ roles: rolesPDA, memberActivity: activityPDA(voter1.publicKey) })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("MemberStillActive");
+      }
+      const roles = await program.account.roles.fetch(rolesPDA);
+      expect(roles.members[0].roles & 0x80).to.equal(0);
+    });
+  });
+
+  describe("result attestors", () => {
+    const hive = Keypair.generate();
+    const [rolesPDA] = deriveRolesPDA(hive.publicKey);
+    const [attestorsPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("result_attestors"), hive.publicKey.toBuffer()],
+      program.programId
+    );
+    const validators = [Keypair.generate().publicKey, Keypair.generate().publicKey, Keypair.generate().publicKey];
+
+    function setAttestors(admin: Keypair, set: PublicKey[], threshold: number) {
+      return program.methods
+        .setResultAttestors(set, threshold)
+        .accounts({
+          resultAttestors: attestorsPDA,
+          roles: rolesPDA,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+    }
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(hive.publicKey, LAMPORTS_PER_SOL)
+      );
+      await program.methods
+        .initializeRoles()
+        .accounts({ roles: rolesPDA, hive: hive.publicKey, payer: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([hive, creator])
+        .rpc();
+    });
+
+    it("only the hive admin may set attestors", async () => {
+      try {
+        await setAttestors(voter1, validators, 2);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("MissingRole");
+      }
+    });
+
+    it("rejects a threshold above the validator count", async () => {
+      try {
+        await setAttestors(hive, validators, 4);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidAttestors");
+      }
+    });
+
+    it("rejects a validator listed twice", async () => {
+      try {
+        await setAttestors(hive, [validators[0], validators[0]], 1);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidAttestors");
+      }
+    });
+
+    it("stores a 2-of-3 set", async () => {
+      await setAttestors(hive, validators, 2);
+
+      const attestors = await program.account.resultAttestors.fetch(attestorsPDA);
+      expect(attestors.hive.toBase58()).to.equal(hive.publicKey.toBase58());
+      expect(attestors.validators.map((v) => v.toBase58())).to.deep.equal(validators.map((v) => v.toBase58()));
+      expect(attestors.threshold).to.equal(2);
+      expect((await provider.connection.getAccountInfo(attestorsPDA))!.data.length).to.equal(366);
+    });
+  });
+
+  describe("vote reasons", () => {
+    let actionId: anchor.BN;
+    let actionPDA: PublicKey;
+    let votePDA: PublicKey;
+    const reason = "Treasury runway covers the spend twice over";
+
+    function reasonHash(text: string): number[] {
+      return Array.from(
+        createHash("sha256").update(Buffer.from("kamiyo_vote_reason")).update(votePDA.toBuffer()).update(text).digest()
+      );
+    }
+
+    function setReason(hash: number[]) {
+      return program.methods
+        .setVoteReason(hash)
+        .accounts({ fastAction: actionPDA, fastVote: votePDA, voter: voter1.publicKey })
+        .signers([voter1])
+        .rpc();
+    }
+
+    before(async () => {
+      actionId = await nextActionId();
+      [actionPDA] = deriveFastActionPDA(actionId);
+      [votePDA] = deriveFastVotePDA(actionPDA, voter1.publicKey);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 109)), 50))
+        .accounts({ fastAction: actionPDA, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      await program.methods
+        .voteFast(actionId, true, Array.from(Buffer.alloc(32, 1)), 0)
+        .accounts({
+          fastAction: actionPDA,
+          fastVote: votePDA,
+          voter: voter1.publicKey,
+          agent: voter1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter1])
+        .rpc();
+    });
+
+    it("commits the reason hash onto the ballot", async () => {
+      await setReason(reasonHash(reason));
+
+      const vote = await program.account.fastVote.fetch(votePDA);
+      expect(Array.from(vote.reasonHash)).to.deep.equal(reasonHash(reason));
+    });
+
+    it("keeps the first hash", async () => {
+      try {
+        await setReason(reasonHash("changed my mind"));
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ReasonAlreadySet");
+      }
+    });
 
-      // Try to delegate with wrong PDA
-      const wrongPda = Keypair.generate().publicKey;
+    it("cannot be revealed before finalization", async () => {
+      try {
+        await program.methods
+          .revealReason(reason)
+          .accounts({ fastAction: actionPDA, fastVote: votePDA })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("ActionNotFinalized");
+      }
+    });
+  });
+
+  describe("voter groups", () => {
+    const groupKey = Keypair.generate();
+    const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+    const [groupPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter_group"), groupKey.publicKey.toBuffer()],
+      program.programId
+    );
+    let actionId: anchor.BN;
+    let actionPDA: PublicKey;
+
+    function setGroup(admin: PublicKey, threshold: number, signers: Keypair[] = []) {
+      return program.methods
+        .setVoterGroup(members.map((m) => m.publicKey), threshold, new anchor.BN(5))
+        .accounts({ groupKey: groupKey.publicKey, admin, systemProgram: SystemProgram.programId })
+        .signers(signers)
+        .rpc();
+    }
+
+    // A FROST aggregate signature verifies like a single key's
+    function signed(signer: Keypair, action: PublicKey) {
+      const message = Buffer.concat([Buffer.from("kamiyo_ballot"), action.toBuffer(), Buffer.from([1])]);
+      return Ed25519Program.createInstructionWithPrivateKey({ privateKey: signer.secretKey, message });
+    }
+
+    function voteAsGroup(signer: Keypair, submitter = voter1, id = actionId, action = actionPDA) {
+      return program.methods
+        .voteFastGroup(id, true, Array.from(Buffer.alloc(32, 2)), 0)
+        .accounts({
+          fastAction: action,
+          fastVote: deriveFastVotePDA(action, groupKey.publicKey)[0],
+          voterGroup: groupPDA,
+          submitter: submitter.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .preInstructions([signed(signer, action)])
+        .signers([submitter])
+        .rpc();
+    }
+
+    before(async () => {
+      actionId = await nextActionId();
+      [actionPDA] = deriveFastActionPDA(actionId);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 110)), 50))
+        .accounts({ fastAction: actionPDA, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+    });
 
+    it("only the config admin may register a group", async () => {
       try {
+        await setGroup(voter1.publicKey, 2, [voter1]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("Unauthorized");
+      }
+    });
+
+    it("rejects a threshold above the member count", async () => {
+      try {
+        await setGroup(provider.wallet.publicKey, 4);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidVoterGroup");
+      }
+    });
+
+    it("registers a 2-of-3 group", async () => {
+      await setGroup(provider.wallet.publicKey, 2);
+
+      const group = await program.account.voterGroup.fetch(groupPDA);
+      expect(group.members.map((m) => m.toBase58())).to.deep.equal(members.map((m) => m.publicKey.toBase58()));
+      expect(group.threshold).to.equal(2);
+      expect(group.weight.toNumber()).to.equal(5);
+      expect((await provider.connection.getAccountInfo(groupPDA))!.data.length).to.equal(574);
+    });
+
+    it("rejects a ballot signed by a single member", async () => {
+      try {
+        await voteAsGroup(members[0]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("MissingBallotSignature");
+      }
+    });
+
+    it("counts a group-signed ballot as one voter", async () => {
+      await voteAsGroup(groupKey);
+
+      const vote = await program.account.fastVote.fetch(deriveFastVotePDA(actionPDA, groupKey.publicKey)[0]);
+      expect(vote.voter.toBase58()).to.equal(groupKey.publicKey.toBase58());
+      const action = await program.account.fastAction.fetch(actionPDA);
+      expect(action.votesFor).to.equal(1);
+    });
+
+    describe("pinned relayers", () => {
+      const relayer = voter2;
+      const member = members[0];
+
+      async function pinned(hash: number): Promise<[anchor.BN, PublicKey]> {
+        const id = await nextActionId();
+        const [pda] = deriveFastActionPDA(id);
         await program.methods
-          .delegateAction(delegateId)
+          .createFastAction(actionParams(Array.from(Buffer.alloc(32, hash)), 50))
+          .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+          .signers([creator])
+          .rpc();
+        await program.methods
+          .setActionRelayers([relayer.publicKey], new anchor.BN(3))
+          .accounts({ fastAction: pda, authority: creator.publicKey })
+          .signers([creator])
+          .rpc();
+        return [id, pda];
+      }
+
+      function requestSelfSubmit(action: PublicKey) {
+        return program.methods
+          .requestSelfSubmit()
+          .accounts({ fastAction: action, voterGroup: groupPDA, member: member.publicKey })
+          .signers([member])
+          .rpc();
+      }
+
+      async function waitSlots(slots: number) {
+        const target = (await provider.connection.getSlot()) + slots;
+        while ((await provider.connection.getSlot()) < target) {
+          await new Promise((resolve) => setTimeout(resolve, 200));
+        }
+      }
+
+      async function expectError(promise: Promise<unknown>, error: string) {
+        try {
+          await promise;
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include(error);
+        }
+      }
+
+      before(async () => {
+        const sig = await provider.connection.requestAirdrop(member.publicKey, LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(sig);
+      });
+
+      it("lets only the action authority pin relayers", async () => {
+        const [, pda] = await pinned(146);
+        await expectError(
+          program.methods
+            .setActionRelayers([voter1.publicKey], new anchor.BN(3))
+            .accounts({ fastAction: pda, authority: voter1.publicKey })
+            .signers([voter1])
+            .rpc(),
+          "Unauthorized"
+        );
+
+        const relayers = await program.account.actionRelayers.fetch(
+          PublicKey.findProgramAddressSync([Buffer.from("action_relayers"), pda.toBuffer()], program.programId)[0]
+        );
+        expect(relayers.relayerCount).to.equal(1);
+        expect(relayers.relayers[0].toBase58()).to.equal(relayer.publicKey.toBase58());
+        expect(relayers.fallbackSlots.toNumber()).to.equal(3);
+      });
+
+      it("takes group ballots from a pinned relayer", async () => {
+        const [id, pda] = await pinned(147);
+        await voteAsGroup(groupKey, relayer, id, pda);
+
+        expect((await program.account.fastAction.fetch(pda)).votesFor).to.equal(1);
+      });
+
+      it("turns away other submitters, members included, until the fallback is due", async () => {
+        const [id, pda] = await pinned(148);
+        await expectError(voteAsGroup(groupKey, voter1, id, pda), "RelayerNotAllowed");
+        await expectError(voteAsGroup(groupKey, member, id, pda), "SelfSubmitNotDue");
+
+        await requestSelfSubmit(pda);
+        await expectError(voteAsGroup(groupKey, member, id, pda), "SelfSubmitNotDue");
+        await expectError(voteAsGroup(groupKey, voter1, id, pda), "RelayerNotAllowed");
+      });
+
+      it("lets a member submit once the relayer has sat on the ballot for the fallback slots", async () => {
+        const [id, pda] = await pinned(149);
+        await requestSelfSubmit(pda);
+        await waitSlots(3);
+        await voteAsGroup(groupKey, member, id, pda);
+
+        const action = await program.account.fastAction.fetch(pda);
+        expect(action.votesFor).to.equal(1);
+      });
+
+      it("stops pinning once the action has ballots", async () => {
+        const [id, pda] = await pinned(150);
+        await voteAsGroup(groupKey, relayer, id, pda);
+        await expectError(
+          program.methods
+            .setActionRelayers([voter1.publicKey], new anchor.BN(3))
+            .accounts({ fastAction: pda, authority: creator.publicKey })
+            .signers([creator])
+            .rpc(),
+          "ActionHasVotes"
+        );
+      });
+    });
+  });
+
+  describe("foreign tallies", () => {
+    const hive = Keypair.generate();
+    const [rolesPDA] = deriveRolesPDA(hive.publicKey);
+    // Ethereum, and an EVM contract address left-padded to 32 bytes
+    const chain = 2;
+    const emitter = Array.from(Buffer.concat([Buffer.alloc(12), Buffer.alloc(20, 0xab)]));
+    const chainLe = Buffer.alloc(2);
+    chainLe.writeUInt16LE(chain);
+    const [foreignPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("foreign_hive"), hive.publicKey.toBuffer(), chainLe, Buffer.from(emitter)],
+      program.programId
+    );
+
+    function register(admin: Keypair, maxWeight: number) {
+      return program.methods
+        .registerForeignHive(chain, emitter, new anchor.BN(maxWeight))
+        .accounts({ foreignHive: foreignPDA, roles: rolesPDA, admin: admin.publicKey, systemProgram: SystemProgram.programId })
+        .signers([admin])
+        .rpc();
+    }
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(hive.publicKey, LAMPORTS_PER_SOL)
+      );
+      await program.methods
+        .initializeRoles()
+        .accounts({ roles: rolesPDA, hive: hive.publicKey, payer: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([hive, creator])
+        .rpc();
+    });
+
+    it("only the hive admin may register a foreign hive", async () => {
+      try {
+        await register(voter1, 1_000);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("MissingRole");
+      }
+    });
+
+    it("needs a weight cap", async () => {
+      try {
+        await register(hive, 0);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidForeignHive");
+      }
+    });
+
+    it("registers the EVM contract", async () => {
+      await register(hive, 1_000);
+
+      const foreign = await program.account.foreignHive.fetch(foreignPDA);
+      expect(foreign.hive.toBase58()).to.equal(hive.publicKey.toBase58());
+      expect(foreign.chain).to.equal(chain);
+      expect(Array.from(foreign.emitter)).to.deep.equal(emitter);
+      expect((await provider.connection.getAccountInfo(foreignPDA))!.data.length).to.equal(83);
+    });
+
+    it("rejects tallies for actions outside the hive", async () => {
+      const actionId = await nextActionId();
+      const [actionPDA] = deriveFastActionPDA(actionId);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 111)), 50))
+        .accounts({ fastAction: actionPDA, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      try {
+        await program.methods
+          .submitForeignTally()
           .accounts({
-            pda: wrongPda,
-            payer: creator.publicKey,
+            fastAction: actionPDA,
+            fastVote: deriveFastVotePDA(actionPDA, foreignPDA)[0],
+            foreignHive: foreignPDA,
+            postedVaa: Keypair.generate().publicKey,
+            payer: provider.wallet.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidForeignTally");
+      }
+    });
+  });
+
+  describe("ballot caps", () => {
+    const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+
+    const setCeiling = (max: number) =>
+      program.methods
+        .updateConfig({
+          admin: null,
+          weightMint: null,
+          weightExtensions: null,
+          transferHookProgram: null,
+          maxActionsPerEpoch: null,
+          guardian: null,
+          voteCreditsPerEpoch: null,
+          minAgentBond: null,
+          whistleblowerBps: null,
+          snapshotter: null,
+          maxWindowSlots: null,
+          stakeLockProgram: null,
+          maxLowPriorityActions: null,
+          maxVotesPerAction: max,
+          identityRamp: null,
+          feeRebate: null,
+          maxActionsPerValidator: null,
+        })
+        .accounts({ config: configPDA, admin: provider.wallet.publicKey })
+        .rpc();
+
+    async function create(fill: number) {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, fill)), 50))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      return pda;
+    }
+
+    it("gives actions without an electorate the config's cap", async () => {
+      const action = await program.account.fastAction.fetch(await create(112));
+      expect(action.maxVotes).to.equal(10_000);
+    });
+
+    it("raises the cap past MAX_VOTES_PER_ACTION when the config does", async () => {
+      await setCeiling(50_000);
+      try {
+        const action = await program.account.fastAction.fetch(await create(113));
+        expect(action.maxVotes).to.equal(50_000);
+      } finally {
+        await setCeiling(0);
+      }
+    });
+
+    it("refuses a quorum above the config's cap", async () => {
+      await setCeiling(1);
+      try {
+        await create(114);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidQuorum");
+      } finally {
+        await setCeiling(0);
+      }
+    });
+  });
+
+  describe("validator admission", () => {
+    const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+
+    const setCap = (max: number) =>
+      program.methods
+        .updateConfig({
+          admin: null,
+          weightMint: null,
+          weightExtensions: null,
+          transferHookProgram: null,
+          maxActionsPerEpoch: null,
+          guardian: null,
+          voteCreditsPerEpoch: null,
+          minAgentBond: null,
+          whistleblowerBps: null,
+          snapshotter: null,
+          maxWindowSlots: null,
+          stakeLockProgram: null,
+          maxLowPriorityActions: null,
+          maxVotesPerAction: null,
+          identityRamp: null,
+          feeRebate: null,
+          maxActionsPerValidator: max,
+        })
+        .accounts({ config: configPDA, admin: provider.wallet.publicKey })
+        .rpc();
+
+    it("records the config's cap on actions per validator", async () => {
+      await setCap(8);
+      try {
+        const config = await program.account.globalConfig.fetch(configPDA);
+        expect(config.maxActionsPerValidator).to.equal(8);
+      } finally {
+        await setCap(0);
+      }
+    });
+
+    it("starts a validator with no actions counted", async () => {
+      const validator = Keypair.generate();
+      const [validatorRecord] = deriveValidatorPDA(validator.publicKey);
+      await program.methods
+        .registerValidator(VALIDATOR_BOND)
+        .accounts({
+          validatorRecord,
+          validator: validator.publicKey,
+          payer: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([validator])
+        .rpc();
+
+      const record = await program.account.validatorRecord.fetch(validatorRecord);
+      expect(record.activeActions).to.equal(0);
+
+      try {
+        await program.methods
+          .migrateValidatorRecord()
+          .accounts({
+            validatorRecord,
+            validator: validator.publicKey,
+            payer: provider.wallet.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("AlreadyMigrated");
+      }
+    });
+  });
+
+  describe("ballot privacy", () => {
+    async function create(fill: number, overrides: Record<string, any>): Promise<[anchor.BN, PublicKey]> {
+      const id = await nextActionId();
+      const [pda] = deriveFastActionPDA(id);
+      await program.methods
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, fill)), 50, overrides))
+        .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+      return [id, pda];
+    }
+
+    function commitment(action: PublicKey, voter: PublicKey, voteValue: boolean, salt: Buffer): number[] {
+      return Array.from(
+        createHash("sha256")
+          .update(Buffer.from("ballot_commitment"))
+          .update(action.toBuffer())
+          .update(voter.toBuffer())
+          .update(Buffer.from([voteValue ? 1 : 0]))
+          .update(salt)
+          .digest()
+      );
+    }
+
+    it("takes private levels only on headcount for/against actions", async () => {
+      for (const overrides of [
+        { privacy: { commitReveal: {} }, voteMode: { tokenWeighted: {} } },
+        { privacy: { encrypted: {} }, optionCount: 3 },
+        { privacy: { commitReveal: {} }, audited: true },
+        { privacy: { anonymousZk: {} }, exclusions: [voter2.publicKey] },
+      ]) {
+        try {
+          await create(120, overrides);
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include("InvalidPrivacyLevel");
+        }
+      }
+    });
+
+    it("counts a committed ballot toward turnout until it is revealed", async () => {
+      const [id, pda] = await create(121, { privacy: { commitReveal: {} } });
+      const salt = Buffer.alloc(32, 7);
+      const [votePda] = deriveFastVotePDA(pda, voter1.publicKey);
+      await program.methods
+        .voteFast(id, false, commitment(pda, voter1.publicKey, true, salt), 0)
+        .accounts({
+          fastAction: pda,
+          fastVote: votePda,
+          voter: voter1.publicKey,
+          agent: voter1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter1])
+        .rpc();
+
+      const action = await program.account.fastAction.fetch(pda);
+      expect(action.voteCount).to.equal(1);
+      expect(action.votesFor + action.votesAgainst).to.equal(0);
+      const ballot = await program.account.fastVote.fetch(votePda);
+      expect(ballot.weight.toNumber()).to.equal(0);
+
+      try {
+        await program.methods
+          .revealBallot(true, Array.from(salt))
+          .accounts({ fastAction: pda, fastVote: votePda })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("VotingNotEnded");
+      }
+    });
+
+    it("refuses vote_fast on anonymous actions", async () => {
+      const [id, pda] = await create(122, { privacy: { anonymousZk: {} } });
+      const [votePda] = deriveFastVotePDA(pda, voter1.publicKey);
+      try {
+        await program.methods
+          .voteFast(id, true, Array.from(Buffer.alloc(32, 1)), 0)
+          .accounts({
+            fastAction: pda,
+            fastVote: votePda,
+            voter: voter1.publicKey,
+            agent: voter1.publicKey,
+            systemProgram: SystemProgram.programId,
           })
+          .signers([voter1])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("PrivacyLevelMismatch");
+      }
+    });
+
+    it("takes anonymous ballot verifiers only from the admin", async () => {
+      const verifier = SystemProgram.programId;
+      const [record] = PublicKey.findProgramAddressSync(
+        [Buffer.from("ballot_verifier"), verifier.toBuffer()],
+        program.programId
+      );
+      try {
+        await program.methods
+          .setBallotVerifier(true)
+          .accounts({ verifier, admin: creator.publicKey })
           .signers([creator])
           .rpc();
         expect.fail("Should have thrown");
       } catch (err: any) {
-        expect(err.message).to.include("InvalidPda");
+        expect(err.message).to.include("Unauthorized");
+      }
+      try {
+        await program.methods
+          .setBallotVerifier(true)
+          .accounts({ verifier: Keypair.generate().publicKey, admin: provider.wallet.publicKey })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("InvalidBallotVerifier");
+      }
+
+      await program.methods.setBallotVerifier(true).accounts({ verifier, admin: provider.wallet.publicKey }).rpc();
+      expect((await program.account.ballotVerifier.fetch(record)).allowed).to.equal(true);
+      await program.methods.setBallotVerifier(false).accounts({ verifier, admin: provider.wallet.publicKey }).rpc();
+      expect((await program.account.ballotVerifier.fetch(record)).allowed).to.equal(false);
+    });
+
+    it("proves anonymous ballots only against a Hive account's member root", async () => {
+      const [, pda] = await create(139, { privacy: { anonymousZk: {} } });
+      try {
+        await program.methods
+          .enableAnonymousBallots()
+          .accounts({
+            fastAction: pda,
+            hiveAccount: deriveHivePDA(PublicKey.default)[0],
+            verifier: SystemProgram.programId,
+            authority: creator.publicKey,
+          })
+          .signers([creator])
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("AccountNotInitialized");
+      }
+    });
+  });
+
+  describe("hive onboarding", () => {
+    function init(hive: Keypair, members: any[]) {
+      const margins = Array(16).fill(0);
+      margins[0] = 5;
+      return program.methods
+        .initializeHive({
+          nameHash: Array.from(Buffer.alloc(32, 136)),
+          authority: creator.publicKey,
+          memberRoot: Array.from(Buffer.alloc(32, 137)),
+          overrides: { maxActionsPerEpoch: 0, defaultQuorum: 2 },
+          members,
+          cancellationPolicy: { beforeFirstVote: {} },
+          approvalRounding: { halfEven: {} },
+          contestMargins: margins,
+          insuranceFeeBps: 250,
+        })
+        .accounts({
+          hiveAccount: deriveHivePDA(hive.publicKey)[0],
+          roles: deriveRolesPDA(hive.publicKey)[0],
+          hiveConfig: deriveHiveConfigPDA(hive.publicKey)[0],
+          categoryConfig: deriveCategoryConfigPDA(hive.publicKey)[0],
+          insurancePool: PublicKey.findProgramAddressSync(
+            [Buffer.from("insurance_pool"), hive.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          hive: hive.publicKey,
+          payer: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([hive, creator]);
+    }
+
+    it("opens every account of a new hive in one instruction", async () => {
+      const hive = Keypair.generate();
+      await init(hive, [{ member: creator.publicKey, roles: 0b0101 }]).rpc();
+
+      const hiveAccount = await program.account.hive.fetch(deriveHivePDA(hive.publicKey)[0]);
+      expect(hiveAccount.authority.toBase58()).to.equal(creator.publicKey.toBase58());
+      expect(hiveAccount.overrides.defaultQuorum).to.equal(2);
+      const roles = await program.account.roles.fetch(deriveRolesPDA(hive.publicKey)[0]);
+      expect(roles.members.map((m: any) => [m.member.toBase58(), m.roles])).to.deep.equal([
+        [hive.publicKey.toBase58(), 0b1000],
+        [creator.publicKey.toBase58(), 0b0101],
+      ]);
+      const config = await program.account.hiveConfig.fetch(deriveHiveConfigPDA(hive.publicKey)[0]);
+      expect(config.cancellationPolicy).to.deep.equal({ beforeFirstVote: {} });
+      expect(config.approvalRounding).to.deep.equal({ halfEven: {} });
+      const categories = await program.account.categoryConfig.fetch(deriveCategoryConfigPDA(hive.publicKey)[0]);
+      expect(categories.contestMargins[0]).to.equal(5);
+      const [poolPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("insurance_pool"), hive.publicKey.toBuffer()],
+        program.programId
+      );
+      const pool = await program.account.insurancePool.fetch(poolPDA);
+      expect(pool.feeBps).to.equal(250);
+    });
+
+    it("rejects members repeating the hive key or holding no role", async () => {
+      const hive = Keypair.generate();
+      for (const members of [
+        [{ member: hive.publicKey, roles: 0b0001 }],
+        [{ member: voter1.publicKey, roles: 0 }],
+        [
+          { member: voter1.publicKey, roles: 0b0001 },
+          { member: voter1.publicKey, roles: 0b0010 },
+        ],
+      ]) {
+        try {
+          await init(hive, members).rpc();
+          expect.fail("Should have thrown");
+        } catch (err: any) {
+          expect(err.message).to.include("InvalidHiveMembers");
+        }
       }
     });
   });
 
   describe("account sizes", () => {
     it("FastAction size is correct", async () => {
-      const testId = new anchor.BN(Date.now() + 500);
+      const testId = await nextActionId();
       const [pda] = deriveFastActionPDA(testId);
 
       await program.methods
-        .createFastAction(testId, Array.from(Buffer.alloc(32, 10)), 50, Array.from(Buffer.alloc(32)))
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 10)), 50))
         .accounts({
           fastAction: pda,
           creator: creator.publicKey,
@@ -554,15 +6965,15 @@ describe("kamiyo-fast-voting", () => {
 
       const info = await provider.connection.getAccountInfo(pda);
       expect(info).to.not.be.null;
-      expect(info!.data.length).to.equal(145);
+      expect(info!.data.length).to.equal(1361);
     });
 
     it("FastVote size is correct", async () => {
-      const testId = new anchor.BN(Date.now() + 600);
+      const testId = await nextActionId();
       const [actionPda] = deriveFastActionPDA(testId);
 
       await program.methods
-        .createFastAction(testId, Array.from(Buffer.alloc(32, 11)), 50, Array.from(Buffer.alloc(32)))
+        .createFastAction(actionParams(Array.from(Buffer.alloc(32, 11)), 50))
         .accounts({
           fastAction: actionPda,
           creator: creator.publicKey,
@@ -573,11 +6984,12 @@ describe("kamiyo-fast-voting", () => {
 
       const [votePda] = deriveFastVotePDA(actionPda, creator.publicKey);
       await program.methods
-        .voteFast(testId, true, Array.from(Buffer.alloc(32, 1)))
+        .voteFast(testId, true, Array.from(Buffer.alloc(32, 1)), 0)
         .accounts({
           fastAction: actionPda,
           fastVote: votePda,
           voter: creator.publicKey,
+          agent: creator.publicKey,
           systemProgram: SystemProgram.programId,
         })
         .signers([creator])
@@ -585,7 +6997,7 @@ describe("kamiyo-fast-voting", () => {
 
       const info = await provider.connection.getAccountInfo(votePda);
       expect(info).to.not.be.null;
-      expect(info!.data.length).to.equal(114);
+      expect(info!.data.length).to.equal(154);
     });
   });
 });
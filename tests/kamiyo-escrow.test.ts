@@ -0,0 +1,167 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { KamiyoEscrow } from "../target/types/kamiyo_escrow";
+import { KamiyoFastVoting } from "../target/types/kamiyo_fast_voting";
+
+describe("kamiyo-escrow", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const escrowProgram = anchor.workspace.KamiyoEscrow as Program<KamiyoEscrow>;
+  const voting = anchor.workspace.KamiyoFastVoting as Program<KamiyoFastVoting>;
+
+  const DEPOSIT_A = new anchor.BN(LAMPORTS_PER_SOL / 4);
+  const DEPOSIT_B = new anchor.BN(LAMPORTS_PER_SOL / 2);
+
+  let creator: Keypair;
+  let partyA: Keypair;
+  let partyB: Keypair;
+
+  function deriveEscrowPDA(action: PublicKey, a: PublicKey, b: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), action.toBuffer(), a.toBuffer(), b.toBuffer()],
+      escrowProgram.programId
+    );
+  }
+
+  async function ensureConfig() {
+    const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], voting.programId);
+    if (await provider.connection.getAccountInfo(configPDA)) return;
+
+    const [programData] = PublicKey.findProgramAddressSync(
+      [voting.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+    await voting.methods
+      .initializeConfig(PublicKey.default, 0, PublicKey.default)
+      .accounts({
+        config: configPDA,
+        admin: provider.wallet.publicKey,
+        fastVotingProgram: voting.programId,
+        programData,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  async function createAction(fill: number): Promise<PublicKey> {
+    const [counterPDA] = PublicKey.findProgramAddressSync([Buffer.from("action_counter")], voting.programId);
+    const counter = await voting.account.actionCounter.fetchNullable(counterPDA);
+    const id = counter ? counter.nextId : new anchor.BN(0);
+    const [pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fast_action"), id.toArrayLike(Buffer, "le", 8)],
+      voting.programId
+    );
+    await voting.methods
+      .createFastAction({
+        actionHash: Array.from(Buffer.alloc(32, fill)),
+        threshold: 60,
+        descriptionHash: Array.from(Buffer.alloc(32)),
+        voteMode: { headcount: {} },
+        tierThresholds: [0, 0, 0],
+        dependencies: [],
+        adaptiveQuorum: null,
+        minTurnoutBps: 0,
+        optionCount: 0,
+        ranked: false,
+        marketGate: null,
+        kind: { standard: {} },
+        weightCap: null,
+        classLimits: [],
+        audited: false,
+        earlyVoteBonus: false,
+        exclusions: [],
+        deadlineExtension: null,
+        identityWeighted: false,
+        memberCollection: null,
+        lockStake: false,
+        priority: 1,
+      })
+      .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+      .signers([creator])
+      .rpc();
+    return pda;
+  }
+
+  function open(action: PublicKey, a: Keypair, b: Keypair) {
+    const [escrow] = deriveEscrowPDA(action, a.publicKey, b.publicKey);
+    return escrowProgram.methods
+      .openEscrow(DEPOSIT_A, DEPOSIT_B)
+      .accounts({
+        escrow,
+        fastAction: action,
+        partyA: a.publicKey,
+        partyB: b.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([a, b])
+      .rpc();
+  }
+
+  before(async () => {
+    await ensureConfig();
+
+    creator = Keypair.generate();
+    partyA = Keypair.generate();
+    partyB = Keypair.generate();
+    const sigs = await Promise.all(
+      [creator, partyA, partyB].map((k) => provider.connection.requestAirdrop(k.publicKey, 2 * LAMPORTS_PER_SOL))
+    );
+    await Promise.all(sigs.map((sig) => provider.connection.confirmTransaction(sig)));
+  });
+
+  it("locks both deposits against an undecided action", async () => {
+    const action = await createAction(103);
+    await open(action, partyA, partyB);
+
+    const [pda] = deriveEscrowPDA(action, partyA.publicKey, partyB.publicKey);
+    const escrow = await escrowProgram.account.escrow.fetch(pda);
+    expect(escrow.fastAction.toBase58()).to.equal(action.toBase58());
+    expect(escrow.partyA.toBase58()).to.equal(partyA.publicKey.toBase58());
+    expect(escrow.partyB.toBase58()).to.equal(partyB.publicKey.toBase58());
+    expect(escrow.amountA.toString()).to.equal(DEPOSIT_A.toString());
+    expect(escrow.amountB.toString()).to.equal(DEPOSIT_B.toString());
+
+    const info = await provider.connection.getAccountInfo(pda);
+    expect(info!.data.length).to.equal(129);
+    const rent = await provider.connection.getMinimumBalanceForRentExemption(129);
+    expect(info!.lamports).to.equal(rent + DEPOSIT_A.toNumber() + DEPOSIT_B.toNumber());
+  });
+
+  it("will not settle before the action is final", async () => {
+    const action = await createAction(104);
+    await open(action, partyA, partyB);
+
+    const [escrow] = deriveEscrowPDA(action, partyA.publicKey, partyB.publicKey);
+    try {
+      await escrowProgram.methods
+        .settleEscrow()
+        .accounts({ escrow, fastAction: action, partyA: partyA.publicKey, partyB: partyB.publicKey })
+        .rpc();
+      expect.fail("Should have thrown");
+    } catch (err: any) {
+      expect(err.message).to.include("ActionNotFinal");
+    }
+  });
+
+  it("rejects an account that is not a fast voting action", async () => {
+    try {
+      await open(creator.publicKey, partyA, partyB);
+      expect.fail("Should have thrown");
+    } catch (err: any) {
+      expect(err.message).to.include("InvalidAction");
+    }
+  });
+
+  it("needs two distinct parties", async () => {
+    const action = await createAction(105);
+    try {
+      await open(action, partyA, partyA);
+      expect.fail("Should have thrown");
+    } catch (err: any) {
+      expect(err.message).to.include("InvalidParties");
+    }
+  });
+});
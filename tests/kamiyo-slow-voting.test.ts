@@ -0,0 +1,279 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { KamiyoFastVoting } from "../target/types/kamiyo_fast_voting";
+import { KamiyoSlowVoting } from "../target/types/kamiyo_slow_voting";
+
+describe("kamiyo-slow-voting", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const slow = anchor.workspace.KamiyoSlowVoting as Program<KamiyoSlowVoting>;
+  const voting = anchor.workspace.KamiyoFastVoting as Program<KamiyoFastVoting>;
+
+  const SLOTS_PER_DAY = 216_000;
+
+  const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("slow_config")], slow.programId);
+
+  let creator: Keypair;
+  let agent: Keypair;
+  let stranger: Keypair;
+
+  const settings = {
+    windowSlots: new anchor.BN(2 * SLOTS_PER_DAY),
+    timelockSlots: new anchor.BN(SLOTS_PER_DAY),
+    quorum: 1,
+    promotionThreshold: new anchor.BN(LAMPORTS_PER_SOL),
+    promotionMint: PublicKey.default,
+    weightProgram: PublicKey.default,
+  };
+
+  function deriveSlowActionPDA(creator: PublicKey, actionHash: Buffer): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("slow_action"), creator.toBuffer(), actionHash],
+      slow.programId
+    )[0];
+  }
+
+  function deriveBallotPDA(action: PublicKey, voter: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("slow_ballot"), action.toBuffer(), voter.toBuffer()],
+      slow.programId
+    )[0];
+  }
+
+  function deriveAgentPDA(agent: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync([Buffer.from("agent"), agent.toBuffer()], voting.programId)[0];
+  }
+
+  async function ensureConfig() {
+    if (await provider.connection.getAccountInfo(configPDA)) return;
+
+    const [programData] = PublicKey.findProgramAddressSync(
+      [slow.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+    await slow.methods
+      .initializeConfig(settings, Buffer.alloc(0))
+      .accounts({
+        config: configPDA,
+        admin: provider.wallet.publicKey,
+        slowVotingProgram: slow.programId,
+        programData,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  function createSlowAction(fill: number, windowSlots: number, timelockSlots = SLOTS_PER_DAY) {
+    const actionHash = Buffer.alloc(32, fill);
+    return slow.methods
+      .createSlowAction(
+        Array.from(actionHash),
+        60,
+        new anchor.BN(windowSlots),
+        new anchor.BN(timelockSlots),
+        PublicKey.default,
+        Buffer.alloc(0)
+      )
+      .accounts({
+        config: configPDA,
+        slowAction: deriveSlowActionPDA(creator.publicKey, actionHash),
+        creator: creator.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([creator])
+      .rpc();
+  }
+
+  function vote(action: PublicKey, voter: Keypair, value: boolean) {
+    return slow.methods
+      .castSlowVote(value)
+      .accounts({
+        slowAction: action,
+        slowBallot: deriveBallotPDA(action, voter.publicKey),
+        agentRecord: deriveAgentPDA(voter.publicKey),
+        voter: voter.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter])
+      .rpc();
+  }
+
+  before(async () => {
+    await ensureConfig();
+
+    creator = Keypair.generate();
+    agent = Keypair.generate();
+    stranger = Keypair.generate();
+    const sigs = await Promise.all(
+      [creator, agent, stranger].map((k) => provider.connection.requestAirdrop(k.publicKey, 2 * LAMPORTS_PER_SOL))
+    );
+    await Promise.all(sigs.map((sig) => provider.connection.confirmTransaction(sig)));
+
+    await voting.methods
+      .registerAgent(new anchor.BN(10_000_000))
+      .accounts({ agent: agent.publicKey, systemProgram: SystemProgram.programId })
+      .signers([agent])
+      .rpc();
+  });
+
+  it("stores the config", async () => {
+    const config = await slow.account.slowConfig.fetch(configPDA);
+    expect(config.settings.windowSlots.toNumber()).to.be.at.least(2 * SLOTS_PER_DAY);
+    expect(config.settings.timelockSlots.toNumber()).to.be.at.least(SLOTS_PER_DAY);
+    expect((await provider.connection.getAccountInfo(configPDA))!.data.length).to.equal(201);
+  });
+
+  it("rejects a config window under two days", async () => {
+    try {
+      await slow.methods
+        .updateConfig({ ...settings, windowSlots: new anchor.BN(SLOTS_PER_DAY) }, Buffer.alloc(0))
+        .accounts({ config: configPDA, admin: provider.wallet.publicKey })
+        .rpc();
+      expect.fail("Should have thrown");
+    } catch (err: any) {
+      expect(err.message).to.include("InvalidWindow");
+    }
+  });
+
+  it("rejects a slow action with a shorter window than the config's", async () => {
+    try {
+      await createSlowAction(1, SLOTS_PER_DAY);
+      expect.fail("Should have thrown");
+    } catch (err: any) {
+      expect(err.message).to.include("InvalidWindow");
+    }
+  });
+
+  it("counts one ballot per registered agent", async () => {
+    await createSlowAction(2, 2 * SLOTS_PER_DAY);
+    const action = deriveSlowActionPDA(creator.publicKey, Buffer.alloc(32, 2));
+    await vote(action, agent, true);
+
+    const account = await slow.account.slowAction.fetch(action);
+    expect(account.votesFor).to.equal(1);
+    expect(account.weightFor.toNumber()).to.equal(1);
+    expect(account.deadlineSlot.sub(account.createdSlot).toNumber()).to.equal(2 * SLOTS_PER_DAY);
+
+    try {
+      await vote(action, agent, false);
+      expect.fail("Should have thrown");
+    } catch (err: any) {
+      expect(err.message).to.include("already in use");
+    }
+  });
+
+  it("refuses ballots from unregistered agents", async () => {
+    const action = deriveSlowActionPDA(creator.publicKey, Buffer.alloc(32, 2));
+    try {
+      await vote(action, stranger, true);
+      expect.fail("Should have thrown");
+    } catch (err: any) {
+      expect(err.message).to.include("AgentNotRegistered");
+    }
+  });
+
+  it("refuses to tally while the window is open", async () => {
+    const action = deriveSlowActionPDA(creator.publicKey, Buffer.alloc(32, 2));
+    try {
+      await slow.methods.tallySlowAction().accounts({ slowAction: action }).rpc();
+      expect.fail("Should have thrown");
+    } catch (err: any) {
+      expect(err.message).to.include("VotingOpen");
+    }
+  });
+
+  it("only promotes Budget and contested actions", async () => {
+    const [counterPDA] = PublicKey.findProgramAddressSync([Buffer.from("action_counter")], voting.programId);
+    const counter = await voting.account.actionCounter.fetchNullable(counterPDA);
+    const id = counter ? counter.nextId : new anchor.BN(0);
+    const [fastAction] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fast_action"), id.toArrayLike(Buffer, "le", 8)],
+      voting.programId
+    );
+    await voting.methods
+      .createFastAction({
+        actionHash: Array.from(Buffer.alloc(32, 3)),
+        threshold: 60,
+        descriptionHash: Array.from(Buffer.alloc(32)),
+        voteMode: { headcount: {} },
+        tierThresholds: [0, 0, 0],
+        dependencies: [],
+        adaptiveQuorum: null,
+        minTurnoutBps: 0,
+        optionCount: 0,
+        ranked: false,
+        marketGate: null,
+        kind: { standard: {} },
+        weightCap: null,
+        classLimits: [],
+        audited: false,
+        earlyVoteBonus: false,
+        exclusions: [],
+        deadlineExtension: null,
+        identityWeighted: false,
+        memberCollection: null,
+        lockStake: false,
+        priority: 1,
+        tags: [],
+        quorumWeightBps: 0,
+        privacy: { public: {} },
+        thresholdBps: 0,
+      })
+      .accounts({ fastAction, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+      .signers([creator])
+      .rpc();
+
+    const budget = {
+      recipient: creator.publicKey,
+      mint: PublicKey.default,
+      amount: new anchor.BN(2 * LAMPORTS_PER_SOL),
+      startSlot: new anchor.BN(0),
+      endSlot: new anchor.BN(1),
+    };
+    const seeds = (prefix: string) =>
+      PublicKey.findProgramAddressSync([Buffer.from(prefix), fastAction.toBuffer()], voting.programId)[0];
+    const slowAction = PublicKey.findProgramAddressSync(
+      [Buffer.from("slow_action"), fastAction.toBuffer()],
+      slow.programId
+    )[0];
+    try {
+      await slow.methods
+        .promoteFastAction(budget)
+        .accounts({
+          config: configPDA,
+          fastAction,
+          weightStrategy: seeds("weight_strategy"),
+          stream: seeds("stream"),
+          slowAction,
+          payer: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+      expect.fail("Should have thrown");
+    } catch (err: any) {
+      expect(err.message).to.include("InvalidAction");
+    }
+
+    try {
+      await slow.methods
+        .promoteContestedAction()
+        .accounts({
+          config: configPDA,
+          fastAction,
+          weightStrategy: seeds("weight_strategy"),
+          slowAction,
+          payer: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+      expect.fail("Should have thrown");
+    } catch (err: any) {
+      expect(err.message).to.include("NotContested");
+    }
+  });
+});
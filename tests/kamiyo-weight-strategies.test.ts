@@ -0,0 +1,271 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { createAccount, createMint, mintTo } from "@solana/spl-token";
+import { expect } from "chai";
+import { KamiyoFastVoting } from "../target/types/kamiyo_fast_voting";
+import { KamiyoWeightStrategies } from "../target/types/kamiyo_weight_strategies";
+
+describe("kamiyo-weight-strategies", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const strategies = anchor.workspace.KamiyoWeightStrategies as Program<KamiyoWeightStrategies>;
+  const voting = anchor.workspace.KamiyoFastVoting as Program<KamiyoFastVoting>;
+
+  let creator: Keypair;
+  let voter: Keypair;
+
+  // StrategyParams, borsh-encoded
+  function flat(weight: number): Buffer {
+    return Buffer.concat([Buffer.from([0]), new anchor.BN(weight).toArrayLike(Buffer, "le", 8)]);
+  }
+
+  function reputation(authority: PublicKey): Buffer {
+    return Buffer.concat([Buffer.from([2]), authority.toBuffer()]);
+  }
+
+  // A Reputation account stands in for the vault: the agent is the receipt
+  // mint, the score the underlying, and updated_slot the vault's slot
+  function receipt(vault: PublicKey, maxStalenessSlots: number): Buffer {
+    const offsets = Buffer.alloc(6);
+    offsets.writeUInt16LE(40, 0);
+    offsets.writeUInt16LE(72, 2);
+    offsets.writeUInt16LE(80, 4);
+    return Buffer.concat([
+      Buffer.from([3]),
+      vault.toBuffer(),
+      offsets,
+      new anchor.BN(maxStalenessSlots).toArrayLike(Buffer, "le", 8),
+    ]);
+  }
+
+  function deriveStrategyPDA(action: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync([Buffer.from("weight_strategy"), action.toBuffer()], voting.programId)[0];
+  }
+
+  function deriveReputationPDA(authority: PublicKey, agent: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("reputation"), authority.toBuffer(), agent.toBuffer()],
+      strategies.programId
+    )[0];
+  }
+
+  function deriveReceiptRatePDA(action: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync([Buffer.from("receipt_rate"), action.toBuffer()], strategies.programId)[0];
+  }
+
+  async function ensureConfig() {
+    const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], voting.programId);
+    if (await provider.connection.getAccountInfo(configPDA)) return;
+
+    const [programData] = PublicKey.findProgramAddressSync(
+      [voting.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+    await voting.methods
+      .initializeConfig(PublicKey.default, 0, PublicKey.default)
+      .accounts({
+        config: configPDA,
+        admin: provider.wallet.publicKey,
+        fastVotingProgram: voting.programId,
+        programData,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  async function createAction(fill: number, voteMode: object): Promise<[anchor.BN, PublicKey]> {
+    const [counterPDA] = PublicKey.findProgramAddressSync([Buffer.from("action_counter")], voting.programId);
+    const counter = await voting.account.actionCounter.fetchNullable(counterPDA);
+    const id = counter ? counter.nextId : new anchor.BN(0);
+    const [pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fast_action"), id.toArrayLike(Buffer, "le", 8)],
+      voting.programId
+    );
+    await voting.methods
+      .createFastAction({
+        actionHash: Array.from(Buffer.alloc(32, fill)),
+        threshold: 60,
+        descriptionHash: Array.from(Buffer.alloc(32)),
+        voteMode: voteMode as any,
+        tierThresholds: [0, 0, 0],
+        dependencies: [],
+        adaptiveQuorum: null,
+        minTurnoutBps: 0,
+        optionCount: 0,
+        ranked: false,
+        marketGate: null,
+        kind: { standard: {} },
+        weightCap: null,
+        classLimits: [],
+        audited: false,
+        earlyVoteBonus: false,
+        exclusions: [],
+        deadlineExtension: null,
+        identityWeighted: false,
+        memberCollection: null,
+        lockStake: false,
+        priority: 1,
+      })
+      .accounts({ fastAction: pda, creator: creator.publicKey, systemProgram: SystemProgram.programId })
+      .signers([creator])
+      .rpc();
+    return [id, pda];
+  }
+
+  function setStrategy(action: PublicKey, params: Buffer) {
+    return voting.methods
+      .setWeightStrategy(params)
+      .accounts({
+        fastAction: action,
+        weightStrategy: deriveStrategyPDA(action),
+        strategyProgram: strategies.programId,
+        authority: creator.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([creator])
+      .rpc();
+  }
+
+  function vote(id: anchor.BN, action: PublicKey, remaining: PublicKey[] = []) {
+    return voting.methods
+      .voteFastStrategy(id, true, Array.from(Buffer.alloc(32, 1)), 0)
+      .accounts({
+        fastAction: action,
+        weightStrategy: deriveStrategyPDA(action),
+        strategyProgram: strategies.programId,
+        voter: voter.publicKey,
+        metrics: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(remaining.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false })))
+      .signers([voter])
+      .rpc();
+  }
+
+  before(async () => {
+    await ensureConfig();
+
+    creator = Keypair.generate();
+    voter = Keypair.generate();
+    const sigs = await Promise.all(
+      [creator, voter].map((k) => provider.connection.requestAirdrop(k.publicKey, 2 * LAMPORTS_PER_SOL))
+    );
+    await Promise.all(sigs.map((sig) => provider.connection.confirmTransaction(sig)));
+  });
+
+  it("weighs a ballot through a flat strategy", async () => {
+    const [id, action] = await createAction(106, { tokenWeighted: {} });
+    await setStrategy(action, flat(7));
+
+    const strategy = await voting.account.weightStrategy.fetch(deriveStrategyPDA(action));
+    expect(strategy.program.toBase58()).to.equal(strategies.programId.toBase58());
+    expect(Buffer.from(strategy.params).equals(flat(7))).to.equal(true);
+    expect((await provider.connection.getAccountInfo(deriveStrategyPDA(action)))!.data.length).to.equal(173);
+
+    await vote(id, action);
+    const account = await voting.account.fastAction.fetch(action);
+    expect(account.weightFor.toNumber()).to.equal(7);
+  });
+
+  it("weighs a ballot by the voter's reputation", async () => {
+    const [id, action] = await createAction(107, { tokenWeighted: {} });
+    await setStrategy(action, reputation(creator.publicKey));
+    const score = deriveReputationPDA(creator.publicKey, voter.publicKey);
+    await strategies.methods
+      .setReputation(voter.publicKey, new anchor.BN(12))
+      .accounts({ reputation: score, authority: creator.publicKey, systemProgram: SystemProgram.programId })
+      .signers([creator])
+      .rpc();
+
+    await vote(id, action, [score]);
+    const account = await voting.account.fastAction.fetch(action);
+    expect(account.weightFor.toNumber()).to.equal(12);
+  });
+
+  describe("receipt tokens", () => {
+    let mint: PublicKey;
+    let voterAccount: PublicKey;
+    let vault: PublicKey;
+
+    // 40 of the 100 receipts are the voter's
+    before(async () => {
+      mint = await createMint(provider.connection, creator, creator.publicKey, null, 0);
+      voterAccount = await createAccount(provider.connection, creator, mint, voter.publicKey);
+      const other = await createAccount(provider.connection, creator, mint, creator.publicKey);
+      await mintTo(provider.connection, creator, mint, voterAccount, creator, 40);
+      await mintTo(provider.connection, creator, mint, other, creator, 60);
+      vault = deriveReputationPDA(creator.publicKey, mint);
+    });
+
+    // 250 underlying behind the receipts, updated now
+    function updateVault() {
+      return strategies.methods
+        .setReputation(mint, new anchor.BN(250))
+        .accounts({ reputation: vault, authority: creator.publicKey, systemProgram: SystemProgram.programId })
+        .signers([creator])
+        .rpc();
+    }
+
+    function snapshotRate(action: PublicKey) {
+      return strategies.methods
+        .snapshotReceiptRate()
+        .accounts({
+          fastAction: action,
+          weightStrategy: deriveStrategyPDA(action),
+          vault,
+          receiptMint: mint,
+          receiptRate: deriveReceiptRatePDA(action),
+          payer: creator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+    }
+
+    it("weighs receipts in the underlying they redeem for", async () => {
+      const [id, action] = await createAction(109, { tokenWeighted: {} });
+      await updateVault();
+      await setStrategy(action, receipt(vault, 1_000));
+
+      try {
+        await vote(id, action, [voterAccount, deriveReceiptRatePDA(action)]);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("RateNotRecorded");
+      }
+
+      await snapshotRate(action);
+      const rate = await strategies.account.receiptRate.fetch(deriveReceiptRatePDA(action));
+      expect(rate.underlying.toNumber()).to.equal(250);
+      expect(rate.receiptSupply.toNumber()).to.equal(100);
+
+      await vote(id, action, [voterAccount, deriveReceiptRatePDA(action)]);
+      const account = await voting.account.fastAction.fetch(action);
+      expect(account.weightFor.toNumber()).to.equal(100);
+    });
+
+    it("refuses a vault updated too far from the snapshot slot", async () => {
+      const [, action] = await createAction(110, { tokenWeighted: {} });
+      await updateVault();
+      await setStrategy(action, receipt(vault, 0));
+      try {
+        await snapshotRate(action);
+        expect.fail("Should have thrown");
+      } catch (err: any) {
+        expect(err.message).to.include("VaultStale");
+      }
+    });
+  });
+
+  it("only takes strategies on token-weighted actions", async () => {
+    const [, action] = await createAction(108, { headcount: {} });
+    try {
+      await setStrategy(action, flat(1));
+      expect.fail("Should have thrown");
+    } catch (err: any) {
+      expect(err.message).to.include("InvalidWeightStrategy");
+    }
+  });
+});